@@ -0,0 +1,630 @@
+//! Soak test for the SuperConfig registry under realistic churn.
+//!
+//! Worker threads create, update, read and delete entries they own, while observer
+//! threads keep reading entries owned by other workers, the way configuration watchers
+//! do. At every checkpoint the workload is paused and these invariants are checked:
+//!
+//! - **Stats consistency**: the registry's counters match the operations that succeeded,
+//!   `total_handles` matches `len()`, and `memory_usage_bytes` matches the live entries
+//! - **Bounded memory**: resident memory grows by less than `--max-rss-growth-mb` after
+//!   the first checkpoint (Linux only)
+//! - **No deadlocks**: a watchdog fails the run when no operation completes for
+//!   `--stall-timeout` seconds
+//! - **Consistent reads**: owners read back what they wrote, and observers never read an
+//!   older version of an entry after a newer one
+//!
+//! Run it in release mode for a few minutes:
+//!
+//! ```bash
+//! cargo run --release --bin stress -- --duration 300 --workers 16 --report stress.json
+//! ```
+//!
+//! The process exits with status 1 when an invariant fails and 2 when the watchdog fires.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use superconfig::{ConfigHandle, ConfigRegistry};
+
+// Workload mix for worker threads, in percent
+const CREATE_PERCENT: u64 = 30;
+const UPDATE_PERCENT: u64 = 25;
+const READ_PERCENT: u64 = 35;
+// The remaining 10% are deletes
+
+// Upper bound for the heap payload of each entry
+const MAX_PAYLOAD_BYTES: u64 = 1024;
+
+// Only the first violations are kept in the report
+const MAX_RECORDED_VIOLATIONS: usize = 100;
+
+const USAGE: &str = "\
+Usage: stress [OPTIONS]
+
+Options:
+  --duration <SECS>             Total run time [default: 300]
+  --workers <N>                 Threads creating/updating/deleting entries [default: available cores]
+  --observers <N>               Threads reading other workers' entries [default: 2]
+  --handles-per-worker <N>      Maximum live entries per worker [default: 256]
+  --checkpoint-interval <SECS>  Time between invariant checks [default: 10]
+  --max-rss-growth-mb <MB>      Allowed resident memory growth after the first checkpoint [default: 64]
+  --stall-timeout <SECS>        Time without progress before a deadlock is reported [default: 10]
+  --report <PATH>               Also write the report as JSON to PATH
+  --help                        Print this message";
+
+// Entry stored in the registry
+#[derive(Clone, Debug)]
+struct Payload {
+    owner: usize,
+    version: u64,
+    data: Vec<u8>,
+}
+
+// Command-line settings
+#[derive(Clone, Debug, Serialize)]
+struct Settings {
+    duration_secs: u64,
+    workers: usize,
+    observers: usize,
+    handles_per_worker: usize,
+    checkpoint_interval_secs: u64,
+    max_rss_growth_mb: u64,
+    stall_timeout_secs: u64,
+    #[serde(skip)]
+    report: Option<PathBuf>,
+}
+
+impl Settings {
+    fn from_args() -> Result<Self, String> {
+        let mut settings = Self {
+            duration_secs: 300,
+            workers: thread::available_parallelism().map_or(4, usize::from),
+            observers: 2,
+            handles_per_worker: 256,
+            checkpoint_interval_secs: 10,
+            max_rss_growth_mb: 64,
+            stall_timeout_secs: 10,
+            report: None,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--help" {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {arg}"))?;
+            let number = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid value for {arg}: {value}"))
+            };
+            match arg.as_str() {
+                "--duration" => settings.duration_secs = number()?,
+                "--workers" => settings.workers = number()? as usize,
+                "--observers" => settings.observers = number()? as usize,
+                "--handles-per-worker" => settings.handles_per_worker = number()? as usize,
+                "--checkpoint-interval" => settings.checkpoint_interval_secs = number()?,
+                "--max-rss-growth-mb" => settings.max_rss_growth_mb = number()?,
+                "--stall-timeout" => settings.stall_timeout_secs = number()?,
+                "--report" => settings.report = Some(PathBuf::from(value)),
+                _ => return Err(format!("unknown option {arg}\n\n{USAGE}")),
+            }
+        }
+
+        if settings.workers == 0 || settings.handles_per_worker == 0 {
+            return Err("--workers and --handles-per-worker must be at least 1".to_string());
+        }
+        Ok(settings)
+    }
+}
+
+// Small xorshift generator, so runs don't need an external RNG
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+// Operations that succeeded, to compare against the registry's own statistics
+#[derive(Default)]
+struct Counters {
+    creates: AtomicU64,
+    updates: AtomicU64,
+    deletes: AtomicU64,
+    reads: AtomicU64,
+    live_read_misses: AtomicU64,
+}
+
+// State shared by all threads
+struct Shared {
+    settings: Settings,
+    registry: Arc<ConfigRegistry>,
+    // Workers and observers hold a read guard per operation; checkpoints take the write guard
+    gate: RwLock<()>,
+    // Handles published by their owners; worker `w` owns slots `w * handles_per_worker..`
+    slots: Vec<Mutex<Option<ConfigHandle<Payload>>>>,
+    counters: Counters,
+    progress: AtomicU64,
+    paused: AtomicBool,
+    stop: AtomicBool,
+    violations: Mutex<Vec<String>>,
+    violation_count: AtomicU64,
+}
+
+impl Shared {
+    fn violation(&self, message: String) {
+        self.violation_count.fetch_add(1, Ordering::Relaxed);
+        let mut violations = self.violations.lock().unwrap();
+        if violations.len() < MAX_RECORDED_VIOLATIONS {
+            violations.push(message);
+        }
+    }
+}
+
+// Entry owned by a worker thread
+struct Owned {
+    handle: ConfigHandle<Payload>,
+    version: u64,
+    data_len: usize,
+    slot: usize,
+}
+
+fn run_worker(shared: &Shared, worker: usize) {
+    let mut rng = Rng::new(worker as u64 + 1);
+    let first_slot = worker * shared.settings.handles_per_worker;
+    let mut free_slots: Vec<usize> =
+        (first_slot..first_slot + shared.settings.handles_per_worker).collect();
+    let mut owned: Vec<Owned> = Vec::new();
+    let registry = &shared.registry;
+
+    while !shared.stop.load(Ordering::Relaxed) {
+        let _guard = shared.gate.read().unwrap();
+        let roll = rng.below(100);
+
+        if owned.is_empty() || (roll < CREATE_PERCENT && !free_slots.is_empty()) {
+            let Some(slot) = free_slots.pop() else {
+                continue;
+            };
+            let data_len = rng.below(MAX_PAYLOAD_BYTES) as usize;
+            let payload = Payload {
+                owner: worker,
+                version: 0,
+                data: vec![0; data_len],
+            };
+            match registry.create(payload) {
+                Ok(handle) => {
+                    shared.counters.creates.fetch_add(1, Ordering::Relaxed);
+                    *shared.slots[slot].lock().unwrap() = Some(handle.clone());
+                    owned.push(Owned {
+                        handle,
+                        version: 0,
+                        data_len,
+                        slot,
+                    });
+                }
+                Err(error) => {
+                    free_slots.push(slot);
+                    shared.violation(format!("worker {worker}: create failed: {error}"));
+                }
+            }
+        } else if roll < CREATE_PERCENT + UPDATE_PERCENT {
+            let index = rng.below(owned.len() as u64) as usize;
+            let entry = &mut owned[index];
+            let data_len = rng.below(MAX_PAYLOAD_BYTES) as usize;
+            let payload = Payload {
+                owner: worker,
+                version: entry.version + 1,
+                data: vec![0; data_len],
+            };
+            match registry.update(&entry.handle, payload) {
+                Ok(()) => {
+                    shared.counters.updates.fetch_add(1, Ordering::Relaxed);
+                    entry.version += 1;
+                    entry.data_len = data_len;
+                }
+                Err(error) => shared.violation(format!("worker {worker}: update failed: {error}")),
+            }
+        } else if roll < CREATE_PERCENT + UPDATE_PERCENT + READ_PERCENT {
+            let entry = &owned[rng.below(owned.len() as u64) as usize];
+            match registry.read(&entry.handle) {
+                Ok(payload) => {
+                    shared.counters.reads.fetch_add(1, Ordering::Relaxed);
+                    if payload.version != entry.version || payload.data.len() != entry.data_len {
+                        shared.violation(format!(
+                            "worker {worker}: read version {} ({} bytes) of handle {}, wrote version {} ({} bytes)",
+                            payload.version,
+                            payload.data.len(),
+                            entry.handle.id(),
+                            entry.version,
+                            entry.data_len
+                        ));
+                    }
+                }
+                Err(error) => shared.violation(format!("worker {worker}: read failed: {error}")),
+            }
+        } else {
+            let entry = owned.swap_remove(rng.below(owned.len() as u64) as usize);
+            // Unpublish first, so observers only read handles that are still live
+            *shared.slots[entry.slot].lock().unwrap() = None;
+            free_slots.push(entry.slot);
+            match registry.delete(&entry.handle) {
+                Ok(payload) => {
+                    shared.counters.deletes.fetch_add(1, Ordering::Relaxed);
+                    if payload.version != entry.version {
+                        shared.violation(format!(
+                            "worker {worker}: deleted version {} of handle {}, wrote {}",
+                            payload.version,
+                            entry.handle.id(),
+                            entry.version
+                        ));
+                    }
+                }
+                Err(error) => shared.violation(format!("worker {worker}: delete failed: {error}")),
+            }
+        }
+
+        shared.progress.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn run_observer(shared: &Shared, observer: usize) {
+    let mut rng = Rng::new(u64::MAX - observer as u64);
+    let mut last_seen: HashMap<u64, u64> = HashMap::new();
+
+    while !shared.stop.load(Ordering::Relaxed) {
+        let _guard = shared.gate.read().unwrap();
+        let slot = rng.below(shared.slots.len() as u64) as usize;
+
+        // Holding the slot keeps the owner from deleting the entry while it is read
+        let published = shared.slots[slot].lock().unwrap();
+        if let Some(handle) = published.as_ref() {
+            match shared.registry.read(handle) {
+                Ok(payload) => {
+                    shared.counters.reads.fetch_add(1, Ordering::Relaxed);
+                    let owner = slot / shared.settings.handles_per_worker;
+                    if payload.owner != owner {
+                        shared.violation(format!(
+                            "observer {observer}: handle {} in worker {owner}'s slot holds worker {}'s entry",
+                            handle.id(),
+                            payload.owner
+                        ));
+                    }
+                    let last = last_seen.entry(handle.id()).or_insert(payload.version);
+                    if payload.version < *last {
+                        shared.violation(format!(
+                            "observer {observer}: handle {} went back from version {} to {}",
+                            handle.id(),
+                            last,
+                            payload.version
+                        ));
+                    }
+                    *last = (*last).max(payload.version);
+                }
+                // The entry is live, so the read raced with an update
+                Err(_) => {
+                    shared
+                        .counters
+                        .live_read_misses
+                        .fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        drop(published);
+
+        // Forget entries that were deleted, so the map stays bounded
+        if last_seen.len() > shared.slots.len() * 4 {
+            last_seen.clear();
+        }
+        shared.progress.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Fails the run when no operation completes for the stall timeout
+fn run_watchdog(shared: &Shared) {
+    let timeout = Duration::from_secs(shared.settings.stall_timeout_secs);
+    let mut last_progress = shared.progress.load(Ordering::Relaxed);
+    let mut last_change = Instant::now();
+
+    while !shared.stop.load(Ordering::Relaxed) {
+        thread::sleep(Duration::from_millis(200));
+        let progress = shared.progress.load(Ordering::Relaxed);
+        if progress != last_progress || shared.paused.load(Ordering::Relaxed) {
+            last_progress = progress;
+            last_change = Instant::now();
+        } else if last_change.elapsed() >= timeout {
+            eprintln!(
+                "💀 No progress for {}s after {progress} operations: deadlock suspected",
+                timeout.as_secs()
+            );
+            std::process::exit(2);
+        }
+    }
+}
+
+// Invariant check results at one checkpoint
+#[derive(Clone, Debug, Serialize)]
+struct Checkpoint {
+    elapsed_secs: f64,
+    operations: u64,
+    live_entries: usize,
+    memory_usage_bytes: u64,
+    rss_kb: Option<u64>,
+}
+
+// Pauses the workload and checks the registry invariants
+fn checkpoint(shared: &Shared, started: Instant, rss_baseline: Option<u64>) -> Checkpoint {
+    let _guard = shared.gate.write().unwrap();
+    shared.paused.store(true, Ordering::Relaxed);
+
+    let registry = &shared.registry;
+    let stats = registry.stats();
+    let live = registry.len();
+    let published = shared
+        .slots
+        .iter()
+        .filter(|slot| slot.lock().unwrap().is_some())
+        .count();
+    let counters = &shared.counters;
+
+    let check = |name: &str, actual: u64, expected: u64| {
+        if actual != expected {
+            shared.violation(format!("stats: {name} is {actual}, expected {expected}"));
+        }
+    };
+    check("total_handles", stats.total_handles, live as u64);
+    check("live entries", live as u64, published as u64);
+    check(
+        "total_creates",
+        stats.total_creates,
+        counters.creates.load(Ordering::Relaxed),
+    );
+    check(
+        "total_updates",
+        stats.total_updates,
+        counters.updates.load(Ordering::Relaxed),
+    );
+    check(
+        "total_deletes",
+        stats.total_deletes,
+        counters.deletes.load(Ordering::Relaxed),
+    );
+    check(
+        "total_reads",
+        stats.total_reads,
+        counters.reads.load(Ordering::Relaxed),
+    );
+    check(
+        "memory_usage_bytes",
+        stats.memory_usage_bytes,
+        (live * std::mem::size_of::<Payload>()) as u64,
+    );
+
+    let rss_kb = resident_memory_kb();
+    if let (Some(baseline), Some(current)) = (rss_baseline, rss_kb) {
+        let growth_mb = current.saturating_sub(baseline) / 1024;
+        if growth_mb > shared.settings.max_rss_growth_mb {
+            shared.violation(format!(
+                "memory: resident memory grew by {growth_mb} MB since the first checkpoint (limit {} MB)",
+                shared.settings.max_rss_growth_mb
+            ));
+        }
+    }
+
+    shared.paused.store(false, Ordering::Relaxed);
+    Checkpoint {
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        operations: shared.progress.load(Ordering::Relaxed),
+        live_entries: live,
+        memory_usage_bytes: stats.memory_usage_bytes,
+        rss_kb,
+    }
+}
+
+// Resident set size of this process, from /proc on Linux
+fn resident_memory_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+// Final report
+#[derive(Debug, Serialize)]
+struct Report {
+    settings: Settings,
+    passed: bool,
+    elapsed_secs: f64,
+    operations: u64,
+    operations_per_sec: f64,
+    creates: u64,
+    updates: u64,
+    deletes: u64,
+    reads: u64,
+    live_read_misses: u64,
+    final_entries: usize,
+    peak_rss_kb: Option<u64>,
+    violation_count: u64,
+    violations: Vec<String>,
+    checkpoints: Vec<Checkpoint>,
+}
+
+fn print_report(report: &Report) {
+    println!();
+    println!("📊 STRESS TEST REPORT");
+    println!("{:-<70}", "");
+    println!(
+        "Duration:          {:.1}s ({} workers, {} observers)",
+        report.elapsed_secs, report.settings.workers, report.settings.observers
+    );
+    println!(
+        "Operations:        {} ({:.0} ops/s)",
+        report.operations, report.operations_per_sec
+    );
+    println!("Creates/updates:   {} / {}", report.creates, report.updates);
+    println!("Deletes/reads:     {} / {}", report.deletes, report.reads);
+    println!("Live read misses:  {}", report.live_read_misses);
+    println!("Final entries:     {}", report.final_entries);
+    if let Some(peak) = report.peak_rss_kb {
+        println!("Peak RSS:          {:.1} MB", peak as f64 / 1024.0);
+    }
+    println!("Checkpoints:       {}", report.checkpoints.len());
+    println!("{:-<70}", "");
+
+    if report.passed {
+        println!("✅ All invariants held");
+    } else {
+        println!("❌ {} invariant violations:", report.violation_count);
+        for violation in &report.violations {
+            println!("   - {violation}");
+        }
+    }
+    if report.live_read_misses > 0 {
+        println!(
+            "Note: live read misses are reads of published entries that failed while the owner was updating them"
+        );
+    }
+}
+
+fn main() {
+    let settings = match Settings::from_args() {
+        Ok(settings) => settings,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(64);
+        }
+    };
+
+    println!("🚀 SuperConfig Registry Stress Test");
+    println!(
+        "Running {}s with {} workers × {} entries and {} observers, checkpoint every {}s",
+        settings.duration_secs,
+        settings.workers,
+        settings.handles_per_worker,
+        settings.observers,
+        settings.checkpoint_interval_secs
+    );
+
+    let shared = Arc::new(Shared {
+        registry: ConfigRegistry::new(),
+        gate: RwLock::new(()),
+        slots: (0..settings.workers * settings.handles_per_worker)
+            .map(|_| Mutex::new(None))
+            .collect(),
+        counters: Counters::default(),
+        progress: AtomicU64::new(0),
+        paused: AtomicBool::new(false),
+        stop: AtomicBool::new(false),
+        violations: Mutex::new(Vec::new()),
+        violation_count: AtomicU64::new(0),
+        settings: settings.clone(),
+    });
+
+    let mut threads = Vec::new();
+    for worker in 0..settings.workers {
+        let shared = Arc::clone(&shared);
+        threads.push(thread::spawn(move || run_worker(&shared, worker)));
+    }
+    for observer in 0..settings.observers {
+        let shared = Arc::clone(&shared);
+        threads.push(thread::spawn(move || run_observer(&shared, observer)));
+    }
+    let watchdog = {
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || run_watchdog(&shared))
+    };
+
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs(settings.duration_secs);
+    let interval = Duration::from_secs(settings.checkpoint_interval_secs.max(1));
+    let mut checkpoints: Vec<Checkpoint> = Vec::new();
+    let mut rss_baseline = None;
+
+    while Instant::now() < deadline {
+        thread::sleep(interval.min(deadline.saturating_duration_since(Instant::now())));
+        let result = checkpoint(&shared, started, rss_baseline);
+        rss_baseline = rss_baseline.or(result.rss_kb);
+        println!(
+            "⏱️  {:>6.1}s  {:>12} ops  {:>6} entries  RSS {}",
+            result.elapsed_secs,
+            result.operations,
+            result.live_entries,
+            result.rss_kb.map_or_else(
+                || "n/a".to_string(),
+                |kb| format!("{:.1} MB", kb as f64 / 1024.0)
+            )
+        );
+        checkpoints.push(result);
+    }
+
+    shared.stop.store(true, Ordering::Relaxed);
+    for thread in threads {
+        thread.join().expect("stress thread panicked");
+    }
+    watchdog.join().expect("watchdog panicked");
+
+    // Final check with every thread stopped
+    let last = checkpoint(&shared, started, rss_baseline);
+    checkpoints.push(last);
+
+    let elapsed_secs = started.elapsed().as_secs_f64();
+    let counters = &shared.counters;
+    let operations = shared.progress.load(Ordering::Relaxed);
+    let violation_count = shared.violation_count.load(Ordering::Relaxed);
+    let report = Report {
+        passed: violation_count == 0,
+        elapsed_secs,
+        operations,
+        operations_per_sec: operations as f64 / elapsed_secs,
+        creates: counters.creates.load(Ordering::Relaxed),
+        updates: counters.updates.load(Ordering::Relaxed),
+        deletes: counters.deletes.load(Ordering::Relaxed),
+        reads: counters.reads.load(Ordering::Relaxed),
+        live_read_misses: counters.live_read_misses.load(Ordering::Relaxed),
+        final_entries: shared.registry.len(),
+        peak_rss_kb: checkpoints
+            .iter()
+            .filter_map(|checkpoint| checkpoint.rss_kb)
+            .max(),
+        violation_count,
+        violations: shared.violations.lock().unwrap().clone(),
+        checkpoints,
+        settings,
+    };
+
+    print_report(&report);
+    if let Some(path) = &report.settings.report {
+        let json = serde_json::to_string_pretty(&report).expect("report should serialize");
+        if let Err(error) = std::fs::write(path, json) {
+            eprintln!("Failed to write report to {}: {error}", path.display());
+        }
+    }
+
+    if !report.passed {
+        std::process::exit(1);
+    }
+}
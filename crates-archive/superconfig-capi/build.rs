@@ -0,0 +1,10 @@
+//! Generates `include/superconfig.h` from the `extern "C"` functions with cbindgen
+
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::generate(&crate_dir)
+        .expect("Unable to generate the C header")
+        .write_to_file(format!("{crate_dir}/include/superconfig.h"));
+}
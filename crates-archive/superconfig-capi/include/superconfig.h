@@ -0,0 +1,216 @@
+#ifndef SUPERCONFIG_H
+#define SUPERCONFIG_H
+
+/* Generated by cbindgen from superconfig-capi. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ The call succeeded
+ */
+#define SUPERCONFIG_OK 0
+
+/*
+ A pointer was `NULL` or a string wasn't UTF-8
+ */
+#define SUPERCONFIG_ERROR_INVALID_ARGUMENT 1
+
+/*
+ The handle or the key path doesn't exist
+ */
+#define SUPERCONFIG_ERROR_NOT_FOUND 2
+
+/*
+ The value at the key path doesn't have the requested type
+ */
+#define SUPERCONFIG_ERROR_WRONG_TYPE 3
+
+/*
+ A file or string failed to load or parse, or files couldn't be watched
+ */
+#define SUPERCONFIG_ERROR_LOAD 4
+
+/*
+ Configuration registry holding JSON entries (`superconfig_registry_t`)
+
+ Opaque to C, created by [`superconfig_registry_new`] and released by
+ [`superconfig_registry_free`].
+ */
+typedef struct superconfig_registry_t superconfig_registry_t;
+
+/*
+ Hot reload of an entry from its files (`superconfig_watcher_t`)
+
+ Opaque to C, created by [`superconfig_watch`] and released by
+ [`superconfig_watcher_free`].
+ */
+typedef struct superconfig_watcher_t superconfig_watcher_t;
+
+/*
+ Function called with its user data and the handle ID after each update of an
+ entry (`superconfig_update_callback_t`)
+ */
+typedef void (*superconfig_update_callback_t)(void *user_data, uint64_t handle);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Message of the last error on the calling thread, or `NULL` if there was none
+
+ The string belongs to the library and stays valid until the next failing call on
+ this thread.
+ */
+const char *superconfig_last_error(void);
+
+/*
+ Release a string returned by the library
+
+ # Safety
+
+ `string` is `NULL` or a string returned by the library, not yet released.
+ */
+void superconfig_string_free(char *string);
+
+/*
+ Call `callback(user_data, handle)` after each update of the entry `handle`,
+ storing the subscription ID in `*subscription`
+
+ Updates include reloads by a [`superconfig_watch`] watcher. The callback runs on
+ the updating thread until [`superconfig_unsubscribe`] is called.
+
+ # Safety
+
+ `registry` is a live registry, `subscription` valid for writes, and `user_data`
+ usable by `callback` from any thread until the subscription is removed.
+ */
+int32_t superconfig_subscribe(const superconfig_registry_t *registry,
+                              uint64_t handle,
+                              superconfig_update_callback_t callback,
+                              void *user_data,
+                              uint64_t *subscription);
+
+/*
+ Remove a subscription made with [`superconfig_subscribe`]
+
+ # Safety
+
+ `registry` is a live registry.
+ */
+int32_t superconfig_unsubscribe(const superconfig_registry_t *registry, uint64_t subscription);
+
+/*
+ Reload the entry `handle` from the `count` files `paths` when they change,
+ storing the watcher in `*watcher`
+
+ Files are merged in order like layered files. Reloads wait until the files have
+ been quiet for `debounce_ms` milliseconds, or 200 with `0`. Files that fail to load
+ are logged and the entry keeps its configuration.
+
+ # Safety
+
+ `registry` is a live registry, `paths` an array of `count` NUL-terminated strings,
+ and `watcher` valid for writes.
+ */
+int32_t superconfig_watch(const superconfig_registry_t *registry,
+                          uint64_t handle,
+                          const char *const *paths,
+                          size_t count,
+                          uint32_t debounce_ms,
+                          superconfig_watcher_t **watcher);
+
+/*
+ Stop a watcher and release it
+
+ # Safety
+
+ `watcher` is `NULL` or a watcher not yet released.
+ */
+void superconfig_watcher_free(superconfig_watcher_t *watcher);
+
+/*
+ Create an empty registry, released with [`superconfig_registry_free`]
+ */
+superconfig_registry_t *superconfig_registry_new(void);
+
+/*
+ Release a registry and its entries
+
+ Watchers of the registry stop reloading, but must still be released.
+
+ # Safety
+
+ `registry` is `NULL` or a registry not yet released, not in use by another thread.
+ */
+void superconfig_registry_free(superconfig_registry_t *registry);
+
+/*
+ Load the file at `path`, in the format of its extension or content, into
+ `*handle`
+
+ # Safety
+
+ `registry` is a live registry, `path` a NUL-terminated string, and `handle` valid
+ for writes.
+ */
+int32_t superconfig_load_file(const superconfig_registry_t *registry,
+                              const char *path,
+                              uint64_t *handle);
+
+/*
+ Load `content` in `format` (`"json"`, `"toml"`, `"yaml"`, ...) into `*handle`
+
+ With a `NULL` `format`, the format is detected from the content.
+
+ # Safety
+
+ `registry` is a live registry, `content` a NUL-terminated string, `format` `NULL`
+ or a NUL-terminated string, and `handle` valid for writes.
+ */
+int32_t superconfig_load_str(const superconfig_registry_t *registry,
+                             const char *content,
+                             const char *format,
+                             uint64_t *handle);
+
+/*
+ Store the value at `path` of an entry, such as `"database.host"`, in `*value`
+
+ Strings are returned as they are, and other values as JSON text (`5432`, `true`,
+ `["a","b"]`). Release the string with `superconfig_string_free`.
+
+ # Safety
+
+ `registry` is a live registry, `path` a NUL-terminated string, and `value` valid
+ for writes.
+ */
+int32_t superconfig_get_string(const superconfig_registry_t *registry,
+                               uint64_t handle,
+                               const char *path,
+                               char **value);
+
+/*
+ Store the number at `path` of an entry, such as `"database.port"`, in `*value`
+
+ Fails with `SUPERCONFIG_ERROR_WRONG_TYPE` if the value isn't a number; integers
+ beyond 2^53 lose precision.
+
+ # Safety
+
+ `registry` is a live registry, `path` a NUL-terminated string, and `value` valid
+ for writes.
+ */
+int32_t superconfig_get_number(const superconfig_registry_t *registry,
+                               uint64_t handle,
+                               const char *path,
+                               double *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SUPERCONFIG_H */
@@ -0,0 +1,243 @@
+//! Update callbacks, and hot reload of entries from their files
+
+use std::ffi::{c_char, c_void};
+use std::slice;
+use std::time::Duration;
+use superconfig::RegistryEvent;
+use superconfig::watch::{FileWatcher, HotReload};
+
+use crate::registry::{Registry, registry_arg};
+use crate::{
+    SUPERCONFIG_ERROR_INVALID_ARGUMENT, SUPERCONFIG_ERROR_LOAD, SUPERCONFIG_ERROR_NOT_FOUND,
+    SUPERCONFIG_OK, fail, out_arg, store, str_arg,
+};
+
+/// Function called with its user data and the handle ID after each update of an
+/// entry (`superconfig_update_callback_t`)
+pub type UpdateCallback = Option<unsafe extern "C" fn(user_data: *mut c_void, handle: u64)>;
+
+/// User data of a callback, passed back as it was given
+struct UserData(*mut c_void);
+
+// The caller of `superconfig_subscribe` allows the user data to be used from the
+// threads making updates
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    // Taking `self` makes closures capture the whole `UserData`, not the bare pointer
+    const fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+/// Hot reload of an entry from its files (`superconfig_watcher_t`)
+///
+/// Opaque to C, created by [`superconfig_watch`] and released by
+/// [`superconfig_watcher_free`].
+pub struct Watcher {
+    /// Stops watching when dropped
+    #[allow(dead_code)]
+    watcher: FileWatcher,
+}
+
+/// Call `callback(user_data, handle)` after each update of the entry `handle`,
+/// storing the subscription ID in `*subscription`
+///
+/// Updates include reloads by a [`superconfig_watch`] watcher. The callback runs on
+/// the updating thread until [`superconfig_unsubscribe`] is called.
+///
+/// # Safety
+///
+/// `registry` is a live registry, `subscription` valid for writes, and `user_data`
+/// usable by `callback` from any thread until the subscription is removed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_subscribe(
+    registry: *const Registry,
+    handle: u64,
+    callback: UpdateCallback,
+    user_data: *mut c_void,
+    subscription: *mut u64,
+) -> i32 {
+    let result = (|| {
+        // SAFETY: forwarded from the caller's contract
+        let registry = unsafe { registry_arg(registry)? };
+        out_arg(subscription, "subscription")?;
+        let callback = callback.ok_or_else(|| {
+            fail(
+                SUPERCONFIG_ERROR_INVALID_ARGUMENT,
+                "superconfig.capi: callback is NULL",
+            )
+        })?;
+        registry.handle(handle)?;
+        let user_data = UserData(user_data);
+        Ok(registry.registry.subscribe(move |event| {
+            if matches!(event, RegistryEvent::Updated { handle_id } if *handle_id == handle) {
+                // SAFETY: the caller allows calls with the user data from any thread
+                unsafe { callback(user_data.get(), handle) };
+            }
+        }))
+    })();
+    // SAFETY: checked non-NULL before any success
+    unsafe { store(result, subscription) }
+}
+
+/// Remove a subscription made with [`superconfig_subscribe`]
+///
+/// # Safety
+///
+/// `registry` is a live registry.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_unsubscribe(
+    registry: *const Registry,
+    subscription: u64,
+) -> i32 {
+    // SAFETY: forwarded from the caller's contract
+    let registry = match unsafe { registry_arg(registry) } {
+        Ok(registry) => registry,
+        Err(status) => return status,
+    };
+    if registry.registry.unsubscribe(subscription) {
+        SUPERCONFIG_OK
+    } else {
+        fail(
+            SUPERCONFIG_ERROR_NOT_FOUND,
+            &format!("superconfig.capi: No subscription {subscription}"),
+        )
+    }
+}
+
+/// Reload the entry `handle` from the `count` files `paths` when they change,
+/// storing the watcher in `*watcher`
+///
+/// Files are merged in order like layered files. Reloads wait until the files have
+/// been quiet for `debounce_ms` milliseconds, or 200 with `0`. Files that fail to load
+/// are logged and the entry keeps its configuration.
+///
+/// # Safety
+///
+/// `registry` is a live registry, `paths` an array of `count` NUL-terminated strings,
+/// and `watcher` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_watch(
+    registry: *const Registry,
+    handle: u64,
+    paths: *const *const c_char,
+    count: usize,
+    debounce_ms: u32,
+    watcher: *mut *mut Watcher,
+) -> i32 {
+    let result = (|| {
+        // SAFETY: forwarded from the caller's contract
+        let registry = unsafe { registry_arg(registry)? };
+        out_arg(watcher, "watcher")?;
+        if paths.is_null() && count > 0 {
+            return Err(fail(
+                SUPERCONFIG_ERROR_INVALID_ARGUMENT,
+                "superconfig.capi: paths is NULL",
+            ));
+        }
+        let paths = if count == 0 {
+            &[]
+        } else {
+            // SAFETY: `count` strings per the contract
+            unsafe { slice::from_raw_parts(paths, count) }
+        };
+        let paths = paths
+            .iter()
+            // SAFETY: NUL-terminated per the contract
+            .map(|&path| unsafe { str_arg(path, "path") })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut reload = HotReload::new(paths);
+        if debounce_ms > 0 {
+            reload = reload.debounce(Duration::from_millis(debounce_ms.into()));
+        }
+        let started = reload
+            .start(&registry.registry, &registry.handle(handle)?)
+            .map_err(|e| fail(SUPERCONFIG_ERROR_LOAD, &e))?;
+        Ok(Box::into_raw(Box::new(Watcher { watcher: started })))
+    })();
+    // SAFETY: checked non-NULL before any success
+    unsafe { store(result, watcher) }
+}
+
+/// Stop a watcher and release it
+///
+/// # Safety
+///
+/// `watcher` is `NULL` or a watcher not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_watcher_free(watcher: *mut Watcher) {
+    if !watcher.is_null() {
+        // SAFETY: created by `superconfig_watch` per the contract
+        drop(unsafe { Box::from_raw(watcher) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{superconfig_load_file, superconfig_registry_free, superconfig_registry_new};
+    use std::ffi::CString;
+    use std::ptr;
+    use std::sync::mpsc::{Sender, channel};
+
+    unsafe extern "C" fn on_update(user_data: *mut c_void, handle: u64) {
+        // SAFETY: the test's sender, alive until the registry is released
+        let sender = unsafe { &*user_data.cast::<Sender<u64>>() };
+        sender.send(handle).unwrap();
+    }
+
+    #[test]
+    fn test_watch_calls_subscribers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.json");
+        std::fs::write(&path, r#"{"port": 80}"#).unwrap();
+        let path_arg = CString::new(path.to_str().unwrap()).unwrap();
+        let paths = [path_arg.as_ptr()];
+        let (sender, updates) = channel();
+        let user_data = (&raw const sender).cast_mut().cast();
+
+        let registry = superconfig_registry_new();
+        let mut handle = 0;
+        let mut subscription = 0;
+        let mut watcher = ptr::null_mut();
+        // SAFETY: live registry, NUL-terminated strings and valid outputs
+        unsafe {
+            assert_eq!(
+                superconfig_load_file(registry, path_arg.as_ptr(), &raw mut handle),
+                SUPERCONFIG_OK
+            );
+            let status = superconfig_subscribe(
+                registry,
+                handle,
+                Some(on_update),
+                user_data,
+                &raw mut subscription,
+            );
+            assert_eq!(status, SUPERCONFIG_OK);
+            let status =
+                superconfig_watch(registry, handle, paths.as_ptr(), 1, 20, &raw mut watcher);
+            assert_eq!(status, SUPERCONFIG_OK);
+
+            std::fs::write(&path, r#"{"port": 8080}"#).unwrap();
+            assert_eq!(updates.recv_timeout(Duration::from_secs(10)), Ok(handle));
+
+            assert_eq!(
+                superconfig_unsubscribe(registry, subscription),
+                SUPERCONFIG_OK
+            );
+            let status = superconfig_unsubscribe(registry, subscription);
+            assert_eq!(status, SUPERCONFIG_ERROR_NOT_FOUND);
+            let status =
+                superconfig_subscribe(registry, handle, None, user_data, &raw mut subscription);
+            assert_eq!(status, SUPERCONFIG_ERROR_INVALID_ARGUMENT);
+            let status = superconfig_watch(registry, handle, ptr::null(), 0, 0, &raw mut watcher);
+            assert_eq!(status, SUPERCONFIG_ERROR_LOAD);
+
+            superconfig_watcher_free(watcher);
+            superconfig_registry_free(registry);
+        }
+    }
+}
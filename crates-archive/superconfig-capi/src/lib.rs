@@ -0,0 +1,175 @@
+//! C interface of the `SuperConfig` registry
+//!
+//! Built into `libsuperconfig_capi` (shared and static), with the header
+//! `include/superconfig.h` generated by cbindgen, so services in Go (through cgo), C
+//! and C++ can embed the registry. Entries are stored as JSON trees and addressed by
+//! their `uint64_t` handle IDs; values are read by key path.
+//!
+//! ```c
+//! #include "superconfig.h"
+//!
+//! superconfig_registry_t *registry = superconfig_registry_new();
+//! uint64_t handle;
+//! if (superconfig_load_file(registry, "app.toml", &handle) != SUPERCONFIG_OK) {
+//!     fprintf(stderr, "%s\n", superconfig_last_error());
+//! }
+//!
+//! double port;
+//! superconfig_get_number(registry, handle, "database.port", &port);
+//! char *host;
+//! superconfig_get_string(registry, handle, "database.host", &host);
+//! superconfig_string_free(host);
+//!
+//! superconfig_registry_free(registry);
+//! ```
+//!
+//! ## Conventions
+//!
+//! - Functions return `SUPERCONFIG_OK` (`0`) or an error status, and store their
+//!   result through an output pointer only on success. The message of the last error
+//!   on the calling thread is returned by [`superconfig_last_error`].
+//! - Strings are NUL-terminated UTF-8. Strings returned by the library are released
+//!   with [`superconfig_string_free`]; strings passed in are only borrowed.
+//! - Registries and watchers are released with their `_free` function, once. A
+//!   registry may be used from several threads at once.
+//! - Update callbacks run on the thread making the update, such as a watcher's thread,
+//!   and must not call back into the library's `_free` functions.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+mod events;
+mod registry;
+
+pub use events::{
+    UpdateCallback, Watcher, superconfig_subscribe, superconfig_unsubscribe, superconfig_watch,
+    superconfig_watcher_free,
+};
+pub use registry::{
+    Registry, superconfig_get_number, superconfig_get_string, superconfig_load_file,
+    superconfig_load_str, superconfig_registry_free, superconfig_registry_new,
+};
+
+/// The call succeeded
+pub const SUPERCONFIG_OK: i32 = 0;
+/// A pointer was `NULL` or a string wasn't UTF-8
+pub const SUPERCONFIG_ERROR_INVALID_ARGUMENT: i32 = 1;
+/// The handle or the key path doesn't exist
+pub const SUPERCONFIG_ERROR_NOT_FOUND: i32 = 2;
+/// The value at the key path doesn't have the requested type
+pub const SUPERCONFIG_ERROR_WRONG_TYPE: i32 = 3;
+/// A file or string failed to load or parse, or files couldn't be watched
+pub const SUPERCONFIG_ERROR_LOAD: i32 = 4;
+
+thread_local! {
+    /// Message of the last error on this thread, returned by `superconfig_last_error`
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Record `message` as the last error of this thread and return `status`
+fn fail(status: i32, message: &str) -> i32 {
+    // Messages come from Rust strings, which may only hold NULs in user input
+    let message = CString::new(message.replace('\0', "\\0")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+/// Borrow the C string `string` as UTF-8
+///
+/// # Safety
+///
+/// `string` is `NULL` or a NUL-terminated string valid for `'a`.
+unsafe fn str_arg<'a>(string: *const c_char, name: &str) -> Result<&'a str, i32> {
+    if string.is_null() {
+        return Err(fail(
+            SUPERCONFIG_ERROR_INVALID_ARGUMENT,
+            &format!("superconfig.capi: {name} is NULL"),
+        ));
+    }
+    // SAFETY: non-NULL and NUL-terminated per the contract
+    unsafe { CStr::from_ptr(string) }.to_str().map_err(|_| {
+        fail(
+            SUPERCONFIG_ERROR_INVALID_ARGUMENT,
+            &format!("superconfig.capi: {name} is not UTF-8"),
+        )
+    })
+}
+
+/// Check that the output pointer `output` isn't `NULL`
+fn out_arg<T>(output: *mut T, name: &str) -> Result<(), i32> {
+    if output.is_null() {
+        return Err(fail(
+            SUPERCONFIG_ERROR_INVALID_ARGUMENT,
+            &format!("superconfig.capi: {name} is NULL"),
+        ));
+    }
+    Ok(())
+}
+
+/// Status of a result, storing its value through `output` on success
+///
+/// # Safety
+///
+/// `output` is valid for writes.
+unsafe fn store<T>(result: Result<T, i32>, output: *mut T) -> i32 {
+    match result {
+        Ok(value) => {
+            // SAFETY: valid for writes per the contract
+            unsafe { output.write(value) };
+            SUPERCONFIG_OK
+        }
+        Err(status) => status,
+    }
+}
+
+/// Message of the last error on the calling thread, or `NULL` if there was none
+///
+/// The string belongs to the library and stays valid until the next failing call on
+/// this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn superconfig_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Release a string returned by the library
+///
+/// # Safety
+///
+/// `string` is `NULL` or a string returned by the library, not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: allocated by `CString::into_raw` per the contract
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_error_and_arguments() {
+        // SAFETY: NULL is allowed
+        let error = unsafe { str_arg(ptr::null(), "path") }.unwrap_err();
+        assert_eq!(error, SUPERCONFIG_ERROR_INVALID_ARGUMENT);
+        // SAFETY: set by the failing call above
+        let message = unsafe { CStr::from_ptr(superconfig_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "superconfig.capi: path is NULL");
+
+        let invalid = c"\xff";
+        // SAFETY: NUL-terminated
+        let error = unsafe { str_arg(invalid.as_ptr(), "path") }.unwrap_err();
+        assert_eq!(error, SUPERCONFIG_ERROR_INVALID_ARGUMENT);
+
+        // Errors are per thread
+        std::thread::spawn(|| assert!(superconfig_last_error().is_null()))
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,317 @@
+//! Registries, loading entries, and reading values by key path
+
+use serde_json::Value;
+use std::ffi::{CString, c_char};
+use std::sync::Arc;
+use superconfig::{ConfigHandle, ConfigRegistry, Format, HandleId};
+
+use crate::{
+    SUPERCONFIG_ERROR_INVALID_ARGUMENT, SUPERCONFIG_ERROR_LOAD, SUPERCONFIG_ERROR_NOT_FOUND,
+    SUPERCONFIG_ERROR_WRONG_TYPE, fail, out_arg, store, str_arg,
+};
+
+/// Configuration registry holding JSON entries (`superconfig_registry_t`)
+///
+/// Opaque to C, created by [`superconfig_registry_new`] and released by
+/// [`superconfig_registry_free`].
+pub struct Registry {
+    pub(crate) registry: Arc<ConfigRegistry>,
+}
+
+impl Registry {
+    /// Typed handle of the entry `handle_id`, checking it holds a JSON tree
+    pub(crate) fn handle(&self, handle_id: HandleId) -> Result<ConfigHandle<Value>, i32> {
+        self.registry
+            .resolve(handle_id)
+            .map_err(|e| fail(SUPERCONFIG_ERROR_NOT_FOUND, &e))
+    }
+
+    /// The value at `path` of the entry `handle_id`
+    fn value(&self, handle_id: HandleId, path: &str) -> Result<Value, i32> {
+        self.registry
+            .get_path(&self.handle(handle_id)?, path)
+            .map_err(|e| fail(SUPERCONFIG_ERROR_NOT_FOUND, &e))
+    }
+}
+
+/// Borrow the registry `registry`
+///
+/// # Safety
+///
+/// `registry` is `NULL` or a registry not yet released, valid for `'a`.
+pub unsafe fn registry_arg<'a>(registry: *const Registry) -> Result<&'a Registry, i32> {
+    // SAFETY: `NULL` or a live registry per the contract
+    unsafe { registry.as_ref() }.ok_or_else(|| {
+        fail(
+            SUPERCONFIG_ERROR_INVALID_ARGUMENT,
+            "superconfig.capi: registry is NULL",
+        )
+    })
+}
+
+/// Create an empty registry, released with [`superconfig_registry_free`]
+#[unsafe(no_mangle)]
+pub extern "C" fn superconfig_registry_new() -> *mut Registry {
+    Box::into_raw(Box::new(Registry {
+        registry: ConfigRegistry::new(),
+    }))
+}
+
+/// Release a registry and its entries
+///
+/// Watchers of the registry stop reloading, but must still be released.
+///
+/// # Safety
+///
+/// `registry` is `NULL` or a registry not yet released, not in use by another thread.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_registry_free(registry: *mut Registry) {
+    if !registry.is_null() {
+        // SAFETY: created by `superconfig_registry_new` per the contract
+        drop(unsafe { Box::from_raw(registry) });
+    }
+}
+
+/// Load the file at `path`, in the format of its extension or content, into
+/// `*handle`
+///
+/// # Safety
+///
+/// `registry` is a live registry, `path` a NUL-terminated string, and `handle` valid
+/// for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_load_file(
+    registry: *const Registry,
+    path: *const c_char,
+    handle: *mut u64,
+) -> i32 {
+    let result = (|| {
+        // SAFETY: forwarded from the caller's contract
+        let (registry, path) = unsafe { (registry_arg(registry)?, str_arg(path, "path")?) };
+        out_arg(handle, "handle")?;
+        let loaded = registry
+            .registry
+            .create_from_file::<Value>(path)
+            .map_err(|e| fail(SUPERCONFIG_ERROR_LOAD, &e))?;
+        Ok(loaded.id())
+    })();
+    // SAFETY: checked non-NULL before any success
+    unsafe { store(result, handle) }
+}
+
+/// Load `content` in `format` (`"json"`, `"toml"`, `"yaml"`, ...) into `*handle`
+///
+/// With a `NULL` `format`, the format is detected from the content.
+///
+/// # Safety
+///
+/// `registry` is a live registry, `content` a NUL-terminated string, `format` `NULL`
+/// or a NUL-terminated string, and `handle` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_load_str(
+    registry: *const Registry,
+    content: *const c_char,
+    format: *const c_char,
+    handle: *mut u64,
+) -> i32 {
+    let result = (|| {
+        // SAFETY: forwarded from the caller's contract
+        let (registry, content) =
+            unsafe { (registry_arg(registry)?, str_arg(content, "content")?) };
+        out_arg(handle, "handle")?;
+        let format = if format.is_null() {
+            Format::detect(content).ok_or_else(|| {
+                fail(
+                    SUPERCONFIG_ERROR_LOAD,
+                    "superconfig.capi: Unknown format of content, pass its format",
+                )
+            })?
+        } else {
+            // SAFETY: non-NULL, so NUL-terminated per the contract
+            let name = unsafe { str_arg(format, "format")? };
+            serde_json::from_value(Value::from(name)).map_err(|_| {
+                fail(
+                    SUPERCONFIG_ERROR_INVALID_ARGUMENT,
+                    &format!("superconfig.capi: Unknown format '{name}'"),
+                )
+            })?
+        };
+        let loaded = registry
+            .registry
+            .create_from_str::<Value>(content, format)
+            .map_err(|e| fail(SUPERCONFIG_ERROR_LOAD, &e))?;
+        Ok(loaded.id())
+    })();
+    // SAFETY: checked non-NULL before any success
+    unsafe { store(result, handle) }
+}
+
+/// Store the value at `path` of an entry, such as `"database.host"`, in `*value`
+///
+/// Strings are returned as they are, and other values as JSON text (`5432`, `true`,
+/// `["a","b"]`). Release the string with `superconfig_string_free`.
+///
+/// # Safety
+///
+/// `registry` is a live registry, `path` a NUL-terminated string, and `value` valid
+/// for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_get_string(
+    registry: *const Registry,
+    handle: u64,
+    path: *const c_char,
+    value: *mut *mut c_char,
+) -> i32 {
+    let result = (|| {
+        // SAFETY: forwarded from the caller's contract
+        let (registry, path) = unsafe { (registry_arg(registry)?, str_arg(path, "path")?) };
+        out_arg(value, "value")?;
+        let text = match registry.value(handle, path)? {
+            Value::String(text) => text,
+            other => other.to_string(),
+        };
+        CString::new(text).map(CString::into_raw).map_err(|_| {
+            fail(
+                SUPERCONFIG_ERROR_WRONG_TYPE,
+                &format!("superconfig.capi: Value at {path} contains NUL"),
+            )
+        })
+    })();
+    // SAFETY: checked non-NULL before any success
+    unsafe { store(result, value) }
+}
+
+/// Store the number at `path` of an entry, such as `"database.port"`, in `*value`
+///
+/// Fails with `SUPERCONFIG_ERROR_WRONG_TYPE` if the value isn't a number; integers
+/// beyond 2^53 lose precision.
+///
+/// # Safety
+///
+/// `registry` is a live registry, `path` a NUL-terminated string, and `value` valid
+/// for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn superconfig_get_number(
+    registry: *const Registry,
+    handle: u64,
+    path: *const c_char,
+    value: *mut f64,
+) -> i32 {
+    let result = (|| {
+        // SAFETY: forwarded from the caller's contract
+        let (registry, path) = unsafe { (registry_arg(registry)?, str_arg(path, "path")?) };
+        out_arg(value, "value")?;
+        registry.value(handle, path)?.as_f64().ok_or_else(|| {
+            fail(
+                SUPERCONFIG_ERROR_WRONG_TYPE,
+                &format!("superconfig.capi: Value at {path} is not a number"),
+            )
+        })
+    })();
+    // SAFETY: checked non-NULL before any success
+    unsafe { store(result, value) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SUPERCONFIG_OK, superconfig_last_error, superconfig_string_free};
+    use std::ffi::CStr;
+    use std::ptr;
+
+    fn last_error() -> String {
+        // SAFETY: set by the failing call before
+        unsafe { CStr::from_ptr(superconfig_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_load_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.toml");
+        std::fs::write(
+            &path,
+            "[database]\nhost = \"db\"\nport = 5432\ntags = [\"a\"]",
+        )
+        .unwrap();
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+
+        let registry = superconfig_registry_new();
+        let mut handle = 0;
+        let mut port = 0.0;
+        let mut text = ptr::null_mut();
+        // SAFETY: live registry, NUL-terminated strings and valid outputs
+        unsafe {
+            assert_eq!(
+                superconfig_load_file(registry, path.as_ptr(), &raw mut handle),
+                SUPERCONFIG_OK
+            );
+            assert_eq!(
+                superconfig_get_number(registry, handle, c"database.port".as_ptr(), &raw mut port),
+                SUPERCONFIG_OK
+            );
+            assert!((port - 5432.0).abs() < f64::EPSILON);
+
+            for (key, expected) in [(c"database.host", "db"), (c"database.tags", r#"["a"]"#)] {
+                let status = superconfig_get_string(registry, handle, key.as_ptr(), &raw mut text);
+                assert_eq!(status, SUPERCONFIG_OK);
+                assert_eq!(CStr::from_ptr(text).to_str().unwrap(), expected);
+                superconfig_string_free(text);
+            }
+
+            let mut inline = 0;
+            let status =
+                superconfig_load_str(registry, c"PORT=80".as_ptr(), ptr::null(), &raw mut inline);
+            assert_eq!(status, SUPERCONFIG_OK);
+            assert_ne!(inline, handle);
+            superconfig_registry_free(registry);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let registry = superconfig_registry_new();
+        let mut handle = 0;
+        let mut port = 0.0;
+        // SAFETY: live registry, NUL-terminated strings and valid outputs
+        unsafe {
+            let status = superconfig_load_file(registry, c"missing.toml".as_ptr(), &raw mut handle);
+            assert_eq!(status, SUPERCONFIG_ERROR_LOAD);
+            assert!(last_error().contains("Failed to read"));
+
+            let status = superconfig_load_str(
+                registry,
+                c"{\"host\": \"db\"}".as_ptr(),
+                c"xml".as_ptr(),
+                &raw mut handle,
+            );
+            assert_eq!(status, SUPERCONFIG_ERROR_INVALID_ARGUMENT);
+            assert_eq!(last_error(), "superconfig.capi: Unknown format 'xml'");
+
+            let status = superconfig_load_str(
+                registry,
+                c"{\"host\": \"db\"}".as_ptr(),
+                c"json".as_ptr(),
+                &raw mut handle,
+            );
+            assert_eq!(status, SUPERCONFIG_OK);
+            let host = c"host".as_ptr();
+            let status = superconfig_get_number(registry, handle, host, &raw mut port);
+            assert_eq!(status, SUPERCONFIG_ERROR_WRONG_TYPE);
+            let status = superconfig_get_number(registry, handle + 1, host, &raw mut port);
+            assert_eq!(status, SUPERCONFIG_ERROR_NOT_FOUND);
+            let status = superconfig_get_number(registry, handle, c"port".as_ptr(), &raw mut port);
+            assert_eq!(status, SUPERCONFIG_ERROR_NOT_FOUND);
+            let status = superconfig_get_number(registry, handle, host, ptr::null_mut());
+            assert_eq!(status, SUPERCONFIG_ERROR_INVALID_ARGUMENT);
+            let status = superconfig_get_number(ptr::null(), handle, host, &raw mut port);
+            assert_eq!(status, SUPERCONFIG_ERROR_INVALID_ARGUMENT);
+            assert!(
+                port.abs() < f64::EPSILON,
+                "port was written by failed calls"
+            );
+            superconfig_registry_free(registry);
+        }
+    }
+}
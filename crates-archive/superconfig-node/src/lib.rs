@@ -0,0 +1,42 @@
+//! Node.js bindings for the `SuperConfig` registry
+//!
+//! Built with the napi CLI into a `.node` addon, with `index.js` loading the binary of
+//! the platform and `index.d.ts` typing it. Entries are stored as JSON trees and
+//! cross the boundary as JavaScript values; handles are their numeric IDs.
+//!
+//! ```js
+//! const { Registry } = require('superconfig')
+//!
+//! const registry = new Registry()
+//! const handle = await registry.loadFile('app.toml')
+//! registry.get(handle, 'database.port')
+//!
+//! const watcher = registry.watch(handle, ['app.toml'])
+//! watcher.on('change', ({ generation }) => console.log('reloaded', generation))
+//! watcher.on('error', ({ error }) => console.error(error))
+//! ```
+
+use napi::{Error, Result};
+use superconfig::HandleId;
+
+mod registry;
+mod watch;
+
+pub use registry::{LoadFile, LoadUrl, Registry, UrlOptions};
+pub use watch::FileWatcher;
+
+/// Throw `message` as a JavaScript `Error`
+#[allow(clippy::needless_pass_by_value)] // Used with `map_err`
+fn js_error(message: String) -> Error {
+    Error::from_reason(message)
+}
+
+/// Handle ID of a JavaScript handle number
+fn handle_id(handle: i64) -> Result<HandleId> {
+    HandleId::try_from(handle).map_err(|_| js_error(format!("Invalid handle {handle}")))
+}
+
+/// JavaScript number of a handle ID
+fn handle_number(id: HandleId) -> Result<i64> {
+    i64::try_from(id).map_err(|_| js_error(format!("Handle {id} exceeds the number range")))
+}
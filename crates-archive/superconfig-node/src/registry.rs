@@ -0,0 +1,198 @@
+//! The `Registry` class, and the tasks behind its Promises
+
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Result, Task};
+use napi_derive::napi;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use superconfig::sources::http::{DEFAULT_BACKOFF, HttpSource};
+use superconfig::{ConfigHandle, ConfigRegistry};
+
+use crate::watch::FileWatcher;
+use crate::{handle_id, handle_number, js_error};
+
+/// Configuration registry holding JSON entries, addressed by handle ID
+#[napi]
+pub struct Registry {
+    registry: Arc<ConfigRegistry>,
+}
+
+/// Request options of `Registry.loadUrl`
+#[napi(object)]
+pub struct UrlOptions {
+    /// Headers sent with the request, such as `Authorization`
+    pub headers: Option<HashMap<String, String>>,
+    /// Time allowed for the whole request, 10 seconds by default
+    pub timeout_ms: Option<u32>,
+    /// Retries after a transport error, `429` or `5xx` response, 3 by default
+    pub retries: Option<u32>,
+}
+
+impl Registry {
+    /// Typed handle of the entry `handle`, checking it holds a JSON tree
+    fn handle(&self, handle: i64) -> Result<ConfigHandle<Value>> {
+        self.registry.resolve(handle_id(handle)?).map_err(js_error)
+    }
+}
+
+#[napi]
+#[allow(clippy::needless_pass_by_value)] // napi passes JavaScript strings as `String`
+impl Registry {
+    #[napi(constructor)]
+    fn new() -> Self {
+        Self {
+            registry: ConfigRegistry::new(),
+        }
+    }
+
+    /// Store `data`, a JSON-serializable value, and return its handle
+    #[napi(ts_args_type = "data: unknown")]
+    fn create(&self, data: Value) -> Result<i64> {
+        let handle = self.registry.create(data).map_err(js_error)?;
+        handle_number(handle.id())
+    }
+
+    /// Load a file in the format of its extension, off the JavaScript thread
+    #[napi(ts_return_type = "Promise<number>")]
+    fn load_file(&self, path: String) -> AsyncTask<LoadFile> {
+        AsyncTask::new(LoadFile {
+            registry: Arc::clone(&self.registry),
+            path: PathBuf::from(path),
+        })
+    }
+
+    /// Fetch a document over HTTP(S), off the JavaScript thread
+    #[napi(ts_return_type = "Promise<number>")]
+    fn load_url(&self, url: String, options: Option<UrlOptions>) -> AsyncTask<LoadUrl> {
+        let mut source = HttpSource::new(url);
+        if let Some(options) = options {
+            for (name, value) in options.headers.unwrap_or_default() {
+                source = source.with_header(name, value);
+            }
+            if let Some(timeout_ms) = options.timeout_ms {
+                source = source.with_timeout(Duration::from_millis(timeout_ms.into()));
+            }
+            if let Some(retries) = options.retries {
+                source = source.with_retries(retries, DEFAULT_BACKOFF);
+            }
+        }
+        AsyncTask::new(LoadUrl {
+            registry: Arc::clone(&self.registry),
+            source,
+        })
+    }
+
+    /// The data of an entry
+    #[napi(ts_return_type = "unknown")]
+    fn read(&self, handle: i64) -> Result<Value> {
+        let data = self
+            .registry
+            .read(&self.handle(handle)?)
+            .map_err(js_error)?;
+        Ok(Value::clone(&data))
+    }
+
+    /// The value at a key path of an entry, such as `'database.port'` or `'hosts.0'`
+    #[napi(ts_return_type = "unknown")]
+    fn get(&self, handle: i64, path: String) -> Result<Value> {
+        self.registry
+            .get_path(&self.handle(handle)?, &path)
+            .map_err(js_error)
+    }
+
+    /// Set the value at a key path of an entry, adding missing object keys
+    #[napi(ts_args_type = "handle: number, path: string, value: unknown")]
+    fn set(&self, handle: i64, path: String, value: Value) -> Result<()> {
+        self.registry
+            .set_path(&self.handle(handle)?, &path, value)
+            .map_err(js_error)
+    }
+
+    /// Replace the data of an entry
+    #[napi(ts_args_type = "handle: number, data: unknown")]
+    fn update(&self, handle: i64, data: Value) -> Result<()> {
+        self.registry
+            .update(&self.handle(handle)?, data)
+            .map_err(js_error)
+    }
+
+    /// Remove an entry and return its last data
+    #[napi(ts_return_type = "unknown")]
+    fn delete(&self, handle: i64) -> Result<Value> {
+        let data = self
+            .registry
+            .delete(&self.handle(handle)?)
+            .map_err(js_error)?;
+        Ok(Value::clone(&data))
+    }
+
+    /// Registry statistics
+    #[napi(ts_return_type = "Record<string, unknown>")]
+    fn stats(&self) -> Result<Value> {
+        serde_json::to_value(self.registry.stats()).map_err(|e| js_error(e.to_string()))
+    }
+
+    /// Reload an entry from `paths` when they change, until the watcher is closed
+    ///
+    /// Reloads are emitted as `'change'` events, and files that fail to load as
+    /// `'error'` events.
+    #[napi]
+    fn watch(
+        &self,
+        handle: i64,
+        paths: Vec<String>,
+        debounce_ms: Option<u32>,
+    ) -> Result<FileWatcher> {
+        let debounce = debounce_ms.map(|debounce_ms| Duration::from_millis(debounce_ms.into()));
+        FileWatcher::start(&self.registry, &self.handle(handle)?, paths, debounce)
+    }
+}
+
+/// Loading of a file, run on the libuv thread pool by `Registry.loadFile`
+pub struct LoadFile {
+    registry: Arc<ConfigRegistry>,
+    path: PathBuf,
+}
+
+impl Task for LoadFile {
+    type Output = i64;
+    type JsValue = i64;
+
+    fn compute(&mut self) -> Result<i64> {
+        let handle = self
+            .registry
+            .create_from_file::<Value>(&self.path)
+            .map_err(js_error)?;
+        handle_number(handle.id())
+    }
+
+    fn resolve(&mut self, _env: Env, output: i64) -> Result<i64> {
+        Ok(output)
+    }
+}
+
+/// Fetch of a document, run on the libuv thread pool by `Registry.loadUrl`
+pub struct LoadUrl {
+    registry: Arc<ConfigRegistry>,
+    source: HttpSource,
+}
+
+impl Task for LoadUrl {
+    type Output = i64;
+    type JsValue = i64;
+
+    fn compute(&mut self) -> Result<i64> {
+        let handle = self
+            .registry
+            .load_url_with::<Value>(&self.source)
+            .map_err(js_error)?;
+        handle_number(handle.id())
+    }
+
+    fn resolve(&mut self, _env: Env, output: i64) -> Result<i64> {
+        Ok(output)
+    }
+}
@@ -0,0 +1,136 @@
+//! The `FileWatcher` class, emitting hot reloads to JavaScript listeners
+
+use napi::bindgen_prelude::Unknown;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Result, Status};
+use napi_derive::napi;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use superconfig::watch::{self, HotReload, ReloadEvent};
+use superconfig::{ConfigHandle, ConfigRegistry};
+
+use crate::js_error;
+
+/// JavaScript function called with an event object from any thread
+type Listener = ThreadsafeFunction<Value, Unknown<'static>, Value, Status, false>;
+
+/// Listeners of each event, added with `FileWatcher.on`
+#[derive(Default)]
+struct Listeners {
+    change: Vec<Listener>,
+    error: Vec<Listener>,
+}
+
+impl Listeners {
+    /// Queue `event` on the JavaScript thread of each of its listeners
+    fn emit(&self, event: &ReloadEvent) {
+        let (listeners, event) = match event {
+            ReloadEvent::Reloaded {
+                handle_id,
+                generation,
+            } => (
+                &self.change,
+                json!({"handleId": handle_id, "generation": generation}),
+            ),
+            ReloadEvent::Failed { handle_id, error } => {
+                (&self.error, json!({"handleId": handle_id, "error": error}))
+            }
+            _ => return,
+        };
+        for listener in listeners {
+            listener.call(event.clone(), ThreadsafeFunctionCallMode::NonBlocking);
+        }
+    }
+}
+
+/// Running hot reload of an entry, returned by `Registry.watch`
+///
+/// Emits `'change'` with `{ handleId, generation }` after each reload, and `'error'`
+/// with `{ handleId, error }` when the files fail to load; the entry then keeps its
+/// configuration. Listeners keep Node.js running until the watcher is closed.
+#[napi]
+pub struct FileWatcher {
+    watcher: Option<watch::FileWatcher>,
+    listeners: Arc<Mutex<Listeners>>,
+}
+
+impl FileWatcher {
+    pub(crate) fn start(
+        registry: &Arc<ConfigRegistry>,
+        handle: &ConfigHandle<Value>,
+        paths: Vec<String>,
+        debounce: Option<Duration>,
+    ) -> Result<Self> {
+        let listeners = Arc::new(Mutex::new(Listeners::default()));
+        let emitting = Arc::clone(&listeners);
+        let mut reload = HotReload::new(paths).on_reload(move |event| {
+            emitting
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .emit(event);
+        });
+        if let Some(debounce) = debounce {
+            reload = reload.debounce(debounce);
+        }
+        let watcher = reload.start(registry, handle).map_err(js_error)?;
+        Ok(Self {
+            watcher: Some(watcher),
+            listeners,
+        })
+    }
+}
+
+#[napi]
+#[allow(clippy::needless_pass_by_value)] // napi passes JavaScript strings as `String`
+impl FileWatcher {
+    /// Call `listener` on each `'change'` or `'error'` event
+    #[napi(
+        ts_args_type = "event: 'change' | 'error', listener: (event: { handleId: number, generation?: number, error?: string }) => void"
+    )]
+    fn on(&self, event: String, listener: Listener) -> Result<()> {
+        if !matches!(event.as_str(), "change" | "error") {
+            return Err(js_error(format!("Unknown event '{event}'")));
+        }
+        let mut listeners = self
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if event == "change" {
+            listeners.change.push(listener);
+        } else {
+            listeners.error.push(listener);
+        }
+        drop(listeners);
+        Ok(())
+    }
+
+    /// Reload the files now and return the entry's new generation
+    ///
+    /// Listeners are called as for changes. Throws if the files fail to load.
+    #[napi]
+    fn reload(&self) -> Result<i64> {
+        let watcher = self
+            .watcher
+            .as_ref()
+            .ok_or_else(|| js_error("superconfig.watch: Watcher was closed".to_string()))?;
+        let generation = watcher.reload().map_err(js_error)?;
+        i64::try_from(generation).map_err(|e| js_error(e.to_string()))
+    }
+
+    /// Stop watching and release the listeners
+    #[napi]
+    fn close(&mut self) {
+        drop(self.watcher.take());
+        *self
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Listeners::default();
+    }
+
+    /// Whether the watcher is still running
+    #[napi(getter)]
+    const fn running(&self) -> bool {
+        self.watcher.is_some()
+    }
+}
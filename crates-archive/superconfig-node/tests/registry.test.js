@@ -0,0 +1,58 @@
+// Tests of the addon, run with `npm test` after `npm run build`
+const assert = require('node:assert/strict')
+const fs = require('node:fs')
+const os = require('node:os')
+const path = require('node:path')
+const { test } = require('node:test')
+
+const { Registry } = require('..')
+
+test('create, read, update and delete', () => {
+  const registry = new Registry()
+  const handle = registry.create({ host: 'localhost', ports: [80, 443] })
+  assert.deepEqual(registry.read(handle), { host: 'localhost', ports: [80, 443] })
+  assert.equal(registry.get(handle, 'ports.1'), 443)
+
+  registry.set(handle, 'host', 'db.internal')
+  registry.update(handle, { ...registry.read(handle), debug: true })
+  assert.equal(registry.get(handle, 'host'), 'db.internal')
+  assert.equal(registry.get(handle, 'debug'), true)
+
+  const stats = registry.stats()
+  assert.equal(stats.total_handles, 1)
+  assert.equal(stats.total_updates, 2)
+
+  assert.equal(registry.delete(handle).debug, true)
+  assert.throws(() => registry.read(handle), /not found/)
+  assert.throws(() => registry.get(registry.create({ port: 80 }), 'missing.key'))
+})
+
+test('loadFile resolves a handle and watch emits changes', async (t) => {
+  const directory = fs.mkdtempSync(path.join(os.tmpdir(), 'superconfig-'))
+  t.after(() => fs.rmSync(directory, { recursive: true, force: true }))
+  const file = path.join(directory, 'app.toml')
+  fs.writeFileSync(file, 'name = "api"\nport = 80\n')
+
+  const registry = new Registry()
+  const handle = await registry.loadFile(file)
+  assert.deepEqual(registry.read(handle), { name: 'api', port: 80 })
+  await assert.rejects(registry.loadFile(path.join(directory, 'missing.toml')))
+
+  const watcher = registry.watch(handle, [file], 20)
+  t.after(() => watcher.close())
+  const changed = new Promise((resolve) => watcher.on('change', resolve))
+  assert.throws(() => watcher.on('reloaded', () => {}), /Unknown event/)
+
+  fs.writeFileSync(file, 'name = "api"\nport = 8080\n')
+  const event = await changed
+  assert.equal(event.handleId, handle)
+  assert.equal(registry.get(handle, 'port'), 8080)
+
+  fs.writeFileSync(file, 'port = [')
+  assert.throws(() => watcher.reload())
+  assert.equal(registry.get(handle, 'port'), 8080)
+
+  watcher.close()
+  assert.equal(watcher.running, false)
+  assert.throws(() => watcher.reload(), /closed/)
+})
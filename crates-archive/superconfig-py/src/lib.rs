@@ -0,0 +1,87 @@
+//! Python bindings for the `SuperConfig` registry
+//!
+//! Built with maturin into the `superconfig` Python module. Entries are stored as JSON
+//! trees and cross the boundary as Python objects converted through the `json` module,
+//! so `read` returns dicts, lists and scalars. Handles are their `int` IDs.
+//!
+//! ```python
+//! import superconfig
+//!
+//! registry = superconfig.Registry()
+//! handle = registry.create_from_file("app.toml")
+//! registry.get(handle, "database.port")
+//!
+//! watcher = registry.watch(handle, ["app.toml"], lambda event: print(event))
+//! ```
+//!
+//! Registry errors raise [`ConfigError`]. With the `callback` feature (on by default),
+//! [`set_log_callback`] forwards the binding's logs, such as reload results, to Python
+//! through logfusion's callback backend.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use serde_json::Value;
+
+mod registry;
+mod watch;
+
+pub use registry::Registry;
+pub use watch::FileWatcher;
+
+create_exception!(
+    superconfig,
+    ConfigError,
+    PyException,
+    "Error reported by the configuration registry"
+);
+
+/// Raise `message` as a [`ConfigError`]
+#[allow(clippy::needless_pass_by_value)] // Used with `map_err`
+fn config_error(message: String) -> PyErr {
+    ConfigError::new_err(message)
+}
+
+/// Convert a Python object to a JSON tree with `json.dumps`
+fn to_json(data: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let json = data
+        .py()
+        .import("json")?
+        .call_method1("dumps", (data,))?
+        .extract::<String>()?;
+    serde_json::from_str(&json).map_err(|e| config_error(format!("Invalid JSON value: {e}")))
+}
+
+/// Convert a JSON tree to a Python object with `json.loads`
+fn to_python(py: Python<'_>, tree: &Value) -> PyResult<PyObject> {
+    let json = serde_json::to_string(tree).map_err(|e| config_error(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+/// Call `callback(level, target, message)` for each log of the binding
+///
+/// Replaces the previous callback. The callback runs on the thread that logged, such
+/// as a file watcher's thread, and must not raise: exceptions are printed and
+/// ignored. Logs are still written to the `tracing` subscriber.
+#[cfg(feature = "callback")]
+#[pyfunction]
+fn set_log_callback(callback: PyObject) {
+    logfusion::set_callback(Box::new(move |level, target, message| {
+        Python::with_gil(|py| {
+            if let Err(error) = callback.call1(py, (level, target, message)) {
+                error.print(py);
+            }
+        });
+    }));
+}
+
+#[pymodule]
+#[pyo3(name = "superconfig")]
+fn superconfig_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Registry>()?;
+    m.add_class::<FileWatcher>()?;
+    m.add("ConfigError", m.py().get_type::<ConfigError>())?;
+    #[cfg(feature = "callback")]
+    m.add_function(wrap_pyfunction!(set_log_callback, m)?)?;
+    Ok(())
+}
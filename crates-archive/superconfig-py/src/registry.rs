@@ -0,0 +1,129 @@
+//! The `Registry` Python class
+
+use pyo3::prelude::*;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use superconfig::watch::HotReload;
+use superconfig::{ConfigHandle, ConfigRegistry, HandleId};
+
+use crate::watch::{self, FileWatcher};
+use crate::{config_error, to_json, to_python};
+
+/// Configuration registry holding JSON entries, addressed by handle ID
+#[pyclass(module = "superconfig", frozen)]
+pub struct Registry {
+    registry: Arc<ConfigRegistry>,
+}
+
+impl Registry {
+    /// Typed handle of the entry `handle_id`, checking it holds a JSON tree
+    fn handle(&self, handle_id: HandleId) -> PyResult<ConfigHandle<Value>> {
+        self.registry.resolve(handle_id).map_err(config_error)
+    }
+}
+
+#[pymethods]
+impl Registry {
+    #[new]
+    fn new() -> Self {
+        Self {
+            registry: ConfigRegistry::new(),
+        }
+    }
+
+    /// Store `data`, a JSON-serializable object, and return its handle ID
+    fn create(&self, data: &Bound<'_, PyAny>) -> PyResult<HandleId> {
+        let handle = self.registry.create(to_json(data)?).map_err(config_error)?;
+        Ok(handle.id())
+    }
+
+    /// Load a file in the format of its extension and return its handle ID
+    fn create_from_file(&self, path: PathBuf) -> PyResult<HandleId> {
+        let handle = self
+            .registry
+            .create_from_file::<Value>(path)
+            .map_err(config_error)?;
+        Ok(handle.id())
+    }
+
+    /// The data of an entry, as dicts, lists and scalars
+    fn read(&self, py: Python<'_>, handle_id: HandleId) -> PyResult<PyObject> {
+        let data = self
+            .registry
+            .read(&self.handle(handle_id)?)
+            .map_err(config_error)?;
+        to_python(py, &data)
+    }
+
+    /// The value at a key path of an entry, such as `"database.port"` or `"hosts.0"`
+    fn get(&self, py: Python<'_>, handle_id: HandleId, path: &str) -> PyResult<PyObject> {
+        let value = self
+            .registry
+            .get_path::<Value>(&self.handle(handle_id)?, path)
+            .map_err(config_error)?;
+        to_python(py, &value)
+    }
+
+    /// Set the value at a key path of an entry, adding missing object keys
+    fn set(&self, handle_id: HandleId, path: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.registry
+            .set_path(&self.handle(handle_id)?, path, to_json(value)?)
+            .map_err(config_error)
+    }
+
+    /// Replace the data of an entry
+    fn update(&self, handle_id: HandleId, data: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.registry
+            .update(&self.handle(handle_id)?, to_json(data)?)
+            .map_err(config_error)
+    }
+
+    /// Remove an entry and return its last data
+    fn delete(&self, py: Python<'_>, handle_id: HandleId) -> PyResult<PyObject> {
+        let data = self
+            .registry
+            .delete(&self.handle(handle_id)?)
+            .map_err(config_error)?;
+        to_python(py, &data)
+    }
+
+    /// Registry statistics, as a dict
+    fn stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let stats =
+            serde_json::to_value(self.registry.stats()).map_err(|e| config_error(e.to_string()))?;
+        to_python(py, &stats)
+    }
+
+    /// Reload an entry from `paths` when they change, until the watcher is stopped
+    ///
+    /// `callback`, if given, receives each reload as a dict like
+    /// `{"event": "reloaded", "handle_id": 1, "generation": 2}` or
+    /// `{"event": "failed", "handle_id": 1, "error": "..."}`, on the watcher's thread.
+    #[pyo3(signature = (handle_id, paths, callback = None, debounce_ms = None))]
+    fn watch(
+        &self,
+        handle_id: HandleId,
+        paths: Vec<PathBuf>,
+        callback: Option<PyObject>,
+        debounce_ms: Option<u64>,
+    ) -> PyResult<FileWatcher> {
+        let handle = self.handle(handle_id)?;
+        let mut reload = HotReload::new(paths).on_reload(watch::log_reload);
+        if let Some(debounce_ms) = debounce_ms {
+            reload = reload.debounce(Duration::from_millis(debounce_ms));
+        }
+        if let Some(callback) = callback {
+            reload = reload.on_reload(move |event| watch::call_back(&callback, event));
+        }
+        let watcher = reload
+            .start(&self.registry, &handle)
+            .map_err(config_error)?;
+        Ok(FileWatcher::new(watcher))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Registry(entries={})", self.registry.stats().total_handles)
+    }
+}
@@ -0,0 +1,87 @@
+//! The `FileWatcher` Python class, and reload events bridged to Python
+
+use pyo3::prelude::*;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use superconfig::watch::{self, ReloadEvent};
+
+use crate::{config_error, to_python};
+
+/// Running hot reload of an entry, returned by `Registry.watch`
+///
+/// Watching stops with `stop`, or when the watcher is garbage collected.
+#[pyclass(module = "superconfig", frozen)]
+pub struct FileWatcher {
+    watcher: Mutex<Option<watch::FileWatcher>>,
+}
+
+impl FileWatcher {
+    pub(crate) const fn new(watcher: watch::FileWatcher) -> Self {
+        Self {
+            watcher: Mutex::new(Some(watcher)),
+        }
+    }
+
+    /// The running watcher, `None` once stopped
+    fn watcher(&self) -> MutexGuard<'_, Option<watch::FileWatcher>> {
+        // A panic while locked can't leave the option half-changed
+        self.watcher.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[pymethods]
+impl FileWatcher {
+    /// Reload the files now and return the entry's new generation
+    fn reload(&self, py: Python<'_>) -> PyResult<u64> {
+        // Callbacks take the GIL, so it's released while they may run
+        py.allow_threads(|| {
+            self.watcher()
+                .as_ref()
+                .ok_or_else(|| "superconfig.watch: Watcher was stopped".to_string())?
+                .reload()
+        })
+        .map_err(config_error)
+    }
+
+    /// Stop watching, waiting for a running reload to finish
+    fn stop(&self, py: Python<'_>) {
+        let watcher = self.watcher().take();
+        // Dropping joins the watcher's thread, which may be waiting for the GIL
+        py.allow_threads(|| drop(watcher));
+    }
+
+    /// Whether the watcher is still running
+    #[getter]
+    fn running(&self) -> bool {
+        self.watcher().is_some()
+    }
+}
+
+/// Log a reload through logfusion, reaching the callback of `set_log_callback`
+pub fn log_reload(event: &ReloadEvent) {
+    match event {
+        ReloadEvent::Reloaded {
+            handle_id,
+            generation,
+        } => {
+            logfusion::info!(target: "superconfig.py", "Reloaded handle {}, generation {}", handle_id, generation);
+        }
+        ReloadEvent::Failed { handle_id, error } => {
+            logfusion::warn!(target: "superconfig.py", "Reload of handle {} failed: {}", handle_id, error);
+        }
+        _ => {}
+    }
+}
+
+/// Pass a reload to a Python callback as a dict
+pub fn call_back(callback: &PyObject, event: &ReloadEvent) {
+    Python::with_gil(|py| {
+        let result = serde_json::to_value(event)
+            .map_err(|e| config_error(e.to_string()))
+            .and_then(|event| to_python(py, &event))
+            .and_then(|event| callback.call1(py, (event,)));
+        if let Err(error) = result {
+            logfusion::error!(target: "superconfig.py", "Reload callback failed: {}", error);
+            error.print(py);
+        }
+    });
+}
@@ -0,0 +1,81 @@
+"""Tests of the superconfig module, run after `maturin develop`."""
+
+import os
+import tempfile
+import threading
+import unittest
+
+import superconfig
+
+
+class RegistryTest(unittest.TestCase):
+    def setUp(self):
+        self.registry = superconfig.Registry()
+
+    def test_create_read_update_delete(self):
+        handle = self.registry.create({"host": "localhost", "ports": [80, 443]})
+        self.assertEqual(self.registry.read(handle), {"host": "localhost", "ports": [80, 443]})
+        self.assertEqual(self.registry.get(handle, "ports.1"), 443)
+
+        self.registry.set(handle, "host", "db.internal")
+        self.registry.update(handle, {**self.registry.read(handle), "debug": True})
+        self.assertEqual(self.registry.get(handle, "host"), "db.internal")
+        self.assertTrue(self.registry.get(handle, "debug"))
+
+        stats = self.registry.stats()
+        self.assertEqual(stats["total_handles"], 1)
+        self.assertEqual(stats["total_updates"], 2)
+
+        self.assertEqual(self.registry.delete(handle)["debug"], True)
+        with self.assertRaises(superconfig.ConfigError):
+            self.registry.read(handle)
+
+    def test_errors(self):
+        handle = self.registry.create({"port": 80})
+        with self.assertRaisesRegex(superconfig.ConfigError, "not found"):
+            self.registry.read(handle + 1)
+        with self.assertRaises(superconfig.ConfigError):
+            self.registry.get(handle, "missing.key")
+        with self.assertRaises(TypeError):
+            self.registry.create({"handler": object()})
+
+    def test_create_from_file_and_watch(self):
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "app.toml")
+            with open(path, "w") as file:
+                file.write('name = "api"\nport = 80\n')
+            handle = self.registry.create_from_file(path)
+            self.assertEqual(self.registry.read(handle), {"name": "api", "port": 80})
+
+            events = []
+            logs = []
+            reloaded = threading.Event()
+            superconfig.set_log_callback(lambda *log: logs.append(log))
+
+            def on_reload(event):
+                events.append(event)
+                reloaded.set()
+
+            watcher = self.registry.watch(handle, [path], on_reload, debounce_ms=20)
+            with open(path, "w") as file:
+                file.write('name = "api"\nport = 8080\n')
+            self.assertTrue(reloaded.wait(10))
+            self.assertEqual(self.registry.get(handle, "port"), 8080)
+            self.assertEqual(events[0]["event"], "reloaded")
+            self.assertEqual(events[0]["handle_id"], handle)
+
+            with open(path, "w") as file:
+                file.write("port = [")
+            with self.assertRaises(superconfig.ConfigError):
+                watcher.reload()
+            self.assertEqual(self.registry.get(handle, "port"), 8080)
+            self.assertIn(("warn", "superconfig.py"), [log[:2] for log in logs])
+
+            watcher.stop()
+            self.assertFalse(watcher.running)
+            with self.assertRaises(superconfig.ConfigError):
+                watcher.reload()
+
+
+if __name__ == "__main__":
+    unittest.main()
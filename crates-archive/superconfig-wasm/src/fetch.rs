@@ -0,0 +1,113 @@
+//! Fetching documents with the browser's `fetch`
+
+use js_sys::Promise;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use superconfig::Format;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, Response};
+
+#[wasm_bindgen]
+extern "C" {
+    /// `fetch` of the global scope, so it works in windows and workers alike
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_request(request: &Request) -> Promise;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const URL_OPTIONS: &str = r"
+/** Request options of `Registry.loadUrl` */
+export interface UrlOptions {
+  /** Headers sent with the request, such as `Authorization` */
+  headers?: Record<string, string>;
+  /** Format of the document, instead of the one of its content type or URL */
+  format?: 'json' | 'json5' | 'toml' | 'yaml' | 'ini' | 'dotenv';
+}
+";
+
+/// Request options of `Registry.loadUrl`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct UrlOptions {
+    pub headers: BTreeMap<String, String>,
+    pub format: Option<Format>,
+}
+
+/// A fetched document: its body, and its format when its content type names one
+pub struct Document {
+    pub body: String,
+    pub format: Option<Format>,
+}
+
+/// `GET` the document at `url`
+///
+/// Fails on network errors, which include requests blocked by CORS, and on responses
+/// other than `2xx`.
+#[allow(clippy::future_not_send)] // JavaScript values stay on the thread that made them
+pub async fn fetch(url: &str, options: &UrlOptions) -> Result<Document, String> {
+    let init = RequestInit::new();
+    init.set_method("GET");
+    let request = Request::new_with_str_and_init(url, &init).map_err(js_message)?;
+    for (name, value) in &options.headers {
+        request.headers().set(name, value).map_err(js_message)?;
+    }
+
+    let response: Response = JsFuture::from(fetch_with_request(&request))
+        .await
+        .map_err(|e| format!("superconfig.wasm: Failed to fetch {url}: {}", js_message(e)))?
+        .unchecked_into();
+    if !response.ok() {
+        return Err(format!(
+            "superconfig.wasm: HTTP {} from {url}",
+            response.status()
+        ));
+    }
+    let content_type = response.headers().get("content-type").ok().flatten();
+    let body = JsFuture::from(response.text().map_err(js_message)?)
+        .await
+        .map_err(js_message)?
+        .as_string()
+        .unwrap_or_default();
+    Ok(Document {
+        body,
+        format: options
+            .format
+            .or_else(|| content_type.as_deref().and_then(format_of_media_type)),
+    })
+}
+
+/// The format named by a media type, like `application/json` or `application/ld+json`
+fn format_of_media_type(content_type: &str) -> Option<Format> {
+    let media_type = content_type.split(';').next()?.trim();
+    Format::ALL.into_iter().find(|format| {
+        media_type.ends_with(&format!("/{}", format.name()))
+            || media_type.ends_with(&format!("+{}", format.name()))
+    })
+}
+
+/// Message of a JavaScript exception
+#[allow(clippy::needless_pass_by_value)] // Used with `map_err`
+fn js_message(error: JsValue) -> String {
+    error.dyn_ref::<js_sys::Error>().map_or_else(
+        || format!("{error:?}"),
+        |error| String::from(error.message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_of_media_type() {
+        let format = format_of_media_type;
+        assert_eq!(format("application/json"), Some(Format::Json));
+        assert_eq!(
+            format("application/ld+json; charset=utf-8"),
+            Some(Format::Json)
+        );
+        assert_eq!(format("application/toml"), Some(Format::Toml));
+        assert_eq!(format("text/plain"), None);
+    }
+}
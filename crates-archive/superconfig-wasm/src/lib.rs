@@ -0,0 +1,51 @@
+//! WebAssembly build of the `SuperConfig` registry for browsers
+//!
+//! Built with wasm-pack into an ES module with its TypeScript definitions. Browsers
+//! have no filesystem, so configuration files are written to an in-memory one, the
+//! [`Vfs`], and loaded from there; remote documents are fetched with the browser's
+//! `fetch`. Parsing, layering and key paths are the same as on the server. Entries are
+//! stored as JSON trees and cross the boundary as JavaScript values; handles are their
+//! numeric IDs.
+//!
+//! ```js
+//! import init, { Registry } from 'superconfig-wasm'
+//!
+//! await init()
+//! const registry = new Registry()
+//! const handle = registry.loadStr('app.toml', '[database]\nport = 5432')
+//! registry.get(handle, 'database.port')
+//!
+//! registry.writeFile('local.json', '{"database": {"port": 6432}}')
+//! const layered = registry.loadFiles(['app.toml', 'local.json'])
+//!
+//! const remote = await registry.loadUrl('/config/app.yaml', { headers: { 'X-Env': 'dev' } })
+//! ```
+//!
+//! Hot reload, HTTP refresh and the other sources built on threads or the filesystem
+//! are not available in the browser.
+
+use superconfig::HandleId;
+use wasm_bindgen::JsError;
+
+mod fetch;
+mod registry;
+mod vfs;
+
+pub use registry::Registry;
+pub use vfs::Vfs;
+
+/// Throw `message` as a JavaScript `Error`
+#[allow(clippy::needless_pass_by_value)] // Used with `map_err`
+fn js_error(message: String) -> JsError {
+    JsError::new(&message)
+}
+
+/// Handle ID of a JavaScript handle number
+fn handle_id(handle: u32) -> HandleId {
+    HandleId::from(handle)
+}
+
+/// JavaScript number of a handle ID
+fn handle_number(id: HandleId) -> Result<u32, JsError> {
+    u32::try_from(id).map_err(|_| js_error(format!("Handle {id} exceeds the number range")))
+}
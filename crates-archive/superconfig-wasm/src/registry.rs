@@ -0,0 +1,247 @@
+//! The `Registry` class
+
+use js_sys::Promise;
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use superconfig::{ConfigHandle, ConfigRegistry, Format};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+use crate::fetch::{self, UrlOptions};
+use crate::vfs::{Vfs, format_of};
+use crate::{handle_id, handle_number, js_error};
+
+/// Configuration registry holding JSON entries, addressed by handle ID, with the
+/// in-memory files they are loaded from
+#[wasm_bindgen]
+pub struct Registry {
+    registry: Arc<ConfigRegistry>,
+    vfs: Rc<RefCell<Vfs>>,
+}
+
+impl Registry {
+    /// Typed handle of the entry `handle`, checking it holds a JSON tree
+    fn handle(&self, handle: u32) -> Result<ConfigHandle<Value>, JsError> {
+        self.registry.resolve(handle_id(handle)).map_err(js_error)
+    }
+}
+
+#[wasm_bindgen]
+#[allow(clippy::needless_pass_by_value)] // wasm-bindgen passes JavaScript strings as `String`
+impl Registry {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)] // JavaScript classes are built with `new`
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            registry: ConfigRegistry::new(),
+            vfs: Rc::default(),
+        }
+    }
+
+    /// Write `contents` to the file `name`, then load it and return its handle
+    ///
+    /// The format is `format`, or else the one of the name's extension, or else
+    /// detected from the contents.
+    ///
+    /// # Errors
+    ///
+    /// Throws if `format` is unknown or the contents fail to parse.
+    #[wasm_bindgen(js_name = loadStr)]
+    pub fn load_str(
+        &self,
+        name: String,
+        contents: String,
+        format: Option<String>,
+    ) -> Result<u32, JsError> {
+        self.write_file(name.clone(), contents, format)?;
+        self.load_files(vec![name])
+    }
+
+    /// Write `contents` to the file `name`, replacing it, for `loadFiles`
+    ///
+    /// # Errors
+    ///
+    /// Throws if `format` is unknown.
+    #[wasm_bindgen(js_name = writeFile)]
+    pub fn write_file(
+        &self,
+        name: String,
+        contents: String,
+        format: Option<String>,
+    ) -> Result<(), JsError> {
+        let format = format.as_deref().map(parse_format).transpose()?;
+        self.vfs.borrow_mut().write(name, contents, format);
+        Ok(())
+    }
+
+    /// Remove the file `name`, returning whether it existed
+    #[wasm_bindgen(js_name = removeFile)]
+    #[must_use]
+    pub fn remove_file(&self, name: String) -> bool {
+        self.vfs.borrow_mut().remove(&name)
+    }
+
+    /// Names of the files written or fetched
+    #[must_use]
+    pub fn files(&self) -> Vec<String> {
+        self.vfs.borrow().names().map(String::from).collect()
+    }
+
+    /// Load the files `names` into one entry and return its handle
+    ///
+    /// Files are merged in order: objects merge recursively, later values replace
+    /// earlier ones, and `null` removes a key set by an earlier file.
+    ///
+    /// # Errors
+    ///
+    /// Throws if a file is missing or fails to parse.
+    #[wasm_bindgen(js_name = loadFiles)]
+    pub fn load_files(&self, names: Vec<String>) -> Result<u32, JsError> {
+        let handle = self
+            .vfs
+            .borrow()
+            .load(&self.registry, &names)
+            .map_err(js_error)?;
+        handle_number(handle.id())
+    }
+
+    /// Fetch a document, store it as the file named by its URL, and load it
+    ///
+    /// The format is `options.format`, or else the one of the response's content type
+    /// or of the URL's extension, or else detected from the body. The Promise rejects
+    /// on network errors, blocked cross-origin requests and non-`2xx` responses.
+    #[wasm_bindgen(
+        js_name = loadUrl,
+        unchecked_return_type = "Promise<number>"
+    )]
+    pub fn load_url(
+        &self,
+        url: String,
+        #[wasm_bindgen(unchecked_param_type = "UrlOptions")] options: JsValue,
+    ) -> Promise {
+        let registry = Arc::clone(&self.registry);
+        let vfs = Rc::clone(&self.vfs);
+        future_to_promise(async move {
+            let options: UrlOptions = if options.is_undefined() || options.is_null() {
+                UrlOptions::default()
+            } else {
+                serde_wasm_bindgen::from_value(options)?
+            };
+            let document = fetch::fetch(&url, &options).await.map_err(js_error)?;
+            let format = match document.format {
+                Some(format) => format,
+                None => format_of(&url, &document.body).map_err(js_error)?,
+            };
+            let mut vfs = vfs.borrow_mut();
+            vfs.write(url.as_str(), document.body, Some(format));
+            let handle = vfs.load(&registry, &[url]).map_err(js_error)?;
+            drop(vfs);
+            Ok(JsValue::from(handle_number(handle.id())?))
+        })
+    }
+
+    /// The data of an entry
+    ///
+    /// # Errors
+    ///
+    /// Throws if there's no JSON entry `handle`.
+    #[wasm_bindgen(unchecked_return_type = "unknown")]
+    pub fn read(&self, handle: u32) -> Result<JsValue, JsError> {
+        let data = self
+            .registry
+            .read(&self.handle(handle)?)
+            .map_err(js_error)?;
+        to_js(&*data)
+    }
+
+    /// The value at a key path of an entry, such as `'database.port'` or `'hosts.0'`
+    ///
+    /// # Errors
+    ///
+    /// Throws if there's no JSON entry `handle` or no value at `path`.
+    #[wasm_bindgen(unchecked_return_type = "unknown")]
+    pub fn get(&self, handle: u32, path: String) -> Result<JsValue, JsError> {
+        let value = self
+            .registry
+            .get_path::<Value>(&self.handle(handle)?, &path)
+            .map_err(js_error)?;
+        to_js(&value)
+    }
+
+    /// Set the value at a key path of an entry, adding missing object keys
+    ///
+    /// # Errors
+    ///
+    /// Throws if there's no JSON entry `handle` or `value` isn't JSON.
+    pub fn set(
+        &self,
+        handle: u32,
+        path: String,
+        #[wasm_bindgen(unchecked_param_type = "unknown")] value: JsValue,
+    ) -> Result<(), JsError> {
+        self.registry
+            .set_path(&self.handle(handle)?, &path, from_js(value)?)
+            .map_err(js_error)
+    }
+
+    /// Replace the data of an entry
+    ///
+    /// # Errors
+    ///
+    /// Throws if there's no JSON entry `handle` or `data` isn't JSON.
+    pub fn update(
+        &self,
+        handle: u32,
+        #[wasm_bindgen(unchecked_param_type = "unknown")] data: JsValue,
+    ) -> Result<(), JsError> {
+        self.registry
+            .update(&self.handle(handle)?, from_js(data)?)
+            .map_err(js_error)
+    }
+
+    /// Remove an entry and return its last data
+    ///
+    /// # Errors
+    ///
+    /// Throws if there's no JSON entry `handle`.
+    #[wasm_bindgen(unchecked_return_type = "unknown")]
+    pub fn delete(&self, handle: u32) -> Result<JsValue, JsError> {
+        let data = self
+            .registry
+            .delete(&self.handle(handle)?)
+            .map_err(js_error)?;
+        to_js(&*data)
+    }
+
+    /// Registry statistics
+    ///
+    /// # Errors
+    ///
+    /// Throws if the statistics fail to convert.
+    #[wasm_bindgen(unchecked_return_type = "Record<string, unknown>")]
+    pub fn stats(&self) -> Result<JsValue, JsError> {
+        to_js(&self.registry.stats())
+    }
+}
+
+/// The format named `name`, such as `"toml"`
+fn parse_format(name: &str) -> Result<Format, JsError> {
+    serde_json::from_value(Value::from(name))
+        .map_err(|_| js_error(format!("superconfig.wasm: Unknown format '{name}'")))
+}
+
+/// Convert data to a JavaScript value, with objects for maps
+fn to_js(data: &impl Serialize) -> Result<JsValue, JsError> {
+    data.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| js_error(e.to_string()))
+}
+
+/// Convert a JavaScript value to a JSON tree
+fn from_js(value: JsValue) -> Result<Value, JsError> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| js_error(format!("superconfig.wasm: Invalid JSON value: {e}")))
+}
@@ -0,0 +1,186 @@
+//! In-memory filesystem standing in for configuration files in the browser
+
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use superconfig::core::patch::apply_merge_patch;
+use superconfig::{ConfigHandle, ConfigRegistry, Format};
+
+/// A file of the [`Vfs`]
+#[derive(Debug, Clone)]
+struct File {
+    contents: String,
+    format: Option<Format>,
+}
+
+/// Files written by name and parsed like files on disk
+///
+/// A file is parsed in the format given when writing it, or else in the format of its
+/// name's extension, or else of its content, as with
+/// [`ConfigRegistry::create_from_file`]. Names are only keys: `app.toml` and
+/// `./app.toml` are different files.
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::ConfigRegistry;
+/// use superconfig_wasm::Vfs;
+///
+/// let mut vfs = Vfs::default();
+/// vfs.write("defaults.toml", "host = \"localhost\"\nport = 80", None);
+/// vfs.write("local.json", r#"{"port": 8080}"#, None);
+///
+/// let registry = ConfigRegistry::new();
+/// let handle = vfs.load(&registry, &["defaults.toml", "local.json"]).unwrap();
+/// assert_eq!(registry.read(&handle).unwrap()["port"], 8080);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Vfs {
+    files: BTreeMap<String, File>,
+}
+
+impl Vfs {
+    /// Write `contents` to the file `name`, replacing it if it exists
+    pub fn write(
+        &mut self,
+        name: impl Into<String>,
+        contents: impl Into<String>,
+        format: Option<Format>,
+    ) {
+        let file = File {
+            contents: contents.into(),
+            format,
+        };
+        self.files.insert(name.into(), file);
+    }
+
+    /// Contents of the file `name`
+    #[must_use]
+    pub fn read(&self, name: &str) -> Option<&str> {
+        self.files.get(name).map(|file| file.contents.as_str())
+    }
+
+    /// Remove the file `name`, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.files.remove(name).is_some()
+    }
+
+    /// Names of the files, in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Parse the file `name` into a JSON tree
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if there's no file `name`, its format is unknown, or it
+    /// fails to parse.
+    pub fn parse(&self, name: &str) -> Result<Value, String> {
+        let file = self
+            .files
+            .get(name)
+            .ok_or_else(|| format!("superconfig.wasm: No file {name}"))?;
+        let format = match file.format {
+            Some(format) => format,
+            None => format_of(name, &file.contents)?,
+        };
+        format
+            .parse(&file.contents)
+            .map_err(|e| format!("{e} (in {name})"))
+    }
+
+    /// Parse and merge the files `names` into a JSON entry of `registry`
+    ///
+    /// Files are merged in order like `ConfigRegistry::create_from_files`: tables merge
+    /// recursively, later values replace earlier ones, and `null` removes a key.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if a file is missing or fails to parse, or if the
+    /// registry rejects the entry.
+    pub fn load(
+        &self,
+        registry: &ConfigRegistry,
+        names: &[impl AsRef<str>],
+    ) -> Result<ConfigHandle<Value>, String> {
+        let mut tree = Value::Object(Map::new());
+        for name in names {
+            apply_merge_patch(&mut tree, &self.parse(name.as_ref())?);
+        }
+        registry.create(tree)
+    }
+}
+
+/// The format of the document `name`, from its extension or else its content
+pub fn format_of(name: &str, contents: &str) -> Result<Format, String> {
+    let path = name.split(['?', '#']).next().unwrap_or_default();
+    Format::from_path(Path::new(path))
+        .or_else(|| Format::detect(contents))
+        .ok_or_else(|| format!("superconfig.wasm: Unknown format of {name}, pass its format"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_by_format_extension_and_content() {
+        let mut vfs = Vfs::default();
+        vfs.write("app.yml", "port: 80", None);
+        vfs.write("app", "[server]\nport = 80", None);
+        vfs.write("app.conf", "port: 80", Some(Format::Yaml));
+        vfs.write("https://example.com/app.json?v=2", r#"{"port": 80}"#, None);
+
+        let port = |name| vfs.parse(name).unwrap();
+        assert_eq!(port("app.yml")["port"], 80);
+        assert_eq!(port("app")["server"]["port"], 80);
+        assert_eq!(port("app.conf")["port"], 80);
+        assert_eq!(port("https://example.com/app.json?v=2")["port"], 80);
+        assert_eq!(
+            vfs.names().collect::<Vec<_>>(),
+            [
+                "app",
+                "app.conf",
+                "app.yml",
+                "https://example.com/app.json?v=2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_layers_files() {
+        let mut vfs = Vfs::default();
+        vfs.write(
+            "defaults.toml",
+            "host = \"localhost\"\n[db]\nport = 80",
+            None,
+        );
+        vfs.write("local.env", "DB__PORT=8080", None);
+
+        let registry = ConfigRegistry::new();
+        let handle = vfs
+            .load(&registry, &["defaults.toml", "local.env"])
+            .unwrap();
+        let data = registry.read(&handle).unwrap();
+        assert_eq!(data["host"], "localhost");
+        assert_eq!(data["db"]["port"], 8080);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut vfs = Vfs::default();
+        vfs.write("notes", "just text", None);
+        vfs.write("app.json", "{\"port\": ", None);
+
+        assert!(vfs.parse("missing.json").unwrap_err().contains("No file"));
+        assert!(vfs.parse("notes").unwrap_err().contains("Unknown format"));
+        let error = vfs.parse("app.json").unwrap_err();
+        assert!(error.starts_with("superconfig.formats: Invalid json"));
+        assert!(error.ends_with("(in app.json)"));
+
+        assert!(vfs.remove("notes"));
+        assert!(!vfs.remove("notes"));
+        assert_eq!(vfs.read("notes"), None);
+    }
+}
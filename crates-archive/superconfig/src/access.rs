@@ -4,11 +4,15 @@
 //! exporting to different formats, and debugging configuration state.
 
 use figment::Error;
-use figment::error::Actual;
+use figment::error::{Actual, Kind};
+use figment::value::Value;
+use std::time::Duration;
 
 impl crate::SuperConfig {
     /// Export configuration as pretty-formatted JSON string
     ///
+    /// Secret values are masked according to the [redaction policy](Self::with_redaction).
+    ///
     /// # Examples
     /// ```rust
     /// use superconfig::SuperConfig;
@@ -25,7 +29,7 @@ impl crate::SuperConfig {
     /// # Ok::<(), figment::Error>(())
     /// ```
     pub fn as_json(&self) -> Result<String, Error> {
-        let value = self.figment.extract::<serde_json::Value>()?;
+        let value = self.redacted_value()?;
         serde_json::to_string_pretty(&value).map_err(|e| {
             Error::from(figment::error::Kind::InvalidType(
                 Actual::Other(e.to_string()),
@@ -36,6 +40,8 @@ impl crate::SuperConfig {
 
     /// Export configuration as YAML string
     ///
+    /// Secret values are masked according to the [redaction policy](Self::with_redaction).
+    ///
     /// # Examples
     /// ```rust
     /// use superconfig::SuperConfig;
@@ -52,7 +58,7 @@ impl crate::SuperConfig {
     /// # Ok::<(), figment::Error>(())
     /// ```
     pub fn as_yaml(&self) -> Result<String, Error> {
-        let value = self.figment.extract::<serde_json::Value>()?;
+        let value = self.redacted_value()?;
         serde_yml::to_string(&value).map_err(|e| {
             Error::from(figment::error::Kind::InvalidType(
                 Actual::Other(e.to_string()),
@@ -63,6 +69,8 @@ impl crate::SuperConfig {
 
     /// Export configuration as TOML string
     ///
+    /// Secret values are masked according to the [redaction policy](Self::with_redaction).
+    ///
     /// # Examples
     /// ```rust
     /// use superconfig::SuperConfig;
@@ -79,13 +87,15 @@ impl crate::SuperConfig {
     /// # Ok::<(), figment::Error>(())
     /// ```
     pub fn as_toml(&self) -> Result<String, Error> {
-        let value = self.figment.extract::<toml::Value>()?;
-        toml::to_string_pretty(&value).map_err(|e| {
-            Error::from(figment::error::Kind::InvalidType(
-                Actual::Other(e.to_string()),
-                "valid TOML".into(),
-            ))
-        })
+        let value = self.redacted_value()?;
+        toml::Value::try_from(value)
+            .and_then(|value| toml::to_string_pretty(&value))
+            .map_err(|e| {
+                Error::from(figment::error::Kind::InvalidType(
+                    Actual::Other(e.to_string()),
+                    "valid TOML".into(),
+                ))
+            })
     }
 
     /// Get a string value from configuration
@@ -114,6 +124,29 @@ impl crate::SuperConfig {
         self.figment.extract_inner(key.as_ref())
     }
 
+    /// Get a secret string value from configuration
+    ///
+    /// The value is wrapped in a [`SecretString`](crate::secret::SecretString), so it stays
+    /// masked if it ends up in a log line or a serialized struct.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"api": {"token": "abc123"}}"#);
+    ///
+    /// let token = config.get_secret("api.token")?;
+    /// assert_eq!(token.expose_secret(), "abc123");
+    /// assert_eq!(format!("{token:?}"), "SecretString(***MASKED***)");
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn get_secret<K: AsRef<str>>(&self, key: K) -> Result<crate::secret::SecretString, Error> {
+        self.figment
+            .extract_inner(key.as_ref())
+            .map_err(|error| self.redaction.redact_error(error))
+    }
+
     /// Get an array value from configuration
     ///
     /// # Examples
@@ -143,6 +176,183 @@ impl crate::SuperConfig {
         self.figment.extract_inner(key)
     }
 
+    /// Get a boolean value from configuration
+    ///
+    /// Besides booleans, accepts `1` and `0` and the strings `"true"`, `"yes"`, `"on"`,
+    /// `"1"`, `"false"`, `"no"`, `"off"` and `"0"`, in any case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is missing or its value isn't a boolean; the error
+    /// names the key and the source that set it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"debug": "yes", "name": "app"}"#);
+    ///
+    /// assert!(config.get_bool("debug")?);
+    /// assert!(config.get_bool("name").is_err());
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn get_bool(&self, key: &str) -> Result<bool, Error> {
+        self.get_parsed(key, "a boolean", Value::to_bool_lossy)
+    }
+
+    /// Get a signed integer value from configuration
+    ///
+    /// Accepts integers and strings holding one, such as `"-5"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is missing or its value isn't an integer that fits in
+    /// an `i64`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new().with_defaults_string(r#"{"offset": -30}"#);
+    /// assert_eq!(config.get_i64("offset")?, -30);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn get_i64(&self, key: &str) -> Result<i64, Error> {
+        self.get_parsed(key, "a 64-bit signed integer", |value| {
+            let num = value.to_num_lossy()?;
+            let wide = match num.to_i128() {
+                Some(signed) => signed,
+                None => i128::try_from(num.to_u128()?).ok()?,
+            };
+            i64::try_from(wide).ok()
+        })
+    }
+
+    /// Get an unsigned integer value from configuration
+    ///
+    /// Accepts non-negative integers and strings holding one, such as `"5000"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is missing or its value isn't an integer that fits in
+    /// a `u64`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"timeout_ms": 5000, "retries": -1}"#);
+    ///
+    /// assert_eq!(config.get_u64("timeout_ms")?, 5000);
+    /// assert!(config.get_u64("retries").is_err());
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn get_u64(&self, key: &str) -> Result<u64, Error> {
+        self.get_parsed(key, "a 64-bit unsigned integer", |value| {
+            u64::try_from(value.to_num_lossy()?.to_u128_lossy()?).ok()
+        })
+    }
+
+    /// Get a floating point value from configuration
+    ///
+    /// Accepts floats, integers and strings holding either.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is missing or its value isn't a number.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"ratio": 0.75, "scale": 2}"#);
+    ///
+    /// assert_eq!(config.get_f64("ratio")?, 0.75);
+    /// assert_eq!(config.get_f64("scale")?, 2.0);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn get_f64(&self, key: &str) -> Result<f64, Error> {
+        self.get_parsed(key, "a number", |value| {
+            let num = value.to_num_lossy()?;
+            num.to_f64()
+                .or_else(|| num.to_i128().map(|signed| signed as f64))
+                .or_else(|| num.to_u128().map(|unsigned| unsigned as f64))
+        })
+    }
+
+    /// Get a duration value from configuration
+    ///
+    /// Accepts human-readable durations such as `"30s"`, `"5m"` or `"1h 30m"`, and
+    /// numbers of seconds; see [`units`](crate::units) for every unit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is missing or its value isn't a duration.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use std::time::Duration;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"cache": {"ttl": "5m"}, "timeout": 30}"#);
+    ///
+    /// assert_eq!(config.get_duration("cache.ttl")?, Duration::from_secs(300));
+    /// assert_eq!(config.get_duration("timeout")?, Duration::from_secs(30));
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn get_duration(&self, key: &str) -> Result<Duration, Error> {
+        self.get_parsed(
+            key,
+            "a duration such as \"30s\" or \"5m\"",
+            |value| match value {
+                Value::String(_, text) => crate::units::parse_duration(text),
+                Value::Num(_, num) => {
+                    let seconds = num
+                        .to_f64()
+                        .or_else(|| num.to_u128_lossy().map(|unsigned| unsigned as f64))?;
+                    Duration::try_from_secs_f64(seconds).ok()
+                }
+                _ => None,
+            },
+        )
+    }
+
+    /// Get a byte size value from configuration
+    ///
+    /// Accepts human-readable sizes such as `"10MB"` or `"1.5 GiB"`, and numbers of
+    /// bytes; see [`units`](crate::units) for every unit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is missing or its value isn't a byte size.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"max_upload": "10MB", "buffer": "64KiB"}"#);
+    ///
+    /// assert_eq!(config.get_bytes("max_upload")?, 10_000_000);
+    /// assert_eq!(config.get_bytes("buffer")?, 65_536);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn get_bytes(&self, key: &str) -> Result<u64, Error> {
+        self.get_parsed(
+            key,
+            "a byte size such as \"512\" or \"10MB\"",
+            |value| match value {
+                Value::String(_, text) => crate::units::parse_bytes(text),
+                Value::Num(_, num) => u64::try_from(num.to_u128_lossy()?).ok(),
+                _ => None,
+            },
+        )
+    }
+
     /// Check if a configuration key exists
     ///
     /// # Examples
@@ -226,7 +436,7 @@ impl crate::SuperConfig {
     /// # Ok::<(), figment::Error>(())
     /// ```
     pub fn debug_config(&self) -> Result<String, Error> {
-        let json_value = self.figment.extract::<serde_json::Value>()?;
+        let json_value = self.redacted_value()?;
         let pretty_json = serde_json::to_string_pretty(&json_value).map_err(|e| {
             Error::from(figment::error::Kind::InvalidType(
                 Actual::Other(e.to_string()),
@@ -234,9 +444,18 @@ impl crate::SuperConfig {
             ))
         })?;
 
+        let overrides = match &self.env_overrides {
+            Some(overrides) if !overrides.is_empty() => format!(
+                "EMERGENCY OVERRIDES ACTIVE: {}\n\n",
+                overrides.keys().join(", ")
+            ),
+            _ => String::new(),
+        };
+
         Ok(format!(
-            "=== SuperConfig Debug ===\n\nWarnings: {:?}\n\nFinal Configuration:\n{pretty_json}\n\nProvider Chain:\n{:#?}",
-            self.warnings, self.figment
+            "=== SuperConfig Debug ===\n\n{overrides}Warnings: {:?}\n\nFinal Configuration:\n{pretty_json}\n\nProvider Chain:\n{:#?}",
+            self.warnings,
+            self.debug_sources()
         ))
     }
 
@@ -261,4 +480,90 @@ impl crate::SuperConfig {
     pub fn debug_sources(&self) -> Vec<figment::Metadata> {
         self.figment.metadata().cloned().collect()
     }
+
+    /// Where the final value of `key` came from
+    ///
+    /// Returns the provider that set the value last, with the file or environment
+    /// variable it was read from and, for files, the line of the key; see
+    /// [`provenance`](crate::provenance). Keys inside arrays report the source of the
+    /// array. Returns `None` for tables and keys the configuration doesn't have.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// # unsafe { std::env::set_var("ORIGIN_DOC_DATABASE_URL", "postgres://env") };
+    /// // Environment: ORIGIN_DOC_DATABASE_URL=postgres://env
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"database": {"url": "postgres://localhost"}}"#)
+    ///     .with_env("ORIGIN_DOC_");
+    ///
+    /// let origin = config.origin("database.url").unwrap();
+    /// assert_eq!(origin.provider, "Env::Nested");
+    /// assert_eq!(origin.name.as_deref(), Some("ORIGIN_DOC_DATABASE_URL"));
+    /// assert!(config.origin("database").is_none());
+    /// ```
+    pub fn origin(&self, key: &str) -> Option<crate::Origin> {
+        let tree = self.figment.extract::<serde_json::Value>().ok()?;
+        let value = tree.pointer(&format!("/{}", key.replace('.', "/")));
+        if value.is_none_or(serde_json::Value::is_object) {
+            return None;
+        }
+        let mut origin = self.provenance.get(key)?.clone();
+        origin.line = origin.file().and_then(|file| {
+            ["", "_add", "_remove"]
+                .iter()
+                .find_map(|suffix| crate::provenance::find_line(file, &format!("{key}{suffix}")))
+        });
+        Some(origin)
+    }
+
+    /// Report of where the final value of every key came from
+    ///
+    /// Lists each key with its [`origin`](Self::origin), one per line and sorted by key.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"name": "app", "port": 8080}"#);
+    ///
+    /// println!("{}", config.debug_provenance());
+    /// ```
+    pub fn debug_provenance(&self) -> String {
+        self.provenance
+            .iter()
+            .filter_map(|(key, _)| Some(format!("{key} <- {}", self.origin(key)?)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Convert the value of `key` with `parse`, failing with an error that names the
+    /// key, the value and its source when `parse` returns `None`
+    fn get_parsed<T>(
+        &self,
+        key: &str,
+        expected: &str,
+        parse: impl FnOnce(&Value) -> Option<T>,
+    ) -> Result<T, Error> {
+        let value = self
+            .figment
+            .find_value(key)
+            .map_err(|error| self.redaction.redact_error(error))?;
+        parse(&value).ok_or_else(|| {
+            let mut error =
+                Error::from(Kind::InvalidValue(value.to_actual(), expected.into())).with_path(key);
+            error.metadata = self.figment.get_metadata(value.tag()).cloned();
+            error.profile = Some(self.figment.profile().clone());
+            self.redaction.redact_error(error)
+        })
+    }
+
+    /// The merged configuration with secret values masked by the redaction policy
+    pub(crate) fn redacted_value(&self) -> Result<serde_json::Value, Error> {
+        let mut value = self.figment.extract::<serde_json::Value>()?;
+        self.redaction.redact(&mut value);
+        Ok(value)
+    }
 }
@@ -0,0 +1,201 @@
+//! Configuration-driven discovery of additional sources
+//!
+//! [`SuperConfig::with_discovered_sources`] loads the files matching glob patterns listed
+//! in the configuration loaded so far (the bootstrap configuration), so deployments can
+//! add configuration directories without rebuilding the application. Patterns come from:
+//!
+//! - The array of strings at the given key, in any source merged before the call. Entries
+//!   without glob characters name a single file
+//! - The [`SOURCES_ENV`] environment variable, holding patterns separated like `PATH`
+//!
+//! Discovered files may list more patterns under the same key, which are loaded in a
+//! further pass. A file is never loaded twice, so files listing each other stop after
+//! one round. Relative patterns are resolved against the directory of the file listing
+//! them, like [path values](crate::paths).
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dir = tempfile::tempdir()?;
+//! std::fs::create_dir(dir.path().join("site.d"))?;
+//! std::fs::write(
+//!     dir.path().join("app.toml"),
+//!     "port = 80\nsources = [\"site.d/*.toml\"]",
+//! )?;
+//! std::fs::write(dir.path().join("site.d/10-port.toml"), "port = 8080")?;
+//!
+//! let config = SuperConfig::new()
+//!     .with_file(dir.path().join("app.toml"))
+//!     .with_discovered_sources("sources");
+//!
+//! assert_eq!(config.extract_inner::<u16>("port")?, 8080);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::paths::normalize_path;
+use crate::providers::{Universal, Wildcard};
+use crate::verbosity::{self, DebugCollector};
+use figment::Source;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Environment variable listing extra source patterns, separated like `PATH`
+pub const SOURCES_ENV: &str = "SUPERCONFIG_SOURCES";
+
+impl crate::SuperConfig {
+    /// Load the files matching the glob patterns listed at `key` and in [`SOURCES_ENV`]
+    ///
+    /// Discovered files are merged in the order of the patterns, then of the files each
+    /// pattern matches, on top of everything merged before. Passes repeat while the
+    /// discovered files list patterns matching files not loaded yet. Invalid patterns are
+    /// collected as [`warnings`](Self::warnings). See the [module documentation](crate::discovery).
+    pub fn with_discovered_sources(mut self, key: &str) -> Self {
+        let step = self.next_step();
+        self.debug_step(
+            verbosity::INFO,
+            "discovery",
+            step,
+            &format!("Discovering sources listed at: {key}"),
+        );
+
+        let mut seen_patterns = HashSet::new();
+        let mut loaded: HashSet<PathBuf> = self
+            .metadata()
+            .filter_map(|metadata| match &metadata.source {
+                Some(Source::File(file)) => Some(canonical(file)),
+                _ => None,
+            })
+            .collect();
+        let mut pending = self.env_source_patterns();
+
+        for pass in 1.. {
+            pending.extend(self.listed_source_patterns(key));
+            let patterns: Vec<String> = pending
+                .drain(..)
+                .filter(|pattern| seen_patterns.insert(pattern.clone()))
+                .collect();
+
+            let mut files = Vec::new();
+            for pattern in &patterns {
+                let matches = if pattern.contains(['*', '?', '[', '{']) {
+                    let wildcard = Wildcard::new(pattern);
+                    if let Some(error) = wildcard.has_errors() {
+                        self.warnings.push(format!(
+                            "Invalid discovered source pattern {pattern}: {error}"
+                        ));
+                        continue;
+                    }
+                    wildcard.discover_files()
+                } else {
+                    // Plain file paths are loaded as they are
+                    let file = PathBuf::from(pattern);
+                    if file.is_file() {
+                        vec![file]
+                    } else {
+                        Vec::new()
+                    }
+                };
+                files.extend(
+                    matches
+                        .into_iter()
+                        .filter(|file| loaded.insert(canonical(file))),
+                );
+            }
+            if files.is_empty() {
+                break;
+            }
+
+            for file in files {
+                self.debug_step_result(
+                    verbosity::DEBUG,
+                    "discovery",
+                    step,
+                    &format!("Pass {pass}: loading {}", file.display()),
+                    true,
+                );
+                self = self.merge_validated(Universal::file(file));
+            }
+        }
+        self
+    }
+
+    /// Patterns of [`SOURCES_ENV`], relative ones resolved against the working directory
+    fn env_source_patterns(&self) -> Vec<String> {
+        std::env::var_os(SOURCES_ENV)
+            .map(|value| {
+                std::env::split_paths(&value)
+                    .filter(|pattern| !pattern.as_os_str().is_empty())
+                    .map(|pattern| pattern.to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Patterns listed at `key`, resolved against the directory of the file listing them
+    fn listed_source_patterns(&self, key: &str) -> Vec<String> {
+        let Ok(patterns) = self.figment.extract_inner::<Vec<String>>(key) else {
+            return Vec::new();
+        };
+        let base = self
+            .figment
+            .find_metadata(key)
+            .and_then(|metadata| match &metadata.source {
+                Some(Source::File(file)) => file.parent().map(Path::to_path_buf),
+                _ => None,
+            });
+        patterns
+            .iter()
+            .map(|pattern| {
+                normalize_path(pattern, base.as_deref())
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+}
+
+/// Canonical form of `file` for detecting files loaded twice, `file` itself if it is missing
+fn canonical(file: &Path) -> PathBuf {
+    file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuperConfig;
+
+    #[test]
+    fn test_sources_listing_each_other_load_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (dir.path().join("a.toml"), dir.path().join("b.toml"));
+        std::fs::write(&a, "from_a = 1\nsources = [\"b.toml\"]").unwrap();
+        std::fs::write(&b, "from_b = 2\nsources = [\"a.toml\", \"c.d/*.toml\"]").unwrap();
+        std::fs::create_dir(dir.path().join("c.d")).unwrap();
+        std::fs::write(dir.path().join("c.d/c.toml"), "from_b = 3\nsources = []").unwrap();
+
+        let config = SuperConfig::new()
+            .with_file(&a)
+            .with_discovered_sources("sources");
+
+        assert_eq!(config.extract_inner::<u8>("from_a").unwrap(), 1);
+        assert_eq!(config.extract_inner::<u8>("from_b").unwrap(), 3);
+        let files = config
+            .metadata()
+            .filter(|metadata| matches!(metadata.source, Some(Source::File(_))))
+            .count();
+        assert_eq!(files, 3);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_a_warning() {
+        let config = SuperConfig::new()
+            .with_defaults_string(r#"{"sources": ["[invalid"]}"#)
+            .with_discovered_sources("sources");
+        assert_eq!(config.warnings().len(), 1);
+        assert!(config.warnings()[0].contains("[invalid"));
+    }
+}
@@ -0,0 +1,786 @@
+//! Writing the configuration back to files, keeping their comments and key order
+//!
+//! [`as_toml`](crate::SuperConfig::as_toml) and [`as_yaml`](crate::SuperConfig::as_yaml)
+//! serialize the configuration from scratch: comments are lost and keys come out sorted.
+//! To write a modified configuration back to a file people edit by hand,
+//! [`update_toml`](crate::SuperConfig::update_toml) and
+//! [`update_yaml`](crate::SuperConfig::update_yaml) edit the existing document instead:
+//!
+//! - Values that didn't change keep their spelling, such as quotes and number formats
+//! - Changed values are replaced in place, keeping the comment after them
+//! - Keys the configuration no longer has are removed, and new keys are appended to
+//!   their table
+//!
+//! [`write_to_file`](crate::SuperConfig::write_to_file) updates an existing TOML or YAML
+//! file this way, and writes other files from scratch.
+//!
+//! Secret values are masked according to the [redaction
+//! policy](crate::SuperConfig::with_redaction), as in the other exports. Secret keys
+//! the document already sets keep their value, so writing a file back doesn't replace
+//! its secrets with the mask.
+//!
+//! TOML documents are edited with `toml_edit`. YAML documents are edited line by line:
+//! block mappings are updated in place, and entries the editor doesn't understand (flow
+//! collections, block scalars, anchors) are rewritten as a whole when they change.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//!
+//! let original = "# Server settings\n[server]\nhost = 'localhost' # bind address\nport = 8080\n";
+//!
+//! let config = SuperConfig::new()
+//!     .with_defaults_string(r#"{"server": {"host": "0.0.0.0", "port": 8080, "workers": 4}}"#);
+//!
+//! assert_eq!(
+//!     config.update_toml(original)?,
+//!     "# Server settings\n[server]\nhost = \"0.0.0.0\" # bind address\nport = 8080\nworkers = 4\n"
+//! );
+//! # Ok::<(), figment::Error>(())
+//! ```
+
+use figment::Error;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::secret::RedactionPolicy;
+use crate::verbosity::{self, DebugCollector};
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Pretty-printed JSON
+    Json,
+    /// TOML
+    Toml,
+    /// YAML
+    Yaml,
+}
+
+impl ExportFormat {
+    /// The format of a file, from its extension (`json`, `toml`, `yaml` or `yml`)
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+/// Update the keys of a TOML table to `map`
+///
+/// `inline` tells whether the table is an inline table, whose values must be inline too.
+fn update_toml_table(
+    table: &mut dyn toml_edit::TableLike,
+    map: &Map<String, Value>,
+    path: &str,
+    inline: bool,
+    redaction: &RedactionPolicy,
+) {
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| map.get(key).is_none_or(Value::is_null))
+        .collect();
+    for key in &removed {
+        table.remove(key);
+    }
+    // The spacing before the closing brace belongs to the last key
+    if inline && !removed.is_empty() {
+        table.fmt();
+    }
+
+    for (key, value) in map {
+        match table.get_mut(key) {
+            Some(item) => update_toml_item(item, value, &join(path, key), redaction),
+            None => {
+                if let Some(item) = toml_item(value, inline) {
+                    table.insert(key, item);
+                }
+            }
+        }
+    }
+}
+
+/// Update one TOML item to `value`, keeping it as it is when the value didn't change
+fn update_toml_item(
+    item: &mut toml_edit::Item,
+    value: &Value,
+    path: &str,
+    redaction: &RedactionPolicy,
+) {
+    if redaction.is_secret(path) {
+        return;
+    }
+
+    if let Value::Object(map) = value {
+        let inline = item.is_value();
+        if let Some(table) = item.as_table_like_mut() {
+            update_toml_table(table, map, path, inline, redaction);
+            return;
+        }
+    }
+
+    if let (toml_edit::Item::ArrayOfTables(tables), Value::Array(items)) = (&mut *item, value)
+        && items.iter().all(Value::is_object)
+    {
+        while tables.len() > items.len() {
+            tables.remove(tables.len() - 1);
+        }
+        for (index, map) in items.iter().filter_map(Value::as_object).enumerate() {
+            match tables.get_mut(index) {
+                Some(table) => update_toml_table(table, map, path, false, redaction),
+                None => {
+                    let mut table = toml_edit::Table::new();
+                    update_toml_table(&mut table, map, path, false, redaction);
+                    tables.push(table);
+                }
+            }
+        }
+        return;
+    }
+
+    // Keep the document's spelling of values that didn't change
+    if item
+        .as_value()
+        .is_some_and(|current| toml_to_json(current).as_ref() == Some(value))
+    {
+        return;
+    }
+    let Some(mut replacement) = toml_value(value) else {
+        return;
+    };
+    if let Some(current) = item.as_value() {
+        *replacement.decor_mut() = current.decor().clone();
+    }
+    *item = toml_edit::Item::Value(replacement);
+}
+
+/// A new TOML item for `value`: tables and arrays of tables outside inline tables,
+/// inline values otherwise, and nothing for nulls
+fn toml_item(value: &Value, inline: bool) -> Option<toml_edit::Item> {
+    match value {
+        Value::Null => None,
+        Value::Object(map) if !inline => {
+            let mut table = toml_edit::Table::new();
+            for (key, value) in map {
+                if let Some(item) = toml_item(value, false) {
+                    table.insert(key, item);
+                }
+            }
+            Some(toml_edit::Item::Table(table))
+        }
+        Value::Array(items)
+            if !inline && !items.is_empty() && items.iter().all(Value::is_object) =>
+        {
+            let mut tables = toml_edit::ArrayOfTables::new();
+            for item in items {
+                if let Some(toml_edit::Item::Table(table)) = toml_item(item, false) {
+                    tables.push(table);
+                }
+            }
+            Some(toml_edit::Item::ArrayOfTables(tables))
+        }
+        _ => toml_value(value).map(toml_edit::Item::Value),
+    }
+}
+
+fn toml_value(value: &Value) -> Option<toml_edit::Value> {
+    toml::Value::try_from(value).ok()?.to_string().parse().ok()
+}
+
+fn toml_to_json(value: &toml_edit::Value) -> Option<Value> {
+    let text = format!("value = {}", value.clone().decorated("", ""));
+    let mut table: toml::Table = toml::from_str(&text).ok()?;
+    serde_json::to_value(table.remove("value")?).ok()
+}
+
+/// A `key: value` entry of a YAML block mapping, spanning `start..end` up to its last
+/// non-blank, non-comment line
+struct YamlEntry {
+    key: String,
+    indent: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Lines replacing `start..end` of the original document
+struct YamlEdit {
+    start: usize,
+    end: usize,
+    depth: usize,
+    lines: Vec<String>,
+}
+
+/// Line-based editor of YAML documents made of block mappings
+struct YamlEditor<'a> {
+    lines: Vec<&'a str>,
+    edits: Vec<YamlEdit>,
+    redaction: &'a RedactionPolicy,
+}
+
+impl YamlEditor<'_> {
+    /// Update the block mapping on `start..end` to `map`, or return `false` without
+    /// editing when the lines aren't a block mapping
+    ///
+    /// New keys of an empty block are indented by `indent`.
+    fn update_block(
+        &mut self,
+        start: usize,
+        end: usize,
+        indent: usize,
+        map: &Map<String, Value>,
+        path: &str,
+        depth: usize,
+    ) -> bool {
+        let Some(entries) = yaml_entries(&self.lines, start, end) else {
+            return false;
+        };
+
+        for entry in &entries {
+            let key_path = join(path, &entry.key);
+            match map.get(&entry.key) {
+                None => self.edits.push(YamlEdit {
+                    start: entry.start,
+                    end: entry.end,
+                    depth,
+                    lines: Vec::new(),
+                }),
+                Some(_) if self.redaction.is_secret(&key_path) => {}
+                Some(value) => self.update_entry(entry, value, &key_path, depth),
+            }
+        }
+
+        let indent = entries.first().map_or(indent, |entry| entry.indent);
+        let position = entries.last().map_or(start, |entry| entry.end);
+        let lines: Vec<String> = map
+            .iter()
+            .filter(|(key, _)| !entries.iter().any(|entry| &entry.key == *key))
+            .flat_map(|(key, value)| yaml_lines(key, value, indent))
+            .collect();
+        if !lines.is_empty() {
+            self.edits.push(YamlEdit {
+                start: position,
+                end: position,
+                depth,
+                lines,
+            });
+        }
+        true
+    }
+
+    fn update_entry(&mut self, entry: &YamlEntry, value: &Value, path: &str, depth: usize) {
+        let line = self.lines[entry.start];
+        let Some((_, rest)) = split_yaml_entry(line.trim_start()) else {
+            return;
+        };
+        let (text, comment) = split_yaml_comment(rest);
+
+        if text.is_empty()
+            && let Value::Object(map) = value
+            && self.update_block(
+                entry.start + 1,
+                entry.end,
+                entry.indent + 2,
+                map,
+                path,
+                depth + 1,
+            )
+        {
+            return;
+        }
+
+        if entry.end == entry.start + 1
+            && !value.is_object()
+            && !value.is_array()
+            && is_plain_scalar(text)
+        {
+            if serde_yml::from_str::<Value>(text).ok().as_ref() == Some(value) {
+                return;
+            }
+            let Ok(serialized) = serde_yml::to_string(value) else {
+                return;
+            };
+            let prefix = &line[..line.len() - rest.len()];
+            self.edits.push(YamlEdit {
+                start: entry.start,
+                end: entry.end,
+                depth,
+                lines: vec![format!("{prefix} {}{comment}", serialized.trim_end())],
+            });
+            return;
+        }
+
+        // Other entries are compared as a whole, and rewritten if they changed
+        let text: Vec<&str> = self.lines[entry.start..entry.end]
+            .iter()
+            .map(|line| {
+                line.get(entry.indent..)
+                    .unwrap_or_else(|| line.trim_start())
+            })
+            .collect();
+        let current = serde_yml::from_str::<Map<String, Value>>(&text.join("\n"))
+            .ok()
+            .and_then(|mut map| map.remove(&entry.key));
+        if current.as_ref() != Some(value) {
+            self.edits.push(YamlEdit {
+                start: entry.start,
+                end: entry.end,
+                depth,
+                lines: yaml_lines(&entry.key, value, entry.indent),
+            });
+        }
+    }
+
+    /// The document with the edits applied
+    fn finish(mut self) -> String {
+        // Apply from the bottom up; at the same line, outer insertions go after inner ones
+        self.edits
+            .sort_by(|a, b| b.start.cmp(&a.start).then(a.depth.cmp(&b.depth)));
+        let mut lines: Vec<String> = self.lines.iter().map(|line| line.to_string()).collect();
+        for edit in self.edits {
+            lines.splice(edit.start..edit.end, edit.lines);
+        }
+        let mut document = lines.join("\n");
+        document.push('\n');
+        document
+    }
+}
+
+fn is_yaml_content(line: &str) -> bool {
+    let line = line.trim();
+    !(line.is_empty() || line.starts_with('#') || line == "---" || line == "...")
+}
+
+/// The entries of the block mapping on `lines[start..end]`, or `None` if it isn't one
+fn yaml_entries(lines: &[&str], start: usize, end: usize) -> Option<Vec<YamlEntry>> {
+    let mut entries: Vec<YamlEntry> = Vec::new();
+    let mut block_indent = None;
+    for (index, line) in lines.iter().enumerate().take(end).skip(start) {
+        if !is_yaml_content(line) {
+            continue;
+        }
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let block_indent = *block_indent.get_or_insert(indent);
+        let entry = (indent == block_indent)
+            .then(|| split_yaml_entry(line.trim_start()))
+            .flatten();
+        match (entry, entries.last_mut()) {
+            (Some((key, _)), _) => entries.push(YamlEntry {
+                key,
+                indent,
+                start: index,
+                end: index + 1,
+            }),
+            // Nested lines, and sequences at the indentation of their key
+            (None, Some(last)) if indent >= block_indent => last.end = index + 1,
+            _ => return None,
+        }
+    }
+    Some(entries)
+}
+
+/// Split a `key: rest` mapping entry into its unquoted key and the text after the colon
+fn split_yaml_entry(text: &str) -> Option<(String, &str)> {
+    if text == "-" || text.starts_with("- ") {
+        return None;
+    }
+    let (key, after) = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let close = text[1..].find(quote)? + 1;
+            (text[1..close].to_string(), &text[close + 1..])
+        }
+        _ => {
+            let colon = text
+                .match_indices(':')
+                .map(|(index, _)| index)
+                .find(|&index| {
+                    text[index + 1..].is_empty() || text[index + 1..].starts_with([' ', '\t'])
+                })?;
+            let key = text[..colon].trim_end();
+            if key.is_empty() || key.contains(" #") || key.starts_with(['[', '{', '?', '#']) {
+                return None;
+            }
+            (key.to_string(), &text[colon..])
+        }
+    };
+    let rest = after.strip_prefix(':')?;
+    (rest.is_empty() || rest.starts_with([' ', '\t'])).then_some((key, rest))
+}
+
+/// Split the text after a key's colon into the value and the comment after it, with
+/// the whitespace before the comment
+fn split_yaml_comment(rest: &str) -> (&str, &str) {
+    let text = rest.trim_start();
+    let value_end = match text.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let mut escaped = false;
+            text.char_indices()
+                .skip(1)
+                .find(|&(_, c)| {
+                    let closes = c == quote && !escaped;
+                    escaped = quote == '"' && c == '\\' && !escaped;
+                    closes
+                })
+                .map_or(text.len(), |(index, _)| index + 1)
+        }
+        _ => 0,
+    };
+    let comment = text[value_end..]
+        .match_indices('#')
+        .map(|(index, _)| value_end + index)
+        .find(|&index| index == 0 || text[..index].ends_with([' ', '\t']));
+    match comment {
+        Some(index) => {
+            let value = text[..index].trim_end();
+            (value, &text[value.len()..])
+        }
+        None => (text.trim_end(), ""),
+    }
+}
+
+/// Whether the YAML value text is a one-line scalar the editor can replace
+fn is_plain_scalar(text: &str) -> bool {
+    match text.chars().next() {
+        None | Some('|' | '>' | '&' | '*' | '!' | '[' | '{') => false,
+        Some(quote @ ('"' | '\'')) => text.len() > 1 && text.ends_with(quote),
+        Some(_) => true,
+    }
+}
+
+/// The lines of a `key: value` entry, indented by `indent`
+fn yaml_lines(key: &str, value: &Value, indent: usize) -> Vec<String> {
+    let entry = Map::from_iter([(key.to_string(), value.clone())]);
+    let text = serde_yml::to_string(&entry).unwrap_or_default();
+    text.lines()
+        .map(|line| format!("{}{line}", " ".repeat(indent)))
+        .collect()
+}
+
+impl crate::SuperConfig {
+    /// Export the configuration in `format`
+    ///
+    /// Equivalent to [`as_json`](Self::as_json), [`as_toml`](Self::as_toml) or
+    /// [`as_yaml`](Self::as_yaml).
+    pub fn export(&self, format: ExportFormat) -> Result<String, Error> {
+        match format {
+            ExportFormat::Json => self.as_json(),
+            ExportFormat::Toml => self.as_toml(),
+            ExportFormat::Yaml => self.as_yaml(),
+        }
+    }
+
+    /// Update the TOML document `original` to the configuration, keeping its comments,
+    /// formatting and key order
+    ///
+    /// See [`export`](crate::export) for what is kept. TOML has no null, so keys set to
+    /// null are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration fails to load or `original` isn't valid TOML.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let original = "name = \"app\"  # display name\nlegacy = true\n";
+    /// let config = SuperConfig::new().with_defaults_string(r#"{"name": "api"}"#);
+    ///
+    /// assert_eq!(config.update_toml(original)?, "name = \"api\"  # display name\n");
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn update_toml(&self, original: &str) -> Result<String, Error> {
+        let value = self.redacted_value()?;
+        let mut document: toml_edit::DocumentMut = original
+            .parse()
+            .map_err(|e| Error::from(format!("Cannot update TOML document: {e}")))?;
+        if let Value::Object(map) = &value {
+            update_toml_table(document.as_table_mut(), map, "", false, &self.redaction);
+        }
+        Ok(document.to_string())
+    }
+
+    /// Update the YAML document `original` to the configuration, keeping its comments
+    /// and key order
+    ///
+    /// See [`export`](crate::export) for what is kept. An empty document is written like
+    /// [`as_yaml`](Self::as_yaml).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration fails to load, if `original` isn't a YAML
+    /// mapping, or if it uses YAML the line editor can't update correctly.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let original = "# Database\ndatabase:\n  url: postgres://localhost  # primary\n  pool: 5\n";
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"database": {"url": "postgres://db", "pool": 5}}"#);
+    ///
+    /// assert_eq!(
+    ///     config.update_yaml(original)?,
+    ///     "# Database\ndatabase:\n  url: postgres://db  # primary\n  pool: 5\n"
+    /// );
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn update_yaml(&self, original: &str) -> Result<String, Error> {
+        let value = self.redacted_value()?;
+        if !original.lines().any(is_yaml_content) {
+            return self.as_yaml();
+        }
+        let Value::Object(map) = &value else {
+            return self.as_yaml();
+        };
+
+        let mut editor = YamlEditor {
+            lines: original.lines().collect(),
+            edits: Vec::new(),
+            redaction: &self.redaction,
+        };
+        if !editor.update_block(0, editor.lines.len(), 0, map, "", 0) {
+            return Err(Error::from(
+                "Cannot update YAML document: it isn't a block mapping".to_string(),
+            ));
+        }
+        let document = editor.finish();
+
+        // Secret values kept from the document are masked on both sides
+        let mut updated = serde_yml::from_str::<Value>(&document)
+            .map_err(|e| Error::from(format!("Cannot update YAML document: {e}")))?;
+        self.redaction.redact(&mut updated);
+        if updated != value {
+            return Err(Error::from(
+                "Cannot update YAML document in place; export it with as_yaml instead".to_string(),
+            ));
+        }
+        Ok(document)
+    }
+
+    /// Write the configuration to the file at `path` in `format`
+    ///
+    /// Existing TOML and YAML files are updated with [`update_toml`](Self::update_toml)
+    /// and [`update_yaml`](Self::update_yaml), keeping their comments and key order; other
+    /// files are written from scratch. The file is replaced atomically, so readers never
+    /// see it half-written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration fails to load, the existing file can't be
+    /// updated, or the file can't be written.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use superconfig::export::ExportFormat;
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # let path = dir.path().join("app.toml");
+    /// # std::fs::write(&path, "# Port to listen on\nport = 8080\n").unwrap();
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_file(&path)
+    ///     .with_defaults_string(r#"{"port": 9090}"#);
+    ///
+    /// config.write_to_file(&path, ExportFormat::Toml)?;
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Port to listen on\nport = 9090\n");
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn write_to_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: ExportFormat,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        self.debug(
+            verbosity::INFO,
+            "export",
+            &format!("Writing configuration to {}", path.display()),
+        );
+
+        let original = match std::fs::read_to_string(path) {
+            Ok(original) => Some(original),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(Error::from(format!("Cannot read {}: {e}", path.display())));
+            }
+        };
+        let mut content = match (format, original) {
+            (ExportFormat::Toml, Some(original)) => self.update_toml(&original)?,
+            (ExportFormat::Yaml, Some(original)) => self.update_yaml(&original)?,
+            (format, _) => self.export(format)?,
+        };
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = path.with_file_name(format!(".{file_name}.tmp"));
+        std::fs::write(&temp, content)
+            .and_then(|()| std::fs::rename(&temp, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&temp);
+                Error::from(format!("Cannot write {}: {e}", path.display()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuperConfig;
+
+    fn config(json: &str) -> SuperConfig {
+        SuperConfig::new().with_defaults_string(json)
+    }
+
+    #[test]
+    fn test_update_toml_keeps_comments_and_order() {
+        let original = r#"# App settings
+name = "app"
+debug = false # local only
+
+[server]
+# Bind address
+host = "localhost"
+port = 8_080
+limits = { body = 1024, headers = 64 }
+
+[[upstreams]]
+url = "http://a"
+
+[[upstreams]]
+url = "http://b"
+"#;
+        let config = config(
+            r#"{
+                "name": "app",
+                "debug": true,
+                "server": {"host": "localhost", "port": 8080, "limits": {"body": 2048}, "tls": {"enabled": true}},
+                "upstreams": [{"url": "http://a", "weight": 2}]
+            }"#,
+        );
+
+        assert_eq!(
+            config.update_toml(original).unwrap(),
+            r#"# App settings
+name = "app"
+debug = true # local only
+
+[server]
+# Bind address
+host = "localhost"
+port = 8_080
+limits = { body = 2048 }
+
+[server.tls]
+enabled = true
+
+[[upstreams]]
+url = "http://a"
+weight = 2
+"#
+        );
+    }
+
+    #[test]
+    fn test_update_keeps_secrets_of_the_document() {
+        let config = config(r#"{"api_token": "from-env", "admin_password": "new", "port": 1}"#);
+
+        let updated = config
+            .update_toml("api_token = \"s3cr3t\"\nport = 2\n")
+            .unwrap();
+        assert_eq!(
+            updated,
+            "api_token = \"s3cr3t\"\nport = 1\nadmin_password = \"***MASKED***\"\n"
+        );
+
+        let updated = config.update_yaml("api_token: s3cr3t\nport: 2\n").unwrap();
+        assert_eq!(
+            updated,
+            "api_token: s3cr3t\nport: 1\nadmin_password: '***MASKED***'\n"
+        );
+    }
+
+    #[test]
+    fn test_update_yaml_keeps_comments_and_order() {
+        let original = "---
+# App settings
+name: app
+debug: false  # local only
+server:
+  # Bind address
+  host: localhost
+  port: 8080
+  legacy: true
+
+features:
+- auth
+- metrics
+labels: {team: core}
+";
+        let config = config(
+            r#"{
+                "name": "app",
+                "debug": true,
+                "server": {"host": "0.0.0.0", "port": 8080, "workers": 4},
+                "features": ["auth", "metrics"],
+                "labels": {"team": "platform"},
+                "region": "eu"
+            }"#,
+        );
+
+        assert_eq!(
+            config.update_yaml(original).unwrap(),
+            "---
+# App settings
+name: app
+debug: true  # local only
+server:
+  # Bind address
+  host: '0.0.0.0'
+  port: 8080
+  workers: 4
+
+features:
+- auth
+- metrics
+labels:
+  team: platform
+region: eu
+"
+        );
+    }
+
+    #[test]
+    fn test_split_yaml_comment() {
+        assert_eq!(split_yaml_comment(" value  # note"), ("value", "  # note"));
+        assert_eq!(split_yaml_comment(" 'a # b' # c"), ("'a # b'", " # c"));
+        assert_eq!(split_yaml_comment(r#" "a \" # b""#), (r#""a \" # b""#, ""));
+        assert_eq!(split_yaml_comment(" url#fragment"), ("url#fragment", ""));
+        assert_eq!(split_yaml_comment(""), ("", ""));
+    }
+
+    #[test]
+    fn test_export_format_from_path() {
+        assert_eq!(ExportFormat::from_path("app.YML"), Some(ExportFormat::Yaml));
+        assert_eq!(
+            ExportFormat::from_path("app.toml"),
+            Some(ExportFormat::Toml)
+        );
+        assert_eq!(ExportFormat::from_path("app.ini"), None);
+    }
+}
@@ -83,7 +83,8 @@ impl SuperConfig {
     /// Add a configuration file with smart format detection
     ///
     /// Uses the Universal provider for automatic format detection and caching.
-    /// Supports .toml, .yaml/.yml, .json files with fallback chains.
+    /// Supports .toml, .yaml/.yml, .json files with fallback chains. Files with a
+    /// byte order mark or UTF-16 encoding are converted to UTF-8 and a warning is collected.
     ///
     /// # Examples
     /// ```rust,no_run
@@ -139,7 +140,7 @@ impl SuperConfig {
             }
         }
 
-        self.merge(crate::providers::Universal::file(path))
+        self.merge_validated(crate::providers::Universal::file(path))
     }
 
     /// Add environment variables with a prefix
@@ -194,15 +195,11 @@ impl SuperConfig {
             // Show individual env vars at trace level
             if self.verbosity >= verbosity::TRACE {
                 for (key, value) in &env_vars {
-                    // Mask sensitive values (anything with 'password', 'secret', 'token', 'key' in name)
-                    let display_value = if key.to_lowercase().contains("password")
-                        || key.to_lowercase().contains("secret")
-                        || key.to_lowercase().contains("token")
-                        || key.to_lowercase().contains("key")
-                    {
-                        "***MASKED***".to_string()
+                    // Mask sensitive values (names matching the redaction policy)
+                    let display_value = if self.redaction.is_secret(key) {
+                        crate::secret::REDACTED
                     } else {
-                        value.clone()
+                        value.as_str()
                     };
                     self.debug(verbosity::TRACE, "env", &format!("  {key}={display_value}"));
                 }
@@ -268,10 +265,12 @@ impl SuperConfig {
             }
         }
 
-        self.merge(crate::providers::Wildcard::hierarchical(
-            "config",
-            base_name_str,
-        ))
+        let provider = self.merge_policies.iter().fold(
+            crate::providers::Wildcard::hierarchical("config", base_name_str)
+                .with_path_keys(self.path_keys.keys().cloned()),
+            |provider, (pattern, policy)| provider.with_merge_policy(pattern, policy),
+        );
+        self.merge(provider)
     }
 
     /// Add default configuration values from a serializable struct
@@ -424,6 +423,40 @@ impl SuperConfig {
         }
     }
 
+    /// Add the arguments given to a clap command, with the `clap` feature
+    ///
+    /// Maps each argument to the key named by its id, skipping clap's default values;
+    /// see the [`Clap`](crate::Clap) provider for key names and value types, and
+    /// `merge(Clap::new(matches).with_type(..))` for type hints.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use clap::{Arg, Command, value_parser};
+    /// use superconfig::SuperConfig;
+    ///
+    /// let matches = Command::new("app")
+    ///     .arg(Arg::new("server.port").long("port").value_parser(value_parser!(u16)))
+    ///     .get_matches_from(["app", "--port", "9090"]);
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"server": {"host": "localhost", "port": 8080}}"#)
+    ///     .with_clap(&matches);
+    /// assert_eq!(config.extract_inner::<u16>("server.port")?, 9090);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    #[cfg(feature = "clap")]
+    pub fn with_clap(self, matches: &clap::ArgMatches) -> Self {
+        let step = self.next_step();
+        let provider = crate::Clap::new(matches);
+        self.debug_step(
+            verbosity::INFO,
+            "cli",
+            step,
+            "Loading CLI arguments parsed by clap",
+        );
+        self.merge(provider)
+    }
+
     /// Add an optional configuration file with smart format detection
     ///
     /// Uses the Universal provider for automatic format detection and caching.
@@ -447,6 +480,272 @@ impl SuperConfig {
         }
     }
 
+    /// Add a configuration file that is verified against its `.sha256` sidecar
+    ///
+    /// Uses the Verified provider with [`Verification::Sha256Sidecar`](crate::Verification::Sha256Sidecar).
+    /// Verification failures are collected as warnings under [`VerifyPolicy::Warn`](crate::VerifyPolicy::Warn)
+    /// and make extraction fail under [`VerifyPolicy::Enforce`](crate::VerifyPolicy::Enforce).
+    /// Use [`merge_validated`](Self::merge_validated) with a [`Verified`](crate::Verified) provider
+    /// for signature verification.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use superconfig::{SuperConfig, VerifyPolicy};
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_verified_file("/etc/myapp/config.toml", VerifyPolicy::Enforce);
+    /// ```
+    pub fn with_verified_file<P: AsRef<std::path::Path>>(
+        self,
+        path: P,
+        policy: crate::VerifyPolicy,
+    ) -> Self {
+        let path_str = path.as_ref().to_string_lossy();
+        let step = self.next_step();
+
+        self.debug_step(
+            verbosity::INFO,
+            "file",
+            step,
+            &format!("Loading verified configuration file: {path_str} (policy: {policy:?})"),
+        );
+
+        let provider = crate::Verified::file(path.as_ref()).policy(policy);
+        if policy != crate::VerifyPolicy::Ignore {
+            // The provider keeps this outcome, and parses the bytes verified here
+            let result = provider.verify();
+            self.debug_step_result(
+                verbosity::DEBUG,
+                "file",
+                step,
+                &match &result {
+                    Ok(_) => format!("Integrity verified: {path_str}"),
+                    Err(e) => e.clone(),
+                },
+                result.is_ok(),
+            );
+        }
+
+        self.merge_validated(provider)
+    }
+
+    /// Add a configuration file that is decrypted at load time
+    ///
+    /// Uses the [`Encrypted`](crate::Encrypted) provider: files with the `.enc` extension
+    /// are decrypted whole, and `ENC[AES256_GCM,...]` values are decrypted in place. The
+    /// key is read from `key` only when the file needs it, and a missing key or failed
+    /// decryption makes extraction fail.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use superconfig::{KeySource, SuperConfig};
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_encrypted_file("config.toml.enc", KeySource::Env("APP_CONFIG_KEY".into()));
+    /// ```
+    pub fn with_encrypted_file<P: AsRef<std::path::Path>>(
+        self,
+        path: P,
+        key: crate::KeySource,
+    ) -> Self {
+        let path_str = path.as_ref().to_string_lossy();
+        let step = self.next_step();
+
+        let key_origin = match &key {
+            crate::KeySource::Env(var) => format!("key from ${var}"),
+            crate::KeySource::File(keyfile) => format!("key from {}", keyfile.display()),
+            crate::KeySource::Key(_) => "in-memory key".to_string(),
+        };
+        self.debug_step(
+            verbosity::INFO,
+            "file",
+            step,
+            &format!("Loading encrypted configuration file: {path_str} ({key_origin})"),
+        );
+
+        self.merge(crate::Encrypted::file(path.as_ref()).key(key))
+    }
+
+    /// Add a configuration file with an explicit duplicate key policy
+    ///
+    /// Behaves like [`with_file`](Self::with_file), but keys repeated within the file are
+    /// resolved according to `policy` instead of silently keeping the last definition.
+    /// Under [`DuplicateKeyPolicy::Warn`](crate::DuplicateKeyPolicy::Warn) the duplicated keys
+    /// are collected as warnings; under [`DuplicateKeyPolicy::Error`](crate::DuplicateKeyPolicy::Error)
+    /// extraction fails.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use superconfig::{DuplicateKeyPolicy, SuperConfig};
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_file_duplicate_keys("config.yaml", DuplicateKeyPolicy::Warn);
+    /// ```
+    pub fn with_file_duplicate_keys<P: AsRef<std::path::Path>>(
+        self,
+        path: P,
+        policy: crate::DuplicateKeyPolicy,
+    ) -> Self {
+        let path_str = path.as_ref().to_string_lossy();
+        let step = self.next_step();
+
+        self.debug_step(
+            verbosity::INFO,
+            "file",
+            step,
+            &format!("Loading configuration file: {path_str} (duplicate keys: {policy:?})"),
+        );
+
+        let provider = crate::providers::Universal::file_with_duplicate_keys(path.as_ref(), policy);
+        if let Some(warning) = provider.warning() {
+            self.debug_step_result(verbosity::DEBUG, "file", step, warning, false);
+        }
+
+        self.merge_validated(provider)
+    }
+
+    /// Enable emergency per-key overrides from `SUPERCONFIG_OVERRIDE__` environment variables
+    ///
+    /// Variables like `SUPERCONFIG_OVERRIDE__database__port=5433` override the matching key
+    /// as the final layer: they are re-applied after every later merge, so no other source
+    /// can replace them. Each active override is collected as a warning, logged at
+    /// [`INFO`](verbosity::INFO) verbosity, listed by [`debug_config`](Self::debug_config),
+    /// and reported as the key's source in Figment's metadata. See
+    /// [`EnvOverrides`](crate::EnvOverrides) for the variable format.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use superconfig::SuperConfig;
+    ///
+    /// // Environment: SUPERCONFIG_OVERRIDE__database__port=5433
+    /// let config = SuperConfig::new()
+    ///     .with_env_overrides()
+    ///     .with_file("config.toml")       // database.port = 5432
+    ///     .with_env("APP_");              // Overrides still win
+    ///
+    /// assert_eq!(config.env_overrides(), vec!["database.port"]);
+    /// ```
+    pub fn with_env_overrides(self) -> Self {
+        self.with_overrides(crate::EnvOverrides::new())
+    }
+
+    /// Enable emergency overrides from an explicit [`EnvOverrides`](crate::EnvOverrides) provider
+    ///
+    /// Behaves like [`with_env_overrides`](Self::with_env_overrides), for overrides collected
+    /// elsewhere than the process environment. Replaces previously enabled overrides.
+    pub fn with_overrides(mut self, overrides: crate::EnvOverrides) -> Self {
+        let step = self.next_step();
+
+        self.debug_step(
+            verbosity::INFO,
+            "override",
+            step,
+            "Enabling emergency overrides (SUPERCONFIG_OVERRIDE__*)",
+        );
+
+        if overrides.is_empty() {
+            self.debug_step_result(
+                verbosity::DEBUG,
+                "override",
+                step,
+                "No emergency overrides set",
+                true,
+            );
+        }
+
+        for (variable, key) in overrides.variables() {
+            let message = format!("Emergency override active: {key} set by {variable}");
+            self.debug_step_result(verbosity::INFO, "override", step, &message, true);
+            self.warnings.push(message);
+        }
+
+        self.env_overrides = Some(overrides);
+        self.apply_env_overrides().apply_array_merging()
+    }
+
+    /// Dotted paths of the keys set by emergency overrides
+    ///
+    /// Empty unless [`with_env_overrides`](Self::with_env_overrides) was called and at least
+    /// one `SUPERCONFIG_OVERRIDE__` variable is set.
+    pub fn env_overrides(&self) -> Vec<String> {
+        self.env_overrides
+            .as_ref()
+            .map(crate::EnvOverrides::keys)
+            .unwrap_or_default()
+    }
+
+    /// Merge the array of tables at `path` by the `key` field of its entries
+    ///
+    /// By default a later source replaces an array, and `_add` appends to it. For arrays
+    /// registered here, entries with the same `key` are merged instead: the later source
+    /// updates the fields it sets and keeps the others, and entries with new keys are
+    /// appended. `<field>_add` follows the same rules, and `<field>_remove` takes key values.
+    /// Register keyed arrays before adding the sources that set them.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use serde_json::{Value, json};
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_array_key("servers", "name")
+    ///     .with_defaults_string(r#"{"servers": [
+    ///         {"name": "a", "port": 80, "tls": false},
+    ///         {"name": "b", "port": 81}
+    ///     ]}"#)
+    ///     .with_defaults_string(r#"{
+    ///         "servers": [{"name": "a", "tls": true}, {"name": "c", "port": 83}],
+    ///         "servers_remove": ["b"]
+    ///     }"#);
+    ///
+    /// let servers: Value = config.extract_inner("servers")?;
+    /// assert_eq!(servers, json!([
+    ///     {"name": "a", "port": 80, "tls": true},
+    ///     {"name": "c", "port": 83}
+    /// ]));
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn with_array_key(mut self, path: &str, key: &str) -> Self {
+        self.array_keys.insert(path.to_string(), key.to_string());
+        self
+    }
+
+    /// Set the policy deciding which keys hold secrets
+    ///
+    /// Values of secret keys are masked in [`as_json`](Self::as_json),
+    /// [`as_yaml`](Self::as_yaml), [`as_toml`](Self::as_toml),
+    /// [`debug_config`](Self::debug_config), verbosity output, and extraction errors. The
+    /// default policy masks keys whose name contains `password`, `secret`, `token`, or
+    /// `key`; pass [`RedactionPolicy::none`](crate::secret::RedactionPolicy::none) to export
+    /// secrets verbatim. Set it before adding sources so their verbosity output is masked too.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use superconfig::schema::ConfigSchema;
+    /// use superconfig::secret::RedactionPolicy;
+    /// use serde_json::json;
+    ///
+    /// let schema = ConfigSchema::from_value(&json!({"database": {"dsn": ""}}))
+    ///     .secret("database.dsn");
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_redaction(RedactionPolicy::default().with_schema(&schema))
+    ///     .with_defaults_string(r#"{"database": {"dsn": "postgres://admin:pw@db"}}"#);
+    ///
+    /// assert!(!config.as_json()?.contains("postgres://"));
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn with_redaction(mut self, policy: crate::secret::RedactionPolicy) -> Self {
+        self.redaction = policy;
+        self
+    }
+
+    /// The policy deciding which keys hold secrets
+    pub fn redaction(&self) -> &crate::secret::RedactionPolicy {
+        &self.redaction
+    }
+
     /// Add environment variables with a prefix and empty value filtering
     ///
     /// Combines the Nested provider for JSON parsing and automatic nesting
@@ -498,15 +797,11 @@ impl SuperConfig {
             // Show individual env vars at trace level
             if self.verbosity >= verbosity::TRACE {
                 for (key, value) in &env_vars {
-                    // Mask sensitive values (anything with 'password', 'secret', 'token', 'key' in name)
-                    let display_value = if key.to_lowercase().contains("password")
-                        || key.to_lowercase().contains("secret")
-                        || key.to_lowercase().contains("token")
-                        || key.to_lowercase().contains("key")
-                    {
-                        "***MASKED***".to_string()
+                    // Mask sensitive values (names matching the redaction policy)
+                    let display_value = if self.redaction.is_secret(key) {
+                        crate::secret::REDACTED
                     } else {
-                        value.clone()
+                        value.as_str()
                     };
                     self.debug(verbosity::TRACE, "env", &format!("  {key}={display_value}"));
                 }
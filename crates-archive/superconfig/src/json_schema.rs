@@ -0,0 +1,324 @@
+//! JSON Schema documents for editor completion of configuration files
+//!
+//! [`SuperConfig::as_json_schema`] infers a JSON Schema (draft 2020-12) from the merged
+//! configuration: the type of every key, nested tables as objects with their properties,
+//! and the items of arrays. Published next to the configuration files, for example through
+//! a `"$schema"` key or the editor's schema mapping, it gives editors completion and type
+//! checking of the keys the application knows.
+//!
+//! Only the shape of the configuration is inferred; values never appear in the schema, so
+//! secrets don't leak into it. Keys are never marked required, since any source may
+//! leave them to another one.
+//!
+//! With the `schemars` feature, [`SuperConfig::as_json_schema_for`] starts from the schema
+//! schemars derives for the configuration struct, with its descriptions, enums and
+//! required fields, and adds the inferred keys the struct doesn't describe.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! use serde_json::json;
+//!
+//! let config = SuperConfig::new()
+//!     .with_defaults_string(r#"{"port": 8080, "hosts": ["a", "b"], "tls": {"enabled": true}}"#);
+//!
+//! let schema = config.as_json_schema()?;
+//! assert_eq!(schema["properties"]["port"], json!({"type": "integer"}));
+//! assert_eq!(schema["properties"]["hosts"]["items"], json!({"type": "string"}));
+//! assert_eq!(schema["properties"]["tls"]["properties"]["enabled"], json!({"type": "boolean"}));
+//! # Ok::<(), figment::Error>(())
+//! ```
+//!
+//! [`SuperConfig::as_json_schema`]: crate::SuperConfig::as_json_schema
+//! [`SuperConfig::as_json_schema_for`]: crate::SuperConfig::as_json_schema_for
+
+use figment::Error;
+use serde_json::{Map, Value, json};
+
+/// Dialect of the generated schemas
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Infer the JSON Schema of a configuration value, without the `$schema` keyword
+///
+/// Tables become objects listing their keys as properties. The items of an array are
+/// described by one schema covering every element: tables are combined key by key,
+/// integers and floats become numbers, and other mixed elements list their types.
+pub fn infer(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({}),
+        Value::Bool(_) => json!({"type": "boolean"}),
+        Value::Number(number) if number.is_f64() => json!({"type": "number"}),
+        Value::Number(_) => json!({"type": "integer"}),
+        Value::String(_) => json!({"type": "string"}),
+        Value::Array(items) => {
+            let mut schema = json!({"type": "array"});
+            if let Some(items) = items.iter().map(infer).reduce(|a, b| unify(&a, &b)) {
+                schema["items"] = items;
+            }
+            schema
+        }
+        Value::Object(map) => {
+            let properties: Map<String, Value> = map
+                .iter()
+                .map(|(key, value)| (key.clone(), infer(value)))
+                .collect();
+            json!({"type": "object", "properties": properties})
+        }
+    }
+}
+
+/// One schema accepting the values of both `a` and `b`
+fn unify(a: &Value, b: &Value) -> Value {
+    if a == b || b.as_object().is_some_and(Map::is_empty) {
+        return a.clone();
+    }
+    if a.as_object().is_some_and(Map::is_empty) {
+        return b.clone();
+    }
+
+    match (a["type"].as_str(), b["type"].as_str()) {
+        (Some("object"), Some("object")) => {
+            let mut properties = a["properties"].as_object().cloned().unwrap_or_default();
+            for (key, schema) in b["properties"].as_object().into_iter().flatten() {
+                let unified = match properties.get(key) {
+                    Some(existing) => unify(existing, schema),
+                    None => schema.clone(),
+                };
+                properties.insert(key.clone(), unified);
+            }
+            json!({"type": "object", "properties": properties})
+        }
+        (Some("array"), Some("array")) => match (a.get("items"), b.get("items")) {
+            (Some(a_items), Some(b_items)) => {
+                json!({"type": "array", "items": unify(a_items, b_items)})
+            }
+            (Some(items), None) | (None, Some(items)) => {
+                json!({"type": "array", "items": items})
+            }
+            (None, None) => json!({"type": "array"}),
+        },
+        (Some("integer" | "number"), Some("integer" | "number")) => json!({"type": "number"}),
+        _ => {
+            let mut types: Vec<&str> = Vec::new();
+            for schema in [a, b] {
+                match &schema["type"] {
+                    Value::String(name) => types.push(name),
+                    Value::Array(names) => types.extend(names.iter().filter_map(Value::as_str)),
+                    _ => {}
+                }
+            }
+            if types.contains(&"number") {
+                types.retain(|name| *name != "integer");
+            }
+            types.sort_unstable();
+            types.dedup();
+            json!({"type": types})
+        }
+    }
+}
+
+/// Add the properties of `inferred` that `schema` doesn't describe, recursively
+///
+/// Keywords `schema` sets are kept as they are. Objects that forbid additional
+/// properties, or don't list properties, get none added.
+#[cfg(feature = "schemars")]
+fn add_inferred(schema: &mut Value, inferred: &Value) {
+    let Some(schema) = schema.as_object_mut() else {
+        return;
+    };
+
+    if let Some(inferred_items) = inferred.get("items")
+        && let Some(items) = schema.get_mut("items")
+    {
+        add_inferred(items, inferred_items);
+    }
+
+    let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+    let (Some(properties), Some(inferred_properties)) = (
+        schema.get_mut("properties").and_then(Value::as_object_mut),
+        inferred.get("properties").and_then(Value::as_object),
+    ) else {
+        return;
+    };
+    for (key, inferred_property) in inferred_properties {
+        match properties.get_mut(key) {
+            Some(property) => add_inferred(property, inferred_property),
+            None if !closed => {
+                properties.insert(key.clone(), inferred_property.clone());
+            }
+            None => {}
+        }
+    }
+}
+
+impl crate::SuperConfig {
+    /// Infer a JSON Schema from the merged configuration
+    ///
+    /// Returns a draft 2020-12 schema document describing the type of every key, for
+    /// editors to complete and check configuration files. See [`json_schema`](crate::json_schema).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration fails to load.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"database": {"url": "postgres://localhost"}}"#);
+    ///
+    /// let schema = config.as_json_schema()?;
+    /// assert_eq!(schema["properties"]["database"]["type"], "object");
+    ///
+    /// // Publish it for editors
+    /// let published = serde_json::to_string_pretty(&schema).unwrap();
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn as_json_schema(&self) -> Result<Value, Error> {
+        let value = self.figment.extract::<Value>()?;
+        let mut schema = json!({"$schema": SCHEMA_DIALECT});
+        if let (Some(document), Value::Object(inferred)) = (schema.as_object_mut(), infer(&value)) {
+            document.extend(inferred);
+        }
+        Ok(schema)
+    }
+
+    /// JSON Schema of `T`, derived by schemars, with the inferred keys it doesn't describe
+    ///
+    /// Nested structs are inlined so their properties can be completed. Keywords from
+    /// `T`'s schema take precedence; keys of the merged configuration that `T` doesn't
+    /// have are added from [`as_json_schema`](Self::as_json_schema), unless `T` denies
+    /// unknown fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration fails to load.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// /// Server settings
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Server {
+    ///     /// Port to listen on
+    ///     port: u16,
+    /// }
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults_string(r#"{"port": 8080, "plugins": {"auth": true}}"#);
+    ///
+    /// let schema = config.as_json_schema_for::<Server>()?;
+    /// assert_eq!(schema["properties"]["port"]["description"], "Port to listen on");
+    /// assert_eq!(schema["required"][0], "port");
+    /// assert_eq!(schema["properties"]["plugins"]["properties"]["auth"]["type"], "boolean");
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub fn as_json_schema_for<T: schemars::JsonSchema>(&self) -> Result<Value, Error> {
+        let inferred = self.as_json_schema()?;
+        let mut schema = schemars::generate::SchemaSettings::draft2020_12()
+            .with(|settings| settings.inline_subschemas = true)
+            .into_generator()
+            .into_root_schema_for::<T>()
+            .to_value();
+        add_inferred(&mut schema, &inferred);
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_nested_tables_and_arrays() {
+        let schema = infer(&json!({
+            "name": "app",
+            "ratio": 0.5,
+            "unset": null,
+            "empty": [],
+            "weights": [1, 2.5],
+            "mixed": ["a", 1, true],
+            "servers": [
+                {"host": "a", "port": 80},
+                {"host": "b", "tls": {"enabled": true}},
+            ],
+        }));
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "ratio": {"type": "number"},
+                    "unset": {},
+                    "empty": {"type": "array"},
+                    "weights": {"type": "array", "items": {"type": "number"}},
+                    "mixed": {"type": "array", "items": {"type": ["boolean", "integer", "string"]}},
+                    "servers": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "host": {"type": "string"},
+                                "port": {"type": "integer"},
+                                "tls": {
+                                    "type": "object",
+                                    "properties": {"enabled": {"type": "boolean"}},
+                                },
+                            },
+                        },
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_schema_has_no_values() {
+        let config = crate::SuperConfig::new()
+            .with_defaults_string(r#"{"api": {"token": "s3cr3t"}, "port": 8080}"#);
+        let schema = config.as_json_schema().unwrap();
+
+        assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+        assert_eq!(schema["type"], "object");
+        assert!(!schema.to_string().contains("s3cr3t"));
+        assert!(!schema.to_string().contains("8080"));
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_inferred_keys_added_to_open_objects() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "port": {"type": "integer", "minimum": 1},
+                "tls": {
+                    "type": "object",
+                    "properties": {"cert": {"type": "string"}},
+                    "additionalProperties": false,
+                },
+            },
+        });
+        let inferred = infer(&json!({
+            "port": 80,
+            "extra": "x",
+            "tls": {"cert": "a.pem", "key": "a.key"},
+        }));
+        add_inferred(&mut schema, &inferred);
+
+        assert_eq!(schema["properties"]["port"]["minimum"], 1);
+        assert_eq!(schema["properties"]["extra"], json!({"type": "string"}));
+        assert!(
+            schema["properties"]["tls"]["properties"]
+                .get("key")
+                .is_none()
+        );
+    }
+}
@@ -10,7 +10,7 @@
 //!
 //! - **🔄 Resilient Loading**: Continues loading even when some configs fail, collecting warnings instead of crashing
 //! - **🌳 Hierarchical Discovery**: Git-style config inheritance across system → user → project → local levels  
-//! - **🔀 Intelligent Array Merging**: Advanced composition with `_add`/`_remove` patterns across all sources
+//! - **🔀 Intelligent Array Merging**: Advanced composition with `_add`/`_remove` patterns across all sources, and arrays of tables merged by an identity field
 //! - **🎯 Pattern-Based Discovery**: Powerful glob patterns for flexible multi-source configuration loading
 //! - **🧠 Smart Format Detection**: Content-based parsing with intelligent caching and fallback strategies
 //! - **⚡ Enhanced Environment Variables**: JSON parsing, automatic nesting, and smart type detection
@@ -27,12 +27,25 @@
 //! - **Nested Environment Variables** - JSON parsing, automatic nesting, and smart type detection  
 //! - **Empty Value Filtering** - Automatic filtering while preserving meaningful falsy values
 //! - **Wildcard Pattern Discovery** - Unified glob-based configuration discovery with advanced sorting
+//! - **Wildcard Watching** - `Wildcard::watch()` re-discovers and re-merges files as their directories change (`watch` feature)
 //!
 //! ### 🛠️ Built-in Configuration Methods
 //! - **Fluent Builder API** - `.with_file()`, `.with_env()`, `.with_hierarchical_config()`, `.with_defaults()`
 //! - **Array Merging** - Intelligent composition with `_add`/`_remove` patterns across all sources
 //! - **Access & Export** - `.as_json()`, `.as_yaml()`, `.get_string()`, `.has_key()`, `.debug_config()`
+//! - **Typed Getters** - `.get_bool()`, `.get_u64()`, `.get_duration()` (`"30s"`, `"5m"`) and `.get_bytes()` (`"10MB"`) with errors naming the key and its source (see [`units`])
 //! - **Warning System** - Resilient loading with comprehensive error collection and reporting
+//! - **Secret Redaction** - [`secret::SecretString`] fields and a [`secret::RedactionPolicy`] masking secrets in exports and logs
+//! - **Lossy Extraction** - `.extract_lossy()` fills what it can and reports missing and invalid keys in a [`lossy::ExtractionReport`]
+//! - **Struct Defaults** - `.extract_with_defaults()` merges a default instance beneath the sources, and [`PartialConfig`] extracts override files setting only some fields (see [`partial`])
+//! - **Source Discovery** - `.with_discovered_sources()` loads extra files from glob patterns listed in the configuration or in `SUPERCONFIG_SOURCES` (see [`discovery`])
+//! - **Path Values** - `.with_path_key()` expands `~`, converts separators, and resolves relative paths against the file that set them (see [`paths`])
+//! - **Schema Evolution** - `.schema()` and [`schema::ConfigSchema::compare`] to catch breaking config changes between releases
+//! - **JSON Schema** - `.as_json_schema()` infers a JSON Schema from the merged configuration for editor completion, merged with a schemars-derived one by `.as_json_schema_for()` (see [`json_schema`])
+//! - **Round-Trip Export** - `.update_toml()`, `.update_yaml()` and `.write_to_file()` write the configuration back to existing files, keeping their comments and key order (see [`export`])
+//! - **Linting** - `.lint()` checks the merged configuration for unknown, deprecated, duplicate and empty keys, with the file and line of each finding, to gate CI (see [`lint`])
+//! - **Key Migrations** - `.with_migrations()` renames old keys and converts old value formats in every source, reporting each deprecated key with the file that set it (see [`migration`])
+//! - **Test Helpers** - [`assert_config_eq!`] compares configurations and lists the keys that differ on failure
 //!
 //! ### 💯 100% Figment Compatibility  
 //! - All Figment methods and functionalities work out of the box with SuperConfig
@@ -268,16 +281,41 @@ use std::ops::Deref;
 pub use figment;
 
 pub mod access;
+pub mod discovery;
+pub mod export;
 mod fluent;
+pub mod json_schema;
+pub mod lint;
+pub mod lossy;
 pub mod merge;
+pub mod migration;
+pub mod partial;
+pub mod paths;
+pub mod policy;
+pub mod provenance;
 pub mod providers;
+pub mod schema;
+pub mod secret;
+pub mod testing;
+pub mod units;
 pub mod verbosity;
 
 // Re-export enhanced providers for existing Figment users
 pub use providers::{
-    Empty, MergeOrder, Nested, SearchStrategy, Universal, Wildcard, WildcardBuilder,
+    DEFAULT_KEY_ENV, DiscoveryLimits, Dotenv, DuplicateKeyPolicy, Empty, Encrypted, EncryptionKey,
+    EnvOverrides, Ini, Json5, KeySource, MergeOrder, Nested, OVERRIDE_PREFIX, SearchStrategy,
+    SymlinkPolicy, Universal, Verification, Verified, VerifyPolicy, Wildcard, WildcardBuilder,
 };
 
+#[cfg(feature = "clap")]
+pub use providers::{ArgType, Clap};
+#[cfg(feature = "watch")]
+pub use providers::{WildcardEvent, WildcardWatcher};
+
+pub use partial::{Partial, PartialConfig};
+pub use policy::MergePolicy;
+pub use provenance::Origin;
+
 // Re-export verbosity types and constants for clients
 pub use verbosity::{DEBUG, INFO, SILENT, TRACE, VerbosityLevel};
 
@@ -422,6 +460,22 @@ pub struct SuperConfig {
     figment: Figment,
     warnings: Vec<String>,
     verbosity: u8,
+    // Emergency overrides re-applied after every merge, once enabled
+    env_overrides: Option<providers::EnvOverrides>,
+    // Keys masked in exports, debug output, and extraction errors
+    redaction: secret::RedactionPolicy,
+    // Identity field of each array of tables merged by key, by dotted path
+    array_keys: std::collections::BTreeMap<String, String>,
+    // How keys matching each pattern merge with earlier values
+    merge_policies: policy::MergePolicies,
+    // Keys holding filesystem paths, by dotted path, with whether they must exist
+    path_keys: std::collections::BTreeMap<String, bool>,
+    // Which source set the final value of each key
+    provenance: provenance::Provenance,
+    // Renamed keys and old value formats rewritten in every source merged
+    migrations: migration::MigrationMap,
+    // Keys the merged sources set under an old name or in an old format
+    deprecations: Vec<migration::Deprecation>,
     // Use internal mutability for debug state to avoid requiring &mut self
     debug_state: RefCell<DebugState>,
 }
@@ -439,6 +493,14 @@ impl SuperConfig {
             figment: Figment::new(),
             warnings: Vec::new(),
             verbosity: verbosity::SILENT,
+            env_overrides: None,
+            redaction: secret::RedactionPolicy::default(),
+            array_keys: std::collections::BTreeMap::new(),
+            merge_policies: policy::MergePolicies::default(),
+            path_keys: std::collections::BTreeMap::new(),
+            provenance: provenance::Provenance::default(),
+            migrations: migration::MigrationMap::default(),
+            deprecations: Vec::new(),
             debug_state: RefCell::new(DebugState {
                 debug_messages: Vec::new(),
                 step_counter: 0,
@@ -448,10 +510,20 @@ impl SuperConfig {
 
     /// Create SuperConfig from an existing Figment
     pub fn from_figment(figment: Figment) -> Self {
+        let mut provenance = provenance::Provenance::default();
+        provenance.record(&figment, None);
         Self {
             figment,
             warnings: Vec::new(),
             verbosity: verbosity::SILENT,
+            env_overrides: None,
+            redaction: secret::RedactionPolicy::default(),
+            array_keys: std::collections::BTreeMap::new(),
+            merge_policies: policy::MergePolicies::default(),
+            path_keys: std::collections::BTreeMap::new(),
+            provenance,
+            migrations: migration::MigrationMap::default(),
+            deprecations: Vec::new(),
             debug_state: RefCell::new(DebugState {
                 debug_messages: Vec::new(),
                 step_counter: 0,
@@ -487,7 +559,10 @@ impl SuperConfig {
     pub fn extract<'de, T: serde::Deserialize<'de>>(&self) -> Result<T, figment::Error> {
         self.debug(verbosity::INFO, "extract", "Extracting final configuration");
 
-        let result = self.figment.extract::<T>();
+        let result = self
+            .check_existing_paths()
+            .and_then(|()| self.figment.extract::<T>())
+            .map_err(|error| self.redaction.redact_error(error));
 
         match &result {
             Ok(_) => {
@@ -500,7 +575,8 @@ impl SuperConfig {
 
                 // Show final merged config at trace level
                 if self.verbosity >= verbosity::TRACE {
-                    if let Ok(json_value) = self.figment.extract::<serde_json::Value>() {
+                    if let Ok(mut json_value) = self.figment.extract::<serde_json::Value>() {
+                        self.redaction.redact(&mut json_value);
                         if let Ok(pretty_json) = serde_json::to_string_pretty(&json_value) {
                             self.debug(
                                 verbosity::TRACE,
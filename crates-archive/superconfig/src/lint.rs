@@ -0,0 +1,746 @@
+//! Configuration linting for CI
+//!
+//! [`SuperConfig::lint`](crate::SuperConfig::lint) runs a set of [`Rule`]s over the
+//! merged configuration and returns a [`LintReport`] of [`Diagnostic`]s. Each diagnostic
+//! names the key it's about and, when known, the [`Origin`] of its value: the file and
+//! line, or the environment variable, that set it.
+//!
+//! Built-in rules:
+//!
+//! - [`UnknownKeys`] - Keys the configuration struct doesn't have, with a suggestion for
+//!   likely typos (error)
+//! - [`DeprecatedKeys`] - Old key names that should be replaced (warning)
+//! - [`DuplicateKeys`] - Keys set by more than one configuration file (warning)
+//! - [`EmptyValues`] - Blank strings and empty tables, usually a variable set to nothing
+//!   or a table left unfinished (warning)
+//!
+//! Custom checks implement [`Rule`].
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! use superconfig::lint::{DeprecatedKeys, EmptyValues, UnknownKeys};
+//! use superconfig::schema::ConfigSchema;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize, Default)]
+//! struct Server { host: String, port: u16 }
+//!
+//! let config = SuperConfig::new()
+//!     .with_defaults_string(r#"{"host": "", "prot": 80, "listen_port": 8080}"#);
+//!
+//! // `listen_port` was renamed to `port`
+//! let schema = ConfigSchema::from_defaults(&Server::default())?.alias("port", "listen_port");
+//! let report = config.lint(&[
+//!     &UnknownKeys::new(schema.clone()),
+//!     &DeprecatedKeys::from_schema(&schema),
+//!     &EmptyValues,
+//! ])?;
+//!
+//! assert!(report.has_errors());
+//! let unknown = report.errors().next().unwrap();
+//! assert_eq!(unknown.key, "prot");
+//! assert!(unknown.message.contains("did you mean `port`"));
+//! assert_eq!(report.warnings().count(), 2);
+//!
+//! // In CI
+//! if !report.is_clean() {
+//!     eprintln!("{report}");
+//! }
+//! # Ok::<(), figment::Error>(())
+//! ```
+
+use figment::Error;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+
+use crate::Origin;
+use crate::schema::{ConfigSchema, FieldType};
+use crate::verbosity::{self, DebugCollector};
+
+/// How serious a [`Diagnostic`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth fixing, but the configuration works
+    Warning,
+    /// The configuration is likely wrong
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// A problem a [`Rule`] found with one key
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// Name of the rule that reported it
+    pub rule: String,
+    /// How serious it is
+    pub severity: Severity,
+    /// Dotted path of the key
+    pub key: String,
+    /// What is wrong, without the key's value
+    pub message: String,
+    /// Where the key's value was set, if known
+    pub origin: Option<Origin>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: `{}` {}",
+            self.severity, self.rule, self.key, self.message
+        )?;
+        if let Some(origin) = &self.origin {
+            write!(f, " (at {origin})")?;
+        }
+        Ok(())
+    }
+}
+
+/// A check of the merged configuration
+///
+/// # Examples
+/// ```rust
+/// use superconfig::SuperConfig;
+/// use superconfig::lint::{Diagnostic, LintContext, Rule, Severity};
+///
+/// /// Ports below 1024 need privileges
+/// struct PrivilegedPort;
+///
+/// impl Rule for PrivilegedPort {
+///     fn name(&self) -> &str {
+///         "privileged-port"
+///     }
+///
+///     fn check(&self, context: &LintContext<'_>) -> Vec<Diagnostic> {
+///         let port = context.tree().pointer("/server/port").and_then(|port| port.as_u64());
+///         match port {
+///             Some(port) if port < 1024 => vec![context.diagnostic(
+///                 self,
+///                 Severity::Warning,
+///                 "server.port",
+///                 format!("is {port}, which needs root privileges"),
+///             )],
+///             _ => Vec::new(),
+///         }
+///     }
+/// }
+///
+/// let config = SuperConfig::new().with_defaults_string(r#"{"server": {"port": 80}}"#);
+/// let report = config.lint(&[&PrivilegedPort])?;
+/// assert_eq!(report.diagnostics[0].rule, "privileged-port");
+/// # Ok::<(), figment::Error>(())
+/// ```
+pub trait Rule {
+    /// Short name of the rule in diagnostics, such as `unknown-key`
+    fn name(&self) -> &str;
+
+    /// Report the problems found in the configuration
+    fn check(&self, context: &LintContext<'_>) -> Vec<Diagnostic>;
+}
+
+/// The configuration being linted
+pub struct LintContext<'a> {
+    config: &'a crate::SuperConfig,
+    tree: Value,
+}
+
+impl<'a> LintContext<'a> {
+    /// The configuration being linted
+    pub fn config(&self) -> &'a crate::SuperConfig {
+        self.config
+    }
+
+    /// The merged configuration tree
+    pub fn tree(&self) -> &Value {
+        &self.tree
+    }
+
+    /// Dotted paths and values of every key that isn't a non-empty table, in key order
+    ///
+    /// Arrays are listed as one value, like in [`provenance`](crate::provenance).
+    pub fn leaves(&self) -> Vec<(String, &Value)> {
+        let mut leaves = Vec::new();
+        if let Value::Object(map) = &self.tree {
+            for (key, value) in map {
+                collect_leaves(key.clone(), value, &mut leaves);
+            }
+        }
+        leaves
+    }
+
+    /// Where the value of `key` was set, with its line for files
+    pub fn origin(&self, key: &str) -> Option<Origin> {
+        self.config
+            .origin(key)
+            .or_else(|| self.config.provenance.get(key).cloned())
+    }
+
+    /// Origins of earlier values of `key` that later sources replaced, oldest first
+    pub fn shadowed(&self, key: &str) -> Vec<Origin> {
+        self.config
+            .provenance
+            .shadowed(key)
+            .iter()
+            .map(|origin| {
+                let mut origin = origin.clone();
+                origin.line = origin
+                    .file()
+                    .and_then(|file| crate::provenance::find_line(file, key));
+                origin
+            })
+            .collect()
+    }
+
+    /// A diagnostic of `rule` about `key`, with the origin of the key's value
+    pub fn diagnostic(
+        &self,
+        rule: &(impl Rule + ?Sized),
+        severity: Severity,
+        key: &str,
+        message: impl Into<String>,
+    ) -> Diagnostic {
+        Diagnostic {
+            rule: rule.name().to_string(),
+            severity,
+            key: key.to_string(),
+            message: message.into(),
+            origin: self.origin(key),
+        }
+    }
+}
+
+fn collect_leaves<'v>(path: String, value: &'v Value, leaves: &mut Vec<(String, &'v Value)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                collect_leaves(format!("{path}.{key}"), value, leaves);
+            }
+        }
+        _ => leaves.push((path, value)),
+    }
+}
+
+/// Diagnostics of a [`SuperConfig::lint`](crate::SuperConfig::lint) run, sorted by key
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintReport {
+    /// Every problem found
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    /// Returns `true` when no rule reported anything
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Returns `true` when a rule reported an error
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// The diagnostics of error severity
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    /// The diagnostics of warning severity
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+    }
+}
+
+impl fmt::Display for LintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "configuration passed every lint rule");
+        }
+        write!(
+            f,
+            "configuration has {} errors and {} warnings:",
+            self.errors().count(),
+            self.warnings().count()
+        )?;
+        for diagnostic in &self.diagnostics {
+            write!(f, "\n  {diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reports keys the configuration struct doesn't have
+///
+/// The known keys come from a [`ConfigSchema`], usually derived from the struct's
+/// defaults with [`of`](Self::of). Tables whose schema has no keys of its own, such as
+/// maps or `None` defaults, accept any key below them. Aliases listed in the schema are
+/// known keys, left to [`DeprecatedKeys`]. Only the outermost unknown key of a table is
+/// reported.
+#[derive(Debug, Clone)]
+pub struct UnknownKeys {
+    schema: ConfigSchema,
+}
+
+impl UnknownKeys {
+    /// Check against the keys of `schema`
+    pub fn new(schema: ConfigSchema) -> Self {
+        Self { schema }
+    }
+
+    /// Check against the keys of `T`, from its `Default` value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the default value can't be serialized.
+    pub fn of<T: Serialize + Default>() -> Result<Self, Error> {
+        Ok(Self::new(ConfigSchema::from_defaults(&T::default())?))
+    }
+
+    /// Whether the schema describes `path` or accepts any key in its place
+    fn is_known(&self, path: &str) -> bool {
+        if self.schema.get(path).is_some() || self.is_alias(path) {
+            return true;
+        }
+        // The closest table of the schema holding `path` decides
+        let mut parent = path;
+        while let Some(end) = parent.rfind('.') {
+            parent = &parent[..end];
+            if let Some(field) = self.schema.get(parent) {
+                return match field.field_type {
+                    FieldType::Object => self.children(parent).next().is_none(),
+                    _ => true,
+                };
+            }
+        }
+        false
+    }
+
+    fn is_alias(&self, path: &str) -> bool {
+        self.schema.fields().any(|(_, field)| {
+            field
+                .aliases
+                .iter()
+                .any(|alias| alias == path || path.starts_with(&format!("{alias}.")))
+        })
+    }
+
+    /// Keys of the schema directly below `parent`, or at the top for `""`
+    fn children<'s>(&'s self, parent: &'s str) -> impl Iterator<Item = &'s str> {
+        self.schema.fields().filter_map(move |(path, _)| {
+            let name = if parent.is_empty() {
+                path
+            } else {
+                path.strip_prefix(parent)?.strip_prefix('.')?
+            };
+            (!name.contains('.')).then_some(name)
+        })
+    }
+
+    fn check_table(
+        &self,
+        context: &LintContext<'_>,
+        parent: &str,
+        map: &serde_json::Map<String, Value>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for (key, value) in map {
+            let path = if parent.is_empty() {
+                key.clone()
+            } else {
+                format!("{parent}.{key}")
+            };
+            if !self.is_known(&path) {
+                let mut message = "is not a known configuration key".to_string();
+                if let Some(suggestion) = closest(key, self.children(parent)) {
+                    message.push_str(&format!("; did you mean `{suggestion}`?"));
+                }
+                diagnostics.push(context.diagnostic(self, Severity::Error, &path, message));
+            } else if let Value::Object(map) = value {
+                self.check_table(context, &path, map, diagnostics);
+            }
+        }
+    }
+}
+
+impl Rule for UnknownKeys {
+    fn name(&self) -> &str {
+        "unknown-key"
+    }
+
+    fn check(&self, context: &LintContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if let Value::Object(map) = context.tree() {
+            self.check_table(context, "", map, &mut diagnostics);
+        }
+        diagnostics
+    }
+}
+
+/// The candidate closest to `name`, if it is close enough to be a typo
+fn closest<'c>(name: &str, candidates: impl Iterator<Item = &'c str>) -> Option<&'c str> {
+    let limit = (name.chars().count() / 3).clamp(1, 3);
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance, counting an adjacent transposition as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>(); a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Reports keys set under an old name
+#[derive(Debug, Clone, Default)]
+pub struct DeprecatedKeys {
+    /// Old dotted paths with the keys replacing them
+    keys: Vec<(String, String)>,
+}
+
+impl DeprecatedKeys {
+    /// A rule without deprecated keys; add them with [`key`](Self::key)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The aliases of `schema`, deprecated in favour of the key listing them
+    pub fn from_schema(schema: &ConfigSchema) -> Self {
+        let keys = schema
+            .fields()
+            .flat_map(|(path, field)| {
+                field
+                    .aliases
+                    .iter()
+                    .map(move |alias| (alias.clone(), path.to_string()))
+            })
+            .collect();
+        Self { keys }
+    }
+
+    /// Report `old_path`, replaced by `new_path`
+    pub fn key(mut self, old_path: &str, new_path: &str) -> Self {
+        self.keys.push((old_path.to_string(), new_path.to_string()));
+        self
+    }
+}
+
+impl Rule for DeprecatedKeys {
+    fn name(&self) -> &str {
+        "deprecated-key"
+    }
+
+    fn check(&self, context: &LintContext<'_>) -> Vec<Diagnostic> {
+        self.keys
+            .iter()
+            .filter(|(old_path, _)| {
+                context
+                    .tree()
+                    .pointer(&format!("/{}", old_path.replace('.', "/")))
+                    .is_some()
+            })
+            .map(|(old_path, new_path)| {
+                context.diagnostic(
+                    self,
+                    Severity::Warning,
+                    old_path,
+                    format!("is deprecated; use `{new_path}` instead"),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Reports keys set by more than one configuration file
+///
+/// Layering defaults, files and environment variables is expected, so only keys two
+/// different files set are reported: usually a setting copied into an override file and
+/// forgotten there. Files merged by one [`Wildcard`](crate::Wildcard) provider are
+/// checked by its [`DuplicateKeyPolicy`](crate::DuplicateKeyPolicy) instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DuplicateKeys;
+
+impl Rule for DuplicateKeys {
+    fn name(&self) -> &str {
+        "duplicate-key"
+    }
+
+    fn check(&self, context: &LintContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (key, origin) in context.config().provenance.iter() {
+            let Some(winner) = origin.file() else {
+                continue;
+            };
+            let shadowed = context.shadowed(key);
+            let mut seen: BTreeSet<&Path> = BTreeSet::from([winner]);
+            let files: Vec<String> = shadowed
+                .iter()
+                .filter(|origin| origin.file().is_some_and(|file| seen.insert(file)))
+                .map(|origin| match (origin.file(), origin.line) {
+                    (Some(file), Some(line)) => format!("{}:{line}", file.display()),
+                    (Some(file), None) => file.display().to_string(),
+                    _ => unreachable!("filtered on files"),
+                })
+                .collect();
+            if !files.is_empty() {
+                diagnostics.push(context.diagnostic(
+                    self,
+                    Severity::Warning,
+                    key,
+                    format!(
+                        "is also set by {}, which this file overrides",
+                        files.join(", ")
+                    ),
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// Reports blank strings and empty tables
+///
+/// An empty value is usually an environment variable exported without a value or a
+/// table whose keys were never filled in. The [`Empty`](crate::providers::Empty)
+/// provider filters such values out instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmptyValues;
+
+impl Rule for EmptyValues {
+    fn name(&self) -> &str {
+        "empty-value"
+    }
+
+    fn check(&self, context: &LintContext<'_>) -> Vec<Diagnostic> {
+        context
+            .leaves()
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let message = match value {
+                    Value::String(text) if text.trim().is_empty() => "is a blank string",
+                    Value::Object(_) => "is an empty table",
+                    _ => return None,
+                };
+                Some(context.diagnostic(self, Severity::Warning, &key, message))
+            })
+            .collect()
+    }
+}
+
+impl crate::SuperConfig {
+    /// Check the merged configuration with `rules`
+    ///
+    /// Diagnostics are sorted by key, then by rule. See [`lint`](crate::lint) for the
+    /// built-in rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration fails to load.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use superconfig::lint::{DuplicateKeys, EmptyValues};
+    ///
+    /// let config = SuperConfig::new().with_defaults_string(r#"{"name": "app"}"#);
+    /// let report = config.lint(&[&DuplicateKeys, &EmptyValues])?;
+    /// assert!(report.is_clean());
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn lint(&self, rules: &[&dyn Rule]) -> Result<LintReport, Error> {
+        self.debug(
+            verbosity::INFO,
+            "lint",
+            &format!("Linting with {} rules", rules.len()),
+        );
+
+        let context = LintContext {
+            config: self,
+            tree: self.figment.extract()?,
+        };
+        let mut diagnostics: Vec<Diagnostic> =
+            rules.iter().flat_map(|rule| rule.check(&context)).collect();
+        diagnostics.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.rule.cmp(&b.rule)));
+
+        self.debug(
+            verbosity::DEBUG,
+            "lint",
+            &format!("Found {} problems", diagnostics.len()),
+        );
+        Ok(LintReport { diagnostics })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuperConfig;
+
+    #[derive(Serialize, Default)]
+    struct Config {
+        name: String,
+        database: Database,
+        labels: std::collections::BTreeMap<String, String>,
+        plugin: Option<Database>,
+    }
+
+    #[derive(Serialize, Default)]
+    struct Database {
+        url: String,
+        pool: u32,
+    }
+
+    fn lint(json: &str, rules: &[&dyn Rule]) -> LintReport {
+        SuperConfig::new()
+            .with_defaults_string(json)
+            .lint(rules)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let rule = UnknownKeys::of::<Config>().unwrap();
+        let report = lint(
+            r#"{
+                "name": "app",
+                "nmae": "typo",
+                "database": {"url": "x", "pol": 5, "extra": {"a": 1}},
+                "labels": {"team": "core"},
+                "plugin": {"anything": true},
+                "cache": {"ttl": 5}
+            }"#,
+            &[&rule],
+        );
+
+        let keys: Vec<(&str, &str)> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.key.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("cache", "is not a known configuration key"),
+                ("database.extra", "is not a known configuration key"),
+                (
+                    "database.pol",
+                    "is not a known configuration key; did you mean `pool`?"
+                ),
+                (
+                    "nmae",
+                    "is not a known configuration key; did you mean `name`?"
+                ),
+            ]
+        );
+        assert!(report.errors().all(|d| d.rule == "unknown-key"));
+    }
+
+    #[test]
+    fn test_deprecated_keys_from_schema() {
+        let schema = ConfigSchema::from_defaults(&Config::default())
+            .unwrap()
+            .alias("database.url", "database.dsn");
+        let report = lint(
+            r#"{"database": {"dsn": "x"}}"#,
+            &[
+                &UnknownKeys::new(schema.clone()),
+                &DeprecatedKeys::from_schema(&schema),
+            ],
+        );
+
+        assert_eq!(report.diagnostics.len(), 1);
+        let diagnostic = &report.diagnostics[0];
+        assert_eq!(diagnostic.key, "database.dsn");
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert!(diagnostic.to_string().starts_with(
+            "warning[deprecated-key]: `database.dsn` is deprecated; use `database.url` instead (at "
+        ));
+    }
+
+    #[test]
+    fn test_empty_values() {
+        let report = lint(
+            r#"{"name": " ", "tags": [], "debug": false, "database": {}, "port": 0}"#,
+            &[&EmptyValues],
+        );
+        let keys: Vec<&str> = report.diagnostics.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, ["database", "name"]);
+        assert!(!report.has_errors());
+    }
+
+    #[test]
+    fn test_duplicate_keys_across_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base = temp_dir.path().join("base.toml");
+        let local = temp_dir.path().join("local.toml");
+        std::fs::write(&base, "name = \"base\"\nport = 80\n").unwrap();
+        std::fs::write(&local, "port = 8080\n").unwrap();
+
+        let config = SuperConfig::new()
+            .with_defaults_string(r#"{"name": "default", "port": 1}"#)
+            .with_file(&base)
+            .with_file(&local);
+        let report = config.lint(&[&DuplicateKeys]).unwrap();
+
+        assert_eq!(report.diagnostics.len(), 1);
+        let diagnostic = &report.diagnostics[0];
+        assert_eq!(diagnostic.key, "port");
+        assert_eq!(
+            diagnostic.message,
+            format!(
+                "is also set by {}:2, which this file overrides",
+                base.display()
+            )
+        );
+        assert_eq!(
+            diagnostic.origin.as_ref().unwrap().file(),
+            Some(local.as_path())
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("port", "port"), 0);
+        assert_eq!(edit_distance("prot", "port"), 1);
+        assert_eq!(edit_distance("hots", "host"), 1);
+        assert_eq!(edit_distance("pool_size", "pool"), 5);
+        assert_eq!(
+            closest("databse", ["database", "name"].into_iter()),
+            Some("database")
+        );
+        assert_eq!(closest("cache", ["database", "name"].into_iter()), None);
+    }
+}
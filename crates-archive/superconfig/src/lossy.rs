@@ -0,0 +1,405 @@
+//! Best-effort extraction for tooling
+//!
+//! [`SuperConfig::extract_lossy`](crate::SuperConfig::extract_lossy) extracts a
+//! configuration struct even when some keys are missing or hold invalid values. Those
+//! keys take the value from the struct's `Default` implementation, fields with a declared
+//! `#[serde(default)]` keep their usual default, and every replaced key is listed in an
+//! [`ExtractionReport`]. Linters, documentation generators and migration scripts can then
+//! work with the rest of the configuration and report the problems together.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Default, Serialize, Deserialize)]
+//! struct Server { host: String, port: u16, workers: u32 }
+//!
+//! # fn main() -> Result<(), figment::Error> {
+//! let config = SuperConfig::new()
+//!     .with_defaults_string(r#"{"host": "example.com", "port": "http"}"#);
+//!
+//! let (server, report) = config.extract_lossy::<Server>()?;
+//! assert_eq!(server.host, "example.com");
+//! assert_eq!(server.port, 0);              // Invalid, replaced by the default
+//! assert_eq!(report.missing, ["workers"]);
+//! assert_eq!(report.invalid[0].path, "port");
+//! # Ok(())
+//! # }
+//! ```
+
+use figment::{Error, Figment, error::Kind, providers::Serialized};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::fmt;
+
+use crate::verbosity::{self, DebugCollector};
+
+/// A key whose value could not be extracted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidField {
+    /// Dotted path of the key, with array elements as `tags[1]`
+    pub path: String,
+    /// Why the value was rejected, with secret values redacted
+    pub message: String,
+}
+
+/// Keys [`SuperConfig::extract_lossy`](crate::SuperConfig::extract_lossy) replaced with
+/// their default value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractionReport {
+    /// Dotted paths of required keys missing from the configuration
+    pub missing: Vec<String>,
+    /// Keys present with a value of the wrong type or out of range
+    pub invalid: Vec<InvalidField>,
+}
+
+impl ExtractionReport {
+    /// Returns `true` when every key was extracted from the configuration
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.invalid.is_empty()
+    }
+}
+
+impl fmt::Display for ExtractionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_complete() {
+            return write!(f, "configuration extracted completely");
+        }
+        write!(
+            f,
+            "configuration extracted with {} missing and {} invalid keys:",
+            self.missing.len(),
+            self.invalid.len()
+        )?;
+        for path in &self.missing {
+            write!(f, "\n  - {path}: missing")?;
+        }
+        for field in &self.invalid {
+            write!(f, "\n  ! {}: {}", field.path, field.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl crate::SuperConfig {
+    /// Extract configuration, replacing missing and invalid keys with defaults
+    ///
+    /// Unlike [`extract`](Self::extract), a missing key or a value of the wrong type does
+    /// not fail the extraction: the key takes its value from `T::default()` (or its
+    /// `#[serde(default)]`), and the returned [`ExtractionReport`] lists it. See the
+    /// [`lossy`](crate::lossy) module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration can't be read at all, or if a key can't be
+    /// replaced because `T::default()` has no value for it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Default, Serialize, Deserialize)]
+    /// struct Config { name: String, retries: u8 }
+    ///
+    /// let config = SuperConfig::new().with_defaults_string(r#"{"retries": 300}"#);
+    /// let (extracted, report) = config.extract_lossy::<Config>()?;
+    ///
+    /// assert_eq!(extracted.retries, 0);
+    /// assert!(!report.is_complete());
+    /// println!("{report}");
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn extract_lossy<T>(&self) -> Result<(T, ExtractionReport), Error>
+    where
+        T: DeserializeOwned + Serialize + Default,
+    {
+        self.debug(
+            verbosity::INFO,
+            "extract_lossy",
+            "Extracting configuration, replacing missing and invalid keys",
+        );
+
+        let mut data = self
+            .figment
+            .extract::<Value>()
+            .map_err(|error| self.redaction.redact_error(error))?;
+        let defaults =
+            serde_json::to_value(T::default()).map_err(|error| Error::from(error.to_string()))?;
+        let mut report = ExtractionReport::default();
+        let mut last_replaced: Option<(Vec<String>, Option<Value>)> = None;
+
+        loop {
+            let error = match Figment::from(Serialized::defaults(&data)).extract::<T>() {
+                Ok(value) => {
+                    self.debug_result(
+                        verbosity::INFO,
+                        "extract_lossy",
+                        &report.to_string(),
+                        report.is_complete(),
+                    );
+                    return Ok((value, report));
+                }
+                Err(error) => self.redaction.redact_error(error),
+            };
+
+            // Serde stops at the first problem, so each attempt fixes one key
+            let Some((path, problem)) = failed_key(&error) else {
+                return Err(error);
+            };
+            // A key failing again with the value just put there can't be fixed
+            if last_replaced.as_ref().is_some_and(|(last, value)| {
+                *last == path && lookup(&data, &path) == value.as_ref()
+            }) {
+                return Err(error);
+            }
+            let replacement = lookup(&defaults, &path).cloned();
+            let key = display_path(&data, &path);
+            let replaced = match problem {
+                Problem::Missing => {
+                    let replaced =
+                        replacement.is_some() && replace(&mut data, &path, replacement.clone());
+                    if replaced {
+                        report.missing.push(key.clone());
+                    }
+                    replaced
+                }
+                Problem::Invalid(message) => {
+                    report.invalid.push(InvalidField {
+                        path: key.clone(),
+                        message,
+                    });
+                    replace(&mut data, &path, replacement.clone())
+                }
+            };
+
+            if !replaced {
+                return Err(error);
+            }
+            last_replaced = Some((path, replacement));
+            self.debug(
+                verbosity::DEBUG,
+                "extract_lossy",
+                &format!("Replaced '{key}' with its default"),
+            );
+        }
+    }
+}
+
+/// What is wrong with a key
+enum Problem {
+    Missing,
+    Invalid(String),
+}
+
+/// Returns the path of the key an extraction error is about
+fn failed_key(error: &Error) -> Option<(Vec<String>, Problem)> {
+    let mut path = error.path.clone();
+    let problem = match &error.kind {
+        Kind::MissingField(field) => {
+            path.push(field.to_string());
+            Problem::Missing
+        }
+        Kind::UnknownField(field, _) => {
+            path.push(field.clone());
+            Problem::Invalid(error.kind.to_string())
+        }
+        Kind::InvalidType(..)
+        | Kind::InvalidValue(..)
+        | Kind::InvalidLength(..)
+        | Kind::UnknownVariant(..) => Problem::Invalid(error.kind.to_string()),
+        _ => return None,
+    };
+    (!path.is_empty()).then_some((path, problem))
+}
+
+/// Formats `path` as a dotted key, with array elements as `[index]`
+fn display_path(data: &Value, path: &[String]) -> String {
+    let mut key = String::new();
+    for (depth, segment) in path.iter().enumerate() {
+        if matches!(lookup(data, &path[..depth]), Some(Value::Array(_))) {
+            key.push_str(&format!("[{segment}]"));
+        } else {
+            if depth > 0 {
+                key.push('.');
+            }
+            key.push_str(segment);
+        }
+    }
+    key
+}
+
+/// Returns the value at `path`, indexing arrays by position
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+        _ => None,
+    })
+}
+
+/// Sets the value at `path`, or removes it when `value` is `None`
+///
+/// Returns `false` if the path can't be changed.
+fn replace(data: &mut Value, path: &[String], value: Option<Value>) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut current = data;
+    for key in parents {
+        if current.is_null() {
+            *current = Value::Object(serde_json::Map::new());
+        }
+        current = match current {
+            Value::Object(map) => map
+                .entry(key.clone())
+                .or_insert(Value::Object(serde_json::Map::new())),
+            Value::Array(items) => match key.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                Some(item) => item,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+
+    match (current, value) {
+        (Value::Object(map), Some(value)) => {
+            map.insert(last.clone(), value);
+            true
+        }
+        (Value::Object(map), None) => map.remove(last).is_some(),
+        (Value::Array(items), value) => match last.parse::<usize>() {
+            Ok(index) if index < items.len() => {
+                match value {
+                    Some(value) => items[index] = value,
+                    None => {
+                        items.remove(index);
+                    }
+                }
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuperConfig;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Database {
+        url: String,
+        pool: u32,
+    }
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        host: String,
+        port: u16,
+        database: Database,
+        #[serde(default = "default_retries")]
+        retries: u8,
+        tags: Vec<String>,
+    }
+
+    fn default_retries() -> u8 {
+        3
+    }
+
+    fn config(value: Value) -> SuperConfig {
+        SuperConfig::new().with_defaults(value)
+    }
+
+    #[test]
+    fn test_complete_configuration_has_empty_report() {
+        let (extracted, report) = config(json!({
+            "host": "db.local",
+            "port": 5432,
+            "database": { "url": "postgres://db", "pool": 4 },
+            "tags": ["a"]
+        }))
+        .extract_lossy::<Config>()
+        .unwrap();
+
+        assert!(report.is_complete());
+        assert_eq!(extracted.retries, 3);
+        assert_eq!(report.to_string(), "configuration extracted completely");
+    }
+
+    #[test]
+    fn test_missing_and_invalid_keys_take_defaults() {
+        let (extracted, report) = config(json!({
+            "host": "db.local",
+            "port": 70000,
+            "database": { "pool": -1 },
+            "tags": ["a", 3, "c"]
+        }))
+        .extract_lossy::<Config>()
+        .unwrap();
+
+        assert_eq!(
+            extracted,
+            Config {
+                host: "db.local".to_string(),
+                port: 0,
+                database: Database::default(),
+                retries: 3,
+                tags: vec!["a".to_string(), "c".to_string()],
+            }
+        );
+        assert_eq!(report.missing, ["database.url"]);
+        let invalid: Vec<&str> = report
+            .invalid
+            .iter()
+            .map(|field| field.path.as_str())
+            .collect();
+        assert_eq!(invalid, ["database.pool", "port", "tags[1]"]);
+        assert!(report.invalid[1].message.contains("70000"));
+        assert!(report.to_string().starts_with(
+            "configuration extracted with 1 missing and 3 invalid keys:\n  - database.url: missing"
+        ));
+    }
+
+    #[test]
+    fn test_missing_table_is_filled_from_defaults() {
+        let (extracted, report) = config(json!({ "host": "db.local", "port": 1, "tags": [] }))
+            .extract_lossy::<Config>()
+            .unwrap();
+
+        assert_eq!(extracted.database, Database::default());
+        assert_eq!(report.missing, ["database"]);
+        assert!(report.invalid.is_empty());
+    }
+
+    #[test]
+    fn test_key_without_default_fails() {
+        #[derive(Debug, Default, Serialize, Deserialize)]
+        #[allow(dead_code)]
+        struct Credentials {
+            user: String,
+            #[serde(skip_serializing)]
+            token: String,
+        }
+
+        // The token is absent from the serialized defaults, so nothing can replace it
+        let error = config(json!({ "user": "admin", "token": 42 }))
+            .extract_lossy::<Credentials>()
+            .unwrap_err();
+        assert!(error.to_string().contains("token"));
+    }
+
+    #[test]
+    fn test_paths_are_displayed_with_array_indexes() {
+        let data = json!({ "servers": [{ "ports": [1, 2] }] });
+        let path: Vec<String> = ["servers", "0", "ports", "1"].map(String::from).to_vec();
+        assert_eq!(display_path(&data, &path), "servers[0].ports[1]");
+        assert_eq!(lookup(&data, &path), Some(&json!(2)));
+    }
+}
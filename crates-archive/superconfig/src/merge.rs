@@ -4,11 +4,18 @@
 //! Figment's standard merge functionality by adding:
 //! - Warning collection from providers with validation errors
 //! - Array merging with _add/_remove patterns
+//! - Arrays of tables merged by an identity field (see [`SuperConfig::with_array_key`](crate::SuperConfig::with_array_key))
+//! - Per-key merge policies (see [`SuperConfig::with_merge_policy`](crate::SuperConfig::with_merge_policy))
 //! - Resilient configuration loading that continues despite provider errors
 
-use figment::{Error, Figment, Provider, providers::Format};
-use serde_json;
-use std::collections::HashSet;
+use crate::policy::MergePolicies;
+use crate::provenance;
+use figment::{
+    Error, Figment, Provider,
+    providers::{Format, Serialized},
+};
+use serde_json::{self, Value};
+use std::collections::{BTreeMap, HashSet};
 
 /// Trait for providers that can have validation errors
 ///
@@ -45,21 +52,25 @@ impl crate::SuperConfig {
     /// }
     /// ```
     pub fn merge<P: Provider>(mut self, provider: P) -> Self {
-        self.figment = self.figment.merge(provider);
-        self.apply_array_merging()
+        self.merge_layer(provider);
+        self.apply_env_overrides().apply_array_merging()
     }
 
     /// Merge a validated provider with warning collection
+    ///
+    /// Validation errors are collected after the provider is loaded, so providers can
+    /// report problems found while loading, such as files a [`Wildcard`](crate::Wildcard)
+    /// provider skipped.
     pub fn merge_validated<P: Provider + ValidatedProvider>(mut self, provider: P) -> Self {
+        // Merge the provider regardless of validation errors, then apply array merging
+        self.merge_layer(&provider);
+
         // Check for validation errors and collect as warnings
         if let Some(error) = provider.validation_error() {
             self.warnings
                 .push(format!("Provider validation error: {error}"));
         }
-
-        // Merge the provider regardless of validation errors, then apply array merging
-        self.figment = self.figment.merge(provider);
-        self.apply_array_merging()
+        self.apply_env_overrides().apply_array_merging()
     }
 
     /// Merge an optional provider with warning collection
@@ -82,17 +93,78 @@ impl crate::SuperConfig {
         }
     }
 
+    /// Merge a provider, combining arrays of tables registered with
+    /// [`with_array_key`](Self::with_array_key) entry by entry and normalizing keys
+    /// registered with [`with_path_key`](Self::with_path_key)
+    ///
+    /// Figment replaces arrays wholesale, so keyed arrays are merged from the arrays of the
+    /// configuration so far and of the new layer, then set on top of the merged layer.
+    /// Records the origin of the keys the provider sets. Registered
+    /// [migrations](Self::with_migrations) are applied to the provider first.
+    fn merge_layer<P: Provider>(&mut self, provider: P) {
+        if self.migrations.is_empty() {
+            self.merge_source(provider);
+        } else {
+            let migrated = self.migrate(&provider);
+            self.merge_source(migrated);
+        }
+    }
+
+    fn merge_source<P: Provider>(&mut self, provider: P) {
+        let figment = std::mem::replace(&mut self.figment, Figment::new());
+        if self.array_keys.is_empty() && self.path_keys.is_empty() && self.merge_policies.is_empty()
+        {
+            let after = provenance::newest_tag(&figment);
+            self.figment = figment.merge(provider);
+            self.provenance.record(&self.figment, after);
+            return;
+        }
+
+        let layer = Figment::from(provider);
+        self.provenance.record(&layer, None);
+        let keyed: Vec<(&String, Vec<Value>)> = self
+            .array_keys
+            .iter()
+            .filter_map(|(path, key)| {
+                let base = figment.extract_inner::<Vec<Value>>(path).ok()?;
+                let update = layer.extract_inner::<Vec<Value>>(path).ok()?;
+                Some((path, ArrayMergeHelper::merge_keyed(base, update, key)))
+            })
+            .collect();
+
+        let mut figment = self.merge_with_paths(figment, layer, &self.merge_policies);
+        for (path, merged) in keyed {
+            figment = figment.merge(Serialized::default(path, merged));
+        }
+        self.figment = figment;
+    }
+
+    /// Re-apply emergency overrides on top of everything merged so far
+    ///
+    /// Keeps `SUPERCONFIG_OVERRIDE__` variables the final layer once
+    /// [`with_env_overrides`](Self::with_env_overrides) enabled them.
+    pub(crate) fn apply_env_overrides(mut self) -> Self {
+        if let Some(overrides) = &self.env_overrides {
+            let figment = std::mem::replace(&mut self.figment, Figment::new());
+            let layer = Figment::from(overrides.clone());
+            self.provenance.record(&layer, None);
+            // Overrides replace values whatever the merge policy, as they are re-applied
+            self.figment = self.merge_with_paths(figment, layer, &MergePolicies::default());
+        }
+        self
+    }
+
     /// Apply array merging to current configuration
     ///
     /// This method processes _add and _remove patterns in the configuration to
     /// intelligently merge arrays across all configuration sources.
-    fn apply_array_merging(mut self) -> Self {
+    pub(crate) fn apply_array_merging(mut self) -> Self {
         // Optimization: Check if array merging is needed before expensive operations
         if !ArrayMergeHelper::needs_array_merging(&self.figment) {
             return self; // No merging needed - early return
         }
 
-        self.figment = ArrayMergeHelper::apply_array_merging(self.figment);
+        self.figment = ArrayMergeHelper::apply_array_merging(self.figment, &self.array_keys);
         self
     }
 
@@ -195,7 +267,7 @@ impl ArrayMergeHelper {
     }
 
     /// Apply array merging to the figment configuration
-    fn apply_array_merging(figment: Figment) -> Figment {
+    fn apply_array_merging(figment: Figment, array_keys: &BTreeMap<String, String>) -> Figment {
         // Extract complete configuration as JSON for processing
         let json_config = match figment.extract::<serde_json::Value>() {
             Ok(config) => config,
@@ -208,7 +280,7 @@ impl ArrayMergeHelper {
         );
 
         // Apply array merging transformations
-        let merged_config = Self::merge_object_arrays(json_config);
+        let merged_config = Self::merge_object_arrays(json_config, "", array_keys);
 
         eprintln!(
             "DEBUG: After array merging: {}",
@@ -220,7 +292,14 @@ impl ArrayMergeHelper {
     }
 
     /// Core array merging logic with _add and _remove pattern support
-    fn merge_object_arrays(mut value: serde_json::Value) -> serde_json::Value {
+    ///
+    /// `path` is the dotted path of `value`, to find the arrays merged by key: their
+    /// `_add` entries update the entry with the same key, and `_remove` takes keys.
+    fn merge_object_arrays(
+        mut value: serde_json::Value,
+        path: &str,
+        array_keys: &BTreeMap<String, String>,
+    ) -> serde_json::Value {
         match &mut value {
             serde_json::Value::Object(obj) => {
                 let mut fields_to_remove = Vec::new();
@@ -258,10 +337,18 @@ impl ArrayMergeHelper {
 
                     eprintln!("DEBUG: Initial base array for '{base_field}': {result_array:?}");
 
+                    let identity = array_keys.get(&Self::child_path(path, base_field));
+
                     // Apply _add operations
                     if let Some(add_value) = obj.get(&add_key).and_then(|v| v.as_array()) {
                         eprintln!("DEBUG: Adding values to '{base_field}': {add_value:?}");
-                        result_array.extend(add_value.clone());
+                        result_array = match identity {
+                            Some(key) => Self::merge_keyed(result_array, add_value.clone(), key),
+                            None => {
+                                result_array.extend(add_value.clone());
+                                result_array
+                            }
+                        };
                         fields_to_remove.push(add_key);
                     } else {
                         eprintln!("DEBUG: No _add values found for '{base_field}'");
@@ -271,7 +358,12 @@ impl ArrayMergeHelper {
                     if let Some(remove_value) = obj.get(&remove_key).and_then(|v| v.as_array()) {
                         eprintln!("DEBUG: Removing values from '{base_field}': {remove_value:?}");
                         let before_count = result_array.len();
-                        result_array.retain(|item| !remove_value.contains(item));
+                        result_array.retain(|item| {
+                            !remove_value.contains(item)
+                                && !identity.is_some_and(|key| {
+                                    Self::removed_by_key(item, remove_value, key)
+                                })
+                        });
                         eprintln!(
                             "DEBUG: Removed {} items from '{base_field}'",
                             before_count - result_array.len()
@@ -297,8 +389,12 @@ impl ArrayMergeHelper {
                 }
 
                 // Recursively process nested objects
-                for value in obj.values_mut() {
-                    *value = Self::merge_object_arrays(value.clone());
+                for (key, value) in obj.iter_mut() {
+                    *value = Self::merge_object_arrays(
+                        value.clone(),
+                        &Self::child_path(path, key),
+                        array_keys,
+                    );
                 }
 
                 serde_json::Value::Object(obj.clone())
@@ -307,7 +403,7 @@ impl ArrayMergeHelper {
                 // Recursively process array elements
                 let processed_array: Vec<serde_json::Value> = arr
                     .iter()
-                    .map(|item| Self::merge_object_arrays(item.clone()))
+                    .map(|item| Self::merge_object_arrays(item.clone(), path, array_keys))
                     .collect();
                 serde_json::Value::Array(processed_array)
             }
@@ -315,4 +411,56 @@ impl ArrayMergeHelper {
             other => other.clone(),
         }
     }
+
+    /// Dotted path of `key` inside the table at `path` (empty for the root)
+    fn child_path(path: &str, key: &str) -> String {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    }
+
+    /// Merge the entries of `update` into `base` by their `key` field
+    ///
+    /// Entries whose key matches an entry of `base` update it field by field, in place;
+    /// other entries (including those without the key) are appended.
+    fn merge_keyed(mut base: Vec<Value>, update: Vec<Value>, key: &str) -> Vec<Value> {
+        for entry in update {
+            let existing = entry
+                .get(key)
+                .and_then(|id| base.iter().position(|item| item.get(key) == Some(id)));
+            match existing {
+                Some(index) => Self::merge_entry(&mut base[index], entry),
+                None => base.push(entry),
+            }
+        }
+        base
+    }
+
+    /// Deep-merge `update` into `target`, tables field by field and other values replaced
+    fn merge_entry(target: &mut Value, update: Value) {
+        match (target, update) {
+            (Value::Object(target), Value::Object(update)) => {
+                for (field, value) in update {
+                    match target.get_mut(&field) {
+                        Some(existing) => Self::merge_entry(existing, value),
+                        None => {
+                            target.insert(field, value);
+                        }
+                    }
+                }
+            }
+            (target, update) => *target = update,
+        }
+    }
+
+    /// Whether `item` is listed in `removed`, by its key value or as a table with that key
+    fn removed_by_key(item: &Value, removed: &[Value], key: &str) -> bool {
+        item.get(key).is_some_and(|id| {
+            removed
+                .iter()
+                .any(|entry| entry == id || entry.get(key) == Some(id))
+        })
+    }
 }
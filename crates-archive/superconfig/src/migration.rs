@@ -0,0 +1,447 @@
+//! Key migrations keeping configuration written for older releases working
+//!
+//! When a release renames `server.addr` to `server.host`, existing configuration files
+//! and environment variables still set the old key. A [`MigrationMap`] registered with
+//! [`SuperConfig::with_migrations`] rewrites every source as it is merged: renamed keys
+//! move to their new path, and value transforms convert values written in an old format.
+//! Each migrated key is reported as a [`Deprecation`] naming the file and line, or the
+//! environment variable, that still uses it.
+//!
+//! Deprecations are collected as [warnings](crate::SuperConfig::warnings), logged at
+//! [`INFO`](crate::verbosity::INFO) verbosity, and listed with their origin by
+//! [`SuperConfig::deprecations`].
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! use superconfig::migration::MigrationMap;
+//! use serde_json::{Value, json};
+//!
+//! let migrations = MigrationMap::new()
+//!     .rename("server.addr", "server.host")
+//!     // Timeouts used to be in seconds
+//!     .rename_with("timeout", "timeout_ms", |value| match value.as_u64() {
+//!         Some(seconds) => Ok(json!(seconds * 1000)),
+//!         None => Err("expected a number of seconds".to_string()),
+//!     })
+//!     // `level` used to take uppercase names
+//!     .transform("log.level", |value| match value {
+//!         Value::String(level) => Ok(Value::String(level.to_lowercase())),
+//!         other => Ok(other),
+//!     });
+//!
+//! let config = SuperConfig::new()
+//!     .with_migrations(migrations)
+//!     .with_defaults_string(r#"{"server": {"addr": "0.0.0.0"}, "timeout": 5, "log": {"level": "WARN"}}"#);
+//!
+//! assert_eq!(config.extract_inner::<String>("server.host")?, "0.0.0.0");
+//! assert_eq!(config.extract_inner::<u64>("timeout_ms")?, 5000);
+//! assert_eq!(config.extract_inner::<String>("log.level")?, "warn");
+//! assert!(config.find_value("server.addr").is_err());
+//!
+//! let deprecated: Vec<&str> = config.deprecations().iter().map(|d| d.key.as_str()).collect();
+//! assert_eq!(deprecated, ["server.addr", "timeout", "log.level"]);
+//! # Ok::<(), figment::Error>(())
+//! ```
+//!
+//! [`SuperConfig::with_migrations`]: crate::SuperConfig::with_migrations
+//! [`SuperConfig::deprecations`]: crate::SuperConfig::deprecations
+
+use figment::{
+    Error, Metadata, Profile, Provider,
+    value::{Dict, Map, Tag, Value},
+};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::provenance::{self, Origin};
+use crate::verbosity::{self, DebugCollector};
+
+/// Conversion of a value written in an old format
+type Transform = Arc<dyn Fn(serde_json::Value) -> Result<serde_json::Value, String> + Send + Sync>;
+
+#[derive(Clone)]
+struct Migration {
+    from: String,
+    to: String,
+    transform: Option<Transform>,
+}
+
+/// Renamed keys and value transforms, applied in the order they were added
+///
+/// A later migration sees the result of the earlier ones, so chained renames across
+/// several releases (`a → b`, then `b → c`) migrate the oldest files too.
+#[derive(Clone, Default)]
+pub struct MigrationMap {
+    migrations: Vec<Migration>,
+}
+
+impl fmt::Debug for MigrationMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.migrations
+                    .iter()
+                    .map(|migration| format!("{} → {}", migration.from, migration.to)),
+            )
+            .finish()
+    }
+}
+
+impl MigrationMap {
+    /// An empty map; add migrations with [`rename`](Self::rename),
+    /// [`rename_with`](Self::rename_with) and [`transform`](Self::transform)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the value of `old_path` to `new_path`
+    ///
+    /// When a source sets both keys, the value of `new_path` is kept and the old one is
+    /// dropped.
+    pub fn rename(mut self, old_path: &str, new_path: &str) -> Self {
+        self.migrations.push(Migration {
+            from: old_path.to_string(),
+            to: new_path.to_string(),
+            transform: None,
+        });
+        self
+    }
+
+    /// Move the value of `old_path` to `new_path`, converting it with `transform`
+    ///
+    /// An error returned by `transform` fails the source, naming the key.
+    pub fn rename_with<F>(mut self, old_path: &str, new_path: &str, transform: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> Result<serde_json::Value, String> + Send + Sync + 'static,
+    {
+        self.migrations.push(Migration {
+            from: old_path.to_string(),
+            to: new_path.to_string(),
+            transform: Some(Arc::new(transform)),
+        });
+        self
+    }
+
+    /// Convert the value of `path` with `transform`, keeping its key
+    ///
+    /// Values `transform` returns unchanged are in the current format and aren't
+    /// reported. An error returned by `transform` fails the source, naming the key.
+    pub fn transform<F>(self, path: &str, transform: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> Result<serde_json::Value, String> + Send + Sync + 'static,
+    {
+        self.rename_with(path, path, transform)
+    }
+
+    /// Returns `true` when no migration was added
+    pub fn is_empty(&self) -> bool {
+        self.migrations.is_empty()
+    }
+
+    /// Apply the migrations to the keys of one profile, returning the migrated keys
+    /// with the keys replacing them and whether their value was dropped
+    fn apply(&self, dict: &mut Dict) -> Result<Vec<(String, String, bool)>, String> {
+        let mut applied = Vec::new();
+        for migration in &self.migrations {
+            let from: Vec<&str> = migration.from.split('.').collect();
+            let to: Vec<&str> = migration.to.split('.').collect();
+            let Some(mut value) = take(dict, &from) else {
+                continue;
+            };
+
+            if let Some(transform) = &migration.transform {
+                let old = serde_json::to_value(&value)
+                    .map_err(|e| format!("`{}`: {e}", migration.from))?;
+                let new =
+                    transform(old.clone()).map_err(|e| format!("`{}`: {e}", migration.from))?;
+                if from == to && new == old {
+                    insert(dict, &to, value);
+                    continue;
+                }
+                value = Value::serialize(new).map_err(|e| format!("`{}`: {e}", migration.from))?;
+            }
+
+            let ignored = from != to && contains(dict, &to);
+            if !ignored {
+                insert(dict, &to, value);
+            }
+            applied.push((migration.from.clone(), migration.to.clone(), ignored));
+        }
+        Ok(applied)
+    }
+}
+
+/// Remove the value at `path`, and the tables it leaves empty
+fn take(dict: &mut Dict, path: &[&str]) -> Option<Value> {
+    let (key, rest) = path.split_first()?;
+    if rest.is_empty() {
+        return dict.remove(*key);
+    }
+    let Value::Dict(_, nested) = dict.get_mut(*key)? else {
+        return None;
+    };
+    let value = take(nested, rest)?;
+    if nested.is_empty() {
+        dict.remove(*key);
+    }
+    Some(value)
+}
+
+fn contains(dict: &Dict, path: &[&str]) -> bool {
+    match path.split_first() {
+        Some((key, [])) => dict.contains_key(*key),
+        Some((key, rest)) => match dict.get(*key) {
+            Some(Value::Dict(_, nested)) => contains(nested, rest),
+            _ => false,
+        },
+        None => false,
+    }
+}
+
+/// Insert `value` at `path`, replacing non-dictionary values on the way
+fn insert(dict: &mut Dict, path: &[&str], value: Value) {
+    let Some((key, rest)) = path.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        dict.insert(key.to_string(), value);
+        return;
+    }
+
+    let entry = dict
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Dict(Tag::default(), Dict::new()));
+    if !matches!(entry, Value::Dict(..)) {
+        *entry = Value::Dict(Tag::default(), Dict::new());
+    }
+    if let Value::Dict(_, nested) = entry {
+        insert(nested, rest, value);
+    }
+}
+
+/// A key a source still sets under its old name or in an old format
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deprecation {
+    /// Dotted path of the deprecated key
+    pub key: String,
+    /// Dotted path its value was moved to; the same as `key` for a value transform
+    pub replacement: String,
+    /// Whether the value was dropped because the source also sets `replacement`
+    pub ignored: bool,
+    /// Where the deprecated key was set
+    pub origin: Origin,
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key == self.replacement {
+            write!(f, "`{}` has a value in a deprecated format", self.key)?;
+        } else if self.ignored {
+            write!(
+                f,
+                "`{}` is deprecated and ignored, as `{}` is also set",
+                self.key, self.replacement
+            )?;
+        } else {
+            write!(
+                f,
+                "`{}` is deprecated; use `{}` instead",
+                self.key, self.replacement
+            )?;
+        }
+        write!(f, " (at {})", self.origin)
+    }
+}
+
+/// A source with the migrations applied, merged in place of the provider it wraps
+pub(crate) struct Migrated {
+    metadata: Metadata,
+    profile: Option<Profile>,
+    data: Result<Map<Profile, Dict>, Error>,
+    deprecations: Vec<Deprecation>,
+}
+
+impl Migrated {
+    /// Read `provider` and apply `migrations` to its keys
+    pub(crate) fn new<P: Provider>(provider: &P, migrations: &MigrationMap) -> Self {
+        let metadata = provider.metadata();
+        let mut deprecations = Vec::new();
+        let data = provider.data().and_then(|mut data| {
+            for (profile, dict) in &mut data {
+                let applied = migrations
+                    .apply(dict)
+                    .map_err(|e| Error::from(format!("Cannot migrate {}: {e}", metadata.name)))?;
+                for (key, replacement, ignored) in applied {
+                    let keys: Vec<&str> = key.split('.').collect();
+                    let mut origin = Origin::new(&metadata, profile, &keys);
+                    origin.line = origin
+                        .file()
+                        .and_then(|file| provenance::find_line(file, &key));
+                    deprecations.push(Deprecation {
+                        key,
+                        replacement,
+                        ignored,
+                        origin,
+                    });
+                }
+            }
+            Ok(data)
+        });
+
+        Self {
+            profile: provider.profile(),
+            metadata,
+            data,
+            deprecations,
+        }
+    }
+
+    pub(crate) fn deprecations(&self) -> &[Deprecation] {
+        &self.deprecations
+    }
+}
+
+impl Provider for Migrated {
+    fn metadata(&self) -> Metadata {
+        self.metadata.clone()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        self.data.clone()
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.profile.clone()
+    }
+}
+
+impl crate::SuperConfig {
+    /// Migrate renamed keys and old value formats of the sources merged from now on
+    ///
+    /// Each source is rewritten by `migrations` before it is merged, so configuration
+    /// written for older releases keeps working. Every migrated key is collected as a
+    /// warning, logged at [`INFO`](verbosity::INFO) verbosity, and listed by
+    /// [`deprecations`](Self::deprecations). Register migrations before adding sources;
+    /// replaces previously registered migrations. See [`migration`](crate::migration).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use superconfig::migration::MigrationMap;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_migrations(MigrationMap::new().rename("server.addr", "server.host"))
+    ///     .with_defaults_string(r#"{"server": {"addr": "0.0.0.0"}}"#);
+    ///
+    /// assert_eq!(config.extract_inner::<String>("server.host")?, "0.0.0.0");
+    /// assert!(config.warnings()[0].contains("`server.addr` is deprecated; use `server.host` instead"));
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn with_migrations(mut self, migrations: MigrationMap) -> Self {
+        self.debug(
+            verbosity::DEBUG,
+            "migration",
+            &format!("Registered {} key migrations", migrations.migrations.len()),
+        );
+        self.migrations = migrations;
+        self
+    }
+
+    /// Keys the merged sources set under an old name or in an old format
+    ///
+    /// Empty unless [`with_migrations`](Self::with_migrations) registered migrations and
+    /// a later source used a migrated key.
+    pub fn deprecations(&self) -> &[Deprecation] {
+        &self.deprecations
+    }
+
+    /// Apply the registered migrations to `provider`, reporting the deprecated keys it sets
+    pub(crate) fn migrate<P: Provider>(&mut self, provider: &P) -> Migrated {
+        let migrated = Migrated::new(provider, &self.migrations);
+        for deprecation in migrated.deprecations() {
+            let message = format!("Deprecated key: {deprecation}");
+            self.debug(verbosity::INFO, "migration", &message);
+            self.warnings.push(message);
+        }
+        self.deprecations
+            .extend(migrated.deprecations().iter().cloned());
+        migrated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuperConfig;
+    use serde_json::json;
+
+    #[test]
+    fn test_rename_moves_values_and_drops_empty_tables() {
+        let mut dict: Dict = Value::serialize(json!({
+            "server": {"addr": "0.0.0.0"},
+            "old": {"name": "a"},
+            "new": {"name": "b"},
+        }))
+        .unwrap()
+        .into_dict()
+        .unwrap();
+
+        let applied = MigrationMap::new()
+            .rename("server.addr", "server.host")
+            .rename("old.name", "new.name")
+            .rename("missing", "elsewhere")
+            .apply(&mut dict)
+            .unwrap();
+
+        assert_eq!(
+            applied,
+            [
+                ("server.addr".to_string(), "server.host".to_string(), false),
+                ("old.name".to_string(), "new.name".to_string(), true),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&dict).unwrap(),
+            json!({"server": {"host": "0.0.0.0"}, "new": {"name": "b"}})
+        );
+    }
+
+    #[test]
+    fn test_chained_renames() {
+        let config = SuperConfig::new()
+            .with_migrations(MigrationMap::new().rename("a", "b").rename("b", "c"))
+            .with_defaults_string(r#"{"a": 1}"#);
+
+        assert_eq!(config.extract_inner::<i32>("c").unwrap(), 1);
+        assert_eq!(config.deprecations().len(), 2);
+    }
+
+    #[test]
+    fn test_failed_transform_names_the_key() {
+        let config = SuperConfig::new()
+            .with_migrations(
+                MigrationMap::new().transform("port", |_| Err("not a port".to_string())),
+            )
+            .with_defaults_string(r#"{"port": "eighty"}"#);
+
+        let error = config
+            .extract::<serde_json::Value>()
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("`port`: not a port"), "{error}");
+    }
+
+    #[test]
+    fn test_unchanged_values_not_reported() {
+        let lowercase =
+            |value: serde_json::Value| Ok(json!(value.as_str().unwrap_or_default().to_lowercase()));
+        let config = SuperConfig::new()
+            .with_migrations(MigrationMap::new().transform("level", lowercase))
+            .with_defaults_string(r#"{"level": "warn"}"#);
+
+        assert!(config.deprecations().is_empty());
+        assert!(!config.has_warnings());
+    }
+}
@@ -0,0 +1,192 @@
+//! Defaults from a struct instance and partial overrides
+//!
+//! Extracting a configuration struct fails when a source leaves out one of its fields,
+//! unless every field is marked `#[serde(default)]`. Two tools avoid that:
+//!
+//! - [`SuperConfig::extract_with_defaults`](crate::SuperConfig::extract_with_defaults)
+//!   takes a complete instance and merges it beneath the loaded sources, so any key no
+//!   source sets keeps the value of the instance
+//! - [`PartialConfig`] pairs a struct with a copy whose fields are all optional, so an
+//!   override file setting a few keys can be extracted on its own and applied to a
+//!   complete value. With the `derive` feature, `#[derive(PartialConfig)]` generates the
+//!   copy, named `Partial<Struct>`; nested tables marked `#[partial(nested)]` are
+//!   overridden key by key
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Server { host: String, port: u16, workers: u32 }
+//!
+//! let defaults = Server { host: "localhost".into(), port: 8080, workers: 4 };
+//! let config = SuperConfig::new().with_defaults_string(r#"{"port": 9090}"#);
+//!
+//! let server = config.extract_with_defaults(defaults)?;
+//! assert_eq!(server.host, "localhost");
+//! assert_eq!(server.port, 9090);
+//! # Ok::<(), figment::Error>(())
+//! ```
+
+use figment::{Error, Figment, providers::Serialized};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::verbosity::{self, DebugCollector};
+
+#[cfg(feature = "derive")]
+pub use superconfig_macros::PartialConfig;
+
+/// A configuration struct with a counterpart whose fields are all optional
+///
+/// Derive it with `#[derive(PartialConfig)]` (`derive` feature), which needs `serde` as a
+/// dependency of the deriving crate, or implement it by hand:
+///
+/// ```rust
+/// use superconfig::{Partial, PartialConfig, SuperConfig};
+/// use serde::{Deserialize, Serialize};
+///
+/// struct Server { host: String, port: u16 }
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct PartialServer { host: Option<String>, port: Option<u16> }
+///
+/// impl PartialConfig for Server {
+///     type Partial = PartialServer;
+///
+///     fn apply(&mut self, partial: PartialServer) {
+///         if let Some(host) = partial.host { self.host = host; }
+///         if let Some(port) = partial.port { self.port = port; }
+///     }
+/// }
+///
+/// let overrides: Partial<Server> = SuperConfig::new()
+///     .with_defaults_string(r#"{"port": 9090}"#)
+///     .extract()?;
+///
+/// let mut server = Server { host: "localhost".into(), port: 8080 };
+/// server.apply(overrides);
+/// assert_eq!((server.host.as_str(), server.port), ("localhost", 9090));
+/// # Ok::<(), figment::Error>(())
+/// ```
+pub trait PartialConfig: Sized {
+    /// The struct with every field optional
+    type Partial: Serialize + DeserializeOwned + Default;
+
+    /// Replace the fields `partial` sets
+    fn apply(&mut self, partial: Self::Partial);
+}
+
+/// The partial counterpart of a [`PartialConfig`] struct
+pub type Partial<T> = <T as PartialConfig>::Partial;
+
+impl crate::SuperConfig {
+    /// Extract configuration, taking the keys no source sets from `defaults`
+    ///
+    /// `defaults` is merged beneath every loaded source, as if it had been added first
+    /// with [`with_defaults`](Self::with_defaults), so the fields of `T` don't need
+    /// `#[serde(default)]`. Tables are merged key by key: a source setting one key of a
+    /// nested table keeps the other keys of `defaults`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `defaults` can't be serialized or a source sets a key to a
+    /// value of the wrong type, like [`extract`](Self::extract).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Database { url: String, timeout: u32 }
+    ///
+    /// let config = SuperConfig::new().with_defaults_string(r#"{"timeout": 30}"#);
+    /// let database = config.extract_with_defaults(Database {
+    ///     url: "postgres://localhost".into(),
+    ///     timeout: 5,
+    /// })?;
+    ///
+    /// assert_eq!(database.url, "postgres://localhost");
+    /// assert_eq!(database.timeout, 30);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn extract_with_defaults<T>(&self, defaults: T) -> Result<T, Error>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.debug(
+            verbosity::INFO,
+            "extract_with_defaults",
+            "Merging defaults beneath the loaded sources",
+        );
+
+        let mut config = self.clone();
+        config.figment = Figment::from(Serialized::defaults(defaults))
+            .merge(self.figment.clone())
+            .select(self.figment.profile().clone());
+        config.extract()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuperConfig;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        name: String,
+        tags: Vec<String>,
+        limits: Limits,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Limits {
+        cpu: u32,
+        memory: u32,
+    }
+
+    fn defaults() -> Config {
+        Config {
+            name: "default".to_string(),
+            tags: vec!["a".to_string()],
+            limits: Limits {
+                cpu: 1,
+                memory: 256,
+            },
+        }
+    }
+
+    #[test]
+    fn test_defaults_fill_missing_keys() {
+        let config = SuperConfig::new()
+            .with_defaults_string(r#"{"tags": ["b"], "limits": {"memory": 512}}"#);
+        let extracted = config.extract_with_defaults(defaults()).unwrap();
+        assert_eq!(
+            extracted,
+            Config {
+                name: "default".to_string(),
+                tags: vec!["b".to_string()],
+                limits: Limits {
+                    cpu: 1,
+                    memory: 512
+                },
+            }
+        );
+
+        let empty = SuperConfig::new()
+            .extract_with_defaults(defaults())
+            .unwrap();
+        assert_eq!(empty, defaults());
+    }
+
+    #[test]
+    fn test_invalid_values_still_fail() {
+        let config = SuperConfig::new().with_defaults_string(r#"{"limits": {"cpu": "many"}}"#);
+        let error = config.extract_with_defaults(defaults()).unwrap_err();
+        assert_eq!(error.path, ["limits", "cpu"]);
+    }
+}
@@ -0,0 +1,319 @@
+//! Filesystem path values
+//!
+//! Keys registered with [`SuperConfig::with_path_key`] hold filesystem paths. Their string
+//! values (or arrays of strings) are normalized as each source is merged:
+//!
+//! - A leading `~` is expanded to the home directory
+//! - `/` and `\` are both treated as separators and converted to the platform's one, so
+//!   files written on Windows work on Unix and the other way around
+//! - Relative paths set by a configuration file are resolved against that file's
+//!   directory, not the working directory: `certs/server.pem` in `/etc/app/config.toml`
+//!   becomes `/etc/app/certs/server.pem` wherever the application is started. Relative
+//!   paths from other sources (environment variables, defaults) are left relative
+//! - `.` and `..` components are removed without touching the filesystem
+//!
+//! Keys marked with [`ConfigSchema::existing_path`] and registered with
+//! [`SuperConfig::with_path_schema`] must also exist on disk:
+//! [`extract`](SuperConfig::extract) fails with an error naming the key otherwise.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! use superconfig::schema::ConfigSchema;
+//! use serde_json::json;
+//! use std::path::PathBuf;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dir = tempfile::tempdir()?;
+//! let file = dir.path().join("app.toml");
+//! std::fs::write(&file, "log_dir = \"logs/../var/log\"\ncert = \"missing.pem\"")?;
+//!
+//! let schema = ConfigSchema::from_value(&json!({"log_dir": "", "cert": ""}))
+//!     .path("log_dir")
+//!     .existing_path("cert");
+//! let config = SuperConfig::new()
+//!     .with_path_schema(&schema)
+//!     .with_file(&file);
+//!
+//! let log_dir: PathBuf = config.extract_inner("log_dir")?;
+//! assert_eq!(log_dir, dir.path().join("var").join("log"));
+//!
+//! let error = config.extract::<serde_json::Value>().unwrap_err();
+//! assert!(error.to_string().contains("cert"));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::SuperConfig;
+use crate::policy::MergePolicies;
+use crate::schema::ConfigSchema;
+use figment::{
+    Error, Figment, Metadata, Source,
+    error::{Actual, Kind},
+    providers::Serialized,
+    value::Value as FigmentValue,
+};
+use serde_json::Value;
+use std::path::{Component, MAIN_SEPARATOR, Path, PathBuf};
+
+/// Normalize a configured path, resolving relative paths against `base` when given
+///
+/// # Examples
+/// ```rust
+/// use superconfig::paths::normalize_path;
+/// use std::path::{Path, PathBuf};
+///
+/// let path = normalize_path("./certs\\..\\keys/server.pem", Some(Path::new("/etc/app")));
+/// assert_eq!(path, PathBuf::from("/etc/app/keys/server.pem"));
+///
+/// assert_eq!(normalize_path("/var/log/", None), PathBuf::from("/var/log"));
+/// assert_eq!(normalize_path("logs", None), PathBuf::from("logs"));
+/// ```
+pub fn normalize_path(raw: &str, base: Option<&Path>) -> PathBuf {
+    let separated: String = raw
+        .chars()
+        .map(|c| {
+            if c == '/' || c == '\\' {
+                MAIN_SEPARATOR
+            } else {
+                c
+            }
+        })
+        .collect();
+    let expanded = expand_home(&separated);
+    let resolved = match base {
+        Some(base) if expanded.is_relative() => base.join(expanded),
+        _ => expanded,
+    };
+    lexically_normalize(&resolved)
+}
+
+/// Replace a leading `~` with the home directory, leaving `~user` paths untouched
+fn expand_home(path: &str) -> PathBuf {
+    let Some(rest) = path.strip_prefix('~') else {
+        return PathBuf::from(path);
+    };
+    if !rest.is_empty() && !rest.starts_with(MAIN_SEPARATOR) {
+        return PathBuf::from(path);
+    }
+    match dirs::home_dir() {
+        Some(home) => home.join(rest.trim_start_matches(MAIN_SEPARATOR)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Remove `.` components and fold `..` into their parent where there is one
+fn lexically_normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // `..` of the root is the root
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        normalized.push(".");
+    }
+    normalized
+}
+
+/// Normalize a string or an array of strings, `None` for any other value
+fn normalize_value(value: &FigmentValue, base: Option<&Path>) -> Option<Value> {
+    let normalize = |raw: &str| Value::String(normalize_path(raw, base).to_string_lossy().into());
+    match value {
+        FigmentValue::String(_, raw) => Some(normalize(raw)),
+        FigmentValue::Array(_, items) => items
+            .iter()
+            .map(|item| item.as_str().map(normalize))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        _ => None,
+    }
+}
+
+impl SuperConfig {
+    /// Treat the value of `path` as a filesystem path
+    ///
+    /// The value is normalized as described in the [module documentation](crate::paths).
+    /// Register path keys before adding the sources that set them.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use std::path::PathBuf;
+    ///
+    /// let dir = tempfile::tempdir()?;
+    /// std::fs::write(dir.path().join("app.toml"), "data_dir = \"data\"")?;
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_path_key("data_dir")
+    ///     .with_file(dir.path().join("app.toml"));
+    ///
+    /// let data_dir: PathBuf = config.extract_inner("data_dir")?;
+    /// assert_eq!(data_dir, dir.path().join("data"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_path_key(mut self, path: &str) -> Self {
+        self.path_keys.entry(path.to_string()).or_insert(false);
+        self
+    }
+
+    /// Register the keys marked with [`ConfigSchema::path`] or [`ConfigSchema::existing_path`]
+    ///
+    /// Keys marked with `existing_path` make [`extract`](Self::extract) fail when their
+    /// final value does not exist on disk.
+    pub fn with_path_schema(mut self, schema: &ConfigSchema) -> Self {
+        for (path, field) in schema.fields().filter(|(_, field)| field.path) {
+            let must_exist = self.path_keys.entry(path.to_string()).or_insert(false);
+            *must_exist |= field.must_exist;
+        }
+        self
+    }
+
+    /// Paths of the registered path keys
+    pub fn path_keys(&self) -> impl Iterator<Item = &str> {
+        self.path_keys.keys().map(String::as_str)
+    }
+
+    /// Merge `layer` into `figment`, then the normalized values of the path keys it sets
+    pub(crate) fn merge_with_paths(
+        &self,
+        figment: Figment,
+        layer: Figment,
+        policies: &MergePolicies,
+    ) -> Figment {
+        let normalized: Vec<(&String, Value)> = self
+            .path_keys
+            .keys()
+            .filter_map(|path| {
+                let value = layer.find_value(path).ok()?;
+                let base = layer
+                    .find_metadata(path)
+                    .and_then(|metadata| match &metadata.source {
+                        Some(Source::File(file)) => file.parent(),
+                        _ => None,
+                    });
+                Some((path, normalize_value(&value, base)?))
+            })
+            .collect();
+
+        let mut figment = policies.merge(figment, layer);
+        for (path, value) in normalized {
+            figment = figment.merge(Serialized::default(path, value));
+        }
+        figment
+    }
+
+    /// Check that every path key marked `must_exist` points to an existing file or directory
+    pub(crate) fn check_existing_paths(&self) -> Result<(), Error> {
+        let mut errors: Option<Error> = None;
+        for path in self
+            .path_keys
+            .iter()
+            .filter_map(|(path, must_exist)| must_exist.then_some(path))
+        {
+            let Ok(value) = self.figment.find_value(path) else {
+                continue;
+            };
+            let missing = match &value {
+                FigmentValue::String(_, file) => vec![file.as_str()],
+                FigmentValue::Array(_, items) => {
+                    items.iter().filter_map(|item| item.as_str()).collect()
+                }
+                _ => Vec::new(),
+            };
+            for file in missing.into_iter().filter(|file| !Path::new(file).exists()) {
+                let mut error = Error::from(Kind::InvalidValue(
+                    Actual::Str(file.to_string()),
+                    "an existing path".to_string(),
+                ));
+                error.path = path.split('.').map(str::to_string).collect();
+                error.profile = Some(self.figment.profile().clone());
+                error.metadata = Some(Metadata::named("SuperConfig::with_path_schema"));
+                errors = Some(match errors {
+                    Some(previous) => previous.chain(error),
+                    None => error,
+                });
+            }
+        }
+        errors.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_separators_and_dots() {
+        let expected: PathBuf = ["a", "c", "d.toml"].iter().collect();
+        assert_eq!(normalize_path("a/b\\..\\c/./d.toml", None), expected);
+        assert_eq!(normalize_path("../x/..", None), PathBuf::from(".."));
+        assert_eq!(normalize_path("./", None), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_normalize_relative_to_base() {
+        let base = Path::new("/etc/app");
+        assert_eq!(
+            normalize_path("../shared/ca.pem", Some(base)),
+            PathBuf::from("/etc/shared/ca.pem")
+        );
+        // Absolute paths ignore the base
+        assert_eq!(
+            normalize_path("/srv/ca.pem", Some(base)),
+            PathBuf::from("/srv/ca.pem")
+        );
+    }
+
+    #[test]
+    fn test_home_expansion() {
+        let home = dirs::home_dir().expect("home directory");
+        assert_eq!(normalize_path("~", None), home);
+        assert_eq!(
+            normalize_path("~/.app", Some(Path::new("/etc"))),
+            home.join(".app")
+        );
+        assert_eq!(normalize_path("~other/x", None), PathBuf::from("~other/x"));
+    }
+
+    #[test]
+    fn test_path_arrays_and_other_values() {
+        let config = SuperConfig::new()
+            .with_path_key("dirs")
+            .with_path_key("port")
+            .with_defaults_string(r#"{"dirs": ["a/./b", "c\\d"], "port": 80}"#);
+
+        let dirs: Vec<PathBuf> = config.extract_inner("dirs").unwrap();
+        assert_eq!(dirs, vec![PathBuf::from("a/b"), PathBuf::from("c/d")]);
+        // Values that are not strings are left to extraction
+        assert_eq!(config.extract_inner::<u16>("port").unwrap(), 80);
+    }
+
+    #[test]
+    fn test_existing_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = ConfigSchema::from_value(&serde_json::json!({"a": "", "b": "", "c": ""}))
+            .existing_path("a")
+            .existing_path("b")
+            .path("c");
+        let present = dir.path().to_string_lossy().to_string();
+        let config = SuperConfig::new().with_path_schema(&schema).with_defaults(
+            serde_json::json!({"a": present, "b": "/no/such/file", "c": "/no/such/dir"}),
+        );
+
+        assert_eq!(config.path_keys().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+        let error = config.extract::<Value>().unwrap_err();
+        assert_eq!(error.count(), 1);
+        assert_eq!(error.path, vec!["b".to_string()]);
+    }
+}
@@ -0,0 +1,349 @@
+//! Per-key merge policies
+//!
+//! When a source sets a key that an earlier source set too, Figment merges tables key
+//! by key and lets the later value replace anything else, arrays included. Keys
+//! registered with [`SuperConfig::with_merge_policy`] are merged by their
+//! [`MergePolicy`] instead:
+//!
+//! | Policy | Arrays | Tables |
+//! |--------|--------|--------|
+//! | [`Replace`](MergePolicy::Replace) | Later array replaces earlier one | Later table replaces earlier one |
+//! | [`Append`](MergePolicy::Append) | Later entries appended | Merged key by key |
+//! | [`Unique`](MergePolicy::Unique) | Later entries appended unless already present | Merged key by key |
+//! | [`DeepMerge`](MergePolicy::DeepMerge) | Merged entry by entry, by index | Merged key by key |
+//!
+//! Keys are matched by dotted path globs: `*` matches one key (`features.*` matches
+//! `features.web` but not `features`), and `**` any number of them. When several
+//! patterns match a key, the one registered last applies. The `_add`/`_remove` suffixes
+//! keep working alongside policies.
+//!
+//! [`Wildcard::with_merge_policy`](crate::Wildcard::with_merge_policy) applies policies
+//! between the files a single Wildcard provider discovers.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::{MergePolicy, SuperConfig};
+//! use serde_json::{Value, json};
+//!
+//! let config = SuperConfig::new()
+//!     .with_merge_policy("features.*", MergePolicy::Unique)
+//!     .with_merge_policy("limits", MergePolicy::Replace)
+//!     .with_defaults_string(r#"{
+//!         "features": {"web": ["auth", "cache"]},
+//!         "limits": {"cpu": 2, "memory": 512}
+//!     }"#)
+//!     .with_defaults_string(r#"{
+//!         "features": {"web": ["cache", "metrics"]},
+//!         "limits": {"cpu": 4}
+//!     }"#);
+//!
+//! let merged: Value = config.extract()?;
+//! assert_eq!(merged, json!({
+//!     "features": {"web": ["auth", "cache", "metrics"]},
+//!     "limits": {"cpu": 4}
+//! }));
+//! # Ok::<(), figment::Error>(())
+//! ```
+
+use figment::{Figment, providers::Serialized};
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How the value of a key is merged with the value an earlier source set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// The later value replaces the earlier one, tables included
+    Replace,
+    /// Arrays are concatenated, earlier entries first
+    Append,
+    /// Arrays are concatenated, skipping entries the earlier array already has
+    Unique,
+    /// Tables are merged key by key and arrays entry by entry, recursively
+    DeepMerge,
+}
+
+impl MergePolicy {
+    /// Merge `update` into `base` according to the policy
+    fn merge(self, base: &Value, update: &Value) -> Value {
+        match (self, base, update) {
+            (Self::Replace, _, update) => update.clone(),
+            (Self::Append, Value::Array(base), Value::Array(update)) => {
+                Value::Array(base.iter().chain(update).cloned().collect())
+            }
+            (Self::Unique, Value::Array(base), Value::Array(update)) => {
+                let mut merged = base.clone();
+                for entry in update {
+                    if !merged.contains(entry) {
+                        merged.push(entry.clone());
+                    }
+                }
+                Value::Array(merged)
+            }
+            (_, Value::Object(_), Value::Object(_)) | (Self::DeepMerge, _, _) => {
+                deep_merge(base, update)
+            }
+            (_, _, update) => update.clone(),
+        }
+    }
+}
+
+/// Merge `update` into `base`: tables key by key, arrays by index, other values replaced
+fn deep_merge(base: &Value, update: &Value) -> Value {
+    match (base, update) {
+        (Value::Object(base), Value::Object(update)) => {
+            let mut merged = base.clone();
+            for (key, value) in update {
+                let value = match base.get(key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(base), Value::Array(update)) => {
+            let mut merged: Vec<Value> = base
+                .iter()
+                .zip(update)
+                .map(|(existing, value)| deep_merge(existing, value))
+                .collect();
+            if base.len() > update.len() {
+                merged.extend(base[update.len()..].iter().cloned());
+            } else {
+                merged.extend(update[base.len()..].iter().cloned());
+            }
+            Value::Array(merged)
+        }
+        (_, update) => update.clone(),
+    }
+}
+
+/// Merge policies registered by key path glob, in registration order
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MergePolicies {
+    rules: Vec<(String, GlobMatcher, MergePolicy)>,
+}
+
+impl MergePolicies {
+    /// Register `policy` for keys matching `pattern`
+    pub(crate) fn add(&mut self, pattern: &str, policy: MergePolicy) -> Result<(), String> {
+        let glob = GlobBuilder::new(&pattern.replace('.', "/"))
+            .literal_separator(true)
+            .build()
+            .map_err(|e| format!("Invalid merge policy pattern '{pattern}': {e}"))?;
+        self.rules
+            .push((pattern.to_string(), glob.compile_matcher(), policy));
+        Ok(())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Patterns and policies, for passing them on to another provider
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, MergePolicy)> {
+        self.rules
+            .iter()
+            .map(|(pattern, _, policy)| (pattern.as_str(), *policy))
+    }
+
+    /// The policy of the key at `path`, from the last matching rule
+    fn policy_for(&self, path: &[&str]) -> Option<MergePolicy> {
+        let path = path.join("/");
+        self.rules
+            .iter()
+            .rev()
+            .find(|(_, matcher, _)| matcher.is_match(&path))
+            .map(|(_, _, policy)| *policy)
+    }
+
+    /// Merge `layer` over `figment`, applying the policies of the keys both set
+    pub(crate) fn merge(&self, figment: Figment, layer: Figment) -> Figment {
+        if self.is_empty() {
+            return figment.merge(layer);
+        }
+        let (Ok(base), Ok(update)) = (figment.extract::<Value>(), layer.extract::<Value>()) else {
+            return figment.merge(layer);
+        };
+
+        let mut merged = Vec::new();
+        self.collect(&base, &update, &mut Vec::new(), &mut merged);
+        let replaces_table = merged
+            .iter()
+            .any(|(_, value, replaced)| *replaced && value.is_object());
+
+        let figment = figment.merge(layer);
+        if merged.is_empty() {
+            return figment;
+        }
+        if replaces_table {
+            // Merging can't remove keys, so a replaced table needs the tree rebuilt
+            let Ok(mut tree) = figment.extract::<Value>() else {
+                return figment;
+            };
+            for (path, value, _) in merged {
+                set(&mut tree, &path, value);
+            }
+            return Figment::from(Serialized::defaults(tree));
+        }
+        merged
+            .into_iter()
+            .fold(figment, |figment, (path, value, _)| {
+                figment.merge(Serialized::default(&path.join("."), value))
+            })
+    }
+
+    /// Collect the merged value of each key of `update` with a policy, as its path, value
+    /// and whether the policy is `Replace`
+    fn collect<'a>(
+        &self,
+        base: &Value,
+        update: &'a Value,
+        path: &mut Vec<&'a str>,
+        merged: &mut Vec<(Vec<String>, Value, bool)>,
+    ) {
+        let Value::Object(table) = update else {
+            return;
+        };
+        for (key, value) in table {
+            let Some(existing) = base.get(key) else {
+                continue;
+            };
+            path.push(key);
+            match self.policy_for(path) {
+                Some(policy) => merged.push((
+                    path.iter().map(ToString::to_string).collect(),
+                    policy.merge(existing, value),
+                    policy == MergePolicy::Replace,
+                )),
+                None => self.collect(existing, value, path, merged),
+            }
+            path.pop();
+        }
+    }
+}
+
+/// Set the value at `path` in `tree`, whose tables along the path exist
+fn set(tree: &mut Value, path: &[String], value: Value) {
+    let target = path
+        .iter()
+        .try_fold(tree, |node, key| node.get_mut(key.as_str()));
+    if let Some(target) = target {
+        *target = value;
+    }
+}
+
+impl crate::SuperConfig {
+    /// Merge the keys matching `pattern` by `policy` when later sources set them again
+    ///
+    /// Patterns are dotted key paths where `*` matches one key and `**` any number of
+    /// them; see the [module documentation](crate::policy). Register policies before
+    /// adding the sources they apply to. An invalid pattern is recorded as a warning.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::{MergePolicy, SuperConfig};
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_merge_policy("plugins", MergePolicy::Append)
+    ///     .with_defaults_string(r#"{"plugins": ["core"]}"#)
+    ///     .with_defaults_string(r#"{"plugins": ["extra"]}"#);
+    ///
+    /// let plugins: Vec<String> = config.extract_inner("plugins")?;
+    /// assert_eq!(plugins, ["core", "extra"]);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn with_merge_policy(mut self, pattern: &str, policy: MergePolicy) -> Self {
+        if let Err(error) = self.merge_policies.add(pattern, policy) {
+            self.warnings.push(error);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policies(rules: &[(&str, MergePolicy)]) -> MergePolicies {
+        let mut policies = MergePolicies::default();
+        for (pattern, policy) in rules {
+            policies.add(pattern, *policy).unwrap();
+        }
+        policies
+    }
+
+    fn merge(policies: &MergePolicies, base: Value, update: Value) -> Value {
+        policies
+            .merge(
+                Figment::from(Serialized::defaults(base)),
+                Figment::from(Serialized::defaults(update)),
+            )
+            .extract()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_array_policies() {
+        let policies = policies(&[
+            ("append", MergePolicy::Append),
+            ("unique", MergePolicy::Unique),
+            ("deep", MergePolicy::DeepMerge),
+        ]);
+        let merged = merge(
+            &policies,
+            json!({"append": [1, 2], "unique": [1, 2], "deep": [{"a": 1, "b": 1}, {"a": 2}], "other": [1]}),
+            json!({"append": [2, 3], "unique": [2, 3], "deep": [{"b": 2}], "other": [2]}),
+        );
+        assert_eq!(
+            merged,
+            json!({
+                "append": [1, 2, 2, 3],
+                "unique": [1, 2, 3],
+                "deep": [{"a": 1, "b": 2}, {"a": 2}],
+                "other": [2],
+            })
+        );
+    }
+
+    #[test]
+    fn test_replace_removes_earlier_keys() {
+        let policies = policies(&[("servers.*", MergePolicy::Replace)]);
+        let merged = merge(
+            &policies,
+            json!({"servers": {"a": {"host": "a", "port": 80}}, "db": {"host": "x", "port": 1}}),
+            json!({"servers": {"a": {"host": "b"}}, "db": {"host": "y"}}),
+        );
+        assert_eq!(
+            merged,
+            json!({"servers": {"a": {"host": "b"}}, "db": {"host": "y", "port": 1}})
+        );
+    }
+
+    #[test]
+    fn test_globs_and_precedence() {
+        let policies = policies(&[
+            ("**.tags", MergePolicy::Append),
+            ("prod.*", MergePolicy::Replace),
+        ]);
+        assert_eq!(
+            policies.policy_for(&["a", "b", "tags"]),
+            Some(MergePolicy::Append)
+        );
+        assert_eq!(policies.policy_for(&["tags"]), Some(MergePolicy::Append));
+        assert_eq!(
+            policies.policy_for(&["prod", "tags"]),
+            Some(MergePolicy::Replace)
+        );
+        assert_eq!(policies.policy_for(&["prod"]), None);
+        assert!(
+            MergePolicies::default()
+                .add("a.[", MergePolicy::Append)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Per-key provenance: which source set the final value of each key
+//!
+//! Every merge records, for each key the merged provider sets, the provider's
+//! [`Metadata`]: its name, the file or code location it read, and how the source names
+//! the key (`APP_DATABASE_URL` for environment variables). A later source setting the
+//! key again replaces the record, so it always describes the value that won.
+//!
+//! [`SuperConfig::origin`](crate::SuperConfig::origin) returns the [`Origin`] of one key
+//! and [`SuperConfig::debug_provenance`](crate::SuperConfig::debug_provenance) lists
+//! them all. For files, the line of the key is looked up when the origin is requested:
+//! it is found for TOML, INI, YAML and JSON files laid out one key per line, and left
+//! out otherwise.
+//!
+//! Keys set by a [`Wildcard`](crate::Wildcard) provider point at the discovered file
+//! that set them, and keys of [included](crate::providers::include) files at the file
+//! including them. Arrays are leaves: the origin of `servers.0.host` is the source that
+//! set `servers`, and `features_add` entries are attributed to `features`.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! # let dir = tempfile::tempdir().unwrap();
+//! # let path = dir.path().join("app.toml");
+//! # std::fs::write(&path, "[database]\nhost = \"db\"\nurl = \"postgres://db\"\n").unwrap();
+//!
+//! let config = SuperConfig::new()
+//!     .with_defaults_string(r#"{"database": {"host": "localhost", "pool": 5}}"#)
+//!     .with_file(&path);
+//!
+//! let origin = config.origin("database.url").unwrap();
+//! assert_eq!(origin.line, Some(3));
+//! assert!(origin.to_string().ends_with("app.toml:3 (Format::Universal::TOML file)"));
+//!
+//! // Keys only the defaults set still come from them
+//! assert!(config.origin("database.pool").unwrap().source.is_none());
+//! ```
+
+use figment::{
+    Error, Figment, Metadata, Profile, Source,
+    value::{Dict, Map, Tag, Value},
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Prefix of a key's interpolation naming the file that set it
+const FILE_PREFIX: &str = "file:";
+
+/// Where the final value of a key came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Origin {
+    /// Name of the provider that set the value, such as `Env::Nested`
+    pub provider: String,
+    /// The file or code location the provider read, if any
+    pub source: Option<Source>,
+    /// The key as the source names it, such as the environment variable `APP_PORT`
+    pub name: Option<String>,
+    /// 1-based line of the key in the source file, when it could be found
+    pub line: Option<usize>,
+}
+
+impl Origin {
+    pub(crate) fn new(metadata: &Metadata, profile: &Profile, keys: &[&str]) -> Self {
+        let interpolated = metadata.interpolate(profile, keys);
+        let (source, name) = match interpolated.strip_prefix(FILE_PREFIX) {
+            Some(path) if !path.is_empty() => (Some(Source::File(PathBuf::from(path))), None),
+            // Providers without their own interpolater name keys `profile.key.path`
+            _ if interpolated == format!("{profile}.{}", keys.join(".")) => {
+                (metadata.source.clone(), None)
+            }
+            _ if interpolated.is_empty() => (metadata.source.clone(), None),
+            _ => (metadata.source.clone(), Some(interpolated)),
+        };
+        Self {
+            provider: metadata.name.to_string(),
+            source,
+            name,
+            line: None,
+        }
+    }
+
+    /// The file the value was read from, if any
+    pub fn file(&self) -> Option<&Path> {
+        self.source.as_ref().and_then(Source::file_path)
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.source, self.line, &self.name) {
+            (Some(source), Some(line), _) => write!(f, "{source}:{line} ({})", self.provider),
+            (Some(source @ Source::File(_)), None, _) | (Some(source), None, None) => {
+                write!(f, "{source} ({})", self.provider)
+            }
+            (_, _, Some(name)) => write!(f, "{name} ({})", self.provider),
+            (None, _, None) => f.write_str(&self.provider),
+        }
+    }
+}
+
+/// Origins of the keys set so far, by dotted path
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Provenance {
+    origins: BTreeMap<String, Origin>,
+    /// Earlier origins of keys a later source set again, oldest first
+    shadowed: BTreeMap<String, Vec<Origin>>,
+}
+
+impl Provenance {
+    /// Record the origin of every key of `figment` set by a provider merged after the
+    /// one tagged `after`, or of every key without it
+    pub(crate) fn record(&mut self, figment: &Figment, after: Option<Tag>) {
+        let Ok(serde_json::Value::Object(tree)) = figment.extract::<serde_json::Value>() else {
+            return;
+        };
+        let mut leaves = Vec::new();
+        for key in tree.keys() {
+            if let Ok(value) = figment.find_value(key) {
+                collect_leaves(vec![key.clone()], &value, &mut leaves);
+            }
+        }
+
+        for (keys, tag) in leaves {
+            let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+            if after.is_some_and(|after| tag <= after) {
+                continue;
+            }
+            let Some(metadata) = figment.get_metadata(tag) else {
+                continue;
+            };
+            let profile = tag.profile().unwrap_or_default();
+            let origin = Origin::new(metadata, &profile, &keys);
+            let key = base_key(&keys);
+            if let Some(previous) = self.origins.insert(key.clone(), origin)
+                && self.origins.get(&key) != Some(&previous)
+            {
+                let shadowed = self.shadowed.entry(key).or_default();
+                if !shadowed.contains(&previous) {
+                    shadowed.push(previous);
+                }
+            }
+        }
+    }
+
+    /// Origins of earlier values of `key` that later sources replaced, oldest first
+    pub(crate) fn shadowed(&self, key: &str) -> &[Origin] {
+        self.shadowed.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// The origin of `key`, or of the array holding it, without its line
+    pub(crate) fn get(&self, key: &str) -> Option<&Origin> {
+        let mut key = key;
+        loop {
+            if let Some(origin) = self.origins.get(key) {
+                return Some(origin);
+            }
+            key = &key[..key.rfind('.')?];
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &Origin)> {
+        self.origins.iter()
+    }
+}
+
+/// The newest tag among the values of `figment`, to tell apart the values a merge adds
+pub(crate) fn newest_tag(figment: &Figment) -> Option<Tag> {
+    let tree = figment.extract::<serde_json::Value>().ok()?;
+    let mut leaves = Vec::new();
+    for key in tree.as_object()?.keys() {
+        if let Ok(value) = figment.find_value(key) {
+            collect_leaves(vec![key.clone()], &value, &mut leaves);
+        }
+    }
+    leaves.into_iter().map(|(_, tag)| tag).max()
+}
+
+/// Collect the path and tag of each value below `value` that isn't a non-empty table
+fn collect_leaves(path: Vec<String>, value: &Value, leaves: &mut Vec<(Vec<String>, Tag)>) {
+    match value {
+        Value::Dict(_, dict) if !dict.is_empty() => {
+            for (key, value) in dict {
+                let mut path = path.clone();
+                path.push(key.clone());
+                collect_leaves(path, value, leaves);
+            }
+        }
+        _ => leaves.push((path, value.tag())),
+    }
+}
+
+/// Dotted path of `keys`, naming `_add`/`_remove` entries after the array they change
+fn base_key(keys: &[&str]) -> String {
+    let key = keys.join(".");
+    match key
+        .strip_suffix("_add")
+        .or_else(|| key.strip_suffix("_remove"))
+    {
+        Some(base) => base.to_string(),
+        None => key,
+    }
+}
+
+/// `data` read from another figment, with the tags of its values cleared
+///
+/// A provider returning the values of a figment it merged would otherwise keep their
+/// tags, whose metadata the figment merging the provider doesn't have; cleared values
+/// are tagged with the provider's own metadata.
+pub(crate) fn untagged(data: Map<Profile, Dict>) -> Result<Map<Profile, Dict>, Error> {
+    data.into_iter()
+        .map(|(profile, dict)| {
+            let dict = Value::serialize(dict)?.into_dict().unwrap_or_default();
+            Ok((profile, dict))
+        })
+        .collect()
+}
+
+/// Which file set each key of a provider merging several files
+///
+/// The metadata of such a provider names that file when interpolating a key, which
+/// [`Origin`] picks up as the key's source.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileOrigins(Arc<Mutex<BTreeMap<String, PathBuf>>>);
+
+impl FileOrigins {
+    /// Remember the files the keys of `config` were read from
+    pub(crate) fn update(&self, config: &crate::SuperConfig) {
+        let files = config
+            .provenance
+            .iter()
+            .filter_map(|(key, origin)| Some((key.clone(), origin.file()?.to_path_buf())))
+            .collect();
+        if let Ok(mut origins) = self.0.lock() {
+            *origins = files;
+        }
+    }
+
+    /// Metadata named `name` interpolating keys to the file that set them
+    pub(crate) fn metadata(&self, name: &'static str) -> Metadata {
+        let origins = self.0.clone();
+        Metadata::named(name).interpolater(move |_: &Profile, keys: &[&str]| {
+            let origins = origins.lock().ok();
+            let file = origins
+                .as_ref()
+                .and_then(|origins| origins.get(&keys.join(".")));
+            file.map(|file| format!("{FILE_PREFIX}{}", file.display()))
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Best-effort 1-based line of the key at `path` in the file at `file`
+pub(crate) fn find_line(file: &Path, path: &str) -> Option<usize> {
+    let content = std::fs::read_to_string(file).ok()?;
+    let extension = file.extension()?.to_str()?.to_ascii_lowercase();
+    let tables = matches!(extension.as_str(), "toml" | "ini" | "cfg" | "conf");
+    let path: Vec<&str> = path.split('.').collect();
+    find_line_in(&content, &path, tables)
+}
+
+/// Find the line setting `path` by matching its keys in order down the file
+///
+/// With `tables`, `[a.b]` headers set the table the following keys belong to and keys
+/// may be dotted, as in TOML and INI.
+fn find_line_in(content: &str, path: &[&str], tables: bool) -> Option<usize> {
+    // Keys of `path` matched so far, or `None` inside a table of another path
+    let mut matched = Some(0);
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) || line.starts_with("//") {
+            continue;
+        }
+        if tables && line.starts_with('[') {
+            let header = line.trim_start_matches('[');
+            let header = &header[..header.find(']')?];
+            let keys = split_key(header);
+            if keys == path {
+                return Some(index + 1);
+            }
+            matched = path.starts_with(&keys).then_some(keys.len());
+            continue;
+        }
+        let (Some(depth), Some(key)) = (matched, line_key(line)) else {
+            continue;
+        };
+        let keys = if tables { split_key(key) } else { vec![key] };
+        if path[depth..].starts_with(&keys) {
+            matched = Some(depth + keys.len());
+            if depth + keys.len() == path.len() {
+                return Some(index + 1);
+            }
+        }
+    }
+    None
+}
+
+/// The key a `key = value`, `key: value` or `"key": value` line sets
+fn line_key(line: &str) -> Option<&str> {
+    let line = line.strip_prefix("- ").unwrap_or(line).trim_start();
+    if let Some(quote) = line.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let rest = &line[1..];
+        let end = rest.find(quote)?;
+        let after = rest[end + 1..].trim_start();
+        return after.starts_with([':', '=']).then(|| &rest[..end]);
+    }
+    let end = line.find([':', '='])?;
+    let key = line[..end].trim();
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    valid.then_some(key)
+}
+
+/// The keys of a dotted TOML key, without their quotes
+fn split_key(key: &str) -> Vec<&str> {
+    key.split('.')
+        .map(|key| key.trim().trim_matches(['"', '\'']))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_line_in_formats() {
+        let toml = "name = \"app\"\n\n[database]\nhost = \"db\"\n# url = \"old\"\nurl = \"new\"\n\n[server.tls]\ncert = \"a.pem\"\n";
+        assert_eq!(find_line_in(toml, &["database", "url"], true), Some(6));
+        assert_eq!(
+            find_line_in(toml, &["server", "tls", "cert"], true),
+            Some(9)
+        );
+        assert_eq!(find_line_in(toml, &["server", "host"], true), None);
+        assert_eq!(find_line_in("a.b.c = 1", &["a", "b", "c"], true), Some(1));
+
+        let yaml = "server:\n  host: web\ndatabase:\n  host: db\n  url: postgres://db\n";
+        assert_eq!(find_line_in(yaml, &["database", "host"], false), Some(4));
+        assert_eq!(find_line_in(yaml, &["database", "url"], false), Some(5));
+
+        let json = "{\n  \"database\": {\n    \"url\": \"x\"\n  }\n}";
+        assert_eq!(find_line_in(json, &["database", "url"], false), Some(3));
+    }
+
+    #[test]
+    fn test_origin_display_and_base_keys() {
+        let env = Metadata::named("Env::Nested").interpolater(|_: &Profile, keys: &[&str]| {
+            format!("APP_{}", keys.join("_").to_uppercase())
+        });
+        let origin = Origin::new(&env, &Profile::Default, &["database", "url"]);
+        assert_eq!(origin.name.as_deref(), Some("APP_DATABASE_URL"));
+        assert_eq!(origin.to_string(), "APP_DATABASE_URL (Env::Nested)");
+
+        let file = Metadata::named("Toml").source(Source::File(PathBuf::from("/etc/app.toml")));
+        let mut origin = Origin::new(&file, &Profile::Default, &["port"]);
+        assert_eq!(origin.name, None);
+        assert_eq!(origin.to_string(), "/etc/app.toml (Toml)");
+        origin.line = Some(2);
+        assert_eq!(origin.to_string(), "/etc/app.toml:2 (Toml)");
+
+        assert_eq!(base_key(&["features_add"]), "features");
+        assert_eq!(base_key(&["a", "b_remove"]), "a.b");
+    }
+}
@@ -0,0 +1,363 @@
+//! Command-line arguments parsed by clap, with the `clap` feature
+//!
+//! The [`Clap`] provider turns [`ArgMatches`] into configuration, so a clap command
+//! can override configuration files without a serializable mirror struct for
+//! `with_cli_opt`:
+//!
+//! - **Keys**: the id of each argument is its key; dots nest it (`database.url`) and
+//!   dashes become underscores (`log-level` → `log_level`). The arguments of a
+//!   subcommand are nested under its name
+//! - **Only given arguments**: values from the command line or an `env` fallback are
+//!   merged, while clap's default values are skipped so they don't override files
+//! - **Types**: values parsed by a `value_parser` for booleans, integers, floats or
+//!   paths keep that type, and string values are detected: `8080` is a number, `true`
+//!   a boolean and `[1, 2]` an array, like in environment variables
+//! - **Multiple values**: arguments given several times or split at their
+//!   `value_delimiter` become arrays
+//! - **Type hints**: [`Clap::with_type`] sets the [`ArgType`] of a key, to keep
+//!   `007` a string or make a single value an array
+//!
+//! ```rust
+//! use clap::{Arg, ArgAction, Command};
+//! use superconfig::SuperConfig;
+//!
+//! let matches = Command::new("app")
+//!     .arg(Arg::new("database.url").long("database-url"))
+//!     .arg(Arg::new("port").long("port").default_value("80"))
+//!     .arg(Arg::new("features").long("features").value_delimiter(','))
+//!     .arg(Arg::new("verbose").short('v').action(ArgAction::SetTrue))
+//!     .get_matches_from(["app", "--database-url", "postgres://cli", "--features", "auth,cache", "-v"]);
+//!
+//! let config = SuperConfig::new()
+//!     .with_defaults_string(r#"{"port": 8080}"#)
+//!     .with_clap(&matches);
+//!
+//! assert_eq!(config.get_string("database.url")?, "postgres://cli");
+//! assert_eq!(config.extract_inner::<u16>("port")?, 8080);
+//! assert_eq!(config.get_array::<String>("features")?, ["auth", "cache"]);
+//! assert!(config.extract_inner::<bool>("verbose")?);
+//! # Ok::<(), figment::Error>(())
+//! ```
+
+use super::Nested;
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use figment::{
+    Error, Metadata, Profile, Provider,
+    value::{Dict, Map, Tag, Value},
+};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Type a [`Clap`] argument's values are converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgType {
+    /// The type clap parsed, with the type of string values detected
+    Auto,
+    /// The text given, unparsed
+    String,
+    /// A signed integer
+    Integer,
+    /// A floating point number
+    Float,
+    /// `true`/`false`, `yes`/`no`, `on`/`off` or `1`/`0`
+    Bool,
+    /// An array of the values, even when a single one is given
+    List,
+}
+
+/// A value of an argument, typed by clap's value parser or as given
+#[derive(Debug, Clone)]
+enum ArgValue {
+    Typed(Value),
+    Text(String),
+}
+
+/// The given values of one argument
+#[derive(Debug, Clone)]
+struct ArgValues {
+    path: Vec<String>,
+    values: Vec<ArgValue>,
+}
+
+/// Provider for arguments parsed by clap
+///
+/// See the [module documentation](self) for how arguments map to keys.
+#[derive(Debug, Clone)]
+pub struct Clap {
+    args: Vec<ArgValues>,
+    types: BTreeMap<String, ArgType>,
+}
+
+impl Clap {
+    /// Capture the arguments given in `matches`
+    pub fn new(matches: &ArgMatches) -> Self {
+        let mut args = Vec::new();
+        collect(matches, &[], &mut args);
+        Self {
+            args,
+            types: BTreeMap::new(),
+        }
+    }
+
+    /// Convert the values of the argument at dotted `key` to `arg_type`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use clap::{Arg, Command};
+    /// use superconfig::{ArgType, Clap, SuperConfig};
+    ///
+    /// let matches = Command::new("app")
+    ///     .arg(Arg::new("pin").long("pin"))
+    ///     .arg(Arg::new("hosts").long("host"))
+    ///     .get_matches_from(["app", "--pin", "007", "--host", "a"]);
+    ///
+    /// let config = SuperConfig::new().merge(
+    ///     Clap::new(&matches)
+    ///         .with_type("pin", ArgType::String)
+    ///         .with_type("hosts", ArgType::List),
+    /// );
+    /// assert_eq!(config.get_string("pin")?, "007");
+    /// assert_eq!(config.get_array::<String>("hosts")?, ["a"]);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn with_type(mut self, key: &str, arg_type: ArgType) -> Self {
+        self.types.insert(key.to_string(), arg_type);
+        self
+    }
+}
+
+impl Provider for Clap {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Clap")
+            .interpolater(|_: &Profile, keys: &[&str]| format!("argument `{}`", keys.join(".")))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        for arg in &self.args {
+            let key = arg.path.join(".");
+            let arg_type = self.types.get(&key).copied().unwrap_or(ArgType::Auto);
+            let values = arg
+                .values
+                .iter()
+                .map(|value| convert(value, arg_type, &key))
+                .collect::<Result<Vec<_>, _>>()?;
+            let value = match (arg_type, <[Value; 1]>::try_from(values)) {
+                (ArgType::List, Ok(value)) => Value::from(Vec::from(value)),
+                (_, Ok([value])) => value,
+                (_, Err(values)) => Value::from(values),
+            };
+            let path: Vec<&str> = arg.path.iter().map(String::as_str).collect();
+            Nested::insert_nested_value(&mut dict, &path, value)?;
+        }
+        Ok(Map::from([(Profile::Default, dict)]))
+    }
+}
+
+/// Collect the given arguments of `matches` and its subcommand, nested under `prefix`
+fn collect(matches: &ArgMatches, prefix: &[String], args: &mut Vec<ArgValues>) {
+    for id in matches.ids() {
+        let id = id.as_str();
+        if !matches!(
+            matches.value_source(id),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        ) {
+            continue;
+        }
+        let Some(values) = values(matches, id) else {
+            continue;
+        };
+        let path = prefix
+            .iter()
+            .cloned()
+            .chain(id.split('.').map(|key| key.replace('-', "_")))
+            .collect();
+        args.push(ArgValues { path, values });
+    }
+
+    if let Some((name, matches)) = matches.subcommand() {
+        let mut prefix = prefix.to_vec();
+        prefix.push(name.replace('-', "_"));
+        collect(matches, &prefix, args);
+    }
+}
+
+/// The values of argument `id`, typed when clap parsed them to a known type
+fn values(matches: &ArgMatches, id: &str) -> Option<Vec<ArgValue>> {
+    macro_rules! typed {
+        ($($ty:ty),*) => {$(
+            if let Ok(Some(values)) = matches.try_get_many::<$ty>(id) {
+                return Some(values.map(|v| ArgValue::Typed(Value::from(v.clone()))).collect());
+            }
+        )*};
+    }
+    typed!(
+        bool, i64, i32, i16, i8, u64, u32, u16, u8, usize, isize, f64, f32
+    );
+
+    if let Ok(Some(values)) = matches.try_get_many::<PathBuf>(id) {
+        return Some(
+            values
+                .map(|path| ArgValue::Typed(Value::from(path.to_string_lossy().into_owned())))
+                .collect(),
+        );
+    }
+    // Strings and types clap doesn't tell apart are taken as given
+    let raw = matches.try_get_raw(id).ok()??;
+    Some(
+        raw.map(|value| ArgValue::Text(value.to_string_lossy().into_owned()))
+            .collect(),
+    )
+}
+
+/// Convert `value` of the argument at `key` to `arg_type`
+fn convert(value: &ArgValue, arg_type: ArgType, key: &str) -> Result<Value, Error> {
+    let text = match value {
+        ArgValue::Typed(value) if matches!(arg_type, ArgType::Auto | ArgType::List) => {
+            return Ok(value.clone());
+        }
+        ArgValue::Typed(Value::String(_, text)) | ArgValue::Text(text) => text.clone(),
+        ArgValue::Typed(value) => value_text(value),
+    };
+    let invalid = |expected: &str| {
+        Error::from(format!(
+            "argument `{key}`: expected {expected}, found `{text}`"
+        ))
+    };
+    match arg_type {
+        ArgType::Auto | ArgType::List => detect(&text),
+        ArgType::String => Ok(Value::String(Tag::Default, text)),
+        ArgType::Integer => text
+            .trim()
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| invalid("an integer")),
+        ArgType::Float => text
+            .trim()
+            .parse::<f64>()
+            .map(Value::from)
+            .map_err(|_| invalid("a number")),
+        ArgType::Bool => match Nested::parse_env_value(&text)? {
+            value @ Value::Bool(..) => Ok(value),
+            _ => Err(invalid("a boolean")),
+        },
+    }
+}
+
+/// `text` as a boolean, number, JSON array or table, or else a string
+///
+/// Unlike environment variables, `1` and `0` are numbers rather than booleans.
+fn detect(text: &str) -> Result<Value, Error> {
+    let trimmed = text.trim();
+    if let Ok(value) = trimmed.parse::<i64>() {
+        return Ok(Value::from(value));
+    }
+    if let Ok(value) = trimmed.parse::<f64>() {
+        return Ok(Value::from(value));
+    }
+    Nested::parse_env_value(text)
+}
+
+/// The text of a typed scalar value
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Bool(_, value) => value.to_string(),
+        Value::Num(_, num) => num
+            .to_i128()
+            .map(|n| n.to_string())
+            .or_else(|| num.to_f64().map(|n| n.to_string()))
+            .unwrap_or_default(),
+        Value::String(_, text) => text.clone(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command, value_parser};
+
+    fn command() -> Command {
+        Command::new("app")
+            .arg(
+                Arg::new("server.port")
+                    .long("port")
+                    .value_parser(value_parser!(u16)),
+            )
+            .arg(Arg::new("log-level").long("log-level"))
+            .arg(Arg::new("ratio").long("ratio"))
+            .arg(Arg::new("tags").long("tag").action(ArgAction::Append))
+            .arg(Arg::new("verbose").short('v').action(ArgAction::Count))
+            .arg(Arg::new("debug").long("debug").action(ArgAction::SetTrue))
+            .subcommand(Command::new("serve").arg(Arg::new("workers").long("workers")))
+    }
+
+    fn dict(clap: Clap) -> Dict {
+        clap.data().unwrap().remove(&Profile::Default).unwrap()
+    }
+
+    #[test]
+    fn test_keys_and_types() {
+        let matches = command().get_matches_from([
+            "app",
+            "--port",
+            "8080",
+            "--log-level",
+            "debug",
+            "--ratio",
+            "0.5",
+            "--tag",
+            "a",
+            "--tag",
+            "b",
+            "-vv",
+            "serve",
+            "--workers",
+            "4",
+        ]);
+        let dict = dict(Clap::new(&matches));
+
+        let server = dict["server"].as_dict().unwrap();
+        assert_eq!(server["port"].to_u128(), Some(8080));
+        assert_eq!(dict["log_level"].as_str(), Some("debug"));
+        assert_eq!(dict["ratio"].to_f64(), Some(0.5));
+        let tags: Vec<&str> = dict["tags"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert_eq!(tags, ["a", "b"]);
+        assert_eq!(dict["verbose"].to_u128(), Some(2));
+        assert_eq!(
+            dict["serve"].as_dict().unwrap()["workers"].to_i128(),
+            Some(4)
+        );
+        // Defaults clap filled in are left out
+        assert!(!dict.contains_key("debug"));
+    }
+
+    #[test]
+    fn test_type_hints() {
+        let matches = command().get_matches_from(["app", "--log-level", "1", "--tag", "007"]);
+        let dict = dict(
+            Clap::new(&matches)
+                .with_type("log_level", ArgType::String)
+                .with_type("tags", ArgType::List),
+        );
+        assert_eq!(dict["log_level"].as_str(), Some("1"));
+        assert_eq!(dict["tags"].as_array().unwrap().len(), 1);
+
+        let matches = command().get_matches_from(["app", "--ratio", "high"]);
+        let error = Clap::new(&matches)
+            .with_type("ratio", ArgType::Float)
+            .data()
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("argument `ratio`: expected a number, found `high`")
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Duplicate key handling for configuration sources
+//!
+//! Duplicate keys show up in two places, and by default both resolve silently in favour of
+//! the last definition:
+//!
+//! ```text
+//! Within one file:        { "port": 80, "port": 8080 }      → JSON and YAML parsers keep 8080
+//! Same precedence level:  conf.d/a.toml  port = 80          → Wildcard merges files in order,
+//!                         conf.d/b.toml  port = 8080           b.toml wins
+//! ```
+//!
+//! A [`DuplicateKeyPolicy`] makes that choice explicit:
+//!
+//! - [`DuplicateKeyPolicy::LastWins`] - Later definitions override earlier ones (default, no extra work)
+//! - [`DuplicateKeyPolicy::FirstWins`] - The first definition is kept, later ones are ignored
+//! - [`DuplicateKeyPolicy::Error`] - The provider fails and names every duplicated key
+//! - [`DuplicateKeyPolicy::Warn`] - Last wins, and a warning is collected by SuperConfig
+//!
+//! TOML forbids duplicate keys in its specification, so duplicates within a TOML file are
+//! always a parse error regardless of the policy. Across files the policy applies to every format.
+//!
+//! ## Usage Examples
+//!
+//! ```rust,no_run
+//! use superconfig::{DuplicateKeyPolicy, SuperConfig, Universal, Wildcard};
+//!
+//! let config = SuperConfig::new()
+//!     .with_file_duplicate_keys("config.yaml", DuplicateKeyPolicy::Error)
+//!     .merge_validated(
+//!         Wildcard::new("conf.d/*.toml").with_duplicate_keys(DuplicateKeyPolicy::Warn),
+//!     );
+//! ```
+
+use figment::{
+    Error, Metadata, Profile, Provider,
+    value::{Map, Value},
+};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::{cell::RefCell, collections::BTreeMap, fmt, path::PathBuf};
+
+/// What to do when the same key is defined more than once at the same precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    /// Keep the last definition (what the underlying parsers do)
+    #[default]
+    LastWins,
+    /// Keep the first definition
+    FirstWins,
+    /// Refuse to load the source
+    Error,
+    /// Keep the last definition and collect a warning
+    Warn,
+}
+
+/// Parsed content together with the keys that were defined more than once
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Scanned {
+    /// Parsed value, resolved according to the policy used for the scan
+    pub value: serde_json::Value,
+    /// Dotted paths of duplicated keys, in document order
+    pub duplicates: Vec<String>,
+}
+
+/// Parse JSON content, recording duplicated keys
+pub(crate) fn scan_json(content: &str, policy: DuplicateKeyPolicy) -> Result<Scanned, String> {
+    let mut deserializer = serde_json::Deserializer::from_str(content);
+    let scanned = scan(&mut deserializer, policy).map_err(|e| e.to_string())?;
+    deserializer.end().map_err(|e| e.to_string())?;
+    Ok(scanned)
+}
+
+/// Parse YAML content, recording duplicated keys
+pub(crate) fn scan_yaml(content: &str, policy: DuplicateKeyPolicy) -> Result<Scanned, String> {
+    scan(serde_yml::Deserializer::from_str(content), policy).map_err(|e| e.to_string())
+}
+
+fn scan<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    policy: DuplicateKeyPolicy,
+) -> Result<Scanned, D::Error> {
+    let duplicates = RefCell::new(Vec::new());
+    let value = ScanSeed {
+        path: String::new(),
+        policy,
+        duplicates: &duplicates,
+    }
+    .deserialize(deserializer)?;
+
+    Ok(Scanned {
+        value,
+        duplicates: duplicates.into_inner(),
+    })
+}
+
+/// Data loaded by a provider, keyed by the file it came from
+pub(crate) type LoadedSource = (PathBuf, Map<Profile, Map<String, Value>>);
+
+/// Find leaf keys that are defined by more than one of the given sources
+///
+/// Each source is identified by the path it was loaded from. Array merge keys
+/// (`*_add` / `*_remove`) are meant to be repeated across files and are not reported.
+pub(crate) fn conflicts(sources: &[LoadedSource]) -> Vec<String> {
+    let mut first_seen: BTreeMap<String, &PathBuf> = BTreeMap::new();
+    let mut conflicts = Vec::new();
+
+    for (path, data) in sources {
+        let mut keys = Vec::new();
+        for (profile, dict) in data {
+            collect_leaf_keys(&format!("{profile}"), dict, &mut keys);
+        }
+
+        for key in keys {
+            match first_seen.get(&key) {
+                Some(first) => conflicts.push(format!(
+                    "`{}` is defined in both {} and {}",
+                    display_key(&key),
+                    first.display(),
+                    path.display()
+                )),
+                None => {
+                    first_seen.insert(key, path);
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Collect `profile.dotted.key` paths for every non-dictionary value
+fn collect_leaf_keys(prefix: &str, dict: &Map<String, Value>, keys: &mut Vec<String>) {
+    for (key, value) in dict {
+        if key.ends_with("_add") || key.ends_with("_remove") {
+            continue;
+        }
+
+        let path = format!("{prefix}.{key}");
+        match value {
+            Value::Dict(_, nested) => collect_leaf_keys(&path, nested, keys),
+            _ => keys.push(path),
+        }
+    }
+}
+
+/// Strip the profile from a collected key path (`default.server.port` → `server.port`)
+fn display_key(key: &str) -> &str {
+    key.split_once('.').map_or(key, |(_, rest)| rest)
+}
+
+/// Provider that always fails with the given message
+pub(crate) struct Rejected(pub String);
+
+impl Provider for Rejected {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Rejected")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
+        Err(Error::from(self.0.clone()))
+    }
+}
+
+/// Deserialization seed that builds a JSON value and records duplicated keys
+struct ScanSeed<'a> {
+    path: String,
+    policy: DuplicateKeyPolicy,
+    duplicates: &'a RefCell<Vec<String>>,
+}
+
+impl ScanSeed<'_> {
+    fn child(&self, segment: &str) -> Self {
+        let path = if self.path.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{segment}", self.path)
+        };
+
+        ScanSeed {
+            path,
+            policy: self.policy,
+            duplicates: self.duplicates,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ScanSeed<'_> {
+    type Value = serde_json::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ScanSeed<'_> {
+    type Value = serde_json::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a configuration value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(serde_json::Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(serde_json::Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(self.child(&items.len().to_string()))? {
+            items.push(item);
+        }
+        Ok(serde_json::Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut object = serde_json::Map::new();
+        while let Some(MapKey(key)) = map.next_key()? {
+            let value = map.next_value_seed(self.child(&key))?;
+
+            if object.contains_key(&key) {
+                self.duplicates.borrow_mut().push(self.child(&key).path);
+                if self.policy == DuplicateKeyPolicy::FirstWins {
+                    continue;
+                }
+            }
+            object.insert(key, value);
+        }
+        Ok(serde_json::Value::Object(object))
+    }
+}
+
+/// Map key that accepts any scalar, since YAML keys need not be strings
+struct MapKey(String);
+
+impl<'de> de::Deserialize<'de> for MapKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyVisitor;
+
+        impl Visitor<'_> for KeyVisitor {
+            type Value = MapKey;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a scalar map key")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<MapKey, E> {
+                Ok(MapKey(v.to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<MapKey, E> {
+                Ok(MapKey(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<MapKey, E> {
+                Ok(MapKey(v.to_string()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<MapKey, E> {
+                Ok(MapKey(v.to_string()))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<MapKey, E> {
+                Ok(MapKey(v.to_string()))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<MapKey, E> {
+                Ok(MapKey("null".to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(KeyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::{Format, Toml};
+
+    #[test]
+    fn test_json_without_duplicates() {
+        let scanned =
+            scan_json(r#"{"a": 1, "b": {"c": [1, 2]}}"#, DuplicateKeyPolicy::Warn).unwrap();
+        assert!(scanned.duplicates.is_empty());
+        assert_eq!(scanned.value["b"]["c"][1], 2);
+    }
+
+    #[test]
+    fn test_json_duplicates_first_and_last_wins() {
+        let content = r#"{"port": 80, "db": {"host": "a", "host": "b"}, "port": 8080}"#;
+
+        let last = scan_json(content, DuplicateKeyPolicy::LastWins).unwrap();
+        assert_eq!(last.duplicates, vec!["db.host", "port"]);
+        assert_eq!(last.value["port"], 8080);
+        assert_eq!(last.value["db"]["host"], "b");
+
+        let first = scan_json(content, DuplicateKeyPolicy::FirstWins).unwrap();
+        assert_eq!(first.value["port"], 80);
+        assert_eq!(first.value["db"]["host"], "a");
+    }
+
+    #[test]
+    fn test_yaml_duplicates() {
+        let content = "server:\n  port: 80\n  port: 8080\nitems:\n  - name: x\n    name: y\n";
+        let scanned = scan_yaml(content, DuplicateKeyPolicy::FirstWins).unwrap();
+        assert_eq!(scanned.duplicates, vec!["server.port", "items.0.name"]);
+        assert_eq!(scanned.value["server"]["port"], 80);
+        assert_eq!(scanned.value["items"][0]["name"], "x");
+    }
+
+    #[test]
+    fn test_invalid_content_is_an_error() {
+        assert!(scan_json(r#"{"a": 1} trailing"#, DuplicateKeyPolicy::Warn).is_err());
+        assert!(scan_yaml("a: [1, 2", DuplicateKeyPolicy::Warn).is_err());
+    }
+
+    #[test]
+    fn test_conflicts_across_sources() {
+        let a = Toml::string("port = 80\nlist_add = [1]\n[db]\nhost = \"a\"\n")
+            .data()
+            .unwrap();
+        let b = Toml::string("port = 8080\nlist_add = [2]\n[db]\nname = \"b\"\n")
+            .data()
+            .unwrap();
+
+        let conflicts = conflicts(&[(PathBuf::from("a.toml"), a), (PathBuf::from("b.toml"), b)]);
+        assert_eq!(
+            conflicts,
+            vec!["`port` is defined in both a.toml and b.toml".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_rejected_provider_fails() {
+        let error = Rejected("nope".to_string()).data().unwrap_err();
+        assert!(error.to_string().contains("nope"));
+    }
+}
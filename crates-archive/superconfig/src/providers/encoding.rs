@@ -0,0 +1,180 @@
+//! Encoding normalization for configuration file content
+//!
+//! Configuration files produced by Windows tooling frequently arrive with a byte order mark,
+//! as UTF-16, or with CRLF line endings. Figment's format providers expect plain UTF-8, so
+//! this module converts such content before it reaches a parser:
+//!
+//! ```text
+//! EF BB BF ...          → UTF-8 with BOM    → BOM stripped (warning)
+//! FF FE ...             → UTF-16LE with BOM → decoded to UTF-8 (warning)
+//! FE FF ...             → UTF-16BE with BOM → decoded to UTF-8 (warning)
+//! 7B 00 0A 00 ...       → UTF-16LE, no BOM  → decoded to UTF-8 (warning)
+//! ...\r\n...            → CRLF line endings → normalized to \n
+//! ```
+
+/// Result of normalizing raw configuration bytes
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Normalized {
+    /// UTF-8 content with `\n` line endings
+    pub content: String,
+    /// Description of any encoding conversion that was applied
+    pub warning: Option<String>,
+}
+
+/// Detected source encoding
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SourceEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le { bom: bool },
+    Utf16Be { bom: bool },
+}
+
+/// Convert raw configuration bytes to normalized UTF-8
+///
+/// Returns an error when the bytes are neither valid UTF-8 nor decodable UTF-16.
+pub(crate) fn normalize(bytes: &[u8]) -> Result<Normalized, String> {
+    let encoding = detect(bytes);
+    let (decoded, warning) = match encoding {
+        SourceEncoding::Utf8 => (
+            String::from_utf8(bytes.to_vec()).map_err(|e| format!("invalid UTF-8: {e}"))?,
+            None,
+        ),
+        SourceEncoding::Utf8Bom => (
+            String::from_utf8(bytes[3..].to_vec()).map_err(|e| format!("invalid UTF-8: {e}"))?,
+            Some("stripped UTF-8 byte order mark".to_string()),
+        ),
+        SourceEncoding::Utf16Le { bom } => (
+            decode_utf16(&bytes[if bom { 2 } else { 0 }..], u16::from_le_bytes)?,
+            Some("converted from UTF-16LE to UTF-8".to_string()),
+        ),
+        SourceEncoding::Utf16Be { bom } => (
+            decode_utf16(&bytes[if bom { 2 } else { 0 }..], u16::from_be_bytes)?,
+            Some("converted from UTF-16BE to UTF-8".to_string()),
+        ),
+    };
+
+    let content = if decoded.contains('\r') {
+        decoded.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        decoded
+    };
+
+    Ok(Normalized { content, warning })
+}
+
+/// Check whether content starting with `prefix` must be converted before parsing
+///
+/// Only BOM and UTF-16 content needs conversion; plain UTF-8 (including CRLF line
+/// endings, which all supported parsers accept) can be handed to a parser directly.
+pub(crate) fn needs_conversion(prefix: &[u8]) -> bool {
+    detect(prefix) != SourceEncoding::Utf8
+}
+
+/// Strip a leading byte order mark from already decoded content
+pub(crate) fn strip_bom(content: &str) -> &str {
+    content.strip_prefix('\u{feff}').unwrap_or(content)
+}
+
+/// Detect the encoding from the byte order mark, falling back to a NUL-byte heuristic
+/// for BOM-less UTF-16 (configuration files start with ASCII, so every other byte is zero)
+fn detect(bytes: &[u8]) -> SourceEncoding {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, ..] => SourceEncoding::Utf8Bom,
+        [0xFF, 0xFE, ..] => SourceEncoding::Utf16Le { bom: true },
+        [0xFE, 0xFF, ..] => SourceEncoding::Utf16Be { bom: true },
+        [a, 0, b, 0, ..] if *a != 0 && *b != 0 => SourceEncoding::Utf16Le { bom: false },
+        [0, a, 0, b, ..] if *a != 0 && *b != 0 => SourceEncoding::Utf16Be { bom: false },
+        _ => SourceEncoding::Utf8,
+    }
+}
+
+/// Decode UTF-16 code units using the given byte order
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err("invalid UTF-16: odd number of bytes".to_string());
+    }
+
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|e| format!("invalid UTF-16: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFF, 0xFE] } else { vec![] };
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    fn utf16be(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFE, 0xFF];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        bytes
+    }
+
+    #[test]
+    fn test_plain_utf8_is_unchanged() {
+        let result = normalize(b"host = \"localhost\"\n").unwrap();
+        assert_eq!(result.content, "host = \"localhost\"\n");
+        assert!(result.warning.is_none());
+    }
+
+    #[test]
+    fn test_utf8_bom_is_stripped() {
+        let result = normalize(b"\xEF\xBB\xBF{\"a\": 1}").unwrap();
+        assert_eq!(result.content, "{\"a\": 1}");
+        assert!(result.warning.unwrap().contains("byte order mark"));
+    }
+
+    #[test]
+    fn test_utf16le_with_and_without_bom() {
+        for bom in [true, false] {
+            let result = normalize(&utf16le("{\"name\": \"café\"}\r\n", bom)).unwrap();
+            assert_eq!(result.content, "{\"name\": \"café\"}\n");
+            assert!(result.warning.unwrap().contains("UTF-16LE"));
+        }
+    }
+
+    #[test]
+    fn test_utf16be_with_bom() {
+        let result = normalize(&utf16be("key: value")).unwrap();
+        assert_eq!(result.content, "key: value");
+        assert!(result.warning.unwrap().contains("UTF-16BE"));
+    }
+
+    #[test]
+    fn test_crlf_is_normalized_without_warning() {
+        let result = normalize(b"[server]\r\nport = 80\rhost = \"x\"\r\n").unwrap();
+        assert_eq!(result.content, "[server]\nport = 80\nhost = \"x\"\n");
+        assert!(result.warning.is_none());
+    }
+
+    #[test]
+    fn test_invalid_content_is_an_error() {
+        assert!(normalize(&[0xC3, 0x28]).is_err());
+        assert!(normalize(&[0xFF, 0xFE, 0x41]).is_err());
+        assert!(normalize(&[0xFF, 0xFE, 0x00, 0xD8]).is_err());
+    }
+
+    #[test]
+    fn test_needs_conversion() {
+        assert!(needs_conversion(b"\xEF\xBB\xBF{"));
+        assert!(needs_conversion(&utf16le("{}", false)));
+        assert!(!needs_conversion(b"{\r\n"));
+        assert!(!needs_conversion(b""));
+    }
+
+    #[test]
+    fn test_strip_bom() {
+        assert_eq!(strip_bom("\u{feff}a = 1"), "a = 1");
+        assert_eq!(strip_bom("a = 1"), "a = 1");
+    }
+}
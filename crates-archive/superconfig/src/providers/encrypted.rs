@@ -0,0 +1,527 @@
+//! Encrypted configuration provider
+//!
+//! The Encrypted provider loads configuration files that are encrypted with AES-256-GCM,
+//! decrypting them at load time with a key taken from an environment variable, a keyfile,
+//! or the application itself. Two kinds of encryption are supported, and may be combined:
+//!
+//! ```text
+//! config.toml.enc                              → whole file encrypted, parsed as TOML
+//! password = "ENC[AES256_GCM,data:...,iv:...,tag:...,type:str]"
+//!                                              → single value encrypted, SOPS-style
+//! ```
+//!
+//! Whole files are the 12-byte nonce followed by the ciphertext and its tag. The `.enc`
+//! extension is stripped to find the format, which is otherwise detected like
+//! [`Universal`] does. Encrypted values keep the rest of the file readable and diffable;
+//! their `type` (`str`, `int`, `float` or `bool`) restores the original value type.
+//! Both are produced with [`EncryptionKey::encrypt`] and [`EncryptionKey::encrypt_value`].
+//!
+//! Keys are 32 bytes, written as base64 or 64 hex digits. The key is only resolved when
+//! the file is encrypted or holds encrypted values, so plain files load without one.
+//!
+//! ## Usage Examples
+//!
+//! ```rust,no_run
+//! use superconfig::{Encrypted, KeySource, SuperConfig};
+//!
+//! // Key from SUPERCONFIG_KEY
+//! let config = SuperConfig::new().with_encrypted_file("config.toml.enc", KeySource::default());
+//!
+//! // Key from a keyfile
+//! let config = SuperConfig::new()
+//!     .merge(Encrypted::file("secrets.yaml").key(KeySource::File("/run/keys/config".into())));
+//! ```
+
+use super::{encoding, format::Universal};
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use figment::{
+    Error, Metadata, Profile, Provider, Source,
+    value::{Map, Value},
+};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the key when no other [`KeySource`] is given
+pub const DEFAULT_KEY_ENV: &str = "SUPERCONFIG_KEY";
+
+/// Extension marking a wholly encrypted file (`config.toml.enc`)
+const FILE_EXTENSION: &str = "enc";
+
+const VALUE_PREFIX: &str = "ENC[AES256_GCM,";
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// An AES-256-GCM key, wiped from memory when dropped
+#[derive(Clone)]
+pub struct EncryptionKey(Zeroizing<[u8; 32]>);
+
+impl EncryptionKey {
+    /// Create a key from its raw bytes
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+
+    /// Generate a random key
+    pub fn generate() -> Self {
+        Self::from_bytes(Aes256Gcm::generate_key(OsRng).into())
+    }
+
+    /// Parse a key written as base64 or 64 hex digits
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let bytes = if text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+            (0..64)
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&text[i..i + 2], 16))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?
+        } else {
+            STANDARD
+                .decode(text)
+                .map_err(|_| "key is neither base64 nor hex".to_string())?
+        };
+        let bytes = Zeroizing::new(bytes);
+        <[u8; 32]>::try_from(bytes.as_slice())
+            .map(Self::from_bytes)
+            .map_err(|_| format!("key must be 32 bytes, got {}", bytes.len()))
+    }
+
+    /// The key as base64, for storing it in an environment variable or keyfile
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(*self.0)
+    }
+
+    /// Encrypt the content of a whole file
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut data = nonce.to_vec();
+        data.extend(self.seal(&nonce, plaintext));
+        data
+    }
+
+    /// Decrypt the content of a file encrypted with [`encrypt`](Self::encrypt)
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        if data.len() < NONCE_LEN + TAG_LEN {
+            return Err("encrypted data is truncated".to_string());
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        self.open(nonce, ciphertext)
+    }
+
+    /// Encrypt a string, number or boolean as an `ENC[AES256_GCM,...]` value
+    pub fn encrypt_value(&self, value: &serde_json::Value) -> Result<String, String> {
+        let (plaintext, kind) = match value {
+            serde_json::Value::String(text) => (text.clone(), "str"),
+            serde_json::Value::Bool(flag) => (flag.to_string(), "bool"),
+            serde_json::Value::Number(number) if number.is_f64() => (number.to_string(), "float"),
+            serde_json::Value::Number(number) => (number.to_string(), "int"),
+            _ => return Err("only strings, numbers and booleans can be encrypted".to_string()),
+        };
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = self.seal(&nonce, plaintext.as_bytes());
+        let (data, tag) = sealed.split_at(sealed.len() - TAG_LEN);
+        Ok(format!(
+            "{VALUE_PREFIX}data:{},iv:{},tag:{},type:{kind}]",
+            STANDARD.encode(data),
+            STANDARD.encode(nonce),
+            STANDARD.encode(tag)
+        ))
+    }
+
+    /// Decrypt an `ENC[AES256_GCM,...]` value into the value it was encrypted from
+    pub fn decrypt_value(&self, text: &str) -> Result<serde_json::Value, String> {
+        let fields = text
+            .strip_prefix(VALUE_PREFIX)
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or("not an ENC[AES256_GCM,...] value")?;
+        let field = |name: &str| {
+            fields
+                .split(',')
+                .find_map(|field| field.strip_prefix(name)?.strip_prefix(':'))
+                .ok_or_else(|| format!("encrypted value has no `{name}`"))
+        };
+        let decode = |name: &str| {
+            STANDARD
+                .decode(field(name)?)
+                .map_err(|e| format!("invalid `{name}` of encrypted value: {e}"))
+        };
+
+        let mut sealed = decode("data")?;
+        sealed.extend(decode("tag")?);
+        let plaintext = Zeroizing::new(self.open(&decode("iv")?, &sealed)?);
+        let plaintext = std::str::from_utf8(&plaintext)
+            .map_err(|_| "decrypted value is not UTF-8".to_string())?;
+
+        let invalid = |kind: &str| format!("decrypted value is not a valid {kind}");
+        match field("type")? {
+            "str" => Ok(serde_json::Value::from(plaintext)),
+            "int" => plaintext
+                .parse::<i64>()
+                .map(serde_json::Value::from)
+                .or_else(|_| plaintext.parse::<u64>().map(serde_json::Value::from))
+                .map_err(|_| invalid("int")),
+            "float" => plaintext
+                .parse::<f64>()
+                .map(serde_json::Value::from)
+                .map_err(|_| invalid("float")),
+            "bool" => match plaintext.to_ascii_lowercase().as_str() {
+                "true" => Ok(serde_json::Value::Bool(true)),
+                "false" => Ok(serde_json::Value::Bool(false)),
+                _ => Err(invalid("bool")),
+            },
+            kind => Err(format!("unknown encrypted value type `{kind}`")),
+        }
+    }
+
+    fn seal(&self, nonce: &Nonce<<Aes256Gcm as AeadCore>::NonceSize>, plaintext: &[u8]) -> Vec<u8> {
+        self.cipher()
+            .encrypt(nonce, plaintext)
+            .expect("AES-GCM encryption of in-memory data cannot fail")
+    }
+
+    fn open(&self, nonce: &[u8], sealed: &[u8]) -> Result<Vec<u8>, String> {
+        if nonce.len() != NONCE_LEN {
+            return Err(format!(
+                "nonce must be {NONCE_LEN} bytes, got {}",
+                nonce.len()
+            ));
+        }
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| "decryption failed, wrong key or corrupted data".to_string())
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&*self.0))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptionKey({})", crate::secret::REDACTED)
+    }
+}
+
+/// Where the decryption key comes from
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    /// Read the key from an environment variable
+    Env(String),
+    /// Read the key from a file, such as a mounted secret
+    File(PathBuf),
+    /// Use a key held by the application
+    Key(EncryptionKey),
+}
+
+impl Default for KeySource {
+    fn default() -> Self {
+        Self::Env(DEFAULT_KEY_ENV.to_string())
+    }
+}
+
+impl KeySource {
+    /// Read and parse the key
+    pub fn resolve(&self) -> Result<EncryptionKey, String> {
+        match self {
+            Self::Env(var) => {
+                let text = Zeroizing::new(
+                    std::env::var(var)
+                        .map_err(|_| format!("decryption key variable {var} is not set"))?,
+                );
+                EncryptionKey::parse(&text).map_err(|e| format!("invalid key in {var}: {e}"))
+            }
+            Self::File(path) => {
+                let text = Zeroizing::new(
+                    fs::read_to_string(path)
+                        .map_err(|e| format!("failed to read keyfile {}: {e}", path.display()))?,
+                );
+                EncryptionKey::parse(&text)
+                    .map_err(|e| format!("invalid key in {}: {e}", path.display()))
+            }
+            Self::Key(key) => Ok(key.clone()),
+        }
+    }
+}
+
+/// Configuration provider that decrypts files and values at load time
+///
+/// Parsing is delegated to [`Universal`], so format detection works exactly as it does
+/// for [`SuperConfig::with_file`](crate::SuperConfig::with_file).
+#[derive(Debug, Clone)]
+pub struct Encrypted {
+    path: PathBuf,
+    key: KeySource,
+}
+
+impl Encrypted {
+    /// Create an encrypted provider for the given file
+    ///
+    /// Defaults to the key in the [`DEFAULT_KEY_ENV`] environment variable.
+    pub fn file<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            key: KeySource::default(),
+        }
+    }
+
+    /// Set where the decryption key comes from
+    pub fn key(mut self, key: KeySource) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Get the file path being decrypted
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the file, decrypting it if it has the `.enc` extension
+    fn content(&self) -> Result<String, String> {
+        let path = self.path.display();
+        let bytes = fs::read(&self.path)
+            .map_err(|e| format!("Cannot decrypt {path}: failed to read file: {e}"))?;
+        let bytes = if self.is_encrypted_file() {
+            let key = self
+                .key
+                .resolve()
+                .map_err(|e| format!("Cannot decrypt {path}: {e}"))?;
+            Zeroizing::new(
+                key.decrypt(&bytes)
+                    .map_err(|e| format!("Cannot decrypt {path}: {e}"))?,
+            )
+        } else {
+            Zeroizing::new(bytes)
+        };
+        encoding::normalize(&bytes)
+            .map(|normalized| normalized.content)
+            .map_err(|e| format!("Decrypted file {path} could not be decoded: {e}"))
+    }
+
+    fn is_encrypted_file(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|ext| ext == FILE_EXTENSION)
+    }
+
+    /// The path whose extension names the format (`config.toml.enc` → `config.toml`)
+    fn format_path(&self) -> PathBuf {
+        if self.is_encrypted_file() {
+            self.path.with_extension("")
+        } else {
+            self.path.clone()
+        }
+    }
+}
+
+impl Provider for Encrypted {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Encrypted").source(Source::File(self.path.clone()))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
+        let content = Zeroizing::new(self.content().map_err(Error::from)?);
+        let mut data = Universal::from_content(&self.format_path(), &content).data()?;
+
+        let has_encrypted_values = data
+            .values()
+            .flat_map(|dict| dict.values())
+            .any(contains_encrypted);
+        if !has_encrypted_values {
+            return Ok(data);
+        }
+
+        let path = self.path.display();
+        let key = self
+            .key
+            .resolve()
+            .map_err(|e| Error::from(format!("Cannot decrypt values of {path}: {e}")))?;
+        for dict in data.values_mut() {
+            for (name, value) in dict.iter_mut() {
+                decrypt_values(value, &key, name)
+                    .map_err(|e| Error::from(format!("Cannot decrypt values of {path}: {e}")))?;
+            }
+        }
+        Ok(data)
+    }
+}
+
+fn is_encrypted_value(text: &str) -> bool {
+    text.starts_with(VALUE_PREFIX)
+}
+
+fn contains_encrypted(value: &Value) -> bool {
+    match value {
+        Value::String(_, text) => is_encrypted_value(text),
+        Value::Dict(_, dict) => dict.values().any(contains_encrypted),
+        Value::Array(_, items) => items.iter().any(contains_encrypted),
+        _ => false,
+    }
+}
+
+/// Replace every encrypted value under `value`, whose key path is `path`
+fn decrypt_values(value: &mut Value, key: &EncryptionKey, path: &str) -> Result<(), String> {
+    match value {
+        Value::String(_, text) if is_encrypted_value(text) => {
+            let decrypted = key
+                .decrypt_value(text)
+                .map_err(|e| format!("`{path}`: {e}"))?;
+            *value = Value::serialize(decrypted).map_err(|e| format!("`{path}`: {e}"))?;
+        }
+        Value::Dict(_, dict) => {
+            for (name, value) in dict.iter_mut() {
+                decrypt_values(value, key, &format!("{path}.{name}"))?;
+            }
+        }
+        Value::Array(_, items) => {
+            for (index, value) in items.iter_mut().enumerate() {
+                decrypt_values(value, key, &format!("{path}.{index}"))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Figment;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const CONTENT: &str = "[server]\nhost = \"localhost\"\nport = 8080\n";
+
+    #[test]
+    fn test_key_parsing() {
+        let key = EncryptionKey::generate();
+        let parsed = EncryptionKey::parse(&format!(" {}\n", key.to_base64())).unwrap();
+        assert_eq!(parsed.to_base64(), key.to_base64());
+
+        let hex = "00".repeat(31) + "ff";
+        let parsed = EncryptionKey::parse(&hex).unwrap();
+        assert_eq!(parsed.0[31], 0xff);
+
+        assert!(
+            EncryptionKey::parse("c2hvcnQ=")
+                .unwrap_err()
+                .contains("32 bytes")
+        );
+        assert!(EncryptionKey::parse("not a key!").is_err());
+        assert_eq!(format!("{key:?}"), "EncryptionKey(***MASKED***)");
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let key = EncryptionKey::generate();
+        let data = key.encrypt(b"secret");
+        assert_eq!(key.decrypt(&data).unwrap(), b"secret");
+
+        let error = EncryptionKey::generate().decrypt(&data).unwrap_err();
+        assert!(error.contains("wrong key"));
+        assert!(key.decrypt(&data[..20]).unwrap_err().contains("truncated"));
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let key = EncryptionKey::generate();
+        for value in [
+            json!("hunter2"),
+            json!(-5),
+            json!(u64::MAX),
+            json!(1.5),
+            json!(true),
+        ] {
+            let encrypted = key.encrypt_value(&value).unwrap();
+            assert!(encrypted.starts_with("ENC[AES256_GCM,data:"));
+            assert_eq!(key.decrypt_value(&encrypted).unwrap(), value);
+        }
+        assert!(key.encrypt_value(&json!([1])).is_err());
+        assert!(key.decrypt_value("ENC[AES256_GCM,data:AA==]").is_err());
+    }
+
+    #[test]
+    fn test_encrypted_file_uses_inner_extension() {
+        let dir = TempDir::new().unwrap();
+        let key = EncryptionKey::generate();
+        let path = dir.path().join("config.toml.enc");
+        fs::write(&path, key.encrypt(CONTENT.as_bytes())).unwrap();
+
+        let figment = Figment::from(Encrypted::file(&path).key(KeySource::Key(key)));
+        assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+
+        let error =
+            Figment::from(Encrypted::file(&path).key(KeySource::Key(EncryptionKey::generate())))
+                .extract_inner::<u16>("server.port")
+                .unwrap_err();
+        assert!(error.to_string().contains("wrong key"));
+    }
+
+    #[test]
+    fn test_encrypted_values_are_decrypted_with_keyfile() {
+        let dir = TempDir::new().unwrap();
+        let key = EncryptionKey::generate();
+        let keyfile = dir.path().join("key");
+        fs::write(&keyfile, key.to_base64()).unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(
+            &path,
+            format!(
+                "database:\n  user: app\n  password: \"{}\"\n  ports: [\"{}\"]\n",
+                key.encrypt_value(&json!("hunter2")).unwrap(),
+                key.encrypt_value(&json!(5432)).unwrap()
+            ),
+        )
+        .unwrap();
+
+        let figment = Figment::from(Encrypted::file(&path).key(KeySource::File(keyfile)));
+        assert_eq!(
+            figment
+                .extract_inner::<String>("database.password")
+                .unwrap(),
+            "hunter2"
+        );
+        assert_eq!(
+            figment.extract_inner::<Vec<u16>>("database.ports").unwrap(),
+            [5432]
+        );
+    }
+
+    #[test]
+    fn test_plain_file_needs_no_key() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, CONTENT).unwrap();
+
+        let provider = Encrypted::file(&path).key(KeySource::Env("SUPERCONFIG_TEST_NO_KEY".into()));
+        assert_eq!(
+            Figment::from(provider)
+                .extract_inner::<String>("server.host")
+                .unwrap(),
+            "localhost"
+        );
+    }
+
+    #[test]
+    fn test_missing_key_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.json.enc");
+        fs::write(&path, EncryptionKey::generate().encrypt(b"{}")).unwrap();
+
+        let error = Encrypted::file(&path)
+            .key(KeySource::Env("SUPERCONFIG_TEST_NO_KEY".into()))
+            .data()
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("SUPERCONFIG_TEST_NO_KEY is not set")
+        );
+    }
+}
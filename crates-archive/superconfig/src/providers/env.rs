@@ -53,7 +53,13 @@ impl Nested {
         // Cache environment variables during construction for performance
         let env_vars = Self::collect_env_vars(&prefix);
 
-        let metadata = Metadata::named("Env::Nested");
+        // Name keys after the variable that sets them
+        let metadata = Metadata::named("Env::Nested").interpolater({
+            let (prefix, separator) = (prefix.clone(), separator.clone());
+            move |_: &Profile, keys: &[&str]| {
+                format!("{prefix}{}", keys.join(&separator).to_uppercase())
+            }
+        });
 
         Self {
             prefix,
@@ -70,7 +76,7 @@ impl Nested {
 
     /// Set custom metadata name for this provider
     pub fn named<S: AsRef<str>>(mut self, name: S) -> Self {
-        self.metadata = Metadata::named(name.as_ref().to_string());
+        self.metadata.name = name.as_ref().to_string().into();
         self
     }
 
@@ -90,7 +96,7 @@ impl Nested {
     /// - Booleans: true, false, yes, no, 1, 0, on, off
     /// - Numbers: integers and floats
     /// - Strings: fallback for everything else
-    fn parse_env_value(value: &str) -> Result<Value, Error> {
+    pub(crate) fn parse_env_value(value: &str) -> Result<Value, Error> {
         let trimmed = value.trim();
 
         // Try parsing as JSON first (arrays and objects)
@@ -157,7 +163,7 @@ impl Nested {
     ///
     /// Creates intermediate dictionaries as needed and handles conflicts
     /// by preferring the new value over existing ones.
-    fn insert_nested_value(
+    pub(crate) fn insert_nested_value(
         dict: &mut Dict,
         path_parts: &[&str],
         value: Value,
@@ -1,7 +1,7 @@
 //! Universal format detection provider with performance optimizations
 //!
-//! The Universal provider automatically detects configuration file formats (JSON, TOML, YAML)
-//! from content with intelligent caching and extension fallback for optimal performance.
+//! The Universal provider automatically detects configuration file formats (JSON, JSON5, TOML,
+//! YAML, INI and dotenv) from content with intelligent caching and extension fallback for optimal performance.
 //!
 //! ## Detection Strategy & Scenarios
 //!
@@ -9,10 +9,13 @@
 //!
 //! ### Scenario 1: Standard Files with Known Extensions (Fast Path)
 //! ```text
-//! config.json    → JSON parser (extension-based)
+//! config.json    → JSON parser (extension-based, JSON5 if strict JSON rejects it)
+//! config.json5   → JSON5 parser (extension-based, `json5` feature)
 //! config.toml    → TOML parser (extension-based)
 //! config.yaml    → YAML parser (extension-based)
 //! config.yml     → YAML parser (extension-based)
+//! config.ini     → INI parser (extension-based)
+//! .env           → Dotenv parser (file name, also .env.local, .env.production, ...)
 //! ```
 //! **Performance**: Fastest path, no content reading required.
 //!
@@ -47,6 +50,8 @@
 //! 2. config.yaml    (if exists → YAML parser)
 //! 3. config.yml     (if exists → YAML parser)
 //! 4. config.json    (if exists → JSON parser)
+//! 5. config.ini     (if exists → INI parser)
+//! 6. config.json5   (if exists → JSON5 parser)
 //! ```
 //! **Convenience**: Automatic discovery of common configuration files.
 //!
@@ -64,6 +69,22 @@
 //!
 //! Content-based format detection uses these patterns (in order of specificity):
 //!
+//! ### JSON5 Detection (Checked First, `json5` Feature)
+//! ```text
+//! // comments and trailing commas, which strict JSON rejects
+//! { host: 'localhost', ports: [80, 443,], }
+//! ```
+//! Content that strict JSON parses is still JSON; see the [`json5`](super::json5) module.
+//!
+//! ### Dotenv and INI Detection (Checked Before TOML)
+//! ```text
+//! export API_KEY=abc  # Upper case KEY=value lines → dotenv
+//! ; comment           # `;` comments or bare values TOML rejects → INI
+//! [section]
+//! host = localhost
+//! ```
+//! See the [`ini`](super::ini) module for how both formats nest their keys.
+//!
 //! ### TOML Detection (Checked First - Most Specific)
 //! ```toml
 //! [section]           # Section headers
@@ -77,6 +98,14 @@
 //! nested:
 //!   subkey: value
 //! ```
+//! YAML files may share values through anchors and merge keys:
+//! ```yaml
+//! defaults: &defaults
+//!   timeout: 30
+//! production:
+//!   <<: *defaults     # → production.timeout = 30
+//!   host: prod.local
+//! ```
 //!
 //! ### JSON Detection (Checked Last - Most Permissive)
 //! ```json
@@ -84,6 +113,23 @@
 //! ["item1", "item2"]  # Array wrapping (with additional validation)
 //! ```
 //!
+//! ### Scenario 6: Windows Encodings
+//! ```text
+//! config.json saved as UTF-16LE (with or without BOM) → decoded to UTF-8 → JSON parser
+//! config.toml with a UTF-8 BOM                        → BOM stripped     → TOML parser
+//! ```
+//! **Tolerance**: Converted content has CRLF line endings normalized, and a warning is
+//! recorded (see [`Universal::warning`]) instead of the file failing to parse.
+//!
+//! ### Scenario 7: Includes
+//! ```text
+//! config.toml containing:
+//! include = ["base.toml", "region/${REGION}.toml"]
+//! → base.toml, then region/eu.toml, then config.toml merged in that order
+//! ```
+//! **Composition**: See the [`include`](super::include) module for path resolution and
+//! cycle detection.
+//!
 //! ## Performance Optimizations
 //!
 //! 1. **Extension-First Detection**: Avoids file I/O when extension is known
@@ -106,19 +152,30 @@
 //! let provider = Universal::string("[section]\nkey=val"); // → TOML
 //! ```
 
+use super::{
+    duplicates::{self, DuplicateKeyPolicy, Rejected},
+    encoding, include,
+    ini::{self, Dotenv, Ini},
+    json5::{self, Json5},
+};
+use crate::merge::ValidatedProvider;
 use figment::{
     Error, Metadata, Profile, Provider,
-    providers::Format,
+    providers::{Format, Yaml, YamlExtended},
     value::{Map, Value},
 };
 use std::{
     collections::HashMap,
     fs,
+    io::Read,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::UNIX_EPOCH,
 };
 
+/// Extensions searched, in priority order, for a base filename without one
+const EXTENSIONS: [&str; 6] = ["toml", "yaml", "yml", "json", "ini", "json5"];
+
 /// Cache entry for format detection results
 #[derive(Debug, Clone)]
 struct FormatCacheEntry {
@@ -137,13 +194,58 @@ lazy_static::lazy_static! {
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum ConfigFormat {
     Json,
+    Json5,
     Toml,
     Yaml,
+    Ini,
+    Dotenv,
+}
+
+/// YAML with merge keys (`<<: *anchor`) applied
+///
+/// Documents without a merge key are parsed by plain [`Yaml`], which keeps the last of
+/// repeated keys where the merging parser would reject them.
+struct YamlMerge;
+
+impl Format for YamlMerge {
+    type Error = <Yaml as Format>::Error;
+
+    const NAME: &'static str = Yaml::NAME;
+
+    fn from_str<T: serde::de::DeserializeOwned>(string: &str) -> Result<T, Self::Error> {
+        if string.contains("<<") {
+            YamlExtended::from_str(string)
+        } else {
+            Yaml::from_str(string)
+        }
+    }
+}
+
+/// An existing file no format could read, such as one without read permission
+struct Unreadable(PathBuf);
+
+impl Provider for Unreadable {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("unreadable file").source(self.0.as_path())
+    }
+
+    fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
+        let reason = match fs::read(&self.0) {
+            Err(error) => error.to_string(),
+            Ok(_) => "not in a supported configuration format".to_string(),
+        };
+        Err(Error::from(format!(
+            "cannot read configuration file: {reason}"
+        )))
+    }
 }
 
 /// Universal configuration provider with automatic format detection and caching
 pub struct Universal {
     provider: Box<dyn Provider>,
+    warning: Option<String>,
+    /// File the configuration was read from, against which includes are resolved
+    path: Option<PathBuf>,
 }
 
 impl Universal {
@@ -153,31 +255,33 @@ impl Universal {
     /// 1. If file exists: Extension-based detection (fast path)
     /// 2. If extension fails: Content-based detection with caching
     /// 3. If content detection fails: Try parsing with each format until one works
-    /// 4. If file doesn't exist: Try multiple extensions (.toml, .yaml, .yml, .json)
+    /// 4. If file doesn't exist: Try multiple extensions (.toml, .yaml, .yml, .json, .ini, .json5)
     /// 5. Final fallback: Empty provider
+    ///
+    /// Files with a byte order mark or UTF-16 encoding are converted to UTF-8 (with
+    /// CRLF line endings normalized) before parsing, and a warning is recorded that
+    /// SuperConfig collects when the provider is merged.
     pub fn file<P: AsRef<Path>>(path: P) -> Self {
         let path = path.as_ref();
 
-        // First try: exact file with extension-based detection
-        if path.exists() {
-            if let Some(provider) = Self::try_extension_detection(path) {
-                return Self { provider };
-            }
-
-            // Second try: content-based detection with caching
-            if let Some(provider) = Self::try_cached_content_detection(path) {
-                return Self { provider };
-            }
-
-            // Third try: brute force - try parsing with each format
-            if let Some(provider) = Self::try_all_formats(path) {
-                return Self { provider };
-            }
+        if path.exists()
+            && let Some(universal) = Self::try_existing_file(path)
+        {
+            return universal;
         }
 
         // Fourth try: multiple extensions for base path (if file doesn't exist)
-        if let Some(provider) = Self::try_multiple_extensions(path) {
-            return Self { provider };
+        if let Some(universal) = Self::try_multiple_extensions(path) {
+            return universal;
+        }
+
+        // A file that exists but couldn't be loaded fails instead of loading nothing
+        if path.is_file() {
+            return Self {
+                provider: Box::new(Unreadable(path.to_path_buf())),
+                warning: None,
+                path: None,
+            };
         }
 
         // Final fallback: empty provider
@@ -186,33 +290,246 @@ impl Universal {
 
     /// Create a Universal provider from string content with format detection
     pub fn string<S: AsRef<str>>(content: S) -> Self {
-        let content = content.as_ref();
+        let content = encoding::strip_bom(content.as_ref());
         let format = Self::detect_format_from_content(content);
 
-        let provider: Box<dyn Provider> = match format {
+        Self {
+            provider: Self::string_provider(content, format),
+            warning: None,
+            path: None,
+        }
+    }
+
+    /// Create a Universal provider from content that was already read from `path`
+    ///
+    /// The file extension is used when it is known, otherwise the format is detected
+    /// from the content. Used by providers that need to inspect the raw bytes before
+    /// parsing (for example [`Verified`](super::Verified)).
+    pub(crate) fn from_content(path: &Path, content: &str) -> Self {
+        Self {
+            provider: Self::string_provider(content, Self::format_for(path, content)),
+            warning: None,
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    /// Create a Universal provider from a file path with an explicit duplicate key policy
+    ///
+    /// JSON and YAML parsers silently keep the last definition of a repeated key; this
+    /// constructor scans the file first and applies `policy` instead. TOML rejects duplicate
+    /// keys on its own, so TOML files behave exactly like [`Universal::file`].
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use superconfig::{DuplicateKeyPolicy, Universal};
+    ///
+    /// let provider = Universal::file_with_duplicate_keys("config.yaml", DuplicateKeyPolicy::Warn);
+    /// if let Some(warning) = provider.warning() {
+    ///     eprintln!("{warning}");
+    /// }
+    /// ```
+    pub fn file_with_duplicate_keys<P: AsRef<Path>>(path: P, policy: DuplicateKeyPolicy) -> Self {
+        let universal = Self::file(path.as_ref());
+        if policy == DuplicateKeyPolicy::LastWins {
+            return universal;
+        }
+
+        let Some(path) = Self::resolve_existing(path.as_ref()) else {
+            return universal;
+        };
+        let Ok(normalized) = fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| encoding::normalize(&bytes))
+        else {
+            return universal;
+        };
+
+        let scanned = match Self::format_for(&path, &normalized.content) {
+            ConfigFormat::Json => duplicates::scan_json(&normalized.content, policy),
+            ConfigFormat::Yaml => duplicates::scan_yaml(&normalized.content, policy),
+            ConfigFormat::Json5 | ConfigFormat::Toml | ConfigFormat::Ini | ConfigFormat::Dotenv => {
+                return universal;
+            }
+        };
+
+        // Parse errors are left for the regular provider to report
+        let Ok(scanned) = scanned else {
+            return universal;
+        };
+        if scanned.duplicates.is_empty() {
+            return universal;
+        }
+
+        let message = format!(
+            "{}: duplicate keys: {}",
+            path.display(),
+            scanned.duplicates.join(", ")
+        );
+        match policy {
+            DuplicateKeyPolicy::LastWins => universal,
+            DuplicateKeyPolicy::FirstWins => Self {
+                provider: Box::new(figment::providers::Serialized::defaults(scanned.value)),
+                ..universal
+            },
+            DuplicateKeyPolicy::Error => Self {
+                provider: Box::new(Rejected(message)),
+                warning: None,
+                path: None,
+            },
+            DuplicateKeyPolicy::Warn => Self {
+                warning: Some(match universal.warning {
+                    Some(warning) => format!("{warning}; {message}"),
+                    None => message,
+                }),
+                ..universal
+            },
+        }
+    }
+
+    /// Choose the format from the file extension, falling back to content detection
+    fn format_for(path: &Path, content: &str) -> ConfigFormat {
+        if Self::is_dotenv_file(path) {
+            return ConfigFormat::Dotenv;
+        }
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+
+        match extension.as_deref() {
+            Some("json") if json5::looks_like_json5(content) => ConfigFormat::Json5,
+            Some("json") => ConfigFormat::Json,
+            Some("json5") => ConfigFormat::Json5,
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml" | "yml") => ConfigFormat::Yaml,
+            Some("ini") => ConfigFormat::Ini,
+            Some("env") => ConfigFormat::Dotenv,
+            _ => Self::detect_format_from_content(content),
+        }
+    }
+
+    /// Resolve the file [`Universal::file`] would load, including the extension search
+    fn resolve_existing(path: &Path) -> Option<PathBuf> {
+        if path.exists() {
+            return Some(path.to_path_buf());
+        }
+
+        EXTENSIONS
+            .iter()
+            .map(|ext| path.with_extension(ext))
+            .find(|candidate| candidate.exists())
+    }
+
+    /// Create a string-backed provider for a known format
+    fn string_provider(content: &str, format: ConfigFormat) -> Box<dyn Provider> {
+        match format {
             ConfigFormat::Json => Box::new(figment::providers::Json::string(content)),
+            ConfigFormat::Json5 => Box::new(Json5::string(content)),
             ConfigFormat::Toml => Box::new(figment::providers::Toml::string(content)),
-            ConfigFormat::Yaml => Box::new(figment::providers::Yaml::string(content)),
-        };
+            ConfigFormat::Yaml => Box::new(YamlMerge::string(content)),
+            ConfigFormat::Ini => Box::new(Ini::string(content)),
+            ConfigFormat::Dotenv => Box::new(Dotenv::string(content)),
+        }
+    }
 
-        Self { provider }
+    /// The parsed data with the files it includes merged beneath it
+    ///
+    /// `stack` holds the files whose includes are being resolved, see [`include`].
+    pub(crate) fn data_with_includes(
+        &self,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Map<Profile, Map<String, Value>>, Error> {
+        let data = self.provider.data()?;
+        match &self.path {
+            Some(path) => include::resolve(path, data, stack),
+            None => Ok(data),
+        }
+    }
+
+    /// Get the warning recorded while loading, if any (e.g. an encoding conversion)
+    pub fn warning(&self) -> Option<&str> {
+        self.warning.as_deref()
     }
 
     /// Try multiple common extensions for a base filename
     pub fn file_with_extensions<P: AsRef<Path>>(base_path: P) -> Self {
-        Self::try_multiple_extensions(base_path.as_ref())
-            .map(|provider| Self { provider })
-            .unwrap_or_else(Self::empty_provider)
+        Self::try_multiple_extensions(base_path.as_ref()).unwrap_or_else(Self::empty_provider)
+    }
+
+    /// Load an existing file, trying each detection strategy in order
+    fn try_existing_file(path: &Path) -> Option<Self> {
+        // First try: convert BOM/UTF-16 content to UTF-8 before any parser sees it
+        if let Some(universal) = Self::try_encoding_conversion(path) {
+            return Some(universal);
+        }
+
+        // Second try: extension-based detection
+        // Third try: content-based detection with caching
+        // Fourth try: brute force - try parsing with each format
+        Self::try_extension_detection(path)
+            .or_else(|| Self::try_cached_content_detection(path))
+            .or_else(|| Self::try_all_formats(path))
+            .map(|provider| Self {
+                provider,
+                warning: None,
+                path: Some(path.to_path_buf()),
+            })
+    }
+
+    /// Convert files that are not plain UTF-8 (BOM, UTF-16) and parse the converted content
+    ///
+    /// Only the first bytes are inspected for plain UTF-8 files, so the fast path stays cheap.
+    fn try_encoding_conversion(path: &Path) -> Option<Self> {
+        let mut prefix = [0u8; 4];
+        let read = fs::File::open(path)
+            .and_then(|mut file| file.read(&mut prefix))
+            .ok()?;
+        if !encoding::needs_conversion(&prefix[..read]) {
+            return None;
+        }
+
+        let path_str = path.display();
+        let normalized = fs::read(path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| encoding::normalize(&bytes));
+
+        Some(match normalized {
+            Ok(normalized) => {
+                let mut universal = Self::from_content(path, &normalized.content);
+                universal.warning = normalized
+                    .warning
+                    .map(|warning| format!("{path_str}: {warning}"));
+                universal
+            }
+            Err(e) => Self {
+                warning: Some(format!("{path_str}: failed to decode file: {e}")),
+                ..Self::empty_provider()
+            },
+        })
     }
 
     /// Fast path: try extension-based detection first
     fn try_extension_detection(path: &Path) -> Option<Box<dyn Provider>> {
+        if Self::is_dotenv_file(path) {
+            return Some(Box::new(Dotenv::file(path)));
+        }
         let extension = path.extension()?.to_str()?.to_lowercase();
 
         match extension.as_str() {
+            // Strict JSON stays on the JSON parser; only read the file when JSON5 is enabled
+            "json"
+                if cfg!(feature = "json5")
+                    && fs::read_to_string(path)
+                        .is_ok_and(|content| json5::looks_like_json5(&content)) =>
+            {
+                Some(Box::new(Json5::file(path)))
+            }
             "json" => Some(Box::new(figment::providers::Json::file(path))),
+            "json5" => Some(Box::new(Json5::file(path))),
             "toml" => Some(Box::new(figment::providers::Toml::file(path))),
-            "yaml" | "yml" => Some(Box::new(figment::providers::Yaml::file(path))),
+            "yaml" | "yml" => Some(Box::new(YamlMerge::file(path))),
+            "ini" => Some(Box::new(Ini::file(path))),
+            "env" => Some(Box::new(Dotenv::file(path))),
             _ => None,
         }
     }
@@ -260,11 +577,13 @@ impl Universal {
     /// Try parsing with each format until one succeeds
     /// This handles unknown extensions (.cfg) and misidentified formats
     fn try_all_formats(path: &Path) -> Option<Box<dyn Provider>> {
-        // Try each format in order: TOML, YAML, JSON
-        let formats: [Box<dyn Fn() -> Box<dyn Provider>>; 3] = [
+        // Try each format in order: TOML, YAML, JSON, JSON5, INI
+        let formats: [Box<dyn Fn() -> Box<dyn Provider>>; 5] = [
             Box::new(|| Box::new(figment::providers::Toml::file(path)) as Box<dyn Provider>),
-            Box::new(|| Box::new(figment::providers::Yaml::file(path)) as Box<dyn Provider>),
+            Box::new(|| Box::new(YamlMerge::file(path)) as Box<dyn Provider>),
             Box::new(|| Box::new(figment::providers::Json::file(path)) as Box<dyn Provider>),
+            Box::new(|| Box::new(Json5::file(path)) as Box<dyn Provider>),
+            Box::new(|| Box::new(Ini::file(path)) as Box<dyn Provider>),
         ];
 
         for create_provider in &formats {
@@ -282,27 +601,22 @@ impl Universal {
     fn create_provider_for_format(path: &Path, format: ConfigFormat) -> Option<Box<dyn Provider>> {
         match format {
             ConfigFormat::Json => Some(Box::new(figment::providers::Json::file(path))),
+            ConfigFormat::Json5 => Some(Box::new(Json5::file(path))),
             ConfigFormat::Toml => Some(Box::new(figment::providers::Toml::file(path))),
-            ConfigFormat::Yaml => Some(Box::new(figment::providers::Yaml::file(path))),
+            ConfigFormat::Yaml => Some(Box::new(YamlMerge::file(path))),
+            ConfigFormat::Ini => Some(Box::new(Ini::file(path))),
+            ConfigFormat::Dotenv => Some(Box::new(Dotenv::file(path))),
         }
     }
 
     /// Try multiple extensions in priority order
-    fn try_multiple_extensions(base_path: &Path) -> Option<Box<dyn Provider>> {
-        let extensions = ["toml", "yaml", "yml", "json"];
-
-        for ext in &extensions {
+    fn try_multiple_extensions(base_path: &Path) -> Option<Self> {
+        for ext in &EXTENSIONS {
             let path_with_ext = base_path.with_extension(ext);
-            if path_with_ext.exists() {
-                if let Some(provider) = Self::try_extension_detection(&path_with_ext) {
-                    return Some(provider);
-                }
-                if let Some(provider) = Self::try_cached_content_detection(&path_with_ext) {
-                    return Some(provider);
-                }
-                if let Some(provider) = Self::try_all_formats(&path_with_ext) {
-                    return Some(provider);
-                }
+            if path_with_ext.exists()
+                && let Some(universal) = Self::try_existing_file(&path_with_ext)
+            {
+                return Some(universal);
             }
         }
 
@@ -310,12 +624,19 @@ impl Universal {
     }
 
     /// Detect configuration format from content analysis
-    /// Detection order: TOML first (most specific), then YAML, then JSON (most permissive)
+    /// Detection order: JSON5 first (only content strict JSON rejects), dotenv and INI
+    /// (only content TOML rejects), then TOML, then YAML, then JSON (most permissive)
     fn detect_format_from_content(content: &str) -> ConfigFormat {
         let trimmed = content.trim();
 
+        if json5::looks_like_json5(trimmed) {
+            ConfigFormat::Json5
+        } else if ini::looks_like_dotenv(trimmed) {
+            ConfigFormat::Dotenv
+        } else if ini::looks_like_ini(trimmed) {
+            ConfigFormat::Ini
         // Check TOML first - most specific patterns
-        if Self::is_toml_format(trimmed) {
+        } else if Self::is_toml_format(trimmed) {
             ConfigFormat::Toml
         // Then YAML - key: value patterns
         } else if Self::is_yaml_format(trimmed) {
@@ -406,10 +727,19 @@ impl Universal {
         false
     }
 
+    /// Whether `path` is a dotenv file: `.env`, or `.env.` followed by an environment
+    fn is_dotenv_file(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name == ".env" || name.starts_with(".env."))
+    }
+
     /// Create empty provider as final fallback
     fn empty_provider() -> Self {
         Self {
             provider: Box::new(figment::providers::Serialized::defaults(())),
+            warning: None,
+            path: None,
         }
     }
 
@@ -435,10 +765,16 @@ impl Provider for Universal {
     }
 
     fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
-        self.provider.data()
+        self.data_with_includes(&mut Vec::new())
     }
 
     fn profile(&self) -> Option<Profile> {
         self.provider.profile()
     }
 }
+
+impl ValidatedProvider for Universal {
+    fn validation_error(&self) -> Option<Error> {
+        self.warning.clone().map(Error::from)
+    }
+}
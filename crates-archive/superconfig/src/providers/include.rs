@@ -0,0 +1,234 @@
+//! Include directives: configuration files pulling in other files
+//!
+//! A file loaded by the [`Universal`] provider (and so by [`Wildcard`](super::Wildcard)
+//! and `SuperConfig::with_file`) may list other files under a top-level `include` or
+//! `__include__` key:
+//!
+//! ```toml
+//! include = ["base.toml", "region/${REGION}.toml"]
+//!
+//! [server]
+//! port = 8080
+//! ```
+//!
+//! - **Relative paths** are resolved against the directory of the including file
+//! - **`${VAR}`** in a path is replaced with the environment variable `VAR`
+//! - **Merge order**: included files are merged in the order listed, then the
+//!   including file on top, so its own values win. Array suffixes such as
+//!   `features_add` apply to the arrays of the included files
+//! - **Nesting**: included files may include others; a file including itself, directly
+//!   or through other files, is an error naming the cycle
+//!
+//! A single path may be given as a string (`include = "base.toml"`). Missing included
+//! files are errors, like the directive itself when it isn't a path or list of paths.
+
+use super::Universal;
+use figment::{
+    Error, Profile, Provider,
+    providers::Serialized,
+    value::{Dict, Map, Value},
+};
+use std::path::{Path, PathBuf};
+
+/// Keys holding the include directive
+const INCLUDE_KEYS: [&str; 2] = ["include", "__include__"];
+
+/// Merge the files `data` read from `path` includes beneath it
+///
+/// `stack` holds the files whose includes are being resolved, to detect cycles.
+pub(crate) fn resolve(
+    path: &Path,
+    mut data: Map<Profile, Dict>,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Map<Profile, Dict>, Error> {
+    let includes = take_includes(path, &mut data)?;
+    if includes.is_empty() {
+        return Ok(data);
+    }
+
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if let Some(start) = stack.iter().position(|file| *file == canonical) {
+        let cycle: Vec<String> = stack[start..]
+            .iter()
+            .chain([&canonical])
+            .map(|file| file.display().to_string())
+            .collect();
+        return Err(Error::from(format!(
+            "include cycle: {}",
+            cycle.join(" -> ")
+        )));
+    }
+
+    stack.push(canonical);
+    let merged = merge_includes(path, &includes, data, stack);
+    stack.pop();
+    merged
+}
+
+fn merge_includes(
+    path: &Path,
+    includes: &[String],
+    data: Map<Profile, Dict>,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Map<Profile, Dict>, Error> {
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut config = crate::SuperConfig::new();
+    for include in includes {
+        let target = base.join(expand_env(include).map_err(|e| include_error(path, &e))?);
+        if !target.is_file() {
+            return Err(include_error(
+                path,
+                &format!("included file {} does not exist", target.display()),
+            ));
+        }
+        config = merge_data(config, Universal::file(&target).data_with_includes(stack)?);
+    }
+    crate::provenance::untagged(merge_data(config, data).figment.data()?)
+}
+
+/// Merge every profile of `data` into `config`
+fn merge_data(config: crate::SuperConfig, data: Map<Profile, Dict>) -> crate::SuperConfig {
+    data.into_iter().fold(config, |config, (profile, dict)| {
+        config.merge(Serialized::from(dict, profile))
+    })
+}
+
+/// Remove the include directives of every profile, returning their paths in order
+fn take_includes(path: &Path, data: &mut Map<Profile, Dict>) -> Result<Vec<String>, Error> {
+    let mut includes = Vec::new();
+    for dict in data.values_mut() {
+        for key in INCLUDE_KEYS {
+            match dict.remove(key) {
+                None => {}
+                Some(Value::String(_, include)) => includes.push(include),
+                Some(Value::Array(_, items)) => {
+                    for item in items {
+                        let Value::String(_, include) = item else {
+                            return Err(include_error(path, &format!("`{key}` must list paths")));
+                        };
+                        includes.push(include);
+                    }
+                }
+                Some(_) => {
+                    return Err(include_error(
+                        path,
+                        &format!("`{key}` must be a path or a list of paths"),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(includes)
+}
+
+/// Replace the `${VAR}` references of `include` with environment variables
+fn expand_env(include: &str) -> Result<String, String> {
+    let mut expanded = String::with_capacity(include.len());
+    let mut rest = include;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unterminated `${{` in {include}"))?;
+        let name = &rest[start + 2..start + end];
+        let value = std::env::var(name)
+            .map_err(|_| format!("environment variable {name} in {include} is not set"))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn include_error(path: &Path, message: &str) -> Error {
+    Error::from(format!("{}: {message}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn default_dict(universal: &Universal) -> Dict {
+        universal.data().unwrap().remove(&Profile::Default).unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn test_includes_merge_beneath_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "base.toml",
+            "host = \"base\"\nport = 80\nname = \"base\"",
+        );
+        write(dir.path(), "region/eu.yaml", "port: 81\nregion: eu");
+        let main = write(
+            dir.path(),
+            "main.toml",
+            "include = [\"base.toml\", \"region/${SUPERCONFIG_TEST_REGION}.yaml\"]\nname = \"main\"",
+        );
+
+        unsafe { std::env::set_var("SUPERCONFIG_TEST_REGION", "eu") };
+        let dict = default_dict(&Universal::file(&main));
+        unsafe { std::env::remove_var("SUPERCONFIG_TEST_REGION") };
+
+        assert_eq!(dict["host"].as_str(), Some("base"));
+        assert_eq!(dict["port"].to_u128(), Some(81));
+        assert_eq!(dict["region"].as_str(), Some("eu"));
+        assert_eq!(dict["name"].as_str(), Some("main"));
+        assert!(!dict.contains_key("include"));
+    }
+
+    #[test]
+    fn test_nested_includes_and_single_path() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "conf/a.json",
+            r#"{"a": 1, "__include__": "../b.json"}"#,
+        );
+        write(dir.path(), "b.json", r#"{"a": 2, "b": 2}"#);
+        let main = write(dir.path(), "main.json", r#"{"include": "conf/a.json"}"#);
+
+        let dict = default_dict(&Universal::file(main));
+        assert_eq!(dict["a"].to_u128(), Some(1));
+        assert_eq!(dict["b"].to_u128(), Some(2));
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(dir.path(), "a.toml", "include = \"b.toml\"");
+        write(dir.path(), "b.toml", "include = [\"a.toml\"]");
+        let error = Universal::file(&a).data().unwrap_err().to_string();
+        assert!(error.starts_with("include cycle: "), "{error}");
+        assert!(
+            error.contains("a.toml -> ") && error.ends_with("a.toml"),
+            "{error}"
+        );
+
+        let missing = write(dir.path(), "missing.toml", "include = \"nope.toml\"");
+        let error = Universal::file(&missing).data().unwrap_err().to_string();
+        assert!(error.contains("included file") && error.contains("nope.toml"));
+
+        let invalid = write(dir.path(), "invalid.toml", "include = 1");
+        let error = Universal::file(&invalid).data().unwrap_err().to_string();
+        assert!(error.contains("`include` must be a path or a list of paths"));
+
+        let unset = write(
+            dir.path(),
+            "unset.toml",
+            "include = \"${SUPERCONFIG_TEST_UNSET}.toml\"",
+        );
+        let error = Universal::file(&unset).data().unwrap_err().to_string();
+        assert!(error.contains("environment variable SUPERCONFIG_TEST_UNSET"));
+    }
+}
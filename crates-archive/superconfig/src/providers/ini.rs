@@ -0,0 +1,361 @@
+//! INI and dotenv (`.env`) formats for Figment
+//!
+//! [`Ini`] and [`Dotenv`] implement Figment's [`Format`], so `Ini::file("app.ini")` and
+//! `Dotenv::file(".env")` work like Figment's own `Toml::file`, and the
+//! [`Universal`](super::Universal) provider picks them for `.ini`, `.env` and `.env.*`
+//! files or detects them from content.
+//!
+//! ## INI
+//! ```ini
+//! ; comments start with ; or #
+//! name = My App         ; bare values need no quotes
+//!
+//! [database]            ; → database.host, database.port
+//! host = localhost
+//! port = 5432
+//!
+//! [database.replica]    ; dotted sections and keys nest
+//! host: replica.local   ; `key: value` is accepted too
+//! ```
+//!
+//! ## Dotenv
+//! ```text
+//! # comments start with #
+//! export APP_NAME="My App"  # `export` is optional
+//! DATABASE__HOST=localhost  # `__` nests: → database.host
+//! DATABASE__PORT=5432
+//! ```
+//! Keys are lowercased, and `__` separates nesting levels so that single underscores
+//! stay part of the key (`POOL_SIZE` → `pool_size`).
+//!
+//! In both formats, unquoted values become booleans (`true`/`yes`/`on`,
+//! `false`/`no`/`off`), numbers, or JSON arrays and objects when they parse as such;
+//! anything else, and every quoted value, stays a string.
+//!
+//! ```rust
+//! use figment::{Figment, providers::Format};
+//! use superconfig::{Dotenv, Ini};
+//!
+//! let figment = Figment::new()
+//!     .merge(Ini::string("[server]\nhost = localhost\nport = 80"))
+//!     .merge(Dotenv::string("SERVER__PORT=8080"));
+//! assert_eq!(figment.extract_inner::<String>("server.host").unwrap(), "localhost");
+//! assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+//! ```
+
+use figment::providers::Format;
+use serde::de::{DeserializeOwned, Error as _};
+use serde_json::{Map, Value};
+
+/// INI format: `[section]` headers and `key = value` lines
+pub struct Ini;
+
+impl Format for Ini {
+    type Error = serde_json::Error;
+
+    const NAME: &'static str = "INI";
+
+    fn from_str<T: DeserializeOwned>(string: &str) -> Result<T, Self::Error> {
+        let tree = parse_ini(string).map_err(serde_json::Error::custom)?;
+        serde_json::from_value(tree)
+    }
+}
+
+/// Dotenv format: `KEY=value` lines, nested on `__`
+pub struct Dotenv;
+
+impl Format for Dotenv {
+    type Error = serde_json::Error;
+
+    const NAME: &'static str = "Dotenv";
+
+    fn from_str<T: DeserializeOwned>(string: &str) -> Result<T, Self::Error> {
+        let tree = parse_dotenv(string).map_err(serde_json::Error::custom)?;
+        serde_json::from_value(tree)
+    }
+}
+
+/// Parse INI content into a JSON tree
+pub(crate) fn parse_ini(content: &str) -> Result<Value, String> {
+    let mut tree = Map::new();
+    let mut section: Vec<String> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let header = strip_inline_comment(header);
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| format!("line {}: unclosed section header", index + 1))?;
+            section = split_key(name, ".");
+            continue;
+        }
+        let (key, value) = line
+            .split_once(['=', ':'])
+            .ok_or_else(|| format!("line {}: expected `key = value`", index + 1))?;
+        let key = split_key(key, ".");
+        if key.is_empty() {
+            return Err(format!("line {}: missing key", index + 1));
+        }
+        let path: Vec<String> = section.iter().cloned().chain(key).collect();
+        insert(&mut tree, &path, value_of(value));
+    }
+    Ok(Value::Object(tree))
+}
+
+/// Parse dotenv content into a JSON tree
+pub(crate) fn parse_dotenv(content: &str) -> Result<Value, String> {
+    let mut tree = Map::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected `KEY=value`", index + 1))?;
+        let path = split_key(&key.to_lowercase(), "__");
+        if path.is_empty() {
+            return Err(format!("line {}: missing key", index + 1));
+        }
+        insert(&mut tree, &path, value_of(value));
+    }
+    Ok(Value::Object(tree))
+}
+
+/// Whether `content` looks like INI rather than TOML: every line is a section, an
+/// assignment or a comment, and some line uses syntax TOML rejects
+pub(crate) fn looks_like_ini(content: &str) -> bool {
+    let mut in_section = false;
+    let mut ini_only = false;
+    let mut assignments = false;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with(';') {
+            ini_only = true;
+        } else if line.starts_with('[') && line.ends_with(']') {
+            in_section = true;
+        } else if let Some((_, value)) = line.split_once('=') {
+            assignments = true;
+            ini_only |= is_bare_text(value);
+        } else if in_section && let Some((_, value)) = line.split_once(':') {
+            // YAML has no section headers, so `key: value` inside one is INI
+            assignments = true;
+            ini_only |= value.is_empty() || value.starts_with(' ');
+        } else {
+            return false;
+        }
+    }
+    (assignments || in_section) && ini_only
+}
+
+/// Whether `content` looks like dotenv: every line is `KEY=value`, optionally with
+/// `export`, with upper case keys and no spaces before `=`
+pub(crate) fn looks_like_dotenv(content: &str) -> bool {
+    let mut assignments = false;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, _)) = line.split_once('=') else {
+            return false;
+        };
+        let key_is_upper = !key.is_empty()
+            && key.chars().any(|c| c.is_ascii_uppercase())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if !key_is_upper {
+            return false;
+        }
+        assignments = true;
+    }
+    assignments
+}
+
+/// An unquoted value TOML can't parse, such as `localhost`
+fn is_bare_text(value: &str) -> bool {
+    let value = strip_inline_comment(value.trim());
+    let first = value.chars().next();
+    first.is_some_and(|c| !matches!(c, '"' | '\'' | '[' | '{' | '+' | '-') && !c.is_ascii_digit())
+        && !matches!(value, "true" | "false" | "inf" | "nan")
+}
+
+/// Split `key` on `separator` into trimmed, non-empty segments
+fn split_key(key: &str, separator: &str) -> Vec<String> {
+    key.split(separator)
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Remove a `;` or ` #` comment following a value or header
+fn strip_inline_comment(value: &str) -> &str {
+    [" ;", " #", "\t;", "\t#"]
+        .iter()
+        .filter_map(|marker| value.find(marker))
+        .min()
+        .map_or(value, |end| &value[..end])
+        .trim()
+}
+
+/// The value of an assignment: quoted values are strings, others are coerced
+fn value_of(raw: &str) -> Value {
+    let raw = raw.trim();
+    for quote in ['"', '\''] {
+        if let Some(rest) = raw.strip_prefix(quote)
+            && let Some(end) = rest.rfind(quote)
+        {
+            let inner = &rest[..end];
+            return Value::String(if quote == '"' {
+                unescape(inner)
+            } else {
+                inner.to_string()
+            });
+        }
+    }
+    coerce(strip_inline_comment(raw))
+}
+
+/// Resolve the backslash escapes of a double-quoted value
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Coerce an unquoted value into a boolean, number, JSON array or object, or string
+fn coerce(value: &str) -> Value {
+    if (value.starts_with('[') || value.starts_with('{'))
+        && let Ok(parsed) = serde_json::from_str(value)
+    {
+        return parsed;
+    }
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" => return Value::Bool(true),
+        "false" | "no" | "off" => return Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(integer) = value.parse::<i64>() {
+        return Value::from(integer);
+    }
+    if let Ok(float) = value.parse::<f64>()
+        && float.is_finite()
+    {
+        return Value::from(float);
+    }
+    Value::String(value.to_string())
+}
+
+/// Insert `value` at `path`, replacing non-table values on the way with tables
+fn insert(tree: &mut Map<String, Value>, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut table = tree;
+    for key in parents {
+        let node = table
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !node.is_object() {
+            *node = Value::Object(Map::new());
+        }
+        let Value::Object(child) = node else {
+            unreachable!("node was just made a table");
+        };
+        table = child;
+    }
+    table.insert(last.clone(), value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_ini_sections_and_values() {
+        let tree = parse_ini(
+            "; app settings\nname = My App ; inline\n\n[database]\nhost = localhost\nport = 5432\n\
+             enabled = yes\n\n[database.replica]\nhost: \"replica;1\"\nhosts = [\"a\", \"b\"]",
+        )
+        .unwrap();
+        assert_eq!(
+            tree,
+            json!({
+                "name": "My App",
+                "database": {
+                    "host": "localhost",
+                    "port": 5432,
+                    "enabled": true,
+                    "replica": {"host": "replica;1", "hosts": ["a", "b"]},
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ini_errors_name_the_line() {
+        assert_eq!(
+            parse_ini("[a]\njust text").unwrap_err(),
+            "line 2: expected `key = value`"
+        );
+        assert_eq!(
+            parse_ini("[a\nkey = 1").unwrap_err(),
+            "line 1: unclosed section header"
+        );
+    }
+
+    #[test]
+    fn test_parse_dotenv_nests_on_double_underscore() {
+        let tree = parse_dotenv(
+            "# comment\nexport APP_NAME=\"My App\\n\"\nDATABASE__HOST=localhost # local\n\
+             DATABASE__POOL_SIZE=8\nQUOTED='8'\nEMPTY=",
+        )
+        .unwrap();
+        assert_eq!(
+            tree,
+            json!({
+                "app_name": "My App\n",
+                "database": {"host": "localhost", "pool_size": 8},
+                "quoted": "8",
+                "empty": "",
+            })
+        );
+        assert!(parse_dotenv("NO_EQUALS").is_err());
+    }
+
+    #[test]
+    fn test_detection_leaves_toml_and_yaml_alone() {
+        assert!(looks_like_ini("[server]\nhost = localhost"));
+        assert!(looks_like_ini("; comment\nport = 80"));
+        assert!(looks_like_ini("[server]\nhost: localhost"));
+        assert!(!looks_like_ini("[server]\nhost = \"localhost\"\nport = 80"));
+        assert!(!looks_like_ini("debug = true # verbose"));
+        assert!(!looks_like_ini("server:\n  host: localhost"));
+
+        assert!(looks_like_dotenv("export HOST=localhost\nPORT=80"));
+        assert!(!looks_like_dotenv("host = \"localhost\""));
+        assert!(!looks_like_dotenv("[server]\nHOST=x"));
+    }
+}
@@ -0,0 +1,96 @@
+//! JSON5 format for Figment, with the `json5` feature
+//!
+//! [JSON5](https://json5.org) extends JSON with what hand-edited configuration needs:
+//! comments, trailing commas, unquoted keys, single-quoted strings and hexadecimal
+//! numbers. Every JSON document is also JSON5.
+//!
+//! [`Json5`] implements Figment's [`Format`], and the [`Universal`](super::Universal)
+//! provider uses it for `.json5` files and for content starting like JSON that strict
+//! JSON rejects, such as a `.json` file with comments:
+//!
+//! ```rust
+//! # #[cfg(feature = "json5")]
+//! # {
+//! use figment::{Figment, providers::Format};
+//! use superconfig::Json5;
+//!
+//! let figment = Figment::from(Json5::string(
+//!     "{
+//!         // Trailing commas and comments are fine
+//!         server: { host: 'localhost', port: 0x1F90, },
+//!     }",
+//! ));
+//! assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+//! # }
+//! ```
+//!
+//! Without the feature, parsing fails with an error naming it.
+
+use figment::providers::Format;
+use serde::de::DeserializeOwned;
+
+/// JSON5 format: JSON with comments, trailing commas and unquoted keys
+pub struct Json5;
+
+impl Format for Json5 {
+    type Error = serde_json::Error;
+
+    const NAME: &'static str = "JSON5";
+
+    #[cfg(feature = "json5")]
+    fn from_str<T: DeserializeOwned>(string: &str) -> Result<T, Self::Error> {
+        use serde::de::Error as _;
+
+        let tree: serde_json::Value =
+            ::json5::from_str(string).map_err(serde_json::Error::custom)?;
+        serde_json::from_value(tree)
+    }
+
+    #[cfg(not(feature = "json5"))]
+    fn from_str<T: DeserializeOwned>(_string: &str) -> Result<T, Self::Error> {
+        use serde::de::Error as _;
+
+        Err(serde_json::Error::custom(
+            "JSON5 requires the `json5` feature",
+        ))
+    }
+}
+
+/// Whether `content` is JSON5 but not strict JSON: it starts like JSON or with a
+/// comment, and only JSON5 parses it
+pub(crate) fn looks_like_json5(content: &str) -> bool {
+    let content = content.trim_start();
+    let json_like = ["{", "[", "//", "/*"]
+        .iter()
+        .any(|start| content.starts_with(start));
+    cfg!(feature = "json5")
+        && json_like
+        && serde_json::from_str::<serde::de::IgnoredAny>(content).is_err()
+        && Json5::from_str::<serde::de::IgnoredAny>(content).is_ok()
+}
+
+#[cfg(all(test, feature = "json5"))]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    #[test]
+    fn test_parse_json5_extensions() {
+        let value: Value = Json5::from_str(
+            "/* header */ {\n  name: 'app', // comment\n  ports: [80, 0x1BB,],\n  ratio: .5,\n}",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({"name": "app", "ports": [80, 443], "ratio": 0.5})
+        );
+    }
+
+    #[test]
+    fn test_detection_skips_strict_json_and_toml() {
+        assert!(looks_like_json5("{\"a\": 1,}"));
+        assert!(looks_like_json5("// comment\n{a: 1}"));
+        assert!(!looks_like_json5("{\"a\": 1}"));
+        assert!(!looks_like_json5("[server]\nhost = \"a\""));
+    }
+}
@@ -5,7 +5,7 @@
 //! ## Provider Overview
 //!
 //! ### Universal Provider - Smart Format Detection
-//! Automatically detects configuration file formats (JSON, TOML, YAML) with intelligent
+//! Automatically detects configuration file formats (JSON, JSON5, TOML, YAML, INI, dotenv) with intelligent
 //! caching and extension fallback for optimal performance.
 //!
 //! **Key Features:**
@@ -21,6 +21,24 @@
 //!     .with_file("config");  // Auto-detects format internally
 //! ```
 //!
+//! ### Includes - Composing Files
+//! A file may pull in other files with an `include` (or `__include__`) key, resolved
+//! relative to the file, before its own values are merged over them.
+//!
+//! **Key Features:**
+//! - **Relative Paths**: `include = ["base.toml"]` next to the including file
+//! - **Variables**: `region/${REGION}.toml` picks a file from the environment
+//! - **Cycle Detection**: Files including each other fail with the cycle named
+//!
+//! **Usage with SuperConfig:**
+//! ```rust,no_run
+//! use superconfig::SuperConfig;
+//!
+//! // config.toml: include = ["base.toml", "region/${REGION}.toml"]
+//! let config = SuperConfig::new()
+//!     .with_file("config.toml");  // Includes resolved by the Universal provider
+//! ```
+//!
 //! ### Nested Provider - Advanced Environment Variables
 //! Enhanced environment variable parsing with JSON support, automatic nesting,
 //! and smart type detection.
@@ -60,6 +78,11 @@
 //!     .with_cli_opt(Some(CliArgs { debug: true }));  // Automatic empty filtering
 //! ```
 //!
+//! ### Clap Provider - Parsed Command Lines
+//! With the `clap` feature, `Clap` and `SuperConfig::with_clap` merge the arguments in
+//! clap's `ArgMatches` by id, skipping clap's default values; see the `cli` module for
+//! key names, value types and type hints.
+//!
 //! ### Wildcard Provider - Unified Pattern-Based Discovery
 //! Revolutionary unified provider using globset patterns for flexible configuration discovery.
 //! Replaces hierarchical and single-directory providers with a single, powerful solution.
@@ -96,6 +119,75 @@
 //!     .with_cli_opt(cli_args);                                    // CLI arguments
 //! ```
 //!
+//! ### Verified Provider - Integrity Checking
+//! Verifies configuration files against a SHA-256 checksum or a detached minisign
+//! signature before parsing, with a configurable failure policy.
+//!
+//! **Key Features:**
+//! - **Checksums**: Inline digests or `sha256sum`-style sidecar files
+//! - **Signatures**: Ed25519 signatures produced by `minisign`
+//! - **Policies**: Ignore, warn (collected by SuperConfig), or enforce (refuse to load)
+//!
+//! **Usage with SuperConfig:**
+//! ```rust,no_run
+//! use superconfig::{SuperConfig, VerifyPolicy};
+//!
+//! let config = SuperConfig::new()
+//!     .with_verified_file("config.toml", VerifyPolicy::Enforce);  // Checks config.toml.sha256
+//! ```
+//!
+//! ### Encrypted Provider - Decryption at Load Time
+//! Loads AES-256-GCM encrypted files, or files with individually encrypted values, and
+//! decrypts them with a key from an environment variable or keyfile.
+//!
+//! **Key Features:**
+//! - **Whole Files**: `config.toml.enc` is decrypted, then parsed as TOML
+//! - **Single Values**: SOPS-style `ENC[AES256_GCM,...]` strings keep the file diffable
+//! - **Key Sources**: `SUPERCONFIG_KEY` by default, a keyfile, or an in-memory key
+//!
+//! **Usage with SuperConfig:**
+//! ```rust,no_run
+//! use superconfig::{KeySource, SuperConfig};
+//!
+//! let config = SuperConfig::new()
+//!     .with_encrypted_file("config.toml.enc", KeySource::File("/run/keys/config".into()));
+//! ```
+//!
+//! ### Duplicate Keys - Explicit Conflict Resolution
+//! Decides what happens when a key is repeated within one file or across files that a
+//! Wildcard provider merges at the same precedence.
+//!
+//! **Key Features:**
+//! - **Policies**: Last wins (default), first wins, error, or warn
+//! - **In-File Detection**: JSON and YAML duplicates that parsers would silently drop
+//! - **Cross-File Detection**: Keys defined by more than one discovered file
+//!
+//! **Usage with SuperConfig:**
+//! ```rust,no_run
+//! use superconfig::{DuplicateKeyPolicy, SuperConfig};
+//!
+//! let config = SuperConfig::new()
+//!     .with_file_duplicate_keys("config.yaml", DuplicateKeyPolicy::Error);
+//! ```
+//!
+//! ### EnvOverrides Provider - Emergency Overrides
+//! Overrides any key from `SUPERCONFIG_OVERRIDE__` environment variables as the final
+//! layer, for emergency production tweaks.
+//!
+//! **Key Features:**
+//! - **Per-Key Targeting**: `SUPERCONFIG_OVERRIDE__database__port=5433` → `database.port`
+//! - **Always Last**: Re-applied after every merge, so no source can replace an override
+//! - **Loudly Reported**: Warnings, verbosity output, `debug_config()`, and key metadata
+//!
+//! **Usage with SuperConfig:**
+//! ```rust,no_run
+//! use superconfig::SuperConfig;
+//!
+//! let config = SuperConfig::new()
+//!     .with_env_overrides()          // Opt in, before or after other sources
+//!     .with_file("config.toml");
+//! ```
+//!
 //! ## Performance Characteristics
 //!
 //! All providers implement optimization strategies:
@@ -104,15 +196,38 @@
 //! - **Efficient Parsing**: Single-pass processing with type inference
 //! - **Memory Optimized**: Minimal memory footprint for large configurations
 
+#[cfg(feature = "clap")]
+pub mod cli;
+pub mod duplicates;
+mod encoding;
+pub mod encrypted;
 pub mod env;
 pub mod filter;
 pub mod format;
+pub mod include;
+pub mod ini;
+pub mod json5;
+pub mod overrides;
+pub mod verify;
 pub mod wildcard;
 
 // New unified exports
-pub use wildcard::{MergeOrder, SearchStrategy, Wildcard, WildcardBuilder};
+pub use wildcard::{
+    DiscoveryLimits, MergeOrder, SearchStrategy, SymlinkPolicy, Wildcard, WildcardBuilder,
+};
+
+#[cfg(feature = "watch")]
+pub use wildcard::{WildcardEvent, WildcardWatcher};
 
 // Existing exports
+#[cfg(feature = "clap")]
+pub use cli::{ArgType, Clap};
+pub use duplicates::DuplicateKeyPolicy;
+pub use encrypted::{DEFAULT_KEY_ENV, Encrypted, EncryptionKey, KeySource};
 pub use env::Nested;
 pub use filter::Empty;
 pub use format::Universal;
+pub use ini::{Dotenv, Ini};
+pub use json5::Json5;
+pub use overrides::{EnvOverrides, OVERRIDE_PREFIX};
+pub use verify::{Verification, Verified, VerifyPolicy};
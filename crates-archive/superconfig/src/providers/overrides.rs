@@ -0,0 +1,208 @@
+//! Emergency per-key overrides from `SUPERCONFIG_OVERRIDE__` environment variables
+//!
+//! Every variable named `SUPERCONFIG_OVERRIDE__<key>__<key>...` sets one configuration
+//! key, with `__` separating nesting levels:
+//!
+//! ```text
+//! SUPERCONFIG_OVERRIDE__database__port=5433   → database.port = 5433
+//! SUPERCONFIG_OVERRIDE__log_level=debug       → log_level = "debug"
+//! ```
+//!
+//! Unlike prefixed environment variables, overrides are not one layer among others:
+//! once enabled with [`SuperConfig::with_env_overrides`](crate::SuperConfig::with_env_overrides),
+//! SuperConfig re-applies them after every later merge, so they win over any source
+//! regardless of order. They are meant for emergency production tweaks, and are reported
+//! as such: each one is collected as a warning, logged at [`INFO`](crate::verbosity::INFO)
+//! verbosity, and shows up as the source of the key in Figment's metadata.
+//!
+//! Values are parsed like [`Nested`](crate::Nested) values (JSON arrays and objects,
+//! booleans, numbers, then strings), and keys are lowercased.
+
+use crate::providers::Nested;
+use figment::{
+    Error, Metadata, Profile, Provider,
+    value::{Dict, Map, Tag, Value},
+};
+
+/// Environment variable prefix of emergency overrides
+pub const OVERRIDE_PREFIX: &str = "SUPERCONFIG_OVERRIDE__";
+
+/// Separator between the nesting levels of an override key
+const SEPARATOR: &str = "__";
+
+/// Provider for emergency `SUPERCONFIG_OVERRIDE__` environment variables
+#[derive(Debug, Clone)]
+pub struct EnvOverrides {
+    /// `(variable, value)` pairs, sorted by variable name
+    vars: Vec<(String, String)>,
+}
+
+impl EnvOverrides {
+    /// Collect the `SUPERCONFIG_OVERRIDE__` variables of the current environment
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::EnvOverrides;
+    ///
+    /// // Environment: SUPERCONFIG_OVERRIDE__database__port=5433
+    /// let overrides = EnvOverrides::new();
+    /// // Creates: { database: { port: 5433 } }
+    /// ```
+    pub fn new() -> Self {
+        Self::from_vars(std::env::vars())
+    }
+
+    /// Create the provider from explicit `(variable, value)` pairs
+    ///
+    /// Variables without the `SUPERCONFIG_OVERRIDE__` prefix or without a key are ignored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::EnvOverrides;
+    ///
+    /// let overrides = EnvOverrides::from_vars([("SUPERCONFIG_OVERRIDE__database__port", "5433")]);
+    /// assert_eq!(overrides.keys(), vec!["database.port"]);
+    /// ```
+    pub fn from_vars<I, K, V>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut vars: Vec<(String, String)> = vars
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .filter(|(key, _)| !Self::path(key).is_empty())
+            .collect();
+        vars.sort();
+        Self { vars }
+    }
+
+    /// Returns `true` when no override is set
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Dotted paths of the overridden keys, sorted by variable name
+    pub fn keys(&self) -> Vec<String> {
+        self.vars
+            .iter()
+            .map(|(key, _)| Self::path(key).join("."))
+            .collect()
+    }
+
+    /// Overriding variables and the dotted key each one sets
+    pub fn variables(&self) -> impl Iterator<Item = (&str, String)> {
+        self.vars
+            .iter()
+            .map(|(key, _)| (key.as_str(), Self::path(key).join(".")))
+    }
+
+    /// Key path of an override variable, empty for other variables
+    fn path(variable: &str) -> Vec<String> {
+        let Some(key) = variable.strip_prefix(OVERRIDE_PREFIX) else {
+            return Vec::new();
+        };
+        let parts: Vec<String> = key.split(SEPARATOR).map(str::to_lowercase).collect();
+        if parts.iter().any(String::is_empty) {
+            return Vec::new();
+        }
+        parts
+    }
+
+    /// Insert `value` at `path`, replacing non-dictionary values on the way
+    fn insert(dict: &mut Dict, path: &[String], value: Value) {
+        let Some((key, rest)) = path.split_first() else {
+            return;
+        };
+        if rest.is_empty() {
+            dict.insert(key.clone(), value);
+            return;
+        }
+
+        let entry = dict
+            .entry(key.clone())
+            .or_insert_with(|| Value::Dict(Tag::default(), Dict::new()));
+        if !matches!(entry, Value::Dict(..)) {
+            *entry = Value::Dict(Tag::default(), Dict::new());
+        }
+        if let Value::Dict(_, nested) = entry {
+            Self::insert(nested, rest, value);
+        }
+    }
+}
+
+impl Default for EnvOverrides {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Provider for EnvOverrides {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("EMERGENCY OVERRIDE (SUPERCONFIG_OVERRIDE__)")
+            .interpolater(|_, keys: &[&str]| format!("{OVERRIDE_PREFIX}{}", keys.join(SEPARATOR)))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        for (key, value) in &self.vars {
+            let value = Nested::parse_env_value(value)?;
+            Self::insert(&mut dict, &Self::path(key), value);
+        }
+        Ok(Map::from([(Profile::Default, dict)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Figment;
+
+    fn overrides() -> EnvOverrides {
+        EnvOverrides::from_vars([
+            ("SUPERCONFIG_OVERRIDE__database__port", "5433"),
+            ("SUPERCONFIG_OVERRIDE__LOG_LEVEL", "debug"),
+            ("SUPERCONFIG_OVERRIDE__database__pool__max", "20"),
+            ("SUPERCONFIG_OVERRIDE__", "ignored"),
+            ("SUPERCONFIG_OVERRIDE__broken____key", "ignored"),
+            ("APP_DATABASE_PORT", "ignored"),
+        ])
+    }
+
+    #[test]
+    fn test_keys_are_nested_and_lowercased() {
+        assert_eq!(
+            overrides().keys(),
+            vec!["log_level", "database.pool.max", "database.port"]
+        );
+        assert!(EnvOverrides::from_vars([("APP_PORT", "80")]).is_empty());
+    }
+
+    #[test]
+    fn test_values_are_typed() {
+        let figment = Figment::from(overrides());
+
+        assert_eq!(figment.extract_inner::<u16>("database.port").unwrap(), 5433);
+        assert_eq!(
+            figment.extract_inner::<u32>("database.pool.max").unwrap(),
+            20
+        );
+        assert_eq!(
+            figment.extract_inner::<String>("log_level").unwrap(),
+            "debug"
+        );
+    }
+
+    #[test]
+    fn test_metadata_names_the_variable() {
+        let figment = Figment::from(overrides());
+        let metadata = figment.find_metadata("database.port").unwrap();
+
+        assert!(metadata.name.contains("EMERGENCY OVERRIDE"));
+        assert_eq!(
+            metadata.interpolate(&Profile::Default, &["database", "port"]),
+            "SUPERCONFIG_OVERRIDE__database__port"
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Integrity verification provider for configuration files
+//!
+//! The Verified provider checks a configuration file against a SHA-256 checksum or a
+//! detached minisign (Ed25519) signature *before* the content is parsed. What happens when
+//! verification fails is controlled by a [`VerifyPolicy`]:
+//!
+//! - [`VerifyPolicy::Ignore`] - No verification is performed, behaves exactly like [`Universal`]
+//! - [`VerifyPolicy::Warn`] - The file is still loaded, but a warning is collected by SuperConfig
+//! - [`VerifyPolicy::Enforce`] - The provider returns an error and no data is loaded
+//!
+//! ## Supported Verification Methods
+//!
+//! ```text
+//! Verification::Sha256("9f86d0...")       → digest supplied by the application
+//! Verification::Sha256Sidecar             → digest read from `config.toml.sha256`
+//! Verification::Minisign { public_key }   → signature read from `config.toml.minisig`
+//! ```
+//!
+//! Sidecar checksum files use the `sha256sum` output format (`<hex digest>  <file name>`),
+//! so they can be produced with `sha256sum config.toml > config.toml.sha256`. Signatures
+//! are produced with `minisign -Sm config.toml`.
+//!
+//! The bytes that were verified are the bytes that get parsed - each provider reads and
+//! verifies the file once, so it cannot be swapped between the integrity check and
+//! parsing.
+//!
+//! ## Usage Examples
+//!
+//! ```rust,no_run
+//! use superconfig::{SuperConfig, Verification, Verified, VerifyPolicy};
+//!
+//! let config = SuperConfig::new()
+//!     .merge_validated(
+//!         Verified::file("/etc/myapp/config.toml")
+//!             .verification(Verification::Sha256Sidecar)
+//!             .policy(VerifyPolicy::Enforce),
+//!     );
+//! ```
+
+use super::{encoding, format::Universal};
+use crate::merge::ValidatedProvider;
+use figment::{
+    Error, Metadata, Profile, Provider, Source,
+    value::{Map, Value},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// What to do when a configuration file fails integrity verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyPolicy {
+    /// Skip verification entirely
+    Ignore,
+    /// Load the file anyway and collect a warning
+    #[default]
+    Warn,
+    /// Refuse to load the file
+    Enforce,
+}
+
+/// How a configuration file should be verified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// Compare against a known SHA-256 digest (hex encoded)
+    Sha256(String),
+    /// Compare against the digest stored in `<file>.sha256`
+    Sha256Sidecar,
+    /// Verify the minisign signature stored in `<file>.minisig`
+    Minisign {
+        /// Base64 encoded minisign public key (the second line of `minisign.pub`)
+        public_key: String,
+    },
+}
+
+/// Configuration provider that verifies file integrity before parsing
+///
+/// Parsing is delegated to [`Universal`], so format detection works exactly as it does
+/// for [`SuperConfig::with_file`](crate::SuperConfig::with_file). The file is read and
+/// verified once, on first use, and the provider keeps parsing those bytes afterwards.
+#[derive(Debug, Clone)]
+pub struct Verified {
+    path: PathBuf,
+    verification: Verification,
+    policy: VerifyPolicy,
+    checked: OnceLock<Checked>,
+}
+
+/// File content and verification outcome, kept by the provider once read
+#[derive(Debug, Clone)]
+struct Checked {
+    /// Bytes read from the file, or why they couldn't be read
+    content: Result<Vec<u8>, String>,
+    /// Why verification failed, if it did
+    failure: Option<String>,
+}
+
+impl Checked {
+    /// Why the file couldn't be read or verified, if it couldn't
+    fn error(&self) -> Option<&String> {
+        self.content.as_ref().err().or(self.failure.as_ref())
+    }
+}
+
+impl Verified {
+    /// Create a verified provider for the given file
+    ///
+    /// Defaults to [`Verification::Sha256Sidecar`] with [`VerifyPolicy::Warn`].
+    pub fn file<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            verification: Verification::Sha256Sidecar,
+            policy: VerifyPolicy::default(),
+            checked: OnceLock::new(),
+        }
+    }
+
+    /// Set the verification method
+    pub fn verification(mut self, verification: Verification) -> Self {
+        self.verification = verification;
+        self.checked = OnceLock::new();
+        self
+    }
+
+    /// Set the failure policy
+    pub fn policy(mut self, policy: VerifyPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Get the file path being verified
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the file and verify it, returning the verified content
+    ///
+    /// The file is only read on the first call, and on the first use as a provider;
+    /// later calls return the same outcome.
+    pub fn verify(&self) -> Result<Vec<u8>, String> {
+        let checked = self.checked();
+        match checked.error() {
+            Some(message) => Err(message.clone()),
+            None => checked.content.clone(),
+        }
+    }
+
+    /// Read and verify the file on first use
+    fn checked(&self) -> &Checked {
+        self.checked.get_or_init(|| {
+            let path = self.path.display();
+            match fs::read(&self.path) {
+                Ok(content) => Checked {
+                    failure: self.check(&content).err(),
+                    content: Ok(content),
+                },
+                Err(e) => Checked {
+                    content: Err(format!("Cannot verify {path}: failed to read file: {e}")),
+                    failure: None,
+                },
+            }
+        })
+    }
+
+    /// Verify `content` read from the file
+    fn check(&self, content: &[u8]) -> Result<(), String> {
+        let path = self.path.display();
+        match &self.verification {
+            Verification::Sha256(expected) => Self::check_sha256(content, expected)
+                .map_err(|e| format!("Checksum verification failed for {path}: {e}")),
+            Verification::Sha256Sidecar => {
+                let sidecar = Self::sidecar_path(&self.path, "sha256");
+                let expected = fs::read_to_string(&sidecar).map_err(|e| {
+                    format!(
+                        "Cannot verify {path}: failed to read checksum file {}: {e}",
+                        sidecar.display()
+                    )
+                })?;
+                let expected = expected.split_whitespace().next().unwrap_or_default();
+                Self::check_sha256(content, expected)
+                    .map_err(|e| format!("Checksum verification failed for {path}: {e}"))
+            }
+            Verification::Minisign { public_key } => {
+                let sidecar = Self::sidecar_path(&self.path, "minisig");
+                Self::check_minisign(content, &sidecar, public_key)
+                    .map_err(|e| format!("Signature verification failed for {path}: {e}"))
+            }
+        }
+    }
+
+    /// Compare the SHA-256 digest of `content` against a hex encoded digest
+    fn check_sha256(content: &[u8], expected: &str) -> Result<(), String> {
+        let actual: String = Sha256::digest(content)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        if actual.eq_ignore_ascii_case(expected.trim()) {
+            Ok(())
+        } else {
+            Err(format!("expected {}, got {actual}", expected.trim()))
+        }
+    }
+
+    /// Verify a detached minisign signature
+    fn check_minisign(
+        content: &[u8],
+        signature_path: &Path,
+        public_key: &str,
+    ) -> Result<(), String> {
+        let public_key = minisign_verify::PublicKey::from_base64(public_key.trim())
+            .map_err(|e| format!("invalid public key: {e}"))?;
+        let signature = fs::read_to_string(signature_path).map_err(|e| {
+            format!(
+                "failed to read signature file {}: {e}",
+                signature_path.display()
+            )
+        })?;
+        let signature = minisign_verify::Signature::decode(&signature)
+            .map_err(|e| format!("invalid signature file: {e}"))?;
+
+        public_key
+            .verify(content, &signature, true)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Build a sidecar path by appending an extension (`config.toml` → `config.toml.sha256`)
+    fn sidecar_path(path: &Path, extension: &str) -> PathBuf {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(".");
+        sidecar.push(extension);
+        PathBuf::from(sidecar)
+    }
+}
+
+impl Provider for Verified {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Verified").source(Source::File(self.path.clone()))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
+        if self.policy == VerifyPolicy::Ignore {
+            return Universal::file(&self.path).data();
+        }
+
+        let checked = self.checked();
+        if self.policy == VerifyPolicy::Enforce
+            && let Some(message) = checked.error()
+        {
+            return Err(Error::from(message.clone()));
+        }
+
+        // Under `Warn`, the bytes that failed verification are loaded, and an unreadable
+        // file loads nothing: the failure is reported by `validation_error`
+        let Ok(content) = &checked.content else {
+            return Ok(Map::new());
+        };
+        match encoding::normalize(content) {
+            Ok(normalized) => Universal::from_content(&self.path, &normalized.content).data(),
+            Err(e) => Err(Error::from(format!(
+                "Verified file {} could not be decoded: {e}",
+                self.path.display()
+            ))),
+        }
+    }
+}
+
+impl ValidatedProvider for Verified {
+    fn validation_error(&self) -> Option<Error> {
+        match self.policy {
+            VerifyPolicy::Warn => self.checked().error().cloned().map(Error::from),
+            VerifyPolicy::Ignore | VerifyPolicy::Enforce => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const CONTENT: &str = "[server]\nport = 8080\n";
+    const WRONG_SHA256: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+    fn write_config(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("config.toml");
+        fs::write(&path, CONTENT).unwrap();
+        path
+    }
+
+    fn digest(content: &str) -> String {
+        Sha256::digest(content.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    #[test]
+    fn test_sha256_match_loads_data() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(&dir);
+
+        let provider = Verified::file(&path)
+            .verification(Verification::Sha256(digest(CONTENT)))
+            .policy(VerifyPolicy::Enforce);
+
+        assert!(provider.verify().is_ok());
+        let port: u16 = figment::Figment::from(provider)
+            .extract_inner("server.port")
+            .unwrap();
+        assert_eq!(port, 8080);
+    }
+
+    #[test]
+    fn test_sha256_mismatch_enforce_fails() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(&dir);
+
+        let provider = Verified::file(&path)
+            .verification(Verification::Sha256(WRONG_SHA256.to_string()))
+            .policy(VerifyPolicy::Enforce);
+
+        let err = provider.data().unwrap_err();
+        assert!(err.to_string().contains("Checksum verification failed"));
+        assert!(provider.validation_error().is_none());
+    }
+
+    #[test]
+    fn test_sha256_mismatch_warn_loads_with_warning() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(&dir);
+
+        let provider = Verified::file(&path)
+            .verification(Verification::Sha256(WRONG_SHA256.to_string()))
+            .policy(VerifyPolicy::Warn);
+
+        assert!(provider.validation_error().is_some());
+        let port: u16 = figment::Figment::from(provider)
+            .extract_inner("server.port")
+            .unwrap();
+        assert_eq!(port, 8080);
+    }
+
+    #[test]
+    fn test_ignore_skips_verification() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(&dir);
+
+        let provider = Verified::file(&path).policy(VerifyPolicy::Ignore);
+
+        assert!(provider.validation_error().is_none());
+        assert!(provider.data().is_ok());
+    }
+
+    #[test]
+    fn test_sha256_sidecar() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(&dir);
+        fs::write(
+            dir.path().join("config.toml.sha256"),
+            format!("{}  config.toml\n", digest(CONTENT).to_uppercase()),
+        )
+        .unwrap();
+
+        let provider = Verified::file(&path).policy(VerifyPolicy::Enforce);
+        assert!(provider.verify().is_ok());
+
+        // The verified bytes are parsed, not the file swapped in after verification
+        fs::write(&path, "[server]\nport = 9090\n").unwrap();
+        let port: u16 = figment::Figment::from(provider)
+            .extract_inner("server.port")
+            .unwrap();
+        assert_eq!(port, 8080);
+
+        let provider = Verified::file(&path).policy(VerifyPolicy::Enforce);
+        assert!(provider.data().is_err());
+    }
+
+    #[test]
+    fn test_missing_sidecar_is_a_failure() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(&dir);
+
+        let error = Verified::file(&path).verify().unwrap_err();
+        assert!(error.contains("config.toml.sha256"));
+    }
+
+    #[test]
+    fn test_minisign_signature() {
+        // Test vector from the minisign-verify crate: signature of the bytes "test"
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.txt");
+        fs::write(&path, "test").unwrap();
+        fs::write(
+            dir.path().join("config.txt.minisig"),
+            "untrusted comment: signature from minisign secret key
+RWQf6LRCGA9i59SLOFxz6NxvASXDJeRtuZykwQepbDEGt87ig1BNpWaVWuNrm73YiIiJbq71Wi+dP9eKL8OC351vwIasSSbXxwA=
+trusted comment: timestamp:1555779966\tfile:test
+QtKMXWyYcwdpZAlPF7tE2ENJkRd1ujvKjlj1m9RtHTBnZPa5WKU5uWRs5GoP5M/VqE81QFuMKI5k/SfNQUaOAA==",
+        )
+        .unwrap();
+
+        let minisign = || {
+            Verified::file(&path).verification(Verification::Minisign {
+                public_key: "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3".to_string(),
+            })
+        };
+        assert_eq!(minisign().verify().unwrap(), b"test");
+
+        fs::write(&path, "Test").unwrap();
+        let error = minisign().verify().unwrap_err();
+        assert!(error.contains("Signature verification failed"));
+    }
+
+    #[test]
+    fn test_sidecar_path() {
+        assert_eq!(
+            Verified::sidecar_path(Path::new("/etc/app/config.toml"), "sha256"),
+            PathBuf::from("/etc/app/config.toml.sha256")
+        );
+    }
+}
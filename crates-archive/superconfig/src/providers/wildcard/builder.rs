@@ -1,8 +1,16 @@
 //! Builder pattern for advanced Wildcard provider configuration
 
-use crate::providers::wildcard::{core::Wildcard, discovery::SearchStrategy, sorting::MergeOrder};
+use crate::providers::{
+    duplicates::DuplicateKeyPolicy,
+    wildcard::{
+        core::Wildcard,
+        discovery::{DiscoveryLimits, SearchStrategy, SymlinkPolicy},
+        sorting::MergeOrder,
+    },
+};
 use figment::Error;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 /// Advanced builder for configuring Wildcard providers
 ///
@@ -38,6 +46,11 @@ pub struct WildcardBuilder {
     patterns: Vec<String>,
     search_strategy: Option<SearchStrategy>,
     merge_order: Option<MergeOrder>,
+    duplicate_keys: Option<DuplicateKeyPolicy>,
+    strict: bool,
+    limits: DiscoveryLimits,
+    #[cfg(feature = "parallel")]
+    workers: Option<usize>,
 }
 
 impl WildcardBuilder {
@@ -193,6 +206,111 @@ impl WildcardBuilder {
         self
     }
 
+    /// Set the duplicate key policy
+    ///
+    /// See [`Wildcard::with_duplicate_keys`] for how the policy is applied.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::{DuplicateKeyPolicy, WildcardBuilder};
+    ///
+    /// let builder = WildcardBuilder::new()
+    ///     .duplicate_keys(DuplicateKeyPolicy::Warn);
+    /// ```
+    pub fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = Some(policy);
+        self
+    }
+
+    /// Fail the load when a discovered file or a searched directory can't be read
+    ///
+    /// See [`Wildcard::with_strict`]; by default they are skipped and reported as warnings.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::WildcardBuilder;
+    ///
+    /// let provider = WildcardBuilder::new()
+    ///     .pattern("conf.d/*.toml")?
+    ///     .strict(true)
+    ///     .build()?;
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Skip discovered files larger than `bytes`, reporting each one
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::WildcardBuilder;
+    ///
+    /// let builder = WildcardBuilder::new().max_file_size(1024 * 1024);
+    /// ```
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.limits.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Skip discovered files last modified before `time`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::WildcardBuilder;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let builder =
+    ///     WildcardBuilder::new().modified_since(SystemTime::now() - Duration::from_secs(3600));
+    /// ```
+    pub fn modified_since(mut self, time: SystemTime) -> Self {
+        self.limits.modified_since = Some(time);
+        self
+    }
+
+    /// Set how symbolic links are treated during discovery
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::{SymlinkPolicy, WildcardBuilder};
+    ///
+    /// let builder = WildcardBuilder::new().symlinks(SymlinkPolicy::Skip);
+    /// ```
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.limits.symlinks = policy;
+        self
+    }
+
+    /// Stop discovery after `count` files, reporting that the limit was reached
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::WildcardBuilder;
+    ///
+    /// let builder = WildcardBuilder::new().max_files(500);
+    /// ```
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.limits.max_files = Some(count);
+        self
+    }
+
+    /// Parse the discovered files on a pool of `workers` threads (`parallel` feature)
+    ///
+    /// See [`Wildcard::with_parallel_loading`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::WildcardBuilder;
+    ///
+    /// let builder = WildcardBuilder::new().parallel_loading(4);
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn parallel_loading(mut self, workers: usize) -> Self {
+        self.workers = Some(workers);
+        self
+    }
+
     /// Build the final Wildcard provider
     ///
     /// Construct the Wildcard provider with all configured options.
@@ -236,6 +354,17 @@ impl WildcardBuilder {
             wildcard = wildcard.with_merge_order(order);
         }
 
+        if let Some(policy) = self.duplicate_keys {
+            wildcard = wildcard.with_duplicate_keys(policy);
+        }
+
+        wildcard = wildcard.with_strict(self.strict).with_limits(self.limits);
+
+        #[cfg(feature = "parallel")]
+        if let Some(workers) = self.workers {
+            wildcard = wildcard.with_parallel_loading(workers);
+        }
+
         Ok(wildcard)
     }
 }
@@ -444,6 +573,27 @@ impl WildcardBuilder {
             .collect();
         self.merge_order(MergeOrder::Custom(patterns))
     }
+
+    /// Configure profile merge order
+    ///
+    /// Files of the `active` profile, named after a `pattern` with a `{profile}`
+    /// placeholder, override the shared files; files of other profiles are skipped.
+    /// See [`MergeOrder::Profile`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::WildcardBuilder;
+    ///
+    /// // app-base.toml, then app-prod.toml; app-dev.toml is skipped
+    /// let provider = WildcardBuilder::new()
+    ///     .pattern("./config/*.toml")?
+    ///     .profile("prod", "app-{profile}.toml")
+    ///     .build()?;
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn profile(self, active: &str, pattern: &str) -> Self {
+        self.merge_order(MergeOrder::profile(active, pattern))
+    }
 }
 
 #[cfg(test)]
@@ -1,17 +1,25 @@
 //! Core Wildcard provider implementation for pattern-based configuration discovery
 
 use crate::merge::ValidatedProvider;
+use crate::policy::MergePolicy;
+use crate::provenance::FileOrigins;
 use crate::providers::wildcard::{
-    discovery::SearchStrategy,
+    discovery::{DiscoveryLimits, SearchStrategy},
+    loading::LoadedFile,
     parsing::{build_globset, parse_multiple_patterns},
     sorting::MergeOrder,
 };
+use crate::providers::{
+    Universal,
+    duplicates::{self, DuplicateKeyPolicy},
+};
 use figment::{
     Error, Metadata, Profile, Provider,
     value::{Map, Value},
 };
 use globset::GlobSet;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 /// A unified wildcard configuration provider using globset patterns
 ///
@@ -68,8 +76,25 @@ pub struct Wildcard {
     globset: GlobSet,
     /// Search strategy for file discovery
     search_strategy: SearchStrategy,
+    /// Constraints on the discovered files
+    limits: DiscoveryLimits,
     /// Merge order for multiple files
     merge_order: MergeOrder,
+    /// How keys defined more than once are resolved
+    duplicate_keys: DuplicateKeyPolicy,
+    /// Keys holding filesystem paths, resolved against the file that set them
+    path_keys: Vec<String>,
+    /// Merge policies applied between discovered files, by key path pattern
+    merge_policies: Vec<(String, MergePolicy)>,
+    /// Which discovered file set each key, for the origin of its keys
+    origins: FileOrigins,
+    /// Whether files and directories that can't be read fail the load
+    strict: bool,
+    /// What the last load skipped, shared between clones
+    skipped: Arc<Mutex<Vec<String>>>,
+    /// Worker threads parsing the discovered files, when loading in parallel
+    #[cfg(feature = "parallel")]
+    workers: Option<usize>,
     /// Original patterns for metadata
     patterns: Vec<String>,
     /// Cached validation error (if any)
@@ -172,7 +197,16 @@ impl Wildcard {
             Ok((search_strategy, globset)) => Self {
                 globset,
                 search_strategy,
+                limits: DiscoveryLimits::default(),
                 merge_order: MergeOrder::default(),
+                duplicate_keys: DuplicateKeyPolicy::default(),
+                path_keys: Vec::new(),
+                merge_policies: Vec::new(),
+                origins: FileOrigins::default(),
+                strict: false,
+                skipped: Arc::default(),
+                #[cfg(feature = "parallel")]
+                workers: None,
                 patterns: pattern_strings,
                 validation_error: None,
             },
@@ -180,7 +214,16 @@ impl Wildcard {
                 // Use safe defaults when validation fails, store error for warning
                 globset: globset::GlobSetBuilder::new().build().unwrap(),
                 search_strategy: SearchStrategy::Current,
+                limits: DiscoveryLimits::default(),
                 merge_order: MergeOrder::default(),
+                duplicate_keys: DuplicateKeyPolicy::default(),
+                path_keys: Vec::new(),
+                merge_policies: Vec::new(),
+                origins: FileOrigins::default(),
+                strict: false,
+                skipped: Arc::default(),
+                #[cfg(feature = "parallel")]
+                workers: None,
                 patterns: pattern_strings,
                 validation_error: Some(error.to_string()),
             },
@@ -220,6 +263,107 @@ impl Wildcard {
         self
     }
 
+    /// Set the duplicate key policy
+    ///
+    /// Applies to keys repeated within a single JSON or YAML file and to keys defined
+    /// by more than one discovered file, which all share this provider's precedence.
+    /// With [`DuplicateKeyPolicy::FirstWins`] files are merged in reverse order, so the
+    /// first file in the merge order takes priority.
+    ///
+    /// # Arguments
+    /// * `policy` - The duplicate key policy to apply
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::{DuplicateKeyPolicy, Wildcard};
+    ///
+    /// let provider = Wildcard::from_pattern("conf.d/*.toml")
+    ///     .with_duplicate_keys(DuplicateKeyPolicy::Error);
+    /// ```
+    pub fn with_duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Normalize the values of `keys` as filesystem paths in each file
+    ///
+    /// Relative paths are resolved against the directory of the file that set them, as
+    /// [`SuperConfig::with_path_key`](crate::SuperConfig::with_path_key) does for single
+    /// files. Merging the provider loses track of which file set a key, so this has to be
+    /// done by the provider itself.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::Wildcard;
+    ///
+    /// let provider = Wildcard::from_pattern("conf.d/*.toml")
+    ///     .with_path_keys(["tls.cert", "tls.key"]);
+    /// ```
+    pub fn with_path_keys<I, K>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        self.path_keys.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// Merge the keys matching `pattern` by `policy` between the discovered files
+    ///
+    /// Like [`SuperConfig::with_merge_policy`](crate::SuperConfig::with_merge_policy),
+    /// which doesn't reach into a provider's own merges: later files otherwise replace
+    /// the arrays of earlier ones.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::{MergePolicy, Wildcard};
+    ///
+    /// let provider = Wildcard::from_pattern("conf.d/*.toml")
+    ///     .with_merge_policy("plugins", MergePolicy::Unique)
+    ///     .with_merge_policy("servers.*", MergePolicy::Replace);
+    /// ```
+    pub fn with_merge_policy(mut self, pattern: &str, policy: MergePolicy) -> Self {
+        self.merge_policies.push((pattern.to_string(), policy));
+        self
+    }
+
+    /// Fail the load when a discovered file or a searched directory can't be read
+    ///
+    /// By default such files and directories are skipped and the rest loaded: each one is
+    /// described in [`skipped_files`](Self::skipped_files), and reported as a warning in
+    /// [`SuperConfig::warnings`](crate::SuperConfig::warnings) when the provider is merged
+    /// with [`merge_validated`](crate::SuperConfig::merge_validated). In strict mode the
+    /// provider's data is an error listing them instead.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::Wildcard;
+    ///
+    /// let provider = Wildcard::from_pattern("conf.d/*.toml").with_strict(true);
+    /// ```
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Parse the discovered files on a pool of `workers` threads (`parallel` feature)
+    ///
+    /// Speeds up directories holding many files; `0` uses one thread per CPU. The parsed
+    /// files are still merged one by one in the merge order, so the result is the same
+    /// as loading them sequentially.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::Wildcard;
+    ///
+    /// let provider = Wildcard::from_pattern("plugins.d/*.toml").with_parallel_loading(8);
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn with_parallel_loading(mut self, workers: usize) -> Self {
+        self.workers = Some(workers);
+        self
+    }
+
     /// Set the search strategy for file discovery
     ///
     /// Override the automatically determined search strategy with a custom one.
@@ -244,21 +388,86 @@ impl Wildcard {
         self
     }
 
+    /// Constrain the files discovery accepts: size, age, symlinks and count
+    ///
+    /// See [`DiscoveryLimits`]. Useful before searching trees the application doesn't
+    /// control, such as user plugin directories.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::{DiscoveryLimits, SymlinkPolicy, Wildcard};
+    ///
+    /// let provider = Wildcard::from_pattern("plugins/**/*.toml").with_limits(DiscoveryLimits {
+    ///     max_file_size: Some(64 * 1024),
+    ///     max_files: Some(1000),
+    ///     symlinks: SymlinkPolicy::Skip,
+    ///     ..DiscoveryLimits::default()
+    /// });
+    /// ```
+    pub fn with_limits(mut self, limits: DiscoveryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Get the current search strategy
     pub fn search_strategy(&self) -> &SearchStrategy {
         &self.search_strategy
     }
 
+    /// Get the current discovery limits
+    pub fn limits(&self) -> &DiscoveryLimits {
+        &self.limits
+    }
+
     /// Get the current merge order
     pub fn merge_order(&self) -> &MergeOrder {
         &self.merge_order
     }
 
+    /// Get the current duplicate key policy
+    pub fn duplicate_keys(&self) -> DuplicateKeyPolicy {
+        self.duplicate_keys
+    }
+
     /// Get the original patterns
     pub fn patterns(&self) -> &[String] {
         &self.patterns
     }
 
+    /// The compiled patterns discovered files must match
+    #[cfg(feature = "watch")]
+    pub(crate) fn globset(&self) -> &GlobSet {
+        &self.globset
+    }
+
+    /// Whether files and directories that can't be read fail the load
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// The files and directories the last load skipped, each with the reason
+    ///
+    /// Empty until the provider's data is loaded. Clones of the provider share the list,
+    /// so a clone kept before merging reports what the merged provider skipped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::Wildcard;
+    /// use figment::Figment;
+    ///
+    /// let provider = Wildcard::from_pattern("conf.d/*.toml");
+    /// let figment = Figment::new().merge(provider.clone());
+    /// for skipped in provider.skipped_files() {
+    ///     eprintln!("Warning: skipped {skipped}");
+    /// }
+    /// ```
+    pub fn skipped_files(&self) -> Vec<String> {
+        self.skipped
+            .lock()
+            .map(|skipped| skipped.clone())
+            .unwrap_or_default()
+    }
+
     /// Check if the provider has any validation errors
     ///
     /// Returns `Some(error)` if the provider was created with invalid patterns,
@@ -308,41 +517,137 @@ impl Wildcard {
     /// # Returns
     /// Vector of file paths in merge order (lowest to highest priority)
     pub fn discover_files(&self) -> Vec<PathBuf> {
-        let mut files = self.search_strategy.discover_files(&self.globset);
-        files = self.merge_order.sort_files(files);
+        self.discover_files_reporting(&mut Vec::new())
+    }
+
+    /// Discover and sort files, describing what couldn't be read in `errors`
+    fn discover_files_reporting(&self, errors: &mut Vec<String>) -> Vec<PathBuf> {
+        let files =
+            self.search_strategy
+                .discover_files_reporting(&self.globset, &self.limits, errors);
+        self.merge_order.sort_files(files)
+    }
+
+    /// Parse the discovered files, on the worker pool when parallel loading is enabled
+    fn load_files(&self, files: &[PathBuf]) -> Vec<LoadedFile> {
+        #[cfg(feature = "parallel")]
+        if let Some(workers) = self.workers {
+            return super::loading::load_parallel(files, self.duplicate_keys, workers);
+        }
         files
+            .iter()
+            .map(|file| LoadedFile::load(file, self.duplicate_keys))
+            .collect()
+    }
+
+    /// Describe every duplicated key: within each file, then across files
+    fn duplicate_key_report(&self, files: &[PathBuf]) -> Vec<String> {
+        let mut report = Vec::new();
+        let mut sources = Vec::new();
+
+        for file in files {
+            let provider = Universal::file_with_duplicate_keys(file, DuplicateKeyPolicy::Warn);
+            report.extend(provider.warning().map(str::to_string));
+            if let Ok(data) = provider.data() {
+                sources.push((file.clone(), data));
+            }
+        }
+
+        report.extend(duplicates::conflicts(&sources));
+        report
     }
 }
 
 impl Provider for Wildcard {
     fn metadata(&self) -> Metadata {
-        Metadata::named("Wildcard Provider")
+        self.origins.metadata("Wildcard Provider")
     }
 
     fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
-        let files = self.discover_files();
+        let mut skipped = Vec::new();
+        let files = self.discover_files_reporting(&mut skipped);
+
+        if self.duplicate_keys == DuplicateKeyPolicy::Error {
+            let report = self.duplicate_key_report(&files);
+            if !report.is_empty() {
+                return Err(Error::from(format!(
+                    "Duplicate keys in {}: {}",
+                    self.patterns.join(", "),
+                    report.join("; ")
+                )));
+            }
+        }
 
-        if files.is_empty() {
+        // Earlier files must be merged last for them to take priority
+        let files: Vec<PathBuf> = if self.duplicate_keys == DuplicateKeyPolicy::FirstWins {
+            files.into_iter().rev().collect()
+        } else {
+            files
+        };
+
+        let mut loaded = self.load_files(&files);
+        loaded.retain(|file| match file.failure() {
+            Some(failure) => {
+                skipped.push(failure);
+                false
+            }
+            None => true,
+        });
+        if let Ok(mut last) = self.skipped.lock() {
+            last.clone_from(&skipped);
+        }
+        if self.strict && !skipped.is_empty() {
+            return Err(Error::from(format!(
+                "Cannot load {}: {}",
+                self.patterns.join(", "),
+                skipped.join("; ")
+            )));
+        }
+
+        if loaded.is_empty() {
             return Ok(Map::new());
         }
 
         // Use SuperConfig's existing merge logic for proper sequential array processing
-        let mut super_config = crate::SuperConfig::new();
+        let mut super_config = self
+            .path_keys
+            .iter()
+            .fold(crate::SuperConfig::new(), |config, key| {
+                config.with_path_key(key)
+            });
+        super_config = self
+            .merge_policies
+            .iter()
+            .fold(super_config, |config, (pattern, policy)| {
+                config.with_merge_policy(pattern, *policy)
+            });
 
         // Chain merge each file in order - SuperConfig.merge() handles array operations correctly
-        for file_path in files {
-            let provider = crate::providers::Universal::file(&file_path);
-            super_config = super_config.merge(provider);
+        for file in loaded {
+            super_config = super_config.merge(file);
         }
 
         // Extract the final merged data
-        super_config.figment.data()
+        self.origins.update(&super_config);
+        crate::provenance::untagged(super_config.figment.data()?)
     }
 }
 
 impl ValidatedProvider for Wildcard {
     fn validation_error(&self) -> Option<Error> {
-        self.has_errors()
+        if let Some(error) = self.has_errors() {
+            return Some(error);
+        }
+
+        let mut report: Vec<String> = self
+            .skipped_files()
+            .into_iter()
+            .map(|skipped| format!("skipped {skipped}"))
+            .collect();
+        if self.duplicate_keys == DuplicateKeyPolicy::Warn {
+            report.extend(self.duplicate_key_report(&self.discover_files()));
+        }
+        (!report.is_empty()).then(|| Error::from(report.join("; ")))
     }
 }
 
@@ -555,6 +860,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strict_and_lenient_loading() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.toml"), "host = \"a\"").unwrap();
+        fs::write(temp_dir.path().join("b.toml"), "port = = 1").unwrap();
+        fs::write(temp_dir.path().join("c.toml"), "port = 3").unwrap();
+        let pattern = format!("{}/*.toml", temp_dir.path().display());
+
+        let lenient = Wildcard::from_pattern(&pattern);
+        assert!(lenient.skipped_files().is_empty());
+        let data = lenient.data().unwrap();
+        assert_eq!(data[&Profile::Default]["host"].as_str(), Some("a"));
+        assert_eq!(data[&Profile::Default]["port"].to_i128(), Some(3));
+        let skipped = lenient.skipped_files();
+        assert_eq!(skipped.len(), 1);
+        assert!(
+            skipped[0].contains("b.toml: TOML parse error"),
+            "{skipped:?}"
+        );
+        let warning = lenient.validation_error().unwrap().to_string();
+        assert!(warning.starts_with("skipped "), "{warning}");
+
+        let strict = Wildcard::from_pattern(&pattern).with_strict(true);
+        assert!(strict.is_strict());
+        let error = strict.data().unwrap_err().to_string();
+        assert!(error.starts_with("Cannot load "), "{error}");
+        assert!(error.contains("b.toml: TOML parse error"), "{error}");
+
+        fs::remove_file(temp_dir.path().join("b.toml")).unwrap();
+        assert!(strict.data().is_ok());
+        assert!(strict.skipped_files().is_empty());
+    }
+
     #[test]
     fn test_hierarchical_convenience() {
         let provider = Wildcard::hierarchical("config", "myapp");
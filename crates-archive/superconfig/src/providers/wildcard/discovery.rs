@@ -1,7 +1,9 @@
 //! File discovery strategies for wildcard configuration loading
 
 use globset::GlobSet;
-use std::path::PathBuf;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
 /// Defines how to discover files for wildcard matching
@@ -160,40 +162,186 @@ impl SearchStrategy {
     ///
     /// # Error Handling
     /// - Directories that don't exist are silently skipped
-    /// - Files that can't be accessed are silently skipped
-    /// - Permission errors during traversal are logged but don't fail the operation
+    /// - Directories and entries that can't be read are skipped; a
+    ///   [strict](crate::Wildcard::with_strict) Wildcard provider fails on them instead
     pub fn discover_files(&self, glob_set: &GlobSet) -> Vec<PathBuf> {
+        self.discover_files_reporting(glob_set, &DiscoveryLimits::default(), &mut Vec::new())
+    }
+
+    /// Discover files, describing each directory or entry that couldn't be read in `errors`
+    pub(crate) fn discover_files_reporting(
+        &self,
+        glob_set: &GlobSet,
+        limits: &DiscoveryLimits,
+        errors: &mut Vec<String>,
+    ) -> Vec<PathBuf> {
         match self {
-            SearchStrategy::Directories(dirs) => discover_in_directories(dirs, glob_set),
+            SearchStrategy::Directories(dirs) => {
+                discover_in_directories(dirs, glob_set, limits, errors)
+            }
             SearchStrategy::Recursive { roots, max_depth } => {
-                discover_recursive(roots, glob_set, *max_depth)
+                discover_recursive(roots, glob_set, *max_depth, limits, errors)
+            }
+            SearchStrategy::Current => {
+                discover_in_directories(&[PathBuf::from(".")], glob_set, limits, errors)
             }
-            SearchStrategy::Current => discover_in_directories(&[PathBuf::from(".")], glob_set),
             SearchStrategy::Custom(discovery_fn) => {
                 let all_files = discovery_fn();
-                filter_files_by_globset(&all_files, glob_set)
+                filter_files_by_globset(&all_files, glob_set, limits, errors)
             }
         }
     }
 }
 
+/// How discovery treats symbolic links
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// Ignore every symbolic link
+    Skip,
+    /// Load symlinked files, but don't descend into symlinked directories
+    #[default]
+    Files,
+    /// Load symlinked files and descend into symlinked directories; loops are skipped
+    Follow,
+}
+
+/// Constraints on the files discovery accepts, for untrusted or very large trees
+///
+/// Files over `max_file_size` and discovery stopping at `max_files` are reported like
+/// unreadable files: skipped with a warning, or failing a
+/// [strict](crate::Wildcard::with_strict) Wildcard provider. Files modified before
+/// `modified_since` and links skipped by the [`SymlinkPolicy`] are left out silently.
+///
+/// # Examples
+/// ```rust
+/// use superconfig::{DiscoveryLimits, SymlinkPolicy, Wildcard};
+/// use std::time::{Duration, SystemTime};
+///
+/// let provider = Wildcard::from_pattern("plugins/**/*.toml").with_limits(DiscoveryLimits {
+///     max_file_size: Some(1024 * 1024),
+///     max_files: Some(500),
+///     modified_since: Some(SystemTime::now() - Duration::from_secs(86_400)),
+///     symlinks: SymlinkPolicy::Skip,
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DiscoveryLimits {
+    /// Skip files larger than this many bytes
+    pub max_file_size: Option<u64>,
+    /// Skip files last modified before this time
+    pub modified_since: Option<SystemTime>,
+    /// How symbolic links are treated
+    pub symlinks: SymlinkPolicy,
+    /// Stop discovery after this many files; directories are read in file name order,
+    /// so the same files are kept each time
+    pub max_files: Option<usize>,
+}
+
+/// Collects the discovered files that match the patterns and pass the limits
+struct Collector<'a> {
+    glob_set: &'a GlobSet,
+    limits: &'a DiscoveryLimits,
+    errors: &'a mut Vec<String>,
+    files: Vec<PathBuf>,
+}
+
+impl<'a> Collector<'a> {
+    fn new(
+        glob_set: &'a GlobSet,
+        limits: &'a DiscoveryLimits,
+        errors: &'a mut Vec<String>,
+    ) -> Self {
+        Self {
+            glob_set,
+            limits,
+            errors,
+            files: Vec::new(),
+        }
+    }
+
+    /// Consider `path`, returning `false` once the file limit is reached
+    fn offer(&mut self, path: &Path, is_symlink: bool) -> bool {
+        if !self.glob_set.is_match(path)
+            || (is_symlink && self.limits.symlinks == SymlinkPolicy::Skip)
+        {
+            return true;
+        }
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return true;
+        };
+        if !metadata.is_file() {
+            return true;
+        }
+        if let Some(max) = self.limits.max_file_size
+            && metadata.len() > max
+        {
+            self.errors.push(format!(
+                "{} is {} bytes, over the {max} byte limit",
+                path.display(),
+                metadata.len()
+            ));
+            return true;
+        }
+        if let Some(since) = self.limits.modified_since
+            && metadata.modified().is_ok_and(|modified| modified < since)
+        {
+            return true;
+        }
+
+        if self
+            .limits
+            .max_files
+            .is_some_and(|max| self.files.len() >= max)
+        {
+            self.errors.push(format!(
+                "file limit of {} reached at {}; remaining files ignored",
+                self.files.len(),
+                path.display()
+            ));
+            return false;
+        }
+        self.files.push(path.to_path_buf());
+        true
+    }
+}
+
 /// Discover files in specific directories (non-recursive)
-fn discover_in_directories(directories: &[PathBuf], glob_set: &GlobSet) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-
-    for dir in directories {
-        if let Ok(entries) = std::fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() && glob_set.is_match(&path) {
-                    files.push(path);
-                }
+fn discover_in_directories(
+    directories: &[PathBuf],
+    glob_set: &GlobSet,
+    limits: &DiscoveryLimits,
+    errors: &mut Vec<String>,
+) -> Vec<PathBuf> {
+    let mut collector = Collector::new(glob_set, limits, errors);
+
+    'dirs: for dir in directories {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            // Silently skip directories that don't exist
+            Err(error) if error.kind() == ErrorKind::NotFound => continue,
+            Err(error) => {
+                collector.errors.push(read_error(dir, &error));
+                continue;
+            }
+        };
+        let mut entries: Vec<_> = entries
+            .filter_map(|entry| {
+                entry
+                    .map_err(|error| collector.errors.push(read_error(dir, &error)))
+                    .ok()
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let is_symlink = entry.file_type().is_ok_and(|kind| kind.is_symlink());
+            if !collector.offer(&entry.path(), is_symlink) {
+                break 'dirs;
             }
         }
-        // Silently skip directories that don't exist or can't be read
     }
 
-    files
+    collector.files
 }
 
 /// Discover files recursively from root directories
@@ -201,34 +349,65 @@ fn discover_recursive(
     roots: &[PathBuf],
     glob_set: &GlobSet,
     max_depth: Option<usize>,
+    limits: &DiscoveryLimits,
+    errors: &mut Vec<String>,
 ) -> Vec<PathBuf> {
-    let mut files = Vec::new();
+    let mut collector = Collector::new(glob_set, limits, errors);
 
-    for root in roots {
-        let mut walker = WalkDir::new(root);
+    'roots: for root in roots {
+        let mut walker = WalkDir::new(root)
+            .follow_links(limits.symlinks == SymlinkPolicy::Follow)
+            .sort_by_file_name();
 
         if let Some(depth) = max_depth {
             walker = walker.max_depth(depth);
         }
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.is_file() && glob_set.is_match(path) {
-                files.push(path.to_path_buf());
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    let path = error.path().unwrap_or(root);
+                    match error.io_error() {
+                        // Silently skip roots that don't exist
+                        Some(io) if io.kind() == ErrorKind::NotFound => {}
+                        Some(io) => collector.errors.push(read_error(path, io)),
+                        None => collector
+                            .errors
+                            .push(format!("cannot read {}: {error}", path.display())),
+                    }
+                    continue;
+                }
+            };
+            if !collector.offer(entry.path(), entry.path_is_symlink()) {
+                break 'roots;
             }
         }
     }
 
-    files
+    collector.files
+}
+
+/// Describe an I/O error reading `path`
+fn read_error(path: &Path, error: &std::io::Error) -> String {
+    format!("cannot read {}: {error}", path.display())
 }
 
 /// Filter a list of files against a glob set
-fn filter_files_by_globset(files: &[PathBuf], glob_set: &GlobSet) -> Vec<PathBuf> {
-    files
-        .iter()
-        .filter(|path| path.is_file() && glob_set.is_match(path))
-        .cloned()
-        .collect()
+fn filter_files_by_globset(
+    files: &[PathBuf],
+    glob_set: &GlobSet,
+    limits: &DiscoveryLimits,
+    errors: &mut Vec<String>,
+) -> Vec<PathBuf> {
+    let mut collector = Collector::new(glob_set, limits, errors);
+    for path in files {
+        let is_symlink = path.symlink_metadata().is_ok_and(|meta| meta.is_symlink());
+        if !collector.offer(path, is_symlink) {
+            break;
+        }
+    }
+    collector.files
 }
 
 #[cfg(test)]
@@ -256,7 +435,12 @@ mod tests {
         fs::write(dir_path.join("readme.txt"), "test").unwrap();
 
         let glob_set = create_test_globset();
-        let files = discover_in_directories(&[dir_path.to_path_buf()], &glob_set);
+        let files = discover_in_directories(
+            &[dir_path.to_path_buf()],
+            &glob_set,
+            &DiscoveryLimits::default(),
+            &mut Vec::new(),
+        );
 
         assert_eq!(files.len(), 2); // Only .toml and .yaml files
         assert!(
@@ -280,7 +464,13 @@ mod tests {
         fs::write(root_path.join("ignore.txt"), "test").unwrap();
 
         let glob_set = create_test_globset();
-        let files = discover_recursive(&[root_path.to_path_buf()], &glob_set, None);
+        let files = discover_recursive(
+            &[root_path.to_path_buf()],
+            &glob_set,
+            None,
+            &DiscoveryLimits::default(),
+            &mut Vec::new(),
+        );
 
         assert_eq!(files.len(), 2); // Only .toml and .yaml files
         assert!(files.iter().any(|p| p.file_name().unwrap() == "root.toml"));
@@ -303,7 +493,13 @@ mod tests {
         let glob_set = create_test_globset();
 
         // Max depth 2 should find root and sub, but not deep
-        let files = discover_recursive(&[root_path.to_path_buf()], &glob_set, Some(2));
+        let files = discover_recursive(
+            &[root_path.to_path_buf()],
+            &glob_set,
+            Some(2),
+            &DiscoveryLimits::default(),
+            &mut Vec::new(),
+        );
 
         assert_eq!(files.len(), 2);
         assert!(files.iter().any(|p| p.file_name().unwrap() == "root.toml"));
@@ -311,6 +507,93 @@ mod tests {
         assert!(!files.iter().any(|p| p.file_name().unwrap() == "deep.toml"));
     }
 
+    #[test]
+    fn test_size_and_count_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["a.toml", "b.toml", "c.toml"] {
+            fs::write(temp_dir.path().join(name), "x = 1").unwrap();
+        }
+        fs::write(temp_dir.path().join("big.toml"), "x = 1\n".repeat(100)).unwrap();
+        let dirs = [temp_dir.path().to_path_buf()];
+        let glob_set = create_test_globset();
+
+        let mut errors = Vec::new();
+        let limits = DiscoveryLimits {
+            max_file_size: Some(64),
+            ..DiscoveryLimits::default()
+        };
+        let files = discover_in_directories(&dirs, &glob_set, &limits, &mut errors);
+        assert_eq!(files.len(), 3);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("big.toml is 600 bytes, over the 64 byte limit"));
+
+        let mut errors = Vec::new();
+        let limits = DiscoveryLimits {
+            max_files: Some(2),
+            ..DiscoveryLimits::default()
+        };
+        let files = discover_recursive(&dirs, &glob_set, None, &limits, &mut errors);
+        let names: Vec<_> = files.iter().map(|p| p.file_name().unwrap()).collect();
+        assert_eq!(names, ["a.toml", "b.toml"]);
+        assert!(errors[0].starts_with("file limit of 2 reached at "));
+    }
+
+    #[test]
+    fn test_modified_since() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("old.toml"), "x = 1").unwrap();
+        let glob_set = create_test_globset();
+        let dirs = [temp_dir.path().to_path_buf()];
+
+        let future = std::time::SystemTime::now() + std::time::Duration::from_secs(3600);
+        let limits = DiscoveryLimits {
+            modified_since: Some(future),
+            ..DiscoveryLimits::default()
+        };
+        let mut errors = Vec::new();
+        assert!(discover_in_directories(&dirs, &glob_set, &limits, &mut errors).is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policies() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("linked.toml"), "x = 1").unwrap();
+        let root = temp_dir.path().join("root");
+        fs::create_dir(&root).unwrap();
+        fs::write(root.join("plain.toml"), "x = 1").unwrap();
+        std::os::unix::fs::symlink(target.join("linked.toml"), root.join("file.toml")).unwrap();
+        std::os::unix::fs::symlink(&target, root.join("dir")).unwrap();
+        let glob_set = create_test_globset();
+
+        let names = |symlinks| {
+            let limits = DiscoveryLimits {
+                symlinks,
+                ..DiscoveryLimits::default()
+            };
+            let files = discover_recursive(
+                std::slice::from_ref(&root),
+                &glob_set,
+                None,
+                &limits,
+                &mut Vec::new(),
+            );
+            files
+                .iter()
+                .map(|p| p.strip_prefix(&root).unwrap().display().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(SymlinkPolicy::Skip), ["plain.toml"]);
+        assert_eq!(names(SymlinkPolicy::Files), ["file.toml", "plain.toml"]);
+        assert_eq!(
+            names(SymlinkPolicy::Follow),
+            ["dir/linked.toml", "file.toml", "plain.toml"]
+        );
+    }
+
     #[test]
     fn test_custom_discovery() {
         let custom_files = vec![
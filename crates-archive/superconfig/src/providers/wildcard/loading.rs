@@ -0,0 +1,111 @@
+//! Parsing discovered files ahead of merging, optionally on a pool of worker threads
+//!
+//! Parsing dominates the time a Wildcard provider spends on large directories, and each
+//! file parses independently of the others. With the `parallel` feature,
+//! [`Wildcard::with_parallel_loading`](crate::Wildcard::with_parallel_loading) parses
+//! the files on a bounded rayon pool; the results keep the merge order, so they are
+//! merged exactly as if the files had been parsed one by one.
+
+use crate::providers::{Universal, duplicates::DuplicateKeyPolicy};
+use figment::{
+    Error, Metadata, Profile, Provider,
+    value::{Dict, Map},
+};
+use std::path::{Path, PathBuf};
+
+/// A parsed file, merged in place of the Universal provider that parsed it
+pub(crate) struct LoadedFile {
+    path: PathBuf,
+    metadata: Metadata,
+    profile: Option<Profile>,
+    data: Result<Map<Profile, Dict>, Error>,
+}
+
+impl LoadedFile {
+    /// Parse `path` as the Wildcard provider would merge it
+    pub(crate) fn load(path: &Path, duplicate_keys: DuplicateKeyPolicy) -> Self {
+        let provider = Universal::file_with_duplicate_keys(path, duplicate_keys);
+        Self {
+            path: path.to_path_buf(),
+            metadata: provider.metadata(),
+            profile: provider.profile(),
+            data: provider.data(),
+        }
+    }
+
+    /// Why the file couldn't be loaded, naming it, if it couldn't
+    pub(crate) fn failure(&self) -> Option<String> {
+        let error = self.data.as_ref().err()?;
+        Some(format!("{}: {error}", self.path.display()))
+    }
+}
+
+impl Provider for LoadedFile {
+    fn metadata(&self) -> Metadata {
+        self.metadata.clone()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        self.data.clone()
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.profile.clone()
+    }
+}
+
+/// Parse `files` on at most `workers` threads, returning them in the order given
+///
+/// `workers == 0` uses one thread per CPU. Falls back to parsing on the calling thread
+/// when the pool can't be started.
+#[cfg(feature = "parallel")]
+pub(crate) fn load_parallel(
+    files: &[PathBuf],
+    duplicate_keys: DuplicateKeyPolicy,
+    workers: usize,
+) -> Vec<LoadedFile> {
+    use rayon::prelude::*;
+
+    let load = || {
+        files
+            .par_iter()
+            .map(|file| LoadedFile::load(file, duplicate_keys))
+            .collect()
+    };
+    match rayon::ThreadPoolBuilder::new().num_threads(workers).build() {
+        Ok(pool) => pool.install(load),
+        Err(_) => files
+            .iter()
+            .map(|file| LoadedFile::load(file, duplicate_keys))
+            .collect(),
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parallel_loading_keeps_file_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let files: Vec<PathBuf> = (0..64)
+            .map(|i| {
+                let path = temp_dir.path().join(format!("{i:02}.toml"));
+                std::fs::write(&path, format!("index = {i}")).unwrap();
+                path
+            })
+            .collect();
+
+        let loaded = load_parallel(&files, DuplicateKeyPolicy::LastWins, 4);
+        assert_eq!(loaded.len(), files.len());
+        for (i, (file, loaded)) in files.iter().zip(&loaded).enumerate() {
+            assert_eq!(
+                loaded.metadata().source.unwrap().file_path(),
+                Some(file.as_path())
+            );
+            let data = loaded.data().unwrap();
+            assert_eq!(data[&Profile::Default]["index"].to_i128(), Some(i as i128));
+        }
+    }
+}
@@ -10,6 +10,8 @@
 //! - **[`WildcardBuilder`]**: Advanced builder pattern for complex configurations
 //! - **[`SearchStrategy`]**: Defines how files are discovered (directory, recursive, etc.)
 //! - **[`MergeOrder`]**: Controls the order in which multiple files are merged
+//! - **`WildcardWatcher`**: Re-discovers and re-merges the files when their directories
+//!   change (`watch` feature, see the `watch` module)
 //!
 //! # Quick Start
 //!
@@ -98,6 +100,15 @@
 //!     ]));
 //! ```
 //!
+//! ## Profile Merge Ordering
+//! ```rust
+//! use superconfig::{Wildcard, MergeOrder};
+//!
+//! // app-base.toml, then app-prod.toml; files of other profiles are skipped
+//! let provider = Wildcard::from_pattern("./config/*.toml")
+//!     .with_merge_order(MergeOrder::profile("prod", "app-{profile}.toml"));
+//! ```
+//!
 //! ## Builder Pattern for Complex Scenarios
 //! ```rust
 //! use superconfig::{WildcardBuilder, SearchStrategy, MergeOrder};
@@ -194,27 +205,38 @@
 //! - **Alphabetical sorting**: O(n log n) - fastest and most predictable
 //! - **Size/time-based sorting**: O(n log n) + O(n) for metadata access
 //! - **Custom pattern sorting**: O(n log n) + O(n × m) where m is number of patterns
-//! - **Recursive search**: Can be slow on deep trees - use `max_depth` to limit
+//! - **Recursive search**: Can be slow on deep trees - use `max_depth` to limit, and
+//!   [`DiscoveryLimits`] to bound file sizes, file counts and symlink traversal
 //! - **Directory search**: Fast for flat structures
+//! - **Many files**: parsing dominates; with the `parallel` feature,
+//!   `Wildcard::with_parallel_loading` parses files on a bounded thread pool while
+//!   keeping the merge order
 //!
 //! # Error Handling
 //!
 //! - Invalid glob patterns return `globset::Error`
 //! - Non-existent directories are silently skipped
-//! - Unparseable configuration files are silently skipped
-//! - Permission errors during file traversal are logged but don't fail the operation
+//! - Files that can't be read or parsed, and directories that can't be read, are skipped
+//!   and listed by `Wildcard::skipped_files`; merged with `SuperConfig::merge_validated`
+//!   they are reported in `SuperConfig::warnings`
+//! - With `.with_strict(true)` (or `WildcardBuilder::strict`), any of them fails the load
 
 pub mod builder;
 pub mod core;
 pub mod discovery;
+mod loading;
 pub mod parsing;
 pub mod sorting;
+#[cfg(feature = "watch")]
+pub mod watch;
 
 // Re-export the main types for convenience
 pub use builder::WildcardBuilder;
 pub use core::Wildcard;
-pub use discovery::SearchStrategy;
+pub use discovery::{DiscoveryLimits, SearchStrategy, SymlinkPolicy};
 pub use sorting::MergeOrder;
+#[cfg(feature = "watch")]
+pub use watch::{WatchBuilder, WildcardEvent, WildcardWatcher};
 
 // Re-export figment error for consistency
 pub use figment::Error;
@@ -173,6 +173,42 @@ pub enum MergeOrder {
     /// - When search strategy already returns files in the correct order
     /// - Simple configurations where explicit ordering isn't needed
     AsDiscovered,
+
+    /// Profile files in the order Figment merges profiles, skipping inactive profiles
+    ///
+    /// `pattern` is a filename pattern holding a `{profile}` placeholder, such as
+    /// `"*-{profile}.toml"` or `"config.{profile}.*"`. A file matching it belongs to the
+    /// profile the placeholder matched, compared case-insensitively:
+    ///
+    /// 1. Files that don't match the pattern, and files of the `base`, `common` and
+    ///    `default` profiles, merge first
+    /// 2. Files of the `active` profile merge next
+    /// 3. Files of the `global` profile merge last, overriding every profile
+    ///
+    /// Files of any other profile are skipped. Files of the same rank merge alphabetically.
+    /// A pattern without the placeholder matches no profile, which sorts alphabetically.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::MergeOrder;
+    /// use std::path::PathBuf;
+    ///
+    /// let order = MergeOrder::profile("prod", "*-{profile}.toml");
+    /// let files = ["app-prod.toml", "app-dev.toml", "app-base.toml", "db.toml"]
+    ///     .map(PathBuf::from)
+    ///     .to_vec();
+    ///
+    /// assert_eq!(
+    ///     order.sort_files(files),
+    ///     ["app-base.toml", "db.toml", "app-prod.toml"].map(PathBuf::from)
+    /// );
+    /// ```
+    Profile {
+        /// The profile whose files are merged
+        active: String,
+        /// Filename pattern with a `{profile}` placeholder
+        pattern: String,
+    },
 }
 
 impl Default for MergeOrder {
@@ -182,6 +218,16 @@ impl Default for MergeOrder {
 }
 
 impl MergeOrder {
+    /// Merge the files of the `active` profile, named after `pattern`
+    ///
+    /// See [`MergeOrder::Profile`].
+    pub fn profile(active: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self::Profile {
+            active: active.into(),
+            pattern: pattern.into(),
+        }
+    }
+
     /// Sort a list of file paths according to this merge order strategy
     ///
     /// # Arguments
@@ -299,6 +345,22 @@ impl MergeOrder {
                 // Return files in discovery order without additional sorting
                 files
             }
+
+            MergeOrder::Profile { active, pattern } => {
+                let mut ranked: Vec<(usize, PathBuf)> = files
+                    .into_iter()
+                    .filter_map(|file| Some((profile_rank(&file, active, pattern)?, file)))
+                    .collect();
+                ranked.sort_by(|(rank_a, a), (rank_b, b)| {
+                    rank_a.cmp(rank_b).then_with(|| {
+                        a.file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("")
+                            .cmp(b.file_name().and_then(|n| n.to_str()).unwrap_or(""))
+                    })
+                });
+                ranked.into_iter().map(|(_, file)| file).collect()
+            }
         }
     }
 }
@@ -354,6 +416,59 @@ fn pattern_priority(path: &Path, patterns: &[String]) -> usize {
     usize::MAX // No match - sort at end
 }
 
+/// Profiles whose files every profile builds on
+const BASE_PROFILES: [&str; 3] = ["base", "common", "default"];
+
+/// Rank of a file in profile ordering, or `None` if it belongs to an inactive profile
+///
+/// - 0: No profile, or a base profile (processed first)
+/// - 1: The active profile
+/// - 2: The global profile (processed last)
+fn profile_rank(path: &Path, active: &str, pattern: &str) -> Option<usize> {
+    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let Some(profile) = file_profile(filename, pattern) else {
+        return Some(0);
+    };
+
+    if BASE_PROFILES
+        .iter()
+        .any(|base| profile.eq_ignore_ascii_case(base))
+    {
+        Some(0)
+    } else if profile.eq_ignore_ascii_case(active) {
+        Some(1)
+    } else if profile.eq_ignore_ascii_case("global") {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// The profile a filename matched against the `{profile}` placeholder of `pattern`
+///
+/// Prefers the longest match for the part before the placeholder, then the shortest
+/// profile, so `"*-{profile}.toml"` gives `my-app-prod.toml` the profile `prod`.
+fn file_profile<'a>(filename: &'a str, pattern: &str) -> Option<&'a str> {
+    let (before, after) = pattern.split_once("{profile}")?;
+    let boundaries: Vec<usize> = filename
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([filename.len()])
+        .collect();
+
+    for &start in boundaries.iter().rev() {
+        if !pattern_matches(&filename[..start], before) {
+            continue;
+        }
+        for &end in boundaries.iter().filter(|&&end| end > start) {
+            if pattern_matches(&filename[end..], after) {
+                return Some(&filename[start..end]);
+            }
+        }
+    }
+    None
+}
+
 /// Simple glob pattern matching for custom ordering
 ///
 /// Supports basic glob patterns: `*`, `?`, and literal strings
@@ -444,4 +559,52 @@ mod tests {
         assert_eq!(sorted[2].file_name().unwrap(), "local.toml"); // Third pattern
         assert_eq!(sorted[3].file_name().unwrap(), "other.json"); // No match, alphabetical
     }
+
+    #[test]
+    fn test_profile_sorting() {
+        let files = vec![
+            PathBuf::from("app-prod.toml"),
+            PathBuf::from("app-global.toml"),
+            PathBuf::from("app-dev.toml"),
+            PathBuf::from("app-base.toml"),
+            PathBuf::from("my-app-PROD.toml"),
+            PathBuf::from("database.toml"),
+        ];
+
+        let sorted = MergeOrder::profile("prod", "*-{profile}.toml").sort_files(files.clone());
+        assert_eq!(
+            sorted,
+            [
+                "app-base.toml",
+                "database.toml",
+                "app-prod.toml",
+                "my-app-PROD.toml",
+                "app-global.toml",
+            ]
+            .map(PathBuf::from)
+        );
+
+        // Without the placeholder no file has a profile
+        let sorted = MergeOrder::profile("prod", "*.toml").sort_files(files);
+        assert_eq!(sorted.len(), 6);
+        assert_eq!(sorted[0], PathBuf::from("app-base.toml"));
+    }
+
+    #[test]
+    fn test_file_profile() {
+        assert_eq!(
+            file_profile("app-prod.toml", "*-{profile}.toml"),
+            Some("prod")
+        );
+        assert_eq!(
+            file_profile("config.dev.yaml", "config.{profile}.*"),
+            Some("dev")
+        );
+        assert_eq!(
+            file_profile("staging.json", "{profile}.json"),
+            Some("staging")
+        );
+        assert_eq!(file_profile("app.toml", "*-{profile}.toml"), None);
+        assert_eq!(file_profile("app-.toml", "*-{profile}.toml"), None);
+    }
 }
@@ -0,0 +1,507 @@
+//! Watching the directories of a Wildcard provider and merging its files again on change
+//!
+//! A Wildcard provider discovers its files once, when a Figment is built from it.
+//! [`WildcardWatcher`] keeps watching the directories the provider searches, and when a
+//! matching file is added, removed or changed it discovers and merges the files again,
+//! reporting the new Figment. Editors often save a file with several writes or by
+//! replacing it, so changes are debounced: the files are merged once the directories have
+//! been quiet for the debounce delay. When the files fail to load, the watcher keeps the
+//! last merged Figment and reports the failure, so a half-written file never takes effect.
+//!
+//! Each merge is reported to the callbacks registered with [`WatchBuilder::on_change`]
+//! and to the channels of [`WildcardWatcher::changes`]. Watching stops when the
+//! [`WildcardWatcher`] is dropped.
+//!
+//! # What Is Watched
+//!
+//! - **Directories** and **Current** strategies: each existing directory, without its
+//!   subdirectories
+//! - **Recursive** strategy: each existing root with everything beneath it
+//! - **Custom** strategy: the directories of the files it discovered at start
+//!
+//! Directories that don't exist when the watcher starts are not watched.
+//!
+//! ```rust
+//! use superconfig::{Wildcard, WildcardEvent};
+//! use std::time::Duration;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! std::fs::write(dir.path().join("base.toml"), "port = 80").unwrap();
+//!
+//! let pattern = format!("{}/*.toml", dir.path().display());
+//! let watcher = Wildcard::new(&pattern)
+//!     .watch()
+//!     .debounce(Duration::from_millis(20))
+//!     .start()?;
+//! let changes = watcher.changes();
+//!
+//! std::fs::write(dir.path().join("local.toml"), "port = 8080").unwrap();
+//! while let Ok(event) = changes.recv_timeout(Duration::from_secs(10)) {
+//!     if let WildcardEvent::Reloaded { files, .. } = event {
+//!         if files.len() == 2 {
+//!             break;
+//!         }
+//!     }
+//! }
+//! assert_eq!(watcher.figment().extract_inner::<u16>("port")?, 8080);
+//! # Ok::<(), figment::Error>(())
+//! ```
+
+use super::{SearchStrategy, Wildcard};
+use figment::{Error, Figment, Provider};
+use notify::{
+    EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    event::{CreateKind, RemoveKind},
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, mpsc},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// Result of merging the files of a [`WildcardWatcher`] again
+// Sent once per merge, so keeping the Figment inline costs nothing worth a Box
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum WildcardEvent {
+    /// The files were discovered and merged into a new Figment
+    Reloaded {
+        /// The merged files, now returned by [`WildcardWatcher::figment`]
+        figment: Figment,
+        /// The discovered files, in merge order
+        files: Vec<PathBuf>,
+    },
+    /// The files failed to load, and the watcher kept the last merged Figment
+    Failed {
+        /// Why the files failed to load
+        error: String,
+    },
+}
+
+type ChangeCallback = Arc<dyn Fn(&WildcardEvent) + Send + Sync>;
+
+/// Callbacks and channels receiving the events of a watcher
+#[derive(Default)]
+struct Listeners {
+    callbacks: Vec<ChangeCallback>,
+    channels: Mutex<Vec<mpsc::Sender<WildcardEvent>>>,
+}
+
+impl Listeners {
+    fn emit(&self, event: &WildcardEvent) {
+        for callback in &self.callbacks {
+            callback(event);
+        }
+        // Channels whose receiver was dropped are removed
+        if let Ok(mut channels) = self.channels.lock() {
+            channels.retain(|sender| sender.send(event.clone()).is_ok());
+        }
+    }
+}
+
+/// Builder of a [`WildcardWatcher`], created by [`Wildcard::watch`]
+#[must_use]
+pub struct WatchBuilder {
+    provider: Wildcard,
+    debounce: Duration,
+    callbacks: Vec<ChangeCallback>,
+}
+
+impl WatchBuilder {
+    /// Debounce delay used unless [`debounce`](Self::debounce) sets another
+    pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+    /// Merge the files once the directories have been quiet for `delay`
+    pub const fn debounce(mut self, delay: Duration) -> Self {
+        self.debounce = delay;
+        self
+    }
+
+    /// Call `callback` with the result of each merge, on the watcher's thread
+    pub fn on_change(mut self, callback: impl Fn(&WildcardEvent) + Send + Sync + 'static) -> Self {
+        self.callbacks.push(Arc::new(callback));
+        self
+    }
+
+    /// Merge the files and start watching their directories
+    ///
+    /// The first merge isn't reported as an event; it's available from
+    /// [`WildcardWatcher::figment`] right away, holding the error if it failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if none of the searched directories exist or the file system
+    /// can't be watched.
+    pub fn start(self) -> Result<WildcardWatcher, Error> {
+        let roots = watch_roots(&self.provider);
+        if roots.is_empty() {
+            return Err(Error::from(format!(
+                "No existing directories to watch for {}",
+                self.provider.patterns().join(", ")
+            )));
+        }
+
+        let (events, changes) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(events)
+            .map_err(|e| Error::from(format!("Cannot watch files: {e}")))?;
+        for (directory, mode) in &roots {
+            watcher
+                .watch(directory, *mode)
+                .map_err(|e| Error::from(format!("Cannot watch {}: {e}", directory.display())))?;
+        }
+
+        let reloader = Arc::new(Reloader {
+            current: Mutex::new(Figment::from(&self.provider)),
+            provider: self.provider,
+            listeners: Listeners {
+                callbacks: self.callbacks,
+                channels: Mutex::new(Vec::new()),
+            },
+        });
+        let worker = Arc::clone(&reloader);
+        let debounce = self.debounce;
+        let thread = std::thread::Builder::new()
+            .name("superconfig-wildcard-watch".to_string())
+            .spawn(move || watch_loop(&changes, debounce, &worker))
+            .map_err(|e| Error::from(format!("Cannot start watcher thread: {e}")))?;
+
+        Ok(WildcardWatcher {
+            watcher: Some(watcher),
+            thread: Some(thread),
+            reloader,
+        })
+    }
+}
+
+impl Wildcard {
+    /// Watch the directories this provider searches, merging its files again on change
+    ///
+    /// See the [`watch`](super::watch) module for what is watched.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use superconfig::{SuperConfig, Wildcard, WildcardEvent};
+    ///
+    /// let watcher = Wildcard::new("./plugins/**/*.toml")
+    ///     .watch()
+    ///     .on_change(|event| {
+    ///         if let WildcardEvent::Reloaded { figment, .. } = event {
+    ///             let config = SuperConfig::from_figment(figment.clone());
+    ///             let plugins = config.get_array::<String>("plugins").unwrap_or_default();
+    ///             println!("{} plugins", plugins.len());
+    ///         }
+    ///     })
+    ///     .start()?;
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn watch(self) -> WatchBuilder {
+        WatchBuilder {
+            provider: self,
+            debounce: WatchBuilder::DEFAULT_DEBOUNCE,
+            callbacks: Vec::new(),
+        }
+    }
+}
+
+/// The existing directories to watch for `provider`, with whether to watch beneath them
+fn watch_roots(provider: &Wildcard) -> Vec<(PathBuf, RecursiveMode)> {
+    let candidates: Vec<(PathBuf, RecursiveMode)> = match provider.search_strategy() {
+        SearchStrategy::Directories(dirs) => dirs
+            .iter()
+            .map(|dir| (dir.clone(), RecursiveMode::NonRecursive))
+            .collect(),
+        SearchStrategy::Recursive { roots, .. } => roots
+            .iter()
+            .map(|root| (root.clone(), RecursiveMode::Recursive))
+            .collect(),
+        SearchStrategy::Current => vec![(PathBuf::from("."), RecursiveMode::NonRecursive)],
+        SearchStrategy::Custom(_) => provider
+            .discover_files()
+            .iter()
+            .filter_map(|file| file.parent())
+            .map(|dir| {
+                let dir = if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    dir
+                };
+                (dir.to_path_buf(), RecursiveMode::NonRecursive)
+            })
+            .collect(),
+    };
+
+    let mut roots: Vec<(PathBuf, RecursiveMode)> = Vec::new();
+    for (dir, mode) in candidates {
+        if dir.is_dir() && !roots.iter().any(|(root, _)| *root == dir) {
+            roots.push((dir, mode));
+        }
+    }
+    roots
+}
+
+/// Merges the files of a watcher and reports the result
+struct Reloader {
+    provider: Wildcard,
+    current: Mutex<Figment>,
+    listeners: Listeners,
+}
+
+impl Reloader {
+    fn reload(&self) -> WildcardEvent {
+        let files = self.provider.discover_files();
+        let figment = Figment::from(&self.provider);
+        let event = match figment.data() {
+            Ok(_) => {
+                if let Ok(mut current) = self.current.lock() {
+                    *current = figment.clone();
+                }
+                WildcardEvent::Reloaded { figment, files }
+            }
+            Err(e) => WildcardEvent::Failed {
+                error: e.to_string(),
+            },
+        };
+        self.listeners.emit(&event);
+        event
+    }
+
+    /// Whether `event` may change what the provider discovers or loads
+    fn is_relevant(&self, event: &notify::Event) -> bool {
+        match event.kind {
+            EventKind::Access(_) => false,
+            // Files in a new or removed subdirectory don't get events of their own
+            EventKind::Create(CreateKind::Folder) | EventKind::Remove(RemoveKind::Folder) => true,
+            _ => event
+                .paths
+                .iter()
+                .any(|path| self.provider.globset().is_match(path)),
+        }
+    }
+}
+
+/// Merge the files after each burst of changes, until the watcher is dropped
+fn watch_loop(
+    changes: &mpsc::Receiver<notify::Result<notify::Event>>,
+    debounce: Duration,
+    reloader: &Reloader,
+) {
+    // The channel disconnects when the `WildcardWatcher` drops the notify watcher
+    while let Ok(event) = changes.recv() {
+        let relevant = match &event {
+            Ok(event) => reloader.is_relevant(event),
+            Err(e) => {
+                reloader.listeners.emit(&WildcardEvent::Failed {
+                    error: format!("File watch error: {e}"),
+                });
+                false
+            }
+        };
+        if !relevant {
+            continue;
+        }
+        loop {
+            match changes.recv_timeout(debounce) {
+                Ok(_) => {}
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        reloader.reload();
+    }
+}
+
+/// Running watch of a Wildcard provider, stopped when dropped
+pub struct WildcardWatcher {
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+    reloader: Arc<Reloader>,
+}
+
+impl WildcardWatcher {
+    /// The Figment of the last successful merge, or of the first one if none succeeded
+    pub fn figment(&self) -> Figment {
+        match self.reloader.current.lock() {
+            Ok(current) => current.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Receive the result of each merge from now on
+    ///
+    /// Sends never block, so events queue up until received.
+    #[must_use]
+    pub fn changes(&self) -> mpsc::Receiver<WildcardEvent> {
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut channels) = self.reloader.listeners.channels.lock() {
+            channels.push(sender);
+        }
+        receiver
+    }
+
+    /// Discover and merge the files now, without waiting for a change
+    ///
+    /// The result is also reported to the watcher's callbacks and channels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files failed to load; the watcher keeps the last merged
+    /// Figment.
+    pub fn reload(&self) -> Result<Figment, Error> {
+        match self.reloader.reload() {
+            WildcardEvent::Reloaded { figment, .. } => Ok(figment),
+            WildcardEvent::Failed { error } => Err(Error::from(error)),
+        }
+    }
+}
+
+impl Drop for WildcardWatcher {
+    fn drop(&mut self) {
+        // Dropping the notify watcher disconnects the loop's channel
+        drop(self.watcher.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for WildcardWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WildcardWatcher")
+            .field("patterns", &self.reloader.provider.patterns())
+            .field("running", &self.thread.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Receive events until one matches, since a write may be seen as several changes
+    fn wait_for(
+        changes: &mpsc::Receiver<WildcardEvent>,
+        matches: impl Fn(&WildcardEvent) -> bool,
+    ) -> WildcardEvent {
+        loop {
+            let event = changes.recv_timeout(Duration::from_secs(10)).unwrap();
+            if matches(&event) {
+                return event;
+            }
+        }
+    }
+
+    fn reloaded_with(count: usize) -> impl Fn(&WildcardEvent) -> bool {
+        move |event| matches!(event, WildcardEvent::Reloaded { files, .. } if files.len() == count)
+    }
+
+    #[test]
+    fn test_added_and_removed_files_are_merged() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("base.toml");
+        std::fs::write(&base, "name = \"base\"\nport = 80").unwrap();
+
+        let seen = Arc::new(Mutex::new(0));
+        let callback_seen = Arc::clone(&seen);
+        let pattern = format!("{}/*.toml", temp_dir.path().display());
+        let watcher = Wildcard::new(&pattern)
+            .watch()
+            .debounce(Duration::from_millis(20))
+            .on_change(move |_| *callback_seen.lock().unwrap() += 1)
+            .start()
+            .unwrap();
+        let changes = watcher.changes();
+        assert_eq!(watcher.figment().extract_inner::<u16>("port").unwrap(), 80);
+
+        // Files the patterns don't match are ignored
+        std::fs::write(temp_dir.path().join("notes.txt"), "port = 1").unwrap();
+        let local = temp_dir.path().join("local.toml");
+        std::fs::write(&local, "port = 8080").unwrap();
+        let event = wait_for(&changes, reloaded_with(2));
+        let WildcardEvent::Reloaded { figment, files } = event else {
+            unreachable!()
+        };
+        assert_eq!(files, vec![base.clone(), local.clone()]);
+        assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8080);
+        assert_eq!(
+            watcher.figment().extract_inner::<u16>("port").unwrap(),
+            8080
+        );
+
+        std::fs::remove_file(&local).unwrap();
+        wait_for(&changes, reloaded_with(1));
+        assert_eq!(watcher.figment().extract_inner::<u16>("port").unwrap(), 80);
+        assert!(*seen.lock().unwrap() >= 2);
+    }
+
+    #[test]
+    fn test_failed_merge_keeps_last_figment() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("app.toml");
+        std::fs::write(&path, "port = 80").unwrap();
+
+        let pattern = format!("{}/*.toml", temp_dir.path().display());
+        let watcher = Wildcard::new(&pattern)
+            .with_strict(true)
+            .watch()
+            .debounce(Duration::from_millis(20))
+            .start()
+            .unwrap();
+        let changes = watcher.changes();
+
+        std::fs::write(&path, "port = ").unwrap();
+        let event = wait_for(&changes, |event| {
+            matches!(event, WildcardEvent::Failed { .. })
+        });
+        assert!(matches!(&event, WildcardEvent::Failed { error } if error.contains("app.toml")));
+        assert_eq!(watcher.figment().extract_inner::<u16>("port").unwrap(), 80);
+        assert!(watcher.reload().is_err());
+
+        std::fs::write(&path, "port = 81").unwrap();
+        wait_for(&changes, reloaded_with(1));
+        assert_eq!(watcher.figment().extract_inner::<u16>("port").unwrap(), 81);
+    }
+
+    #[test]
+    fn test_recursive_roots_watch_new_subdirectories() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("root.json"), r#"{"root": true}"#).unwrap();
+
+        let pattern = format!("{}/**/*.json", temp_dir.path().display());
+        let watcher = Wildcard::new(&pattern)
+            .watch()
+            .debounce(Duration::from_millis(50))
+            .start()
+            .unwrap();
+        let changes = watcher.changes();
+
+        let plugin = temp_dir.path().join("plugins").join("auth");
+        std::fs::create_dir_all(&plugin).unwrap();
+        std::fs::write(plugin.join("auth.json"), r#"{"auth": true}"#).unwrap();
+        wait_for(&changes, reloaded_with(2));
+        assert!(watcher.figment().extract_inner::<bool>("auth").unwrap());
+    }
+
+    #[test]
+    fn test_missing_directories_and_manual_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = format!("{}/missing/*.toml", temp_dir.path().display());
+        let error = Wildcard::new(&missing).watch().start().unwrap_err();
+        assert!(error.to_string().contains("No existing directories"));
+
+        let pattern = format!("{}/*.toml", temp_dir.path().display());
+        let watcher = Wildcard::new(&pattern).watch().start().unwrap();
+        let changes = watcher.changes();
+        std::fs::write(temp_dir.path().join("late.toml"), "late = 1").unwrap();
+
+        let figment = watcher.reload().unwrap();
+        assert_eq!(figment.extract_inner::<u8>("late").unwrap(), 1);
+        assert!(matches!(
+            changes.recv_timeout(Duration::from_secs(10)).unwrap(),
+            WildcardEvent::Reloaded { .. }
+        ));
+        drop(watcher);
+    }
+}
@@ -0,0 +1,703 @@
+//! Configuration schemas and breaking change detection between releases
+//!
+//! A [`ConfigSchema`] lists every key of a configuration with its type, derived from the
+//! defaults of a configuration struct or from a loaded [`SuperConfig`](crate::SuperConfig).
+//! Constraints that cannot be derived (required keys, numeric ranges, allowed values,
+//! aliases of renamed keys, secret keys, and filesystem paths) are added with builder methods.
+//!
+//! Storing the schema of each release (see [`ConfigSchema::to_json`]) lets CI compare it
+//! with the schema of the next one, and fail when configuration files that worked with the
+//! old release would break:
+//!
+//! - [`BreakingChange::RemovedKey`] - A key disappeared
+//! - [`BreakingChange::TypeChanged`] - A key's type changed (integer → float is allowed)
+//! - [`BreakingChange::ConstraintTightened`] - A key became required or an existing path, or its range or allowed values shrank
+//! - [`BreakingChange::RenamedWithoutAlias`] - A key was renamed and the new key has no alias for the old name
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::schema::ConfigSchema;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize, Default)]
+//! struct ServerV1 { addr: String, port: u16 }
+//!
+//! #[derive(Serialize, Default)]
+//! struct ServerV2 { host: String, port: u16 }
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let old = ConfigSchema::from_defaults(&ServerV1::default())?.range("port", 1.0, 65535.0);
+//! let new = ConfigSchema::from_defaults(&ServerV2::default())?.range("port", 1024.0, 65535.0);
+//!
+//! let diff = ConfigSchema::compare(&old, &new);
+//! assert!(diff.is_breaking());
+//! assert_eq!(diff.breaking.len(), 2); // addr → host, port minimum raised
+//!
+//! // Gate the release: every breaking change needs a migration entry
+//! let migrated = ["addr", "port"];
+//! assert!(diff.unmigrated(&migrated).is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use figment::{Error, Figment, providers::Serialized};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt};
+
+/// Type of a configuration key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    /// Text value
+    String,
+    /// Whole number
+    Integer,
+    /// Floating point number
+    Float,
+    /// `true` or `false`
+    Boolean,
+    /// List of values
+    Array,
+    /// Nested table, whose keys are listed separately
+    Object,
+    /// Unset value (`None` defaults), compatible with every type
+    Null,
+}
+
+impl FieldType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::String(_) => Self::String,
+            Value::Number(number) if number.is_f64() => Self::Float,
+            Value::Number(_) => Self::Integer,
+            Value::Bool(_) => Self::Boolean,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+            Value::Null => Self::Null,
+        }
+    }
+
+    /// Whether values valid for `self` are still accepted as `new`
+    fn accepted_as(self, new: Self) -> bool {
+        self == new
+            || self == Self::Null
+            || new == Self::Null
+            || (self == Self::Integer && new == Self::Float)
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Array => "array",
+            Self::Object => "object",
+            Self::Null => "null",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Type and constraints of one configuration key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    /// Type of the key
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    /// Whether configuration files must set the key
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    /// Smallest accepted numeric value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+    /// Largest accepted numeric value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+    /// Exhaustive list of accepted values
+    #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<Vec<Value>>,
+    /// Former dotted paths of the key that are still accepted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Whether the key holds a secret, masked by [`RedactionPolicy::with_schema`](crate::secret::RedactionPolicy::with_schema)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secret: bool,
+    /// Whether the key holds a filesystem path, normalized by [`SuperConfig::with_path_schema`](crate::SuperConfig::with_path_schema)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub path: bool,
+    /// Whether the path must exist when the configuration is extracted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub must_exist: bool,
+}
+
+impl FieldSchema {
+    fn new(field_type: FieldType) -> Self {
+        Self {
+            field_type,
+            required: false,
+            minimum: None,
+            maximum: None,
+            allowed: None,
+            aliases: Vec::new(),
+            secret: false,
+            path: false,
+            must_exist: false,
+        }
+    }
+
+    /// Describe the constraints of `new` that reject values `self` accepted
+    fn tightened(&self, new: &Self) -> Vec<String> {
+        let mut tightened = Vec::new();
+        if new.required && !self.required {
+            tightened.push("became required".to_string());
+        }
+        if new.must_exist && !self.must_exist {
+            tightened.push("must be an existing path".to_string());
+        }
+        if let Some(minimum) = new.minimum
+            && self.minimum.is_none_or(|old| minimum > old)
+        {
+            tightened.push(format!("minimum raised to {minimum}"));
+        }
+        if let Some(maximum) = new.maximum
+            && self.maximum.is_none_or(|old| maximum < old)
+        {
+            tightened.push(format!("maximum lowered to {maximum}"));
+        }
+        if let Some(allowed) = &new.allowed {
+            match &self.allowed {
+                Some(old) => {
+                    let dropped: Vec<String> = old
+                        .iter()
+                        .filter(|value| !allowed.contains(value))
+                        .map(Value::to_string)
+                        .collect();
+                    if !dropped.is_empty() {
+                        tightened.push(format!("no longer allows {}", dropped.join(", ")));
+                    }
+                }
+                None => tightened.push("restricted to a list of values".to_string()),
+            }
+        }
+        tightened
+    }
+}
+
+/// Keys of a configuration with their types and constraints
+///
+/// Keys are dotted paths (`database.pool.max`). Nested tables are listed as
+/// [`FieldType::Object`] keys followed by their own keys. The schema serializes to a
+/// JSON object keyed by path, meant to be committed next to each release.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConfigSchema {
+    fields: BTreeMap<String, FieldSchema>,
+}
+
+impl ConfigSchema {
+    /// Derive the schema of a configuration struct from its default values
+    ///
+    /// `None` fields are serialized as nothing, so give optional fields a value in the
+    /// instance used for the schema to have them listed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::schema::{ConfigSchema, FieldType};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize, Default)]
+    /// struct Config { name: String, port: u16 }
+    ///
+    /// let schema = ConfigSchema::from_defaults(&Config::default())?;
+    /// assert_eq!(schema.get("port").unwrap().field_type, FieldType::Integer);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn from_defaults<T: Serialize>(defaults: &T) -> Result<Self, Error> {
+        let value = Figment::from(Serialized::defaults(defaults)).extract::<Value>()?;
+        Ok(Self::from_value(&value))
+    }
+
+    /// Derive a schema from a configuration tree
+    ///
+    /// Non-object values yield an empty schema, since they have no keys.
+    pub fn from_value(value: &Value) -> Self {
+        let mut schema = Self::default();
+        if let Value::Object(map) = value {
+            schema.collect("", map);
+        }
+        schema
+    }
+
+    /// Load a schema stored with [`to_json`](Self::to_json)
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the schema as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Schema of the key at `path`
+    pub fn get(&self, path: &str) -> Option<&FieldSchema> {
+        self.fields.get(path)
+    }
+
+    /// All keys with their schemas, sorted by path
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &FieldSchema)> {
+        self.fields
+            .iter()
+            .map(|(path, field)| (path.as_str(), field))
+    }
+
+    /// Mark `path` as required
+    ///
+    /// Like every builder method, paths missing from the schema are ignored.
+    pub fn require(self, path: &str) -> Self {
+        self.with_field(path, |field| field.required = true)
+    }
+
+    /// Limit `path` to numbers between `minimum` and `maximum` (inclusive)
+    pub fn range(self, path: &str, minimum: f64, maximum: f64) -> Self {
+        self.with_field(path, |field| {
+            field.minimum = Some(minimum);
+            field.maximum = Some(maximum);
+        })
+    }
+
+    /// Limit `path` to the given values
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::schema::ConfigSchema;
+    ///
+    /// let schema = ConfigSchema::from_value(&serde_json::json!({ "log_level": "info" }))
+    ///     .allowed("log_level", ["debug", "info", "warn", "error"]);
+    /// assert_eq!(schema.get("log_level").unwrap().allowed.as_ref().unwrap().len(), 4);
+    /// ```
+    pub fn allowed<I, V>(self, path: &str, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        let values: Vec<Value> = values.into_iter().map(Into::into).collect();
+        self.with_field(path, |field| field.allowed = Some(values))
+    }
+
+    /// Record that `path` was previously named `old_path`
+    ///
+    /// Renames with an alias are not reported as breaking changes, on the assumption
+    /// that the application still accepts the old name (for instance with `#[serde(alias)]`).
+    pub fn alias(self, path: &str, old_path: &str) -> Self {
+        self.with_field(path, |field| field.aliases.push(old_path.to_string()))
+    }
+
+    /// Mark `path` as holding a secret
+    ///
+    /// Secret keys are masked in exports and logs once the schema is added to the
+    /// [`RedactionPolicy`](crate::secret::RedactionPolicy) of a configuration.
+    pub fn secret(self, path: &str) -> Self {
+        self.with_field(path, |field| field.secret = true)
+    }
+
+    /// Mark `path` as holding a filesystem path
+    ///
+    /// Path keys are normalized (home directory expanded, separators converted, relative
+    /// paths resolved against the file that set them) once the schema is registered with
+    /// [`SuperConfig::with_path_schema`](crate::SuperConfig::with_path_schema).
+    pub fn path(self, path: &str) -> Self {
+        self.with_field(path, |field| field.path = true)
+    }
+
+    /// Mark `path` as holding a filesystem path that must exist
+    pub fn existing_path(self, path: &str) -> Self {
+        self.with_field(path, |field| {
+            field.path = true;
+            field.must_exist = true;
+        })
+    }
+
+    /// Paths of the keys marked with [`secret`](Self::secret)
+    pub fn secret_keys(&self) -> impl Iterator<Item = &str> {
+        self.fields()
+            .filter(|(_, field)| field.secret)
+            .map(|(path, _)| path)
+    }
+
+    /// Compare the schema of an old release with the schema of a new one
+    ///
+    /// Keys removed from a table while a key of the same type was added to it are
+    /// reported as renames. A removed table is reported once, not for each of its keys.
+    pub fn compare(old: &Self, new: &Self) -> SchemaDiff {
+        let mut breaking = Vec::new();
+        let mut removed: Vec<String> = Vec::new();
+
+        for (path, old_field) in &old.fields {
+            let Some(new_field) = new.fields.get(path) else {
+                if !removed.iter().any(|parent| is_child(path, parent)) {
+                    removed.push(path.clone());
+                }
+                continue;
+            };
+            if !old_field.field_type.accepted_as(new_field.field_type) {
+                breaking.push(BreakingChange::TypeChanged {
+                    key: path.clone(),
+                    old: old_field.field_type,
+                    new: new_field.field_type,
+                });
+                continue;
+            }
+            for constraint in old_field.tightened(new_field) {
+                breaking.push(BreakingChange::ConstraintTightened {
+                    key: path.clone(),
+                    constraint,
+                });
+            }
+        }
+
+        let added: Vec<String> = new
+            .fields
+            .keys()
+            .filter(|path| !old.fields.contains_key(*path))
+            .cloned()
+            .collect();
+        let mut renamed_to: Vec<&String> = Vec::new();
+
+        for path in removed {
+            if new.aliases_path(&path) {
+                continue;
+            }
+            let old_type = old.fields[&path].field_type;
+            let rename = added.iter().find(|candidate| {
+                !renamed_to.contains(candidate)
+                    && parent(candidate) == parent(&path)
+                    && new.fields[*candidate].field_type == old_type
+            });
+            match rename {
+                Some(new_key) => {
+                    renamed_to.push(new_key);
+                    breaking.push(BreakingChange::RenamedWithoutAlias {
+                        old_key: path,
+                        new_key: new_key.clone(),
+                    });
+                }
+                None => breaking.push(BreakingChange::RemovedKey { key: path }),
+            }
+        }
+
+        SchemaDiff { breaking, added }
+    }
+
+    fn collect(&mut self, prefix: &str, map: &serde_json::Map<String, Value>) {
+        for (key, value) in map {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            self.fields
+                .insert(path.clone(), FieldSchema::new(FieldType::of(value)));
+            if let Value::Object(nested) = value {
+                self.collect(&path, nested);
+            }
+        }
+    }
+
+    fn with_field(mut self, path: &str, update: impl FnOnce(&mut FieldSchema)) -> Self {
+        if let Some(field) = self.fields.get_mut(path) {
+            update(field);
+        }
+        self
+    }
+
+    /// Whether a key of this schema lists `path`, or one of its parent tables, as an alias
+    fn aliases_path(&self, path: &str) -> bool {
+        self.fields.values().any(|field| {
+            field
+                .aliases
+                .iter()
+                .any(|alias| alias == path || is_child(path, alias))
+        })
+    }
+}
+
+fn parent(path: &str) -> &str {
+    path.rsplit_once('.').map_or("", |(parent, _)| parent)
+}
+
+fn is_child(path: &str, parent: &str) -> bool {
+    path.strip_prefix(parent)
+        .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// A change that makes configuration accepted by an old release invalid in a new one
+#[derive(Debug, Clone, PartialEq)]
+pub enum BreakingChange {
+    /// The key no longer exists
+    RemovedKey {
+        /// Dotted path of the removed key
+        key: String,
+    },
+    /// The key's type changed
+    TypeChanged {
+        /// Dotted path of the key
+        key: String,
+        /// Type in the old release
+        old: FieldType,
+        /// Type in the new release
+        new: FieldType,
+    },
+    /// The key accepts fewer values than before
+    ConstraintTightened {
+        /// Dotted path of the key
+        key: String,
+        /// Description of the tightened constraint
+        constraint: String,
+    },
+    /// The key was renamed and the new key has no alias for the old name
+    RenamedWithoutAlias {
+        /// Dotted path in the old release
+        old_key: String,
+        /// Dotted path in the new release
+        new_key: String,
+    },
+}
+
+impl BreakingChange {
+    /// Dotted path of the affected key in the old release
+    pub fn key(&self) -> &str {
+        match self {
+            Self::RemovedKey { key }
+            | Self::TypeChanged { key, .. }
+            | Self::ConstraintTightened { key, .. }
+            | Self::RenamedWithoutAlias { old_key: key, .. } => key,
+        }
+    }
+}
+
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RemovedKey { key } => write!(f, "`{key}` was removed"),
+            Self::TypeChanged { key, old, new } => {
+                write!(f, "`{key}` changed type from {old} to {new}")
+            }
+            Self::ConstraintTightened { key, constraint } => write!(f, "`{key}` {constraint}"),
+            Self::RenamedWithoutAlias { old_key, new_key } => {
+                write!(f, "`{old_key}` was renamed to `{new_key}` without an alias")
+            }
+        }
+    }
+}
+
+/// Result of [`ConfigSchema::compare`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDiff {
+    /// Changes that break configuration written for the old release
+    pub breaking: Vec<BreakingChange>,
+    /// Keys only present in the new release
+    pub added: Vec<String>,
+}
+
+impl SchemaDiff {
+    /// Returns `true` when at least one change is breaking
+    pub fn is_breaking(&self) -> bool {
+        !self.breaking.is_empty()
+    }
+
+    /// Breaking changes whose key has no migration entry
+    ///
+    /// `migrated` lists the old-release paths covered by a migration; a path also covers
+    /// the keys nested under it.
+    pub fn unmigrated(&self, migrated: &[&str]) -> Vec<&BreakingChange> {
+        self.breaking
+            .iter()
+            .filter(|change| {
+                !migrated
+                    .iter()
+                    .any(|path| change.key() == *path || is_child(change.key(), path))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.breaking.is_empty() {
+            return f.write_str("No breaking configuration changes");
+        }
+        writeln!(
+            f,
+            "{} breaking configuration change(s):",
+            self.breaking.len()
+        )?;
+        for change in &self.breaking {
+            writeln!(f, "  - {change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl crate::SuperConfig {
+    /// Derive the schema of the merged configuration
+    ///
+    /// # Examples
+    /// ```rust
+    /// use superconfig::SuperConfig;
+    /// use superconfig::schema::FieldType;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config { database: Database }
+    /// #[derive(Serialize)]
+    /// struct Database { host: String }
+    ///
+    /// let config = SuperConfig::new()
+    ///     .with_defaults(Config { database: Database { host: "localhost".into() } });
+    ///
+    /// let schema = config.schema()?;
+    /// assert_eq!(schema.get("database").unwrap().field_type, FieldType::Object);
+    /// assert_eq!(schema.get("database.host").unwrap().field_type, FieldType::String);
+    /// # Ok::<(), figment::Error>(())
+    /// ```
+    pub fn schema(&self) -> Result<ConfigSchema, Error> {
+        let value = self.figment.extract::<Value>()?;
+        Ok(ConfigSchema::from_value(&value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(value: Value) -> ConfigSchema {
+        ConfigSchema::from_value(&value)
+    }
+
+    #[test]
+    fn test_derived_types() {
+        let schema = schema(json!({
+            "name": "app",
+            "port": 8080,
+            "ratio": 0.5,
+            "debug": false,
+            "features": ["auth"],
+            "database": { "pool": { "max": 10 } },
+        }));
+
+        let types: Vec<(&str, FieldType)> = schema
+            .fields()
+            .map(|(path, field)| (path, field.field_type))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("database", FieldType::Object),
+                ("database.pool", FieldType::Object),
+                ("database.pool.max", FieldType::Integer),
+                ("debug", FieldType::Boolean),
+                ("features", FieldType::Array),
+                ("name", FieldType::String),
+                ("port", FieldType::Integer),
+                ("ratio", FieldType::Float),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compatible_changes() {
+        let old = schema(json!({ "port": 8080, "timeout": 5, "token": null }))
+            .range("port", 1.0, 65535.0)
+            .allowed("timeout", [5, 10]);
+        let new = schema(json!({ "port": 8080, "timeout": 5.0, "token": "x", "added": true }))
+            .range("port", 0.0, 65535.0)
+            .allowed("timeout", [5, 10, 30]);
+
+        let diff = ConfigSchema::compare(&old, &new);
+        assert!(!diff.is_breaking(), "{diff}");
+        assert_eq!(diff.added, vec!["added"]);
+    }
+
+    #[test]
+    fn test_breaking_changes() {
+        let old = schema(json!({
+            "cache": { "size": 10, "ttl": 60 },
+            "port": 8080,
+            "level": "info",
+            "workers": 4,
+        }))
+        .allowed("level", ["debug", "info"]);
+        let new = schema(json!({ "port": "8080", "level": "info", "workers": 4 }))
+            .allowed("level", ["info"])
+            .range("workers", 1.0, 2.0)
+            .require("workers");
+
+        let diff = ConfigSchema::compare(&old, &new);
+        let messages: Vec<String> = diff.breaking.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "`level` no longer allows \"debug\"",
+                "`port` changed type from integer to string",
+                "`workers` became required",
+                "`workers` minimum raised to 1",
+                "`workers` maximum lowered to 2",
+                "`cache` was removed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_renames() {
+        let old = schema(json!({ "server": { "addr": "0.0.0.0", "port": 80 } }));
+        let renamed = schema(json!({ "server": { "host": "0.0.0.0", "port": 80 } }));
+
+        let diff = ConfigSchema::compare(&old, &renamed);
+        assert_eq!(
+            diff.breaking,
+            vec![BreakingChange::RenamedWithoutAlias {
+                old_key: "server.addr".to_string(),
+                new_key: "server.host".to_string(),
+            }]
+        );
+
+        let aliased = renamed.alias("server.host", "server.addr");
+        assert!(!ConfigSchema::compare(&old, &aliased).is_breaking());
+    }
+
+    #[test]
+    fn test_unmigrated() {
+        let old = schema(json!({ "server": { "addr": "", "port": 80 }, "legacy": true }));
+        let new = schema(json!({ "server": { "port": "80" } }));
+
+        let diff = ConfigSchema::compare(&old, &new);
+        assert_eq!(diff.breaking.len(), 3);
+
+        let unmigrated = diff.unmigrated(&["server"]);
+        assert_eq!(
+            unmigrated,
+            vec![&BreakingChange::RemovedKey {
+                key: "legacy".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let schema = schema(json!({ "port": 8080, "level": "info" }))
+            .range("port", 1.0, 65535.0)
+            .allowed("level", ["info", "debug"])
+            .require("level")
+            .alias("port", "listen_port");
+
+        let json = schema.to_json().unwrap();
+        assert!(json.contains("\"enum\""));
+        assert_eq!(ConfigSchema::from_json(&json).unwrap(), schema);
+    }
+}
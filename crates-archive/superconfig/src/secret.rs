@@ -0,0 +1,403 @@
+//! Secret values and redaction of sensitive keys
+//!
+//! Configuration often carries credentials: database passwords, API tokens, signing keys.
+//! This module keeps them out of logs and exports in two complementary ways:
+//!
+//! - [`SecretString`] and [`SecretBytes`] are field types for secrets. They deserialize from
+//!   any provider like a `String`/`Vec<u8>`, print and serialize as `***MASKED***`, and
+//!   overwrite their memory with zeros when dropped.
+//! - A [`RedactionPolicy`] names the keys that hold secrets, by name pattern (`password`,
+//!   `secret`, `token`, and `key` by default) or by exact path, including the keys marked
+//!   with [`ConfigSchema::secret`](crate::schema::ConfigSchema::secret). SuperConfig applies
+//!   it to everything it prints or exports: `as_json()`, `as_yaml()`, `as_toml()`,
+//!   `debug_config()`, verbosity output, and extraction errors. Secrets stay masked even
+//!   when the application extracts them into a plain `String`.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::SuperConfig;
+//! use superconfig::secret::{RedactionPolicy, SecretString};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     password: SecretString,
+//!     dsn: String,
+//! }
+//!
+//! # fn main() -> Result<(), figment::Error> {
+//! let config = SuperConfig::new()
+//!     .with_defaults_string(r#"{"host": "db", "password": "hunter2", "dsn": "postgres://db"}"#)
+//!     .with_redaction(RedactionPolicy::default().with_key("dsn"));
+//!
+//! let database: Database = config.extract()?;
+//! assert_eq!(database.password.expose_secret(), "hunter2");
+//! assert_eq!(format!("{:?}", database.password), "SecretString(***MASKED***)");
+//!
+//! // Exports mask `password` (name pattern) and `dsn` (exact key), not `host`
+//! let json = config.as_json()?;
+//! assert!(!json.contains("hunter2") && !json.contains("postgres://"));
+//! assert!(json.contains("db"));
+//! # Ok(())
+//! # }
+//! ```
+
+use figment::Error;
+use figment::error::{Actual, Kind};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Placeholder printed and exported in place of secret values
+pub const REDACTED: &str = "***MASKED***";
+
+/// Key name fragments treated as secrets by [`RedactionPolicy::default`]
+pub const DEFAULT_PATTERNS: [&str; 4] = ["password", "secret", "token", "key"];
+
+/// A secret string, masked in `Debug` and serialization and zeroed on drop
+///
+/// Read the value with [`expose_secret`](Self::expose_secret), so every use of a secret is
+/// explicit and easy to audit.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Wrap a secret value
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    /// Borrow the secret value
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Length of the secret in bytes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the secret is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self(secret.to_string())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString({REDACTED})")
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Secret bytes, masked in `Debug` and serialization and zeroed on drop
+///
+/// Deserializes from a string (its UTF-8 bytes), a byte buffer, or an array of bytes.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    /// Wrap secret bytes
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self(secret.into())
+    }
+
+    /// Borrow the secret bytes
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Number of secret bytes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no secret bytes
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(secret: Vec<u8>) -> Self {
+        Self(secret)
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes({REDACTED})")
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Serialize for SecretBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = SecretBytes;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string, bytes, or an array of bytes")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(SecretBytes(value.as_bytes().to_vec()))
+            }
+
+            fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Ok(SecretBytes(value.into_bytes()))
+            }
+
+            fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+                Ok(SecretBytes(value.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(SecretBytes(value))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = SecretBytes(Vec::with_capacity(seq.size_hint().unwrap_or(0)));
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.0.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        deserializer.deserialize_any(BytesVisitor)
+    }
+}
+
+/// Which configuration keys hold secrets
+///
+/// A key is secret when its dotted path, or the path of a table containing it, was added
+/// with [`with_key`](Self::with_key), or when its name (the last path segment) contains one
+/// of the patterns, ignoring case. A secret table is masked as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    patterns: Vec<String>,
+    keys: BTreeSet<String>,
+}
+
+impl Default for RedactionPolicy {
+    /// Masks keys whose name contains `password`, `secret`, `token`, or `key`
+    fn default() -> Self {
+        Self {
+            patterns: DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            keys: BTreeSet::new(),
+        }
+    }
+}
+
+impl RedactionPolicy {
+    /// A policy masking nothing, to start from an empty pattern list or to disable redaction
+    pub fn none() -> Self {
+        Self {
+            patterns: Vec::new(),
+            keys: BTreeSet::new(),
+        }
+    }
+
+    /// Also mask keys whose name contains `pattern` (case-insensitive)
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_lowercase());
+        self
+    }
+
+    /// Also mask the key (or table) at the dotted `path`
+    pub fn with_key(mut self, path: &str) -> Self {
+        self.keys.insert(path.to_string());
+        self
+    }
+
+    /// Also mask the keys marked with [`ConfigSchema::secret`](crate::schema::ConfigSchema::secret)
+    pub fn with_schema(mut self, schema: &crate::schema::ConfigSchema) -> Self {
+        self.keys.extend(schema.secret_keys().map(str::to_string));
+        self
+    }
+
+    /// Whether the key at the dotted `path` holds a secret
+    pub fn is_secret(&self, path: &str) -> bool {
+        let in_secret_table = self.keys.iter().any(|key| {
+            path == key
+                || path
+                    .strip_prefix(key.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        });
+        if in_secret_table {
+            return true;
+        }
+
+        let name = path.rsplit('.').next().unwrap_or(path).to_lowercase();
+        self.patterns.iter().any(|pattern| name.contains(pattern))
+    }
+
+    /// Replace the secret values of a configuration tree with [`REDACTED`]
+    pub fn redact(&self, value: &mut Value) {
+        if let Value::Object(map) = value {
+            for (key, child) in map.iter_mut() {
+                self.redact_at(key, child);
+            }
+        }
+    }
+
+    fn redact_at(&self, path: &str, value: &mut Value) {
+        if self.is_secret(path) {
+            *value = Value::String(REDACTED.to_string());
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    self.redact_at(&format!("{path}.{key}"), child);
+                }
+            }
+            // Array items share the path of their array
+            Value::Array(items) => {
+                for item in items {
+                    if let Value::Object(map) = item {
+                        for (key, child) in map.iter_mut() {
+                            self.redact_at(&format!("{path}.{key}"), child);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Mask the offending values that extraction errors quote for secret keys
+    ///
+    /// `invalid type: found string "hunter2", expected u16` becomes
+    /// `invalid type: found ***MASKED***, expected u16`.
+    pub fn redact_error(&self, error: Error) -> Error {
+        let errors: Vec<Error> = error
+            .into_iter()
+            .map(|mut error| {
+                if self.is_secret(&error.path.join(".")) {
+                    error.kind = match error.kind {
+                        Kind::InvalidType(_, expected) => {
+                            Kind::InvalidType(Actual::Other(REDACTED.to_string()), expected)
+                        }
+                        Kind::InvalidValue(_, expected) => {
+                            Kind::InvalidValue(Actual::Other(REDACTED.to_string()), expected)
+                        }
+                        kind => kind,
+                    };
+                }
+                error
+            })
+            .collect();
+
+        // Errors iterate newest first; chain them back oldest first
+        errors
+            .into_iter()
+            .rev()
+            .reduce(|previous, error| previous.chain(error))
+            .expect("an error chain holds at least one error")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_secret_string_is_masked() {
+        let secret = SecretString::from("hunter2");
+        assert_eq!(secret.expose_secret(), "hunter2");
+        assert_eq!(format!("{secret:?}"), "SecretString(***MASKED***)");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"***MASKED***\"");
+
+        let parsed: SecretString = serde_json::from_str("\"s3cret\"").unwrap();
+        assert_eq!(parsed.expose_secret(), "s3cret");
+    }
+
+    #[test]
+    fn test_secret_bytes_accept_strings_and_arrays() {
+        let from_string: SecretBytes = serde_json::from_str("\"abc\"").unwrap();
+        let from_array: SecretBytes = serde_json::from_str("[97, 98, 99]").unwrap();
+        assert_eq!(from_string, from_array);
+        assert_eq!(from_array.expose_secret(), b"abc");
+        assert_eq!(format!("{from_array:?}"), "SecretBytes(***MASKED***)");
+    }
+
+    #[test]
+    fn test_policy_matches_patterns_and_keys() {
+        let policy = RedactionPolicy::default().with_key("database.dsn");
+        assert!(policy.is_secret("database.password"));
+        assert!(policy.is_secret("api.AUTH_TOKEN"));
+        assert!(policy.is_secret("database.dsn"));
+        assert!(policy.is_secret("database.dsn.user"));
+        assert!(!policy.is_secret("database.dsn_timeout"));
+        assert!(!policy.is_secret("database.host"));
+        assert!(!RedactionPolicy::none().is_secret("password"));
+    }
+
+    #[test]
+    fn test_redact_walks_tables_and_arrays() {
+        let mut value = json!({
+            "host": "db",
+            "credentials": {"user": "admin", "password": "hunter2"},
+            "servers": [{"name": "a", "token": "t1"}],
+            "secrets": {"anything": "x"},
+        });
+        RedactionPolicy::default().redact(&mut value);
+
+        assert_eq!(value["host"], "db");
+        assert_eq!(value["credentials"]["user"], "admin");
+        assert_eq!(value["credentials"]["password"], REDACTED);
+        assert_eq!(value["servers"][0]["name"], "a");
+        assert_eq!(value["servers"][0]["token"], REDACTED);
+        assert_eq!(value["secrets"], REDACTED);
+    }
+}
@@ -0,0 +1,310 @@
+//! Test helpers for comparing configuration values
+//!
+//! [`assert_config_eq!`](crate::assert_config_eq) compares two configurations through
+//! their serialized form. On failure it lists the keys that differ instead of printing
+//! both values with `Debug`:
+//!
+//! ```text
+//! configurations differ (3 differences):
+//!   - database.pool: missing, expected 10
+//!   + debug: unexpected, found true
+//!   ~ port: expected 8080, found 9090
+//! ```
+//!
+//! Keys are dotted paths, with array elements as `features[1]`. Both sides only need to
+//! implement [`Serialize`], so an extracted struct can be compared with a
+//! [`serde_json::Value`] or another struct.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::{SuperConfig, assert_config_eq};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Default, Serialize, Deserialize)]
+//! struct Server { host: String, port: u16 }
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = SuperConfig::new().with_defaults(Server { host: "localhost".into(), port: 80 });
+//! let extracted: Server = config.extract()?;
+//!
+//! assert_config_eq!(extracted, serde_json::json!({ "host": "localhost", "port": 80 }));
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+pub use crate::assert_config_eq;
+
+/// A key whose value differs between two configurations
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigDifference {
+    /// The key is expected but missing
+    Missing {
+        /// Dotted path of the key
+        path: String,
+        /// Expected value
+        expected: Value,
+    },
+    /// The key is present but not expected
+    Extra {
+        /// Dotted path of the key
+        path: String,
+        /// Actual value
+        actual: Value,
+    },
+    /// The key has a different value
+    Changed {
+        /// Dotted path of the key
+        path: String,
+        /// Actual value
+        actual: Value,
+        /// Expected value
+        expected: Value,
+    },
+}
+
+impl ConfigDifference {
+    /// Dotted path of the key, empty for the whole configuration
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Missing { path, .. } | Self::Extra { path, .. } | Self::Changed { path, .. } => {
+                path
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConfigDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match self.path() {
+            "" => "(root)",
+            path => path,
+        };
+        match self {
+            Self::Missing { expected, .. } => write!(f, "- {path}: missing, expected {expected}"),
+            Self::Extra { actual, .. } => write!(f, "+ {path}: unexpected, found {actual}"),
+            Self::Changed {
+                actual, expected, ..
+            } => write!(f, "~ {path}: expected {expected}, found {actual}"),
+        }
+    }
+}
+
+/// Lists the keys that differ between `actual` and `expected`, sorted by path
+///
+/// Both values are compared in their serialized form. Objects are compared key by key
+/// and arrays element by element; any other difference is reported at the key holding it.
+///
+/// # Errors
+///
+/// Returns an error if either value cannot be serialized to JSON.
+pub fn config_diff<A, E>(
+    actual: &A,
+    expected: &E,
+) -> Result<Vec<ConfigDifference>, serde_json::Error>
+where
+    A: Serialize + ?Sized,
+    E: Serialize + ?Sized,
+{
+    let actual = serde_json::to_value(actual)?;
+    let expected = serde_json::to_value(expected)?;
+    let mut differences = Vec::new();
+    diff_values(String::new(), &actual, &expected, &mut differences);
+    differences.sort_by(|a, b| a.path().cmp(b.path()));
+    Ok(differences)
+}
+
+/// Formats differences as the failure message of [`assert_config_eq!`](crate::assert_config_eq)
+pub fn format_diff(differences: &[ConfigDifference]) -> String {
+    let mut message = format!(
+        "configurations differ ({} difference{}):",
+        differences.len(),
+        if differences.len() == 1 { "" } else { "s" }
+    );
+    for difference in differences {
+        message.push_str("\n  ");
+        message.push_str(&difference.to_string());
+    }
+    message
+}
+
+fn diff_values(path: String, actual: &Value, expected: &Value, out: &mut Vec<ConfigDifference>) {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, expected_value) in expected {
+                let child = child_key(&path, key);
+                match actual.get(key) {
+                    Some(actual_value) => diff_values(child, actual_value, expected_value, out),
+                    None => out.push(ConfigDifference::Missing {
+                        path: child,
+                        expected: expected_value.clone(),
+                    }),
+                }
+            }
+            for (key, actual_value) in actual {
+                if !expected.contains_key(key) {
+                    out.push(ConfigDifference::Extra {
+                        path: child_key(&path, key),
+                        actual: actual_value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(actual), Value::Array(expected)) => {
+            for index in 0..actual.len().max(expected.len()) {
+                let child = format!("{path}[{index}]");
+                match (actual.get(index), expected.get(index)) {
+                    (Some(actual), Some(expected)) => diff_values(child, actual, expected, out),
+                    (None, Some(expected)) => out.push(ConfigDifference::Missing {
+                        path: child,
+                        expected: expected.clone(),
+                    }),
+                    (Some(actual), None) => out.push(ConfigDifference::Extra {
+                        path: child,
+                        actual: actual.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if actual != expected => out.push(ConfigDifference::Changed {
+            path,
+            actual: actual.clone(),
+            expected: expected.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn child_key(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+/// Asserts that two configurations are equal, listing the differing keys on failure
+///
+/// Both arguments only need to implement [`Serialize`](serde::Serialize); they are
+/// compared with [`config_diff`](crate::testing::config_diff). Like [`assert_eq!`], an
+/// optional format string and arguments are added to the failure message.
+///
+/// ```rust
+/// use superconfig::assert_config_eq;
+/// use serde_json::json;
+///
+/// assert_config_eq!(json!({ "port": 8080 }), json!({ "port": 8080 }));
+/// ```
+///
+/// ```rust,should_panic
+/// use superconfig::assert_config_eq;
+/// use serde_json::json;
+///
+/// // Panics with "~ port: expected 8080, found 9090"
+/// assert_config_eq!(json!({ "port": 9090 }), json!({ "port": 8080 }), "loading {}", "app.toml");
+/// ```
+#[macro_export]
+macro_rules! assert_config_eq {
+    (@check $actual:expr, $expected:expr, $context:expr) => {
+        match $crate::testing::config_diff(&$actual, &$expected) {
+            ::std::result::Result::Ok(differences) => {
+                if !differences.is_empty() {
+                    ::std::panic!(
+                        "{}{}",
+                        $context,
+                        $crate::testing::format_diff(&differences)
+                    );
+                }
+            }
+            ::std::result::Result::Err(error) => {
+                ::std::panic!("assert_config_eq!: failed to serialize configuration: {error}");
+            }
+        }
+    };
+    ($actual:expr, $expected:expr $(,)?) => {
+        $crate::assert_config_eq!(@check $actual, $expected, ::std::string::String::new())
+    };
+    ($actual:expr, $expected:expr, $($arg:tt)+) => {
+        $crate::assert_config_eq!(@check $actual, $expected, ::std::format!("{}\n", ::std::format_args!($($arg)+)))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_equal_configurations_have_no_differences() {
+        let config = json!({ "host": "localhost", "features": ["auth"], "db": { "pool": 5 } });
+        assert!(config_diff(&config, &config.clone()).unwrap().is_empty());
+        assert_config_eq!(config, config.clone());
+    }
+
+    #[test]
+    fn test_differences_are_listed_by_path() {
+        let actual = json!({
+            "port": 9090,
+            "debug": true,
+            "db": { "url": "postgres://db" },
+            "features": ["auth", "metrics", "tracing"]
+        });
+        let expected = json!({
+            "port": 8080,
+            "db": { "url": "postgres://db", "pool": 10 },
+            "features": ["auth", "logging"]
+        });
+
+        let differences = config_diff(&actual, &expected).unwrap();
+        let paths: Vec<&str> = differences.iter().map(ConfigDifference::path).collect();
+        assert_eq!(
+            paths,
+            ["db.pool", "debug", "features[1]", "features[2]", "port"]
+        );
+        assert_eq!(
+            format_diff(&differences),
+            "configurations differ (5 differences):\n  \
+             - db.pool: missing, expected 10\n  \
+             + debug: unexpected, found true\n  \
+             ~ features[1]: expected \"logging\", found \"metrics\"\n  \
+             + features[2]: unexpected, found \"tracing\"\n  \
+             ~ port: expected 8080, found 9090"
+        );
+    }
+
+    #[test]
+    fn test_type_changes_are_reported_at_the_key() {
+        let differences =
+            config_diff(&json!({ "db": "sqlite" }), &json!({ "db": { "pool": 1 } })).unwrap();
+        assert_eq!(
+            differences,
+            [ConfigDifference::Changed {
+                path: "db".to_string(),
+                actual: json!("sqlite"),
+                expected: json!({ "pool": 1 }),
+            }]
+        );
+        assert_eq!(
+            config_diff(&json!(1), &json!(2)).unwrap()[0].to_string(),
+            "~ (root): expected 2, found 1"
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "while loading app.toml\nconfigurations differ (1 difference):\n  ~ port"
+    )]
+    fn test_macro_panics_with_the_diff() {
+        assert_config_eq!(
+            json!({ "port": 9090 }),
+            json!({ "port": 8080 }),
+            "while loading {}",
+            "app.toml"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Human-readable durations and byte sizes
+//!
+//! Configuration files and environment variables often spell timeouts and limits the way
+//! people say them: `"30s"`, `"1h30m"`, `"10MB"`. [`SuperConfig::get_duration`] and
+//! [`SuperConfig::get_bytes`] read such values with the parsers of this module, which
+//! also accept plain numbers.
+//!
+//! ## Durations
+//!
+//! One or more `<number><unit>` parts, optionally separated by spaces, summed up. The
+//! number may have a fraction (`"1.5h"`). A number without a unit is in seconds.
+//!
+//! | Unit | Spellings |
+//! |------|-----------|
+//! | nanoseconds | `ns` |
+//! | microseconds | `us`, `µs` |
+//! | milliseconds | `ms` |
+//! | seconds | `s`, `sec`, `secs`, `second`, `seconds` |
+//! | minutes | `m`, `min`, `mins`, `minute`, `minutes` |
+//! | hours | `h`, `hr`, `hrs`, `hour`, `hours` |
+//! | days | `d`, `day`, `days` |
+//! | weeks | `w`, `week`, `weeks` |
+//!
+//! ## Byte Sizes
+//!
+//! A `<number><unit>` with an optional space, where units are case-insensitive. `KB`,
+//! `MB`, `GB` and `TB` are powers of 1000; `KiB`, `MiB`, `GiB` and `TiB` are powers of
+//! 1024. A number without a unit, or with `B`, is in bytes.
+//!
+//! ## Usage Examples
+//!
+//! ```rust
+//! use superconfig::units::{parse_bytes, parse_duration};
+//! use std::time::Duration;
+//!
+//! assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+//! assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+//! assert_eq!(parse_bytes("10MB"), Some(10_000_000));
+//! assert_eq!(parse_bytes("1.5 KiB"), Some(1536));
+//! assert_eq!(parse_bytes("ten"), None);
+//! ```
+//!
+//! [`SuperConfig::get_duration`]: crate::SuperConfig::get_duration
+//! [`SuperConfig::get_bytes`]: crate::SuperConfig::get_bytes
+
+use std::time::Duration;
+
+/// Duration units and their length in seconds
+const DURATION_UNITS: [(&[&str], f64); 8] = [
+    (&["ns"], 1e-9),
+    (&["us", "µs"], 1e-6),
+    (&["ms"], 1e-3),
+    (&["s", "sec", "secs", "second", "seconds"], 1.0),
+    (&["m", "min", "mins", "minute", "minutes"], 60.0),
+    (&["h", "hr", "hrs", "hour", "hours"], 3_600.0),
+    (&["d", "day", "days"], 86_400.0),
+    (&["w", "week", "weeks"], 604_800.0),
+];
+
+/// Byte size units, lowercase, and their size in bytes
+const BYTE_UNITS: [(&str, u64); 9] = [
+    ("b", 1),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+];
+
+/// Parse a human-readable duration such as `"30s"`, `"5m"` or `"1h 30m"`
+///
+/// Returns `None` if the text isn't a duration. See the [module](self) for the units.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    if let Ok(seconds) = text.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+
+    if text.is_empty() {
+        return None;
+    }
+    let mut rest = text;
+    let mut seconds = 0.0;
+    while !rest.is_empty() {
+        let (number, unit, remainder) = split_quantity(rest)?;
+        let (_, scale) = DURATION_UNITS
+            .iter()
+            .find(|(spellings, _)| spellings.contains(&unit))?;
+        seconds += number * scale;
+        rest = remainder.trim_start();
+    }
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Parse a human-readable byte size such as `"512"`, `"10MB"` or `"1.5 GiB"`
+///
+/// Returns `None` if the text isn't a byte size or doesn't fit in a `u64`. See the
+/// [module](self) for the units.
+pub fn parse_bytes(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if let Ok(bytes) = text.parse::<u64>() {
+        return Some(bytes);
+    }
+
+    let (number, unit, rest) = split_quantity(text)?;
+    if !rest.trim().is_empty() {
+        return None;
+    }
+    let unit = unit.to_ascii_lowercase();
+    let (_, scale) = BYTE_UNITS.iter().find(|(name, _)| *name == unit)?;
+    let bytes = (number * *scale as f64).round();
+    (bytes >= 0.0 && bytes < u64::MAX as f64).then_some(bytes as u64)
+}
+
+/// Split a leading `<number><unit>` off `text`, returning the number, the unit and the
+/// rest of the text
+fn split_quantity(text: &str) -> Option<(f64, &str, &str)> {
+    let number_end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let number = text[..number_end].parse::<f64>().ok()?;
+
+    let unit_text = text[number_end..].trim_start();
+    let unit_end = unit_text
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(unit_text.len());
+    let unit = &unit_text[..unit_end];
+    if unit.is_empty() {
+        return None;
+    }
+    Some((number, unit, &unit_text[unit_end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("0.5"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("5 minutes"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::from_secs(5_400)));
+        assert_eq!(
+            parse_duration("1h 30m 15s"),
+            Some(Duration::from_secs(5_415))
+        );
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(172_800)));
+        assert_eq!(parse_duration("1w"), Some(Duration::from_secs(604_800)));
+        assert_eq!(parse_duration("100us"), Some(Duration::from_micros(100)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("10ns"), Some(Duration::from_nanos(10)));
+
+        for invalid in ["", "s", "5 parsecs", "-5s", "-1", "5s junk", "1..5s"] {
+            assert_eq!(parse_duration(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("512"), Some(512));
+        assert_eq!(parse_bytes("512B"), Some(512));
+        assert_eq!(parse_bytes("10KB"), Some(10_000));
+        assert_eq!(parse_bytes("10 mb"), Some(10_000_000));
+        assert_eq!(parse_bytes("2GB"), Some(2_000_000_000));
+        assert_eq!(parse_bytes("1TB"), Some(1_000_000_000_000));
+        assert_eq!(parse_bytes("1KiB"), Some(1_024));
+        assert_eq!(parse_bytes("1.5 MiB"), Some(1_572_864));
+        assert_eq!(parse_bytes("1GiB"), Some(1_073_741_824));
+
+        for invalid in ["", "MB", "10 parsecs", "-1KB", "10MB 5KB", "99999999999TiB"] {
+            assert_eq!(parse_bytes(invalid), None, "{invalid}");
+        }
+    }
+}
@@ -12,7 +12,10 @@ use serde::{Deserialize, Serialize};
 use serial_test::serial;
 use std::env;
 use std::fs;
-use superconfig::{SuperConfig, Wildcard};
+use superconfig::{
+    DuplicateKeyPolicy, EnvOverrides, MergePolicy, SuperConfig, VerifyPolicy, Wildcard,
+    WildcardBuilder, assert_config_eq,
+};
 use tempfile::TempDir;
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
@@ -63,8 +66,7 @@ fn test_superconfig_builder_basic() {
     let config = SuperConfig::new().with_defaults(TestConfig::default());
 
     let result: TestConfig = config.extract().expect("Failed to extract config");
-    assert_eq!(result.host, "localhost");
-    assert_eq!(result.port, 8080);
+    assert_config_eq!(result, TestConfig::default());
 }
 
 #[test]
@@ -139,9 +141,19 @@ timeout = 60
         .with_file(&config_path);
 
     let extracted: TestConfig = config.extract()?;
-    assert_eq!(extracted.host, "toml.example.com");
-    assert_eq!(extracted.port, 9090);
-    assert_eq!(extracted.database.url, "mysql://localhost");
+    assert_config_eq!(
+        extracted,
+        TestConfig {
+            host: "toml.example.com".to_string(),
+            port: 9090,
+            features: vec![],
+            database: DatabaseConfig {
+                url: "mysql://localhost".to_string(),
+                timeout: 60,
+                allowed_origins: vec![],
+            },
+        }
+    );
 
     // Test access methods
     let host = config.get_string("host")?;
@@ -889,3 +901,1078 @@ fn test_conversion_methods() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_verified_file_policies() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, r#"host = "verified.example.com""#)?;
+    fs::write(
+        temp_dir.path().join("config.toml.sha256"),
+        "0000000000000000000000000000000000000000000000000000000000000000  config.toml\n",
+    )?;
+
+    // Warn: tampered file still loads, but a warning is collected
+    let config = SuperConfig::new().with_verified_file(&config_path, VerifyPolicy::Warn);
+    assert!(config.has_warnings());
+    assert!(config.warnings()[0].contains("Checksum verification failed"));
+    let warned: TestConfig = config.extract()?;
+    assert_eq!(warned.host, "verified.example.com");
+
+    // Enforce: tampered file is refused
+    let config = SuperConfig::new().with_verified_file(&config_path, VerifyPolicy::Enforce);
+    assert!(config.extract::<TestConfig>().is_err());
+
+    // Ignore: no verification, no warnings
+    let config = SuperConfig::new().with_verified_file(&config_path, VerifyPolicy::Ignore);
+    assert!(!config.has_warnings());
+    let ignored: TestConfig = config.extract()?;
+    assert_eq!(ignored.host, "verified.example.com");
+
+    Ok(())
+}
+
+#[test]
+fn test_utf16_and_bom_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+
+    // UTF-16LE JSON with BOM and CRLF line endings, as written by Windows export tools
+    let utf16_file = temp_dir.path().join("export.json");
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(
+        "{\r\n  \"host\": \"utf16.example.com\"\r\n}\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes),
+    );
+    fs::write(&utf16_file, bytes)?;
+
+    let config = SuperConfig::new().with_file(&utf16_file);
+    assert!(config.warnings()[0].contains("converted from UTF-16LE"));
+    let utf16_config: TestConfig = config.extract()?;
+    assert_eq!(utf16_config.host, "utf16.example.com");
+
+    // UTF-8 TOML with BOM
+    let bom_file = temp_dir.path().join("config.toml");
+    fs::write(&bom_file, b"\xEF\xBB\xBFhost = \"bom.example.com\"\r\n")?;
+
+    let config = SuperConfig::new().with_file(&bom_file);
+    assert!(config.warnings()[0].contains("byte order mark"));
+    let bom_config: TestConfig = config.extract()?;
+    assert_eq!(bom_config.host, "bom.example.com");
+
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_key_policies() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let yaml_file = temp_dir.path().join("config.yaml");
+    fs::write(
+        &yaml_file,
+        "host: first.example.com\nport: 80\nhost: last.example.com\n",
+    )?;
+
+    // Default: parsers keep the last definition without complaint
+    let config = SuperConfig::new().with_file(&yaml_file);
+    assert!(!config.has_warnings());
+    assert_eq!(config.extract::<TestConfig>()?.host, "last.example.com");
+
+    let config = SuperConfig::new().with_file_duplicate_keys(&yaml_file, DuplicateKeyPolicy::Warn);
+    assert!(config.warnings()[0].contains("duplicate keys: host"));
+    assert_eq!(config.extract::<TestConfig>()?.host, "last.example.com");
+
+    let config =
+        SuperConfig::new().with_file_duplicate_keys(&yaml_file, DuplicateKeyPolicy::FirstWins);
+    assert_eq!(config.extract::<TestConfig>()?.host, "first.example.com");
+
+    let config = SuperConfig::new().with_file_duplicate_keys(&yaml_file, DuplicateKeyPolicy::Error);
+    assert!(config.extract::<TestConfig>().is_err());
+
+    // Files discovered by one Wildcard provider share a precedence level
+    let conf_d = temp_dir.path().join("conf.d");
+    fs::create_dir(&conf_d)?;
+    fs::write(
+        conf_d.join("10-base.toml"),
+        "host = \"base.example.com\"\nport = 80\n",
+    )?;
+    fs::write(conf_d.join("20-site.toml"), "host = \"site.example.com\"\n")?;
+    let pattern = format!("{}/*.toml", conf_d.display());
+
+    let wildcard = |policy| Wildcard::new(&pattern).with_duplicate_keys(policy);
+
+    let config = SuperConfig::new().merge_validated(wildcard(DuplicateKeyPolicy::Warn));
+    assert!(config.warnings()[0].contains("`host` is defined in both"));
+    let warned: TestConfig = config.extract()?;
+    assert_eq!(warned.host, "site.example.com");
+    assert_eq!(warned.port, 80);
+
+    let config = SuperConfig::new().merge(wildcard(DuplicateKeyPolicy::FirstWins));
+    assert_eq!(config.extract::<TestConfig>()?.host, "base.example.com");
+
+    let config = SuperConfig::new().merge(wildcard(DuplicateKeyPolicy::Error));
+    let error = config.extract::<TestConfig>().unwrap_err();
+    assert!(error.to_string().contains("Duplicate keys"));
+
+    Ok(())
+}
+
+#[test]
+fn test_emergency_overrides_are_final_layer() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config_file = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_file,
+        "host = \"file.example.com\"\nport = 8080\n\n[database]\ntimeout = 30\n",
+    )?;
+
+    let overrides = EnvOverrides::from_vars([
+        ("SUPERCONFIG_OVERRIDE__port", "9090"),
+        ("SUPERCONFIG_OVERRIDE__database__timeout", "5"),
+    ]);
+
+    // Sources merged after the overrides were enabled still lose to them
+    let config = SuperConfig::new()
+        .with_defaults(TestConfig::default())
+        .with_overrides(overrides)
+        .with_file(&config_file)
+        .with_defaults_string("port = 1234");
+
+    let result: TestConfig = config.extract()?;
+    assert_eq!(result.host, "file.example.com");
+    assert_eq!(result.port, 9090);
+    assert_eq!(result.database.timeout, 5);
+
+    assert_eq!(config.env_overrides(), vec!["database.timeout", "port"]);
+    assert!(config.warnings()[0].contains(
+        "Emergency override active: database.timeout set by SUPERCONFIG_OVERRIDE__database__timeout"
+    ));
+    assert!(
+        config
+            .debug_config()?
+            .contains("EMERGENCY OVERRIDES ACTIVE")
+    );
+    let source = config
+        .find_metadata("port")
+        .expect("port should have a source");
+    assert!(source.name.contains("EMERGENCY OVERRIDE"));
+
+    // Without opting in, nothing is overridden
+    let config = SuperConfig::new().with_file(&config_file);
+    assert!(config.env_overrides().is_empty());
+    assert_eq!(config.extract::<TestConfig>()?.port, 8080);
+
+    Ok(())
+}
+
+#[test]
+fn test_secrets_are_masked_in_exports_and_errors() -> Result<(), Box<dyn std::error::Error>> {
+    use superconfig::schema::ConfigSchema;
+    use superconfig::secret::{RedactionPolicy, SecretString};
+
+    let temp_dir = TempDir::new()?;
+    let config_file = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_file,
+        r#"
+host = "secret-free.example.com"
+port = 8080
+
+[database]
+password = "hunter2"
+dsn = "postgres://admin:pw@db"
+"#,
+    )?;
+
+    #[derive(Deserialize)]
+    struct Database {
+        password: SecretString,
+        dsn: String,
+    }
+
+    let schema = ConfigSchema::from_value(&serde_json::json!({"database": {"dsn": ""}}))
+        .secret("database.dsn");
+    let config = SuperConfig::new()
+        .with_redaction(RedactionPolicy::default().with_schema(&schema))
+        .with_file(&config_file);
+
+    // Extraction keeps the values, secret types mask them
+    let database: Database = config.extract_inner("database")?;
+    assert_eq!(database.password.expose_secret(), "hunter2");
+    assert_eq!(database.dsn, "postgres://admin:pw@db");
+    assert_eq!(config.get_secret("database.password")?.len(), 7);
+
+    // Exports and debug output never contain secrets, even extracted as plain strings
+    for output in [
+        config.as_json()?,
+        config.as_yaml()?,
+        config.as_toml()?,
+        config.debug_config()?,
+    ] {
+        assert!(output.contains("secret-free.example.com"));
+        assert!(!output.contains("hunter2"));
+        assert!(!output.contains("postgres://"));
+    }
+
+    // Values quoted by extraction errors are masked for secret keys
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Numeric {
+        database: NumericDatabase,
+    }
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct NumericDatabase {
+        password: u32,
+    }
+    let error = config.extract::<Numeric>().unwrap_err().to_string();
+    assert!(error.contains("***MASKED***"));
+    assert!(!error.contains("hunter2"));
+
+    // Redaction can be disabled explicitly
+    let config = config.with_redaction(RedactionPolicy::none());
+    assert!(config.as_json()?.contains("hunter2"));
+
+    Ok(())
+}
+
+#[test]
+fn test_keyed_array_merging() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let base_config = temp_dir.path().join("base.toml");
+    fs::write(
+        &base_config,
+        r#"
+[[cluster.servers]]
+name = "primary"
+host = "10.0.0.1"
+port = 5432
+
+[[cluster.servers]]
+name = "replica"
+host = "10.0.0.2"
+port = 5432
+
+[[cluster.servers]]
+name = "legacy"
+host = "10.0.0.9"
+"#,
+    )?;
+    let override_config = temp_dir.path().join("override.toml");
+    fs::write(
+        &override_config,
+        r#"
+[cluster]
+servers_remove = ["legacy"]
+
+[[cluster.servers]]
+name = "replica"
+port = 6432
+
+[[cluster.servers_add]]
+name = "analytics"
+host = "10.0.0.3"
+"#,
+    )?;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        name: String,
+        host: String,
+        port: Option<u16>,
+    }
+
+    let config = SuperConfig::new()
+        .with_array_key("cluster.servers", "name")
+        .with_file(&base_config)
+        .with_file(&override_config);
+    let servers: Vec<Server> = config.extract_inner("cluster.servers")?;
+
+    let summary: Vec<(&str, &str, Option<u16>)> = servers
+        .iter()
+        .map(|server| (server.name.as_str(), server.host.as_str(), server.port))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("primary", "10.0.0.1", Some(5432)),
+            ("replica", "10.0.0.2", Some(6432)),
+            ("analytics", "10.0.0.3", None),
+        ]
+    );
+
+    // Without a key, the later array replaces the earlier one
+    let config = SuperConfig::new()
+        .with_file(&base_config)
+        .with_file(&override_config);
+    let names: Vec<String> = config
+        .extract_inner::<Vec<serde_json::Value>>("cluster.servers")?
+        .iter()
+        .filter_map(|server| server["name"].as_str().map(str::to_string))
+        .collect();
+    assert_eq!(names, vec!["replica", "analytics"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_lossy_reports_problems_and_masks_secrets() -> Result<(), Box<dyn std::error::Error>>
+{
+    use superconfig::secret::RedactionPolicy;
+
+    let temp_dir = TempDir::new()?;
+    let config_file = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_file,
+        r#"
+host = "example.com"
+port = "eighty"
+
+[database]
+password = "hunter2"
+"#,
+    )?;
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct Database {
+        password: u32,
+        pool: u32,
+    }
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct Config {
+        host: String,
+        port: u16,
+        database: Database,
+    }
+
+    let config = SuperConfig::new()
+        .with_redaction(RedactionPolicy::default())
+        .with_file(&config_file);
+    assert!(config.extract::<Config>().is_err());
+
+    let (extracted, report) = config.extract_lossy::<Config>()?;
+    assert_eq!(extracted.host, "example.com");
+    assert_eq!(extracted.port, 0);
+    assert_eq!(report.missing, ["database.pool"]);
+    let invalid: Vec<&str> = report
+        .invalid
+        .iter()
+        .map(|field| field.path.as_str())
+        .collect();
+    assert_eq!(invalid, ["database.password", "port"]);
+
+    // Secret values never appear in the report
+    let report = report.to_string();
+    assert!(report.contains("eighty"));
+    assert!(report.contains("***MASKED***"));
+    assert!(!report.contains("hunter2"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_path_keys_resolve_against_their_file() -> Result<(), Box<dyn std::error::Error>> {
+    use std::path::PathBuf;
+    use superconfig::schema::ConfigSchema;
+
+    let temp_dir = TempDir::new()?;
+    let base_dir = temp_dir.path().join("base");
+    let local_dir = temp_dir.path().join("local");
+    fs::create_dir_all(base_dir.join("certs"))?;
+    fs::create_dir_all(&local_dir)?;
+    fs::write(base_dir.join("certs/ca.pem"), "")?;
+    fs::write(
+        base_dir.join("10-app.toml"),
+        "ca = \"certs/ca.pem\"\nlog_dir = \"logs\"",
+    )?;
+    fs::write(local_dir.join("20-app.toml"), "log_dir = \"./logs\\\\app\"")?;
+
+    let schema = ConfigSchema::from_value(&serde_json::json!({"ca": "", "log_dir": ""}))
+        .existing_path("ca")
+        .path("log_dir");
+    let patterns = [
+        format!("{}/*.toml", base_dir.display()),
+        format!("{}/*.toml", local_dir.display()),
+    ];
+
+    // Files merged by a Wildcard resolve against their own directory too
+    let config = SuperConfig::new()
+        .with_path_schema(&schema)
+        .merge(Wildcard::from_patterns(&patterns).with_path_keys(["ca", "log_dir"]));
+    let ca: PathBuf = config.extract_inner("ca")?;
+    let log_dir: PathBuf = config.extract_inner("log_dir")?;
+    assert_eq!(ca, base_dir.join("certs").join("ca.pem"));
+    assert_eq!(log_dir, local_dir.join("logs").join("app"));
+    assert!(config.extract::<serde_json::Value>().is_ok());
+
+    // The working directory plays no part, and missing required paths fail extraction
+    fs::remove_file(base_dir.join("certs/ca.pem"))?;
+    let original_dir = env::current_dir()?;
+    env::set_current_dir(&local_dir)?;
+    let result = SuperConfig::new()
+        .with_path_schema(&schema)
+        .with_file(base_dir.join("10-app.toml"))
+        .extract::<serde_json::Value>();
+    env::set_current_dir(original_dir)?;
+
+    let error = result.unwrap_err();
+    assert_eq!(error.path, ["ca"]);
+    assert!(error.to_string().contains("an existing path"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_discovered_sources_from_config_and_env() -> Result<(), Box<dyn std::error::Error>> {
+    use superconfig::discovery::SOURCES_ENV;
+
+    let temp_dir = TempDir::new()?;
+    let site_dir = temp_dir.path().join("site");
+    let extra_dir = temp_dir.path().join("extra");
+    fs::create_dir_all(&site_dir)?;
+    fs::create_dir_all(&extra_dir)?;
+    fs::write(
+        temp_dir.path().join("app.toml"),
+        "host = \"localhost\"\nport = 80\nsources = [\"site/*.toml\"]",
+    )?;
+    fs::write(site_dir.join("port.toml"), "port = 8080")?;
+    fs::write(extra_dir.join("host.toml"), "host = \"site.example.com\"")?;
+
+    unsafe {
+        env::set_var(SOURCES_ENV, format!("{}/*.toml", extra_dir.display()));
+    }
+    let config = SuperConfig::new()
+        .with_file(temp_dir.path().join("app.toml"))
+        .with_discovered_sources("sources")
+        .with_env("DISCOVERY_TEST_");
+    unsafe {
+        env::remove_var(SOURCES_ENV);
+    }
+
+    // Patterns of the environment variable are loaded before those of the configuration
+    assert_eq!(config.extract_inner::<String>("host")?, "site.example.com");
+    assert_eq!(config.extract_inner::<u16>("port")?, 8080);
+    assert!(config.warnings().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_ini_and_dotenv_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let ini_file = temp_dir.path().join("app.ini");
+    fs::write(
+        &ini_file,
+        "; defaults\nhost = ini.example.com\nport = 80\n\n[database]\nurl = postgres://db\ntimeout = 5",
+    )?;
+    let dotenv_file = temp_dir.path().join(".env.local");
+    fs::write(&dotenv_file, "export PORT=8080\nDATABASE__TIMEOUT=10")?;
+    // Unknown extension: INI detected from its bare values
+    let cfg_file = temp_dir.path().join("legacy.cfg");
+    fs::write(&cfg_file, "[database]\nurl = mysql://legacy")?;
+
+    let config: TestConfig = SuperConfig::new()
+        .with_file(&ini_file)
+        .with_file(&dotenv_file)
+        .extract()?;
+    assert_eq!(config.host, "ini.example.com");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.database.url, "postgres://db");
+    assert_eq!(config.database.timeout, 10);
+
+    let legacy: TestConfig = SuperConfig::new().with_file(&cfg_file).extract()?;
+    assert_eq!(legacy.database.url, "mysql://legacy");
+
+    Ok(())
+}
+
+#[test]
+fn test_yaml_anchors_and_merge_keys() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let yaml_file = temp_dir.path().join("config.yaml");
+    fs::write(
+        &yaml_file,
+        "defaults: &defaults\n  url: postgres://shared\n  timeout: 30\n\
+         database:\n  <<: *defaults\n  timeout: 5\nfeatures: &features [auth]\n",
+    )?;
+
+    let config: TestConfig = SuperConfig::new().with_file(&yaml_file).extract()?;
+    assert_eq!(config.database.url, "postgres://shared");
+    assert_eq!(config.database.timeout, 5);
+    assert_eq!(config.features, vec!["auth"]);
+
+    Ok(())
+}
+
+#[cfg(feature = "json5")]
+#[test]
+fn test_json5_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let json5_file = temp_dir.path().join("config.json5");
+    fs::write(
+        &json5_file,
+        "{\n  // JSON5 allows comments\n  host: 'json5.example.com',\n  port: 0x1F90,\n}",
+    )?;
+    // A .json file with comments and trailing commas, and one without an extension
+    let json_file = temp_dir.path().join("config.json");
+    fs::write(&json_file, "{\n  \"features\": [\"auth\",], // trailing\n}")?;
+    let bare_file = temp_dir.path().join("database");
+    fs::write(
+        &bare_file,
+        "/* db */ {database: {url: 'postgres://db', timeout: 5,}}",
+    )?;
+
+    let config: TestConfig = SuperConfig::new()
+        .with_file(&json5_file)
+        .with_file(&json_file)
+        .with_file(&bare_file)
+        .extract()?;
+    assert_eq!(config.host, "json5.example.com");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.features, vec!["auth"]);
+    assert_eq!(config.database.url, "postgres://db");
+    assert_eq!(config.database.timeout, 5);
+
+    Ok(())
+}
+
+#[test]
+fn test_include_directive() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let shared = temp_dir.path().join("shared");
+    fs::create_dir(&shared)?;
+    fs::write(
+        shared.join("base.yaml"),
+        "host: base.example.com\nport: 80\nfeatures: [auth]\ndatabase:\n  timeout: 5\n",
+    )?;
+    let conf_d = temp_dir.path().join("conf.d");
+    fs::create_dir(&conf_d)?;
+    fs::write(
+        conf_d.join("10-app.toml"),
+        "include = [\"../shared/base.yaml\"]\nport = 8080\nfeatures_add = [\"cache\"]\n",
+    )?;
+
+    let config: TestConfig = SuperConfig::new()
+        .with_file(conf_d.join("10-app.toml"))
+        .extract()?;
+    assert_eq!(config.host, "base.example.com");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.features, vec!["auth", "cache"]);
+    assert_eq!(config.database.timeout, 5);
+
+    // Files discovered by a Wildcard provider resolve their includes the same way
+    let pattern = format!("{}/*.toml", conf_d.display());
+    let config: TestConfig = SuperConfig::new()
+        .merge(Wildcard::new(&pattern))
+        .extract()?;
+    assert_eq!(config.host, "base.example.com");
+    assert_eq!(config.port, 8080);
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_policies() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("10-base.toml"),
+        "features = [\"auth\", \"cache\"]\n[database]\nurl = \"postgres://base\"\ntimeout = 5\n",
+    )?;
+    fs::write(
+        temp_dir.path().join("20-site.toml"),
+        "features = [\"cache\", \"metrics\"]\n[database]\nurl = \"postgres://site\"\n",
+    )?;
+    let pattern = format!("{}/*.toml", temp_dir.path().display());
+
+    // Between the files of one Wildcard provider
+    let provider = Wildcard::new(&pattern)
+        .with_merge_policy("features", MergePolicy::Unique)
+        .with_merge_policy("database", MergePolicy::Replace);
+    let config: TestConfig = SuperConfig::new().merge(provider).extract()?;
+    assert_eq!(config.features, vec!["auth", "cache", "metrics"]);
+    assert_eq!(config.database.url, "postgres://site");
+    assert_eq!(config.database.timeout, 0);
+
+    // Between sources merged by SuperConfig
+    let config: TestConfig = SuperConfig::new()
+        .with_merge_policy("features", MergePolicy::Append)
+        .with_file(temp_dir.path().join("10-base.toml"))
+        .with_file(temp_dir.path().join("20-site.toml"))
+        .extract()?;
+    assert_eq!(config.features, vec!["auth", "cache", "cache", "metrics"]);
+    assert_eq!(config.database.timeout, 5);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_key_provenance() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let app = temp_dir.path().join("app.toml");
+    fs::write(
+        &app,
+        "host = \"app.example.com\"\n\n[database]\nurl = \"postgres://app\"\ntimeout = 5\n",
+    )?;
+    let conf_d = temp_dir.path().join("conf.d");
+    fs::create_dir(&conf_d)?;
+    fs::write(conf_d.join("10-site.yaml"), "database:\n  timeout: 30\n")?;
+
+    unsafe { env::set_var("PROVENANCE_TEST_PORT", "9090") };
+    let config = SuperConfig::new()
+        .with_defaults_string(r#"{"host": "localhost", "features": ["auth"]}"#)
+        .with_file(&app)
+        .merge(Wildcard::new(&format!("{}/*.yaml", conf_d.display())))
+        .with_env("PROVENANCE_TEST_");
+    unsafe { env::remove_var("PROVENANCE_TEST_PORT") };
+
+    let url = config.origin("database.url").expect("url has an origin");
+    assert_eq!(url.file(), Some(app.as_path()));
+    assert_eq!(url.line, Some(4));
+
+    let timeout = config
+        .origin("database.timeout")
+        .expect("timeout has an origin");
+    assert_eq!(timeout.file(), Some(conf_d.join("10-site.yaml").as_path()));
+    assert_eq!(timeout.line, Some(2));
+
+    let port = config.origin("port").expect("port has an origin");
+    assert_eq!(port.provider, "Env::Nested");
+    assert_eq!(port.to_string(), "PROVENANCE_TEST_PORT (Env::Nested)");
+
+    assert_eq!(config.origin("host").and_then(|o| o.line), Some(1));
+    assert!(config.origin("features.0").is_some());
+    assert!(config.origin("database").is_none());
+    assert!(config.origin("missing").is_none());
+
+    let report = config.debug_provenance();
+    assert!(report.contains("database.url <- "), "{report}");
+    assert!(report.contains("port <- PROVENANCE_TEST_PORT (Env::Nested)"));
+    assert_eq!(report.lines().count(), 5);
+
+    Ok(())
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_defaults_and_partial_overrides() -> Result<(), Box<dyn std::error::Error>> {
+    use superconfig::{Partial, PartialConfig};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PartialConfig)]
+    struct Server {
+        host: String,
+        port: u16,
+        #[partial(nested)]
+        database: Database,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PartialConfig)]
+    struct Database {
+        url: String,
+        timeout: u32,
+    }
+
+    let defaults = Server {
+        host: "localhost".to_string(),
+        port: 8080,
+        database: Database {
+            url: "postgres://localhost".to_string(),
+            timeout: 5,
+        },
+    };
+
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path().join("base.toml");
+    fs::write(&base, "host = \"example.com\"\n")?;
+    let server = SuperConfig::new()
+        .with_file(&base)
+        .extract_with_defaults(defaults.clone())?;
+    assert_eq!(server.host, "example.com");
+    assert_eq!(server.database, defaults.database);
+
+    // An override file setting a few keys, applied to the complete configuration
+    let overrides = temp_dir.path().join("override.yaml");
+    fs::write(&overrides, "port: 9090\ndatabase:\n  timeout: 30\n")?;
+    let partial: Partial<Server> = SuperConfig::new().with_file(&overrides).extract()?;
+    assert_eq!(partial.host, None);
+
+    let mut server = server;
+    server.apply(partial);
+    assert_eq!(server.host, "example.com");
+    assert_eq!(server.port, 9090);
+    assert_eq!(server.database.url, "postgres://localhost");
+    assert_eq!(server.database.timeout, 30);
+
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_wildcard_loading() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("000-base.toml"),
+        "host = \"base\"\nfeatures = [\"auth\"]\n",
+    )?;
+    for i in 1..200 {
+        fs::write(
+            temp_dir.path().join(format!("{i:03}-plugin.toml")),
+            format!("port = {i}\nfeatures_add = [\"plugin{i}\"]\n"),
+        )?;
+    }
+    let pattern = format!("{}/*.toml", temp_dir.path().display());
+
+    let sequential: TestConfig = SuperConfig::new()
+        .merge(Wildcard::new(&pattern))
+        .extract()?;
+    let parallel: TestConfig = SuperConfig::new()
+        .merge(Wildcard::new(&pattern).with_parallel_loading(4))
+        .extract()?;
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel.port, 199);
+    assert_eq!(parallel.features.len(), 200);
+    assert_eq!(parallel.features[1], "plugin1");
+
+    Ok(())
+}
+
+#[test]
+fn test_wildcard_strict_and_skipped_file_warnings() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("10-base.toml"),
+        "host = \"base\"\nport = 80\n",
+    )?;
+    fs::write(temp_dir.path().join("20-broken.toml"), "port = = 8080\n")?;
+    fs::write(
+        temp_dir.path().join("30-binary.cfg"),
+        [0x80, 0x81, 0xfe, 0x00],
+    )?;
+    let pattern = format!("{}/*.{{toml,cfg}}", temp_dir.path().display());
+
+    // Lenient by default: the readable files load and the others become warnings
+    let config = SuperConfig::new().merge_validated(Wildcard::new(&pattern));
+    let loaded: TestConfig = config.extract()?;
+    assert_eq!(loaded.host, "base");
+    assert_eq!(loaded.port, 80);
+    assert_eq!(config.warnings().len(), 1);
+    let warning = &config.warnings()[0];
+    assert!(warning.contains("skipped "), "{warning}");
+    assert!(
+        warning.contains("20-broken.toml: TOML parse error"),
+        "{warning}"
+    );
+    assert!(
+        warning.contains("30-binary.cfg: cannot read configuration file"),
+        "{warning}"
+    );
+
+    let strict = WildcardBuilder::new()
+        .pattern(&pattern)?
+        .strict(true)
+        .build()?;
+    let error = SuperConfig::new()
+        .merge(strict)
+        .extract::<TestConfig>()
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("20-broken.toml"), "{error}");
+
+    // A single file that no format can read fails instead of loading as empty
+    let error = SuperConfig::new()
+        .with_file(temp_dir.path().join("30-binary.cfg"))
+        .extract::<TestConfig>()
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("cannot read configuration file"), "{error}");
+
+    Ok(())
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn test_wildcard_watcher_merges_new_plugins() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+    use superconfig::WildcardEvent;
+
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("00-base.toml"),
+        "host = \"base\"\nfeatures = [\"auth\"]\n",
+    )?;
+    let pattern = format!("{}/*.toml", temp_dir.path().display());
+    let watcher = Wildcard::new(&pattern)
+        .watch()
+        .debounce(Duration::from_millis(20))
+        .start()?;
+    let changes = watcher.changes();
+
+    fs::write(
+        temp_dir.path().join("10-plugin.toml"),
+        "port = 9000\nfeatures_add = [\"metrics\"]\n",
+    )?;
+    loop {
+        let event = changes.recv_timeout(Duration::from_secs(10))?;
+        if matches!(&event, WildcardEvent::Reloaded { files, .. } if files.len() == 2) {
+            break;
+        }
+    }
+
+    let loaded: TestConfig = SuperConfig::from_figment(watcher.figment()).extract()?;
+    assert_eq!(loaded.host, "base");
+    assert_eq!(loaded.port, 9000);
+    assert_eq!(loaded.features, vec!["auth", "metrics"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_wildcard_profile_merge_order() -> Result<(), Box<dyn std::error::Error>> {
+    use superconfig::MergeOrder;
+
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("app-base.toml"),
+        "host = \"base\"\nport = 80\n",
+    )?;
+    fs::write(temp_dir.path().join("app-prod.toml"), "port = 443\n")?;
+    fs::write(
+        temp_dir.path().join("app-dev.toml"),
+        "host = \"dev\"\nport = 8080\n",
+    )?;
+    let pattern = format!("{}/*.toml", temp_dir.path().display());
+
+    let provider =
+        Wildcard::new(&pattern).with_merge_order(MergeOrder::profile("prod", "app-{profile}.toml"));
+    assert_eq!(provider.discover_files().len(), 2);
+    let loaded: TestConfig = SuperConfig::new().merge(provider).extract()?;
+    assert_eq!(loaded.host, "base");
+    assert_eq!(loaded.port, 443);
+
+    let dev = WildcardBuilder::new()
+        .pattern(&pattern)?
+        .profile("dev", "app-{profile}.toml")
+        .build()?;
+    let loaded: TestConfig = SuperConfig::new().merge(dev).extract()?;
+    assert_eq!((loaded.host.as_str(), loaded.port), ("dev", 8080));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_typed_getters() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new()?;
+    let config_path = temp_dir.path().join("app.toml");
+    fs::write(
+        &config_path,
+        "debug = \"on\"\ntimeout_ms = 2500\nmax_upload = \"10MB\"\n\n[cache]\nttl = \"1h 30m\"\n",
+    )?;
+    unsafe {
+        env::set_var("TYPED_CACHE_TTL", "45s");
+        env::set_var("TYPED_RETRIES", "-3");
+    }
+
+    let config = SuperConfig::new()
+        .with_file(&config_path)
+        .with_env("TYPED_");
+    assert!(config.get_bool("debug")?);
+    assert_eq!(config.get_u64("timeout_ms")?, 2500);
+    assert_eq!(config.get_i64("retries")?, -3);
+    assert_eq!(config.get_f64("timeout_ms")?, 2500.0);
+    assert_eq!(config.get_duration("cache.ttl")?, Duration::from_secs(45));
+    assert_eq!(config.get_bytes("max_upload")?, 10_000_000);
+
+    // Errors name the key, the value and the file that set it
+    let error = config.get_duration("max_upload").unwrap_err().to_string();
+    assert!(error.contains("max_upload"), "{error}");
+    assert!(error.contains("10MB"), "{error}");
+    assert!(error.contains("app.toml"), "{error}");
+    let error = config.get_u64("retries").unwrap_err().to_string();
+    assert!(error.contains("TYPED_RETRIES"), "{error}");
+    assert!(config.get_bool("missing").unwrap_err().missing());
+
+    unsafe {
+        env::remove_var("TYPED_CACHE_TTL");
+        env::remove_var("TYPED_RETRIES");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_json_schema_from_merged_config() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config_path = temp_dir.path().join("app.toml");
+    fs::write(
+        &config_path,
+        "host = \"localhost\"\nport = 8080\nfeatures = [\"auth\"]\n\n[[upstreams]]\nurl = \"http://a\"\nweight = 1\n\n[[upstreams]]\nurl = \"http://b\"\nweight = 0.5\n",
+    )?;
+
+    let config = SuperConfig::new().with_file(&config_path);
+    let schema = config.as_json_schema()?;
+    assert_eq!(schema["properties"]["port"]["type"], "integer");
+    assert_eq!(schema["properties"]["features"]["items"]["type"], "string");
+    let upstream = &schema["properties"]["upstreams"]["items"]["properties"];
+    assert_eq!(upstream["url"]["type"], "string");
+    assert_eq!(upstream["weight"]["type"], "number");
+
+    #[cfg(feature = "schemars")]
+    {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Server {
+            /// Host name to bind
+            host: String,
+            port: u16,
+        }
+
+        let schema = config.as_json_schema_for::<Server>()?;
+        assert_eq!(
+            schema["properties"]["host"]["description"],
+            "Host name to bind"
+        );
+        assert_eq!(schema["properties"]["port"]["maximum"], 65535);
+        assert_eq!(schema["properties"]["upstreams"]["type"], "array");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_lint_gates_config_files() -> Result<(), Box<dyn std::error::Error>> {
+    use superconfig::lint::{DeprecatedKeys, DuplicateKeys, EmptyValues, Severity, UnknownKeys};
+
+    let temp_dir = TempDir::new()?;
+    let base_path = temp_dir.path().join("base.toml");
+    let local_path = temp_dir.path().join("local.toml");
+    fs::write(
+        &base_path,
+        "host = \"example.com\"\nport = 80\n\n[database]\nurl = \"\"\n",
+    )?;
+    fs::write(&local_path, "port = 8080\nhots = \"typo\"\n")?;
+
+    let config = SuperConfig::new()
+        .with_defaults(TestConfig::default())
+        .with_file(&base_path)
+        .with_file(&local_path);
+    let report = config.lint(&[
+        &UnknownKeys::of::<TestConfig>()?,
+        &DeprecatedKeys::new().key("database.timeout", "database.timeout_ms"),
+        &DuplicateKeys,
+        &EmptyValues,
+    ])?;
+
+    let found: Vec<(&str, &str, Severity)> = report
+        .diagnostics
+        .iter()
+        .map(|d| (d.key.as_str(), d.rule.as_str(), d.severity))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("database.timeout", "deprecated-key", Severity::Warning),
+            ("database.url", "empty-value", Severity::Warning),
+            ("hots", "unknown-key", Severity::Error),
+            ("port", "duplicate-key", Severity::Warning),
+        ]
+    );
+    assert!(report.has_errors());
+
+    // Diagnostics point at the file and line that set the key
+    let typo = report.errors().next().unwrap();
+    let origin = typo.origin.as_ref().unwrap();
+    assert_eq!(origin.file(), Some(local_path.as_path()));
+    assert_eq!(origin.line, Some(2));
+    assert!(typo.message.contains("did you mean `host`"));
+    let output = report.to_string();
+    assert!(output.contains("1 errors and 3 warnings"), "{output}");
+    assert!(output.contains("base.toml:5"), "{output}");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_migrations_keep_old_config_files_working() -> Result<(), Box<dyn std::error::Error>> {
+    use superconfig::migration::MigrationMap;
+
+    let temp_dir = TempDir::new()?;
+    let config_path = temp_dir.path().join("legacy.toml");
+    fs::write(
+        &config_path,
+        "port = 8080\n\n[db]\nconnection = \"postgres://localhost\"\n",
+    )?;
+    unsafe {
+        env::set_var("MIGRATE_HOSTNAME", "example.com");
+    }
+
+    let migrations = MigrationMap::new()
+        .rename("hostname", "host")
+        .rename("db.connection", "database.url");
+    let config = SuperConfig::new()
+        .with_migrations(migrations)
+        .with_defaults(TestConfig::default())
+        .with_file(&config_path)
+        .with_env("MIGRATE_");
+
+    let extracted: TestConfig = config.extract()?;
+    assert_eq!(extracted.host, "example.com");
+    assert_eq!(extracted.database.url, "postgres://localhost");
+
+    let deprecations = config.deprecations();
+    assert_eq!(deprecations.len(), 2);
+    assert_eq!(deprecations[0].key, "db.connection");
+    assert_eq!(deprecations[0].origin.file(), Some(config_path.as_path()));
+    assert_eq!(deprecations[0].origin.line, Some(4));
+    assert_eq!(deprecations[1].replacement, "host");
+    assert_eq!(
+        deprecations[1].origin.name.as_deref(),
+        Some("MIGRATE_HOSTNAME")
+    );
+    assert!(config.warnings()[0].contains("legacy.toml:4"));
+
+    // The migrated keys are reported where the old ones were set
+    let origin = config.origin("database.url").unwrap();
+    assert_eq!(origin.file(), Some(config_path.as_path()));
+
+    unsafe {
+        env::remove_var("MIGRATE_HOSTNAME");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_write_to_file_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    use superconfig::export::ExportFormat;
+
+    let temp_dir = TempDir::new()?;
+    let yaml_path = temp_dir.path().join("app.yaml");
+    let original = "# Managed by ops\nhost: localhost  # bind address\nport: 8080\ndatabase:\n  # Primary\n  url: postgres://localhost\n";
+    fs::write(&yaml_path, original)?;
+
+    let config = SuperConfig::new()
+        .with_file(&yaml_path)
+        .with_defaults_string(r#"{"port": 9090, "features": ["auth"]}"#);
+    config.write_to_file(&yaml_path, ExportFormat::Yaml)?;
+
+    let written = fs::read_to_string(&yaml_path)?;
+    assert_eq!(
+        written,
+        "# Managed by ops\nhost: localhost  # bind address\nport: 9090\ndatabase:\n  # Primary\n  url: postgres://localhost\nfeatures:\n- auth\n"
+    );
+    // The written file loads back to the same configuration
+    let reloaded: TestConfig = SuperConfig::new().with_file(&yaml_path).extract()?;
+    let expected: TestConfig = config.extract()?;
+    assert_eq!(reloaded, expected);
+
+    // Files that don't exist yet are written from scratch
+    let json_path = temp_dir.path().join("app.json");
+    config.write_to_file(&json_path, ExportFormat::from_path(&json_path).unwrap())?;
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path)?)?;
+    assert_eq!(json["port"], 9090);
+
+    Ok(())
+}
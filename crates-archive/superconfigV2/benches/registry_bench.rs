@@ -55,6 +55,48 @@ fn bench_read_operations(c: &mut Criterion) {
     });
 }
 
+fn bench_pinned_reads(c: &mut Criterion) {
+    let registry = ConfigRegistry::new();
+    let handle = registry.create(BenchConfig::default()).unwrap();
+    let pinned = registry.pin(&handle).unwrap();
+
+    let mut group = c.benchmark_group("registry_pinned");
+    group.bench_function("pinned_load", |b| {
+        b.iter(|| black_box(pinned.load().port));
+    });
+    group.bench_function("pinned_get", |b| {
+        b.iter(|| black_box(pinned.get()));
+    });
+    group.bench_function("read", |b| {
+        b.iter(|| black_box(registry.read(black_box(&handle)).unwrap()));
+    });
+    group.finish();
+}
+
+fn bench_read_errors(c: &mut Criterion) {
+    let registry = ConfigRegistry::new();
+    let handle = registry.create(BenchConfig::default()).unwrap();
+    let missing = registry.create(BenchConfig::default()).unwrap();
+    registry.delete(&missing).unwrap();
+    let mistyped: ConfigHandle<u32> =
+        serde_json::from_str(&serde_json::to_string(&handle).unwrap()).unwrap();
+
+    let mut group = c.benchmark_group("registry_read_errors");
+    group.bench_function("try_read_hit", |b| {
+        b.iter(|| black_box(registry.try_read(black_box(&handle))));
+    });
+    group.bench_function("try_read_missing", |b| {
+        b.iter(|| black_box(registry.try_read(black_box(&missing))));
+    });
+    group.bench_function("try_read_wrong_type", |b| {
+        b.iter(|| black_box(registry.try_read(black_box(&mistyped))));
+    });
+    group.bench_function("read_missing", |b| {
+        b.iter(|| black_box(registry.read(black_box(&missing))));
+    });
+    group.finish();
+}
+
 fn bench_update_operations(c: &mut Criterion) {
     let registry = ConfigRegistry::new();
     let handle = registry.create(BenchConfig::default()).unwrap();
@@ -281,6 +323,8 @@ criterion_group!(
     bench_basic_operations,
     bench_create_operations,
     bench_read_operations,
+    bench_read_errors,
+    bench_pinned_reads,
     bench_update_operations
 );
 
@@ -311,6 +355,8 @@ fn main() {
     bench_basic_operations(&mut criterion);
     bench_create_operations(&mut criterion);
     bench_read_operations(&mut criterion);
+    bench_read_errors(&mut criterion);
+    bench_pinned_reads(&mut criterion);
     bench_update_operations(&mut criterion);
     bench_concurrent_operations(&mut criterion);
     bench_concurrent_reads(&mut criterion);
@@ -0,0 +1,371 @@
+//! Builder API: typed configuration without the registry primitives
+//!
+//! [`SuperConfig::builder`] collects layers of configuration, and
+//! [`build`](SuperConfigBuilder::build) merges them into a `T` stored in a registry. The
+//! returned [`SuperConfig<T>`] reads it with [`get`](SuperConfig::get), and gives access
+//! to the handle and registry for everything else.
+//!
+//! Layers merge like JSON Merge Patches, in this order of precedence:
+//!
+//! 1. Defaults and files, in the order they were added
+//! 2. Files of the active profile, added with
+//!    [`with_profile_file`](SuperConfigBuilder::with_profile_file)
+//! 3. Environment variables and command-line flags, in the order they were added, which
+//!    override files whatever the active profile
+//!
+//! The entry is created with [`create_profiled`](ConfigRegistry::create_profiled), so it
+//! switches profile with [`ConfigRegistry::select_profile`] like any profiled entry.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_json::json;
+//! use superconfig::SuperConfig;
+//! # let dir = tempfile::tempdir().unwrap();
+//! # let app_file = dir.path().join("app.json");
+//! # std::fs::write(&app_file, r#"{"name": "api", "port": 80}"#).unwrap();
+//! # let prod_file = dir.path().join("app.prod.json");
+//! # std::fs::write(&prod_file, r#"{"port": 443}"#).unwrap();
+//!
+//! #[derive(Deserialize)]
+//! struct AppConfig {
+//!     name: String,
+//!     port: u16,
+//!     workers: u32,
+//! }
+//!
+//! let config = SuperConfig::builder()
+//!     .with_defaults(json!({"workers": 4}))
+//!     .with_file(&app_file)
+//!     .with_profile_file("prod", &prod_file)
+//!     .with_cli(["--workers=16"])
+//!     .select_profile("prod")
+//!     .build::<AppConfig>()
+//!     .unwrap();
+//!
+//! let app = config.get().unwrap();
+//! assert_eq!((app.name.as_str(), app.port, app.workers), ("api", 443, 16));
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::core::{ConfigHandle, ConfigRegistry, ProfileSet, patch::apply_merge_patch};
+use crate::formats::parse_file;
+use crate::sources::{CliSource, EnvSource};
+
+/// Typed configuration built by [`SuperConfig::builder`]
+///
+/// Holds the handle of the entry and the registry storing it. Cloning is cheap.
+pub struct SuperConfig<T = ()> {
+    registry: Arc<ConfigRegistry>,
+    handle: ConfigHandle<T>,
+}
+
+impl<T> Clone for SuperConfig<T> {
+    fn clone(&self) -> Self {
+        Self {
+            registry: Arc::clone(&self.registry),
+            handle: ConfigHandle::new(self.handle.id()),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SuperConfig<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuperConfig")
+            .field("handle", &self.handle.id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SuperConfig {
+    /// Start building a configuration
+    #[must_use]
+    pub fn builder() -> SuperConfigBuilder {
+        SuperConfigBuilder::default()
+    }
+}
+
+impl<T: 'static> SuperConfig<T> {
+    /// The current configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the entry was deleted from the registry.
+    pub fn get(&self) -> Result<Arc<T>, String> {
+        self.registry.read(&self.handle)
+    }
+
+    /// Handle of the entry in [`registry`](Self::registry)
+    #[must_use]
+    pub const fn handle(&self) -> &ConfigHandle<T> {
+        &self.handle
+    }
+
+    /// Registry storing the entry
+    #[must_use]
+    pub const fn registry(&self) -> &Arc<ConfigRegistry> {
+        &self.registry
+    }
+}
+
+/// Where a layer's values apply
+#[derive(Debug, Clone)]
+enum Target {
+    Default,
+    Profile(String),
+    Everywhere,
+}
+
+/// A source of configuration values, read when the configuration is built
+#[derive(Debug, Clone)]
+enum Layer {
+    Tree(Value),
+    File(PathBuf),
+    Env(EnvSource),
+    Cli(CliSource),
+}
+
+impl Layer {
+    fn tree(&self, registry: &ConfigRegistry) -> Result<Value, String> {
+        match self {
+            Self::Tree(tree) => Ok(tree.clone()),
+            Self::File(path) => registry.expand(parse_file(path)?),
+            Self::Env(source) => Ok(source.tree()),
+            Self::Cli(source) => Ok(source.tree()),
+        }
+    }
+}
+
+/// Builder of a [`SuperConfig`], created by [`SuperConfig::builder`]
+///
+/// Sources are only read by [`build`](Self::build). See the
+/// [module documentation](self) for the order layers are merged in.
+#[derive(Clone, Default)]
+pub struct SuperConfigBuilder {
+    registry: Option<Arc<ConfigRegistry>>,
+    layers: Vec<(Target, Layer)>,
+    profile: Option<String>,
+}
+
+impl std::fmt::Debug for SuperConfigBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuperConfigBuilder")
+            .field("layers", &self.layers)
+            .field("profile", &self.profile)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SuperConfigBuilder {
+    /// Store the configuration in `registry` instead of a new registry
+    #[must_use]
+    pub fn with_registry(mut self, registry: Arc<ConfigRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Add default values, overridden by every other layer
+    #[must_use]
+    pub fn with_defaults(mut self, defaults: Value) -> Self {
+        self.layers.push((Target::Default, Layer::Tree(defaults)));
+        self
+    }
+
+    /// Add the file at `path`, in the format of its extension or content
+    #[must_use]
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.layers
+            .push((Target::Default, Layer::File(path.into())));
+        self
+    }
+
+    /// Add the file at `path`, applied only when `profile` is active
+    #[must_use]
+    pub fn with_profile_file(mut self, profile: &str, path: impl Into<PathBuf>) -> Self {
+        let target = Target::Profile(profile.to_lowercase());
+        self.layers.push((target, Layer::File(path.into())));
+        self
+    }
+
+    /// Add the environment variables starting with `prefix`
+    ///
+    /// See [`EnvSource::prefixed`]; [`with_env_source`](Self::with_env_source) takes a
+    /// configured source.
+    #[must_use]
+    pub fn with_env(self, prefix: &str) -> Self {
+        self.with_env_source(EnvSource::prefixed(prefix))
+    }
+
+    /// Add the variables of an environment source
+    #[must_use]
+    pub fn with_env_source(mut self, source: EnvSource) -> Self {
+        self.layers.push((Target::Everywhere, Layer::Env(source)));
+        self
+    }
+
+    /// Add the flags of `args`, which shouldn't start with the program name
+    ///
+    /// See [`CliSource`]; [`with_cli_source`](Self::with_cli_source) takes a configured
+    /// source, such as one reading the process's arguments.
+    #[must_use]
+    pub fn with_cli<I>(self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.with_cli_source(CliSource::from_args(args))
+    }
+
+    /// Add the flags of a command-line source
+    #[must_use]
+    pub fn with_cli_source(mut self, source: CliSource) -> Self {
+        self.layers.push((Target::Everywhere, Layer::Cli(source)));
+        self
+    }
+
+    /// Select `profile` in the registry when building
+    ///
+    /// Selecting a profile switches every profiled entry of the registry, see
+    /// [`ConfigRegistry::select_profile`]. Without it, the registry's active profile is
+    /// used.
+    #[must_use]
+    pub fn select_profile(mut self, profile: &str) -> Self {
+        self.profile = Some(profile.to_string());
+        self
+    }
+
+    /// Read every layer, merge them into `T` and store it
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if a file can't be read or parsed, the profile can't be
+    /// selected, the merged configuration doesn't deserialize into `T`, or the entry
+    /// can't be created.
+    pub fn build<T>(self) -> Result<SuperConfig<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let registry = self.registry.unwrap_or_else(ConfigRegistry::new);
+
+        let mut default = Value::Object(Map::new());
+        let mut profiles = BTreeMap::<String, Value>::new();
+        let mut everywhere = Value::Object(Map::new());
+        for (target, layer) in &self.layers {
+            let tree = layer.tree(&registry)?;
+            let merged = match target {
+                Target::Default => &mut default,
+                Target::Profile(profile) => profiles
+                    .entry(profile.clone())
+                    .or_insert_with(|| Value::Object(Map::new())),
+                Target::Everywhere => &mut everywhere,
+            };
+            apply_merge_patch(merged, &tree);
+        }
+
+        apply_merge_patch(&mut default, &everywhere);
+        let mut set = ProfileSet::new(default);
+        for (profile, mut overrides) in profiles {
+            apply_merge_patch(&mut overrides, &everywhere);
+            set = set.with_profile(&profile, overrides);
+        }
+
+        if let Some(profile) = &self.profile {
+            registry.select_profile(profile)?;
+        }
+        let handle = registry.create_profiled(set)?;
+        Ok(SuperConfig { registry, handle })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::fs;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[test]
+    fn test_env_and_cli_override_profile_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("app.json");
+        let prod = dir.path().join("app.prod.json");
+        fs::write(&base, r#"{"host": "localhost", "port": 80}"#).unwrap();
+        fs::write(&prod, r#"{"host": "db.internal", "port": 443}"#).unwrap();
+
+        let config = SuperConfig::builder()
+            .with_file(&base)
+            .with_profile_file("PROD", &prod)
+            .with_env_source(EnvSource::prefixed("APP_").with_vars([("APP_PORT", "8443")]))
+            .build::<Server>()
+            .unwrap();
+        assert_eq!(
+            *config.get().unwrap(),
+            Server {
+                host: "localhost".into(),
+                port: 8443
+            }
+        );
+
+        config.registry().select_profile("prod").unwrap();
+        assert_eq!(
+            *config.get().unwrap(),
+            Server {
+                host: "db.internal".into(),
+                port: 8443
+            }
+        );
+    }
+
+    #[test]
+    fn test_later_layers_win_within_a_level() {
+        let config = SuperConfig::builder()
+            .with_cli(["--port=1"])
+            .with_defaults(json!({"host": "a", "port": 80}))
+            .with_defaults(json!({"host": "b"}))
+            .with_cli(["--port=2"])
+            .build::<Server>()
+            .unwrap();
+        assert_eq!(
+            *config.get().unwrap(),
+            Server {
+                host: "b".into(),
+                port: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_shared_registry_and_errors() {
+        let registry = ConfigRegistry::new();
+        let config = SuperConfig::builder()
+            .with_registry(Arc::clone(&registry))
+            .with_defaults(json!({"host": "a", "port": 80}))
+            .select_profile("staging")
+            .build::<Server>()
+            .unwrap();
+        assert_eq!(registry.active_profile(), "staging");
+        assert_eq!(registry.read(config.handle()).unwrap().port, 80);
+
+        let error = SuperConfig::builder()
+            .with_file("/nonexistent/app.toml")
+            .build::<Server>()
+            .unwrap_err();
+        assert!(error.starts_with("superconfig.formats: Failed to read"));
+
+        let error = SuperConfig::builder()
+            .with_defaults(json!({"host": "a"}))
+            .build::<Server>()
+            .unwrap_err();
+        assert!(error.contains("missing field `port`"));
+    }
+}
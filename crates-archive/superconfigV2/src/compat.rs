@@ -0,0 +1,252 @@
+//! Migration adapters for configuration loaded by config-rs or Figment
+//!
+//! Teams moving to the registry can keep their existing loading code and convert what it
+//! produces into registry structures, with the precedence their sources already had:
+//!
+//! - [`from_config_rs`] converts a `config::Config` (config-rs, `config-rs` feature) to a
+//!   [`Value`] tree. config-rs merges its sources when the `Config` is built, so the tree
+//!   holds the value of the source added last for every key.
+//! - [`profiles_from_figment`] converts a [`Figment`](figment::Figment) (`figment`
+//!   feature) to a [`ProfileSet`], keeping its profiles: the default profile's values
+//!   become the defaults, each named profile overrides them, and values of the global
+//!   profile override every profile, as in Figment.
+//!
+//! [`ConfigRegistry::create_from_config_rs`] and
+//! [`ConfigRegistry::create_profiled_from_figment`] store the result directly.
+//! [`ConfigRegistry::create_from_figment`] instead extracts a Figment's selected
+//! profile only.
+//!
+//! ```
+//! # #[cfg(feature = "config-rs")] {
+//! use serde::Deserialize;
+//! use superconfig::ConfigRegistry;
+//!
+//! #[derive(Deserialize)]
+//! struct Server {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! let config = config::Config::builder()
+//!     .set_default("host", "localhost").unwrap()
+//!     .set_default("port", 80).unwrap()
+//!     .set_override("port", 8080).unwrap()
+//!     .build()
+//!     .unwrap();
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry.create_from_config_rs::<Server>(&config).unwrap();
+//! let server = registry.read(&handle).unwrap();
+//! assert_eq!((server.host.as_str(), server.port), ("localhost", 8080));
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::core::{ConfigHandle, ConfigRegistry};
+#[cfg(feature = "figment")]
+use crate::core::{ProfileSet, patch::apply_merge_patch};
+use logffi::error;
+
+/// Convert the merged values of a config-rs `Config` to a tree
+///
+/// Values keep the type config-rs holds them as: environment variables, for example,
+/// stay strings unless the `Environment` source parsed them.
+///
+/// # Errors
+///
+/// Returns error message if a value can't be represented as JSON.
+#[cfg(feature = "config-rs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "config-rs")))]
+pub fn from_config_rs(config: &config::Config) -> Result<Value, String> {
+    config.clone().try_deserialize::<Value>().map_err(|e| {
+        error!(target: "superconfig.compat", "Failed to convert config-rs configuration: {e}");
+        format!("superconfig.compat: Failed to convert config-rs configuration: {e}")
+    })
+}
+
+/// Convert the profiles of a Figment to a [`ProfileSet`]
+///
+/// The default profile holds the defaults, and every named profile its overrides. The
+/// global profile's values are merged over both, so they win whichever profile is
+/// active, like in Figment.
+///
+/// # Errors
+///
+/// Returns error message if a provider of the Figment fails to load.
+///
+/// # Examples
+///
+/// ```
+/// use figment::{Figment, providers::Serialized};
+/// use serde_json::json;
+/// use superconfig::compat::profiles_from_figment;
+///
+/// let figment = Figment::new()
+///     .merge(Serialized::defaults(json!({"port": 80, "debug": true})))
+///     .merge(Serialized::default("debug", false).profile("prod"))
+///     .merge(Serialized::global("port", 8080));
+///
+/// let profiles = profiles_from_figment(&figment).unwrap();
+/// assert_eq!(profiles.resolve("default"), json!({"port": 8080, "debug": true}));
+/// assert_eq!(profiles.resolve("prod"), json!({"port": 8080, "debug": false}));
+/// ```
+#[cfg(feature = "figment")]
+#[cfg_attr(docsrs, doc(cfg(feature = "figment")))]
+pub fn profiles_from_figment(figment: &figment::Figment) -> Result<ProfileSet, String> {
+    use figment::Provider;
+
+    let data = figment.data().map_err(|e| {
+        error!(target: "superconfig.compat", "Failed to load Figment providers: {e}");
+        format!("superconfig.compat: Failed to load Figment providers: {e}")
+    })?;
+    let tree = |profile: &figment::Profile| {
+        data.get(profile)
+            .and_then(|dict| serde_json::to_value(dict).ok())
+            .unwrap_or_else(|| Value::Object(serde_json::Map::new()))
+    };
+    let global = tree(&figment::Profile::Global);
+
+    let mut default = tree(&figment::Profile::Default);
+    apply_merge_patch(&mut default, &global);
+    let mut profiles = ProfileSet::new(default);
+    for profile in data.keys() {
+        if profile.is_custom() {
+            let mut overrides = tree(profile);
+            apply_merge_patch(&mut overrides, &global);
+            profiles = profiles.with_profile(profile.as_str().as_str(), overrides);
+        }
+    }
+    Ok(profiles)
+}
+
+impl ConfigRegistry {
+    /// Store the configuration of a config-rs `Config`
+    ///
+    /// See [`from_config_rs`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the configuration doesn't deserialize into `T`, or the
+    /// entry can't be created.
+    #[cfg(feature = "config-rs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "config-rs")))]
+    pub fn create_from_config_rs<T>(
+        &self,
+        config: &config::Config,
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let data = serde_json::from_value(from_config_rs(config)?).map_err(|e| {
+            error!(target: "superconfig.compat", "Failed to deserialize config-rs configuration: {e}");
+            format!("superconfig.compat: Failed to deserialize config-rs configuration: {e}")
+        })?;
+        self.create(data)
+    }
+
+    /// Store the configuration of a Figment with all its profiles
+    ///
+    /// The entry switches profile with the registry, like entries created by
+    /// [`create_profiled`](Self::create_profiled). See [`profiles_from_figment`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if a provider fails to load, the resolved data doesn't
+    /// deserialize into `T`, or the entry can't be created.
+    #[cfg(feature = "figment")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "figment")))]
+    pub fn create_profiled_from_figment<T>(
+        &self,
+        figment: &figment::Figment,
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.create_profiled(profiles_from_figment(figment)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[cfg(feature = "config-rs")]
+    #[test]
+    fn test_config_rs_keeps_source_precedence() {
+        let config = config::Config::builder()
+            .set_default("server.host", "localhost")
+            .unwrap()
+            .set_default("server.port", 80)
+            .unwrap()
+            .add_source(config::Config::try_from(&json!({"server": {"port": 8080}})).unwrap())
+            .set_override("server.host", "0.0.0.0")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            from_config_rs(&config).unwrap(),
+            json!({"server": {"host": "0.0.0.0", "port": 8080}})
+        );
+    }
+
+    #[cfg(feature = "config-rs")]
+    #[test]
+    fn test_create_from_config_rs_reports_type_errors() {
+        let config = config::Config::builder()
+            .set_default("host", "localhost")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let registry = ConfigRegistry::new();
+        let error = registry
+            .create_from_config_rs::<Server>(&config)
+            .unwrap_err();
+        assert!(error.starts_with("superconfig.compat: Failed to deserialize"));
+    }
+
+    #[cfg(feature = "figment")]
+    #[test]
+    fn test_figment_profiles_switch_with_registry() {
+        use figment::{Figment, providers::Serialized};
+
+        let figment = Figment::new()
+            .merge(Serialized::defaults(
+                json!({"host": "localhost", "port": 80}),
+            ))
+            .merge(Serialized::default("host", "db.internal").profile("prod"))
+            .merge(Serialized::global("port", 5432));
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_profiled_from_figment::<Server>(&figment)
+            .unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "localhost".into(),
+                port: 5432
+            }
+        );
+
+        registry.select_profile("prod").unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "db.internal".into(),
+                port: 5432
+            }
+        );
+    }
+}
@@ -16,13 +16,18 @@ pub mod startup {
     /// Affects parser pipeline initialization
     pub const SIMD: u32 = 1 << 0;
 
-    /// Pre-allocate thread pool for parallel operations
-    /// Thread pool cannot be created/destroyed at runtime
+    /// Pre-allocate a worker pool for file parsing, background loads and reload callbacks
+    /// Thread pool cannot be created/destroyed at runtime, see `core::pool`
     pub const THREAD_POOL: u32 = 1 << 1;
 
     /// Enable detailed statistics collection with comprehensive metrics
     /// Statistics structure affects memory layout
     pub const DETAILED_STATS: u32 = 1 << 2;
+
+    /// Reuse the handle IDs of deleted, evicted, and cleared entries
+    /// Keeps IDs bounded by the peak number of entries; stale handles may then
+    /// resolve to a newer entry that reused their ID
+    pub const RECYCLE_IDS: u32 = 1 << 3;
 }
 
 /// Runtime flags - can be enabled/disabled freely without affecting core structures
@@ -39,7 +44,8 @@ pub mod runtime {
     /// Can be toggled based on environment (development vs production)
     pub const STRICT_MODE: u64 = 1 << 2;
 
-    /// Enable environment variable expansion (${VAR} syntax)
+    /// Enable placeholder expansion (`${VAR}`, `${other.key}`, `${file:/path}`) in
+    /// configuration loaded from files and strings, see `core::interpolate`
     /// Can be disabled for security in production environments
     pub const ENV_EXPANSION: u64 = 1 << 3;
 
@@ -84,8 +90,18 @@ const ALL_RUNTIME_FLAGS: u64 = runtime::ARRAY_MERGE
     | runtime::ENV_EXPANSION
     | runtime::FORMAT_FALLBACK;
 
+/// Name of each runtime flag, as used by `ConfigRegistry::flags_snapshot`
+pub const RUNTIME_FLAG_NAMES: [(&str, u64); 5] = [
+    ("array_merge", runtime::ARRAY_MERGE),
+    ("parallel", runtime::PARALLEL),
+    ("strict_mode", runtime::STRICT_MODE),
+    ("env_expansion", runtime::ENV_EXPANSION),
+    ("format_fallback", runtime::FORMAT_FALLBACK),
+];
+
 /// All valid startup flags combined  
-const ALL_STARTUP_FLAGS: u32 = startup::SIMD | startup::THREAD_POOL | startup::DETAILED_STATS;
+const ALL_STARTUP_FLAGS: u32 =
+    startup::SIMD | startup::THREAD_POOL | startup::DETAILED_STATS | startup::RECYCLE_IDS;
 
 /// Check if a runtime flag value contains only valid flags
 ///
@@ -128,6 +144,7 @@ mod tests {
         assert_eq!(startup::SIMD, 1);
         assert_eq!(startup::THREAD_POOL, 2);
         assert_eq!(startup::DETAILED_STATS, 4);
+        assert_eq!(startup::RECYCLE_IDS, 8);
 
         // Ensure flags are unique (can be combined with |)
         let combined = startup::SIMD | startup::THREAD_POOL;
@@ -196,12 +213,21 @@ mod tests {
         assert!(is_valid_runtime_flag(0));
     }
 
+    #[test]
+    fn test_runtime_flag_names_cover_every_flag() {
+        let named = RUNTIME_FLAG_NAMES
+            .iter()
+            .fold(0, |flags, &(_, flag)| flags | flag);
+        assert_eq!(named, ALL_RUNTIME_FLAGS);
+    }
+
     #[test]
     fn test_startup_flag_validation() {
         // Valid individual flags
         assert!(is_valid_startup_flag(startup::SIMD));
         assert!(is_valid_startup_flag(startup::THREAD_POOL));
         assert!(is_valid_startup_flag(startup::DETAILED_STATS));
+        assert!(is_valid_startup_flag(startup::RECYCLE_IDS));
 
         // Valid combinations
         assert!(is_valid_startup_flag(startup::SIMD | startup::THREAD_POOL));
@@ -0,0 +1,350 @@
+//! Storage backends persisting the registry across restarts
+//!
+//! A registry with a backend attached by
+//! [`ConfigRegistry::attach_storage`](crate::ConfigRegistry::attach_storage) restores the
+//! entries the backend holds, then writes a [`RegistrySnapshot`] through to it after
+//! every create, update, delete, eviction, clear, and restore. Configuration created at
+//! runtime then survives process restarts. Only entries of types registered with
+//! [`register_snapshot_type`](crate::ConfigRegistry::register_snapshot_type) are
+//! persisted.
+//!
+//! [`MemoryBackend`] keeps the latest snapshot in memory, and [`FileBackend`] writes it
+//! to a JSON or TOML file. Other stores (an embedded database such as sled, a key-value
+//! service) implement [`StorageBackend`].
+//!
+//! ```
+//! use superconfig::{ConfigRegistry, FileBackend};
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let path = dir.path().join("registry.json");
+//!
+//! let registry = ConfigRegistry::new();
+//! registry.register_snapshot_type::<u32>();
+//! registry.attach_storage(FileBackend::new(&path)).unwrap();
+//! registry.create_named("workers", 8_u32).unwrap();
+//!
+//! // After a restart
+//! let restarted = ConfigRegistry::new();
+//! restarted.register_snapshot_type::<u32>();
+//! restarted.attach_storage(FileBackend::new(&path)).unwrap();
+//! let workers = restarted.lookup::<u32>("workers").unwrap();
+//! assert_eq!(*restarted.read(&workers).unwrap(), 8);
+//! ```
+
+use super::snapshot::RegistrySnapshot;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Where a registry persists its snapshots
+///
+/// Calls are serialized by the registry, and each `store` receives a snapshot taken
+/// after the previous one.
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{RegistrySnapshot, StorageBackend};
+/// use std::collections::HashMap;
+/// use std::sync::Mutex;
+///
+/// /// Stores snapshots in a key-value store under one key
+/// struct KeyValueBackend {
+///     store: Mutex<HashMap<String, String>>,
+/// }
+///
+/// impl StorageBackend for KeyValueBackend {
+///     fn load(&self) -> Result<Option<RegistrySnapshot>, String> {
+///         let store = self.store.lock().unwrap();
+///         store
+///             .get("registry")
+///             .map(|json| serde_json::from_str(json).map_err(|e| e.to_string()))
+///             .transpose()
+///     }
+///
+///     fn store(&self, snapshot: &RegistrySnapshot) -> Result<(), String> {
+///         let json = serde_json::to_string(snapshot).map_err(|e| e.to_string())?;
+///         self.store.lock().unwrap().insert("registry".to_string(), json);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait StorageBackend: Send + Sync {
+    /// Load the persisted snapshot, `None` if nothing was stored yet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored data can't be read or parsed.
+    fn load(&self) -> Result<Option<RegistrySnapshot>, String>;
+
+    /// Persist `snapshot`, replacing the one stored before
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot can't be written.
+    fn store(&self, snapshot: &RegistrySnapshot) -> Result<(), String>;
+}
+
+impl<B: StorageBackend + ?Sized> StorageBackend for Arc<B> {
+    fn load(&self) -> Result<Option<RegistrySnapshot>, String> {
+        (**self).load()
+    }
+
+    fn store(&self, snapshot: &RegistrySnapshot) -> Result<(), String> {
+        (**self).store(snapshot)
+    }
+}
+
+/// Backend keeping the latest snapshot in memory
+///
+/// Nothing survives the process, but the stored snapshot can be inspected, and shared
+/// through an `Arc` with a registry created later in the same process.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    snapshot: parking_lot::Mutex<Option<RegistrySnapshot>>,
+}
+
+impl MemoryBackend {
+    /// Create an empty memory backend
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The latest stored snapshot
+    #[must_use]
+    pub fn snapshot(&self) -> Option<RegistrySnapshot> {
+        self.snapshot.lock().clone()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn load(&self) -> Result<Option<RegistrySnapshot>, String> {
+        Ok(self.snapshot())
+    }
+
+    fn store(&self, snapshot: &RegistrySnapshot) -> Result<(), String> {
+        *self.snapshot.lock() = Some(snapshot.clone());
+        Ok(())
+    }
+}
+
+/// File format of a [`FileBackend`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Pretty-printed JSON
+    #[default]
+    Json,
+    /// TOML, which requires the `extended_formats` feature and can't represent `null`
+    Toml,
+}
+
+impl FileFormat {
+    /// The format of `path` from its extension: TOML for `.toml`, JSON otherwise
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+
+    fn encode(self, snapshot: &RegistrySnapshot) -> Result<String, String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(snapshot).map_err(|e| e.to_string()),
+            #[cfg(feature = "extended_formats")]
+            Self::Toml => toml::to_string_pretty(snapshot).map_err(|e| e.to_string()),
+            #[cfg(not(feature = "extended_formats"))]
+            Self::Toml => Err(TOML_UNAVAILABLE.to_string()),
+        }
+    }
+
+    fn decode(self, text: &str) -> Result<RegistrySnapshot, String> {
+        match self {
+            Self::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            #[cfg(feature = "extended_formats")]
+            Self::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+            #[cfg(not(feature = "extended_formats"))]
+            Self::Toml => Err(TOML_UNAVAILABLE.to_string()),
+        }
+    }
+}
+
+#[cfg(not(feature = "extended_formats"))]
+const TOML_UNAVAILABLE: &str = "TOML requires the `extended_formats` feature";
+
+/// Backend writing each snapshot to a file
+///
+/// Snapshots are written to a temporary file next to the target, then renamed over
+/// it, so a crash mid-write leaves the previous snapshot intact. A missing file loads
+/// as no snapshot; its directory must exist.
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{FileBackend, FileFormat};
+///
+/// let backend = FileBackend::new("/var/lib/app/registry.toml");
+/// assert_eq!(backend.format(), FileFormat::Toml);
+///
+/// let backend = FileBackend::new("/var/lib/app/registry.dat").with_format(FileFormat::Json);
+/// assert_eq!(backend.format(), FileFormat::Json);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileBackend {
+    path: PathBuf,
+    format: FileFormat,
+}
+
+impl FileBackend {
+    /// Create a backend for `path`, in the format of its extension
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let format = FileFormat::from_path(&path);
+        Self { path, format }
+    }
+
+    /// Set the file format instead of using the extension's
+    #[must_use]
+    pub const fn with_format(mut self, format: FileFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Path of the file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Format of the file
+    #[must_use]
+    pub const fn format(&self) -> FileFormat {
+        self.format
+    }
+}
+
+impl StorageBackend for FileBackend {
+    fn load(&self) -> Result<Option<RegistrySnapshot>, String> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(format!(
+                    "superconfig.backend: Failed to read {}: {e}",
+                    self.path.display()
+                ));
+            }
+        };
+        self.format.decode(&text).map(Some).map_err(|e| {
+            format!(
+                "superconfig.backend: Failed to parse {}: {e}",
+                self.path.display()
+            )
+        })
+    }
+
+    fn store(&self, snapshot: &RegistrySnapshot) -> Result<(), String> {
+        let text = self.format.encode(snapshot).map_err(|e| {
+            format!(
+                "superconfig.backend: Failed to encode {}: {e}",
+                self.path.display()
+            )
+        })?;
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, text)
+            .and_then(|()| std::fs::rename(&temporary, &self.path))
+            .map_err(|e| {
+                format!(
+                    "superconfig.backend: Failed to write {}: {e}",
+                    self.path.display()
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::snapshot::SnapshotEntry;
+
+    fn sample() -> RegistrySnapshot {
+        RegistrySnapshot {
+            next_id: 3,
+            entries: vec![SnapshotEntry {
+                id: 2,
+                type_name: "u32".to_string(),
+                name: Some("workers".to_string()),
+                data: serde_json::json!(8),
+            }],
+            skipped: vec![1],
+        }
+    }
+
+    #[test]
+    fn test_memory_backend_keeps_latest_snapshot() {
+        let backend = MemoryBackend::new();
+        assert_eq!(backend.load().unwrap(), None);
+
+        backend.store(&RegistrySnapshot::default()).unwrap();
+        backend.store(&sample()).unwrap();
+        assert_eq!(backend.load().unwrap(), Some(sample()));
+    }
+
+    #[test]
+    fn test_file_backend_round_trips_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FileBackend::new(dir.path().join("registry.json"));
+        assert_eq!(backend.load().unwrap(), None);
+
+        backend.store(&sample()).unwrap();
+        assert_eq!(backend.load().unwrap(), Some(sample()));
+        // The temporary file is renamed over the target
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_file_backend_reports_bad_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let error = FileBackend::new(&path).load().unwrap_err();
+        assert!(error.starts_with("superconfig.backend: Failed to parse"));
+
+        let missing_dir = FileBackend::new(dir.path().join("missing/registry.json"));
+        assert!(missing_dir.store(&sample()).is_err());
+    }
+
+    #[test]
+    fn test_file_format_from_extension() {
+        assert_eq!(FileFormat::from_path(Path::new("a.TOML")), FileFormat::Toml);
+        assert_eq!(FileFormat::from_path(Path::new("a.json")), FileFormat::Json);
+        assert_eq!(FileFormat::from_path(Path::new("a")), FileFormat::Json);
+    }
+
+    #[cfg(feature = "extended_formats")]
+    #[test]
+    fn test_file_backend_round_trips_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FileBackend::new(dir.path().join("registry.toml"));
+        backend.store(&sample()).unwrap();
+        assert_eq!(backend.load().unwrap(), Some(sample()));
+    }
+
+    #[cfg(not(feature = "extended_formats"))]
+    #[test]
+    fn test_toml_requires_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FileBackend::new(dir.path().join("registry.toml"));
+        assert!(
+            backend
+                .store(&sample())
+                .unwrap_err()
+                .contains("extended_formats")
+        );
+    }
+}
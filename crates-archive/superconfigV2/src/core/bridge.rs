@@ -0,0 +1,198 @@
+//! Bridge between the registry and Figment, for codebases migrating from V1
+//!
+//! Both directions reuse what has already been loaded instead of loading it twice:
+//!
+//! - [`ConfigRegistry::create_from_figment`] extracts a configuration from a
+//!   [`Figment`], such as the V1 `SuperConfig` pipeline (which dereferences to one), and
+//!   stores it in the registry.
+//! - [`RegistryProvider`] is a [`figment::Provider`] reading an entry of the registry, so
+//!   a Figment pipeline can merge configuration the registry already holds.
+//!
+//! ```
+//! use figment::{Figment, providers::Serialized};
+//! use serde::{Deserialize, Serialize};
+//! use superconfig::ConfigRegistry;
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! struct Server {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! let registry = ConfigRegistry::new();
+//!
+//! // Figment to registry
+//! let figment = Figment::from(Serialized::defaults(Server { host: "localhost".into(), port: 80 }))
+//!     .merge(("port", 8080));
+//! let handle = registry.create_from_figment::<Server>(&figment).unwrap();
+//! assert_eq!(registry.read(&handle).unwrap().port, 8080);
+//!
+//! // Registry to Figment
+//! let server: Server = Figment::from(registry.provider(&handle))
+//!     .merge(("host", "0.0.0.0"))
+//!     .extract()
+//!     .unwrap();
+//! assert_eq!(server, Server { host: "0.0.0.0".into(), port: 8080 });
+//! ```
+
+use figment::{
+    Error, Figment, Metadata, Profile, Provider,
+    value::{Dict, Map},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{handle::ConfigHandle, registry::ConfigRegistry};
+use logffi::error;
+
+/// A [`figment::Provider`] backed by an entry of a [`ConfigRegistry`]
+///
+/// Provides the entry's cached JSON tree, as of the time the Figment merges it, for the
+/// default profile unless [`profile`](Self::profile) selects another. The entry must
+/// serialize to a map. Created by [`ConfigRegistry::provider`].
+#[derive(Clone)]
+pub struct RegistryProvider<'a, T> {
+    registry: &'a ConfigRegistry,
+    handle: ConfigHandle<T>,
+    profile: Profile,
+}
+
+impl<T> RegistryProvider<'_, T> {
+    /// Provide the entry for `profile` instead of the default profile
+    #[must_use]
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+}
+
+impl<T> std::fmt::Debug for RegistryProvider<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryProvider")
+            .field("handle", &self.handle.id())
+            .field("profile", &self.profile)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Serialize + Send + Sync + 'static> Provider for RegistryProvider<'_, T> {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("registry handle {}", self.handle.id()))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let value = self.registry.deserializer(&self.handle)?;
+        let dict = Dict::deserialize(&value).map_err(|e| {
+            error!(target: "superconfig.bridge", "Handle {} is not a map: {e}", self.handle.id());
+            Error::from(format!(
+                "superconfig.bridge: Handle {} is not a map: {e}",
+                self.handle.id()
+            ))
+        })?;
+        Ok(Map::from([(self.profile.clone(), dict)]))
+    }
+}
+
+impl ConfigRegistry {
+    /// Extract a configuration from `figment` and store it in the registry
+    ///
+    /// Works with anything dereferencing to a [`Figment`], like the V1 `SuperConfig`,
+    /// so the configuration it already loaded moves into the registry as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the configuration can't be extracted as `T` or the entry
+    /// can't be created.
+    pub fn create_from_figment<T>(&self, figment: &Figment) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let data = figment.extract::<T>().map_err(|e| {
+            error!(target: "superconfig.bridge", "Failed to extract configuration: {e}");
+            format!("superconfig.bridge: Failed to extract configuration: {e}")
+        })?;
+        self.create(data)
+    }
+
+    /// A [`figment::Provider`] reading the entry of `handle`
+    ///
+    /// See [`RegistryProvider`].
+    pub const fn provider<T>(&self, handle: &ConfigHandle<T>) -> RegistryProvider<'_, T> {
+        RegistryProvider {
+            registry: self,
+            handle: ConfigHandle::new(handle.id()),
+            profile: Profile::Default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::Serialized;
+    use serde_json::json;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[test]
+    fn test_create_from_figment_reports_extract_errors() {
+        let registry = ConfigRegistry::new();
+        let figment = Figment::from(("port", "not a number"));
+
+        let error = registry
+            .create_from_figment::<Server>(&figment)
+            .unwrap_err();
+        assert!(error.starts_with("superconfig.bridge: Failed to extract configuration"));
+    }
+
+    #[test]
+    fn test_provider_uses_latest_generation_and_profile() {
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create(json!({"host": "localhost", "port": 80}))
+            .unwrap();
+        registry
+            .update(&handle, json!({"host": "localhost", "port": 9090}))
+            .unwrap();
+
+        let figment =
+            Figment::from(registry.provider(&handle).profile("production")).select("production");
+        let server: Server = figment.extract().unwrap();
+        assert_eq!(server.port, 9090);
+
+        let metadata = figment.find_metadata("port").unwrap();
+        assert_eq!(metadata.name, format!("registry handle {}", handle.id()));
+    }
+
+    #[test]
+    fn test_provider_requires_a_map() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(vec![1, 2, 3]).unwrap();
+
+        let error = Figment::from(registry.provider(&handle))
+            .extract::<Server>()
+            .unwrap_err();
+        assert!(error.to_string().contains("is not a map"));
+    }
+
+    #[test]
+    fn test_round_trip_through_figment() {
+        let registry = ConfigRegistry::new();
+        let figment = Figment::from(Serialized::defaults(Server {
+            host: "localhost".into(),
+            port: 80,
+        }));
+        let handle = registry.create_from_figment::<Server>(&figment).unwrap();
+
+        let copy = registry
+            .create_from_figment::<Server>(&Figment::from(registry.provider(&handle)))
+            .unwrap();
+        assert_eq!(
+            *registry.read(&copy).unwrap(),
+            *registry.read(&handle).unwrap()
+        );
+    }
+}
@@ -0,0 +1,547 @@
+//! Timeouts and circuit breaking for remote configuration sources
+//!
+//! A slow or flapping remote source (HTTP endpoint, Consul, S3) must not hold up
+//! application startup or hammer a struggling service on every reload.
+//! [`ResilientSource`] wraps any [`AsyncConfigSource`] with:
+//!
+//! - **Timeout** - a load that takes longer than [`SourcePolicy::timeout`] fails
+//! - **Failure counting** - consecutive and total failures are tracked per source
+//! - **Circuit breaker** - after [`SourcePolicy::failure_threshold`] consecutive failures
+//!   the circuit opens, and the source is skipped for [`SourcePolicy::cooldown`]. The
+//!   first load after the cool-down is a trial: success closes the circuit, failure
+//!   opens it again.
+//! - **Stale data** - while a load fails or the circuit is open, the last successfully
+//!   loaded configuration is served instead, with a warning
+//!
+//! The breaker state of every source loaded through a [`ConfigRegistry`] shows up in
+//! [`RegistryStats::sources`](super::RegistryStats::sources), and warnings are both
+//! logged (target `superconfig.source`) and kept by the source ([`ResilientSource::warnings`]).
+//!
+//! Timeouts don't depend on an async runtime: a timer thread is started for each load
+//! that doesn't complete on its first poll.
+
+use std::{
+    future::{Future, poll_fn},
+    pin::pin,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+use super::{source::AsyncConfigSource, time::Instant};
+use logffi::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// State of a source's circuit breaker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// The source is loaded normally
+    #[default]
+    Closed,
+    /// The source is skipped until the cool-down ends
+    Open,
+    /// The cool-down ended; the next load decides whether the circuit closes again
+    HalfOpen,
+}
+
+/// Health of one source, as reported in registry statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceHealth {
+    /// Name of the source
+    pub name: String,
+    /// Current circuit breaker state
+    pub state: CircuitState,
+    /// Failures since the last successful load
+    pub consecutive_failures: u32,
+    /// Successful loads
+    pub total_successes: u64,
+    /// Failed loads, including timeouts
+    pub total_failures: u64,
+    /// Loads that exceeded the timeout
+    pub total_timeouts: u64,
+    /// Loads answered with the cached configuration instead of the source's data
+    pub stale_serves: u64,
+    /// Error of the most recent failed load
+    pub last_error: Option<String>,
+}
+
+/// Timeout and circuit breaker settings of a [`ResilientSource`]
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use superconfig::SourcePolicy;
+///
+/// let policy = SourcePolicy::default()
+///     .with_timeout(Duration::from_secs(2))
+///     .with_failure_threshold(5)
+///     .with_cooldown(Duration::from_secs(60));
+/// assert_eq!(policy.failure_threshold, 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePolicy {
+    /// Longest a single load may take, `None` to wait indefinitely
+    pub timeout: Option<Duration>,
+    /// Consecutive failures that open the circuit (at least 1)
+    pub failure_threshold: u32,
+    /// How long an open circuit skips the source
+    pub cooldown: Duration,
+}
+
+impl Default for SourcePolicy {
+    /// 10 second timeout, circuit opening after 3 consecutive failures for 30 seconds
+    fn default() -> Self {
+        Self {
+            timeout: Some(Duration::from_secs(10)),
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl SourcePolicy {
+    /// Set the timeout of a single load
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Wait for loads indefinitely
+    #[must_use]
+    pub const fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Set the number of consecutive failures that open the circuit
+    #[must_use]
+    pub const fn with_failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = if failures == 0 { 1 } else { failures };
+        self
+    }
+
+    /// Set how long an open circuit skips the source
+    #[must_use]
+    pub const fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// Mutable breaker state, guarded by the source's mutex
+struct Breaker<T> {
+    health: SourceHealth,
+    cached: Option<T>,
+    opened_at: Option<Instant>,
+    warnings: Vec<String>,
+}
+
+/// Outcome of a load attempt
+enum Attempt<T> {
+    Loaded(T),
+    Failed(String),
+    TimedOut(Duration),
+}
+
+/// An [`AsyncConfigSource`] with a timeout, failure counting, and a circuit breaker
+///
+/// The wrapped source's output must be `Clone` so the last good configuration can be
+/// served while the source is failing.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use superconfig::{AsyncConfigSource, ConfigRegistry, ResilientSource, SourcePolicy};
+///
+/// struct Endpoint;
+///
+/// impl AsyncConfigSource for Endpoint {
+///     type Output = String;
+///
+///     fn name(&self) -> &str {
+///         "https://config.example.com"
+///     }
+///
+///     async fn load(&self) -> Result<String, String> {
+///         Ok("from endpoint".to_string())
+///     }
+/// }
+///
+/// # tokio_test::block_on(async {
+/// let source = ResilientSource::new(
+///     Endpoint,
+///     SourcePolicy::default().with_timeout(Duration::from_secs(2)),
+/// );
+/// let registry = ConfigRegistry::new();
+/// let handle = registry.create_from_source(&source).await.unwrap();
+/// assert_eq!(*registry.read(&handle).unwrap(), "from endpoint");
+/// assert_eq!(registry.stats().sources[0].total_successes, 1);
+/// # });
+/// ```
+pub struct ResilientSource<S: AsyncConfigSource> {
+    source: S,
+    policy: SourcePolicy,
+    breaker: Mutex<Breaker<S::Output>>,
+}
+
+impl<S: AsyncConfigSource> ResilientSource<S> {
+    /// Wrap `source` with the given policy
+    pub fn new(source: S, policy: SourcePolicy) -> Self {
+        let health = SourceHealth {
+            name: source.name().to_string(),
+            ..SourceHealth::default()
+        };
+        Self {
+            source,
+            policy,
+            breaker: Mutex::new(Breaker {
+                health,
+                cached: None,
+                opened_at: None,
+                warnings: Vec::new(),
+            }),
+        }
+    }
+
+    /// The wrapped source
+    pub const fn inner(&self) -> &S {
+        &self.source
+    }
+
+    /// The policy applied to the source
+    pub const fn policy(&self) -> &SourcePolicy {
+        &self.policy
+    }
+
+    /// Current circuit breaker state
+    pub fn state(&self) -> CircuitState {
+        self.breaker.lock().health.state
+    }
+
+    /// Warnings collected while the source was failing, oldest first
+    pub fn warnings(&self) -> Vec<String> {
+        self.breaker.lock().warnings.clone()
+    }
+
+    /// Close the circuit and reset the consecutive failure count, keeping the cache
+    pub fn reset(&self) {
+        let mut breaker = self.breaker.lock();
+        breaker.health.state = CircuitState::Closed;
+        breaker.health.consecutive_failures = 0;
+        breaker.opened_at = None;
+    }
+}
+
+impl<S> ResilientSource<S>
+where
+    S: AsyncConfigSource,
+    S::Output: Clone,
+{
+    /// Answer a load while the circuit is open, or move to half-open once the cool-down ended
+    fn skip_while_open(&self) -> Option<Result<S::Output, String>> {
+        let mut breaker = self.breaker.lock();
+        if breaker.health.state != CircuitState::Open {
+            return None;
+        }
+
+        let open_for = breaker.opened_at.map_or(Duration::MAX, |at| at.elapsed());
+        if open_for >= self.policy.cooldown {
+            breaker.health.state = CircuitState::HalfOpen;
+            return None;
+        }
+
+        let retry_in = self.policy.cooldown.saturating_sub(open_for);
+        let reason = format!("circuit open, retrying in {}ms", retry_in.as_millis());
+        let result = Self::serve_stale(&mut breaker, &reason);
+        drop(breaker);
+        Some(result)
+    }
+
+    /// Serve the cached configuration after a failure, or fail when there is none
+    fn serve_stale(breaker: &mut Breaker<S::Output>, reason: &str) -> Result<S::Output, String> {
+        let name = &breaker.health.name;
+        let Some(cached) = breaker.cached.clone() else {
+            return Err(format!(
+                "superconfig.source: Source {name} unavailable ({reason}) and no cached configuration"
+            ));
+        };
+
+        breaker.health.stale_serves = breaker.health.stale_serves.saturating_add(1);
+        let warning = format!("Source {name} unavailable ({reason}), serving cached configuration");
+        warn!(target: "superconfig.source", "{warning}");
+        breaker.warnings.push(warning);
+        Ok(cached)
+    }
+
+    /// Update the breaker with the outcome of a load attempt
+    fn record(&self, attempt: Attempt<S::Output>) -> Result<S::Output, String> {
+        let mut guard = self.breaker.lock();
+        let breaker = &mut *guard;
+        let error = match attempt {
+            Attempt::Loaded(data) => {
+                if breaker.health.state != CircuitState::Closed {
+                    info!(target: "superconfig.source", "Source {} recovered, circuit closed", breaker.health.name);
+                }
+                breaker.health.state = CircuitState::Closed;
+                breaker.health.consecutive_failures = 0;
+                breaker.health.total_successes = breaker.health.total_successes.saturating_add(1);
+                breaker.opened_at = None;
+                breaker.cached = Some(data.clone());
+                return Ok(data);
+            }
+            Attempt::Failed(error) => error,
+            Attempt::TimedOut(timeout) => {
+                breaker.health.total_timeouts = breaker.health.total_timeouts.saturating_add(1);
+                format!("timed out after {}ms", timeout.as_millis())
+            }
+        };
+
+        let health = &mut breaker.health;
+        health.total_failures = health.total_failures.saturating_add(1);
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+        health.last_error = Some(error.clone());
+
+        let trips = health.state == CircuitState::HalfOpen
+            || health.consecutive_failures >= self.policy.failure_threshold;
+        if trips {
+            health.state = CircuitState::Open;
+            breaker.opened_at = Some(Instant::now());
+            let warning = format!(
+                "Source {} failed {} time(s) in a row, circuit open for {}ms",
+                breaker.health.name,
+                breaker.health.consecutive_failures,
+                self.policy.cooldown.as_millis()
+            );
+            warn!(target: "superconfig.source", "{warning}");
+            breaker.warnings.push(warning);
+        }
+
+        let result = Self::serve_stale(breaker, &error);
+        drop(guard);
+        result
+    }
+}
+
+impl<S> AsyncConfigSource for ResilientSource<S>
+where
+    S: AsyncConfigSource,
+    S::Output: Clone,
+{
+    type Output = S::Output;
+
+    fn name(&self) -> &str {
+        self.source.name()
+    }
+
+    async fn load(&self) -> Result<S::Output, String> {
+        if let Some(result) = self.skip_while_open() {
+            return result;
+        }
+
+        let attempt = match self.policy.timeout {
+            Some(timeout) => match with_timeout(self.source.load(), timeout).await {
+                Some(Ok(data)) => Attempt::Loaded(data),
+                Some(Err(error)) => Attempt::Failed(error),
+                None => Attempt::TimedOut(timeout),
+            },
+            None => match self.source.load().await {
+                Ok(data) => Attempt::Loaded(data),
+                Err(error) => Attempt::Failed(error),
+            },
+        };
+        self.record(attempt)
+    }
+
+    fn health(&self) -> Option<SourceHealth> {
+        Some(self.breaker.lock().health.clone())
+    }
+}
+
+/// Timer shared between a timed future and its timer thread
+#[derive(Default)]
+struct Deadline {
+    expired: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Run `future`, giving up with `None` once `timeout` has elapsed
+async fn with_timeout<F: Future>(future: F, timeout: Duration) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut deadline: Option<Arc<Deadline>> = None;
+
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        let deadline = deadline.get_or_insert_with(|| {
+            let deadline = Arc::new(Deadline::default());
+            let timer = Arc::clone(&deadline);
+            std::thread::spawn(move || {
+                std::thread::sleep(timeout);
+                timer.expired.store(true, Ordering::Release);
+                let waker = timer.waker.lock().take();
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            });
+            deadline
+        });
+
+        *deadline.waker.lock() = Some(cx.waker().clone());
+        if deadline.expired.load(Ordering::Acquire) {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ConfigRegistry;
+    use std::sync::atomic::AtomicU32;
+
+    /// Source whose loads succeed, fail, or hang depending on `mode`
+    struct FlakySource {
+        mode: AtomicU32,
+        loads: AtomicU32,
+    }
+
+    const OK: u32 = 0;
+    const FAIL: u32 = 1;
+    const HANG: u32 = 2;
+
+    impl FlakySource {
+        const fn new() -> Self {
+            Self {
+                mode: AtomicU32::new(OK),
+                loads: AtomicU32::new(0),
+            }
+        }
+
+        fn set(&self, mode: u32) {
+            self.mode.store(mode, Ordering::Relaxed);
+        }
+    }
+
+    impl AsyncConfigSource for FlakySource {
+        type Output = u32;
+
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        async fn load(&self) -> Result<u32, String> {
+            let load = self.loads.fetch_add(1, Ordering::Relaxed) + 1;
+            match self.mode.load(Ordering::Relaxed) {
+                FAIL => Err("connection refused".to_string()),
+                HANG => std::future::pending().await,
+                _ => Ok(load),
+            }
+        }
+    }
+
+    fn policy() -> SourcePolicy {
+        SourcePolicy::default()
+            .with_timeout(Duration::from_millis(50))
+            .with_failure_threshold(2)
+            .with_cooldown(Duration::from_millis(100))
+    }
+
+    #[test]
+    fn test_circuit_opens_and_serves_cached_data() {
+        tokio_test::block_on(async {
+            let source = ResilientSource::new(FlakySource::new(), policy());
+            assert_eq!(source.load().await.unwrap(), 1);
+
+            source.inner().set(FAIL);
+            assert_eq!(source.load().await.unwrap(), 1);
+            assert_eq!(source.state(), CircuitState::Closed);
+            assert_eq!(source.load().await.unwrap(), 1);
+            assert_eq!(source.state(), CircuitState::Open);
+
+            // Skipped while open: the source isn't called
+            assert_eq!(source.load().await.unwrap(), 1);
+            assert_eq!(source.inner().loads.load(Ordering::Relaxed), 3);
+
+            let health = source.health().unwrap();
+            assert_eq!(health.consecutive_failures, 2);
+            assert_eq!(health.total_failures, 2);
+            assert_eq!(health.stale_serves, 3);
+            assert_eq!(health.last_error.as_deref(), Some("connection refused"));
+            assert!(source.warnings().iter().any(|w| w.contains("circuit open")));
+        });
+    }
+
+    #[test]
+    fn test_half_open_trial_closes_or_reopens() {
+        tokio_test::block_on(async {
+            let source = ResilientSource::new(FlakySource::new(), policy());
+            source.inner().set(FAIL);
+            assert!(source.load().await.is_err());
+            assert!(source.load().await.is_err());
+            assert_eq!(source.state(), CircuitState::Open);
+
+            // A failed trial reopens the circuit immediately
+            std::thread::sleep(Duration::from_millis(120));
+            assert!(source.load().await.is_err());
+            assert_eq!(source.state(), CircuitState::Open);
+
+            std::thread::sleep(Duration::from_millis(120));
+            source.inner().set(OK);
+            assert_eq!(source.load().await.unwrap(), 4);
+            assert_eq!(source.state(), CircuitState::Closed);
+            assert_eq!(source.health().unwrap().consecutive_failures, 0);
+        });
+    }
+
+    #[test]
+    fn test_slow_source_times_out() {
+        tokio_test::block_on(async {
+            let source = ResilientSource::new(FlakySource::new(), policy());
+            source.inner().set(HANG);
+
+            let started = Instant::now();
+            let error = source.load().await.unwrap_err();
+            assert!(started.elapsed() < Duration::from_secs(2));
+            assert!(error.contains("timed out after 50ms"), "{error}");
+            assert!(error.contains("no cached configuration"));
+            assert_eq!(source.health().unwrap().total_timeouts, 1);
+        });
+    }
+
+    #[test]
+    fn test_registry_reports_source_health() {
+        tokio_test::block_on(async {
+            let registry = ConfigRegistry::new();
+            let source = ResilientSource::new(FlakySource::new(), policy());
+            let handle = registry.create_from_source(&source).await.unwrap();
+
+            source.inner().set(FAIL);
+            registry.update_from_source(&handle, &source).await.unwrap();
+            assert_eq!(*registry.read(&handle).unwrap(), 1);
+
+            let stats = registry.stats();
+            assert_eq!(stats.sources.len(), 1);
+            assert_eq!(stats.sources[0].name, "flaky");
+            assert_eq!(stats.sources[0].total_failures, 1);
+            assert!(
+                registry
+                    .stats_as_json()
+                    .contains("\"consecutive_failures\":1")
+            );
+        });
+    }
+}
@@ -0,0 +1,52 @@
+//! Error types and handle ID definitions
+//!
+//! Most registry operations report failures as `String` messages prefixed with their
+//! log target, such as `"superconfig.registry: Handle 7 not found"`. The read path
+//! instead returns a [`RegistryError`]: a small `Copy` enum built without allocating or
+//! formatting, so code reading in a loop, or probing handles that may be gone, pays
+//! nothing for failures it handles itself. It converts into the usual message with
+//! `String::from`, or `?` in functions returning `Result<_, String>`.
+//!
+//! ```
+//! use superconfig::{ConfigRegistry, RegistryError};
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry.create(8080_u16).unwrap();
+//! registry.delete(&handle).unwrap();
+//!
+//! let error = registry.try_read(&handle).unwrap_err();
+//! assert_eq!(error, RegistryError::HandleNotFound(handle.id()));
+//! assert_eq!(
+//!     String::from(error),
+//!     format!("superconfig.registry: Handle {} not found", handle.id())
+//! );
+//! ```
+
+use thiserror::Error;
+
+/// Unique identifier for configuration handles
+pub type HandleId = u64;
+
+/// Why an entry couldn't be read
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegistryError {
+    /// No entry has the handle's ID, in the registry or its parents
+    #[error("Handle {0} not found")]
+    HandleNotFound(HandleId),
+
+    /// The entry holds another type than the handle's
+    #[error("Wrong type, expected {expected}, found {found}")]
+    WrongType {
+        /// Type of the handle
+        expected: &'static str,
+        /// Type of the stored data
+        found: &'static str,
+    },
+}
+
+impl From<RegistryError> for String {
+    fn from(error: RegistryError) -> Self {
+        format!("superconfig.registry: {error}")
+    }
+}
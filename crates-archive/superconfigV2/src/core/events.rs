@@ -0,0 +1,188 @@
+//! Change notifications emitted by the registry
+//!
+//! Subsystems and FFI layers register a callback with
+//! [`ConfigRegistry::subscribe`](crate::ConfigRegistry::subscribe) and receive a
+//! [`RegistryEvent`] whenever the registry changes, instead of polling it.
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use superconfig::{ConfigRegistry, RegistryEvent, config_flags::runtime};
+//!
+//! let registry = ConfigRegistry::new();
+//! let events = Arc::new(Mutex::new(Vec::new()));
+//! let seen = Arc::clone(&events);
+//! registry.subscribe(move |event| seen.lock().unwrap().push(event.clone()));
+//!
+//! let _registry = registry.enable(runtime::PARALLEL);
+//! assert_eq!(
+//!     events.lock().unwrap()[0],
+//!     RegistryEvent::FlagsChanged { previous: 0, current: runtime::PARALLEL }
+//! );
+//! ```
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+/// Identifier returned by `ConfigRegistry::subscribe`, used to unsubscribe
+pub type SubscriptionId = u64;
+
+/// Change notification delivered to registry subscribers
+///
+/// Serializes with an `event` tag (`{"event": "flags_changed", ...}`) so FFI layers can
+/// forward events as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum RegistryEvent {
+    /// `enable` or `disable` changed the runtime flags
+    FlagsChanged {
+        /// Runtime flags before the change
+        previous: u64,
+        /// Runtime flags right after the change
+        current: u64,
+    },
+    /// `update` replaced the data of an entry
+    Updated {
+        /// Id of the updated handle
+        handle_id: u64,
+    },
+    /// A registry with [`RegistryLimits`](crate::RegistryLimits) evicted an entry to make room
+    Evicted {
+        /// Id of the evicted handle, which no longer resolves
+        handle_id: u64,
+    },
+    /// `evict_expired` removed an entry whose TTL elapsed
+    Expired {
+        /// Id of the expired handle, which no longer resolves
+        handle_id: u64,
+    },
+    /// `select_profile` switched the profiled entries to another profile
+    ProfileChanged {
+        /// Profile active before the switch
+        previous: String,
+        /// Profile active after the switch
+        current: String,
+    },
+}
+
+impl RegistryEvent {
+    /// Runtime flags switched on by this event
+    #[must_use]
+    pub const fn enabled_flags(&self) -> u64 {
+        match self {
+            Self::FlagsChanged { previous, current } => *current & !*previous,
+            Self::Updated { .. }
+            | Self::Evicted { .. }
+            | Self::Expired { .. }
+            | Self::ProfileChanged { .. } => 0,
+        }
+    }
+
+    /// Runtime flags switched off by this event
+    #[must_use]
+    pub const fn disabled_flags(&self) -> u64 {
+        match self {
+            Self::FlagsChanged { previous, current } => *previous & !*current,
+            Self::Updated { .. }
+            | Self::Evicted { .. }
+            | Self::Expired { .. }
+            | Self::ProfileChanged { .. } => 0,
+        }
+    }
+}
+
+type Subscriber = Arc<dyn Fn(&RegistryEvent) + Send + Sync>;
+
+/// Subscriber list of a registry
+#[derive(Default)]
+pub(crate) struct Notifier {
+    next_id: AtomicU64,
+    subscribers: RwLock<Vec<(SubscriptionId, Subscriber)>>,
+}
+
+impl Notifier {
+    pub(crate) fn subscribe(&self, subscriber: Subscriber) -> SubscriptionId {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.subscribers.write().push((id, subscriber));
+        id
+    }
+
+    pub(crate) fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.subscribers.write();
+        let before = subscribers.len();
+        subscribers.retain(|(subscriber_id, _)| *subscriber_id != id);
+        subscribers.len() != before
+    }
+
+    /// Call every subscriber with `event`
+    ///
+    /// The list is copied first, so subscribers may use the registry, subscribe or
+    /// unsubscribe without deadlocking.
+    pub(crate) fn emit(&self, event: &RegistryEvent) {
+        let subscribers: Vec<Subscriber> = self
+            .subscribers
+            .read()
+            .iter()
+            .map(|(_, subscriber)| Arc::clone(subscriber))
+            .collect();
+        for subscriber in subscribers {
+            subscriber(event);
+        }
+    }
+}
+
+impl std::fmt::Debug for Notifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notifier")
+            .field("subscribers", &self.subscribers.read().len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_emit_reaches_subscribers_until_unsubscribed() {
+        let notifier = Notifier::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let id = notifier.subscribe(Arc::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        let event = RegistryEvent::FlagsChanged {
+            previous: 0,
+            current: 1,
+        };
+
+        notifier.emit(&event);
+        assert!(notifier.unsubscribe(id));
+        assert!(!notifier.unsubscribe(id));
+        notifier.emit(&event);
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_event_flag_deltas_and_json() {
+        let event = RegistryEvent::FlagsChanged {
+            previous: 0b0110,
+            current: 0b0011,
+        };
+        assert_eq!(event.enabled_flags(), 0b0001);
+        assert_eq!(event.disabled_flags(), 0b0100);
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"event": "flags_changed", "previous": 6, "current": 3})
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Type-safe handles for accessing configuration data
 
-use super::registry::HandleId;
+use super::{registry::HandleId, time::Instant};
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
@@ -102,6 +102,82 @@ impl<T> std::hash::Hash for ConfigHandle<T> {
     }
 }
 
+/// Type-erased handle for inspecting registry entries without knowing their type
+///
+/// Diagnostic tooling and FFI bridges receive bare handle IDs; an `AnyConfigHandle`
+/// lets them ask the registry what an entry holds before committing to a typed
+/// [`ConfigHandle`]. Like typed handles, it serializes as just the handle ID.
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{AnyConfigHandle, ConfigRegistry};
+///
+/// let registry = ConfigRegistry::new();
+/// let handle = registry.create("localhost".to_string()).unwrap();
+/// let id = handle.id();
+///
+/// let any = AnyConfigHandle::from(handle);
+/// assert_eq!(any.id(), id);
+/// assert!(registry.is_type::<String>(&any));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AnyConfigHandle {
+    id: HandleId,
+}
+
+impl AnyConfigHandle {
+    /// Create a type-erased handle from a raw handle ID
+    #[must_use]
+    pub const fn new(id: HandleId) -> Self {
+        Self { id }
+    }
+
+    /// Get the handle ID
+    #[must_use]
+    pub const fn id(&self) -> HandleId {
+        self.id
+    }
+}
+
+impl<T> From<ConfigHandle<T>> for AnyConfigHandle {
+    fn from(handle: ConfigHandle<T>) -> Self {
+        Self::new(handle.id())
+    }
+}
+
+/// Description of a live registry entry, listed by `ConfigRegistry::handles`
+///
+/// Serializes without `created_at`, which has no portable representation; FFI clients
+/// get `age_ms` instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HandleInfo {
+    /// Handle ID of the entry
+    pub id: HandleId,
+    /// Type name of the stored data, as in `ConfigRegistry::type_name`
+    pub type_name: &'static str,
+    /// Name of entries created with `ConfigRegistry::create_named`
+    pub name: Option<String>,
+    /// Generation of the data, as returned by `ConfigRegistry::generation`
+    pub generation: u64,
+    /// When the entry was created or last updated
+    #[serde(skip)]
+    pub created_at: Instant,
+    /// Milliseconds since `created_at`, when the list was taken
+    pub age_ms: u64,
+    /// Approximate size of the data in bytes, as counted in the registry statistics
+    pub size_bytes: usize,
+}
+
+impl HandleInfo {
+    /// Type-erased handle to the entry
+    #[must_use]
+    pub const fn handle(&self) -> AnyConfigHandle {
+        AnyConfigHandle::new(self.id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,6 +290,17 @@ mod tests {
         // handle_string == handle_int; // This won't compile - good!
     }
 
+    #[test]
+    fn test_any_handle_from_typed_handle() {
+        let handle = ConfigHandle::<TestConfig>::new(7);
+        let any = AnyConfigHandle::from(handle);
+
+        assert_eq!(any.id(), 7);
+        assert_eq!(any, AnyConfigHandle::new(7));
+        assert_eq!(serde_json::to_string(&any).unwrap(), "7");
+        assert_eq!(serde_json::from_str::<AnyConfigHandle>("7").unwrap(), any);
+    }
+
     #[test]
     fn test_handle_deserialize_error_path() {
         // Test the error path in deserialize (line 85)
@@ -0,0 +1,348 @@
+//! Placeholder expansion in configuration values
+//!
+//! With the [`ENV_EXPANSION`](crate::config_flags::runtime::ENV_EXPANSION) runtime flag
+//! enabled, configuration loaded from files and strings has the `${...}` placeholders
+//! of its string values replaced before it is deserialized:
+//!
+//! | Placeholder | Replaced with |
+//! |-------------|---------------|
+//! | `${database.host}` | The value at that key path of the same configuration |
+//! | `${HOME}` | The environment variable, when no key has that path |
+//! | `${env:HOME}` | The environment variable, even if a key has that path |
+//! | `${file:/run/secrets/db}` | The file's content, without its trailing newline |
+//! | `${PORT:-8080}` | `PORT`, or `8080` when it isn't set |
+//! | `$${literal}` | `${literal}`, unexpanded |
+//!
+//! Key paths use the syntax of [`get_path`](ConfigRegistry::get_path). A value that is a
+//! placeholder alone keeps the type of what it refers to, so `port = "${PORT}"` loads
+//! `PORT=8080` as the number `8080` and `"${defaults}"` copies a whole table; otherwise
+//! the text is substituted. Referenced keys are expanded first, and keys referring
+//! back to themselves through other keys are reported as a cycle.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use superconfig::{ConfigRegistry, Format, config_flags::runtime};
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     port: u16,
+//!     url: String,
+//! }
+//!
+//! let registry = ConfigRegistry::new().enable(runtime::ENV_EXPANSION);
+//! let handle = registry
+//!     .create_from_str::<Database>(
+//!         r#"{
+//!             "host": "${DB_HOST_UNSET:-db.internal}",
+//!             "port": "${DB_PORT_UNSET:-5432}",
+//!             "url": "postgres://${host}:${port}"
+//!         }"#,
+//!         Format::Json,
+//!     )
+//!     .unwrap();
+//! assert_eq!(registry.read(&handle).unwrap().url, "postgres://db.internal:5432");
+//! ```
+
+use std::collections::HashMap;
+
+use logffi::error;
+use serde_json::Value;
+
+use super::{patch::resolve, path::parse_path, registry::ConfigRegistry};
+use crate::{config_flags::runtime, sources::env::coerce};
+
+/// Expand the `${...}` placeholders of the string values in `tree`
+///
+/// See the [module documentation](self) for the placeholder syntax.
+///
+/// # Errors
+///
+/// Returns error message if a placeholder is unterminated, refers to a variable, key
+/// or file that doesn't exist and has no default, or is part of a cycle.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+///
+/// let tree = json!({"name": "app", "log": "/var/log/${name}.log", "raw": "$${name}"});
+/// assert_eq!(
+///     superconfig::interpolate(&tree).unwrap(),
+///     json!({"name": "app", "log": "/var/log/app.log", "raw": "${name}"})
+/// );
+/// ```
+pub fn interpolate(tree: &Value) -> Result<Value, String> {
+    Resolver {
+        root: tree,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    }
+    .expand_value(&mut Vec::new(), tree)
+    .map_err(|e| {
+        error!(target: "superconfig.interpolate", "{e}");
+        format!("superconfig.interpolate: {e}")
+    })
+}
+
+impl ConfigRegistry {
+    /// `tree` with its placeholders expanded if `ENV_EXPANSION` is enabled
+    pub(crate) fn expand(&self, tree: Value) -> Result<Value, String> {
+        if self.runtime_enabled(runtime::ENV_EXPANSION) {
+            interpolate(&tree)
+        } else {
+            Ok(tree)
+        }
+    }
+}
+
+/// A piece of a string value
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Expands the values of one tree, resolving referenced keys on demand
+struct Resolver<'a> {
+    root: &'a Value,
+    /// Expanded string values, by dotted key path
+    resolved: HashMap<String, Value>,
+    /// Key paths being expanded, to detect cycles
+    stack: Vec<String>,
+}
+
+impl Resolver<'_> {
+    fn expand_value(&mut self, path: &mut Vec<String>, value: &Value) -> Result<Value, String> {
+        match value {
+            Value::String(text) => self.expand_string(path, text),
+            Value::Array(items) => {
+                let mut expanded = Vec::with_capacity(items.len());
+                for (index, item) in items.iter().enumerate() {
+                    path.push(index.to_string());
+                    let item = self.expand_value(path, item);
+                    path.pop();
+                    expanded.push(item?);
+                }
+                Ok(Value::Array(expanded))
+            }
+            Value::Object(map) => {
+                let mut expanded = serde_json::Map::with_capacity(map.len());
+                for (key, item) in map {
+                    path.push(key.clone());
+                    let item = self.expand_value(path, item);
+                    path.pop();
+                    expanded.insert(key.clone(), item?);
+                }
+                Ok(Value::Object(expanded))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+
+    fn expand_string(&mut self, path: &[String], text: &str) -> Result<Value, String> {
+        if !text.contains('$') {
+            return Ok(Value::String(text.to_string()));
+        }
+        let key = path.join(".");
+        if let Some(value) = self.resolved.get(&key) {
+            return Ok(value.clone());
+        }
+        if let Some(start) = self.stack.iter().position(|entry| *entry == key) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(key);
+            return Err(format!("cycle between keys {}", cycle.join(" -> ")));
+        }
+
+        self.stack.push(key.clone());
+        let expanded = self.expand_text(text, &key);
+        self.stack.pop();
+        let expanded = expanded?;
+        self.resolved.insert(key, expanded.clone());
+        Ok(expanded)
+    }
+
+    fn expand_text(&mut self, text: &str, key: &str) -> Result<Value, String> {
+        let segments = segments(text).map_err(|e| format!("{e} in `{key}`"))?;
+        if let [Segment::Placeholder(expression)] = segments.as_slice() {
+            return self.lookup(expression, true);
+        }
+        let mut expanded = String::with_capacity(text.len());
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => expanded.push_str(text),
+                Segment::Placeholder(expression) => match self.lookup(expression, false)? {
+                    Value::String(value) => expanded.push_str(&value),
+                    value => expanded.push_str(&value.to_string()),
+                },
+            }
+        }
+        Ok(Value::String(expanded))
+    }
+
+    /// The value of the placeholder `expression`, typed if it is a whole value
+    fn lookup(&mut self, expression: &str, typed: bool) -> Result<Value, String> {
+        let (expression, default) = match expression.split_once(":-") {
+            Some((expression, default)) => (expression, Some(default)),
+            None => (expression, None),
+        };
+        let text = |value: String| {
+            if typed {
+                coerce(&value)
+            } else {
+                Value::String(value)
+            }
+        };
+
+        let found = if let Some(file) = expression.strip_prefix("file:") {
+            match std::fs::read_to_string(file) {
+                Ok(content) => Some(text(content.trim_end_matches(['\n', '\r']).to_string())),
+                Err(_) if default.is_some() => None,
+                Err(e) => return Err(format!("cannot read {file}: {e}")),
+            }
+        } else if let Some(name) = expression.strip_prefix("env:") {
+            std::env::var(name).ok().map(text)
+        } else if let Some(target) = parse_path(expression).ok().and_then(|tokens| {
+            resolve(self.root, &tokens)
+                .ok()
+                .map(|value| (tokens, value))
+        }) {
+            let (mut tokens, value) = target;
+            Some(self.expand_value(&mut tokens, value)?)
+        } else {
+            std::env::var(expression).ok().map(text)
+        };
+
+        found
+            .or_else(|| default.map(|default| text(default.to_string())))
+            .ok_or_else(|| format!("`{expression}` is neither a key nor an environment variable"))
+    }
+}
+
+/// Split `text` into literal text and placeholders, unescaping `$${`
+fn segments(text: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            // `$${` is a literal `${`
+            segments.push(Segment::Text(&rest[..start - 1]));
+            segments.push(Segment::Text("${"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| "unterminated placeholder".to_string())?;
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        segments.push(Segment::Placeholder(&rest[start + 2..start + end]));
+        rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_keys_and_typed_values() {
+        let tree = json!({
+            "defaults": {"port": 80, "tags": ["a"]},
+            "server": {"port": "${defaults.port}", "tags": "${defaults.tags}"},
+            "url": "http://host:${server.port}/${servers.0}",
+            "servers": ["${env:SUPERCONFIG_TEST_UNSET:-edge}"],
+        });
+        assert_eq!(
+            interpolate(&tree).unwrap(),
+            json!({
+                "defaults": {"port": 80, "tags": ["a"]},
+                "server": {"port": 80, "tags": ["a"]},
+                "url": "http://host:80/edge",
+                "servers": ["edge"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_environment_and_defaults() {
+        let path = std::env::var("PATH").unwrap();
+        let tree = json!({
+            "path": "${PATH}",
+            "port": "${SUPERCONFIG_TEST_UNSET:-8080}",
+            "label": "port ${SUPERCONFIG_TEST_UNSET:-8080}",
+        });
+        assert_eq!(
+            interpolate(&tree).unwrap(),
+            json!({"path": path, "port": 8080, "label": "port 8080"})
+        );
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret = dir.path().join("secret");
+        std::fs::write(&secret, "hunter2\n").unwrap();
+        let tree = json!({
+            "password": format!("${{file:{}}}", secret.display()),
+            "missing": "${file:/nonexistent/superconfig:-none}",
+        });
+        assert_eq!(
+            interpolate(&tree).unwrap(),
+            json!({"password": "hunter2", "missing": "none"})
+        );
+        let error = interpolate(&json!({"a": "${file:/nonexistent/superconfig}"})).unwrap_err();
+        assert!(error.starts_with("superconfig.interpolate: cannot read /nonexistent/superconfig"));
+    }
+
+    #[test]
+    fn test_escaping() {
+        let tree = json!({"a": "$${a} and $$ and $5", "b": "$${b}${a}"});
+        assert_eq!(
+            interpolate(&tree).unwrap(),
+            json!({"a": "${a} and $$ and $5", "b": "${b}${a} and $$ and $5"})
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            interpolate(&json!({"a": "${b}", "b": {"c": "${a}"}})).unwrap_err(),
+            "superconfig.interpolate: cycle between keys a -> b.c -> a"
+        );
+        assert_eq!(
+            interpolate(&json!({"a": "${a}"})).unwrap_err(),
+            "superconfig.interpolate: cycle between keys a -> a"
+        );
+        assert_eq!(
+            interpolate(&json!({"a": "${b"})).unwrap_err(),
+            "superconfig.interpolate: unterminated placeholder in `a`"
+        );
+        assert_eq!(
+            interpolate(&json!({"a": "${SUPERCONFIG_TEST_UNSET}"})).unwrap_err(),
+            "superconfig.interpolate: `SUPERCONFIG_TEST_UNSET` is neither a key nor an \
+             environment variable"
+        );
+    }
+
+    #[test]
+    fn test_registry_expands_only_with_flag() {
+        let content = r#"{"name": "app", "title": "${name}"}"#;
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_str::<Value>(content, crate::Format::Json)
+            .unwrap();
+        assert_eq!(registry.read(&handle).unwrap()["title"], "${name}");
+
+        let registry = registry.enable(runtime::ENV_EXPANSION);
+        let handle = registry
+            .create_from_str::<Value>(content, crate::Format::Json)
+            .unwrap();
+        assert_eq!(registry.read(&handle).unwrap()["title"], "app");
+    }
+}
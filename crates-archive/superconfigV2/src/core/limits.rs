@@ -0,0 +1,151 @@
+//! Capacity limits and eviction policies of the registry
+//!
+//! A registry created with [`ConfigRegistry::custom_with_limits`](crate::ConfigRegistry::custom_with_limits)
+//! bounds its number of entries and their approximate memory, so long-running
+//! multi-tenant services can't grow it without bound. When a `create` or `update` would
+//! exceed a limit, the [`EvictionPolicy`] decides between rejecting it and evicting the
+//! least recently used entries.
+//!
+//! ```
+//! use superconfig::{ConfigRegistry, EvictionPolicy, RegistryLimits, config_flags::startup};
+//!
+//! let limits = RegistryLimits::default()
+//!     .with_max_entries(2)
+//!     .with_eviction(EvictionPolicy::LeastRecentlyUsed);
+//! let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+//!
+//! let first = registry.create("a".to_string()).unwrap();
+//! let second = registry.create("b".to_string()).unwrap();
+//! registry.read(&first).unwrap();
+//!
+//! // `second` is the least recently used entry, so it makes room for `third`
+//! let third = registry.create("c".to_string()).unwrap();
+//! assert!(registry.contains_handle(&first));
+//! assert!(!registry.contains_handle(&second));
+//! assert!(registry.contains_handle(&third));
+//! assert_eq!(registry.stats().total_evictions, 1);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// What the registry does when an operation would exceed its limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Fail the `create` or `update` with a "Registry full" error
+    #[default]
+    RejectWhenFull,
+    /// Evict the entries read or written least recently until the new data fits
+    ///
+    /// Reads record their access time, which costs an atomic increment per read.
+    LeastRecentlyUsed,
+}
+
+/// Capacity limits of a registry, unbounded by default
+///
+/// Memory is the approximate size reported in
+/// [`RegistryStats::memory_usage_bytes`](crate::RegistryStats::memory_usage_bytes):
+/// the size of the stored values, not of the heap data they own.
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{EvictionPolicy, RegistryLimits};
+///
+/// let limits = RegistryLimits::default()
+///     .with_max_entries(10_000)
+///     .with_max_memory_bytes(64 * 1024 * 1024);
+/// assert_eq!(limits.max_entries, Some(10_000));
+/// assert_eq!(limits.eviction, EvictionPolicy::RejectWhenFull);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegistryLimits {
+    /// Most entries the registry holds, `None` for no limit
+    pub max_entries: Option<usize>,
+    /// Most approximate memory the entries use, `None` for no limit
+    pub max_memory_bytes: Option<u64>,
+    /// What happens when an operation would exceed a limit
+    pub eviction: EvictionPolicy,
+}
+
+impl RegistryLimits {
+    /// Set the most entries the registry holds
+    #[must_use]
+    pub const fn with_max_entries(mut self, entries: usize) -> Self {
+        self.max_entries = Some(entries);
+        self
+    }
+
+    /// Set the most approximate memory the entries use
+    #[must_use]
+    pub const fn with_max_memory_bytes(mut self, bytes: u64) -> Self {
+        self.max_memory_bytes = Some(bytes);
+        self
+    }
+
+    /// Set what happens when an operation would exceed a limit
+    #[must_use]
+    pub const fn with_eviction(mut self, eviction: EvictionPolicy) -> Self {
+        self.eviction = eviction;
+        self
+    }
+
+    /// Whether any limit is set
+    #[must_use]
+    pub const fn is_bounded(&self) -> bool {
+        self.max_entries.is_some() || self.max_memory_bytes.is_some()
+    }
+
+    /// Why a registry with `entries` entries using `memory` bytes exceeds the limits,
+    /// `None` if it doesn't
+    pub(crate) fn exceeded(&self, entries: usize, memory: u64) -> Option<String> {
+        if let Some(max) = self.max_entries
+            && entries > max
+        {
+            return Some(format!("limit of {max} entries"));
+        }
+        if let Some(max) = self.max_memory_bytes
+            && memory > max
+        {
+            return Some(format!("limit of {max} bytes"));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_limits_are_unbounded() {
+        let limits = RegistryLimits::default();
+        assert!(!limits.is_bounded());
+        assert_eq!(limits.exceeded(usize::MAX, u64::MAX), None);
+    }
+
+    #[test]
+    fn test_exceeded_reports_the_limit() {
+        let limits = RegistryLimits::default()
+            .with_max_entries(2)
+            .with_max_memory_bytes(100);
+        assert!(limits.is_bounded());
+        assert_eq!(limits.exceeded(2, 100), None);
+        assert_eq!(limits.exceeded(3, 0).unwrap(), "limit of 2 entries");
+        assert_eq!(limits.exceeded(1, 101).unwrap(), "limit of 100 bytes");
+    }
+
+    #[test]
+    fn test_limits_deserialize_from_json() {
+        let limits: RegistryLimits =
+            serde_json::from_str(r#"{"max_entries": 5, "eviction": "least_recently_used"}"#)
+                .unwrap();
+        assert_eq!(
+            limits,
+            RegistryLimits::default()
+                .with_max_entries(5)
+                .with_eviction(EvictionPolicy::LeastRecentlyUsed)
+        );
+    }
+}
@@ -9,13 +9,44 @@
 //! - [`stats`] - Statistics tracking for registry operations
 //! - [`handle`] - Type-safe handles for configuration access
 //! - [`registry`] - Main configuration registry implementation
+//! - [`limits`] - Capacity limits and eviction policies of the registry
+//! - [`snapshot`] - Serializable snapshots of the registry, restored with `restore`
+//! - [`bridge`] - Creating entries from a Figment and providing them to one (`figment` feature)
+//! - [`backend`] - Storage backends persisting the registry across restarts
+//! - [`events`] - Change notifications delivered to registry subscribers
+//! - [`patch`] - JSON Patch and JSON Merge Patch application
+//! - [`overlay`] - Copy-on-write views of a configuration with a patch applied
+//! - [`path`] - Reading and setting single values by key path, like `database.port`
+//! - [`pinned`] - Entries kept current for reads without a registry lookup
+//! - [`interpolate`] - Expansion of `${...}` placeholders in loaded configuration
+//! - [`source`] - Asynchronous configuration sources
+//! - [`circuit`] - Timeouts and circuit breaking for remote sources
+//! - [`pool`] - Worker threads of registries created with the `THREAD_POOL` flag
+//! - [`plugin`] - Configuration sources loaded from dynamic libraries
+//! - [`profile`] - Per-environment profiles layered over default values
+//! - [`validation`] - Validators run on every create and update of an entry
+//! - [`value`] - Deserializing entries in place from their cached JSON tree
 //!
 //! ## Key Components
 //!
 //! - **`ConfigRegistry`**: The main registry for storing and accessing configuration data
 //! - **`ConfigHandle`<T>**: Type-safe handles that provide zero-cost access
+//! - **`AnyConfigHandle`**: Type-erased handles for runtime type inspection
+//! - **`RegistryLimits`**: Entry and memory limits, with the `EvictionPolicy` applied when full
+//! - **`RegistrySnapshot`**: Serializable copy of the entries, for persistence and rollback
+//! - **`StorageBackend`**: Store that registry changes are written through to, such as a file
 //! - **`RegistryStats`**: Performance and usage statistics
-//! - **`RegistryError`**: Comprehensive error handling
+//! - **`RegistryEvent`**: Change notification, such as a runtime flag flip
+//! - **`OverlayHandle`**: Per-request view merging a patch over a shared configuration
+//! - **`AsyncConfigSource`**: Sources loaded asynchronously into the registry
+//! - **`ResilientSource`**: Timeout and circuit breaker wrapper for remote sources
+//! - **`SourcePlugin`**: Source implemented by a plugin library through a C ABI
+//! - **`Validator`**: Rules checked before configuration is stored, reporting `Violation`s
+//! - **`ConfigValue`**: Cached JSON tree of an entry, deserialized without copying
+//! - **`RegistryProvider`**: Figment provider reading an entry, for pipelines mid-migration
+//! - **`ProfileSet`**: Default values and per-profile overrides, switched with `select_profile`
+//! - **`PinnedConfig`**: Entry data refreshed on update, read in a few nanoseconds
+//! - **`RegistryError`**: Allocation-free errors of the read path ([`ConfigRegistry::try_read`])
 //!
 //! ## Examples
 //!
@@ -32,11 +63,56 @@
 //! assert_eq!(*config, "localhost");
 //! ```
 
+pub mod backend;
+#[cfg(feature = "figment")]
+pub mod bridge;
+pub mod circuit;
+pub mod errors;
+pub mod events;
 pub mod handle;
+pub mod interpolate;
+pub mod limits;
+pub mod overlay;
+pub mod patch;
+pub mod path;
+pub mod pinned;
+pub mod plugin;
+pub mod pool;
+pub mod profile;
 pub mod registry;
+pub mod snapshot;
+pub mod source;
 pub mod stats;
+mod sync;
+pub(crate) mod time;
+pub mod validation;
+pub mod value;
 
 // Re-export key types for convenient access
-pub use handle::ConfigHandle;
+pub use backend::{FileBackend, FileFormat, MemoryBackend, StorageBackend};
+#[cfg(feature = "figment")]
+pub use bridge::RegistryProvider;
+pub use circuit::{CircuitState, ResilientSource, SourceHealth, SourcePolicy};
+pub use errors::{HandleId, RegistryError};
+pub use events::{RegistryEvent, SubscriptionId};
+pub use handle::{AnyConfigHandle, ConfigHandle, HandleInfo};
+pub use interpolate::interpolate;
+pub use limits::{EvictionPolicy, RegistryLimits};
+pub use overlay::OverlayHandle;
+pub use pinned::PinnedConfig;
+pub use plugin::{
+    SOURCE_PLUGIN_ABI_VERSION, SOURCE_PLUGIN_ENTRY, SourcePlugin, SourcePluginEntry,
+    SourcePluginTable,
+};
+pub use pool::BackgroundLoad;
+pub use profile::{DEFAULT_PROFILE, ProfileSet};
 pub use registry::{ConfigRegistry, global_registry};
-pub use stats::RegistryStats;
+pub use snapshot::{RegistrySnapshot, SnapshotEntry};
+pub use source::AsyncConfigSource;
+#[cfg(feature = "tokio")]
+pub use source::BlockingSource;
+pub use stats::{RegistryStats, SourceLoadStats};
+#[cfg(feature = "validator")]
+pub use validation::DeriveValidator;
+pub use validation::{Validator, Violation};
+pub use value::ConfigValue;
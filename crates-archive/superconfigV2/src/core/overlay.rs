@@ -0,0 +1,364 @@
+//! Copy-on-write configuration overlays
+//!
+//! Multi-tenant services often need the shared configuration with a few values
+//! changed for one request or tenant. [`ConfigRegistry::overlay`] creates an
+//! [`OverlayHandle`]: a view of a stored JSON configuration with a small RFC 7386
+//! merge patch on top. The overlay shares the base through its `Arc` and only owns
+//! the patch, so creating one costs the size of the patch, not of the configuration.
+//!
+//! - [`OverlayHandle::get`] borrows values the patch doesn't touch straight from the
+//!   base; only objects the patch changes are merged into a new value
+//! - [`OverlayHandle::to_value`] and [`OverlayHandle::deserialize`] materialize the
+//!   full merged configuration when a typed value is needed
+//! - The overlay reads the base as it was when the overlay was created; later updates
+//!   of the base handle don't affect it
+//! - Dropping the overlay releases the patch and its reference to the base. Live
+//!   overlays are counted in [`RegistryStats::active_overlays`](super::RegistryStats::active_overlays)
+//!
+//! ## Examples
+//!
+//! ```
+//! use serde_json::json;
+//! use superconfig::ConfigRegistry;
+//!
+//! let registry = ConfigRegistry::new();
+//! let base = registry
+//!     .create(json!({ "database": { "host": "db.internal", "pool": 20 }, "debug": false }))
+//!     .unwrap();
+//!
+//! let tenant = registry.overlay(&base, json!({ "database": { "pool": 5 } })).unwrap();
+//! assert_eq!(*tenant.get("/database/pool").unwrap(), 5);
+//! assert_eq!(*tenant.get("/database/host").unwrap(), "db.internal");
+//! assert_eq!(registry.stats().active_overlays, 1);
+//!
+//! drop(tenant);
+//! assert_eq!(registry.stats().active_overlays, 0);
+//! ```
+
+use std::{borrow::Cow, sync::Arc};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{
+    handle::ConfigHandle,
+    patch::{apply_merge_patch, parse_pointer, resolve},
+    registry::ConfigRegistry,
+    sync::{AtomicU64, Ordering},
+};
+use logffi::error;
+
+/// A copy-on-write view of a stored JSON configuration with a merge patch applied
+///
+/// Created with [`ConfigRegistry::overlay`]. See the [module documentation](self).
+#[derive(Debug)]
+pub struct OverlayHandle {
+    base_id: u64,
+    base: Arc<Value>,
+    patch: Value,
+    active: Arc<AtomicU64>,
+}
+
+impl OverlayHandle {
+    /// Id of the handle the overlay was created from
+    #[must_use]
+    pub const fn base_id(&self) -> u64 {
+        self.base_id
+    }
+
+    /// The base configuration, as it was when the overlay was created
+    #[must_use]
+    pub const fn base(&self) -> &Arc<Value> {
+        &self.base
+    }
+
+    /// The merge patch applied over the base
+    #[must_use]
+    pub const fn patch(&self) -> &Value {
+        &self.patch
+    }
+
+    /// Value at the JSON Pointer `pointer` (`""` for the whole configuration)
+    ///
+    /// Values the patch doesn't touch are borrowed from the base. Objects the patch
+    /// changes are merged into an owned value, so reading leaves is cheaper than
+    /// reading the tables that contain them.
+    ///
+    /// Returns `None` when the pointer is invalid or nothing exists at it.
+    #[must_use]
+    pub fn get(&self, pointer: &str) -> Option<Cow<'_, Value>> {
+        let path = parse_pointer(pointer).ok()?;
+        lookup(Some(&self.patch), Some(&self.base), &path)
+    }
+
+    /// Deserialize the value at `pointer` into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if nothing exists at `pointer` or the value is not a valid `T`.
+    pub fn get_as<T: DeserializeOwned>(&self, pointer: &str) -> Result<T, String> {
+        let value = self.get(pointer).ok_or_else(|| {
+            let error_msg = format!(
+                "superconfig.overlay: Path {pointer} not found in overlay of handle {}",
+                self.base_id
+            );
+            error!(target: "superconfig.overlay", "Path {pointer} not found in overlay of handle {}", self.base_id);
+            error_msg
+        })?;
+        T::deserialize(value.as_ref()).map_err(|err| self.deserialize_error::<T>(&err))
+    }
+
+    /// The full merged configuration
+    ///
+    /// This copies the base; prefer [`get`](Self::get) for individual values.
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        let mut merged = (*self.base).clone();
+        apply_merge_patch(&mut merged, &self.patch);
+        merged
+    }
+
+    /// Deserialize the full merged configuration into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the merged configuration is not a valid `T`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.to_value()).map_err(|err| self.deserialize_error::<T>(&err))
+    }
+
+    fn deserialize_error<T>(&self, err: &serde_json::Error) -> String {
+        let error_msg = format!(
+            "superconfig.overlay: Overlay of handle {} is not a valid {}: {err}",
+            self.base_id,
+            std::any::type_name::<T>()
+        );
+        error!(target: "superconfig.overlay", "Overlay of handle {} failed to deserialize: {err}", self.base_id);
+        error_msg
+    }
+}
+
+impl Clone for OverlayHandle {
+    /// Clone the view; the clone shares the base and counts as another live overlay
+    fn clone(&self) -> Self {
+        self.active.fetch_add(1, Ordering::Relaxed);
+        Self {
+            base_id: self.base_id,
+            base: Arc::clone(&self.base),
+            patch: self.patch.clone(),
+            active: Arc::clone(&self.active),
+        }
+    }
+}
+
+impl Drop for OverlayHandle {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Resolve `tokens` in the merge of `patch` over `base`, copying only merged objects
+fn lookup<'a>(
+    patch: Option<&'a Value>,
+    base: Option<&'a Value>,
+    tokens: &[String],
+) -> Option<Cow<'a, Value>> {
+    match patch {
+        // Untouched by the patch: borrow from the base
+        None => resolve(base?, tokens).ok().map(Cow::Borrowed),
+        // Removed by the patch
+        Some(Value::Null) => None,
+        Some(Value::Object(patch_map)) => {
+            if let Some((token, rest)) = tokens.split_first() {
+                let base_child = base
+                    .and_then(Value::as_object)
+                    .and_then(|base| base.get(token));
+                return lookup(patch_map.get(token), base_child, rest);
+            }
+            let mut merged = base
+                .filter(|base| base.is_object())
+                .cloned()
+                .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+            apply_merge_patch(&mut merged, patch?);
+            Some(Cow::Owned(merged))
+        }
+        // Any other patch value replaces the base entirely
+        Some(value) => resolve(value, tokens).ok().map(Cow::Borrowed),
+    }
+}
+
+impl ConfigRegistry {
+    /// Create a copy-on-write overlay of a stored JSON configuration
+    ///
+    /// `patch` is an RFC 7386 merge patch: objects merge recursively, `null` removes a
+    /// key, and other values replace what they cover. Neither the base nor the patch is
+    /// copied; see the [`overlay`](super::overlay) module.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let base = registry.create(json!({ "region": "eu", "limits": { "rps": 100 } })).unwrap();
+    ///
+    /// let request = registry.overlay(&base, json!({ "limits": { "rps": 10 } })).unwrap();
+    /// assert_eq!(request.get_as::<u32>("/limits/rps").unwrap(), 10);
+    /// assert_eq!(registry.read(&base).unwrap()["limits"]["rps"], 100);
+    /// ```
+    pub fn overlay(
+        &self,
+        base: &ConfigHandle<Value>,
+        patch: Value,
+    ) -> Result<OverlayHandle, String> {
+        let data = self
+            .read(base)
+            .map_err(|err| format!("superconfig.overlay: Failed to create overlay: {err}"))?;
+        let active = self.overlay_counter();
+        active.fetch_add(1, Ordering::Relaxed);
+        Ok(OverlayHandle {
+            base_id: base.id(),
+            base: data,
+            patch,
+            active,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    fn base() -> Value {
+        json!({
+            "server": { "host": "localhost", "port": 8080, "tls": { "enabled": false } },
+            "features": ["search", "export"],
+            "debug": true
+        })
+    }
+
+    #[test]
+    fn test_untouched_values_are_borrowed() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(base()).unwrap();
+        let overlay = registry
+            .overlay(&handle, json!({ "server": { "port": 9090 } }))
+            .unwrap();
+
+        assert!(matches!(
+            overlay.get("/server/host"),
+            Some(Cow::Borrowed(_))
+        ));
+        assert!(matches!(overlay.get("/features/1"), Some(Cow::Borrowed(_))));
+        assert!(matches!(
+            overlay.get("/server/port"),
+            Some(Cow::Borrowed(_))
+        ));
+        assert_eq!(*overlay.get("/server/port").unwrap(), 9090);
+        assert_eq!(*overlay.get("/features/1").unwrap(), "export");
+        assert!(Arc::ptr_eq(
+            overlay.base(),
+            &registry.read(&handle).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_patched_objects_are_merged() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(base()).unwrap();
+        let overlay = registry
+            .overlay(
+                &handle,
+                json!({ "server": { "tls": { "enabled": true } }, "debug": null }),
+            )
+            .unwrap();
+
+        let server = overlay.get("/server").unwrap();
+        assert!(matches!(server, Cow::Owned(_)));
+        assert_eq!(server["host"], "localhost");
+        assert_eq!(server["tls"]["enabled"], true);
+        assert!(overlay.get("/debug").is_none());
+        assert!(overlay.get("/missing").is_none());
+        assert!(overlay.get("no-slash").is_none());
+
+        let mut expected = base();
+        apply_merge_patch(&mut expected, overlay.patch());
+        assert_eq!(overlay.to_value(), expected);
+        assert_eq!(*overlay.get("").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_replaced_values_hide_the_base() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(base()).unwrap();
+        let overlay = registry
+            .overlay(
+                &handle,
+                json!({ "server": "unix:/run/app.sock", "features": ["beta"] }),
+            )
+            .unwrap();
+
+        assert_eq!(*overlay.get("/server").unwrap(), "unix:/run/app.sock");
+        assert!(overlay.get("/server/host").is_none());
+        assert_eq!(*overlay.get("/features/0").unwrap(), "beta");
+        assert!(overlay.get("/features/1").is_none());
+    }
+
+    #[test]
+    fn test_overlay_is_typed_and_isolated_from_updates() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Server {
+            host: String,
+            port: u16,
+        }
+
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(base()).unwrap();
+        let overlay = registry
+            .overlay(&handle, json!({ "server": { "port": 9090 } }))
+            .unwrap();
+        registry.update(&handle, json!({})).unwrap();
+
+        let server: Server = overlay.get_as("/server").unwrap();
+        assert_eq!(
+            server,
+            Server {
+                host: "localhost".into(),
+                port: 9090
+            }
+        );
+        assert!(overlay.get_as::<u16>("/server/host").is_err());
+        assert!(
+            overlay
+                .get_as::<u16>("/missing")
+                .unwrap_err()
+                .starts_with("superconfig.overlay: ")
+        );
+        assert_eq!(overlay.deserialize::<Value>().unwrap()["debug"], true);
+    }
+
+    #[test]
+    fn test_overlays_are_counted_until_dropped() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(base()).unwrap();
+
+        let first = registry.overlay(&handle, json!({})).unwrap();
+        let second = first.clone();
+        assert_eq!(registry.stats().active_overlays, 2);
+        drop(first);
+        assert_eq!(registry.stats().active_overlays, 1);
+        drop(second);
+        assert_eq!(registry.stats().active_overlays, 0);
+
+        let missing = ConfigHandle::<Value>::new(999);
+        assert!(registry.overlay(&missing, json!({})).is_err());
+        assert_eq!(registry.stats().active_overlays, 0);
+    }
+}
@@ -0,0 +1,504 @@
+//! JSON Patch and JSON Merge Patch support for stored configuration
+//!
+//! [`ConfigRegistry::apply_patch`] edits a stored configuration in place of a full
+//! round-trip: the value is serialized to JSON, patched, deserialized back, validated,
+//! and only then swapped into the registry. The patch format follows its JSON shape:
+//!
+//! - An array is an [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON Patch
+//!   (`[{"op": "replace", "path": "/port", "value": 8080}]`), applied atomically: if
+//!   any operation fails, nothing is changed
+//! - Anything else is an [RFC 7386](https://www.rfc-editor.org/rfc/rfc7386) Merge Patch
+//!   (`{"port": 8080, "debug": null}`), where `null` removes a key
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use super::{handle::ConfigHandle, registry::ConfigRegistry};
+use logffi::error;
+
+impl ConfigRegistry {
+    /// Apply a JSON Patch or JSON Merge Patch to a stored configuration
+    ///
+    /// Arrays are applied as RFC 6902 JSON Patch documents, other values as RFC 7386
+    /// Merge Patches. The stored value is replaced only if the patch applies and the
+    /// result deserializes back into `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist, a patch operation fails, or
+    /// the patched document is not a valid `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use serde_json::json;
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Server {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(Server { host: "localhost".into(), port: 80 }).unwrap();
+    ///
+    /// registry
+    ///     .apply_patch(&handle, &json!([{ "op": "replace", "path": "/port", "value": 8080 }]))
+    ///     .unwrap();
+    /// registry.apply_patch(&handle, &json!({ "host": "example.com" })).unwrap();
+    ///
+    /// let server = registry.read(&handle).unwrap();
+    /// assert_eq!((server.host.as_str(), server.port), ("example.com", 8080));
+    /// ```
+    pub fn apply_patch<T>(&self, handle: &ConfigHandle<T>, patch: &Value) -> Result<(), String>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        self.apply_patch_with(handle, patch, |_| Ok(()))
+    }
+
+    /// Apply a patch like [`apply_patch`](Self::apply_patch), running `validate` on the
+    /// patched configuration before it is swapped in
+    ///
+    /// The patched configuration only replaces the one it was derived from: if another
+    /// update comes in while the patch is applied, the patch fails instead of overwriting
+    /// it, and can be applied again to the new configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the patch cannot be applied, `validate` rejects the
+    /// result, or the configuration was updated concurrently. The stored configuration
+    /// is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(vec![1_u16, 2]).unwrap();
+    ///
+    /// let result = registry.apply_patch_with(&handle, &json!([{ "op": "remove", "path": "/0" }]), |ports| {
+    ///     if ports.len() < 2 { Err("at least two ports required".to_string()) } else { Ok(()) }
+    /// });
+    /// assert!(result.is_err());
+    /// assert_eq!(*registry.read(&handle).unwrap(), vec![1, 2]);
+    /// ```
+    pub fn apply_patch_with<T, F>(
+        &self,
+        handle: &ConfigHandle<T>,
+        patch: &Value,
+        validate: F,
+    ) -> Result<(), String>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce(&T) -> Result<(), String>,
+    {
+        // Generations start at 1, so reading newer than 0 always returns the data
+        let Some((current, generation)) = self.read_if_newer(handle, 0)? else {
+            return Err(patch_error(handle.id(), "Configuration has no generation"));
+        };
+        let mut document = serde_json::to_value(&*current).map_err(|e| {
+            patch_error(
+                handle.id(),
+                &format!("Failed to serialize configuration: {e}"),
+            )
+        })?;
+
+        if let Value::Array(operations) = patch {
+            apply_json_patch(&mut document, operations)
+                .map_err(|e| patch_error(handle.id(), &e))?;
+        } else {
+            apply_merge_patch(&mut document, patch);
+        }
+
+        let patched: T = serde_json::from_value(document).map_err(|e| {
+            patch_error(
+                handle.id(),
+                &format!("Patched configuration is invalid: {e}"),
+            )
+        })?;
+        validate(&patched)
+            .map_err(|e| patch_error(handle.id(), &format!("Validation failed: {e}")))?;
+
+        self.update_if_generation(handle, patched, generation)
+    }
+}
+
+fn patch_error(handle_id: u64, message: &str) -> String {
+    error!(target: "superconfig.patch", "Patch of handle {handle_id} failed: {message}");
+    format!("superconfig.patch: Patch of handle {handle_id} failed: {message}")
+}
+
+/// Apply an RFC 6902 JSON Patch to `document`, leaving it unchanged on error
+///
+/// # Errors
+///
+/// Returns error message naming the first operation that is malformed or fails.
+pub fn apply_json_patch(document: &mut Value, operations: &[Value]) -> Result<(), String> {
+    let mut patched = document.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        apply_operation(&mut patched, operation)
+            .map_err(|e| format!("operation {index} failed: {e}"))?;
+    }
+    *document = patched;
+    Ok(())
+}
+
+/// Apply an RFC 7386 JSON Merge Patch to `document`
+///
+/// Objects are merged recursively, `null` removes a key, and any other value replaces
+/// the target.
+pub fn apply_merge_patch(document: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *document = patch.clone();
+        return;
+    };
+    if !document.is_object() {
+        *document = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(target) = document {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                apply_merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn apply_operation(document: &mut Value, operation: &Value) -> Result<(), String> {
+    let op = member_str(operation, "op")?;
+    let path = parse_pointer(member_str(operation, "path")?)?;
+
+    match op {
+        "add" => add(document, &path, member(operation, "value")?.clone()),
+        "remove" => remove(document, &path).map(drop),
+        "replace" => {
+            let value = member(operation, "value")?.clone();
+            *resolve_mut(document, &path)? = value;
+            Ok(())
+        }
+        "move" => {
+            let from = parse_pointer(member_str(operation, "from")?)?;
+            if path.len() > from.len() && path[..from.len()] == from[..] {
+                return Err("cannot move a value into one of its children".to_string());
+            }
+            let value = remove(document, &from)?;
+            add(document, &path, value)
+        }
+        "copy" => {
+            let from = parse_pointer(member_str(operation, "from")?)?;
+            let value = resolve(document, &from)?.clone();
+            add(document, &path, value)
+        }
+        "test" => {
+            if resolve(document, &path)? == member(operation, "value")? {
+                Ok(())
+            } else {
+                Err(format!("test failed at {}", to_pointer(&path)))
+            }
+        }
+        other => Err(format!("unknown op `{other}`")),
+    }
+}
+
+fn member<'a>(operation: &'a Value, name: &str) -> Result<&'a Value, String> {
+    operation
+        .get(name)
+        .ok_or_else(|| format!("missing `{name}` member"))
+}
+
+fn member_str<'a>(operation: &'a Value, name: &str) -> Result<&'a str, String> {
+    member(operation, name)?
+        .as_str()
+        .ok_or_else(|| format!("`{name}` must be a string"))
+}
+
+/// Parse an RFC 6901 JSON Pointer into unescaped reference tokens
+pub(crate) fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(tokens) = pointer.strip_prefix('/') else {
+        return Err(format!("invalid JSON pointer `{pointer}`"));
+    };
+    Ok(tokens
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn to_pointer(path: &[String]) -> String {
+    path.iter().fold(String::new(), |mut pointer, token| {
+        pointer.push('/');
+        pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+        pointer
+    })
+}
+
+fn array_index(token: &str, len: usize, allow_end: bool) -> Result<usize, String> {
+    let valid = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    let index = if valid { token.parse().ok() } else { None };
+    match index {
+        Some(index) if index < len || (allow_end && index == len) => Ok(index),
+        _ => Err(format!("invalid array index `{token}`")),
+    }
+}
+
+pub(crate) fn resolve<'a>(document: &'a Value, path: &[String]) -> Result<&'a Value, String> {
+    path.iter().try_fold(document, |value, token| {
+        let child = match value {
+            Value::Object(map) => map.get(token),
+            Value::Array(items) => array_index(token, items.len(), false)
+                .ok()
+                .and_then(|index| items.get(index)),
+            _ => None,
+        };
+        child.ok_or_else(|| format!("path {} does not exist", to_pointer(path)))
+    })
+}
+
+fn resolve_mut<'a>(document: &'a mut Value, path: &[String]) -> Result<&'a mut Value, String> {
+    let missing = || format!("path {} does not exist", to_pointer(path));
+    path.iter().try_fold(document, |value, token| match value {
+        Value::Object(map) => map.get_mut(token).ok_or_else(missing),
+        Value::Array(items) => {
+            let index = array_index(token, items.len(), false).map_err(|_| missing())?;
+            Ok(&mut items[index])
+        }
+        _ => Err(missing()),
+    })
+}
+
+fn add(document: &mut Value, path: &[String], value: Value) -> Result<(), String> {
+    let Some((last, parent)) = path.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+    match resolve_mut(document, parent)? {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            let index = if last == "-" {
+                items.len()
+            } else {
+                array_index(last, items.len(), true)?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(format!("cannot add to {}", to_pointer(path))),
+    }
+}
+
+fn remove(document: &mut Value, path: &[String]) -> Result<Value, String> {
+    let Some((last, parent)) = path.split_last() else {
+        return Err("cannot remove the whole document".to_string());
+    };
+    let missing = || format!("path {} does not exist", to_pointer(path));
+    match resolve_mut(document, parent)? {
+        Value::Object(map) => map.remove(last).ok_or_else(missing),
+        Value::Array(items) => {
+            let index = array_index(last, items.len(), false).map_err(|_| missing())?;
+            Ok(items.remove(index))
+        }
+        _ => Err(missing()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct TestConfig {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        features: Vec<String>,
+    }
+
+    fn patched(mut document: Value, patch: &Value) -> Result<Value, String> {
+        apply_json_patch(&mut document, patch.as_array().unwrap())?;
+        Ok(document)
+    }
+
+    #[test]
+    fn test_json_patch_operations() {
+        let document = json!({ "a": { "b": [1, 2] }, "c": "x", "m~n": 1, "s/t": 2 });
+
+        let result = patched(
+            document,
+            &json!([
+                { "op": "add", "path": "/a/b/1", "value": 9 },
+                { "op": "add", "path": "/a/b/-", "value": 3 },
+                { "op": "replace", "path": "/c", "value": "y" },
+                { "op": "move", "from": "/m~0n", "path": "/moved" },
+                { "op": "copy", "from": "/s~1t", "path": "/copied" },
+                { "op": "remove", "path": "/s~1t" },
+                { "op": "test", "path": "/a/b", "value": [1, 9, 2, 3] }
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            json!({ "a": { "b": [1, 9, 2, 3] }, "c": "y", "moved": 1, "copied": 2 })
+        );
+    }
+
+    #[test]
+    fn test_json_patch_is_atomic() {
+        let mut document = json!({ "a": 1 });
+        let result = apply_json_patch(
+            &mut document,
+            json!([
+                { "op": "replace", "path": "/a", "value": 2 },
+                { "op": "test", "path": "/a", "value": 3 }
+            ])
+            .as_array()
+            .unwrap(),
+        );
+
+        assert!(result.unwrap_err().contains("operation 1 failed"));
+        assert_eq!(document, json!({ "a": 1 }));
+    }
+
+    #[test]
+    fn test_json_patch_errors() {
+        let document = json!({ "a": [1], "b": { "c": 1 } });
+        let error = |patch: Value| patched(document.clone(), &patch).unwrap_err();
+
+        assert!(error(json!([{ "op": "replace", "path": "/x", "value": 1 }])).contains("/x"));
+        assert!(error(json!([{ "op": "add", "path": "/a/01", "value": 1 }])).contains("01"));
+        assert!(error(json!([{ "op": "remove", "path": "/a/1" }])).contains("does not exist"));
+        assert!(
+            error(json!([{ "op": "move", "from": "/b", "path": "/b/d" }])).contains("children")
+        );
+        assert!(error(json!([{ "op": "frobnicate", "path": "/a" }])).contains("unknown op"));
+        assert!(error(json!([{ "op": "add", "path": "a", "value": 1 }])).contains("pointer"));
+        assert!(error(json!([{ "op": "add", "path": "/a" }])).contains("`value`"));
+    }
+
+    #[test]
+    fn test_merge_patch_rfc_example() {
+        let mut document = json!({
+            "title": "Goodbye!",
+            "author": { "givenName": "John", "familyName": "Doe" },
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+        apply_merge_patch(
+            &mut document,
+            &json!({
+                "title": "Hello!",
+                "phoneNumber": "+01-123-456-7890",
+                "author": { "familyName": null },
+                "tags": ["example"]
+            }),
+        );
+
+        assert_eq!(
+            document,
+            json!({
+                "title": "Hello!",
+                "author": { "givenName": "John" },
+                "tags": ["example"],
+                "content": "This will be unchanged",
+                "phoneNumber": "+01-123-456-7890"
+            })
+        );
+    }
+
+    #[test]
+    fn test_registry_apply_patch() {
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create(TestConfig {
+                host: "localhost".to_string(),
+                port: 80,
+                features: vec!["auth".to_string()],
+            })
+            .unwrap();
+
+        registry
+            .apply_patch(
+                &handle,
+                &json!([{ "op": "add", "path": "/features/-", "value": "metrics" }]),
+            )
+            .unwrap();
+        registry
+            .apply_patch(&handle, &json!({ "port": 8080 }))
+            .unwrap();
+
+        let config = registry.read(&handle).unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.features, vec!["auth", "metrics"]);
+        assert_eq!(registry.stats().total_updates, 2);
+    }
+
+    #[test]
+    fn test_rejected_patches_keep_stored_value() {
+        let registry = ConfigRegistry::new();
+        let original = TestConfig {
+            host: "localhost".to_string(),
+            port: 80,
+            features: Vec::new(),
+        };
+        let handle = registry.create(original.clone()).unwrap();
+
+        // Type mismatch after patching
+        let error = registry
+            .apply_patch(&handle, &json!({ "port": "not a number" }))
+            .unwrap_err();
+        assert!(error.contains("Patched configuration is invalid"));
+
+        // Validation hook
+        let error = registry
+            .apply_patch_with(&handle, &json!({ "port": 0 }), |config| {
+                if config.port == 0 {
+                    Err("port must be non-zero".to_string())
+                } else {
+                    Ok(())
+                }
+            })
+            .unwrap_err();
+        assert!(error.contains("port must be non-zero"));
+
+        assert_eq!(*registry.read(&handle).unwrap(), original);
+        assert_eq!(registry.stats().total_updates, 0);
+    }
+
+    #[test]
+    fn test_concurrent_update_fails_the_patch() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(vec![1_u16]).unwrap();
+
+        // An update lands between reading the configuration and swapping the patch in
+        let error = registry
+            .apply_patch_with(
+                &handle,
+                &json!([{ "op": "add", "path": "/-", "value": 2 }]),
+                |_| registry.update(&handle, vec![10]),
+            )
+            .unwrap_err();
+        assert!(error.contains("updated concurrently"));
+        assert_eq!(*registry.read(&handle).unwrap(), vec![10]);
+
+        registry
+            .apply_patch(&handle, &json!([{ "op": "add", "path": "/-", "value": 2 }]))
+            .unwrap();
+        assert_eq!(*registry.read(&handle).unwrap(), vec![10, 2]);
+    }
+}
@@ -0,0 +1,262 @@
+//! Key-path access to single values of stored configuration
+//!
+//! [`ConfigRegistry::get_path`] reads one value of an entry, such as a port, without
+//! reading the whole configuration: the entry is serialized to a JSON tree once, the
+//! tree is cached until the entry is updated, and only the value at the path is
+//! deserialized. [`ConfigRegistry::set_path`] changes one value and stores the result
+//! like [`update`](ConfigRegistry::update).
+//!
+//! Paths are dotted keys, with numbers indexing arrays (`database.port`,
+//! `servers.0.host`), or JSON Pointers starting with `/` for keys containing dots
+//! (`/labels/app.kubernetes.io~1name`). The empty path is the whole configuration.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use superconfig::ConfigRegistry;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     database: Database,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry
+//!     .create(Config { database: Database { host: "localhost".into(), port: 5432 } })
+//!     .unwrap();
+//!
+//! assert_eq!(registry.get_path::<u16>(&handle, "database.port").unwrap(), 5432);
+//! registry.set_path(&handle, "database.host", "db.internal").unwrap();
+//! assert_eq!(registry.read(&handle).unwrap().database.host, "db.internal");
+//! ```
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use super::{
+    handle::ConfigHandle,
+    patch::{parse_pointer, resolve},
+    registry::ConfigRegistry,
+};
+use logffi::error;
+
+impl ConfigRegistry {
+    /// Read the value at `path` in the configuration of `handle` as a `V`
+    ///
+    /// Counts as a read. See the [module documentation](self) for the path syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist, the configuration can't be
+    /// serialized, the path doesn't exist, or its value isn't a valid `V`.
+    pub fn get_path<V>(
+        &self,
+        handle: &ConfigHandle<impl Serialize + 'static>,
+        path: &str,
+    ) -> Result<V, String>
+    where
+        V: DeserializeOwned,
+    {
+        let tree = self.tree(handle)?;
+        let value =
+            resolve(&tree, &parse_path(path)?).map_err(|e| path_error(handle.id(), path, &e))?;
+        V::deserialize(value).map_err(|e| path_error(handle.id(), path, &e.to_string()))
+    }
+
+    /// Set the value at `path` in the configuration of `handle` to `value`
+    ///
+    /// Object keys are added if missing, but their parents and array items must exist.
+    /// The changed tree is deserialized back into `T` and stored with
+    /// [`update`](Self::update), so watchers and subscribers are notified as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist, the path's parent doesn't
+    /// exist, or the changed configuration isn't a valid `T`. The stored configuration
+    /// is left unchanged.
+    pub fn set_path<T>(
+        &self,
+        handle: &ConfigHandle<T>,
+        path: &str,
+        value: impl Serialize,
+    ) -> Result<(), String>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        let value = serde_json::to_value(value)
+            .map_err(|e| path_error(handle.id(), path, &format!("invalid value: {e}")))?;
+        let mut tree = Value::clone(&*self.tree(handle)?);
+        set(&mut tree, &parse_path(path)?, value).map_err(|e| path_error(handle.id(), path, &e))?;
+        let data = T::deserialize(tree).map_err(|e| {
+            path_error(
+                handle.id(),
+                path,
+                &format!("changed configuration is invalid: {e}"),
+            )
+        })?;
+        self.update(handle, data)
+    }
+}
+
+/// Reference tokens of a dotted path or JSON Pointer
+pub(crate) fn parse_path(path: &str) -> Result<Vec<String>, String> {
+    if path.is_empty() || path.starts_with('/') {
+        return parse_pointer(path).map_err(|e| format!("superconfig.path: {e}"));
+    }
+    let tokens: Vec<String> = path.split('.').map(str::to_string).collect();
+    if tokens.iter().any(String::is_empty) {
+        error!(target: "superconfig.path", "Invalid path `{path}`");
+        return Err(format!("superconfig.path: Invalid path `{path}`"));
+    }
+    Ok(tokens)
+}
+
+/// Replace the value at `path`, adding it if its parent is an object without it
+fn set(tree: &mut Value, path: &[String], value: Value) -> Result<(), String> {
+    let Some((last, parent)) = path.split_last() else {
+        *tree = value;
+        return Ok(());
+    };
+    let mut node = tree;
+    for token in parent {
+        node = match node {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(items) => token.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("`{token}` does not exist"))?;
+    }
+    match node {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            let item = last
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get_mut(i))
+                .ok_or_else(|| format!("array index `{last}` does not exist"))?;
+            *item = value;
+            Ok(())
+        }
+        _ => Err(format!("parent of `{last}` is not an object or array")),
+    }
+}
+
+fn path_error(handle_id: u64, path: &str, message: &str) -> String {
+    error!(target: "superconfig.path", "Path `{path}` of handle {handle_id}: {message}");
+    format!("superconfig.path: Path `{path}` of handle {handle_id}: {message}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+        tags: Vec<String>,
+    }
+
+    fn server() -> Server {
+        Server {
+            host: "localhost".to_string(),
+            port: 80,
+            tags: vec!["a".to_string(), "b".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(parse_path("a.0.b").unwrap(), ["a", "0", "b"]);
+        assert_eq!(parse_path("/a.b/c~1d").unwrap(), ["a.b", "c/d"]);
+        assert!(parse_path("").unwrap().is_empty());
+        assert!(parse_path("a..b").is_err());
+        assert!(parse_path("a.").is_err());
+    }
+
+    #[test]
+    fn test_get_path_reads_values() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(server()).unwrap();
+        assert_eq!(registry.get_path::<u16>(&handle, "port").unwrap(), 80);
+        assert_eq!(registry.get_path::<String>(&handle, "tags.1").unwrap(), "b");
+        assert_eq!(
+            registry.get_path::<Value>(&handle, "").unwrap(),
+            json!({"host": "localhost", "port": 80, "tags": ["a", "b"]})
+        );
+
+        let error = registry.get_path::<u16>(&handle, "tags.2").unwrap_err();
+        assert!(error.starts_with("superconfig.path: Path `tags.2` of handle"));
+        assert!(registry.get_path::<u16>(&handle, "host").is_err());
+        // Failed paths still read the entry
+        assert_eq!(registry.stats().total_reads, 5);
+    }
+
+    #[test]
+    fn test_tree_cache_follows_updates() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(server()).unwrap();
+        let first = registry.tree(&handle).unwrap();
+        assert!(Arc::ptr_eq(&first, &registry.tree(&handle).unwrap()));
+
+        registry
+            .update(
+                &handle,
+                Server {
+                    port: 81,
+                    ..server()
+                },
+            )
+            .unwrap();
+        assert_eq!(registry.get_path::<u16>(&handle, "port").unwrap(), 81);
+    }
+
+    #[test]
+    fn test_set_path_updates_entry() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(server()).unwrap();
+        registry.set_path(&handle, "port", 8080).unwrap();
+        registry.set_path(&handle, "tags.0", "z").unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "localhost".to_string(),
+                port: 8080,
+                tags: vec!["z".to_string(), "b".to_string()],
+            }
+        );
+        assert_eq!(registry.generation(&handle).unwrap(), 3);
+
+        assert!(registry.set_path(&handle, "tags.5", "x").is_err());
+        assert!(registry.set_path(&handle, "port.x", 1).is_err());
+        let error = registry.set_path(&handle, "port", "high").unwrap_err();
+        assert!(error.contains("changed configuration is invalid"));
+        assert_eq!(registry.read(&handle).unwrap().port, 8080);
+    }
+
+    #[test]
+    fn test_set_path_adds_keys_and_reads_through_children() {
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create(BTreeMap::from([("a".to_string(), 1)]))
+            .unwrap();
+        registry.set_path(&handle, "b", 2).unwrap();
+
+        let child = registry.child();
+        assert_eq!(child.get_path::<u8>(&handle, "b").unwrap(), 2);
+        child.set_path(&handle, "a", 10).unwrap();
+        assert_eq!(child.get_path::<u8>(&handle, "a").unwrap(), 10);
+        assert_eq!(registry.get_path::<u8>(&handle, "a").unwrap(), 1);
+    }
+}
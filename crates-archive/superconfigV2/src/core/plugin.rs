@@ -0,0 +1,520 @@
+//! Configuration sources loaded from dynamic libraries
+//!
+//! Source plugins let operators add configuration sources (Consul, Vault, in-house
+//! services) to an application without recompiling it: the plugin is a shared library
+//! exposing a C function table, and [`ConfigRegistry::load_source_plugin`] turns it
+//! into a [`SourcePlugin`], an [`AsyncConfigSource`] producing a [`serde_json::Value`].
+//!
+//! ## C ABI
+//!
+//! The interface is plain C so plugins can be written in any language. Configuration
+//! crosses the boundary as NUL-terminated UTF-8 JSON text:
+//!
+//! ```c
+//! #define SUPERCONFIG_SOURCE_ABI_VERSION 1
+//!
+//! typedef struct {
+//!     uint32_t abi_version;                         /* SUPERCONFIG_SOURCE_ABI_VERSION */
+//!     void *context;                                /* plugin state, passed to every call */
+//!     const char *(*name)(void *context);           /* source name, valid until destroy */
+//!     int32_t (*load)(void *context, char **output);
+//!     void (*free_string)(void *context, char *string);
+//!     void (*destroy)(void *context);               /* optional, may be NULL */
+//! } superconfig_source_plugin_t;
+//!
+//! /* Exported by the plugin; options_json is NULL when no options were given */
+//! const superconfig_source_plugin_t *superconfig_source_plugin(const char *options_json);
+//! ```
+//!
+//! - The entry point returns `NULL` when the plugin can't be created. The table is
+//!   copied right away, so it may live in static or temporary storage.
+//! - `load` returns `0` and stores the configuration JSON in `*output` on success. Any
+//!   other value is a failure, with an optional error message in `*output`. Strings
+//!   stored in `*output` are released with `free_string`.
+//! - `load` may be called from several threads at once.
+//! - `destroy` is called once, when the last clone of the [`SourcePlugin`] is dropped.
+//!
+//! Libraries stay loaded for the life of the process: unloading code that may still
+//! own thread-local destructors or running threads is unsound.
+//!
+//! Loading plugins requires the `plugins` feature and a Unix platform (`dlopen`).
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use superconfig::{ConfigRegistry, ResilientSource, SourcePolicy};
+//!
+//! # tokio_test::block_on(async {
+//! let registry = ConfigRegistry::new();
+//! let plugin = registry.load_source_plugin("libconsul_source.so").unwrap();
+//! let source = ResilientSource::new(plugin, SourcePolicy::default());
+//! let handle = registry.create_from_source(&source).await.unwrap();
+//! # });
+//! ```
+
+use std::{
+    ffi::{CStr, CString, c_char, c_void},
+    path::Path,
+    ptr,
+    sync::Arc,
+};
+
+use super::{circuit::SourceHealth, registry::ConfigRegistry, source::AsyncConfigSource};
+use logffi::{error, info};
+use parking_lot::Mutex;
+
+/// Version of the C function table understood by this crate
+pub const SOURCE_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Symbol of the entry point every source plugin exports
+pub const SOURCE_PLUGIN_ENTRY: &str = "superconfig_source_plugin";
+
+/// Signature of the plugin entry point ([`SOURCE_PLUGIN_ENTRY`])
+pub type SourcePluginEntry =
+    unsafe extern "C" fn(options_json: *const c_char) -> *const SourcePluginTable;
+
+/// The C function table of a source plugin (`superconfig_source_plugin_t`)
+///
+/// Function pointers are `Option`s so a table with missing functions is rejected
+/// instead of called.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SourcePluginTable {
+    /// Must be [`SOURCE_PLUGIN_ABI_VERSION`]
+    pub abi_version: u32,
+    /// Plugin state, passed to every function
+    pub context: *mut c_void,
+    /// Returns the source name, valid until `destroy`
+    pub name: Option<unsafe extern "C" fn(context: *mut c_void) -> *const c_char>,
+    /// Loads the configuration as JSON into `*output`, returning `0` on success
+    pub load: Option<unsafe extern "C" fn(context: *mut c_void, output: *mut *mut c_char) -> i32>,
+    /// Releases a string returned through `load`
+    pub free_string: Option<unsafe extern "C" fn(context: *mut c_void, string: *mut c_char)>,
+    /// Releases the plugin state
+    pub destroy: Option<unsafe extern "C" fn(context: *mut c_void)>,
+}
+
+/// The validated function table and load counters shared by clones of a plugin
+struct PluginInstance {
+    table: SourcePluginTable,
+    load: unsafe extern "C" fn(context: *mut c_void, output: *mut *mut c_char) -> i32,
+    free_string: unsafe extern "C" fn(context: *mut c_void, string: *mut c_char),
+    name: String,
+    health: Mutex<SourceHealth>,
+}
+
+// The C ABI requires `load` to be callable from any thread, and the context is only
+// released by `destroy`, once no clone is left
+unsafe impl Send for PluginInstance {}
+unsafe impl Sync for PluginInstance {}
+
+impl Drop for PluginInstance {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.table.destroy {
+            // SAFETY: called once, after the last use of the context
+            unsafe { destroy(self.table.context) };
+        }
+    }
+}
+
+/// A configuration source implemented by a dynamically loaded plugin
+///
+/// Created with [`ConfigRegistry::load_source_plugin`]. Clones share the plugin
+/// instance. `load` calls the plugin synchronously: wrap a clone in a
+/// [`BlockingSource`](super::BlockingSource) to keep slow plugins off async worker
+/// threads. Every plugin reports its load counters in registry statistics.
+#[derive(Clone)]
+pub struct SourcePlugin {
+    instance: Arc<PluginInstance>,
+}
+
+impl std::fmt::Debug for SourcePlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SourcePlugin")
+            .field("name", &self.instance.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SourcePlugin {
+    /// Load the plugin library at `path` and create a source from it
+    ///
+    /// `options` are passed to the plugin's entry point as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the library can't be loaded, doesn't export
+    /// [`SOURCE_PLUGIN_ENTRY`], or returns an invalid function table.
+    pub fn open(
+        path: impl AsRef<Path>,
+        options: Option<&serde_json::Value>,
+    ) -> Result<Self, String> {
+        let path = path.as_ref();
+        let options = options
+            .map(|options| CString::new(options.to_string()))
+            .transpose()
+            .map_err(|err| format!("superconfig.plugin: Invalid plugin options: {err}"))?;
+        let entry = library::entry_point(path)?;
+        let options_ptr = options
+            .as_ref()
+            .map_or(ptr::null(), |options| options.as_ptr());
+
+        // SAFETY: the symbol is declared by the plugin ABI with this signature
+        let table = unsafe { entry(options_ptr) };
+        if table.is_null() {
+            return Err(format!(
+                "superconfig.plugin: Plugin {} failed to initialize",
+                path.display()
+            ));
+        }
+        // SAFETY: a non-null table must point to a valid `superconfig_source_plugin_t`
+        unsafe { Self::from_table(*table) }
+            .map_err(|err| format!("{err} (plugin {})", path.display()))
+    }
+
+    /// Create a source from a plugin function table, such as one linked statically
+    ///
+    /// On error the table's `destroy` function is not called.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the ABI version doesn't match, a required function is
+    /// missing, or the name isn't valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The table must follow the C ABI described in the [module documentation](self):
+    /// its functions must be safe to call with its context, from any thread, until
+    /// `destroy` is called.
+    pub unsafe fn from_table(table: SourcePluginTable) -> Result<Self, String> {
+        if table.abi_version != SOURCE_PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "superconfig.plugin: Unsupported plugin ABI version {} (expected {SOURCE_PLUGIN_ABI_VERSION})",
+                table.abi_version
+            ));
+        }
+        let (Some(name_fn), Some(load), Some(free_string)) =
+            (table.name, table.load, table.free_string)
+        else {
+            return Err("superconfig.plugin: Plugin function table is incomplete".to_string());
+        };
+
+        // SAFETY: the ABI requires `name` to return a NUL-terminated string or NULL
+        let name = unsafe { name_fn(table.context) };
+        if name.is_null() {
+            return Err("superconfig.plugin: Plugin has no name".to_string());
+        }
+        // SAFETY: checked for NULL above
+        let name = unsafe { CStr::from_ptr(name) }
+            .to_str()
+            .map_err(|err| format!("superconfig.plugin: Plugin name is not UTF-8: {err}"))?
+            .to_string();
+
+        let health = Mutex::new(SourceHealth {
+            name: name.clone(),
+            ..SourceHealth::default()
+        });
+        Ok(Self {
+            instance: Arc::new(PluginInstance {
+                table,
+                load,
+                free_string,
+                name,
+                health,
+            }),
+        })
+    }
+
+    /// Name reported by the plugin
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.instance.name
+    }
+
+    /// Call the plugin's `load` function and parse the JSON it returns
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the plugin reports a failure or returns invalid JSON.
+    pub fn load_json(&self) -> Result<serde_json::Value, String> {
+        let instance = &self.instance;
+        let mut output: *mut c_char = ptr::null_mut();
+        // SAFETY: the ABI allows concurrent `load` calls until `destroy`
+        let status = unsafe { (instance.load)(instance.table.context, &raw mut output) };
+
+        let text = if output.is_null() {
+            None
+        } else {
+            // SAFETY: the plugin stored a NUL-terminated string, released right after
+            let text = unsafe { CStr::from_ptr(output) }
+                .to_string_lossy()
+                .into_owned();
+            // SAFETY: `output` was returned by this plugin's `load` and is freed once
+            unsafe { (instance.free_string)(instance.table.context, output) };
+            Some(text)
+        };
+
+        let result = match (status, text) {
+            (0, Some(text)) => serde_json::from_str(&text)
+                .map_err(|err| format!("plugin returned invalid JSON: {err}")),
+            (0, None) => Err("plugin returned no configuration".to_string()),
+            (status, Some(message)) => Err(format!("{message} (status {status})")),
+            (status, None) => Err(format!("plugin failed with status {status}")),
+        };
+        self.record(result.as_ref().err());
+        result
+    }
+
+    /// Update the load counters reported in registry statistics
+    fn record(&self, error: Option<&String>) {
+        let mut health = self.instance.health.lock();
+        match error {
+            None => {
+                health.consecutive_failures = 0;
+                health.total_successes = health.total_successes.saturating_add(1);
+            }
+            Some(error) => {
+                health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+                health.total_failures = health.total_failures.saturating_add(1);
+                health.last_error = Some(error.clone());
+            }
+        }
+    }
+}
+
+impl AsyncConfigSource for SourcePlugin {
+    type Output = serde_json::Value;
+
+    fn name(&self) -> &str {
+        &self.instance.name
+    }
+
+    async fn load(&self) -> Result<serde_json::Value, String> {
+        self.load_json()
+    }
+
+    fn health(&self) -> Option<SourceHealth> {
+        Some(self.instance.health.lock().clone())
+    }
+}
+
+impl ConfigRegistry {
+    /// Load a source plugin from the shared library at `path`
+    ///
+    /// The plugin shows up in [`RegistryStats::sources`](super::RegistryStats::sources)
+    /// right away. Load its configuration with
+    /// [`create_from_source`](Self::create_from_source).
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the library can't be loaded or isn't a valid plugin.
+    pub fn load_source_plugin(&self, path: impl AsRef<Path>) -> Result<SourcePlugin, String> {
+        self.register_plugin(path.as_ref(), None)
+    }
+
+    /// Load a source plugin, passing `options` to its entry point as JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the library can't be loaded or isn't a valid plugin.
+    pub fn load_source_plugin_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        options: &serde_json::Value,
+    ) -> Result<SourcePlugin, String> {
+        self.register_plugin(path.as_ref(), Some(options))
+    }
+
+    fn register_plugin(
+        &self,
+        path: &Path,
+        options: Option<&serde_json::Value>,
+    ) -> Result<SourcePlugin, String> {
+        let plugin = SourcePlugin::open(path, options).inspect_err(|err| {
+            error!(target: "superconfig.plugin", "{err}");
+        })?;
+        info!(target: "superconfig.plugin", "Loaded source plugin {} from {}", plugin.name(), path.display());
+        if let Some(health) = plugin.health() {
+            self.record_source_health(health);
+        }
+        Ok(plugin)
+    }
+}
+
+#[cfg(all(unix, feature = "plugins"))]
+mod library {
+    use super::SourcePluginEntry;
+    use std::{
+        ffi::{CStr, CString},
+        os::unix::ffi::OsStrExt,
+        path::Path,
+    };
+
+    /// Load the library at `path` and resolve its entry point
+    pub(super) fn entry_point(path: &Path) -> Result<SourcePluginEntry, String> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|err| format!("superconfig.plugin: Invalid plugin path: {err}"))?;
+        let symbol = CString::new(super::SOURCE_PLUGIN_ENTRY).expect("symbol has no NUL byte");
+
+        // SAFETY: both strings are NUL-terminated; the handle is never closed
+        unsafe {
+            let handle = libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+            if handle.is_null() {
+                return Err(format!(
+                    "superconfig.plugin: Failed to load plugin {}: {}",
+                    path.display(),
+                    last_error()
+                ));
+            }
+            let entry = libc::dlsym(handle, symbol.as_ptr());
+            if entry.is_null() {
+                return Err(format!(
+                    "superconfig.plugin: {} does not export {}",
+                    path.display(),
+                    super::SOURCE_PLUGIN_ENTRY
+                ));
+            }
+            Ok(std::mem::transmute::<*mut libc::c_void, SourcePluginEntry>(
+                entry,
+            ))
+        }
+    }
+
+    /// The `dlerror` message of the last failed call
+    unsafe fn last_error() -> String {
+        // SAFETY: `dlerror` returns NULL or a NUL-terminated string
+        let message = unsafe { libc::dlerror() };
+        if message.is_null() {
+            "unknown error".to_string()
+        } else {
+            // SAFETY: checked for NULL above
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+}
+
+#[cfg(not(all(unix, feature = "plugins")))]
+mod library {
+    use super::SourcePluginEntry;
+    use std::path::Path;
+
+    /// Plugins can't be loaded in this build
+    pub(super) fn entry_point(path: &Path) -> Result<SourcePluginEntry, String> {
+        Err(format!(
+            "superconfig.plugin: Can't load plugin {}: loading plugins requires the `plugins` feature on a Unix platform",
+            path.display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static DESTROYED: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn name(_context: *mut c_void) -> *const c_char {
+        c"static-plugin".as_ptr()
+    }
+
+    unsafe extern "C" fn load(context: *mut c_void, output: *mut *mut c_char) -> i32 {
+        // The context counts calls; every other load fails
+        let calls = unsafe { &*context.cast::<AtomicU32>() };
+        let (status, text) = if calls.fetch_add(1, Ordering::Relaxed) % 2 == 0 {
+            (0, r#"{"host":"localhost","port":8080}"#)
+        } else {
+            (3, "backend unreachable")
+        };
+        unsafe { *output = CString::new(text).unwrap().into_raw() };
+        status
+    }
+
+    unsafe extern "C" fn free_string(_context: *mut c_void, string: *mut c_char) {
+        drop(unsafe { CString::from_raw(string) });
+    }
+
+    unsafe extern "C" fn destroy(context: *mut c_void) {
+        unsafe { free_context(context) };
+        DESTROYED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    unsafe extern "C" fn free_context(context: *mut c_void) {
+        drop(unsafe { Box::from_raw(context.cast::<AtomicU32>()) });
+    }
+
+    fn table() -> SourcePluginTable {
+        SourcePluginTable {
+            abi_version: SOURCE_PLUGIN_ABI_VERSION,
+            context: Box::into_raw(Box::new(AtomicU32::new(0))).cast(),
+            name: Some(name),
+            load: Some(load),
+            free_string: Some(free_string),
+            destroy: Some(destroy),
+        }
+    }
+
+    #[test]
+    fn test_plugin_loads_json_and_reports_failures() {
+        tokio_test::block_on(async {
+            let plugin = unsafe { SourcePlugin::from_table(table()) }.unwrap();
+            assert_eq!(plugin.name(), "static-plugin");
+
+            let registry = ConfigRegistry::new();
+            let handle = registry.create_from_source(&plugin).await.unwrap();
+            assert_eq!(registry.read(&handle).unwrap()["port"], 8080);
+
+            let error = registry
+                .update_from_source(&handle, &plugin)
+                .await
+                .unwrap_err();
+            assert!(error.contains("backend unreachable (status 3)"));
+
+            let health = &registry.stats().sources[0];
+            assert_eq!(health.name, "static-plugin");
+            assert_eq!(health.total_successes, 1);
+            assert_eq!(health.total_failures, 1);
+
+            let before = DESTROYED.load(Ordering::Relaxed);
+            let clone = plugin.clone();
+            drop(plugin);
+            assert_eq!(DESTROYED.load(Ordering::Relaxed), before);
+            drop(clone);
+            assert_eq!(DESTROYED.load(Ordering::Relaxed), before + 1);
+        });
+    }
+
+    #[test]
+    fn test_invalid_tables_are_rejected() {
+        let mut wrong_version = table();
+        wrong_version.abi_version = 2;
+        let error = unsafe { SourcePlugin::from_table(wrong_version) }.unwrap_err();
+        assert!(error.contains("ABI version 2"));
+        unsafe { free_context(wrong_version.context) };
+
+        let mut incomplete = table();
+        incomplete.load = None;
+        assert!(unsafe { SourcePlugin::from_table(incomplete) }.is_err());
+        unsafe { free_context(incomplete.context) };
+    }
+
+    #[test]
+    fn test_missing_library_is_an_error() {
+        let registry = ConfigRegistry::new();
+        let error = registry
+            .load_source_plugin("/nonexistent/libmissing_source.so")
+            .unwrap_err();
+        assert!(error.starts_with("superconfig.plugin: "));
+        assert!(error.contains("libmissing_source.so"));
+        assert!(registry.stats().sources.is_empty());
+    }
+
+    #[cfg(all(target_os = "linux", feature = "plugins"))]
+    #[test]
+    fn test_library_without_entry_point_is_rejected() {
+        let error = SourcePlugin::open("libc.so.6", None).unwrap_err();
+        assert!(error.contains("does not export superconfig_source_plugin"));
+    }
+}
@@ -0,0 +1,372 @@
+//! Worker pool of registries created with the `THREAD_POOL` startup flag
+//!
+//! Registries created with [`startup::THREAD_POOL`](crate::config_flags::startup::THREAD_POOL)
+//! start a fixed set of worker threads, one per available CPU unless
+//! [`ConfigRegistry::custom_with_threads`] sets the count. Child registries share their
+//! parent's pool. The pool runs:
+//!
+//! - The parsing of each file loaded with
+//!   [`create_from_files`](ConfigRegistry::create_from_files), in parallel
+//! - Loaders started with [`load_in_background`](ConfigRegistry::load_in_background),
+//!   such as fetches from remote sources
+//! - The reload callbacks of [`watch`](crate::watch) file watchers
+//!
+//! Without the flag, files are parsed one after the other, background loads get a
+//! thread each, and reload callbacks run on the watcher's thread.
+//!
+//! Dropping the registry, and its children, shuts the pool down gracefully: the jobs
+//! already queued run to completion and the workers are joined.
+//!
+//! ```
+//! use superconfig::{ConfigRegistry, config_flags::startup};
+//!
+//! let registry = ConfigRegistry::custom_with_threads(startup::NO_FLAGS, 2);
+//! assert!(registry.startup_enabled(startup::THREAD_POOL));
+//! assert_eq!(registry.pool_threads(), 2);
+//!
+//! let load = registry.load_in_background(|| Ok(vec!["eu-west-1".to_string()]));
+//! let handle = load.wait().unwrap();
+//! assert_eq!(registry.read(&handle).unwrap()[0], "eu-west-1");
+//! ```
+
+use std::{
+    cell::Cell,
+    sync::{Arc, mpsc},
+    thread::{self, JoinHandle},
+};
+
+use super::{handle::ConfigHandle, registry::ConfigRegistry, time::Instant};
+use logffi::{debug, error};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+thread_local! {
+    /// Whether the current thread is a pool worker, which must not wait on the pool
+    static IN_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Fixed set of worker threads running queued jobs in order
+pub(crate) struct WorkerPool {
+    sender: Option<mpsc::Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// Start `threads` workers, or one per available CPU when `threads` is 0
+    pub(crate) fn new(threads: usize) -> Self {
+        let threads = if threads == 0 {
+            thread::available_parallelism().map_or(1, usize::from)
+        } else {
+            threads
+        };
+        let (sender, jobs) = mpsc::channel::<Job>();
+        let jobs = Arc::new(parking_lot::Mutex::new(jobs));
+        let workers = (0..threads)
+            .filter_map(|index| {
+                let jobs = Arc::clone(&jobs);
+                thread::Builder::new()
+                    .name(format!("superconfig-worker-{index}"))
+                    .spawn(move || {
+                        IN_WORKER.set(true);
+                        // The channel disconnects when the pool is dropped
+                        loop {
+                            let job = jobs.lock().recv();
+                            let Ok(job) = job else {
+                                break;
+                            };
+                            // A panicking job doesn't take its worker down with it
+                            if std::panic::catch_unwind(std::panic::AssertUnwindSafe(job)).is_err()
+                            {
+                                error!(target: "superconfig.pool", "Worker job panicked");
+                            }
+                        }
+                    })
+                    .inspect_err(|e| {
+                        error!(target: "superconfig.pool", "Cannot start worker thread: {e}");
+                    })
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        debug!(target: "superconfig.pool", "Started {} worker thread(s)", workers.len());
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Number of worker threads
+    pub(crate) const fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queue `job`, or run it on the current thread if no worker could be started
+    pub(crate) fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        let job: Job = Box::new(job);
+        let job = match &self.sender {
+            Some(sender) if !self.workers.is_empty() => match sender.send(job) {
+                Ok(()) => return,
+                Err(mpsc::SendError(job)) => job,
+            },
+            _ => job,
+        };
+        job();
+    }
+
+    /// Run `jobs` on the workers and return their results in order
+    ///
+    /// Runs them on the current thread when it's a worker itself, so jobs never wait on
+    /// a pool that is busy running them.
+    pub(crate) fn map<T, F>(&self, jobs: Vec<F>) -> Vec<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        if IN_WORKER.get() {
+            return jobs.into_iter().map(|job| job()).collect();
+        }
+        let count = jobs.len();
+        let (sender, results) = mpsc::channel();
+        for (index, job) in jobs.into_iter().enumerate() {
+            let sender = sender.clone();
+            self.execute(move || {
+                let _ = sender.send((index, job()));
+            });
+        }
+        drop(sender);
+
+        let mut ordered: Vec<Option<T>> = std::iter::repeat_with(|| None).take(count).collect();
+        for (index, result) in results {
+            ordered[index] = Some(result);
+        }
+        ordered
+            .into_iter()
+            .map(|result| result.expect("a pool job panicked"))
+            .collect()
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // Workers finish the queued jobs, then stop once the channel disconnects
+        drop(self.sender.take());
+        let current = thread::current().id();
+        for worker in self.workers.drain(..) {
+            // The last reference to the registry may be dropped by one of its jobs
+            if worker.thread().id() != current {
+                let _ = worker.join();
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerPool")
+            .field("threads", &self.workers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Configuration being loaded by [`ConfigRegistry::load_in_background`]
+#[must_use = "the load's result is only available through `wait`"]
+#[derive(Debug)]
+pub struct BackgroundLoad<T> {
+    result: mpsc::Receiver<Result<ConfigHandle<T>, String>>,
+}
+
+impl<T> BackgroundLoad<T> {
+    /// Wait for the load to finish and return the handle of the stored entry
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the loader failed or panicked, or the entry can't be
+    /// created.
+    pub fn wait(self) -> Result<ConfigHandle<T>, String> {
+        self.result.recv().unwrap_or_else(|_| {
+            error!(target: "superconfig.pool", "Background load panicked");
+            Err("superconfig.pool: Background load panicked".to_string())
+        })
+    }
+
+    /// The result if the load has finished, or `self` to wait on later
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` if the load is still running.
+    pub fn try_wait(self) -> Result<Result<ConfigHandle<T>, String>, Self> {
+        match self.result.try_recv() {
+            Ok(result) => Ok(result),
+            Err(mpsc::TryRecvError::Empty) => Err(self),
+            Err(mpsc::TryRecvError::Disconnected) => Ok(self.wait()),
+        }
+    }
+}
+
+impl ConfigRegistry {
+    /// Number of worker threads, or 0 without the `THREAD_POOL` startup flag
+    #[must_use]
+    pub fn pool_threads(&self) -> usize {
+        self.pool().map_or(0, WorkerPool::threads)
+    }
+
+    /// Run `loader` in the background and store the configuration it returns
+    ///
+    /// The loader runs on the worker pool with the `THREAD_POOL` startup flag, or on a
+    /// thread of its own otherwise, so slow loads such as remote fetches don't block
+    /// the caller. Failed loads are counted like other sources, under `"background"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let load = registry.load_in_background(|| Err::<u16, _>("unreachable".to_string()));
+    /// assert!(load.wait().unwrap_err().contains("unreachable"));
+    /// ```
+    pub fn load_in_background<T, F>(self: &Arc<Self>, loader: F) -> BackgroundLoad<T>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> Result<T, String> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let registry = Arc::clone(self);
+        let job = move || {
+            let started = Instant::now();
+            let result = loader();
+            registry.record_source_load("background", started.elapsed(), result.is_err());
+            let stored = result
+                .map_err(|e| {
+                    error!(target: "superconfig.pool", "Background load failed: {e}");
+                    format!("superconfig.pool: Background load failed: {e}")
+                })
+                .and_then(|data| registry.create(data));
+            let _ = sender.send(stored);
+        };
+        match self.pool() {
+            Some(pool) => pool.execute(job),
+            None => {
+                if let Err(e) = thread::Builder::new()
+                    .name("superconfig-load".to_string())
+                    .spawn(job)
+                {
+                    error!(target: "superconfig.pool", "Cannot start load thread: {e}");
+                }
+            }
+        }
+        BackgroundLoad { result: receiver }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_flags::startup;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_keeps_order_and_uses_workers() {
+        let pool = WorkerPool::new(3);
+        assert_eq!(pool.threads(), 3);
+
+        let jobs = (0..20_u64)
+            .map(|i| {
+                move || {
+                    thread::sleep(Duration::from_millis(20 - i));
+                    (i, thread::current().name().map(str::to_string))
+                }
+            })
+            .collect();
+        let results = pool.map(jobs);
+        assert_eq!(
+            results.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            (0..20).collect::<Vec<_>>()
+        );
+        assert!(results.iter().all(|(_, name)| {
+            name.as_deref()
+                .is_some_and(|name| name.starts_with("superconfig-worker-"))
+        }));
+    }
+
+    #[test]
+    fn test_drop_runs_queued_jobs() {
+        let done = Arc::new(AtomicUsize::new(0));
+        let pool = WorkerPool::new(1);
+        for _ in 0..10 {
+            let done = Arc::clone(&done);
+            pool.execute(move || {
+                thread::sleep(Duration::from_millis(2));
+                done.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        drop(pool);
+        assert_eq!(done.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn test_nested_map_on_a_single_worker() {
+        let registry = ConfigRegistry::custom_with_threads(startup::NO_FLAGS, 1);
+        let inner = Arc::clone(&registry);
+        let load = registry.load_in_background(move || {
+            let pool = inner.pool().expect("pool enabled");
+            Ok(pool.map(vec![|| 1, || 2]))
+        });
+        let handle = load.wait().unwrap();
+        assert_eq!(*registry.read(&handle).unwrap(), [1, 2]);
+    }
+
+    #[test]
+    fn test_background_load_without_pool() {
+        let registry = ConfigRegistry::new();
+        assert_eq!(registry.pool_threads(), 0);
+
+        let mut load = registry.load_in_background(|| {
+            thread::sleep(Duration::from_millis(10));
+            Ok(thread::current().name().map(str::to_string))
+        });
+        let handle = loop {
+            match load.try_wait() {
+                Ok(result) => break result.unwrap(),
+                Err(pending) => load = pending,
+            }
+        };
+        assert_eq!(
+            registry.read(&handle).unwrap().as_deref(),
+            Some("superconfig-load")
+        );
+        let loads = registry.stats().source_loads;
+        assert_eq!(
+            (loads[0].name.as_str(), loads[0].total_loads),
+            ("background", 1)
+        );
+    }
+
+    #[test]
+    fn test_files_parsed_on_pool_merge_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<_> = (0..4)
+            .map(|i| {
+                let path = dir.path().join(format!("layer{i}.json"));
+                std::fs::write(&path, format!(r#"{{"last": {i}, "layer{i}": true}}"#)).unwrap();
+                path
+            })
+            .collect();
+
+        let registry = ConfigRegistry::custom_with_threads(startup::NO_FLAGS, 2);
+        let handle = registry
+            .create_from_files::<serde_json::Value>(&paths)
+            .unwrap();
+        let tree = registry.read(&handle).unwrap();
+        assert_eq!(tree["last"], 3);
+        assert_eq!(tree.as_object().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_registry_dropped_by_its_own_job() {
+        let registry = ConfigRegistry::custom(startup::THREAD_POOL);
+        let load = registry.load_in_background(|| Ok(1_u8));
+        drop(registry);
+        assert!(load.wait().is_ok());
+    }
+}
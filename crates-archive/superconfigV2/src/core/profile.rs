@@ -0,0 +1,405 @@
+//! Profiles: per-environment configuration layered over defaults
+//!
+//! A [`ProfileSet`] holds a configuration's default values and, for each profile such as
+//! `staging` or `prod`, the values that override them. Entries created with
+//! [`ConfigRegistry::create_profiled`] hold the default values merged with the
+//! registry's active profile, and [`ConfigRegistry::select_profile`] switches every
+//! profiled entry to another profile at once. Profile names ignore case.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_json::json;
+//! use superconfig::{ConfigRegistry, ProfileSet};
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     pool_size: u32,
+//! }
+//!
+//! let profiles = ProfileSet::new(json!({"host": "localhost", "pool_size": 4}))
+//!     .with_profile("prod", json!({"host": "db.internal"}));
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry.create_profiled::<Database>(profiles).unwrap();
+//! assert_eq!(registry.read(&handle).unwrap().host, "localhost");
+//!
+//! registry.select_profile("prod").unwrap();
+//! let database = registry.read(&handle).unwrap();
+//! assert_eq!((database.host.as_str(), database.pool_size), ("db.internal", 4));
+//! ```
+
+use std::collections::BTreeMap;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use super::{
+    events::RegistryEvent,
+    handle::ConfigHandle,
+    patch::apply_merge_patch,
+    registry::{ConfigRegistry, HandleId},
+};
+use logffi::{debug, error};
+
+/// Name of the profile every registry starts with
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Default values of a configuration and the overrides of each profile
+///
+/// Overrides are merged over the defaults like a JSON Merge Patch: tables merge
+/// recursively, other values replace the default, and `null` removes a default key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileSet {
+    default: Value,
+    profiles: BTreeMap<String, Value>,
+}
+
+impl ProfileSet {
+    /// Profiles with `default` values and no overrides yet
+    #[must_use]
+    pub const fn new(default: Value) -> Self {
+        Self {
+            default,
+            profiles: BTreeMap::new(),
+        }
+    }
+
+    /// Override the defaults with `overrides` when `profile` is active
+    ///
+    /// Calling it again for the same profile replaces its overrides.
+    #[must_use]
+    pub fn with_profile(mut self, profile: &str, overrides: Value) -> Self {
+        self.profiles.insert(profile.to_lowercase(), overrides);
+        self
+    }
+
+    /// Profiles from a tree with a table per profile, such as a parsed file
+    ///
+    /// The `default` table holds the defaults, and is empty when missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `tree` is not a table of tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use superconfig::ProfileSet;
+    ///
+    /// let profiles = ProfileSet::from_tree(json!({
+    ///     "default": {"port": 80, "debug": true},
+    ///     "prod": {"debug": false},
+    /// }))
+    /// .unwrap();
+    /// assert_eq!(profiles.resolve("PROD"), json!({"port": 80, "debug": false}));
+    /// ```
+    pub fn from_tree(tree: Value) -> Result<Self, String> {
+        let Value::Object(tables) = tree else {
+            return Err(profile_error("Profiles must be a table of profile tables"));
+        };
+        let mut profiles = Self::new(Value::Object(serde_json::Map::new()));
+        for (profile, table) in tables {
+            if !table.is_object() {
+                return Err(profile_error(&format!("Profile {profile} must be a table")));
+            }
+            if profile.eq_ignore_ascii_case(DEFAULT_PROFILE) {
+                profiles.default = table;
+            } else {
+                profiles = profiles.with_profile(&profile, table);
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Names of the profiles with overrides, lowercase and sorted
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// The defaults merged with the overrides of `profile`, if it has any
+    #[must_use]
+    pub fn resolve(&self, profile: &str) -> Value {
+        let mut resolved = self.default.clone();
+        if let Some(overrides) = self.profiles.get(&profile.to_lowercase()) {
+            apply_merge_patch(&mut resolved, overrides);
+        }
+        resolved
+    }
+}
+
+/// Stores the data resolved for a profile, once every entry resolved successfully
+type Commit = Box<dyn FnOnce(&ConfigRegistry) -> Result<(), String>>;
+
+/// Deserializes the data of an entry for a profile, without storing it yet
+type Stage = Box<dyn Fn(&ProfileSet, &str) -> Result<Commit, String> + Send + Sync>;
+
+/// Profile state of a registry
+pub(crate) struct ProfileState {
+    /// Name of the active profile, lowercase
+    active: parking_lot::RwLock<String>,
+    /// Profiles of each entry created with `create_profiled`
+    entries: DashMap<HandleId, (ProfileSet, Stage)>,
+    /// Serializes profile switches, so entries never mix two profiles
+    selecting: parking_lot::Mutex<()>,
+}
+
+impl ProfileState {
+    pub(crate) fn new(active: String) -> Self {
+        Self {
+            active: parking_lot::RwLock::new(active),
+            entries: DashMap::new(),
+            selecting: parking_lot::Mutex::new(()),
+        }
+    }
+
+    pub(crate) fn active(&self) -> String {
+        self.active.read().clone()
+    }
+}
+
+impl std::fmt::Debug for ProfileState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProfileState")
+            .field("active", &*self.active.read())
+            .field("entries", &self.entries.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConfigRegistry {
+    /// Store the data of `profiles` for the active profile, and switch it with the
+    /// registry's profile
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the resolved data doesn't deserialize into `T`, or the
+    /// entry can't be created.
+    pub fn create_profiled<T>(&self, profiles: ProfileSet) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let state = self.profile_state();
+        // Holding the switch lock keeps the entry from missing a concurrent switch
+        let _selecting = state.selecting.lock();
+        let data = resolve_as::<T>(&profiles, &state.active())?;
+        let handle = self.create(data)?;
+        let id = handle.id();
+        let switch: Stage = Box::new(move |set, name| {
+            let data = resolve_as::<T>(set, name)?;
+            Ok(Box::new(move |registry: &Self| {
+                registry.update(&ConfigHandle::<T>::new(id), data)
+            }))
+        });
+        state.entries.insert(id, (profiles, switch));
+        Ok(handle)
+    }
+
+    /// Switch every profiled entry to `profile`
+    ///
+    /// Entries are switched all or nothing: if the data of any entry doesn't
+    /// deserialize for `profile`, no entry changes and the active profile stays the
+    /// same. Entries without overrides for `profile` get their defaults. Subscribers
+    /// receive a [`RegistryEvent::ProfileChanged`] when the active profile changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `profile` is empty, or an entry doesn't deserialize
+    /// for it.
+    pub fn select_profile(&self, profile: &str) -> Result<(), String> {
+        let profile = profile.trim().to_lowercase();
+        if profile.is_empty() {
+            return Err(profile_error("Profile name is empty"));
+        }
+
+        let state = self.profile_state();
+        let selecting = state.selecting.lock();
+        let commits = state
+            .entries
+            .iter()
+            .map(|entry| {
+                let (set, switch) = entry.value();
+                switch(set, &profile).map(|commit| (*entry.key(), commit))
+            })
+            .collect::<Result<Vec<(HandleId, Commit)>, String>>()
+            .map_err(|e| profile_error(&format!("Cannot select profile {profile}: {e}")))?;
+
+        let previous = std::mem::replace(&mut *state.active.write(), profile.clone());
+        for (handle_id, commit) in commits {
+            // Deleted entries no longer switch
+            if commit(self).is_err() {
+                state.entries.remove(&handle_id);
+            }
+        }
+
+        drop(selecting);
+
+        if previous != profile {
+            debug!(target: "superconfig.profile", "Selected profile {profile} (was {previous})");
+            self.emit_event(&RegistryEvent::ProfileChanged {
+                previous,
+                current: profile,
+            });
+        }
+        Ok(())
+    }
+
+    /// Select the profile named by the environment variable `var`
+    ///
+    /// The active profile stays the same when `var` is unset or empty, so the
+    /// environment can pick a profile without requiring one.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if an entry doesn't deserialize for the profile.
+    pub fn select_profile_from_env(&self, var: &str) -> Result<(), String> {
+        match std::env::var(var) {
+            Ok(profile) if !profile.trim().is_empty() => self.select_profile(&profile),
+            _ => Ok(()),
+        }
+    }
+
+    /// Name of the active profile, lowercase
+    ///
+    /// Registries start with [`DEFAULT_PROFILE`], and children with their parent's
+    /// active profile.
+    #[must_use]
+    pub fn active_profile(&self) -> String {
+        self.profile_state().active()
+    }
+}
+
+/// The data of `profiles` for `profile`, deserialized into `T`
+fn resolve_as<T: DeserializeOwned>(profiles: &ProfileSet, profile: &str) -> Result<T, String> {
+    serde_json::from_value(profiles.resolve(profile)).map_err(|e| {
+        error!(target: "superconfig.profile", "Invalid configuration for profile {profile}: {e}");
+        format!("superconfig.profile: Invalid configuration for profile {profile}: {e}")
+    })
+}
+
+fn profile_error(message: &str) -> String {
+    error!(target: "superconfig.profile", "{message}");
+    format!("superconfig.profile: {message}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    fn servers() -> ProfileSet {
+        ProfileSet::new(json!({"host": "localhost", "port": 80}))
+            .with_profile("Staging", json!({"host": "staging"}))
+            .with_profile("prod", json!({"host": "prod", "port": 443}))
+    }
+
+    #[test]
+    fn test_resolve_layers_profile_over_default() {
+        let profiles = servers();
+        assert_eq!(profiles.profiles().collect::<Vec<_>>(), ["prod", "staging"]);
+        assert_eq!(
+            profiles.resolve("default"),
+            json!({"host": "localhost", "port": 80})
+        );
+        assert_eq!(
+            profiles.resolve("STAGING"),
+            json!({"host": "staging", "port": 80})
+        );
+        assert_eq!(profiles.resolve("missing"), profiles.resolve("default"));
+    }
+
+    #[test]
+    fn test_from_tree_errors() {
+        assert!(ProfileSet::from_tree(json!([])).is_err());
+        let error = ProfileSet::from_tree(json!({"prod": 1})).unwrap_err();
+        assert!(error.contains("Profile prod must be a table"));
+        let profiles = ProfileSet::from_tree(json!({"prod": {"a": 1}})).unwrap();
+        assert_eq!(profiles.resolve("default"), json!({}));
+    }
+
+    #[test]
+    fn test_select_profile_switches_entries_and_notifies() {
+        let registry = ConfigRegistry::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&events);
+        registry.subscribe(move |event| {
+            if let RegistryEvent::ProfileChanged { current, .. } = event {
+                seen.lock().unwrap().push(current.clone());
+            }
+        });
+
+        let handle = registry.create_profiled::<Server>(servers()).unwrap();
+        registry.select_profile("PROD").unwrap();
+        assert_eq!(registry.active_profile(), "prod");
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "prod".to_string(),
+                port: 443,
+            }
+        );
+
+        // Selecting the active profile again doesn't notify
+        registry.select_profile("prod").unwrap();
+        registry.select_profile("staging").unwrap();
+        assert_eq!(registry.read(&handle).unwrap().port, 80);
+        assert_eq!(*events.lock().unwrap(), ["prod", "staging"]);
+
+        // New entries start in the active profile
+        let other = registry.create_profiled::<Server>(servers()).unwrap();
+        assert_eq!(registry.read(&other).unwrap().host, "staging");
+    }
+
+    #[test]
+    fn test_select_profile_is_all_or_nothing() {
+        let registry = ConfigRegistry::new();
+        let valid = registry.create_profiled::<Server>(servers()).unwrap();
+        let broken = ProfileSet::new(json!({"host": "a", "port": 1}))
+            .with_profile("prod", json!({"port": "high"}));
+        registry.create_profiled::<Server>(broken).unwrap();
+
+        let error = registry.select_profile("prod").unwrap_err();
+        assert!(error.starts_with("superconfig.profile: Cannot select profile prod"));
+        assert_eq!(registry.active_profile(), DEFAULT_PROFILE);
+        assert_eq!(registry.read(&valid).unwrap().host, "localhost");
+        assert!(registry.select_profile(" ").is_err());
+    }
+
+    #[test]
+    fn test_deleted_entries_stop_switching() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create_profiled::<Server>(servers()).unwrap();
+        registry.delete(&handle).unwrap();
+        registry.select_profile("prod").unwrap();
+        assert!(registry.profile_state().entries.is_empty());
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_child_starts_with_parent_profile() {
+        let registry = ConfigRegistry::new();
+        registry.select_profile("prod").unwrap();
+        let child = registry.child();
+        assert_eq!(child.active_profile(), "prod");
+        child.select_profile("staging").unwrap();
+        assert_eq!(registry.active_profile(), "prod");
+    }
+
+    #[test]
+    fn test_select_profile_from_unset_env_keeps_profile() {
+        let registry = ConfigRegistry::new();
+        registry
+            .select_profile_from_env("SUPERCONFIG_TEST_UNSET_PROFILE")
+            .unwrap();
+        assert_eq!(registry.active_profile(), DEFAULT_PROFILE);
+    }
+}
@@ -1,21 +1,35 @@
 //! Main configuration registry implementation
 
 use dashmap::DashMap;
-use parking_lot::RwLock;
 use std::{
-    sync::{
-        Arc,
-        atomic::{AtomicU64, Ordering},
-    },
-    time::Instant,
+    any::Any,
+    collections::BTreeMap,
+    sync::{Arc, OnceLock, Weak, mpsc},
+    thread::JoinHandle,
+    time::Duration,
 };
 use superconfig_macros::generate_json_helper;
 
-use super::{handle::ConfigHandle, stats::RegistryStats};
-use logffi::error;
+use super::{
+    backend::StorageBackend,
+    circuit::SourceHealth,
+    errors::RegistryError,
+    events::{Notifier, RegistryEvent, SubscriptionId},
+    handle::{AnyConfigHandle, ConfigHandle, HandleInfo},
+    limits::{EvictionPolicy, RegistryLimits},
+    pinned::{PinSlot, PinnedConfig},
+    pool::WorkerPool,
+    profile::{DEFAULT_PROFILE, ProfileState},
+    snapshot::{RegistrySnapshot, SnapshotEntry},
+    stats::{AtomicStats, RegistryStats, SourceLoadStats},
+    sync::{AtomicU64, Ordering},
+    time::Instant,
+    validation::StoredValidator,
+};
+use logffi::{debug, error};
+use serde::{Serialize, de::DeserializeOwned};
 
-/// Unique identifier for configuration handles
-pub type HandleId = u64;
+pub use super::errors::HandleId;
 
 /// Internal entry stored in the registry
 #[derive(Debug)]
@@ -24,41 +38,55 @@ struct ConfigEntry {
     data: Box<dyn std::any::Any + Send + Sync>,
     /// Type name for runtime type checking
     type_name: &'static str,
-    /// When this entry was created (used for cache eviction in Phase 5)
-    #[allow(dead_code)]
+    /// When this entry was created, reported by `handles`
     created_at: Instant,
-    /// When this entry was last accessed (used for LRU eviction in Phase 5)
-    #[allow(dead_code)]
-    last_accessed: Instant,
+    /// Registry access clock value of the last read or write, for LRU eviction
+    last_accessed: AtomicU64,
     /// Registry-level reference count (for statistics, separate from Arc's count)
     #[allow(dead_code)]
     ref_count: AtomicU64,
     /// Size of the data in bytes (approximate)
     data_size: usize,
+    /// Name registered with `create_named`, removed from the registry's names with the entry
+    name: Option<String>,
+    /// Number of times the data was set: 1 when created, incremented by each update
+    generation: u64,
+    /// Time to live set by `create_with_ttl`, counted from `created_at`
+    ttl: Option<Duration>,
+    /// JSON tree of the data, serialized by the first `get_path` or `set_path`
+    tree: OnceLock<Arc<serde_json::Value>>,
 }
 
 impl ConfigEntry {
-    fn new<T: 'static + Send + Sync>(data: T) -> Self {
+    fn new<T: 'static + Send + Sync>(data: T, accessed: u64) -> Self {
         let data_size = std::mem::size_of::<T>();
         Self {
             data: Box::new(Arc::new(data)), // Always store as Arc<T>
             type_name: std::any::type_name::<T>(),
             created_at: Instant::now(),
-            last_accessed: Instant::now(),
+            last_accessed: AtomicU64::new(accessed),
             ref_count: AtomicU64::new(1),
             data_size,
+            name: None,
+            generation: 1,
+            ttl: None,
+            tree: OnceLock::new(),
         }
     }
 
-    fn get_arc_data<T: 'static>(&self) -> Result<Arc<T>, String> {
+    /// Whether the TTL of the entry elapsed at `now`
+    fn is_expired(&self, now: Instant) -> bool {
+        self.ttl
+            .is_some_and(|ttl| now.saturating_duration_since(self.created_at) >= ttl)
+    }
+
+    fn get_arc_data<T: 'static>(&self) -> Result<Arc<T>, RegistryError> {
         let expected_type = std::any::type_name::<T>();
         if self.type_name != expected_type {
-            let error_msg = format!(
-                "superconfig.registry: Wrong type, expected {}, found {}",
-                expected_type, self.type_name
-            );
-            error!(target: "superconfig.registry", "Wrong type, expected {}, found {}", expected_type, self.type_name);
-            return Err(error_msg);
+            return Err(RegistryError::WrongType {
+                expected: expected_type,
+                found: self.type_name,
+            });
         }
 
         // If we get here, downcast must succeed
@@ -72,6 +100,29 @@ impl ConfigEntry {
 /// lookup times. It supports both startup flags (immutable after creation) and runtime
 /// flags (mutable during operation).
 ///
+/// # Async Contexts
+///
+/// Registry operations never block: they do no I/O, never await, and only take a
+/// `DashMap` shard lock for the duration of a map lookup or insert. The exception is a
+/// registry with a [storage backend](Self::attach_storage): its creates, updates,
+/// deletes, and other changes write a snapshot through to the backend before returning,
+/// so they block on storage I/O and belong in `spawn_blocking` in async code.
+/// Statistics are atomic counters, so concurrent reads don't contend on a shared lock.
+/// Calling [`read`](Self::read) directly from async tasks is safe and needs no
+/// `spawn_blocking`. To await slow source loading during startup, use an
+/// [`AsyncConfigSource`](crate::AsyncConfigSource) with
+/// [`create_from_source`](Self::create_from_source).
+///
+/// # Concurrency
+///
+/// Each operation is atomic with respect to the others on the same handle:
+/// [`update`](Self::update) replaces an entry in place, so concurrent readers get the
+/// old or the new data and never a missing handle. Statistics are recorded outside the
+/// map's locks and end up exact once concurrent operations finish, but a snapshot taken
+/// meanwhile may mix counters from before and after an operation.
+/// [`clear`](Self::clear) is not atomic with respect to concurrent creates. The
+/// counters are model-checked with loom (see `tests/loom_registry.rs`).
+///
 /// # Examples
 ///
 /// ```
@@ -89,14 +140,52 @@ impl ConfigEntry {
 pub struct ConfigRegistry {
     /// Internal storage using `DashMap` for lock-free operations
     entries: DashMap<HandleId, ConfigEntry>,
-    /// Atomic counter for generating unique handle IDs
-    next_id: AtomicU64,
-    /// Registry statistics protected by `RwLock`
-    stats: Arc<RwLock<RegistryStats>>,
+    /// Atomic counter for generating unique handle IDs, shared with child registries
+    next_id: Arc<AtomicU64>,
+    /// IDs of removed entries to reuse, with the `RECYCLE_IDS` startup flag
+    free_ids: Option<parking_lot::Mutex<Vec<HandleId>>>,
+    /// Registry statistics, updated with atomics so reads never wait on a lock
+    stats: AtomicStats,
     /// Startup flags - immutable after registry creation
     startup_flags: u32,
     /// Runtime flags - mutable at runtime
     runtime_flags: Arc<parking_lot::RwLock<u64>>,
+    /// When the registry was created (reported as uptime in statistics)
+    created_at: Instant,
+    /// Latest health reported by each source loaded into the registry, by name
+    sources: DashMap<String, SourceHealth>,
+    /// Load counts and durations of each source loaded into the registry, by name
+    source_loads: DashMap<String, SourceLoadStats>,
+    /// Number of live overlays, shared with each `OverlayHandle`
+    overlays: Arc<AtomicU64>,
+    /// Subscribers notified of registry events
+    events: Notifier,
+    /// Handle IDs of the entries created with `create_named`, by name
+    names: DashMap<String, HandleId>,
+    /// Senders of the `watch` channels of each entry, a `Vec<mpsc::Sender<Arc<T>>>`
+    watchers: DashMap<HandleId, Box<dyn Any + Send + Sync>>,
+    /// Data shared with the `pin`s of each entry, a `PinSlot<T>`
+    pins: DashMap<HandleId, Box<dyn Any + Send + Sync>>,
+    /// Validators of each entry, an `Arc<dyn Validator<T>>`
+    validators: DashMap<HandleId, Box<dyn Any + Send + Sync>>,
+    /// Types registered with `register_snapshot_type`, by type name
+    snapshot_types: DashMap<&'static str, SnapshotCodec>,
+    /// Entry and memory limits, unbounded unless set with `custom_with_limits`
+    limits: RegistryLimits,
+    /// Logical clock ordering entry accesses for LRU eviction
+    access_clock: AtomicU64,
+    /// Serializes the operations that check the limits, so they can't overshoot together
+    capacity: parking_lot::Mutex<()>,
+    /// Registry that reads and lookups fall back to, for registries made with `child`
+    parent: Option<Arc<Self>>,
+    /// Backend that changes are written through to, set by `attach_storage`
+    storage: OnceLock<Box<dyn StorageBackend>>,
+    /// Serializes writes to the backend, so the last one stored has every change
+    persisting: parking_lot::Mutex<()>,
+    /// Active profile and the entries created with `create_profiled`
+    profiles: ProfileState,
+    /// Worker threads with the `THREAD_POOL` startup flag, shared with child registries
+    pool: Option<Arc<WorkerPool>>,
 }
 
 impl ConfigRegistry {
@@ -139,13 +228,163 @@ impl ConfigRegistry {
     /// ```
     #[must_use]
     pub fn custom(startup_flags: u32) -> Arc<Self> {
-        Arc::new(Self {
+        Self::custom_with_limits(startup_flags, RegistryLimits::default())
+    }
+
+    /// Create a new configuration registry with custom startup flags and capacity limits
+    ///
+    /// Creates and updates that would exceed `limits` fail or evict the least recently
+    /// used entries, depending on [`RegistryLimits::eviction`]. Evicted handles no longer
+    /// resolve, and subscribers receive a [`RegistryEvent::Evicted`] for each. Bounded
+    /// registries serialize creates and updates among themselves; reads stay lock-free.
+    ///
+    /// # Examples
+    /// ```
+    /// use superconfig::{ConfigRegistry, RegistryLimits, config_flags::startup};
+    ///
+    /// let limits = RegistryLimits::default().with_max_entries(1);
+    /// let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+    ///
+    /// let _handle = registry.create("first".to_string()).unwrap();
+    /// let error = registry.create("second".to_string()).unwrap_err();
+    /// assert!(error.contains("Registry full"));
+    /// ```
+    #[must_use]
+    pub fn custom_with_limits(startup_flags: u32, limits: RegistryLimits) -> Arc<Self> {
+        Arc::new(Self::build(startup_flags, 0, limits, None))
+    }
+
+    /// Create a new configuration registry with a worker pool of `threads` threads
+    ///
+    /// Enables the `THREAD_POOL` startup flag along with `startup_flags`; 0 threads
+    /// starts one per available CPU, like the flag alone. See [`pool`](super::pool).
+    ///
+    /// # Examples
+    /// ```
+    /// use superconfig::{ConfigRegistry, config_flags::startup};
+    ///
+    /// let registry = ConfigRegistry::custom_with_threads(startup::SIMD, 4);
+    /// assert_eq!(registry.pool_threads(), 4);
+    /// ```
+    #[must_use]
+    pub fn custom_with_threads(startup_flags: u32, threads: usize) -> Arc<Self> {
+        let thread_pool = crate::config_flags::startup::THREAD_POOL;
+        let mut registry = Self::build(
+            startup_flags & !thread_pool,
+            0,
+            RegistryLimits::default(),
+            None,
+        );
+        registry.startup_flags |= thread_pool;
+        registry.pool = Some(Arc::new(WorkerPool::new(threads)));
+        Arc::new(registry)
+    }
+
+    /// Create a scoped registry whose reads fall back to this one
+    ///
+    /// Reads, generations, type names, and name lookups that don't find a handle or name
+    /// in the child continue in the parent, so per-tenant children override a global
+    /// baseline: create entries under the same names with
+    /// [`create_named`](Self::create_named), or [`update`](Self::update) a parent's handle
+    /// through the child to override it in the child only. Deleting the override
+    /// reveals the parent's entry again.
+    ///
+    /// Writes, [`clear`](Self::clear), [`handles`](Self::handles),
+    /// [`contains_handle`](Self::contains_handle), statistics, and subscriptions only
+    /// concern the child's own scope. Handle IDs are allocated from a counter shared with
+    /// the parent, so they never collide. The child starts with the parent's startup and
+    /// runtime flags and active profile, and no limits, and never recycles handle IDs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let global = ConfigRegistry::new();
+    /// let timeout = global.create_named("timeout_ms", 1000_u32).unwrap();
+    /// let region = global.create_named("region", "eu".to_string()).unwrap();
+    ///
+    /// let tenant = global.child();
+    /// tenant.update(&timeout, 250).unwrap();
+    /// assert_eq!(*tenant.read(&timeout).unwrap(), 250);
+    /// assert_eq!(*tenant.read(&region).unwrap(), "eu");
+    /// assert_eq!(*global.read(&timeout).unwrap(), 1000);
+    ///
+    /// tenant.clear();
+    /// assert_eq!(*tenant.read(&timeout).unwrap(), 1000);
+    /// ```
+    #[must_use]
+    pub fn child(self: &Arc<Self>) -> Arc<Self> {
+        let mut child = Self::build(
+            self.startup_flags,
+            self.get_runtime_flags(),
+            RegistryLimits::default(),
+            Some(Arc::clone(self)),
+        );
+        child.next_id = Arc::clone(&self.next_id);
+        child.profiles = ProfileState::new(self.active_profile());
+        Arc::new(child)
+    }
+
+    /// Parent of a registry made with [`child`](Self::child)
+    #[must_use]
+    pub const fn parent(&self) -> Option<&Arc<Self>> {
+        self.parent.as_ref()
+    }
+
+    fn build(
+        startup_flags: u32,
+        runtime_flags: u64,
+        limits: RegistryLimits,
+        parent: Option<Arc<Self>>,
+    ) -> Self {
+        let pool = parent.as_ref().map_or_else(
+            || {
+                (startup_flags & crate::config_flags::startup::THREAD_POOL != 0)
+                    .then(|| Arc::new(WorkerPool::new(0)))
+            },
+            |parent| parent.pool.clone(),
+        );
+        Self {
             entries: DashMap::new(),
-            next_id: AtomicU64::new(1),
-            stats: Arc::new(RwLock::new(RegistryStats::default())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            // Children allocate from their parent's counter, so they never recycle IDs
+            free_ids: (startup_flags & crate::config_flags::startup::RECYCLE_IDS != 0
+                && parent.is_none())
+            .then(|| parking_lot::Mutex::new(Vec::new())),
+            stats: AtomicStats::default(),
             startup_flags,
-            runtime_flags: Arc::new(parking_lot::RwLock::new(0)),
-        })
+            runtime_flags: Arc::new(parking_lot::RwLock::new(runtime_flags)),
+            created_at: Instant::now(),
+            sources: DashMap::new(),
+            source_loads: DashMap::new(),
+            overlays: Arc::new(AtomicU64::new(0)),
+            events: Notifier::default(),
+            names: DashMap::new(),
+            watchers: DashMap::new(),
+            pins: DashMap::new(),
+            validators: DashMap::new(),
+            snapshot_types: DashMap::new(),
+            limits,
+            access_clock: AtomicU64::new(0),
+            capacity: parking_lot::Mutex::new(()),
+            parent,
+            storage: OnceLock::new(),
+            persisting: parking_lot::Mutex::new(()),
+            profiles: ProfileState::new(DEFAULT_PROFILE.to_string()),
+            pool,
+        }
+    }
+
+    /// Worker pool of the registry, with the `THREAD_POOL` startup flag
+    pub(crate) fn pool(&self) -> Option<&WorkerPool> {
+        self.pool.as_deref()
+    }
+
+    /// Capacity limits of the registry
+    #[must_use]
+    pub const fn limits(&self) -> &RegistryLimits {
+        &self.limits
     }
 
     // Flag management methods
@@ -154,6 +393,7 @@ impl ConfigRegistry {
     ///
     /// This method works with Arc<ConfigRegistry> for consistent Arc-based chaining.
     /// Always returns Arc<Self> to continue the chain, errors are collected internally.
+    /// Subscribers receive a [`RegistryEvent::FlagsChanged`] if any flag was off.
     ///
     /// # Examples
     /// ```
@@ -171,11 +411,7 @@ impl ConfigRegistry {
             return self;
         }
 
-        // Directly modify the runtime flags through the Arc
-        {
-            let mut runtime_flags = self.runtime_flags.write();
-            *runtime_flags |= flags;
-        }
+        self.update_runtime_flags(|current| current | flags);
         self
     }
 
@@ -183,6 +419,7 @@ impl ConfigRegistry {
     ///
     /// This method works with Arc<ConfigRegistry> for consistent Arc-based chaining.
     /// Always returns Arc<Self> to continue the chain, errors are collected internally.
+    /// Subscribers receive a [`RegistryEvent::FlagsChanged`] if any flag was on.
     ///
     /// # Examples
     /// ```
@@ -200,12 +437,25 @@ impl ConfigRegistry {
             return self;
         }
 
-        // Directly modify the runtime flags through the Arc
-        {
+        self.update_runtime_flags(|current| current & !flags);
+        self
+    }
+
+    /// Apply `change` to the runtime flags and notify subscribers if they changed
+    ///
+    /// Subscribers are called after the lock is released, so they can read the flags.
+    fn update_runtime_flags(&self, change: impl FnOnce(u64) -> u64) {
+        let (previous, current) = {
             let mut runtime_flags = self.runtime_flags.write();
-            *runtime_flags &= !flags;
+            let previous = *runtime_flags;
+            *runtime_flags = change(previous);
+            (previous, *runtime_flags)
+        };
+        if previous != current {
+            debug!(target: "superconfig.flags", "Runtime flags changed: 0x{previous:X} -> 0x{current:X}");
+            self.events
+                .emit(&RegistryEvent::FlagsChanged { previous, current });
         }
-        self
     }
 
     /// Check if startup flags are enabled
@@ -244,6 +494,72 @@ impl ConfigRegistry {
     pub fn get_runtime_flags(&self) -> u64 {
         *self.runtime_flags.read()
     }
+
+    /// Get the state of every runtime flag by name
+    ///
+    /// Names come from [`RUNTIME_FLAG_NAMES`](crate::config_flags::RUNTIME_FLAG_NAMES).
+    /// `flags_snapshot_as_json()` returns the same map as a JSON string for FFI clients.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::{ConfigRegistry, config_flags::runtime};
+    ///
+    /// let registry = ConfigRegistry::new().enable(runtime::PARALLEL);
+    /// let flags = registry.flags_snapshot();
+    /// assert!(flags["parallel"]);
+    /// assert!(!flags["strict_mode"]);
+    ///
+    /// let json = registry.flags_snapshot_as_json();
+    /// assert!(json.contains("\"parallel\":true"));
+    /// ```
+    #[must_use]
+    #[generate_json_helper(outgoing)]
+    pub fn flags_snapshot(&self) -> BTreeMap<&'static str, bool> {
+        let runtime_flags = *self.runtime_flags.read();
+        crate::config_flags::RUNTIME_FLAG_NAMES
+            .iter()
+            .map(|&(name, flag)| (name, runtime_flags & flag != 0))
+            .collect()
+    }
+
+    /// Register a callback called with every [`RegistryEvent`]
+    ///
+    /// Callbacks run synchronously on the thread that made the change, after the
+    /// registry released its locks, so they may use the registry. Events from
+    /// concurrent changes can arrive in either order; call
+    /// [`flags_snapshot`](Self::flags_snapshot) for the latest state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+    /// use superconfig::{ConfigRegistry, config_flags::runtime};
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let enabled = Arc::new(AtomicU64::new(0));
+    /// let seen = Arc::clone(&enabled);
+    /// let id = registry.subscribe(move |event| {
+    ///     seen.fetch_or(event.enabled_flags(), Ordering::SeqCst);
+    /// });
+    ///
+    /// let registry = registry.enable(runtime::STRICT_MODE);
+    /// assert_eq!(enabled.load(Ordering::SeqCst), runtime::STRICT_MODE);
+    /// assert!(registry.unsubscribe(id));
+    /// ```
+    pub fn subscribe(
+        &self,
+        callback: impl Fn(&RegistryEvent) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        self.events.subscribe(Arc::new(callback))
+    }
+
+    /// Remove a callback registered with [`subscribe`](Self::subscribe)
+    ///
+    /// Returns `false` if no subscription has this id.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        self.events.unsubscribe(id)
+    }
 }
 
 // CRUD Operations
@@ -256,7 +572,22 @@ impl ConfigRegistry {
     ///
     /// # Errors
     ///
-    /// Returns `RegistryError::RegistryFull` if the registry has reached maximum capacity.
+    /// Returns a "Registry full" error if the registry has [limits](Self::custom_with_limits)
+    /// that the new entry would exceed and its policy is
+    /// [`RejectWhenFull`](EvictionPolicy::RejectWhenFull), or if the entry alone exceeds
+    /// the memory limit. Also fails once all handle IDs were issued, which takes
+    /// `u64::MAX` creates unless the registry was created with
+    /// [`startup::RECYCLE_IDS`](crate::config_flags::startup::RECYCLE_IDS).
+    ///
+    /// # Visibility
+    ///
+    /// The entry is stored before `create` returns, under the map's shard lock. Any
+    /// thread that receives the handle afterwards (through a channel, a join, a mutex,
+    /// or any other synchronization) reads the fully written data, with no extra
+    /// fences needed. Handle ids are unique across threads, but only their uniqueness
+    /// is guaranteed: concurrent creates may finish in a different order than their ids.
+    /// With [`startup::RECYCLE_IDS`](crate::config_flags::startup::RECYCLE_IDS), the ids of
+    /// removed entries are reused, so they are only unique among live entries.
     ///
     /// # Examples
     ///
@@ -268,75 +599,56 @@ impl ConfigRegistry {
     /// assert_eq!(handle.id(), 1);
     /// ```
     pub fn create<T: 'static + Send + Sync>(&self, data: T) -> Result<ConfigHandle<T>, String> {
-        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
-        let entry = ConfigEntry::new(data);
-        let data_size = entry.data_size;
-
-        self.entries.insert(id, entry);
-
-        // Update statistics
-        {
-            let mut stats = self.stats.write();
-            stats.increment_creates();
-            stats.add_memory(data_size as u64);
-        }
-
-        Ok(ConfigHandle::new(id))
+        let entry = ConfigEntry::new(data, self.tick());
+        self.insert_entry(entry).map(ConfigHandle::new)
     }
 
-    /// Read configuration data
-    ///
-    /// Returns `Arc<T>` for efficient sharing. Use field access (`config.host`)
-    /// and method calls (`config.validate()`) directly - they're zero-cost due to auto-deref.
+    /// Create a configuration entry that expires `ttl` after it was last written
     ///
-    /// For mutations, create new config and use `update()`.
+    /// Expired entries stay readable until [`evict_expired`](Self::evict_expired), or
+    /// the background pass started by [`expire_every`](Self::expire_every), removes
+    /// them. [`update`](Self::update) keeps the TTL and restarts it, so refreshing a
+    /// cached value keeps it alive.
     ///
     /// # Errors
     ///
-    /// Returns error message if the handle doesn't exist or points to wrong type.
-    ///
-    /// # Performance Notes
-    ///
-    /// - Field access: `config.database.host` (zero cost)
-    /// - Method calls: `config.validate()` (zero cost)
-    /// - Passing to functions: `process(config)` (~1ns to move Arc)
-    /// - Multiple reads share the same underlying data efficiently
+    /// Returns an error for the reasons of [`create`](Self::create).
     ///
     /// # Examples
     ///
     /// ```
     /// use superconfig::ConfigRegistry;
+    /// use std::time::Duration;
     ///
     /// let registry = ConfigRegistry::new();
-    /// let handle = registry.create("test".to_string()).unwrap();
-    /// let data = registry.read(&handle).unwrap();
-    /// assert_eq!(*data, "test");
+    /// let cached = registry.create_with_ttl("fetched".to_string(), Duration::ZERO).unwrap();
+    /// let kept = registry.create("local".to_string()).unwrap();
+    ///
+    /// assert_eq!(registry.evict_expired(), 1);
+    /// assert!(!registry.contains_handle(&cached));
+    /// assert!(registry.contains_handle(&kept));
+    /// assert_eq!(registry.stats().total_expirations, 1);
     /// ```
-    #[generate_json_helper(auto)]
-    pub fn read<T: 'static>(&self, handle: &ConfigHandle<T>) -> Result<Arc<T>, String> {
-        let entry_ref = self.entries.get(&handle.id()).ok_or_else(|| {
-            let error_msg = format!("superconfig.registry: Handle {} not found", handle.id());
-            error!(target: "superconfig.registry", "Handle {} not found", handle.id());
-            error_msg
-        })?;
-
-        // Update statistics
-        {
-            let mut stats = self.stats.write();
-            stats.increment_reads();
-        }
-
-        entry_ref.get_arc_data::<T>()
+    pub fn create_with_ttl<T: 'static + Send + Sync>(
+        &self,
+        data: T,
+        ttl: Duration,
+    ) -> Result<ConfigHandle<T>, String> {
+        let mut entry = ConfigEntry::new(data, self.tick());
+        entry.ttl = Some(ttl);
+        self.insert_entry(entry).map(ConfigHandle::new)
     }
 
-    /// Update data in a configuration handle
+    /// Create a configuration entry that other modules can find by `name`
     ///
-    /// This replaces the entire configuration data with new data.
-    /// Any existing Arc references will continue to point to the old data.
+    /// Separate parts of a program, and FFI clients that can't share Rust handles, get
+    /// the entry back with [`lookup`](Self::lookup). The name is released when the entry
+    /// is deleted, evicted, or cleared; [`update`](Self::update) keeps it.
     ///
     /// # Errors
     ///
-    /// Returns error message if the handle doesn't exist in the registry.
+    /// Returns an error if an entry already has this name, or for the reasons of
+    /// [`create`](Self::create).
     ///
     /// # Examples
     ///
@@ -344,55 +656,52 @@ impl ConfigRegistry {
     /// use superconfig::ConfigRegistry;
     ///
     /// let registry = ConfigRegistry::new();
-    /// let handle = registry.create("old".to_string()).unwrap();
+    /// let handle = registry.create_named("app.db", "postgres://localhost".to_string()).unwrap();
     ///
-    /// registry.update(&handle, "new".to_string()).unwrap();
-    /// let data = registry.read(&handle).unwrap();
-    /// assert_eq!(*data, "new");
+    /// // Elsewhere in the program
+    /// let found = registry.lookup::<String>("app.db").unwrap();
+    /// assert_eq!(found, handle);
+    /// assert_eq!(*registry.read(&found).unwrap(), "postgres://localhost");
+    ///
+    /// assert!(registry.create_named("app.db", String::new()).is_err());
     /// ```
-    pub fn update<T: 'static + Send + Sync>(
+    pub fn create_named<T: 'static + Send + Sync>(
         &self,
-        handle: &ConfigHandle<T>,
-        new_data: T,
-    ) -> Result<(), String> {
-        // Remove old entry to get its size
-        let old_entry = self.entries.remove(&handle.id()).ok_or_else(|| {
-            let error_msg = format!(
-                "superconfig.registry: Handle {} not found for update",
-                handle.id()
-            );
-            error!(target: "superconfig.registry", "Handle {} not found for update", handle.id());
-            error_msg
-        })?;
-
-        let old_size = old_entry.1.data_size;
-
-        // Create new entry with new data
-        let new_entry = ConfigEntry::new(new_data);
-        let new_size = new_entry.data_size;
-
-        // Insert new entry (this atomically replaces the old one)
-        self.entries.insert(handle.id(), new_entry);
-
-        // Update statistics
-        {
-            let mut stats = self.stats.write();
-            stats.increment_updates();
-            stats.remove_memory(old_size as u64);
-            stats.add_memory(new_size as u64);
+        name: &str,
+        data: T,
+    ) -> Result<ConfigHandle<T>, String> {
+        // Reserve the name with the unused ID 0, so no names lock is held while creating
+        match self.names.entry(name.to_string()) {
+            dashmap::Entry::Occupied(_) => {
+                error!(target: "superconfig.registry", "Name '{name}' already in use");
+                return Err(format!(
+                    "superconfig.registry: Name '{name}' already in use"
+                ));
+            }
+            dashmap::Entry::Vacant(vacant) => {
+                vacant.insert(0);
+            }
         }
 
-        Ok(())
+        let mut entry = ConfigEntry::new(data, self.tick());
+        entry.name = Some(name.to_string());
+        match self.insert_entry(entry) {
+            Ok(id) => {
+                self.names.insert(name.to_string(), id);
+                Ok(ConfigHandle::new(id))
+            }
+            Err(error_msg) => {
+                self.names.remove(name);
+                Err(error_msg)
+            }
+        }
     }
 
-    /// Delete a configuration entry and return the data as Arc<T>
-    ///
-    /// Returns the same Arc<T> that was stored internally, avoiding any cloning.
-    /// This is consistent with our zero-copy design philosophy.
+    /// Find the entry created with [`create_named`](Self::create_named) under `name`
     ///
     /// # Errors
     ///
-    /// Returns error message if the handle doesn't exist or points to wrong type.
+    /// Returns an error if no entry has this name or if it holds a type other than `T`.
     ///
     /// # Examples
     ///
@@ -400,42 +709,38 @@ impl ConfigRegistry {
     /// use superconfig::ConfigRegistry;
     ///
     /// let registry = ConfigRegistry::new();
-    /// let handle = registry.create("test".to_string()).unwrap();
+    /// registry.create_named("http.port", 8080_u16).unwrap();
     ///
-    /// let data = registry.delete(&handle).unwrap();
-    /// assert_eq!(*data, "test");
-    /// assert!(!registry.contains_handle(&handle));
+    /// let port = registry.lookup::<u16>("http.port").unwrap();
+    /// assert_eq!(*registry.read(&port).unwrap(), 8080);
+    /// assert!(registry.lookup::<u32>("http.port").unwrap_err().contains("Wrong type"));
+    /// assert!(registry.lookup::<u16>("https.port").unwrap_err().contains("not found"));
     /// ```
-    pub fn delete<T: 'static>(&self, handle: &ConfigHandle<T>) -> Result<Arc<T>, String> {
-        let (_, entry) = self.entries.remove(&handle.id()).ok_or_else(|| {
-            let error_msg = format!(
-                "superconfig.registry: Handle {} not found for delete",
-                handle.id()
-            );
-            error!(target: "superconfig.registry", "Handle {} not found for delete", handle.id());
-            error_msg
-        })?;
-
-        let data_size = entry.data_size;
-
-        // Extract the Arc<T> directly
-        let arc = entry.data.downcast::<Arc<T>>().map_err(|_| {
-            let error_msg = format!("superconfig.registry: Wrong type for delete, expected {}, found {}", std::any::type_name::<T>(), entry.type_name);
-            error!(target: "superconfig.registry", "Wrong type for delete, expected {}, found {}", std::any::type_name::<T>(), entry.type_name);
-            error_msg
+    pub fn lookup<T: 'static>(&self, name: &str) -> Result<ConfigHandle<T>, String> {
+        let handle = self.lookup_any(name).ok_or_else(|| {
+            error!(target: "superconfig.registry", "Name '{name}' not found");
+            format!("superconfig.registry: Name '{name}' not found")
         })?;
-
-        // Update statistics
-        {
-            let mut stats = self.stats.write();
-            stats.increment_deletes();
-            stats.remove_memory(data_size as u64);
+        let expected_type = std::any::type_name::<T>();
+        match self.type_name(handle.id()) {
+            Some(found) if found == expected_type => Ok(ConfigHandle::new(handle.id())),
+            Some(found) => {
+                error!(target: "superconfig.registry", "Wrong type for name '{name}', expected {expected_type}, found {found}");
+                Err(format!(
+                    "superconfig.registry: Wrong type for name '{name}', expected {expected_type}, found {found}"
+                ))
+            }
+            None => {
+                error!(target: "superconfig.registry", "Name '{name}' not found");
+                Err(format!("superconfig.registry: Name '{name}' not found"))
+            }
         }
-
-        Ok(*arc)
     }
 
-    /// Get current registry statistics
+    /// Find the entry named `name` without knowing its type
+    ///
+    /// Returns `None` if no entry has this name, without logging an error.
+    /// `lookup_any_as_json(name)` returns the handle ID as JSON for FFI clients.
     ///
     /// # Examples
     ///
@@ -443,15 +748,32 @@ impl ConfigRegistry {
     /// use superconfig::ConfigRegistry;
     ///
     /// let registry = ConfigRegistry::new();
-    /// let stats = registry.stats();
-    /// assert_eq!(stats.total_handles, 0);
+    /// let handle = registry.create_named("app.name", "demo".to_string()).unwrap();
+    ///
+    /// assert_eq!(registry.lookup_any("app.name").unwrap().id(), handle.id());
+    /// assert_eq!(registry.lookup_any_as_json("app.name"), format!(r#"{{"data":{},"success":true}}"#, handle.id()));
     /// ```
     #[must_use]
-    pub fn stats(&self) -> RegistryStats {
-        self.stats.read().clone()
+    #[generate_json_helper(outgoing)]
+    pub fn lookup_any(&self, name: &str) -> Option<AnyConfigHandle> {
+        self.names
+            .get(name)
+            .map(|id| *id)
+            .filter(|&id| id != 0)
+            .map(AnyConfigHandle::new)
+            .or_else(|| self.parent.as_ref()?.lookup_any(name))
     }
 
-    /// Check if a handle exists in the registry
+    /// Rebuild a typed handle from a handle ID received across the FFI boundary
+    ///
+    /// Checks that the entry exists, here or in a parent registry, and holds a `T`,
+    /// unlike deserializing a [`ConfigHandle`] from its ID. With the `RECYCLE_IDS`
+    /// startup flag, an ID kept after its entry was deleted can resolve to a newer
+    /// entry of the same type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no entry has this ID or if it holds a type other than `T`.
     ///
     /// # Examples
     ///
@@ -459,18 +781,32 @@ impl ConfigRegistry {
     /// use superconfig::ConfigRegistry;
     ///
     /// let registry = ConfigRegistry::new();
-    /// let handle = registry.create("test".to_string()).unwrap();
+    /// let id = registry.create(8080_u16).unwrap().id();
     ///
-    /// assert!(registry.contains_handle(&handle));
-    /// registry.delete(&handle).unwrap();
-    /// assert!(!registry.contains_handle(&handle));
+    /// let port = registry.resolve::<u16>(id).unwrap();
+    /// assert_eq!(*registry.read(&port).unwrap(), 8080);
+    /// assert!(registry.resolve::<String>(id).unwrap_err().contains("Wrong type"));
+    /// assert!(registry.resolve::<u16>(999).unwrap_err().contains("not found"));
     /// ```
-    #[must_use]
-    pub fn contains_handle<T>(&self, handle: &ConfigHandle<T>) -> bool {
-        self.entries.contains_key(&handle.id())
+    pub fn resolve<T: 'static>(&self, handle_id: HandleId) -> Result<ConfigHandle<T>, String> {
+        let expected = std::any::type_name::<T>();
+        let result = match self.type_name(handle_id) {
+            Some(found) if found == expected => return Ok(ConfigHandle::new(handle_id)),
+            Some(found) => RegistryError::WrongType { expected, found },
+            None => RegistryError::HandleNotFound(handle_id),
+        };
+        error!(target: "superconfig.registry", "{result}");
+        Err(result.into())
     }
 
-    /// Clear all entries from the registry
+    /// Rebuild a type-erased handle from a handle ID, checking that the entry exists
+    ///
+    /// For clients that only access the entry as JSON and don't know its Rust type:
+    /// `resolve_dynamic_as_json(id)` returns the handle ID as JSON, or the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no entry has this ID, here or in a parent registry.
     ///
     /// # Examples
     ///
@@ -478,19 +814,65 @@ impl ConfigRegistry {
     /// use superconfig::ConfigRegistry;
     ///
     /// let registry = ConfigRegistry::new();
-    /// let _handle = registry.create("test".to_string()).unwrap();
+    /// let handle = registry.create("demo".to_string()).unwrap();
     ///
-    /// assert_eq!(registry.len(), 1);
-    /// registry.clear();
-    /// assert_eq!(registry.len(), 0);
+    /// let any = registry.resolve_dynamic(handle.id()).unwrap();
+    /// assert!(registry.is_type::<String>(&any));
+    /// assert_eq!(registry.resolve_dynamic_as_json(handle.id()), format!(r#"{{"data":{},"success":true}}"#, handle.id()));
     /// ```
-    pub fn clear(&self) {
-        self.entries.clear();
-        let mut stats = self.stats.write();
-        stats.reset();
+    #[generate_json_helper(outgoing)]
+    pub fn resolve_dynamic(&self, handle_id: HandleId) -> Result<AnyConfigHandle, String> {
+        if self.type_name(handle_id).is_some() {
+            return Ok(AnyConfigHandle::new(handle_id));
+        }
+        let error = RegistryError::HandleNotFound(handle_id);
+        error!(target: "superconfig.registry", "{error}");
+        Err(error.into())
     }
 
-    /// Get the number of entries in the registry
+    /// Store `entry` under a new handle ID, respecting the limits
+    fn insert_entry(&self, entry: ConfigEntry) -> Result<HandleId, String> {
+        let capacity = self.limits.is_bounded().then(|| self.capacity.lock());
+        let evicted = match capacity {
+            Some(_) => self.make_room(entry.data_size as u64, None)?,
+            None => Vec::new(),
+        };
+        let id = match self.allocate_id() {
+            Ok(id) => id,
+            Err(error_msg) => {
+                drop(capacity);
+                self.notify_evicted(evicted);
+                return Err(error_msg);
+            }
+        };
+
+        // Update statistics before the entry becomes visible to other threads
+        self.stats.record_create(entry.data_size as u64);
+        self.entries.insert(id, entry);
+
+        drop(capacity);
+        self.notify_evicted(evicted);
+        self.write_through();
+        Ok(id)
+    }
+
+    /// Read configuration data
+    ///
+    /// Returns `Arc<T>` for efficient sharing. Use field access (`config.host`)
+    /// and method calls (`config.validate()`) directly - they're zero-cost due to auto-deref.
+    ///
+    /// For mutations, create new config and use `update()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist or points to wrong type.
+    ///
+    /// # Performance Notes
+    ///
+    /// - Field access: `config.database.host` (zero cost)
+    /// - Method calls: `config.validate()` (zero cost)
+    /// - Passing to functions: `process(config)` (~1ns to move Arc)
+    /// - Multiple reads share the same underlying data efficiently
     ///
     /// # Examples
     ///
@@ -498,13 +880,1059 @@ impl ConfigRegistry {
     /// use superconfig::ConfigRegistry;
     ///
     /// let registry = ConfigRegistry::new();
-    /// assert_eq!(registry.len(), 0);
-    ///
-    /// let _handle = registry.create("test".to_string()).unwrap();
-    /// assert_eq!(registry.len(), 1);
+    /// let handle = registry.create("test".to_string()).unwrap();
+    /// let data = registry.read(&handle).unwrap();
+    /// assert_eq!(*data, "test");
     /// ```
-    #[must_use]
-    pub fn len(&self) -> usize {
+    #[generate_json_helper(auto)]
+    pub fn read<T: 'static>(&self, handle: &ConfigHandle<T>) -> Result<Arc<T>, String> {
+        self.try_read(handle).map_err(|e| {
+            error!(target: "superconfig.registry", "{e}");
+            String::from(e)
+        })
+    }
+
+    /// Get configuration data as Arc<T>, with errors that don't allocate
+    ///
+    /// Same as [`read`](Self::read), but a failed read returns a [`RegistryError`]
+    /// without formatting a message or logging it, for hot paths that handle missing
+    /// or mistyped handles themselves. See [`errors`](super::errors).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RegistryError::HandleNotFound`] if the handle doesn't exist, or
+    /// [`RegistryError::WrongType`] if it points to another type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::{ConfigHandle, ConfigRegistry};
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(3_u32).unwrap();
+    ///
+    /// let retries = registry.try_read(&handle).map_or(1, |retries| *retries);
+    /// assert_eq!(retries, 3);
+    /// ```
+    #[inline]
+    pub fn try_read<T: 'static>(&self, handle: &ConfigHandle<T>) -> Result<Arc<T>, RegistryError> {
+        let now = self.tick();
+        let Some(entry) = self.entries.get(&handle.id()) else {
+            return self.parent.as_ref().map_or_else(
+                || Err(RegistryError::HandleNotFound(handle.id())),
+                |parent| parent.try_read(handle),
+            );
+        };
+        if self.limits.eviction == EvictionPolicy::LeastRecentlyUsed {
+            entry.last_accessed.store(now, Ordering::Relaxed);
+        }
+        let data = entry.get_arc_data::<T>();
+        drop(entry);
+
+        // Update statistics once the map guard is released
+        self.stats.record_read();
+
+        data
+    }
+
+    /// Read the data of an entry as a JSON tree, serialized once per generation
+    ///
+    /// The tree is cached in the entry, so later calls only clone an `Arc` until the
+    /// entry is updated. Counts as a read.
+    pub(crate) fn tree<T>(&self, handle: &ConfigHandle<T>) -> Result<Arc<serde_json::Value>, String>
+    where
+        T: Serialize + 'static,
+    {
+        let now = self.tick();
+        let Some(entry) = self.entries.get(&handle.id()) else {
+            if let Some(parent) = &self.parent {
+                return parent.tree(handle);
+            }
+            error!(target: "superconfig.registry", "Handle {} not found", handle.id());
+            return Err(format!(
+                "superconfig.registry: Handle {} not found",
+                handle.id()
+            ));
+        };
+        if self.limits.eviction == EvictionPolicy::LeastRecentlyUsed {
+            entry.last_accessed.store(now, Ordering::Relaxed);
+        }
+        let cached = entry.tree.get().cloned();
+        let generation = entry.generation;
+        let data = entry.get_arc_data::<T>();
+        drop(entry);
+        self.stats.record_read();
+        if let Some(tree) = cached {
+            return Ok(tree);
+        }
+
+        // Serialize outside the map guard, and cache the tree only if the entry still
+        // holds the data it was serialized from
+        let tree = Arc::new(serde_json::to_value(&*data?).map_err(|e| {
+            error!(target: "superconfig.registry", "Failed to serialize handle {}: {e}", handle.id());
+            format!(
+                "superconfig.registry: Failed to serialize handle {}: {e}",
+                handle.id()
+            )
+        })?);
+        if let Some(entry) = self.entries.get(&handle.id())
+            && entry.generation == generation
+        {
+            let _ = entry.tree.set(Arc::clone(&tree));
+        }
+        Ok(tree)
+    }
+
+    /// Get the generation of an entry: 1 when created, incremented by each update
+    ///
+    /// Callers caching the data of an entry can compare generations to know whether
+    /// their copy is stale, without reading the data. Checking a generation doesn't count
+    /// as a read.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create("v1".to_string()).unwrap();
+    /// assert_eq!(registry.generation(&handle).unwrap(), 1);
+    ///
+    /// registry.update(&handle, "v2".to_string()).unwrap();
+    /// assert_eq!(registry.generation(&handle).unwrap(), 2);
+    /// ```
+    pub fn generation<T>(&self, handle: &ConfigHandle<T>) -> Result<u64, String> {
+        if let Some(entry) = self.entries.get(&handle.id()) {
+            return Ok(entry.generation);
+        }
+        if let Some(parent) = &self.parent {
+            return parent.generation(handle);
+        }
+        error!(target: "superconfig.registry", "Handle {} not found", handle.id());
+        Err(format!(
+            "superconfig.registry: Handle {} not found",
+            handle.id()
+        ))
+    }
+
+    /// Read the data of an entry only if its generation is newer than `last_generation`
+    ///
+    /// Returns the data with its generation, to pass to the next call, or `None` if the
+    /// caller's copy is current. The data and generation are read together, so they
+    /// always match. Pass 0 to read unconditionally. Only calls returning data count as
+    /// reads.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist or points to wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(10_u32).unwrap();
+    ///
+    /// let (cached, generation) = registry.read_if_newer(&handle, 0).unwrap().unwrap();
+    /// assert_eq!(*cached, 10);
+    /// assert!(registry.read_if_newer(&handle, generation).unwrap().is_none());
+    ///
+    /// registry.update(&handle, 20).unwrap();
+    /// let (cached, _) = registry.read_if_newer(&handle, generation).unwrap().unwrap();
+    /// assert_eq!(*cached, 20);
+    /// ```
+    pub fn read_if_newer<T: 'static>(
+        &self,
+        handle: &ConfigHandle<T>,
+        last_generation: u64,
+    ) -> Result<Option<(Arc<T>, u64)>, String> {
+        let now = self.tick();
+        let Some(entry) = self.entries.get(&handle.id()) else {
+            if let Some(parent) = &self.parent {
+                return parent.read_if_newer(handle, last_generation);
+            }
+            error!(target: "superconfig.registry", "Handle {} not found", handle.id());
+            return Err(format!(
+                "superconfig.registry: Handle {} not found",
+                handle.id()
+            ));
+        };
+        if entry.generation <= last_generation {
+            return Ok(None);
+        }
+        if self.limits.eviction == EvictionPolicy::LeastRecentlyUsed {
+            entry.last_accessed.store(now, Ordering::Relaxed);
+        }
+        let data = entry
+            .get_arc_data::<T>()
+            .map(|data| (data, entry.generation));
+        drop(entry);
+
+        self.stats.record_read();
+        Ok(Some(data?))
+    }
+
+    /// Update data in a configuration handle
+    ///
+    /// This replaces the entire configuration data with new data.
+    /// Any existing Arc references will continue to point to the old data.
+    /// [`watch`](Self::watch) channels receive the new data, and subscribers receive a
+    /// [`RegistryEvent::Updated`]. Entries with a [validator](Self::set_validator) only
+    /// accept valid data.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist in the registry, or if the new
+    /// data breaks the entry's validator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create("old".to_string()).unwrap();
+    ///
+    /// registry.update(&handle, "new".to_string()).unwrap();
+    /// let data = registry.read(&handle).unwrap();
+    /// assert_eq!(*data, "new");
+    /// ```
+    pub fn update<T: 'static + Send + Sync>(
+        &self,
+        handle: &ConfigHandle<T>,
+        new_data: T,
+    ) -> Result<(), String> {
+        self.replace(handle, new_data, None)
+    }
+
+    /// Update data in a configuration handle only if its generation is still `generation`
+    ///
+    /// Compare-and-update for read-modify-write cycles: pass the
+    /// [generation](Self::generation) the new data was derived from, as returned with the
+    /// data by [`read_if_newer`](Self::read_if_newer). If another update came in between,
+    /// nothing is changed and an error is returned, so the caller can read again and
+    /// retry. Otherwise behaves like [`update`](Self::update).
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist in the registry, if its
+    /// generation is no longer `generation`, or if the new data breaks the entry's
+    /// validator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(1_u32).unwrap();
+    ///
+    /// let (current, generation) = registry.read_if_newer(&handle, 0).unwrap().unwrap();
+    /// registry.update(&handle, 10).unwrap();
+    ///
+    /// // The increment is based on stale data
+    /// assert!(registry.update_if_generation(&handle, *current + 1, generation).is_err());
+    /// assert_eq!(*registry.read(&handle).unwrap(), 10);
+    /// ```
+    pub fn update_if_generation<T: 'static + Send + Sync>(
+        &self,
+        handle: &ConfigHandle<T>,
+        new_data: T,
+        generation: u64,
+    ) -> Result<(), String> {
+        self.replace(handle, new_data, Some(generation))
+    }
+
+    /// Replace the data of an entry, if it is at `expected` generation when given
+    fn replace<T: 'static + Send + Sync>(
+        &self,
+        handle: &ConfigHandle<T>,
+        new_data: T,
+        expected: Option<u64>,
+    ) -> Result<(), String> {
+        self.check_valid(handle.id(), &new_data)?;
+        let mut new_entry = ConfigEntry::new(new_data, self.tick());
+        let new_size = new_entry.data_size as u64;
+        // Child registries override the parent's entry with a local one
+        let parent_generation = match &self.parent {
+            Some(parent) if !self.entries.contains_key(&handle.id()) => {
+                parent.generation(handle).ok()
+            }
+            _ => None,
+        };
+        let conflict = |actual: u64| {
+            format!(
+                "Handle {} was updated concurrently: generation {actual}, expected {}",
+                handle.id(),
+                expected.unwrap_or_default()
+            )
+        };
+        let capacity = self.limits.is_bounded().then(|| self.capacity.lock());
+
+        // A stale compare-and-update fails before anything is evicted to make room for it.
+        // Bounded registries hold the capacity lock, so no other update can get in between
+        if let Some(expected) = expected {
+            let actual = self
+                .entries
+                .get(&handle.id())
+                .map(|entry| entry.generation)
+                .or(parent_generation);
+            if let Some(actual) = actual.filter(|&actual| actual != expected) {
+                let message = conflict(actual);
+                error!(target: "superconfig.registry", "{message}");
+                return Err(format!("superconfig.registry: {message}"));
+            }
+        }
+        let evicted = match capacity {
+            Some(_) => {
+                self.make_room(new_size, parent_generation.is_none().then_some(handle.id()))?
+            }
+            None => Vec::new(),
+        };
+
+        // Replace the entry in place, so concurrent readers see either the old or the
+        // new data but never a missing handle. Its memory is added before it becomes
+        // visible, like in `create`, and statistics stay outside the map guard
+        self.stats.add_memory(new_size);
+        let replaced = match self.entries.entry(handle.id()) {
+            dashmap::Entry::Occupied(entry)
+                if expected.is_some_and(|expected| expected != entry.get().generation) =>
+            {
+                Err(conflict(entry.get().generation))
+            }
+            dashmap::Entry::Occupied(mut entry) => {
+                let entry = entry.get_mut();
+                new_entry.name = entry.name.take();
+                new_entry.generation = entry.generation + 1;
+                new_entry.ttl = entry.ttl;
+                // Watchers are notified under the entry's lock, so they receive concurrent
+                // updates in the order they were applied
+                self.notify_watchers::<T>(handle.id(), &new_entry);
+                Ok(Some(std::mem::replace(entry, new_entry)))
+            }
+            dashmap::Entry::Vacant(_)
+                if parent_generation.is_some()
+                    && expected.is_some_and(|expected| Some(expected) != parent_generation) =>
+            {
+                Err(conflict(parent_generation.unwrap_or_default()))
+            }
+            dashmap::Entry::Vacant(vacant) if parent_generation.is_some() => {
+                new_entry.generation = parent_generation.unwrap_or_default() + 1;
+                vacant.insert(new_entry);
+                Ok(None)
+            }
+            dashmap::Entry::Vacant(_) => {
+                Err(format!("Handle {} not found for update", handle.id()))
+            }
+        };
+
+        // Update statistics; the old data is dropped outside the map guard
+        match replaced {
+            Ok(Some(old_entry)) => self.stats.record_update(old_entry.data_size as u64),
+            // A new override in a child registry
+            Ok(None) => self.stats.record_create_without_memory(),
+            Err(message) => {
+                self.stats.remove_memory(new_size);
+                drop(capacity);
+                self.notify_evicted(evicted);
+                error!(target: "superconfig.registry", "{message}");
+                return Err(format!("superconfig.registry: {message}"));
+            }
+        }
+
+        drop(capacity);
+        self.notify_evicted(evicted);
+        self.write_through();
+        self.events.emit(&RegistryEvent::Updated {
+            handle_id: handle.id(),
+        });
+        Ok(())
+    }
+
+    /// Receive the data of an entry each time [`update`](Self::update) replaces it
+    ///
+    /// The channel gets every update made after `watch` returns, in order, and
+    /// disconnects when the entry is deleted, evicted, or cleared. Dropping the receiver
+    /// ends the watch. Sends never block, so slow receivers only queue data; an async
+    /// consumer can drain the channel from `spawn_blocking` or forward it to an async
+    /// channel. For a callback on any entry, use [`subscribe`](Self::subscribe) and
+    /// [`RegistryEvent::Updated`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle doesn't exist or points to wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(1_u32).unwrap();
+    /// let updates = registry.watch(&handle).unwrap();
+    ///
+    /// registry.update(&handle, 2).unwrap();
+    /// registry.update(&handle, 3).unwrap();
+    /// assert_eq!(*updates.recv().unwrap(), 2);
+    /// assert_eq!(*updates.recv().unwrap(), 3);
+    ///
+    /// registry.delete(&handle).unwrap();
+    /// assert!(updates.recv().is_err());
+    /// ```
+    pub fn watch<T: 'static + Send + Sync>(
+        &self,
+        handle: &ConfigHandle<T>,
+    ) -> Result<mpsc::Receiver<Arc<T>>, String> {
+        // Holding the entry while registering keeps a concurrent delete from missing
+        // the new sender
+        let entry = self.entries.get(&handle.id()).ok_or_else(|| {
+            error!(target: "superconfig.registry", "Handle {} not found for watch", handle.id());
+            format!(
+                "superconfig.registry: Handle {} not found for watch",
+                handle.id()
+            )
+        })?;
+        entry.get_arc_data::<T>()?;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut senders = self
+            .watchers
+            .entry(handle.id())
+            .or_insert_with(|| Box::new(Vec::<mpsc::Sender<Arc<T>>>::new()));
+        if let Some(senders) = senders.downcast_mut::<Vec<mpsc::Sender<Arc<T>>>>() {
+            senders.push(sender);
+        }
+        drop(senders);
+        drop(entry);
+        Ok(receiver)
+    }
+
+    /// Keep the data of an entry current without looking it up on each read
+    ///
+    /// [`update`](Self::update) and [`restore`](Self::restore) store the new data in
+    /// the returned pin as they replace the entry, so [`PinnedConfig::load`] never
+    /// touches the registry. Like [`watch`](Self::watch), pins follow the registry's own
+    /// entry: in a child registry, pin the child's override. See
+    /// [`pinned`](super::pinned).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle doesn't exist or points to wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create("info".to_string()).unwrap();
+    /// let level = registry.pin(&handle).unwrap();
+    ///
+    /// registry.update(&handle, "debug".to_string()).unwrap();
+    /// assert_eq!(**level.load(), "debug");
+    /// ```
+    pub fn pin<T: 'static + Send + Sync>(
+        &self,
+        handle: &ConfigHandle<T>,
+    ) -> Result<PinnedConfig<T>, String> {
+        // Holding the entry while registering keeps a concurrent update from replacing
+        // the data before the pin is stored
+        let entry = self.entries.get(&handle.id()).ok_or_else(|| {
+            error!(target: "superconfig.registry", "Handle {} not found for pin", handle.id());
+            format!(
+                "superconfig.registry: Handle {} not found for pin",
+                handle.id()
+            )
+        })?;
+        let data = entry.get_arc_data::<T>()?;
+
+        let mut stored = self
+            .pins
+            .entry(handle.id())
+            .or_insert_with(|| Box::new(PinSlot::new(data.clone().into())));
+        // A snapshot restore can have replaced the entry with data of another type
+        let existing = stored.downcast_ref::<PinSlot<T>>().cloned();
+        let slot = existing.unwrap_or_else(|| {
+            let slot = PinSlot::new(data.into());
+            *stored = Box::new(Arc::clone(&slot));
+            slot
+        });
+        drop(stored);
+        drop(entry);
+        Ok(PinnedConfig::new(slot))
+    }
+
+    /// Send the data of `entry`, about to replace entry `id`, to the watchers and pins
+    /// of `id`
+    fn notify_watchers<T: 'static>(&self, id: HandleId, entry: &ConfigEntry) {
+        if let (Some(slot), Ok(data)) = (self.pins.get(&id), entry.get_arc_data::<T>())
+            && let Some(slot) = slot.downcast_ref::<PinSlot<T>>()
+        {
+            slot.store(data);
+        }
+        let Some(mut senders) = self.watchers.get_mut(&id) else {
+            return;
+        };
+        let (Some(senders), Ok(data)) = (
+            senders.downcast_mut::<Vec<mpsc::Sender<Arc<T>>>>(),
+            entry.get_arc_data::<T>(),
+        ) else {
+            return;
+        };
+        // Receivers that were dropped end their watch
+        senders.retain(|sender| sender.send(Arc::clone(&data)).is_ok());
+    }
+
+    /// Next value of the access clock, only advanced when LRU eviction needs it
+    ///
+    /// The clock is a loom-modeled atomic, so call this before taking a map guard
+    /// (see `core::sync`).
+    fn tick(&self) -> u64 {
+        if self.limits.eviction == EvictionPolicy::LeastRecentlyUsed {
+            self.access_clock.fetch_add(1, Ordering::Relaxed)
+        } else {
+            0
+        }
+    }
+
+    /// Make room for an entry of `size` bytes, replacing the entry `replacing` if set
+    ///
+    /// Called with the capacity lock held. Returns the ids of the evicted entries.
+    fn make_room(&self, size: u64, replacing: Option<HandleId>) -> Result<Vec<HandleId>, String> {
+        let full = |reason: &str| {
+            error!(target: "superconfig.registry", "Registry full, {reason}");
+            format!("superconfig.registry: Registry full, {reason}")
+        };
+        let replaced_size = match replacing {
+            // Updates of missing handles report "not found" instead
+            Some(id) => match self.entries.get(&id) {
+                Some(entry) => entry.data_size as u64,
+                None => return Ok(Vec::new()),
+            },
+            None => 0,
+        };
+        let added = usize::from(replacing.is_none());
+
+        // An entry that can't fit in an otherwise empty registry never fits
+        if let Some(reason) = self.limits.exceeded(added, size) {
+            return Err(full(&format!("entry of {size} bytes exceeds the {reason}")));
+        }
+
+        let mut evicted = Vec::new();
+        loop {
+            let memory = (self.stats.memory_usage() + size).saturating_sub(replaced_size);
+            let Some(reason) = self.limits.exceeded(self.entries.len() + added, memory) else {
+                return Ok(evicted);
+            };
+            if self.limits.eviction == EvictionPolicy::RejectWhenFull {
+                return Err(full(&format!("{reason} reached")));
+            }
+
+            let victim = self
+                .entries
+                .iter()
+                .filter(|entry| Some(*entry.key()) != replacing)
+                .min_by_key(|entry| entry.last_accessed.load(Ordering::Relaxed))
+                .map(|entry| *entry.key());
+            let Some(id) = victim else {
+                return Err(full(&format!("{reason} reached")));
+            };
+            if let Some((_, entry)) = self.entries.remove(&id) {
+                self.stats.record_eviction(entry.data_size as u64);
+                self.release(&entry, id);
+                debug!(target: "superconfig.registry", "Evicted handle {id} ({})", entry.type_name);
+                evicted.push(id);
+            }
+        }
+    }
+
+    /// Take a recycled handle ID, or the next one of the counter
+    ///
+    /// The counter stops at `HandleId::MAX` instead of wrapping around to IDs that may
+    /// still be in use.
+    fn allocate_id(&self) -> Result<HandleId, String> {
+        if let Some(free_ids) = &self.free_ids
+            && let Some(id) = free_ids.lock().pop()
+        {
+            return Ok(id);
+        }
+        self.next_id
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .map_err(|_| {
+                error!(target: "superconfig.registry", "Handle IDs exhausted");
+                "superconfig.registry: Handle IDs exhausted, enable the RECYCLE_IDS startup flag to reuse deleted IDs".to_string()
+            })
+    }
+
+    /// Release the name, watchers, pins and validator of the removed entry `id`
+    ///
+    /// The name is kept if it was reused meanwhile. Dropping the watchers' senders
+    /// disconnects their channels.
+    fn release(&self, entry: &ConfigEntry, id: HandleId) {
+        if let Some(name) = &entry.name {
+            self.names.remove_if(name, |_, named| *named == id);
+        }
+        self.watchers.remove(&id);
+        self.pins.remove(&id);
+        self.validators.remove(&id);
+        if let Some(free_ids) = &self.free_ids {
+            free_ids.lock().push(id);
+        }
+    }
+
+    /// Tell subscribers about evicted entries, once the registry released its locks
+    fn notify_evicted(&self, evicted: Vec<HandleId>) {
+        for handle_id in evicted {
+            self.events.emit(&RegistryEvent::Evicted { handle_id });
+        }
+    }
+
+    /// Remove the entries whose TTL elapsed and return how many were removed
+    ///
+    /// Subscribers receive a [`RegistryEvent::Expired`] for each, and their names and
+    /// [`watch`](Self::watch) channels are released like on delete. Entries updated
+    /// while the pass runs are kept, since the update restarted their TTL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    /// use std::time::Duration;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create_with_ttl(1_u32, Duration::from_secs(60)).unwrap();
+    /// assert_eq!(registry.evict_expired(), 0);
+    /// assert!(registry.contains_handle(&handle));
+    /// ```
+    pub fn evict_expired(&self) -> usize {
+        let now = Instant::now();
+        let expired: Vec<HandleId> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.is_expired(now))
+            .map(|entry| *entry.key())
+            .collect();
+
+        let mut removed = Vec::with_capacity(expired.len());
+        for id in expired {
+            if let Some((_, entry)) = self
+                .entries
+                .remove_if(&id, |_, entry| entry.is_expired(now))
+            {
+                self.stats.record_expiration(entry.data_size as u64);
+                self.release(&entry, id);
+                removed.push(id);
+            }
+        }
+
+        if !removed.is_empty() {
+            debug!(target: "superconfig.registry", "Removed {} expired entries", removed.len());
+            self.write_through();
+        }
+        for &handle_id in &removed {
+            self.events.emit(&RegistryEvent::Expired { handle_id });
+        }
+        removed.len()
+    }
+
+    /// Run [`evict_expired`](Self::evict_expired) every `interval` on a background thread
+    ///
+    /// The thread holds no strong reference to the registry and stops within one
+    /// `interval` after the registry is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    /// use std::time::Duration;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let expiry = registry.expire_every(Duration::from_millis(5));
+    /// let handle = registry.create_with_ttl(1_u32, Duration::ZERO).unwrap();
+    ///
+    /// while registry.contains_handle(&handle) {
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    /// drop(registry);
+    /// expiry.join().unwrap();
+    /// ```
+    pub fn expire_every(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let registry: Weak<Self> = Arc::downgrade(self);
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+                let Some(registry) = registry.upgrade() else {
+                    break;
+                };
+                registry.evict_expired();
+            }
+        })
+    }
+
+    /// Delete a configuration entry and return the data as Arc<T>
+    ///
+    /// Returns the same Arc<T> that was stored internally, avoiding any cloning.
+    /// This is consistent with our zero-copy design philosophy.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist or points to wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create("test".to_string()).unwrap();
+    ///
+    /// let data = registry.delete(&handle).unwrap();
+    /// assert_eq!(*data, "test");
+    /// assert!(!registry.contains_handle(&handle));
+    /// ```
+    pub fn delete<T: 'static>(&self, handle: &ConfigHandle<T>) -> Result<Arc<T>, String> {
+        let (_, entry) = self.entries.remove(&handle.id()).ok_or_else(|| {
+            let error_msg = format!(
+                "superconfig.registry: Handle {} not found for delete",
+                handle.id()
+            );
+            error!(target: "superconfig.registry", "Handle {} not found for delete", handle.id());
+            error_msg
+        })?;
+
+        // Update statistics: the entry is gone even if it has the wrong type
+        self.stats.record_delete(entry.data_size as u64);
+        self.release(&entry, handle.id());
+        self.write_through();
+
+        // Extract the Arc<T> directly
+        let type_name = entry.type_name;
+        let arc = entry.data.downcast::<Arc<T>>().map_err(|_| {
+            let error_msg = format!("superconfig.registry: Wrong type for delete, expected {}, found {}", std::any::type_name::<T>(), type_name);
+            error!(target: "superconfig.registry", "Wrong type for delete, expected {}, found {}", std::any::type_name::<T>(), type_name);
+            error_msg
+        })?;
+
+        Ok(*arc)
+    }
+
+    /// Get current registry statistics
+    ///
+    /// The snapshot includes the current startup/runtime flags and the registry uptime.
+    /// `stats_as_json()` returns the same snapshot as a JSON string for FFI clients.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::{ConfigRegistry, config_flags::runtime};
+    ///
+    /// let registry = ConfigRegistry::new().enable(runtime::STRICT_MODE);
+    /// let stats = registry.stats();
+    /// assert_eq!(stats.total_handles, 0);
+    /// assert_eq!(stats.runtime_flags, runtime::STRICT_MODE);
+    ///
+    /// let json = registry.stats_as_json();
+    /// assert!(json.contains("\"uptime_ms\""));
+    /// ```
+    #[must_use]
+    #[generate_json_helper(outgoing)]
+    pub fn stats(&self) -> RegistryStats {
+        let mut stats = self.stats.snapshot();
+        stats.startup_flags = self.startup_flags;
+        stats.runtime_flags = *self.runtime_flags.read();
+        stats.uptime_ms = u64::try_from(self.created_at.elapsed().as_millis()).unwrap_or(u64::MAX);
+        stats.sources = self
+            .sources
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        stats.sources.sort_by(|a, b| a.name.cmp(&b.name));
+        stats.source_loads = self
+            .source_loads
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        stats.source_loads.sort_by(|a, b| a.name.cmp(&b.name));
+        stats.active_overlays = self.overlays.load(Ordering::Relaxed);
+        stats
+    }
+
+    /// Counter of live overlays, decremented by each `OverlayHandle` on drop
+    pub(crate) fn overlay_counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.overlays)
+    }
+
+    /// Store the health a source reported after a load
+    pub(crate) fn record_source_health(&self, health: SourceHealth) {
+        self.sources.insert(health.name.clone(), health);
+    }
+
+    /// Validator of the entry `id` for data of type `T`
+    pub(crate) fn validator<T: 'static>(&self, id: HandleId) -> Option<StoredValidator<T>> {
+        self.validators
+            .get(&id)
+            .and_then(|validator| validator.downcast_ref::<StoredValidator<T>>().cloned())
+    }
+
+    /// Validate the updates of the entry `id` with `validator`
+    pub(crate) fn store_validator<T: 'static>(&self, id: HandleId, validator: StoredValidator<T>) {
+        self.validators.insert(id, Box::new(validator));
+    }
+
+    /// Active profile and profiled entries, managed by the `profile` module
+    pub(crate) const fn profile_state(&self) -> &ProfileState {
+        &self.profiles
+    }
+
+    /// Notify subscribers of an event raised outside this module
+    pub(crate) fn emit_event(&self, event: &RegistryEvent) {
+        self.events.emit(event);
+    }
+
+    /// Count a load of the source `name` that took `duration`
+    pub(crate) fn record_source_load(&self, name: &str, duration: Duration, failed: bool) {
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        self.source_loads
+            .entry(name.to_string())
+            .or_insert_with(|| SourceLoadStats {
+                name: name.to_string(),
+                ..SourceLoadStats::default()
+            })
+            .record(micros, failed);
+    }
+
+    /// Check if a handle exists in the registry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create("test".to_string()).unwrap();
+    ///
+    /// assert!(registry.contains_handle(&handle));
+    /// registry.delete(&handle).unwrap();
+    /// assert!(!registry.contains_handle(&handle));
+    /// ```
+    #[must_use]
+    pub fn contains_handle<T>(&self, handle: &ConfigHandle<T>) -> bool {
+        self.entries.contains_key(&handle.id())
+    }
+
+    /// Get the type name of the data stored under a handle ID
+    ///
+    /// Returns `None` if the handle doesn't exist. Unlike a failed [`read`](Self::read),
+    /// this never logs an error, so it is safe to use for probing unknown handles.
+    /// `type_name_as_json()` returns the same information as a JSON string for FFI clients.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(8080_u16).unwrap();
+    ///
+    /// assert_eq!(registry.type_name(handle.id()), Some("u16"));
+    /// assert_eq!(registry.type_name(999), None);
+    /// ```
+    #[must_use]
+    #[generate_json_helper(outgoing)]
+    pub fn type_name(&self, handle_id: HandleId) -> Option<&'static str> {
+        self.entries
+            .get(&handle_id)
+            .map(|entry| entry.type_name)
+            .or_else(|| self.parent.as_ref()?.type_name(handle_id))
+    }
+
+    /// Check whether a handle points to data of type `T`
+    ///
+    /// Returns `false` if the handle doesn't exist or holds a different type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::{AnyConfigHandle, ConfigRegistry};
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = AnyConfigHandle::from(registry.create("test".to_string()).unwrap());
+    ///
+    /// assert!(registry.is_type::<String>(&handle));
+    /// assert!(!registry.is_type::<u32>(&handle));
+    /// ```
+    #[must_use]
+    pub fn is_type<T: 'static>(&self, handle: &AnyConfigHandle) -> bool {
+        self.type_name(handle.id()) == Some(std::any::type_name::<T>())
+    }
+
+    /// Get handles to every entry holding data of type `T`, ordered by handle ID
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let first = registry.create("a".to_string()).unwrap();
+    /// let _number = registry.create(42_u32).unwrap();
+    /// let second = registry.create("b".to_string()).unwrap();
+    ///
+    /// assert_eq!(registry.entries_by_type::<String>(), vec![first, second]);
+    /// ```
+    #[must_use]
+    pub fn entries_by_type<T: 'static>(&self) -> Vec<ConfigHandle<T>> {
+        let expected_type = std::any::type_name::<T>();
+        let mut handles: Vec<ConfigHandle<T>> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.type_name == expected_type)
+            .map(|entry| ConfigHandle::new(*entry.key()))
+            .collect();
+        handles.sort_by_key(ConfigHandle::id);
+        handles
+    }
+
+    /// List the live entries, ordered by handle ID
+    ///
+    /// The list is a snapshot taken before iteration starts, so the registry may be used
+    /// while iterating. Listing doesn't count as a read in the statistics and doesn't
+    /// refresh entries for [LRU eviction](crate::EvictionPolicy::LeastRecentlyUsed).
+    /// `handles_as_json()` returns the same list as a JSON string for FFI clients.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let host = registry.create("localhost".to_string()).unwrap();
+    /// let _port = registry.create(8080_u16).unwrap();
+    ///
+    /// let handles: Vec<_> = registry.handles().collect();
+    /// assert_eq!(handles.len(), 2);
+    /// assert_eq!(handles[0].id, host.id());
+    /// assert_eq!(handles[1].type_name, "u16");
+    /// assert_eq!(handles[1].size_bytes, 2);
+    ///
+    /// let json = registry.handles_as_json();
+    /// assert!(json.contains("\"type_name\":\"u16\""));
+    /// ```
+    pub fn handles(&self) -> impl ExactSizeIterator<Item = HandleInfo> + use<> {
+        let mut handles: Vec<HandleInfo> = self
+            .entries
+            .iter()
+            .map(|entry| HandleInfo {
+                id: *entry.key(),
+                type_name: entry.type_name,
+                name: entry.name.clone(),
+                generation: entry.generation,
+                created_at: entry.created_at,
+                age_ms: u64::try_from(entry.created_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+                size_bytes: entry.data_size,
+            })
+            .collect();
+        handles.sort_by_key(|info| info.id);
+        handles.into_iter()
+    }
+
+    /// [`handles`](Self::handles) as a JSON string for FFI clients
+    ///
+    /// Returns `{"success": true, "data": [...]}` like the other `*_as_json` methods.
+    #[must_use]
+    pub fn handles_as_json(&self) -> String {
+        let handles: Vec<HandleInfo> = self.handles().collect();
+        serde_json::json!({"success": true, "data": handles}).to_string()
+    }
+
+    /// Get every entry holding data of type `T` with its data, ordered by handle ID
+    ///
+    /// Like [`handles`](Self::handles), this is a snapshot that neither counts as reads
+    /// nor refreshes entries for LRU eviction. Use
+    /// [`entries_by_type`](Self::entries_by_type) for the handles alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let primary = registry.create("primary".to_string()).unwrap();
+    /// let _port = registry.create(8080_u16).unwrap();
+    /// let _replica = registry.create("replica".to_string()).unwrap();
+    ///
+    /// let found = registry.find_by_type::<String>();
+    /// assert_eq!(found.len(), 2);
+    /// assert_eq!(found[0].0, primary);
+    /// assert_eq!(*found[1].1, "replica");
+    /// ```
+    #[must_use]
+    pub fn find_by_type<T: 'static>(&self) -> Vec<(ConfigHandle<T>, Arc<T>)> {
+        let expected_type = std::any::type_name::<T>();
+        let mut found: Vec<(ConfigHandle<T>, Arc<T>)> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.type_name == expected_type)
+            .filter_map(|entry| {
+                let data = entry.data.downcast_ref::<Arc<T>>()?;
+                Some((ConfigHandle::new(*entry.key()), Arc::clone(data)))
+            })
+            .collect();
+        found.sort_by_key(|(handle, _)| handle.id());
+        found
+    }
+
+    /// Clear all entries from the registry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let _handle = registry.create("test".to_string()).unwrap();
+    ///
+    /// assert_eq!(registry.len(), 1);
+    /// registry.clear();
+    /// assert_eq!(registry.len(), 0);
+    /// ```
+    pub fn clear(&self) {
+        match &self.free_ids {
+            // Free exactly the IDs this clear removed, never one a concurrent delete freed
+            Some(free_ids) => self.entries.retain(|id, _| {
+                free_ids.lock().push(*id);
+                false
+            }),
+            None => self.entries.clear(),
+        }
+        self.names.clear();
+        self.watchers.clear();
+        self.pins.clear();
+        self.validators.clear();
+        self.stats.reset();
+        self.write_through();
+    }
+
+    /// Get the number of entries in the registry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::ConfigRegistry;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// assert_eq!(registry.len(), 0);
+    ///
+    /// let _handle = registry.create("test".to_string()).unwrap();
+    /// assert_eq!(registry.len(), 1);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
         self.entries.len()
     }
 
@@ -515,15 +1943,296 @@ impl ConfigRegistry {
     /// ```
     /// use superconfig::ConfigRegistry;
     ///
-    /// let registry = ConfigRegistry::new();
-    /// assert!(registry.is_empty());
+    /// let registry = ConfigRegistry::new();
+    /// assert!(registry.is_empty());
+    ///
+    /// let _handle = registry.create("test".to_string()).unwrap();
+    /// assert!(!registry.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// Snapshots
+
+/// Functions handling the entries of a type registered with `register_snapshot_type`
+struct SnapshotCodec {
+    encode: fn(&ConfigEntry) -> Result<serde_json::Value, String>,
+    decode: fn(serde_json::Value) -> Result<ConfigEntry, String>,
+    notify_watchers: fn(&ConfigRegistry, HandleId, &ConfigEntry),
+}
+
+impl SnapshotCodec {
+    fn of<T: 'static + Serialize + DeserializeOwned + Send + Sync>() -> Self {
+        Self {
+            encode: |entry| {
+                let data = entry.get_arc_data::<T>()?;
+                serde_json::to_value(&*data).map_err(|e| {
+                    format!(
+                        "superconfig.snapshot: Failed to serialize {}: {e}",
+                        entry.type_name
+                    )
+                })
+            },
+            decode: |value| {
+                serde_json::from_value::<T>(value)
+                    .map(|data| ConfigEntry::new(data, 0))
+                    .map_err(|e| {
+                        format!(
+                            "superconfig.snapshot: Failed to deserialize {}: {e}",
+                            std::any::type_name::<T>()
+                        )
+                    })
+            },
+            notify_watchers: ConfigRegistry::notify_watchers::<T>,
+        }
+    }
+}
+
+impl ConfigRegistry {
+    /// Include the entries holding `T` in [`snapshot`](Self::snapshot)s
+    ///
+    /// Types are identified by [`std::any::type_name`], so a snapshot restores into
+    /// another registry, or another run of the same program, that registered the same
+    /// types.
+    pub fn register_snapshot_type<T: 'static + Serialize + DeserializeOwned + Send + Sync>(&self) {
+        self.snapshot_types
+            .insert(std::any::type_name::<T>(), SnapshotCodec::of::<T>());
+    }
+
+    /// Copy the entries of registered types into a [`RegistrySnapshot`]
+    ///
+    /// Entries of other types are listed in [`RegistrySnapshot::skipped`]. Each entry
+    /// is copied atomically, but entries updated while the snapshot is taken may be
+    /// copied before or after the update. See the [module documentation](super::snapshot).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data of an entry fails to serialize.
+    pub fn snapshot(&self) -> Result<RegistrySnapshot, String> {
+        let mut snapshot = RegistrySnapshot {
+            next_id: self.next_id.load(Ordering::Relaxed),
+            ..RegistrySnapshot::default()
+        };
+        for entry in &self.entries {
+            let Some(codec) = self.snapshot_types.get(entry.type_name) else {
+                snapshot.skipped.push(*entry.key());
+                continue;
+            };
+            snapshot.entries.push(SnapshotEntry {
+                id: *entry.key(),
+                type_name: entry.type_name.to_string(),
+                name: entry.name.clone(),
+                data: (codec.encode)(&entry)?,
+            });
+        }
+        snapshot.entries.sort_by_key(|entry| entry.id);
+        snapshot.skipped.sort_unstable();
+        debug!(target: "superconfig.snapshot", "Snapshot of {} entries, {} skipped", snapshot.entries.len(), snapshot.skipped.len());
+        Ok(snapshot)
+    }
+
+    /// Replace the entries of the registry with those of `snapshot`
+    ///
+    /// Entries keep their handle IDs and names, so existing handles read the restored
+    /// data. Entries not in the snapshot are removed, including the ones it skipped.
+    /// Restored entries that replace live ones get a new [generation](Self::generation),
+    /// notify their [`watch`](Self::watch) channels and emit [`RegistryEvent::Updated`],
+    /// like an update. Like
+    /// [`clear`](Self::clear), restoring is not atomic with respect to concurrent
+    /// operations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the registry unchanged, if an entry's type isn't
+    /// registered, if its data fails to deserialize, or if the snapshot exceeds the
+    /// registry's [limits](Self::custom_with_limits).
+    pub fn restore(&self, snapshot: &RegistrySnapshot) -> Result<(), String> {
+        let fail = |error_msg: String| {
+            error!(target: "superconfig.snapshot", "{error_msg}");
+            error_msg
+        };
+
+        // Decode everything first, so a bad snapshot changes nothing
+        let mut restored = Vec::with_capacity(snapshot.entries.len());
+        for snapshot_entry in &snapshot.entries {
+            let codec = self
+                .snapshot_types
+                .get(snapshot_entry.type_name.as_str())
+                .ok_or_else(|| {
+                    fail(format!(
+                        "superconfig.snapshot: Type {} is not registered for snapshots",
+                        snapshot_entry.type_name
+                    ))
+                })?;
+            let mut entry = (codec.decode)(snapshot_entry.data.clone()).map_err(fail)?;
+            entry.name.clone_from(&snapshot_entry.name);
+            restored.push((snapshot_entry.id, entry, codec.notify_watchers));
+        }
+
+        let capacity = self.capacity.lock();
+        let memory = restored
+            .iter()
+            .map(|(_, entry, _)| entry.data_size as u64)
+            .sum::<u64>();
+        if let Some(reason) = self.limits.exceeded(restored.len(), memory) {
+            return Err(fail(format!(
+                "superconfig.snapshot: Snapshot exceeds the {reason}"
+            )));
+        }
+
+        // Remove the entries the snapshot doesn't have
+        let stale: Vec<HandleId> = self
+            .entries
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|id| snapshot.entry(*id).is_none())
+            .collect();
+        for id in stale {
+            if let Some((_, entry)) = self.entries.remove(&id) {
+                self.stats.record_delete(entry.data_size as u64);
+                self.release(&entry, id);
+            }
+        }
+
+        // Restored IDs are in use again
+        if let Some(free_ids) = &self.free_ids {
+            let restored_ids: std::collections::HashSet<HandleId> =
+                restored.iter().map(|(id, _, _)| *id).collect();
+            free_ids.lock().retain(|id| !restored_ids.contains(id));
+        }
+
+        let mut updated = Vec::new();
+        for (id, mut entry, notify_watchers) in restored {
+            entry.last_accessed.store(self.tick(), Ordering::Relaxed);
+            let size = entry.data_size as u64;
+            let name = entry.name.clone();
+            // Like `update` and `create`, memory is counted before the entry is visible
+            self.stats.add_memory(size);
+            let replaced = if let Some(mut current) = self.entries.get_mut(&id) {
+                entry.generation = current.generation + 1;
+                notify_watchers(self, id, &entry);
+                Some(std::mem::replace(&mut *current, entry))
+            } else {
+                self.entries.insert(id, entry);
+                None
+            };
+            if let Some(old) = replaced {
+                self.stats.record_update(old.data_size as u64);
+                if let Some(old_name) = old.name.filter(|old_name| Some(old_name) != name.as_ref())
+                {
+                    self.names.remove_if(&old_name, |_, named| *named == id);
+                }
+                updated.push(id);
+            } else {
+                self.stats.record_create_without_memory();
+            }
+            if let Some(name) = name {
+                self.names.insert(name, id);
+            }
+        }
+        self.next_id.fetch_max(snapshot.next_id, Ordering::Relaxed);
+        drop(capacity);
+        self.write_through();
+
+        for handle_id in updated {
+            self.events.emit(&RegistryEvent::Updated { handle_id });
+        }
+        Ok(())
+    }
+}
+
+// Storage
+
+impl ConfigRegistry {
+    /// Persist the registry to `backend` from now on, restoring what it holds first
+    ///
+    /// The snapshot the backend holds, if any, is [restored](Self::restore), so register
+    /// the persisted types with [`register_snapshot_type`](Self::register_snapshot_type)
+    /// before attaching. Afterwards, every create, update, delete, eviction, clear, and
+    /// restore writes a [snapshot](Self::snapshot) through to the backend. Write-through
+    /// failures don't fail the change, which is already applied: they are logged, and
+    /// [`persist`](Self::persist) retries. See the [module documentation](super::backend).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a backend is already attached, or if loading or restoring its
+    /// snapshot fails, in which case no backend is attached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::{ConfigRegistry, MemoryBackend};
+    /// use std::sync::Arc;
+    ///
+    /// let backend = Arc::new(MemoryBackend::new());
+    /// let registry = ConfigRegistry::new();
+    /// registry.register_snapshot_type::<String>();
+    /// registry.attach_storage(Arc::clone(&backend)).unwrap();
+    ///
+    /// let handle = registry.create("persisted".to_string()).unwrap();
+    /// let stored = backend.snapshot().unwrap();
+    /// assert_eq!(stored.entry(handle.id()).unwrap().data, "persisted");
+    /// ```
+    pub fn attach_storage(&self, backend: impl StorageBackend + 'static) -> Result<(), String> {
+        let fail = |error_msg: String| {
+            error!(target: "superconfig.backend", "{error_msg}");
+            error_msg
+        };
+        let already_attached =
+            || fail("superconfig.backend: A storage backend is already attached".to_string());
+
+        // Checked under the lock, so a concurrent attach can't write through mid-restore
+        let _persisting = self.persisting.lock();
+        if self.storage.get().is_some() {
+            return Err(already_attached());
+        }
+        let stored = backend.load().map_err(fail)?;
+        if let Some(snapshot) = &stored {
+            self.restore(snapshot)?;
+        }
+        self.storage
+            .set(Box::new(backend))
+            .map_err(|_| already_attached())?;
+        debug!(target: "superconfig.backend", "Storage attached, {} entries restored", stored.map_or(0, |snapshot| snapshot.entries.len()));
+        Ok(())
+    }
+
+    /// Whether a backend was attached with [`attach_storage`](Self::attach_storage)
+    #[must_use]
+    pub fn has_storage(&self) -> bool {
+        self.storage.get().is_some()
+    }
+
+    /// Write a snapshot to the attached backend now
+    ///
+    /// Changes are written through automatically; this retries after a failed write.
     ///
-    /// let _handle = registry.create("test".to_string()).unwrap();
-    /// assert!(!registry.is_empty());
-    /// ```
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.entries.is_empty()
+    /// # Errors
+    ///
+    /// Returns an error if no backend is attached, or if the snapshot fails to
+    /// serialize or store.
+    pub fn persist(&self) -> Result<(), String> {
+        let Some(backend) = self.storage.get() else {
+            error!(target: "superconfig.backend", "No storage backend attached");
+            return Err("superconfig.backend: No storage backend attached".to_string());
+        };
+        let _persisting = self.persisting.lock();
+        self.snapshot()
+            .and_then(|snapshot| backend.store(&snapshot))
+            .inspect_err(|error_msg| {
+                error!(target: "superconfig.backend", "Failed to persist registry: {error_msg}");
+            })
+    }
+
+    /// Write the registry through to the attached backend, if any
+    fn write_through(&self) {
+        if self.storage.get().is_some() {
+            // Failures are logged by `persist`, the change itself stays applied
+            let _ = self.persist();
+        }
     }
 }
 
@@ -720,6 +2429,69 @@ mod tests {
         assert!(error_msg.contains("found"));
     }
 
+    #[test]
+    fn test_resolve_handle_ids() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(SimpleConfig { value: 7 }).unwrap();
+        let tenant = registry.child();
+
+        let resolved = tenant.resolve::<SimpleConfig>(handle.id()).unwrap();
+        assert_eq!(tenant.read(&resolved).unwrap().value, 7);
+        assert_eq!(
+            tenant.resolve_dynamic(handle.id()).unwrap(),
+            AnyConfigHandle::new(handle.id())
+        );
+
+        let error = registry.resolve::<TestConfig>(handle.id()).unwrap_err();
+        assert_eq!(
+            error,
+            String::from(RegistryError::WrongType {
+                expected: std::any::type_name::<TestConfig>(),
+                found: std::any::type_name::<SimpleConfig>(),
+            })
+        );
+
+        registry.delete(&resolved).unwrap();
+        assert!(registry.resolve::<SimpleConfig>(resolved.id()).is_err());
+        assert!(tenant.resolve_dynamic(resolved.id()).is_err());
+        let json: serde_json::Value =
+            serde_json::from_str(&registry.resolve_dynamic_as_json(resolved.id())).unwrap();
+        assert_eq!(json["success"], false);
+    }
+
+    #[test]
+    fn test_try_read_errors() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(SimpleConfig { value: 1 }).unwrap();
+        assert_eq!(registry.try_read(&handle).unwrap().value, 1);
+
+        let wrong_handle = ConfigHandle::<TestConfig>::new(handle.id());
+        let error = registry.try_read(&wrong_handle).unwrap_err();
+        assert_eq!(
+            error,
+            RegistryError::WrongType {
+                expected: std::any::type_name::<TestConfig>(),
+                found: std::any::type_name::<SimpleConfig>(),
+            }
+        );
+        assert_eq!(
+            registry.read(&wrong_handle).unwrap_err(),
+            String::from(error)
+        );
+
+        let child = registry.child();
+        assert_eq!(child.try_read(&handle).unwrap().value, 1);
+        registry.delete(&handle).unwrap();
+        assert_eq!(
+            child.try_read(&handle).unwrap_err(),
+            RegistryError::HandleNotFound(handle.id())
+        );
+        assert_eq!(
+            child.read(&handle).unwrap_err(),
+            format!("superconfig.registry: Handle {} not found", handle.id())
+        );
+    }
+
     #[test]
     fn test_statistics() {
         let registry = ConfigRegistry::new();
@@ -825,6 +2597,35 @@ mod tests {
         assert!(stats.total_reads >= (num_threads * operations_per_thread) as u64);
     }
 
+    #[test]
+    fn test_update_keeps_handle_visible_to_readers() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(SimpleConfig { value: 0 }).unwrap();
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                for value in 1..=500 {
+                    registry.update(&handle, SimpleConfig { value }).unwrap();
+                }
+            });
+            scope.spawn(|| {
+                let mut last = 0;
+                for _ in 0..500 {
+                    let value = registry.read(&handle).unwrap().value;
+                    assert!(value >= last);
+                    last = value;
+                }
+            });
+        });
+
+        let stats = registry.stats();
+        assert_eq!(stats.total_updates, 500);
+        assert_eq!(
+            stats.memory_usage_bytes,
+            std::mem::size_of::<SimpleConfig>() as u64
+        );
+    }
+
     #[test]
     fn test_global_registry() {
         let config = SimpleConfig { value: 123 };
@@ -1222,6 +3023,72 @@ mod tests {
         println!("✅ read_as_json error: {json_result}");
     }
 
+    #[test]
+    fn test_runtime_type_inspection() {
+        let registry = ConfigRegistry::new();
+        let simple = registry.create(SimpleConfig { value: 1 }).unwrap();
+        let name = registry.create("name".to_string()).unwrap();
+        let other = registry.create(SimpleConfig { value: 2 }).unwrap();
+
+        assert!(
+            registry
+                .type_name(simple.id())
+                .unwrap()
+                .ends_with("SimpleConfig")
+        );
+        assert_eq!(registry.type_name(9999), None);
+
+        let any = AnyConfigHandle::from(name);
+        assert!(registry.is_type::<String>(&any));
+        assert!(!registry.is_type::<SimpleConfig>(&any));
+        assert!(!registry.is_type::<String>(&AnyConfigHandle::new(9999)));
+
+        assert_eq!(
+            registry.entries_by_type::<SimpleConfig>(),
+            vec![simple, other]
+        );
+        assert!(registry.entries_by_type::<TestConfig>().is_empty());
+
+        // Probing never counts as a read
+        assert_eq!(registry.stats().total_reads, 0);
+    }
+
+    #[test]
+    fn test_type_name_as_json() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(42_u32).unwrap();
+
+        let result: serde_json::Value =
+            serde_json::from_str(&registry.type_name_as_json(handle.id())).unwrap();
+        assert_eq!(result["success"], true);
+        assert_eq!(result["data"], "u32");
+
+        let missing: serde_json::Value =
+            serde_json::from_str(&registry.type_name_as_json(9999)).unwrap();
+        assert!(missing["data"].is_null());
+    }
+
+    #[test]
+    fn test_stats_as_json_includes_flags_and_uptime() {
+        let registry = ConfigRegistry::custom(startup::SIMD).enable(runtime::PARALLEL);
+        let handle = registry.create(SimpleConfig { value: 1 }).unwrap();
+        let _ = registry.read(&handle).unwrap();
+
+        let json_result = registry.stats_as_json();
+        let result: serde_json::Value = serde_json::from_str(&json_result).unwrap();
+
+        assert_eq!(result["success"], true);
+        assert_eq!(result["data"]["total_creates"], 1);
+        assert_eq!(result["data"]["total_reads"], 1);
+        assert_eq!(result["data"]["startup_flags"], startup::SIMD);
+        assert_eq!(result["data"]["runtime_flags"], runtime::PARALLEL);
+        assert!(result["data"]["uptime_ms"].is_u64());
+
+        // The payload deserializes back into RegistryStats
+        let stats: RegistryStats = serde_json::from_value(result["data"].clone()).unwrap();
+        assert_eq!(stats.total_handles, 1);
+    }
+
     #[test]
     fn test_read_as_json_with_string_data() {
         let registry = ConfigRegistry::new();
@@ -1412,6 +3279,68 @@ mod tests {
         println!("✅ get_runtime_flags: returns correct flag values");
     }
 
+    #[test]
+    fn test_flag_changes_notify_subscribers() {
+        use crate::config_flags::runtime;
+        use std::sync::Mutex;
+
+        let registry = ConfigRegistry::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&events);
+        let observer = Arc::clone(&registry);
+        let id = registry.subscribe(move |event| {
+            // Subscribers run after the flags lock is released
+            assert!(observer.runtime_enabled(runtime::PARALLEL) || event.disabled_flags() != 0);
+            seen.lock().unwrap().push(event.clone());
+        });
+
+        let registry = registry
+            .enable(runtime::PARALLEL)
+            .enable(runtime::PARALLEL) // Already on: no event
+            .enable(0xFFFF_0000) // Invalid: no event
+            .disable(runtime::STRICT_MODE) // Already off: no event
+            .disable(runtime::PARALLEL);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                RegistryEvent::FlagsChanged {
+                    previous: 0,
+                    current: runtime::PARALLEL,
+                },
+                RegistryEvent::FlagsChanged {
+                    previous: runtime::PARALLEL,
+                    current: 0,
+                },
+            ]
+        );
+
+        assert!(registry.unsubscribe(id));
+        let _registry = registry.enable(runtime::STRICT_MODE);
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_flags_snapshot_names_every_runtime_flag() {
+        use crate::config_flags::{RUNTIME_FLAG_NAMES, runtime};
+
+        let registry = ConfigRegistry::new().enable(runtime::STRICT_MODE | runtime::ENV_EXPANSION);
+        let snapshot = registry.flags_snapshot();
+
+        assert_eq!(snapshot.len(), RUNTIME_FLAG_NAMES.len());
+        assert!(snapshot["strict_mode"]);
+        assert!(snapshot["env_expansion"]);
+        assert!(!snapshot["array_merge"]);
+        assert!(!snapshot["parallel"]);
+        assert!(!snapshot["format_fallback"]);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&registry.flags_snapshot_as_json()).unwrap();
+        assert_eq!(json["success"], true);
+        assert_eq!(json["data"]["strict_mode"], true);
+        assert_eq!(json["data"]["parallel"], false);
+    }
+
     #[test]
     fn test_update_handle_not_found() {
         let registry = ConfigRegistry::new();
@@ -1459,6 +3388,11 @@ mod tests {
         assert!(error_msg.contains("expected"));
         assert!(error_msg.contains("found"));
 
+        // The entry is removed either way, and the statistics follow
+        assert!(!registry.contains_handle(&handle));
+        assert_eq!(registry.stats().total_handles, 0);
+        assert_eq!(registry.stats().memory_usage_bytes, 0);
+
         println!("✅ Delete wrong type: error handled correctly");
     }
 
@@ -1705,4 +3639,640 @@ mod tests {
 
         println!("✅ Logging macro coverage test completed with assertions");
     }
+
+    #[test]
+    fn test_handles_lists_live_entries() {
+        let registry = ConfigRegistry::new();
+        let config = registry
+            .create(TestConfig {
+                host: "localhost".to_string(),
+                port: 8080,
+                timeout_ms: 100,
+            })
+            .unwrap();
+        let deleted = registry.create(1_u8).unwrap();
+        let flag = registry.create(true).unwrap();
+        registry.delete(&deleted).unwrap();
+
+        let handles: Vec<HandleInfo> = registry.handles().collect();
+        let ids: Vec<HandleId> = handles.iter().map(|info| info.id).collect();
+        assert_eq!(ids, vec![config.id(), flag.id()]);
+        assert_eq!(handles[0].type_name, std::any::type_name::<TestConfig>());
+        assert_eq!(handles[0].size_bytes, std::mem::size_of::<TestConfig>());
+        assert_eq!(handles[1].handle(), AnyConfigHandle::from(flag));
+
+        // Listing is not a read
+        assert_eq!(registry.stats().total_reads, 0);
+
+        let json: serde_json::Value = serde_json::from_str(&registry.handles_as_json()).unwrap();
+        assert_eq!(json["data"][1]["type_name"], "bool");
+        assert_eq!(json["data"][1]["size_bytes"], 1);
+        assert!(json["data"][1].get("created_at").is_none());
+    }
+
+    #[test]
+    fn test_find_by_type_returns_data() {
+        let registry = ConfigRegistry::new();
+        let first = registry.create(1_u32).unwrap();
+        let _other = registry.create(2_u64).unwrap();
+        let second = registry.create(3_u32).unwrap();
+
+        let found = registry.find_by_type::<u32>();
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].0, *found[0].1), (first, 1));
+        assert_eq!((found[1].0, *found[1].1), (second, 3));
+        assert!(registry.find_by_type::<String>().is_empty());
+    }
+
+    #[test]
+    fn test_named_entries_follow_their_lifecycle() {
+        let limits = RegistryLimits::default()
+            .with_max_entries(2)
+            .with_eviction(EvictionPolicy::LeastRecentlyUsed);
+        let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+
+        let db = registry
+            .create_named("app.db", "sqlite".to_string())
+            .unwrap();
+        registry.update(&db, "postgres".to_string()).unwrap();
+        assert_eq!(registry.lookup::<String>("app.db").unwrap(), db);
+        assert_eq!(
+            registry.handles().next().unwrap().name.as_deref(),
+            Some("app.db")
+        );
+
+        // Deleting releases the name for a new entry
+        registry.delete(&db).unwrap();
+        assert!(registry.lookup_any("app.db").is_none());
+        let db = registry.create_named("app.db", 5432_u16).unwrap();
+
+        // So does eviction
+        let _first = registry.create(1_u8).unwrap();
+        let _second = registry.create(2_u8).unwrap();
+        assert!(!registry.contains_handle(&db));
+        assert!(
+            registry
+                .lookup::<u16>("app.db")
+                .unwrap_err()
+                .contains("not found")
+        );
+
+        // A failed create doesn't keep the name reserved
+        let strict = ConfigRegistry::custom_with_limits(
+            startup::NO_FLAGS,
+            RegistryLimits::default().with_max_entries(0),
+        );
+        assert!(strict.create_named("app.db", 1_u8).is_err());
+        assert!(strict.lookup_any("app.db").is_none());
+    }
+
+    #[test]
+    fn test_watch_receives_updates_until_removed() {
+        let limits = RegistryLimits::default()
+            .with_max_entries(1)
+            .with_eviction(EvictionPolicy::LeastRecentlyUsed);
+        let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+        let updated = Arc::new(AtomicU32::new(0));
+        let seen = Arc::clone(&updated);
+        registry.subscribe(move |event| {
+            if matches!(event, RegistryEvent::Updated { .. }) {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        let handle = registry.create(0_u32).unwrap();
+        let first = registry.watch(&handle).unwrap();
+        let second = registry.watch(&handle).unwrap();
+        drop(second);
+
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let registry = Arc::clone(&registry);
+                thread::spawn(move || {
+                    for value in 1..=25 {
+                        registry.update(&handle, writer * 100 + value).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        // Each writer's updates arrive in order, and the last one received is stored
+        let received: Vec<u32> = first.try_iter().map(|data| *data).collect();
+        assert_eq!(received.len(), 100);
+        for writer in 0..4 {
+            let values: Vec<u32> = received
+                .iter()
+                .copied()
+                .filter(|value| value / 100 == writer)
+                .collect();
+            assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        assert_eq!(*registry.read(&handle).unwrap(), *received.last().unwrap());
+        assert_eq!(updated.load(Ordering::SeqCst), 100);
+
+        // Eviction disconnects the channel
+        let _other = registry.create(1_u8).unwrap();
+        assert!(first.recv().is_err());
+
+        let missing = registry.watch(&handle).unwrap_err();
+        assert!(missing.contains("not found for watch"));
+        let other = registry.create("text".to_string()).unwrap();
+        let wrong = ConfigHandle::<u32>::new(other.id());
+        assert!(registry.watch(&wrong).unwrap_err().contains("Wrong type"));
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let registry = ConfigRegistry::new();
+        registry.register_snapshot_type::<TestConfig>();
+        registry.register_snapshot_type::<u32>();
+        let config = TestConfig {
+            host: "localhost".to_string(),
+            port: 8080,
+            timeout_ms: 100,
+        };
+        let server = registry.create_named("server", config.clone()).unwrap();
+        let retries = registry.create(3_u32).unwrap();
+        let unregistered = registry.create(SimpleConfig { value: 1 }).unwrap();
+
+        let snapshot = registry.snapshot().unwrap();
+        assert_eq!(snapshot.entries.len(), 2);
+        assert_eq!(snapshot.skipped, vec![unregistered.id()]);
+        assert_eq!(
+            snapshot.entry(server.id()).unwrap().name.as_deref(),
+            Some("server")
+        );
+        assert_eq!(
+            snapshot.entry(retries.id()).unwrap().data,
+            serde_json::json!(3)
+        );
+
+        // A bad live update, plus entries created and deleted since
+        let updates = registry.watch(&server).unwrap();
+        registry
+            .update(
+                &server,
+                TestConfig {
+                    port: 0,
+                    ..config.clone()
+                },
+            )
+            .unwrap();
+        registry.delete(&retries).unwrap();
+        let added = registry.create(7_u32).unwrap();
+
+        registry.restore(&snapshot).unwrap();
+        assert_eq!(*registry.read(&server).unwrap(), config);
+        assert_eq!(*registry.read(&retries).unwrap(), 3);
+        assert!(!registry.contains_handle(&added));
+        assert!(!registry.contains_handle(&unregistered));
+        assert_eq!(registry.lookup::<TestConfig>("server").unwrap(), server);
+        assert_eq!(
+            updates.try_iter().map(|data| data.port).collect::<Vec<_>>(),
+            vec![0, 8080]
+        );
+        assert_eq!(registry.stats().total_handles, 2);
+
+        // New entries never reuse the IDs of restored ones
+        assert!(registry.create(1_u32).unwrap().id() > added.id());
+    }
+
+    #[test]
+    fn test_restore_rejects_unknown_types_without_changes() {
+        let registry = ConfigRegistry::new();
+        registry.register_snapshot_type::<String>();
+        let handle = registry.create("kept".to_string()).unwrap();
+
+        let mut snapshot = registry.snapshot().unwrap();
+        snapshot.entries.push(SnapshotEntry {
+            id: 42,
+            type_name: "app::Unknown".to_string(),
+            name: None,
+            data: serde_json::Value::Null,
+        });
+        let error = registry.restore(&snapshot).unwrap_err();
+        assert!(error.contains("Type app::Unknown is not registered"));
+
+        snapshot.entries.pop();
+        snapshot.entries[0].data = serde_json::json!(5);
+        assert!(
+            registry
+                .restore(&snapshot)
+                .unwrap_err()
+                .contains("Failed to deserialize")
+        );
+        assert_eq!(*registry.read(&handle).unwrap(), "kept");
+    }
+
+    #[test]
+    fn test_generations_track_updates() {
+        let registry = ConfigRegistry::new();
+        registry.register_snapshot_type::<u32>();
+        let handle = registry.create(1_u32).unwrap();
+        let snapshot = registry.snapshot().unwrap();
+
+        registry.update(&handle, 2).unwrap();
+        registry.update(&handle, 3).unwrap();
+        assert_eq!(registry.generation(&handle).unwrap(), 3);
+        assert_eq!(registry.handles().next().unwrap().generation, 3);
+
+        // Restoring older data is still a newer generation
+        registry.restore(&snapshot).unwrap();
+        let (data, generation) = registry.read_if_newer(&handle, 3).unwrap().unwrap();
+        assert_eq!((*data, generation), (1, 4));
+
+        // Up-to-date checks don't count as reads; mismatched types still fail
+        let reads = registry.stats().total_reads;
+        assert!(registry.read_if_newer(&handle, 4).unwrap().is_none());
+        assert_eq!(registry.stats().total_reads, reads);
+        let wrong = ConfigHandle::<String>::new(handle.id());
+        assert!(
+            registry
+                .read_if_newer(&wrong, 0)
+                .unwrap_err()
+                .contains("Wrong type")
+        );
+
+        registry.delete(&handle).unwrap();
+        assert!(
+            registry
+                .generation(&handle)
+                .unwrap_err()
+                .contains("not found")
+        );
+    }
+
+    #[test]
+    fn test_update_if_generation_detects_conflicts() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(1_u32).unwrap();
+
+        registry.update_if_generation(&handle, 2, 1).unwrap();
+        let memory = registry.stats().memory_usage_bytes;
+        let error = registry.update_if_generation(&handle, 3, 1).unwrap_err();
+        assert!(error.contains("updated concurrently"));
+        assert_eq!(*registry.read(&handle).unwrap(), 2);
+        assert_eq!(registry.generation(&handle).unwrap(), 2);
+        assert_eq!(registry.stats().total_updates, 1);
+        assert_eq!(registry.stats().memory_usage_bytes, memory);
+
+        // A child's first override compares against the parent's generation
+        let parent = Arc::new(registry);
+        let child = parent.child();
+        assert!(child.update_if_generation(&handle, 4, 1).is_err());
+        child.update_if_generation(&handle, 4, 2).unwrap();
+        assert_eq!(child.generation(&handle).unwrap(), 3);
+        assert_eq!(*parent.read(&handle).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_stale_update_if_generation_evicts_nothing() {
+        let limits = RegistryLimits::default()
+            .with_max_memory_bytes(2)
+            .with_eviction(EvictionPolicy::LeastRecentlyUsed);
+        let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+        let first = registry.create(1_u8).unwrap();
+        let second = registry.create(2_u8).unwrap();
+        let updates = registry.watch(&second).unwrap();
+        registry.update(&first, 3).unwrap();
+
+        // Replacing `first` with larger data would evict `second`, but the update is stale
+        let wider = ConfigHandle::<u16>::new(first.id());
+        let error = registry.update_if_generation(&wider, 4, 1).unwrap_err();
+        assert!(error.contains("updated concurrently"));
+        assert!(registry.contains_handle(&second));
+        assert_eq!(registry.stats().total_evictions, 0);
+        assert!(matches!(updates.try_recv(), Err(mpsc::TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_child_registry_falls_back_to_parent() {
+        let global = ConfigRegistry::new().enable(runtime::PARALLEL);
+        let timeout = global.create_named("timeout_ms", 1000_u32).unwrap();
+        let shared = global.create(true).unwrap();
+
+        let tenant = global.child();
+        assert!(tenant.runtime_enabled(runtime::PARALLEL));
+        assert!(Arc::ptr_eq(tenant.parent().unwrap(), &global));
+
+        // IDs are shared, so child entries never shadow parent entries by accident
+        let local = tenant.create(7_u8).unwrap();
+        assert!(local.id() > shared.id());
+        assert!(global.read(&local).is_err());
+
+        // Names and handles fall through; overrides stay in the child
+        let override_name = tenant.create_named("timeout_ms", 50_u32).unwrap();
+        assert_eq!(tenant.lookup::<u32>("timeout_ms").unwrap(), override_name);
+        assert_eq!(global.lookup::<u32>("timeout_ms").unwrap(), timeout);
+        assert!(tenant.read(&shared).unwrap().as_ref());
+        assert!(tenant.is_type::<bool>(&AnyConfigHandle::from(shared)));
+
+        tenant.update(&timeout, 250).unwrap();
+        assert_eq!(*tenant.read(&timeout).unwrap(), 250);
+        assert_eq!(*global.read(&timeout).unwrap(), 1000);
+        assert_eq!(tenant.generation(&timeout).unwrap(), 2);
+        assert_eq!(tenant.stats().total_handles, 3);
+
+        // Deleting the override reveals the parent's entry
+        tenant.delete(&timeout).unwrap();
+        assert_eq!(*tenant.read(&timeout).unwrap(), 1000);
+        assert!(tenant.delete(&timeout).is_err());
+
+        tenant.clear();
+        assert!(tenant.is_empty());
+        assert_eq!(global.len(), 2);
+        assert_eq!(tenant.lookup::<u32>("timeout_ms").unwrap(), timeout);
+        assert!(tenant.update(&ConfigHandle::<u32>::new(999), 1).is_err());
+    }
+
+    #[test]
+    fn test_storage_writes_changes_through() {
+        use crate::core::backend::MemoryBackend;
+
+        let backend = Arc::new(MemoryBackend::new());
+        let registry = ConfigRegistry::new();
+        registry.register_snapshot_type::<u32>();
+        registry.attach_storage(Arc::clone(&backend)).unwrap();
+        assert!(registry.has_storage());
+
+        let workers = registry.create_named("workers", 4_u32).unwrap();
+        let retries = registry.create(3_u32).unwrap();
+        registry.update(&workers, 8).unwrap();
+        registry.delete(&retries).unwrap();
+
+        let stored = backend.snapshot().unwrap();
+        assert_eq!(stored.entries.len(), 1);
+        assert_eq!(stored.entry(workers.id()).unwrap().data, 8);
+
+        // A restarted registry gets the entries back under the same handles
+        let restarted = ConfigRegistry::new();
+        restarted.register_snapshot_type::<u32>();
+        restarted.attach_storage(Arc::clone(&backend)).unwrap();
+        assert_eq!(*restarted.read(&workers).unwrap(), 8);
+        assert_eq!(restarted.lookup::<u32>("workers").unwrap(), workers);
+        assert!(restarted.create(1_u32).unwrap().id() > retries.id());
+
+        restarted.clear();
+        assert!(backend.snapshot().unwrap().entries.is_empty());
+    }
+
+    #[test]
+    fn test_storage_attach_errors() {
+        use crate::core::backend::{MemoryBackend, StorageBackend};
+
+        let registry = ConfigRegistry::new();
+        assert!(
+            registry
+                .persist()
+                .unwrap_err()
+                .contains("No storage backend")
+        );
+        registry.attach_storage(MemoryBackend::new()).unwrap();
+        assert!(
+            registry
+                .attach_storage(MemoryBackend::new())
+                .unwrap_err()
+                .contains("already attached")
+        );
+
+        // Snapshots of unregistered types don't restore, and attach nothing
+        let backend = MemoryBackend::new();
+        let source = ConfigRegistry::new();
+        source.register_snapshot_type::<String>();
+        source.create("text".to_string()).unwrap();
+        backend.store(&source.snapshot().unwrap()).unwrap();
+        let registry = ConfigRegistry::new();
+        assert!(registry.attach_storage(backend).is_err());
+        assert!(!registry.has_storage());
+    }
+
+    #[test]
+    fn test_storage_failures_keep_changes() {
+        use crate::core::backend::StorageBackend;
+
+        /// Backend failing to store until enabled
+        #[derive(Default)]
+        struct Flaky {
+            available: std::sync::atomic::AtomicBool,
+            stores: AtomicU32,
+        }
+
+        impl StorageBackend for Flaky {
+            fn load(&self) -> Result<Option<RegistrySnapshot>, String> {
+                Ok(None)
+            }
+
+            fn store(&self, _snapshot: &RegistrySnapshot) -> Result<(), String> {
+                if self.available.load(Ordering::Relaxed) {
+                    self.stores.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                } else {
+                    Err("disk full".to_string())
+                }
+            }
+        }
+
+        let backend = Arc::new(Flaky::default());
+        let registry = ConfigRegistry::new();
+        registry.attach_storage(Arc::clone(&backend)).unwrap();
+
+        let handle = registry.create(1_u32).unwrap();
+        assert_eq!(*registry.read(&handle).unwrap(), 1);
+        assert_eq!(registry.persist().unwrap_err(), "disk full");
+
+        backend.available.store(true, Ordering::Relaxed);
+        registry.persist().unwrap();
+        assert_eq!(backend.stores.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_recycle_ids_reuses_removed_ids() {
+        let registry = ConfigRegistry::custom(startup::RECYCLE_IDS);
+        let first = registry.create(1_u32).unwrap();
+        let second = registry.create(2_u32).unwrap();
+        registry.delete(&first).unwrap();
+
+        let reused = registry.create(3_u32).unwrap();
+        assert_eq!(reused.id(), first.id());
+        assert_eq!(registry.create(4_u32).unwrap().id(), 3);
+
+        // Cleared IDs are reused too, so IDs stay bounded by the peak entry count
+        registry.clear();
+        for _ in 0..3 {
+            assert!(registry.create(0_u32).unwrap().id() <= 3);
+        }
+        assert!(!registry.contains_handle(&ConfigHandle::<u32>::new(4)));
+        assert_eq!(*registry.read(&second).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_recycle_ids_skips_restored_ids() {
+        let registry = ConfigRegistry::custom(startup::RECYCLE_IDS);
+        registry.register_snapshot_type::<u32>();
+        let kept = registry.create(1_u32).unwrap();
+        let snapshot = registry.snapshot().unwrap();
+
+        registry.delete(&kept).unwrap();
+        registry.restore(&snapshot).unwrap();
+        assert_ne!(registry.create(2_u32).unwrap(), kept);
+        assert_eq!(*registry.read(&kept).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_handle_ids_never_wrap_around() {
+        let registry = ConfigRegistry::new();
+        registry.next_id.store(HandleId::MAX - 1, Ordering::Relaxed);
+
+        assert_eq!(registry.create(1_u32).unwrap().id(), HandleId::MAX - 1);
+        let error = registry.create(2_u32).unwrap_err();
+        assert!(error.contains("Handle IDs exhausted"));
+        assert_eq!(registry.len(), 1);
+
+        // Recycled IDs keep a registry going past the end of the counter
+        let registry = ConfigRegistry::custom(startup::RECYCLE_IDS);
+        let handle = registry.create(1_u32).unwrap();
+        registry.next_id.store(HandleId::MAX, Ordering::Relaxed);
+        registry.delete(&handle).unwrap();
+        assert_eq!(registry.create(2_u32).unwrap(), handle);
+    }
+
+    #[test]
+    fn test_evict_expired_removes_elapsed_entries() {
+        let registry = ConfigRegistry::new();
+        let expired_ids = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&expired_ids);
+        registry.subscribe(move |event| {
+            if let RegistryEvent::Expired { handle_id } = event {
+                seen.lock().push(*handle_id);
+            }
+        });
+
+        let cached = registry
+            .create_with_ttl(1_u8, Duration::from_millis(20))
+            .unwrap();
+        let fresh = registry
+            .create_with_ttl(2_u32, Duration::from_secs(90))
+            .unwrap();
+        let local = registry.create(3_u64).unwrap();
+
+        thread::sleep(Duration::from_millis(30));
+        // Expired entries stay readable until the pass removes them
+        assert_eq!(*registry.read(&cached).unwrap(), 1);
+        assert_eq!(registry.evict_expired(), 1);
+        assert_eq!(registry.evict_expired(), 0);
+
+        assert!(!registry.contains_handle(&cached));
+        assert!(registry.contains_handle(&fresh));
+        assert!(registry.contains_handle(&local));
+        assert_eq!(*expired_ids.lock(), vec![cached.id()]);
+        let stats = registry.stats();
+        assert_eq!(stats.total_expirations, 1);
+        assert_eq!(stats.total_handles, 2);
+        assert_eq!(stats.memory_usage_bytes, 12);
+    }
+
+    #[test]
+    fn test_update_restarts_ttl() {
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_with_ttl("token".to_string(), Duration::from_millis(200))
+            .unwrap();
+        let updates = registry.watch(&handle).unwrap();
+
+        thread::sleep(Duration::from_millis(150));
+        registry.update(&handle, "refreshed".to_string()).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(registry.evict_expired(), 0);
+
+        thread::sleep(Duration::from_millis(150));
+        assert_eq!(registry.evict_expired(), 1);
+        // Watchers are released with the entry
+        assert_eq!(*updates.recv().unwrap(), "refreshed");
+        assert!(updates.recv().is_err());
+    }
+
+    #[test]
+    fn test_limits_reject_when_full() {
+        let limits = RegistryLimits::default().with_max_entries(2);
+        let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+
+        let first = registry.create(1_u32).unwrap();
+        let _second = registry.create(2_u32).unwrap();
+        let error = registry.create(3_u32).unwrap_err();
+        assert!(error.contains("Registry full, limit of 2 entries reached"));
+        assert_eq!(registry.len(), 2);
+
+        // Updates replace an entry, so they fit; deletes free a slot
+        registry.update(&first, 10).unwrap();
+        registry.delete(&first).unwrap();
+        assert!(registry.create(3_u32).is_ok());
+        assert_eq!(registry.stats().total_evictions, 0);
+    }
+
+    #[test]
+    fn test_limits_evict_least_recently_used() {
+        let limits = RegistryLimits::default()
+            .with_max_entries(3)
+            .with_eviction(EvictionPolicy::LeastRecentlyUsed);
+        let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+        let evicted = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&evicted);
+        registry.subscribe(move |event| {
+            if let RegistryEvent::Evicted { handle_id } = event {
+                seen.lock().push(*handle_id);
+            }
+        });
+
+        let alpha = registry.create("a".to_string()).unwrap();
+        let beta = registry.create("b".to_string()).unwrap();
+        let gamma = registry.create("c".to_string()).unwrap();
+        registry.read(&alpha).unwrap();
+        registry.update(&beta, "b2".to_string()).unwrap();
+
+        // `gamma` is now the least recently used entry, then `alpha`
+        let delta = registry.create("d".to_string()).unwrap();
+        let epsilon = registry.create("e".to_string()).unwrap();
+        assert_eq!(*evicted.lock(), vec![gamma.id(), alpha.id()]);
+        assert!(registry.read(&gamma).unwrap_err().contains("not found"));
+        for handle in [&beta, &delta, &epsilon] {
+            assert!(registry.contains_handle(handle));
+        }
+
+        let stats = registry.stats();
+        assert_eq!(stats.total_evictions, 2);
+        assert_eq!(stats.total_handles, 3);
+        assert_eq!(stats.total_deletes, 0);
+    }
+
+    #[test]
+    fn test_limits_bound_memory() {
+        let limits = RegistryLimits::default()
+            .with_max_memory_bytes(64)
+            .with_eviction(EvictionPolicy::LeastRecentlyUsed);
+        let registry = ConfigRegistry::custom_with_limits(startup::NO_FLAGS, limits);
+
+        let small = registry.create([0_u8; 16]).unwrap();
+        let large = registry.create([0_u8; 32]).unwrap();
+        registry.read(&small).unwrap();
+
+        // 72 bytes would exceed the limit, so the least recently used `large` goes
+        let medium = registry.create([0_u8; 24]).unwrap();
+        assert!(!registry.contains_handle(&large));
+        assert_eq!(registry.stats().memory_usage_bytes, 40);
+
+        // Filling the limit exactly fits, and updates never evict the entry they replace
+        let _fill = registry.create([0_u8; 24]).unwrap();
+        registry.update(&small, [1_u8; 16]).unwrap();
+        assert_eq!(*registry.read(&small).unwrap(), [1_u8; 16]);
+        assert!(registry.contains_handle(&medium));
+        assert_eq!(registry.stats().total_evictions, 1);
+
+        // An entry larger than the limit is rejected even with eviction
+        let error = registry.create([0_u8; 65]).unwrap_err();
+        assert!(error.contains("entry of 65 bytes exceeds the limit of 64 bytes"));
+        assert_eq!(registry.len(), 3);
+    }
 }
@@ -0,0 +1,69 @@
+//! Serializable snapshots of a registry's entries
+//!
+//! [`ConfigRegistry::snapshot`](crate::ConfigRegistry::snapshot) copies every entry whose
+//! type was registered with
+//! [`register_snapshot_type`](crate::ConfigRegistry::register_snapshot_type) into a
+//! [`RegistrySnapshot`], which serializes with serde. Restoring it with
+//! [`restore`](crate::ConfigRegistry::restore) brings the registry back to that state under
+//! the same handle IDs, so tests can isolate themselves, services can persist
+//! configuration across restarts, and a bad live update can be rolled back.
+//!
+//! ```
+//! use superconfig::ConfigRegistry;
+//!
+//! let registry = ConfigRegistry::new();
+//! registry.register_snapshot_type::<String>();
+//! let handle = registry.create_named("app.mode", "stable".to_string()).unwrap();
+//!
+//! let snapshot = registry.snapshot().unwrap();
+//! registry.update(&handle, "experimental".to_string()).unwrap();
+//!
+//! // Roll back
+//! registry.restore(&snapshot).unwrap();
+//! assert_eq!(*registry.read(&handle).unwrap(), "stable");
+//!
+//! // Or persist and restore into a fresh registry after a restart
+//! let json = serde_json::to_string(&snapshot).unwrap();
+//! let restarted = ConfigRegistry::new();
+//! restarted.register_snapshot_type::<String>();
+//! restarted.restore(&serde_json::from_str(&json).unwrap()).unwrap();
+//! let handle = restarted.lookup::<String>("app.mode").unwrap();
+//! assert_eq!(*restarted.read(&handle).unwrap(), "stable");
+//! ```
+
+use super::registry::HandleId;
+use serde::{Deserialize, Serialize};
+
+/// Entries of a registry at one point in time, taken by `ConfigRegistry::snapshot`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegistrySnapshot {
+    /// Next handle ID of the registry, so restoring never reuses later IDs
+    pub next_id: HandleId,
+    /// Entries of registered types, ordered by handle ID
+    pub entries: Vec<SnapshotEntry>,
+    /// Handle IDs of the entries left out because their type wasn't registered
+    pub skipped: Vec<HandleId>,
+}
+
+/// One entry of a [`RegistrySnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// Handle ID of the entry
+    pub id: HandleId,
+    /// Type name of the data, which must be registered to restore it
+    pub type_name: String,
+    /// Name of entries created with `ConfigRegistry::create_named`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The data, serialized as JSON
+    pub data: serde_json::Value,
+}
+
+impl RegistrySnapshot {
+    /// Find the entry with the handle ID `id`
+    #[must_use]
+    pub fn entry(&self, id: HandleId) -> Option<&SnapshotEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+}
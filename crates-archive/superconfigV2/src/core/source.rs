@@ -0,0 +1,313 @@
+//! Asynchronous configuration sources
+//!
+//! Remote sources (HTTP endpoints, object stores, key-value services) load their data
+//! with async I/O. [`AsyncConfigSource`] lets async applications await that loading
+//! during startup and store the result in a [`ConfigRegistry`], without spawning
+//! blocking threads by hand. Once stored, the configuration is read through the
+//! registry's regular non-blocking API.
+//!
+//! With the `tokio` feature, [`BlockingSource`] adapts synchronous loaders (file
+//! parsing, existing blocking clients) by running them on tokio's blocking pool.
+
+use std::future::Future;
+
+use super::{circuit::SourceHealth, handle::ConfigHandle, registry::ConfigRegistry, time::Instant};
+use logffi::error;
+
+/// A configuration source loaded asynchronously
+///
+/// Implementations perform their I/O in [`load`](Self::load) and return the parsed
+/// configuration. The returned future must be `Send` so sources can be loaded from
+/// multi-threaded runtimes.
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{AsyncConfigSource, ConfigRegistry};
+///
+/// struct StaticSource(&'static str);
+///
+/// impl AsyncConfigSource for StaticSource {
+///     type Output = String;
+///
+///     fn name(&self) -> &str {
+///         "static"
+///     }
+///
+///     async fn load(&self) -> Result<String, String> {
+///         Ok(self.0.to_string())
+///     }
+/// }
+///
+/// # tokio_test::block_on(async {
+/// let registry = ConfigRegistry::new();
+/// let handle = registry.create_from_source(&StaticSource("localhost")).await.unwrap();
+/// assert_eq!(*registry.read(&handle).unwrap(), "localhost");
+/// # });
+/// ```
+pub trait AsyncConfigSource: Send + Sync {
+    /// The configuration type produced by this source
+    type Output: Send + Sync + 'static;
+
+    /// Name of the source, used in error messages
+    fn name(&self) -> &str;
+
+    /// Load the configuration from this source
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the source cannot be reached or its data cannot be parsed.
+    fn load(&self) -> impl Future<Output = Result<Self::Output, String>> + Send;
+
+    /// Health of the source after its last load, reported in registry statistics
+    ///
+    /// Plain sources report nothing; [`ResilientSource`](super::ResilientSource) reports
+    /// its failure counters and circuit breaker state.
+    fn health(&self) -> Option<SourceHealth> {
+        None
+    }
+}
+
+impl ConfigRegistry {
+    /// Load configuration from an async source and store it in the registry
+    ///
+    /// Only the source's `load()` is awaited; storing the result is a regular,
+    /// non-blocking [`create`](Self::create).
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the source fails to load.
+    pub async fn create_from_source<S: AsyncConfigSource>(
+        &self,
+        source: &S,
+    ) -> Result<ConfigHandle<S::Output>, String> {
+        let data = self.load_source(source).await?;
+        self.create(data)
+    }
+
+    /// Reload configuration from an async source into an existing handle
+    ///
+    /// The handle keeps its previous data if the source fails to load.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the source fails to load or the handle doesn't exist.
+    pub async fn update_from_source<S: AsyncConfigSource>(
+        &self,
+        handle: &ConfigHandle<S::Output>,
+        source: &S,
+    ) -> Result<(), String> {
+        let data = self.load_source(source).await?;
+        self.update(handle, data)
+    }
+
+    async fn load_source<S: AsyncConfigSource>(&self, source: &S) -> Result<S::Output, String> {
+        let started = Instant::now();
+        let result = source.load().await;
+        self.record_source_load(source.name(), started.elapsed(), result.is_err());
+        if let Some(health) = source.health() {
+            self.record_source_health(health);
+        }
+        result.map_err(|err| {
+            let error_msg = format!(
+                "superconfig.source: Failed to load source {}: {}",
+                source.name(),
+                err
+            );
+            error!(target: "superconfig.source", "Failed to load source {}: {}", source.name(), err);
+            error_msg
+        })
+    }
+}
+
+/// Adapter running a synchronous loader on tokio's blocking thread pool
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{BlockingSource, ConfigRegistry};
+///
+/// # tokio_test::block_on(async {
+/// let source = BlockingSource::new("defaults", || Ok(8080_u16));
+/// let registry = ConfigRegistry::new();
+/// let handle = registry.create_from_source(&source).await.unwrap();
+/// assert_eq!(*registry.read(&handle).unwrap(), 8080);
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub struct BlockingSource<F> {
+    name: String,
+    loader: std::sync::Arc<F>,
+}
+
+#[cfg(feature = "tokio")]
+impl<F> BlockingSource<F> {
+    /// Create a source from a name and a blocking loader
+    pub fn new(name: impl Into<String>, loader: F) -> Self {
+        Self {
+            name: name.into(),
+            loader: std::sync::Arc::new(loader),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<F, T> AsyncConfigSource for BlockingSource<F>
+where
+    F: Fn() -> Result<T, String> + Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    type Output = T;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn load(&self) -> Result<T, String> {
+        let loader = std::sync::Arc::clone(&self.loader);
+        tokio::task::spawn_blocking(move || loader())
+            .await
+            .map_err(|err| format!("blocking loader failed: {err}"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct CountingSource {
+        loads: AtomicU32,
+        fail: bool,
+    }
+
+    impl CountingSource {
+        const fn new(fail: bool) -> Self {
+            Self {
+                loads: AtomicU32::new(0),
+                fail,
+            }
+        }
+    }
+
+    impl AsyncConfigSource for CountingSource {
+        type Output = u32;
+
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        async fn load(&self) -> Result<u32, String> {
+            if self.fail {
+                return Err("unreachable".to_string());
+            }
+            Ok(self.loads.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+    }
+
+    #[test]
+    fn test_create_and_update_from_source() {
+        tokio_test::block_on(async {
+            let registry = ConfigRegistry::new();
+            let source = CountingSource::new(false);
+
+            let handle = registry.create_from_source(&source).await.unwrap();
+            assert_eq!(*registry.read(&handle).unwrap(), 1);
+
+            registry.update_from_source(&handle, &source).await.unwrap();
+            assert_eq!(*registry.read(&handle).unwrap(), 2);
+        });
+    }
+
+    #[test]
+    fn test_failed_load_keeps_existing_data() {
+        tokio_test::block_on(async {
+            let registry = ConfigRegistry::new();
+            let handle = registry.create(7_u32).unwrap();
+
+            let result = registry
+                .update_from_source(&handle, &CountingSource::new(true))
+                .await;
+            let error = result.unwrap_err();
+            assert!(error.contains("counting"));
+            assert!(error.contains("unreachable"));
+            assert_eq!(*registry.read(&handle).unwrap(), 7);
+
+            assert!(
+                registry
+                    .create_from_source(&CountingSource::new(true))
+                    .await
+                    .is_err()
+            );
+            assert_eq!(registry.len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_loads_are_counted_per_source() {
+        tokio_test::block_on(async {
+            let registry = ConfigRegistry::new();
+            let source = CountingSource::new(false);
+            let handle = registry.create_from_source(&source).await.unwrap();
+            registry.update_from_source(&handle, &source).await.unwrap();
+            let _ = registry
+                .update_from_source(&handle, &CountingSource::new(true))
+                .await;
+
+            let stats = registry.stats();
+            assert_eq!(stats.source_loads.len(), 1);
+            let loads = &stats.source_loads[0];
+            assert_eq!(loads.name, "counting");
+            assert_eq!(loads.total_loads, 3);
+            assert_eq!(loads.total_failures, 1);
+            assert!(loads.total_load_micros >= loads.last_load_micros);
+        });
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_blocking_source() {
+        let registry = ConfigRegistry::new();
+        let source = BlockingSource::new("file", || Ok("from disk".to_string()));
+        let handle = registry.create_from_source(&source).await.unwrap();
+        assert_eq!(*registry.read(&handle).unwrap(), "from disk");
+
+        let failing = BlockingSource::new("file", || Err::<String, _>("missing".to_string()));
+        assert!(
+            registry
+                .update_from_source(&handle, &failing)
+                .await
+                .is_err()
+        );
+        assert_eq!(*registry.read(&handle).unwrap(), "from disk");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_reads_from_async_tasks() {
+        // Reads complete without awaiting, so tasks interleaved on a single-threaded
+        // runtime can call them directly
+        let registry = ConfigRegistry::new();
+        let handle = registry.create("value".to_string()).unwrap();
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let registry = std::sync::Arc::clone(&registry);
+                let handle = handle.clone();
+                tokio::spawn(async move {
+                    for _ in 0..100 {
+                        assert_eq!(*registry.read(&handle).unwrap(), "value");
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+
+        let snapshot = registry.stats();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert!(registry.stats().total_reads >= snapshot.total_reads + 800);
+    }
+}
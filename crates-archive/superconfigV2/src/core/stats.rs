@@ -1,7 +1,17 @@
 //! Statistics tracking for the `SuperConfig` V2 registry system
 
+use super::{
+    circuit::SourceHealth,
+    sync::{AtomicU64, Ordering},
+};
+use serde::{Deserialize, Serialize};
+
 /// Statistics about the registry state
-#[derive(Debug, Clone, Default)]
+///
+/// Serializable so FFI layers and dashboards can consume snapshots as JSON
+/// (see `ConfigRegistry::stats_as_json`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RegistryStats {
     /// Total number of active handles
     pub total_handles: u64,
@@ -13,8 +23,24 @@ pub struct RegistryStats {
     pub total_updates: u64,
     /// Total number of delete operations
     pub total_deletes: u64,
+    /// Total number of entries evicted to respect the registry limits
+    pub total_evictions: u64,
+    /// Total number of entries removed by `evict_expired` once their TTL elapsed
+    pub total_expirations: u64,
     /// Approximate memory usage in bytes
     pub memory_usage_bytes: u64,
+    /// Startup flags of the registry at snapshot time
+    pub startup_flags: u32,
+    /// Runtime flags of the registry at snapshot time
+    pub runtime_flags: u64,
+    /// Milliseconds since the registry was created, at snapshot time
+    pub uptime_ms: u64,
+    /// Health of the sources that report it (see `ResilientSource`), sorted by name
+    pub sources: Vec<SourceHealth>,
+    /// Loads of every source loaded into the registry, sorted by name
+    pub source_loads: Vec<SourceLoadStats>,
+    /// Number of live overlays (see `ConfigRegistry::overlay`)
+    pub active_overlays: u64,
 }
 
 impl RegistryStats {
@@ -62,6 +88,162 @@ impl RegistryStats {
     }
 }
 
+/// Loads of one source through `create_from_source` and `update_from_source`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceLoadStats {
+    /// Name of the source
+    pub name: String,
+    /// Loads, successful or not
+    pub total_loads: u64,
+    /// Loads that returned an error
+    pub total_failures: u64,
+    /// Time spent in all loads, in microseconds
+    pub total_load_micros: u64,
+    /// Time spent in the most recent load, in microseconds
+    pub last_load_micros: u64,
+}
+
+impl SourceLoadStats {
+    /// Record a load of `micros` microseconds
+    pub(crate) const fn record(&mut self, micros: u64, failed: bool) {
+        self.total_loads = self.total_loads.saturating_add(1);
+        if failed {
+            self.total_failures = self.total_failures.saturating_add(1);
+        }
+        self.total_load_micros = self.total_load_micros.saturating_add(micros);
+        self.last_load_micros = micros;
+    }
+}
+
+/// Lock-free counters backing [`RegistryStats`]
+///
+/// The registry updates these on every operation, so reads never wait on a lock
+/// held by a writer or a stats snapshot. Counters saturate instead of wrapping.
+#[derive(Debug, Default)]
+pub(crate) struct AtomicStats {
+    total_handles: AtomicU64,
+    total_creates: AtomicU64,
+    total_reads: AtomicU64,
+    total_updates: AtomicU64,
+    total_deletes: AtomicU64,
+    total_evictions: AtomicU64,
+    total_expirations: AtomicU64,
+    memory_usage_bytes: AtomicU64,
+}
+
+impl AtomicStats {
+    fn saturating_add(counter: &AtomicU64, value: u64) {
+        let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_add(value))
+        });
+    }
+
+    fn saturating_sub(counter: &AtomicU64, value: u64) {
+        let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_sub(value))
+        });
+    }
+
+    /// Record a create and the memory of the new entry
+    ///
+    /// Called before the entry is inserted, so a concurrent delete of it never
+    /// subtracts a handle or memory that wasn't added yet.
+    pub(crate) fn record_create(&self, bytes: u64) {
+        Self::saturating_add(&self.total_creates, 1);
+        Self::saturating_add(&self.total_handles, 1);
+        Self::saturating_add(&self.memory_usage_bytes, bytes);
+    }
+
+    /// Record a read
+    pub(crate) fn record_read(&self) {
+        Self::saturating_add(&self.total_reads, 1);
+    }
+
+    /// Add the memory of an entry about to be stored
+    pub(crate) fn add_memory(&self, bytes: u64) {
+        Self::saturating_add(&self.memory_usage_bytes, bytes);
+    }
+
+    /// Release memory added with [`add_memory`](Self::add_memory) for an entry that
+    /// was never stored
+    pub(crate) fn remove_memory(&self, bytes: u64) {
+        Self::saturating_sub(&self.memory_usage_bytes, bytes);
+    }
+
+    /// Record a create whose memory was added with [`add_memory`](Self::add_memory)
+    pub(crate) fn record_create_without_memory(&self) {
+        Self::saturating_add(&self.total_creates, 1);
+        Self::saturating_add(&self.total_handles, 1);
+    }
+
+    /// Record an update that replaced an entry of `old_bytes`
+    ///
+    /// The replacement's memory is added with [`add_memory`](Self::add_memory) before
+    /// it is stored, for the same reason as in [`record_create`](Self::record_create).
+    pub(crate) fn record_update(&self, old_bytes: u64) {
+        Self::saturating_add(&self.total_updates, 1);
+        Self::saturating_sub(&self.memory_usage_bytes, old_bytes);
+    }
+
+    /// Record a delete and release the memory of the removed entry
+    pub(crate) fn record_delete(&self, bytes: u64) {
+        Self::saturating_add(&self.total_deletes, 1);
+        Self::saturating_sub(&self.total_handles, 1);
+        Self::saturating_sub(&self.memory_usage_bytes, bytes);
+    }
+
+    /// Record an eviction and release the memory of the evicted entry
+    pub(crate) fn record_eviction(&self, bytes: u64) {
+        Self::saturating_add(&self.total_evictions, 1);
+        Self::saturating_sub(&self.total_handles, 1);
+        Self::saturating_sub(&self.memory_usage_bytes, bytes);
+    }
+
+    /// Record the removal of an expired entry and release its memory
+    pub(crate) fn record_expiration(&self, bytes: u64) {
+        Self::saturating_add(&self.total_expirations, 1);
+        Self::saturating_sub(&self.total_handles, 1);
+        Self::saturating_sub(&self.memory_usage_bytes, bytes);
+    }
+
+    /// Approximate memory of the stored entries
+    pub(crate) fn memory_usage(&self) -> u64 {
+        self.memory_usage_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Reset all counters to zero
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.total_handles,
+            &self.total_creates,
+            &self.total_reads,
+            &self.total_updates,
+            &self.total_deletes,
+            &self.total_evictions,
+            &self.total_expirations,
+            &self.memory_usage_bytes,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Copy the counters into a [`RegistryStats`] snapshot (flags and uptime are left at zero)
+    pub(crate) fn snapshot(&self) -> RegistryStats {
+        RegistryStats {
+            total_handles: self.total_handles.load(Ordering::Relaxed),
+            total_creates: self.total_creates.load(Ordering::Relaxed),
+            total_reads: self.total_reads.load(Ordering::Relaxed),
+            total_updates: self.total_updates.load(Ordering::Relaxed),
+            total_deletes: self.total_deletes.load(Ordering::Relaxed),
+            total_evictions: self.total_evictions.load(Ordering::Relaxed),
+            total_expirations: self.total_expirations.load(Ordering::Relaxed),
+            memory_usage_bytes: self.memory_usage_bytes.load(Ordering::Relaxed),
+            ..RegistryStats::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +323,54 @@ mod tests {
         assert_eq!(stats.memory_usage_bytes, u64::MAX);
     }
 
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut stats = RegistryStats::new();
+        stats.increment_creates();
+        stats.add_memory(64);
+        stats.runtime_flags = 0b101;
+        stats.uptime_ms = 1500;
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["total_creates"], 1);
+        assert_eq!(json["memory_usage_bytes"], 64);
+        assert_eq!(json["runtime_flags"], 5);
+        assert_eq!(json["uptime_ms"], 1500);
+
+        let restored: RegistryStats = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.total_creates, 1);
+        assert_eq!(restored.runtime_flags, 0b101);
+
+        // Missing fields fall back to defaults
+        let partial: RegistryStats = serde_json::from_str(r#"{"total_reads": 3}"#).unwrap();
+        assert_eq!(partial.total_reads, 3);
+        assert_eq!(partial.uptime_ms, 0);
+    }
+
+    #[test]
+    fn test_atomic_stats_snapshot() {
+        let stats = AtomicStats::default();
+
+        stats.record_create(100);
+        stats.record_create(50);
+        stats.record_read();
+        stats.add_memory(20);
+        stats.record_update(100);
+        stats.record_delete(50);
+        stats.record_delete(500);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.total_creates, 2);
+        assert_eq!(snapshot.total_reads, 1);
+        assert_eq!(snapshot.total_updates, 1);
+        assert_eq!(snapshot.total_deletes, 2);
+        assert_eq!(snapshot.total_handles, 0);
+        assert_eq!(snapshot.memory_usage_bytes, 0); // Saturates instead of wrapping
+
+        stats.reset();
+        assert_eq!(stats.snapshot().total_creates, 0);
+    }
+
     #[test]
     fn test_reset() {
         let mut stats = RegistryStats::new();
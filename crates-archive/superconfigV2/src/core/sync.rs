@@ -0,0 +1,28 @@
+//! Atomics used by the registry's bookkeeping
+//!
+//! Handle ids, statistics and the overlay count are atomic counters. Built with
+//! `RUSTFLAGS="--cfg loom"`, they are loom's model-checked atomics, so the tests in
+//! `tests/loom_registry.rs` can explore every interleaving of concurrent registry
+//! operations around them:
+//!
+//! ```sh
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom_registry
+//! ```
+//!
+//! Loom only sees these atomics. The entries themselves live in a `DashMap`, whose
+//! shard locks loom does not model, so registry code must not touch these atomics
+//! while holding a map guard: loom could switch to a thread spinning on the same shard.
+//!
+//! ## Memory ordering
+//!
+//! All counters use `Relaxed` ordering. Read-modify-write operations on a single
+//! counter are never lost, so ids are unique and every counter ends up exact, but a
+//! [`RegistryStats`](super::RegistryStats) snapshot reads each counter separately and
+//! is not a consistent cut across them. Configuration data is published through the
+//! map's shard locks, not through these counters (see
+//! [`ConfigRegistry::create`](super::ConfigRegistry::create)).
+
+#[cfg(loom)]
+pub use loom::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(loom))]
+pub use std::sync::atomic::{AtomicU64, Ordering};
@@ -0,0 +1,11 @@
+//! Clock of the registry's timestamps and load timings
+//!
+//! Entry creation times, TTLs, circuit breaker cooldowns and source load durations are
+//! measured with an [`Instant`]. In browsers (`wasm32-unknown-unknown`), where `std`'s
+//! `Instant::now` panics, it's `web-time`'s, read from `performance.now()`; elsewhere
+//! it's `std`'s.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub use web_time::Instant;
@@ -0,0 +1,365 @@
+//! Validation of configuration before it is stored
+//!
+//! A [`Validator`] checks rules serde can't express, such as port ranges or non-empty
+//! hosts. Entries created with [`ConfigRegistry::create_validated`], or given a validator
+//! with [`ConfigRegistry::set_validator`], are validated on every
+//! [`update`](ConfigRegistry::update): invalid configuration is rejected with the list
+//! of [`Violation`]s before readers can see it, and the entry keeps its data.
+//!
+//! Closures taking the configuration and returning `Result<(), Vec<Violation>>` are
+//! validators. With the `validator` feature, `DeriveValidator` runs the rules of
+//! the `validator` crate's `#[derive(Validate)]`.
+//!
+//! ```
+//! use superconfig::{ConfigRegistry, Violation};
+//!
+//! struct Server {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! fn check(server: &Server) -> Result<(), Vec<Violation>> {
+//!     let mut violations = Vec::new();
+//!     if server.host.is_empty() {
+//!         violations.push(Violation::new("host", "must not be empty"));
+//!     }
+//!     if server.port < 1024 {
+//!         violations.push(Violation::new("port", "must be at least 1024"));
+//!     }
+//!     if violations.is_empty() { Ok(()) } else { Err(violations) }
+//! }
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry
+//!     .create_validated(Server { host: "localhost".into(), port: 8080 }, check)
+//!     .unwrap();
+//!
+//! let error = registry.update(&handle, Server { host: String::new(), port: 80 }).unwrap_err();
+//! assert!(error.ends_with("host: must not be empty; port: must be at least 1024"));
+//! assert_eq!(registry.read(&handle).unwrap().port, 8080);
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    handle::ConfigHandle,
+    registry::{ConfigRegistry, HandleId},
+};
+use logffi::error;
+
+/// A rule broken by a configuration
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Violation {
+    /// Dotted path of the offending field, empty for the whole configuration
+    pub field: String,
+    /// What is wrong with the field
+    pub message: String,
+}
+
+impl Violation {
+    /// A violation of `field` explained by `message`
+    #[must_use]
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.field.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.field, self.message)
+        }
+    }
+}
+
+/// Checks a configuration before it is stored
+pub trait Validator<T>: Send + Sync {
+    /// Check `config`, returning every rule it breaks
+    ///
+    /// # Errors
+    ///
+    /// Returns the violations of `config`, which must not be empty.
+    fn validate(&self, config: &T) -> Result<(), Vec<Violation>>;
+}
+
+impl<T, F> Validator<T> for F
+where
+    F: Fn(&T) -> Result<(), Vec<Violation>> + Send + Sync,
+{
+    fn validate(&self, config: &T) -> Result<(), Vec<Violation>> {
+        self(config)
+    }
+}
+
+/// Validator running the rules of the `validator` crate's `#[derive(Validate)]`
+///
+/// Nested fields are reported with dotted paths, and list items with their index,
+/// such as `servers.0.port`.
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{ConfigRegistry, DeriveValidator};
+/// use validator::Validate;
+///
+/// #[derive(Validate)]
+/// struct Server {
+///     #[validate(length(min = 1))]
+///     host: String,
+///     #[validate(range(min = 1024))]
+///     port: u16,
+/// }
+///
+/// let registry = ConfigRegistry::new();
+/// let handle = registry
+///     .create_validated(Server { host: "localhost".into(), port: 8080 }, DeriveValidator)
+///     .unwrap();
+/// let error = registry.update(&handle, Server { host: "a".into(), port: 80 }).unwrap_err();
+/// assert!(error.ends_with("port: range"));
+/// ```
+#[cfg(feature = "validator")]
+#[cfg_attr(docsrs, doc(cfg(feature = "validator")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeriveValidator;
+
+#[cfg(feature = "validator")]
+impl<T: validator::Validate> Validator<T> for DeriveValidator {
+    fn validate(&self, config: &T) -> Result<(), Vec<Violation>> {
+        config.validate().map_err(|errors| {
+            let mut violations = Vec::new();
+            collect_violations("", &errors, &mut violations);
+            violations.sort_by(|a, b| a.field.cmp(&b.field));
+            violations
+        })
+    }
+}
+
+/// Flatten the errors of the `validator` crate into violations under `prefix`
+#[cfg(feature = "validator")]
+fn collect_violations(
+    prefix: &str,
+    errors: &validator::ValidationErrors,
+    violations: &mut Vec<Violation>,
+) {
+    use validator::ValidationErrorsKind;
+
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{prefix}.{field}")
+        };
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                violations.extend(field_errors.iter().map(|error| {
+                    let message = error.message.as_ref().unwrap_or(&error.code);
+                    Violation::new(path.clone(), message.to_string())
+                }));
+            }
+            ValidationErrorsKind::Struct(nested) => collect_violations(&path, nested, violations),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_violations(&format!("{path}.{index}"), nested, violations);
+                }
+            }
+        }
+    }
+}
+
+/// Validator stored for an entry, downcast from the registry's `dyn Any`
+pub(crate) type StoredValidator<T> = Arc<dyn Validator<T>>;
+
+impl ConfigRegistry {
+    /// Validate `data` with `validator`, store it, and validate every update of it
+    ///
+    /// # Errors
+    ///
+    /// Returns error message listing the violations if `data` is invalid, or if the
+    /// entry can't be created.
+    pub fn create_validated<T>(
+        &self,
+        data: T,
+        validator: impl Validator<T> + 'static,
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: Send + Sync + 'static,
+    {
+        let validator: StoredValidator<T> = Arc::new(validator);
+        validator
+            .validate(&data)
+            .map_err(|violations| validation_error(None, &violations))?;
+        let handle = self.create(data)?;
+        self.store_validator(handle.id(), validator);
+        Ok(handle)
+    }
+
+    /// Validate every update of `handle` with `validator`, replacing its validator
+    ///
+    /// The current configuration is validated first, so a validated entry never holds
+    /// invalid data. In a child registry, the validator applies to the child's
+    /// overrides of a parent's entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist or points to wrong type, or
+    /// listing the violations if its current configuration is invalid.
+    pub fn set_validator<T>(
+        &self,
+        handle: &ConfigHandle<T>,
+        validator: impl Validator<T> + 'static,
+    ) -> Result<(), String>
+    where
+        T: Send + Sync + 'static,
+    {
+        let validator: StoredValidator<T> = Arc::new(validator);
+        validator
+            .validate(&*self.read(handle)?)
+            .map_err(|violations| validation_error(Some(handle.id()), &violations))?;
+        self.store_validator(handle.id(), validator);
+        Ok(())
+    }
+
+    /// Validate `data` for the entry `id` with its validator, if it has one
+    pub(crate) fn check_valid<T: 'static>(&self, id: HandleId, data: &T) -> Result<(), String> {
+        self.validator::<T>(id).map_or_else(
+            || {
+                self.parent()
+                    .map_or(Ok(()), |parent| parent.check_valid(id, data))
+            },
+            |validator| {
+                validator
+                    .validate(data)
+                    .map_err(|violations| validation_error(Some(id), &violations))
+            },
+        )
+    }
+}
+
+fn validation_error(handle_id: Option<HandleId>, violations: &[Violation]) -> String {
+    let list = violations
+        .iter()
+        .map(Violation::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+    let subject = handle_id.map_or_else(
+        || "Configuration".to_string(),
+        |id| format!("Configuration of handle {id}"),
+    );
+    error!(target: "superconfig.validation", "{subject} is invalid: {list}");
+    format!("superconfig.validation: {subject} is invalid: {list}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Validators take a reference
+    fn positive(value: &i32) -> Result<(), Vec<Violation>> {
+        if *value > 0 {
+            Ok(())
+        } else {
+            Err(vec![Violation::new("", "must be positive")])
+        }
+    }
+
+    #[test]
+    fn test_violation_display() {
+        assert_eq!(Violation::new("a.b", "bad").to_string(), "a.b: bad");
+        assert_eq!(Violation::new("", "bad").to_string(), "bad");
+    }
+
+    #[test]
+    fn test_create_validated_rejects_invalid_data() {
+        let registry = ConfigRegistry::new();
+        let error = registry.create_validated(-1, positive).unwrap_err();
+        assert_eq!(
+            error,
+            "superconfig.validation: Configuration is invalid: must be positive"
+        );
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_updates_are_validated_until_deleted() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create_validated(1, positive).unwrap();
+        registry.update(&handle, 2).unwrap();
+        let error = registry.update(&handle, 0).unwrap_err();
+        assert!(error.contains(&format!("Configuration of handle {}", handle.id())));
+        assert_eq!(*registry.read(&handle).unwrap(), 2);
+        assert_eq!(registry.generation(&handle).unwrap(), 2);
+
+        registry.delete(&handle).unwrap();
+        assert!(registry.validator::<i32>(handle.id()).is_none());
+    }
+
+    #[test]
+    fn test_set_validator_checks_current_data() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(0).unwrap();
+        assert!(registry.set_validator(&handle, positive).is_err());
+        registry.update(&handle, -5).unwrap();
+
+        registry.update(&handle, 3).unwrap();
+        registry.set_validator(&handle, positive).unwrap();
+        assert!(registry.update(&handle, -5).is_err());
+
+        // Replacing the validator changes the rules
+        registry
+            .set_validator(&handle, |_: &i32| -> Result<(), Vec<Violation>> { Ok(()) })
+            .unwrap();
+        registry.update(&handle, -5).unwrap();
+    }
+
+    #[test]
+    fn test_child_overrides_use_parent_validator() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create_validated(1, positive).unwrap();
+        let child = registry.child();
+        assert!(child.update(&handle, -1).is_err());
+        child.update(&handle, 5).unwrap();
+        assert_eq!(*child.read(&handle).unwrap(), 5);
+        assert_eq!(*registry.read(&handle).unwrap(), 1);
+    }
+
+    #[cfg(feature = "validator")]
+    #[test]
+    fn test_derive_validator_reports_nested_paths() {
+        use validator::Validate;
+
+        #[derive(Validate)]
+        struct Server {
+            #[validate(range(min = 1, message = "must not be 0"))]
+            port: u16,
+        }
+
+        #[derive(Validate)]
+        struct Cluster {
+            #[validate(length(min = 1))]
+            name: String,
+            #[validate(nested)]
+            servers: Vec<Server>,
+        }
+
+        let violations = DeriveValidator
+            .validate(&Cluster {
+                name: String::new(),
+                servers: vec![Server { port: 1 }, Server { port: 0 }],
+            })
+            .unwrap_err();
+        assert_eq!(
+            violations,
+            [
+                Violation::new("name", "length"),
+                Violation::new("servers.1.port", "must not be 0"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Deserializing stored configuration straight from the registry's cached tree
+//!
+//! [`ConfigRegistry::deserializer`] returns a [`ConfigValue`]: a shared reference to the
+//! JSON tree the registry caches for an entry (see [`path`](super::path)), which
+//! implements [`Deserializer`] by reference. Deserializing from it walks the cached tree
+//! in place, without cloning it into an intermediate `serde_json::Value` first, and
+//! lends strings out of the tree: types with `&str` fields borrow them from the
+//! `ConfigValue` instead of allocating.
+//!
+//! [`ConfigValue::at`] narrows the value to a key path, with the syntax of
+//! [`get_path`](ConfigRegistry::get_path).
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use superconfig::ConfigRegistry;
+//!
+//! #[derive(Serialize)]
+//! struct Config {
+//!     name: String,
+//!     database: Database,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Database {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! /// A view of the configuration borrowing its strings
+//! #[derive(Deserialize)]
+//! struct DatabaseView<'a> {
+//!     host: &'a str,
+//!     port: u16,
+//! }
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry
+//!     .create(Config {
+//!         name: "api".into(),
+//!         database: Database { host: "db.internal".into(), port: 5432 },
+//!     })
+//!     .unwrap();
+//!
+//! let value = registry.deserializer(&handle).unwrap();
+//! let database = value.at("database").unwrap();
+//! let view = DatabaseView::deserialize(&database).unwrap();
+//! assert_eq!((view.host, view.port), ("db.internal", 5432));
+//!
+//! let name = value.at("name").unwrap();
+//! assert_eq!(<&str>::deserialize(&name).unwrap(), "api");
+//! ```
+
+use serde::{
+    Deserializer, Serialize,
+    de::{self, Visitor},
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::{
+    handle::ConfigHandle,
+    patch::resolve,
+    path::parse_path,
+    registry::{ConfigRegistry, HandleId},
+};
+use logffi::error;
+
+/// The cached configuration tree of an entry, or a value within it, to deserialize from
+///
+/// Holds the tree the registry cached when the value was created, so it keeps
+/// describing that generation of the entry after later updates. Cloning is cheap.
+/// Deserialize with `T::deserialize(&value)`; see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ConfigValue {
+    tree: Arc<Value>,
+    /// Reference tokens of the value within the tree, checked to exist
+    path: Vec<String>,
+    handle_id: HandleId,
+}
+
+impl ConfigValue {
+    /// The value at `path` within this value
+    ///
+    /// See [`path`](super::path) for the path syntax; the empty path is this value.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the path is invalid or doesn't exist.
+    pub fn at(&self, path: &str) -> Result<Self, String> {
+        let mut tokens = self.path.clone();
+        tokens.extend(parse_path(path)?);
+        if let Err(e) = resolve(&self.tree, &tokens) {
+            error!(target: "superconfig.value", "Path `{path}` of handle {}: {e}", self.handle_id);
+            return Err(format!(
+                "superconfig.value: Path `{path}` of handle {}: {e}",
+                self.handle_id
+            ));
+        }
+        Ok(Self {
+            tree: Arc::clone(&self.tree),
+            path: tokens,
+            handle_id: self.handle_id,
+        })
+    }
+
+    /// The JSON value, borrowed from the cached tree
+    #[must_use]
+    pub fn as_json(&self) -> &Value {
+        // The path was resolved when the value was created and the tree never changes
+        resolve(&self.tree, &self.path).unwrap_or(&Value::Null)
+    }
+}
+
+impl Serialize for ConfigValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_json().serialize(serializer)
+    }
+}
+
+/// Forward deserializer methods taking only a visitor to the borrowed JSON value
+macro_rules! forward_to_json {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            self.as_json().$method(visitor)
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for &'de ConfigValue {
+    type Error = serde_json::Error;
+
+    forward_to_json! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_option deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.as_json().deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.as_json().deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.as_json().deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.as_json().deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.as_json().deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.as_json().deserialize_enum(name, variants, visitor)
+    }
+}
+
+impl<'de> de::IntoDeserializer<'de, serde_json::Error> for &'de ConfigValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl ConfigRegistry {
+    /// Get a [`ConfigValue`] to deserialize the configuration of `handle` from
+    ///
+    /// The entry is serialized to a JSON tree once per generation and the tree is
+    /// cached, like for [`get_path`](Self::get_path); deserializing then reads the
+    /// cached tree in place and can borrow its strings. Counts as a read.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist or the configuration can't be
+    /// serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use superconfig::ConfigRegistry;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry
+    ///     .create(BTreeMap::from([("region".to_string(), "eu-west-1".to_string())]))
+    ///     .unwrap();
+    ///
+    /// let value = registry.deserializer(&handle).unwrap();
+    /// let labels = BTreeMap::<&str, &str>::deserialize(&value).unwrap();
+    /// assert_eq!(labels["region"], "eu-west-1");
+    /// ```
+    pub fn deserializer<T>(&self, handle: &ConfigHandle<T>) -> Result<ConfigValue, String>
+    where
+        T: Serialize + 'static,
+    {
+        Ok(ConfigValue {
+            tree: self.tree(handle)?,
+            path: Vec::new(),
+            handle_id: handle.id(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::borrow::Cow;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Mode {
+        Fast,
+        Safe { retries: u8 },
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        port: Option<u16>,
+        modes: Vec<Mode>,
+        ratio: (f32, f32),
+    }
+
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        #[serde(borrow)]
+        alias: Cow<'a, str>,
+    }
+
+    fn config() -> Config {
+        Config {
+            name: "api".to_string(),
+            port: None,
+            modes: vec![Mode::Fast, Mode::Safe { retries: 3 }],
+            ratio: (0.5, 1.5),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_through_cached_tree() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(config()).unwrap();
+
+        let value = registry.deserializer(&handle).unwrap();
+        assert_eq!(Config::deserialize(&value).unwrap(), config());
+        assert_eq!(
+            Mode::deserialize(&value.at("modes.1").unwrap()).unwrap(),
+            Mode::Safe { retries: 3 }
+        );
+        assert_eq!(
+            Option::<u16>::deserialize(&value.at("port").unwrap()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_strings_are_borrowed_from_the_tree() {
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create(serde_json::json!({"name": "api", "alias": "gateway"}))
+            .unwrap();
+
+        let value = registry.deserializer(&handle).unwrap();
+        let borrowed = Borrowed::deserialize(&value).unwrap();
+        let tree_name = value.as_json()["name"].as_str().unwrap();
+        assert_eq!(borrowed.name.as_ptr(), tree_name.as_ptr());
+        assert!(matches!(borrowed.alias, Cow::Borrowed("gateway")));
+    }
+
+    #[test]
+    fn test_value_keeps_its_generation() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(config()).unwrap();
+        let value = registry.deserializer(&handle).unwrap();
+
+        let mut updated = config();
+        updated.name = "worker".to_string();
+        registry.update(&handle, updated).unwrap();
+
+        assert_eq!(
+            <&str>::deserialize(&value.at("name").unwrap()).unwrap(),
+            "api"
+        );
+        let fresh = registry.deserializer(&handle).unwrap();
+        assert_eq!(
+            <&str>::deserialize(&fresh.at("name").unwrap()).unwrap(),
+            "worker"
+        );
+    }
+
+    #[test]
+    fn test_missing_paths_and_type_errors() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(config()).unwrap();
+        let value = registry.deserializer(&handle).unwrap();
+
+        let error = value.at("modes.5").unwrap_err();
+        assert!(error.starts_with("superconfig.value: Path `modes.5` of handle"));
+        assert!(u16::deserialize(&value.at("name").unwrap()).is_err());
+    }
+}
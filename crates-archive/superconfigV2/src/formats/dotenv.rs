@@ -0,0 +1,98 @@
+//! Dotenv format
+
+use super::{
+    FormatParser, content_lines,
+    ini::{split_key, value_of},
+};
+use crate::sources::insert_path;
+use serde_json::{Map, Value};
+
+/// Parser of dotenv (`.env`) configuration
+///
+/// Lines are `KEY=value`, optionally starting with `export`. Keys are lowercased and
+/// nested on `__`, so `DATABASE__POOL_SIZE=8` sets `database.pool_size`. Quoted values
+/// are strings, with escapes such as `\n` resolved in double quotes, and unquoted ones
+/// are coerced like [environment variables](crate::sources::env).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DotenvParser;
+
+impl FormatParser for DotenvParser {
+    fn name(&self) -> &'static str {
+        "dotenv"
+    }
+
+    /// `.env` files have no extension; [`Format::from_path`](super::Format::from_path)
+    /// also recognizes `.env` and `.env.*` file names
+    fn extensions(&self) -> &'static [&'static str] {
+        &["env"]
+    }
+
+    /// `KEY=value` lines only, with upper case keys and no spaces before `=`
+    fn detect(&self, content: &str) -> bool {
+        let mut lines = content_lines(content).peekable();
+        lines.peek().is_some()
+            && lines.all(|line| {
+                let line = line.strip_prefix("export ").unwrap_or(line);
+                line.split_once('=').is_some_and(|(key, _)| {
+                    key.chars().any(|c| c.is_ascii_uppercase())
+                        && key
+                            .chars()
+                            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                })
+            })
+    }
+
+    fn parse(&self, content: &str) -> Result<Value, String> {
+        let mut tree = Map::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `KEY=value`", index + 1))?;
+            let path = split_key(&key.to_lowercase(), "__");
+            if path.is_empty() {
+                return Err(format!("line {}: missing key", index + 1));
+            }
+            insert_path(&mut tree, &path, value_of(value));
+        }
+        Ok(Value::Object(tree))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_nests_on_double_underscore() {
+        let tree = DotenvParser
+            .parse(
+                "# comment\nexport APP_NAME=\"My App\\n\"\nDATABASE__HOST=localhost # local\n\
+                 DATABASE__POOL_SIZE=8\nQUOTED='8'\nEMPTY=",
+            )
+            .unwrap();
+        assert_eq!(
+            tree,
+            json!({
+                "app_name": "My App\n",
+                "database": {"host": "localhost", "pool_size": 8},
+                "quoted": "8",
+                "empty": "",
+            })
+        );
+        assert!(DotenvParser.parse("NO_EQUALS").is_err());
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(DotenvParser.detect("export HOST=localhost\nPORT=80"));
+        assert!(!DotenvParser.detect("host = \"localhost\""));
+        assert!(!DotenvParser.detect("[server]\nHOST=x"));
+        assert!(!DotenvParser.detect("# only a comment"));
+    }
+}
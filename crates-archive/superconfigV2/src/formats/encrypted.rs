@@ -0,0 +1,518 @@
+//! Configuration files decrypted at load time
+//!
+//! [`ConfigRegistry::create_from_encrypted_file`] loads files encrypted with AES-256-GCM,
+//! with a key taken from an environment variable, a keyfile, or the application itself.
+//! Files can be encrypted whole, or hold single encrypted values:
+//!
+//! | Content | Loaded as |
+//! |---------|-----------|
+//! | `config.toml.enc` | Decrypted, then parsed in the format of `config.toml` |
+//! | `"ENC[AES256_GCM,data:...,iv:...,tag:...,type:str]"` value | The value it was encrypted from |
+//!
+//! Whole files are the 12-byte nonce followed by the ciphertext and its tag, as written
+//! by [`EncryptionKey::encrypt`]. Encrypted values, written by
+//! [`EncryptionKey::encrypt_value`], follow SOPS' notation and keep the rest of the file
+//! readable; their `type` (`str`, `int`, `float` or `bool`) restores the value's type.
+//! Keys are 32 bytes, written as base64 or 64 hex digits, and are only read when the
+//! file needs them.
+//!
+//! Decrypted values are ordinary configuration once loaded, so wrap them in
+//! [`Secret`](crate::Secret) to keep them out of logs and exports.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_json::json;
+//! use superconfig::{ConfigRegistry, EncryptionKey, KeySource, Secret};
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     user: String,
+//!     password: Secret<String>,
+//! }
+//!
+//! let key = EncryptionKey::generate();
+//! let password = key.encrypt_value(&json!("hunter2")).unwrap();
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let path = dir.path().join("database.json");
+//! std::fs::write(&path, json!({"user": "app", "password": password}).to_string()).unwrap();
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry
+//!     .create_from_encrypted_file::<Database>(&path, &KeySource::Key(key))
+//!     .unwrap();
+//! assert_eq!(registry.read(&handle).unwrap().password.expose_secret(), "hunter2");
+//! ```
+
+use super::{deserialize, parse_content};
+use crate::core::{ConfigHandle, ConfigRegistry};
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use logffi::error;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the key of [`KeySource::default`]
+pub const DEFAULT_KEY_ENV: &str = "SUPERCONFIG_KEY";
+
+/// Extension of wholly encrypted files, such as `config.toml.enc`
+const FILE_EXTENSION: &str = "enc";
+
+const VALUE_PREFIX: &str = "ENC[AES256_GCM,";
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// An AES-256-GCM key, wiped from memory when dropped
+#[derive(Clone)]
+pub struct EncryptionKey(Zeroizing<[u8; 32]>);
+
+impl EncryptionKey {
+    /// A key made of `bytes`
+    #[must_use]
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+
+    /// A random key
+    #[must_use]
+    pub fn generate() -> Self {
+        Self::from_bytes(Aes256Gcm::generate_key(OsRng).into())
+    }
+
+    /// Parse a key written as base64 or 64 hex digits
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `text` isn't base64 or hex, or isn't 32 bytes long.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let bytes = if text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+            (0..64)
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&text[i..i + 2], 16))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?
+        } else {
+            STANDARD
+                .decode(text)
+                .map_err(|_| "key is neither base64 nor hex".to_string())?
+        };
+        let bytes = Zeroizing::new(bytes);
+        <[u8; 32]>::try_from(bytes.as_slice())
+            .map(Self::from_bytes)
+            .map_err(|_| format!("key must be 32 bytes, got {}", bytes.len()))
+    }
+
+    /// The key as base64, for an environment variable or keyfile
+    #[must_use]
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(*self.0)
+    }
+
+    /// Encrypt the content of a whole file
+    #[must_use]
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut data = nonce.to_vec();
+        data.extend(self.seal(&nonce, plaintext));
+        data
+    }
+
+    /// Decrypt the content of a file written by [`encrypt`](Self::encrypt)
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `data` is truncated, was encrypted with another key, or
+    /// was tampered with.
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        if data.len() < NONCE_LEN + TAG_LEN {
+            return Err("encrypted data is truncated".to_string());
+        }
+        let (nonce, sealed) = data.split_at(NONCE_LEN);
+        self.open(nonce, sealed)
+    }
+
+    /// Encrypt a string, number or boolean as an `ENC[AES256_GCM,...]` value
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `value` is null, an array or an object.
+    pub fn encrypt_value(&self, value: &Value) -> Result<String, String> {
+        let (plaintext, kind) = match value {
+            Value::String(text) => (text.clone(), "str"),
+            Value::Bool(flag) => (flag.to_string(), "bool"),
+            Value::Number(number) if number.is_f64() => (number.to_string(), "float"),
+            Value::Number(number) => (number.to_string(), "int"),
+            _ => return Err("only strings, numbers and booleans can be encrypted".to_string()),
+        };
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = self.seal(&nonce, plaintext.as_bytes());
+        let (data, tag) = sealed.split_at(sealed.len() - TAG_LEN);
+        Ok(format!(
+            "{VALUE_PREFIX}data:{},iv:{},tag:{},type:{kind}]",
+            STANDARD.encode(data),
+            STANDARD.encode(nonce),
+            STANDARD.encode(tag)
+        ))
+    }
+
+    /// Decrypt an `ENC[AES256_GCM,...]` value into the value it was encrypted from
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `text` isn't an encrypted value, was encrypted with
+    /// another key, or doesn't decrypt into a value of its `type`.
+    pub fn decrypt_value(&self, text: &str) -> Result<Value, String> {
+        let fields = text
+            .strip_prefix(VALUE_PREFIX)
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or("not an ENC[AES256_GCM,...] value")?;
+        let field = |name: &str| {
+            fields
+                .split(',')
+                .find_map(|field| field.strip_prefix(name)?.strip_prefix(':'))
+                .ok_or_else(|| format!("encrypted value has no `{name}`"))
+        };
+        let decode = |name: &str| {
+            STANDARD
+                .decode(field(name)?)
+                .map_err(|e| format!("invalid `{name}` of encrypted value: {e}"))
+        };
+
+        let mut sealed = decode("data")?;
+        sealed.extend(decode("tag")?);
+        let plaintext = Zeroizing::new(self.open(&decode("iv")?, &sealed)?);
+        let plaintext = std::str::from_utf8(&plaintext)
+            .map_err(|_| "decrypted value is not UTF-8".to_string())?;
+
+        let invalid = |kind: &str| format!("decrypted value is not a valid {kind}");
+        match field("type")? {
+            "str" => Ok(Value::from(plaintext)),
+            "int" => plaintext
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| plaintext.parse::<u64>().map(Value::from))
+                .map_err(|_| invalid("int")),
+            "float" => plaintext
+                .parse::<f64>()
+                .map(Value::from)
+                .map_err(|_| invalid("float")),
+            "bool" => match plaintext.to_ascii_lowercase().as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => Err(invalid("bool")),
+            },
+            kind => Err(format!("unknown encrypted value type `{kind}`")),
+        }
+    }
+
+    fn seal(&self, nonce: &Nonce<<Aes256Gcm as AeadCore>::NonceSize>, plaintext: &[u8]) -> Vec<u8> {
+        self.cipher()
+            .encrypt(nonce, plaintext)
+            .expect("AES-GCM encryption of in-memory data cannot fail")
+    }
+
+    fn open(&self, nonce: &[u8], sealed: &[u8]) -> Result<Vec<u8>, String> {
+        if nonce.len() != NONCE_LEN {
+            return Err(format!(
+                "nonce must be {NONCE_LEN} bytes, got {}",
+                nonce.len()
+            ));
+        }
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| "decryption failed, wrong key or corrupted data".to_string())
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&*self.0))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(***)")
+    }
+}
+
+/// Where the decryption key comes from
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    /// The key in an environment variable
+    Env(String),
+    /// The key in a file, such as a mounted secret
+    File(PathBuf),
+    /// A key held by the application
+    Key(EncryptionKey),
+}
+
+impl Default for KeySource {
+    /// The key in the [`DEFAULT_KEY_ENV`] environment variable
+    fn default() -> Self {
+        Self::Env(DEFAULT_KEY_ENV.to_string())
+    }
+}
+
+impl KeySource {
+    /// Read and parse the key
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the variable isn't set, the file can't be read, or the
+    /// key is invalid.
+    pub fn resolve(&self) -> Result<EncryptionKey, String> {
+        match self {
+            Self::Env(var) => {
+                let text = Zeroizing::new(
+                    std::env::var(var).map_err(|_| format!("key variable {var} is not set"))?,
+                );
+                EncryptionKey::parse(&text).map_err(|e| format!("invalid key in {var}: {e}"))
+            }
+            Self::File(path) => {
+                let text = Zeroizing::new(
+                    std::fs::read_to_string(path)
+                        .map_err(|e| format!("failed to read keyfile {}: {e}", path.display()))?,
+                );
+                EncryptionKey::parse(&text)
+                    .map_err(|e| format!("invalid key in {}: {e}", path.display()))
+            }
+            Self::Key(key) => Ok(key.clone()),
+        }
+    }
+}
+
+/// Read, decrypt and parse the file at `path`
+fn parse_encrypted_file(path: &Path, key: &KeySource) -> Result<Value, String> {
+    let fail = |message: &str| {
+        error!(target: "superconfig.formats", "Cannot decrypt {}: {message}", path.display());
+        format!(
+            "superconfig.formats: Cannot decrypt {}: {message}",
+            path.display()
+        )
+    };
+
+    let bytes = std::fs::read(path).map_err(|e| fail(&format!("failed to read file: {e}")))?;
+    let encrypted = path.extension().is_some_and(|ext| ext == FILE_EXTENSION);
+    let mut resolved = None;
+    let (content, format_path) = if encrypted {
+        let key = resolved.insert(key.resolve().map_err(|e| fail(&e))?);
+        let plaintext = Zeroizing::new(key.decrypt(&bytes).map_err(|e| fail(&e))?);
+        let content = String::from_utf8(plaintext.to_vec())
+            .map_err(|_| fail("decrypted file is not UTF-8"))?;
+        (Zeroizing::new(content), path.with_extension(""))
+    } else {
+        let content = String::from_utf8(bytes).map_err(|_| fail("file is not UTF-8"))?;
+        (Zeroizing::new(content), path.to_path_buf())
+    };
+
+    let mut tree = parse_content(&format_path, &content)?;
+    if contains_encrypted(&tree) {
+        let key = match resolved {
+            Some(key) => key,
+            None => key.resolve().map_err(|e| fail(&e))?,
+        };
+        decrypt_values(&mut tree, &key, "").map_err(|e| fail(&e))?;
+    }
+    Ok(tree)
+}
+
+fn is_encrypted_value(text: &str) -> bool {
+    text.starts_with(VALUE_PREFIX)
+}
+
+fn contains_encrypted(tree: &Value) -> bool {
+    match tree {
+        Value::String(text) => is_encrypted_value(text),
+        Value::Array(items) => items.iter().any(contains_encrypted),
+        Value::Object(table) => table.values().any(contains_encrypted),
+        _ => false,
+    }
+}
+
+/// Replace every encrypted value in `tree`, whose dotted path is `path`
+fn decrypt_values(tree: &mut Value, key: &EncryptionKey, path: &str) -> Result<(), String> {
+    let child = |name: &dyn fmt::Display| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{path}.{name}")
+        }
+    };
+    match tree {
+        Value::String(text) if is_encrypted_value(text) => {
+            *tree = key
+                .decrypt_value(text)
+                .map_err(|e| format!("value `{path}`: {e}"))?;
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                decrypt_values(item, key, &child(&index))?;
+            }
+        }
+        Value::Object(table) => {
+            for (name, value) in table.iter_mut() {
+                decrypt_values(value, key, &child(name))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl ConfigRegistry {
+    /// Decrypt and parse the file at `path` into `T` and store it
+    ///
+    /// Files with the `.enc` extension are decrypted whole and parsed in the format of
+    /// the rest of their name; encrypted values of any file are decrypted in place. The
+    /// key is read from `key` only if the file needs it. See the
+    /// [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the file can't be read, the key is missing or wrong, the
+    /// file or a value fails to decrypt or parse, or it doesn't deserialize into `T`.
+    pub fn create_from_encrypted_file<T>(
+        &self,
+        path: impl AsRef<Path>,
+        key: &KeySource,
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let path = path.as_ref();
+        let data = deserialize(
+            self.expand(parse_encrypted_file(path, key)?)?,
+            &path.display().to_string(),
+        )?;
+        self.create(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    fn no_key() -> KeySource {
+        KeySource::Env("SUPERCONFIG_TEST_NO_KEY".to_string())
+    }
+
+    #[test]
+    fn test_key_parsing() {
+        let key = EncryptionKey::generate();
+        let parsed = EncryptionKey::parse(&format!(" {}\n", key.to_base64())).unwrap();
+        assert_eq!(parsed.to_base64(), key.to_base64());
+        assert_eq!(EncryptionKey::parse(&"ab".repeat(32)).unwrap().0[31], 0xab);
+
+        assert!(
+            EncryptionKey::parse("c2hvcnQ=")
+                .unwrap_err()
+                .contains("32 bytes")
+        );
+        assert!(EncryptionKey::parse("not a key!").is_err());
+        assert_eq!(format!("{key:?}"), "EncryptionKey(***)");
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let key = EncryptionKey::generate();
+        let data = key.encrypt(b"secret");
+        assert_eq!(key.decrypt(&data).unwrap(), b"secret");
+        assert!(
+            EncryptionKey::generate()
+                .decrypt(&data)
+                .unwrap_err()
+                .contains("wrong key")
+        );
+        assert!(key.decrypt(&data[..20]).unwrap_err().contains("truncated"));
+
+        for value in [
+            json!("hunter2"),
+            json!(-5),
+            json!(u64::MAX),
+            json!(1.5),
+            json!(false),
+        ] {
+            let encrypted = key.encrypt_value(&value).unwrap();
+            assert_eq!(key.decrypt_value(&encrypted).unwrap(), value);
+        }
+        assert!(key.encrypt_value(&json!(null)).is_err());
+        assert!(key.decrypt_value("ENC[AES256_GCM,data:AA==]").is_err());
+    }
+
+    #[test]
+    fn test_encrypted_file_is_parsed_in_inner_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = EncryptionKey::generate();
+        let keyfile = dir.path().join("key");
+        std::fs::write(&keyfile, key.to_base64()).unwrap();
+        let path = dir.path().join("server.json.enc");
+        std::fs::write(&path, key.encrypt(br#"{"host": "db", "port": 5432}"#)).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_encrypted_file::<Server>(&path, &KeySource::File(keyfile))
+            .unwrap();
+        assert_eq!(registry.read(&handle).unwrap().port, 5432);
+
+        let error = registry
+            .create_from_encrypted_file::<Server>(&path, &no_key())
+            .unwrap_err();
+        assert!(error.starts_with("superconfig.formats: Cannot decrypt"));
+        assert!(error.ends_with("key variable SUPERCONFIG_TEST_NO_KEY is not set"));
+    }
+
+    #[test]
+    fn test_encrypted_values_are_decrypted_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = EncryptionKey::generate();
+        let path = dir.path().join("server.json");
+        let port = key.encrypt_value(&json!(8080)).unwrap();
+        std::fs::write(&path, json!({"host": "db", "port": port}).to_string()).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_encrypted_file::<Server>(&path, &KeySource::Key(key))
+            .unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "db".to_string(),
+                port: 8080
+            }
+        );
+
+        let error = registry
+            .create_from_encrypted_file::<Server>(&path, &KeySource::Key(EncryptionKey::generate()))
+            .unwrap_err();
+        assert!(error.contains("value `port`: decryption failed"));
+    }
+
+    #[test]
+    fn test_plain_file_needs_no_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.json");
+        std::fs::write(&path, r#"{"host": "db", "port": 80}"#).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_encrypted_file::<Server>(&path, &no_key())
+            .unwrap();
+        assert_eq!(registry.read(&handle).unwrap().port, 80);
+    }
+}
@@ -0,0 +1,196 @@
+//! INI format
+
+use super::{FormatParser, content_lines};
+use crate::sources::{env::coerce, insert_path};
+use serde_json::{Map, Value};
+
+/// Parser of INI configuration
+///
+/// `[section]` headers nest the keys that follow them, so `host = db` under
+/// `[database]` sets `database.host`; dotted sections and keys nest further. Lines may
+/// use `key = value` or `key: value`, and comments start with `;` or `#`. Quoted values
+/// are strings, and unquoted ones are coerced like
+/// [environment variables](crate::sources::env).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IniParser;
+
+impl FormatParser for IniParser {
+    fn name(&self) -> &'static str {
+        "ini"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ini"]
+    }
+
+    /// Sections, assignments and comments only, with a `;` comment, a bare value such
+    /// as `host = localhost`, or a `key: value` line in a section, which TOML rejects
+    fn detect(&self, content: &str) -> bool {
+        let mut in_section = false;
+        let mut ini_only = false;
+        let mut assignments = false;
+        for line in content_lines(content) {
+            if line.starts_with(';') {
+                ini_only = true;
+            } else if line.starts_with('[') && line.ends_with(']') {
+                in_section = true;
+            } else if let Some((_, value)) = line.split_once('=') {
+                assignments = true;
+                ini_only |= is_bare_text(value);
+            } else if in_section && let Some((_, value)) = line.split_once(':') {
+                // YAML has no section headers, so `key: value` inside one is INI
+                assignments = true;
+                ini_only |= value.is_empty() || value.starts_with(' ');
+            } else {
+                return false;
+            }
+        }
+        (assignments || in_section) && ini_only
+    }
+
+    fn parse(&self, content: &str) -> Result<Value, String> {
+        let mut tree = Map::new();
+        let mut section: Vec<String> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let name = strip_inline_comment(header)
+                    .strip_suffix(']')
+                    .ok_or_else(|| format!("line {}: unclosed section header", index + 1))?;
+                section = split_key(name, ".");
+                continue;
+            }
+            let (key, value) = line
+                .split_once(['=', ':'])
+                .ok_or_else(|| format!("line {}: expected `key = value`", index + 1))?;
+            let key = split_key(key, ".");
+            if key.is_empty() {
+                return Err(format!("line {}: missing key", index + 1));
+            }
+            let path: Vec<String> = section.iter().cloned().chain(key).collect();
+            insert_path(&mut tree, &path, value_of(value));
+        }
+        Ok(Value::Object(tree))
+    }
+}
+
+/// An unquoted value TOML can't parse, such as `localhost`
+fn is_bare_text(value: &str) -> bool {
+    let value = strip_inline_comment(value.trim());
+    value
+        .chars()
+        .next()
+        .is_some_and(|c| !matches!(c, '"' | '\'' | '[' | '{' | '+' | '-') && !c.is_ascii_digit())
+        && !matches!(value, "true" | "false" | "inf" | "nan")
+}
+
+/// Split `key` on `separator` into trimmed, non-empty segments
+pub(super) fn split_key(key: &str, separator: &str) -> Vec<String> {
+    key.split(separator)
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Remove a `;` or `#` comment following a value or header
+fn strip_inline_comment(value: &str) -> &str {
+    [" ;", " #", "\t;", "\t#"]
+        .iter()
+        .filter_map(|marker| value.find(marker))
+        .min()
+        .map_or(value, |end| &value[..end])
+        .trim()
+}
+
+/// The value of an assignment: quoted values are strings, others are coerced
+pub(super) fn value_of(raw: &str) -> Value {
+    let raw = raw.trim();
+    for quote in ['"', '\''] {
+        if let Some(rest) = raw.strip_prefix(quote)
+            && let Some(end) = rest.rfind(quote)
+        {
+            let inner = &rest[..end];
+            return Value::String(if quote == '"' {
+                unescape(inner)
+            } else {
+                inner.to_string()
+            });
+        }
+    }
+    coerce(strip_inline_comment(raw))
+}
+
+/// Resolve the backslash escapes of a double-quoted value
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_sections_and_values() {
+        let tree = IniParser
+            .parse(
+                "; app settings\nname = My App ; inline\n\n[database]\nhost = localhost\n\
+                 port = 5432\nenabled = yes\n\n[database.replica]\nhost: \"replica;1\"\n\
+                 hosts = [\"a\", \"b\"]",
+            )
+            .unwrap();
+        assert_eq!(
+            tree,
+            json!({
+                "name": "My App",
+                "database": {
+                    "host": "localhost",
+                    "port": 5432,
+                    "enabled": true,
+                    "replica": {"host": "replica;1", "hosts": ["a", "b"]},
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        assert_eq!(
+            IniParser.parse("[a]\njust text").unwrap_err(),
+            "line 2: expected `key = value`"
+        );
+        assert_eq!(
+            IniParser.parse("[a\nkey = 1").unwrap_err(),
+            "line 1: unclosed section header"
+        );
+    }
+
+    #[test]
+    fn test_detect_leaves_toml_and_yaml_alone() {
+        assert!(IniParser.detect("[server]\nhost = localhost"));
+        assert!(IniParser.detect("; comment\nport = 80"));
+        assert!(IniParser.detect("[server]\nhost: localhost"));
+        assert!(!IniParser.detect("[server]\nhost = \"localhost\"\nport = 80"));
+        assert!(!IniParser.detect("debug = true # verbose"));
+        assert!(!IniParser.detect("server:\n  host: localhost"));
+    }
+}
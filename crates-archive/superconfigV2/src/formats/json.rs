@@ -0,0 +1,35 @@
+//! JSON format
+
+use super::FormatParser;
+use serde_json::Value;
+
+/// Parser of JSON configuration
+///
+/// With the `json5` feature, content strict JSON rejects is parsed as JSON5, so `.json`
+/// files may hold comments and trailing commas.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonParser;
+
+impl FormatParser for JsonParser {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    /// Objects and arrays that parse; TOML tables also start with `[`
+    fn detect(&self, content: &str) -> bool {
+        let content = content.trim_start();
+        (content.starts_with('{') || content.starts_with('['))
+            && serde_json::from_str::<serde::de::IgnoredAny>(content).is_ok()
+    }
+
+    fn parse(&self, content: &str) -> Result<Value, String> {
+        let parsed = serde_json::from_str(content);
+        #[cfg(feature = "json5")]
+        let parsed = parsed.or_else(|e| ::json5::from_str(content).map_err(|_| e));
+        parsed.map_err(|e| e.to_string())
+    }
+}
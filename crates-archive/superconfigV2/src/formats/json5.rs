@@ -0,0 +1,48 @@
+//! JSON5 format
+
+use super::FormatParser;
+use serde_json::Value;
+
+/// Parser of JSON5 configuration, which requires the `json5` feature
+///
+/// JSON5 is JSON with comments, trailing commas, unquoted keys, single-quoted strings
+/// and hexadecimal numbers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json5Parser;
+
+impl FormatParser for Json5Parser {
+    fn name(&self) -> &'static str {
+        "json5"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json5"]
+    }
+
+    /// Objects and arrays that only JSON5 parses, such as JSON with comments
+    #[cfg(feature = "json5")]
+    fn detect(&self, content: &str) -> bool {
+        let content = content.trim_start();
+        ["{", "[", "//", "/*"]
+            .iter()
+            .any(|start| content.starts_with(start))
+            && ::json5::from_str::<serde::de::IgnoredAny>(content).is_ok()
+    }
+
+    /// Content opening with a `//` or `/*` comment
+    #[cfg(not(feature = "json5"))]
+    fn detect(&self, content: &str) -> bool {
+        let content = content.trim_start();
+        content.starts_with("//") || content.starts_with("/*")
+    }
+
+    #[cfg(feature = "json5")]
+    fn parse(&self, content: &str) -> Result<Value, String> {
+        ::json5::from_str(content).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "json5"))]
+    fn parse(&self, _content: &str) -> Result<Value, String> {
+        Err("JSON5 requires the `json5` feature".to_string())
+    }
+}
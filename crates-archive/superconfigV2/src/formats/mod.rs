@@ -0,0 +1,659 @@
+//! Configuration file formats and loading files into the registry
+//!
+//! Each format has a [`FormatParser`] turning text into a [`serde_json::Value`] tree,
+//! which is then deserialized into the typed configuration. The format of a file is
+//! taken from its extension, or detected from its content when the extension is
+//! missing or unknown:
+//!
+//! | Format | Extensions | Requires |
+//! |--------|------------|----------|
+//! | [`Format::Json`] | `json` | |
+//! | [`Format::Json5`] | `json5` | `json5` feature |
+//! | [`Format::Toml`] | `toml` | `extended_formats` feature |
+//! | [`Format::Yaml`] | `yaml`, `yml` | `extended_formats` feature |
+//! | [`Format::Ini`] | `ini` | |
+//! | [`Format::Dotenv`] | `env`, and files named `.env` or `.env.*` | |
+//!
+//! With the `json5` feature, `.json` files may also use JSON5's comments and trailing
+//! commas. YAML anchors are resolved and merge keys (`<<: *defaults`) applied.
+//!
+//! INI `[section]` headers nest the keys below them (`[db]` then `host = x` sets
+//! `db.host`), and dotenv keys are lowercased and nested on `__` (`DB__HOST=x`).
+//!
+//! [`ConfigRegistry::create_from_file`] parses and stores a typed configuration in one
+//! call, and [`ConfigRegistry::create_from_files`] layers several files, later files
+//! overriding earlier ones:
+//!
+//! ```
+//! use serde::Deserialize;
+//! use superconfig::ConfigRegistry;
+//!
+//! #[derive(Deserialize)]
+//! struct Server {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let defaults = dir.path().join("defaults.json");
+//! let local = dir.path().join("local.json");
+//! std::fs::write(&defaults, r#"{"host": "localhost", "port": 80}"#).unwrap();
+//! std::fs::write(&local, r#"{"port": 8080}"#).unwrap();
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry.create_from_files::<Server>(&[defaults, local]).unwrap();
+//! let server = registry.read(&handle).unwrap();
+//! assert_eq!((server.host.as_str(), server.port), ("localhost", 8080));
+//! ```
+//!
+//! With the [`ENV_EXPANSION`](crate::config_flags::runtime::ENV_EXPANSION) runtime flag,
+//! `${...}` placeholders in the loaded values are expanded first, see the
+//! [`interpolate`](crate::core::interpolate) module.
+//!
+//! With the `encryption` feature, `ConfigRegistry::create_from_encrypted_file` loads
+//! files encrypted whole or holding encrypted values, see the `encrypted` module.
+
+mod dotenv;
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub mod encrypted;
+mod ini;
+mod json;
+mod json5;
+mod toml;
+mod yaml;
+
+pub use dotenv::DotenvParser;
+#[cfg(feature = "encryption")]
+pub use encrypted::{DEFAULT_KEY_ENV, EncryptionKey, KeySource};
+pub use ini::IniParser;
+pub use json::JsonParser;
+pub use json5::Json5Parser;
+pub use toml::TomlParser;
+pub use yaml::YamlParser;
+
+use crate::core::{ConfigHandle, ConfigRegistry, patch::apply_merge_patch};
+use logffi::error;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::path::Path;
+
+/// Parser of one configuration format
+///
+/// Implement it to load formats superconfig doesn't support, with
+/// [`ConfigRegistry::create_from_str_with`].
+///
+/// # Examples
+///
+/// ```
+/// use superconfig::{ConfigRegistry, FormatParser};
+/// use serde_json::{Map, Value};
+///
+/// /// `key: value` lines
+/// struct Colon;
+///
+/// impl FormatParser for Colon {
+///     fn name(&self) -> &'static str {
+///         "colon"
+///     }
+///
+///     fn extensions(&self) -> &'static [&'static str] {
+///         &["colon"]
+///     }
+///
+///     fn detect(&self, content: &str) -> bool {
+///         content.lines().all(|line| line.contains(':'))
+///     }
+///
+///     fn parse(&self, content: &str) -> Result<Value, String> {
+///         let mut map = Map::new();
+///         for line in content.lines() {
+///             let (key, value) = line.split_once(':').ok_or("missing ':'")?;
+///             map.insert(key.trim().to_string(), Value::from(value.trim()));
+///         }
+///         Ok(Value::Object(map))
+///     }
+/// }
+///
+/// let registry = ConfigRegistry::new();
+/// let handle = registry
+///     .create_from_str_with::<std::collections::HashMap<String, String>>(&Colon, "host: db")
+///     .unwrap();
+/// assert_eq!(registry.read(&handle).unwrap()["host"], "db");
+/// ```
+pub trait FormatParser: Send + Sync {
+    /// Name of the format, used in error messages
+    fn name(&self) -> &'static str;
+
+    /// File extensions of the format, lowercase and without the dot
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whether `content` looks like this format, checked cheaply without a full parse
+    fn detect(&self, content: &str) -> bool;
+
+    /// Parse `content` into a JSON tree
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `content` is not valid in this format.
+    fn parse(&self, content: &str) -> Result<Value, String>;
+}
+
+/// A configuration format supported by superconfig
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Format {
+    /// JSON
+    Json,
+    /// JSON5, parsed with the `json5` feature
+    Json5,
+    /// TOML, parsed with the `extended_formats` feature
+    Toml,
+    /// YAML, parsed with the `extended_formats` feature
+    Yaml,
+    /// INI, with `[section]` headers
+    Ini,
+    /// Dotenv, with `KEY=value` lines
+    Dotenv,
+}
+
+impl Format {
+    /// Every format, in the order content detection tries them
+    ///
+    /// JSON5 comes after JSON, and dotenv and INI before TOML, as their detection only
+    /// accepts content the format before rejects.
+    pub const ALL: [Self; 6] = [
+        Self::Json,
+        Self::Json5,
+        Self::Dotenv,
+        Self::Ini,
+        Self::Toml,
+        Self::Yaml,
+    ];
+
+    /// Parser of the format
+    #[must_use]
+    pub fn parser(self) -> &'static dyn FormatParser {
+        match self {
+            Self::Json => &JsonParser,
+            Self::Json5 => &Json5Parser,
+            Self::Toml => &TomlParser,
+            Self::Yaml => &YamlParser,
+            Self::Ini => &IniParser,
+            Self::Dotenv => &DotenvParser,
+        }
+    }
+
+    /// Name of the format
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        self.parser().name()
+    }
+
+    /// The format of `path` from its extension, ignoring case, or [`Format::Dotenv`]
+    /// for files named `.env` or `.env.*`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::Format;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(Format::from_path(Path::new("app.YML")), Some(Format::Yaml));
+    /// assert_eq!(Format::from_path(Path::new(".env.local")), Some(Format::Dotenv));
+    /// assert_eq!(Format::from_path(Path::new("app.conf")), None);
+    /// ```
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name == ".env" || name.starts_with(".env.") {
+            return Some(Self::Dotenv);
+        }
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.parser().extensions().contains(&extension.as_str()))
+    }
+
+    /// The first format whose [`detect`](FormatParser::detect) accepts `content`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::Format;
+    ///
+    /// assert_eq!(Format::detect(r#"{"port": 80}"#), Some(Format::Json));
+    /// assert_eq!(Format::detect("[server]\nport = 80"), Some(Format::Toml));
+    /// assert_eq!(Format::detect("server:\n  port: 80"), Some(Format::Yaml));
+    /// assert_eq!(Format::detect("[server]\nhost = localhost"), Some(Format::Ini));
+    /// assert_eq!(Format::detect("SERVER__PORT=80"), Some(Format::Dotenv));
+    /// assert_eq!(Format::detect("just text"), None);
+    /// ```
+    #[must_use]
+    pub fn detect(content: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.parser().detect(content))
+    }
+
+    /// Parse `content` into a JSON tree
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `content` is not valid in this format, or if the format
+    /// requires a feature that isn't enabled.
+    pub fn parse(self, content: &str) -> Result<Value, String> {
+        self.parser().parse(content).map_err(|e| {
+            let error_msg = format!("superconfig.formats: Invalid {}: {e}", self.name());
+            error!(target: "superconfig.formats", "Invalid {}: {e}", self.name());
+            error_msg
+        })
+    }
+}
+
+/// Meaningful lines of `content`: trimmed, without blank lines and `#` comments
+pub(crate) fn content_lines(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// The format of `path`, from its extension or else its content
+fn format_of(path: &Path, content: &str) -> Result<Format, String> {
+    Format::from_path(path)
+        .or_else(|| Format::detect(content))
+        .ok_or_else(|| {
+            error!(target: "superconfig.formats", "Unknown format of {}", path.display());
+            format!(
+                "superconfig.formats: Unknown format of {}, use a known extension",
+                path.display()
+            )
+        })
+}
+
+/// Read and parse the file at `path`
+pub(crate) fn parse_file(path: &Path) -> Result<Value, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        error!(target: "superconfig.formats", "Failed to read {}: {e}", path.display());
+        format!(
+            "superconfig.formats: Failed to read {}: {e}",
+            path.display()
+        )
+    })?;
+    parse_content(path, &content)
+}
+
+/// Parse `content` read from `path`, in the format of `path` or else of its content
+pub(crate) fn parse_content(path: &Path, content: &str) -> Result<Value, String> {
+    format_of(path, content)?
+        .parse(content)
+        .map_err(|e| format!("{e} (in {})", path.display()))
+}
+
+/// Deserialize a parsed tree into `T`
+pub(crate) fn deserialize<T: DeserializeOwned>(tree: Value, origin: &str) -> Result<T, String> {
+    serde_json::from_value(tree).map_err(|e| {
+        error!(target: "superconfig.formats", "Invalid configuration in {origin}: {e}");
+        format!("superconfig.formats: Invalid configuration in {origin}: {e}")
+    })
+}
+
+/// Parse and merge the files at `paths` into `T`, like `create_from_files`
+pub(crate) fn load_files<T: DeserializeOwned>(
+    registry: &ConfigRegistry,
+    paths: &[impl AsRef<Path>],
+) -> Result<T, String> {
+    // With the `THREAD_POOL` startup flag, files are parsed in parallel
+    let parsed = match registry.pool() {
+        Some(pool) if paths.len() > 1 => pool.map(
+            paths
+                .iter()
+                .map(|path| {
+                    let path = path.as_ref().to_path_buf();
+                    move || parse_file(&path)
+                })
+                .collect(),
+        ),
+        _ => paths.iter().map(|path| parse_file(path.as_ref())).collect(),
+    };
+    let mut tree = Value::Object(serde_json::Map::new());
+    for layer in parsed {
+        apply_merge_patch(&mut tree, &layer?);
+    }
+    let origin = paths
+        .iter()
+        .map(|path| path.as_ref().display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    deserialize(registry.expand(tree)?, &origin)
+}
+
+impl ConfigRegistry {
+    /// Parse the file at `path` into `T` and store it
+    ///
+    /// The format comes from the file's extension, or is detected from its content.
+    /// See the [module documentation](crate::formats).
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the file can't be read, its format is unknown, it fails
+    /// to parse, or it doesn't deserialize into `T`.
+    pub fn create_from_file<T>(&self, path: impl AsRef<Path>) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let path = path.as_ref();
+        let data = deserialize(self.expand(parse_file(path)?)?, &path.display().to_string())?;
+        self.create(data)
+    }
+
+    /// Parse and merge the files at `paths` into `T` and store it
+    ///
+    /// Files are merged in order like JSON Merge Patches: tables merge recursively,
+    /// later values replace earlier ones, and `null` removes a key set by an earlier
+    /// file. Each file may use a different format.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if a file can't be read or parsed, or if the merged
+    /// configuration doesn't deserialize into `T`.
+    pub fn create_from_files<T>(
+        &self,
+        paths: &[impl AsRef<Path>],
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let data = load_files(self, paths)?;
+        self.create(data)
+    }
+
+    /// Parse `content` in `format` into `T` and store it
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `content` fails to parse or doesn't deserialize into `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::{ConfigRegistry, Format};
+    /// use std::collections::BTreeMap;
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry
+    ///     .create_from_str::<BTreeMap<String, u16>>(r#"{"port": 8080}"#, Format::Json)
+    ///     .unwrap();
+    /// assert_eq!(registry.read(&handle).unwrap()["port"], 8080);
+    /// ```
+    pub fn create_from_str<T>(
+        &self,
+        content: &str,
+        format: Format,
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let data = deserialize(self.expand(format.parse(content)?)?, format.name())?;
+        self.create(data)
+    }
+
+    /// Parse `content` with a custom `parser` into `T` and store it
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if `content` fails to parse or doesn't deserialize into `T`.
+    pub fn create_from_str_with<T>(
+        &self,
+        parser: &dyn FormatParser,
+        content: &str,
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let tree = parser.parse(content).map_err(|e| {
+            error!(target: "superconfig.formats", "Invalid {}: {e}", parser.name());
+            format!("superconfig.formats: Invalid {}: {e}", parser.name())
+        })?;
+        let data = deserialize(self.expand(tree)?, parser.name())?;
+        self.create(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(Format::from_path(Path::new("a.json")), Some(Format::Json));
+        assert_eq!(Format::from_path(Path::new("a.Toml")), Some(Format::Toml));
+        assert_eq!(Format::from_path(Path::new("a.yaml")), Some(Format::Yaml));
+        assert_eq!(Format::from_path(Path::new("a.yml")), Some(Format::Yaml));
+        assert_eq!(Format::from_path(Path::new("a.ini")), Some(Format::Ini));
+        assert_eq!(Format::from_path(Path::new("a.json5")), Some(Format::Json5));
+        assert_eq!(
+            Format::from_path(Path::new("dir/.env")),
+            Some(Format::Dotenv)
+        );
+        assert_eq!(
+            Format::from_path(Path::new("prod.env")),
+            Some(Format::Dotenv)
+        );
+        assert_eq!(Format::from_path(Path::new("config")), None);
+    }
+
+    #[test]
+    fn test_format_detection_by_content() {
+        assert_eq!(Format::detect("  [1, 2]"), Some(Format::Json));
+        // TOML tables also start with a bracket
+        assert_eq!(
+            Format::detect("# comment\n[server]\nhost = \"a\""),
+            Some(Format::Toml)
+        );
+        assert_eq!(
+            Format::detect("[[servers]]\nhost = \"a\""),
+            Some(Format::Toml)
+        );
+        assert_eq!(Format::detect("port = 80"), Some(Format::Toml));
+        assert_eq!(Format::detect("---\nport: 80"), Some(Format::Yaml));
+        assert_eq!(Format::detect("- a\n- b"), Some(Format::Yaml));
+        assert_eq!(Format::detect(""), None);
+    }
+
+    #[test]
+    fn test_create_from_file_detects_format_without_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server");
+        std::fs::write(&path, r#"{"host": "db", "port": 5432}"#).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry.create_from_file::<Server>(&path).unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "db".to_string(),
+                port: 5432,
+                tags: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_from_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ConfigRegistry::new();
+
+        let missing = registry.create_from_file::<Server>(dir.path().join("missing.json"));
+        assert!(missing.unwrap_err().contains("Failed to read"));
+
+        let unknown = dir.path().join("server.conf");
+        std::fs::write(&unknown, "host db").unwrap();
+        let error = registry.create_from_file::<Server>(&unknown).unwrap_err();
+        assert!(error.contains("Unknown format"));
+
+        let invalid = dir.path().join("server.json");
+        std::fs::write(&invalid, "{\"host\": ").unwrap();
+        let error = registry.create_from_file::<Server>(&invalid).unwrap_err();
+        assert!(error.starts_with("superconfig.formats: Invalid json"));
+        assert!(error.contains("server.json"));
+
+        std::fs::write(&invalid, r#"{"host": "db"}"#).unwrap();
+        let error = registry.create_from_file::<Server>(&invalid).unwrap_err();
+        assert!(error.contains("missing field `port`"));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_create_from_files_layers_ini_and_dotenv() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("server.cfg");
+        let env = dir.path().join(".env");
+        std::fs::write(&base, "; defaults\nhost = db\nport = 5432\ntags = [\"a\"]").unwrap();
+        std::fs::write(&env, "export PORT=6543\nHOST='replica'").unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_files::<Server>(&[&base, &env])
+            .unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "replica".to_string(),
+                port: 6543,
+                tags: vec!["a".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_from_files_layers_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.json");
+        let env = dir.path().join("env.json");
+        std::fs::write(&base, r#"{"host": "a", "port": 1, "tags": ["x"]}"#).unwrap();
+        std::fs::write(&env, r#"{"host": "b", "tags": null}"#).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_files::<Server>(&[&base, &env])
+            .unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "b".to_string(),
+                port: 1,
+                tags: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_from_str() {
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_str::<BTreeMap<String, Value>>(r#"{"a": {"b": true}}"#, Format::Json)
+            .unwrap();
+        assert_eq!(registry.read(&handle).unwrap()["a"]["b"], true);
+        assert!(
+            registry
+                .create_from_str::<Server>("[]", Format::Json)
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "extended_formats")]
+    #[test]
+    fn test_create_from_files_mixes_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.toml");
+        let env = dir.path().join("env.yaml");
+        std::fs::write(&base, "host = \"a\"\nport = 1\ntags = [\"x\"]").unwrap();
+        std::fs::write(&env, "port: 2\ntags:\n  - y\n  - z").unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry.create_from_files::<Server>(&[base, env]).unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "a".to_string(),
+                port: 2,
+                tags: vec!["y".to_string(), "z".to_string()],
+            }
+        );
+    }
+
+    #[cfg(feature = "extended_formats")]
+    #[test]
+    fn test_yaml_anchors_and_merge_keys() {
+        let tree = Format::Yaml
+            .parse(
+                "base: &base\n  host: a\n  port: 1\ntags: &tags [x]\n\
+                 server:\n  <<: *base\n  port: 2\n  tags: *tags",
+            )
+            .unwrap();
+        assert_eq!(
+            serde_json::from_value::<Server>(tree["server"].clone()).unwrap(),
+            Server {
+                host: "a".to_string(),
+                port: 2,
+                tags: vec!["x".to_string()],
+            }
+        );
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn test_json5_files_and_json_with_comments() {
+        assert_eq!(
+            Format::detect("// server\n{host: 'a', port: 1,}"),
+            Some(Format::Json5)
+        );
+        assert_eq!(Format::detect("{\"port\": 1}"), Some(Format::Json));
+
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.json5");
+        let local = dir.path().join("local.json");
+        std::fs::write(&base, "{host: 'a', port: 0x10, tags: ['x',],}").unwrap();
+        std::fs::write(&local, "{\n  // local override\n  \"port\": 2,\n}").unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_files::<Server>(&[base, local])
+            .unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Server {
+                host: "a".to_string(),
+                port: 2,
+                tags: vec!["x".to_string()],
+            }
+        );
+    }
+
+    #[cfg(not(feature = "json5"))]
+    #[test]
+    fn test_json5_requires_feature() {
+        assert_eq!(Format::detect("/* x */ {a: 1}"), Some(Format::Json5));
+        let error = Format::Json5.parse("{a: 1}").unwrap_err();
+        assert!(error.contains("`json5` feature"));
+    }
+
+    #[cfg(not(feature = "extended_formats"))]
+    #[test]
+    fn test_extended_formats_require_feature() {
+        let error = Format::Yaml.parse("port: 80").unwrap_err();
+        assert!(error.contains("extended_formats"));
+    }
+}
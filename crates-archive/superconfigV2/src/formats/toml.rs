@@ -0,0 +1,77 @@
+//! TOML format
+
+use super::{FormatParser, content_lines};
+use serde_json::Value;
+
+/// Parser of TOML configuration, which requires the `extended_formats` feature
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TomlParser;
+
+impl FormatParser for TomlParser {
+    fn name(&self) -> &'static str {
+        "toml"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["toml"]
+    }
+
+    /// A table header (`[server]`, `[[servers]]`) or `key = value` first line
+    fn detect(&self, content: &str) -> bool {
+        content_lines(content).next().is_some_and(|line| {
+            let table = line.starts_with('[') && line.ends_with(']');
+            let assignment = line.split_once('=').is_some_and(|(key, _)| {
+                let key = key.trim();
+                !key.is_empty()
+                    && key.chars().all(|c| {
+                        c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '"' | '\'' | ' ')
+                    })
+            });
+            table || assignment
+        })
+    }
+
+    #[cfg(feature = "extended_formats")]
+    fn parse(&self, content: &str) -> Result<Value, String> {
+        let table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+        Ok(to_json(toml::Value::Table(table)))
+    }
+
+    #[cfg(not(feature = "extended_formats"))]
+    fn parse(&self, _content: &str) -> Result<Value, String> {
+        Err("TOML requires the `extended_formats` feature".to_string())
+    }
+}
+
+/// Convert a TOML value, with dates as strings instead of TOML's private structures
+#[cfg(feature = "extended_formats")]
+fn to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => Value::from(integer),
+        toml::Value::Float(float) => Value::from(float),
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(all(test, feature = "extended_formats"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml_dates_as_strings() {
+        let tree = TomlParser
+            .parse("released = 2024-05-01\n[server]\nport = 80")
+            .unwrap();
+        assert_eq!(tree["released"], "2024-05-01");
+        assert_eq!(tree["server"]["port"], 80);
+    }
+}
@@ -0,0 +1,49 @@
+//! YAML format
+
+use super::{FormatParser, content_lines};
+use serde_json::Value;
+
+/// Parser of YAML configuration, which requires the `extended_formats` feature
+///
+/// Anchors and aliases are resolved, and merge keys (`<<: *defaults`) merge the
+/// referenced mappings under the keys set next to them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YamlParser;
+
+impl FormatParser for YamlParser {
+    fn name(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yaml", "yml"]
+    }
+
+    /// A document marker (`---`), `key:` mapping, or `- ` sequence first line
+    fn detect(&self, content: &str) -> bool {
+        content_lines(content).next().is_some_and(|line| {
+            line.starts_with("---")
+                || line.starts_with("- ")
+                || line == "-"
+                || line.split_once(':').is_some_and(|(key, rest)| {
+                    !key.is_empty() && (rest.is_empty() || rest.starts_with(' '))
+                })
+        })
+    }
+
+    #[cfg(feature = "extended_formats")]
+    fn parse(&self, content: &str) -> Result<Value, String> {
+        if !content.contains("<<") {
+            return serde_yml::from_str(content).map_err(|e| e.to_string());
+        }
+        let mut document: serde_yml::Value =
+            serde_yml::from_str(content).map_err(|e| e.to_string())?;
+        document.apply_merge().map_err(|e| e.to_string())?;
+        serde_json::to_value(document).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "extended_formats"))]
+    fn parse(&self, _content: &str) -> Result<Value, String> {
+        Err("YAML requires the `extended_formats` feature".to_string())
+    }
+}
@@ -111,19 +111,43 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // Module exports will be added as we implement each phase
 // Phase 1: Core registry system
+pub mod builder; // Phase 3: Public API
+#[cfg(any(feature = "figment", feature = "config-rs"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "figment", feature = "config-rs"))))]
+pub mod compat;
 pub mod config_flags;
 pub mod core;
+pub mod formats; // Phase 2: Format parsers
+pub mod metrics;
+pub mod sources; // Phase 2: Configuration sources
+pub mod types;
+#[cfg(feature = "hot_reload")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hot_reload")))]
+pub mod watch;
 
 // Future phases (commented out until implemented)
-// pub mod providers;   // Phase 2: Configuration engine
-// pub mod parsing;     // Phase 2: Format parsers
 // pub mod merging;     // Phase 2: Configuration composition
-// pub mod builder;     // Phase 3: Public API
 // pub mod features;    // Phase 5: Advanced features
 
 // Re-exports for convenience
+pub use builder::{SuperConfig, SuperConfigBuilder};
 pub use config_flags::*;
 pub use core::*;
+#[cfg(feature = "encryption")]
+pub use formats::{EncryptionKey, KeySource};
+pub use formats::{Format, FormatParser};
+#[cfg(feature = "gcp")]
+pub use sources::GcpSecretManager;
+#[cfg(feature = "http")]
+pub use sources::HttpSource;
+#[cfg(feature = "kv")]
+pub use sources::KvSource;
+#[cfg(feature = "aws")]
+pub use sources::{AwsCredentials, AwsParameterStore, AwsSecretsManager};
+pub use sources::{CliSource, EnvSource};
+#[cfg(feature = "secrets")]
+pub use sources::{SecretRefresh, SecretSource, SecretStore, SecretValue};
+pub use types::Secret;
 
 // Re-export logffi under a logging namespace for better API organization
 /// Logging functionality provided by the logffi crate
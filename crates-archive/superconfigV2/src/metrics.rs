@@ -0,0 +1,428 @@
+//! Prometheus metrics of the registry and its sources
+//!
+//! [`ConfigRegistry::metrics_text`] renders the registry statistics, the load counts and
+//! durations of its sources, and their circuit breaker health in the Prometheus text
+//! exposition format, which `OpenMetrics` scrapers also accept. Serve it from the
+//! application's `/metrics` endpoint:
+//!
+//! ```
+//! use superconfig::ConfigRegistry;
+//!
+//! let registry = ConfigRegistry::new();
+//! registry.create("localhost".to_string()).unwrap();
+//!
+//! let text = registry.metrics_text();
+//! assert!(text.contains("# TYPE superconfig_registry_handles gauge"));
+//! assert!(text.contains("superconfig_registry_handles 1\n"));
+//! assert!(text.contains("superconfig_registry_operations_total{operation=\"create\"} 1\n"));
+//! ```
+//!
+//! Applications already reporting through the [`metrics`](https://docs.rs/metrics)
+//! crate facade enable the `metrics` feature and call
+//! [`ConfigRegistry::record_metrics`] periodically instead, which sets the same
+//! metrics on the installed recorder.
+//!
+//! ## Metrics
+//!
+//! | Name | Type | Labels |
+//! |------|------|--------|
+//! | `superconfig_registry_handles` | gauge | |
+//! | `superconfig_registry_operations_total` | counter | `operation` |
+//! | `superconfig_registry_evictions_total` | counter | |
+//! | `superconfig_registry_expirations_total` | counter | |
+//! | `superconfig_registry_memory_bytes` | gauge | |
+//! | `superconfig_registry_overlays` | gauge | |
+//! | `superconfig_registry_uptime_seconds` | gauge | |
+//! | `superconfig_registry_runtime_flag` | gauge | `flag` |
+//! | `superconfig_source_loads_total` | counter | `source` |
+//! | `superconfig_source_load_failures_total` | counter | `source` |
+//! | `superconfig_source_load_duration_seconds` | summary (`_sum`, `_count`) | `source` |
+//! | `superconfig_source_last_load_duration_seconds` | gauge | `source` |
+//! | `superconfig_source_circuit_state` | gauge | `source`, `state` |
+//! | `superconfig_source_timeouts_total` | counter | `source` |
+//! | `superconfig_source_stale_serves_total` | counter | `source` |
+
+use crate::{
+    ConfigRegistry, RegistryStats,
+    config_flags::RUNTIME_FLAG_NAMES,
+    core::{CircuitState, SourceHealth, SourceLoadStats},
+};
+use std::{fmt::Write, time::Duration};
+
+/// Circuit breaker states with their label values
+const CIRCUIT_STATES: [(CircuitState, &str); 3] = [
+    (CircuitState::Closed, "closed"),
+    (CircuitState::Open, "open"),
+    (CircuitState::HalfOpen, "half_open"),
+];
+
+/// Value of a metric sample
+enum Value {
+    Count(u64),
+    Seconds(Duration),
+}
+
+/// One metric sample, as a name suffix, labels, and value
+struct Sample<'a> {
+    suffix: &'static str,
+    labels: Vec<(&'static str, &'a str)>,
+    value: Value,
+}
+
+impl<'a> Sample<'a> {
+    const fn new(labels: Vec<(&'static str, &'a str)>, value: Value) -> Self {
+        Self {
+            suffix: "",
+            labels,
+            value,
+        }
+    }
+}
+
+/// A metric and all its samples
+struct Family<'a> {
+    name: &'static str,
+    kind: &'static str,
+    help: &'static str,
+    samples: Vec<Sample<'a>>,
+}
+
+/// Every metric of `stats`, in exposition order
+fn families(stats: &RegistryStats) -> Vec<Family<'_>> {
+    let mut families = registry_families(stats);
+    families.extend(source_families(stats));
+    families
+}
+
+/// Metrics of the registry itself
+fn registry_families(stats: &RegistryStats) -> Vec<Family<'_>> {
+    let count = |value: u64| vec![Sample::new(Vec::new(), Value::Count(value))];
+    vec![
+        Family {
+            name: "superconfig_registry_handles",
+            kind: "gauge",
+            help: "Number of entries in the registry",
+            samples: count(stats.total_handles),
+        },
+        Family {
+            name: "superconfig_registry_operations_total",
+            kind: "counter",
+            help: "Registry operations by kind",
+            samples: [
+                ("create", stats.total_creates),
+                ("read", stats.total_reads),
+                ("update", stats.total_updates),
+                ("delete", stats.total_deletes),
+            ]
+            .into_iter()
+            .map(|(operation, value)| {
+                Sample::new(vec![("operation", operation)], Value::Count(value))
+            })
+            .collect(),
+        },
+        Family {
+            name: "superconfig_registry_evictions_total",
+            kind: "counter",
+            help: "Entries evicted to respect the registry limits",
+            samples: count(stats.total_evictions),
+        },
+        Family {
+            name: "superconfig_registry_expirations_total",
+            kind: "counter",
+            help: "Entries removed once their TTL elapsed",
+            samples: count(stats.total_expirations),
+        },
+        Family {
+            name: "superconfig_registry_memory_bytes",
+            kind: "gauge",
+            help: "Approximate memory used by the entries",
+            samples: count(stats.memory_usage_bytes),
+        },
+        Family {
+            name: "superconfig_registry_overlays",
+            kind: "gauge",
+            help: "Number of live overlays",
+            samples: count(stats.active_overlays),
+        },
+        Family {
+            name: "superconfig_registry_uptime_seconds",
+            kind: "gauge",
+            help: "Time since the registry was created",
+            samples: vec![Sample::new(
+                Vec::new(),
+                Value::Seconds(Duration::from_millis(stats.uptime_ms)),
+            )],
+        },
+        Family {
+            name: "superconfig_registry_runtime_flag",
+            kind: "gauge",
+            help: "Whether each runtime flag is enabled",
+            samples: RUNTIME_FLAG_NAMES
+                .iter()
+                .map(|(name, flag)| {
+                    let enabled = stats.runtime_flags & flag != 0;
+                    Sample::new(vec![("flag", *name)], Value::Count(u64::from(enabled)))
+                })
+                .collect(),
+        },
+    ]
+}
+
+/// Metrics of the sources loaded into the registry
+fn source_families(stats: &RegistryStats) -> Vec<Family<'_>> {
+    let per_load = |value: fn(&SourceLoadStats) -> Value| {
+        stats
+            .source_loads
+            .iter()
+            .map(|loads| Sample::new(vec![("source", loads.name.as_str())], value(loads)))
+            .collect()
+    };
+    let per_health = |value: fn(&SourceHealth) -> u64| {
+        stats
+            .sources
+            .iter()
+            .map(|health| {
+                Sample::new(
+                    vec![("source", health.name.as_str())],
+                    Value::Count(value(health)),
+                )
+            })
+            .collect()
+    };
+
+    vec![
+        Family {
+            name: "superconfig_source_loads_total",
+            kind: "counter",
+            help: "Loads of each source, successful or not",
+            samples: per_load(|loads| Value::Count(loads.total_loads)),
+        },
+        Family {
+            name: "superconfig_source_load_failures_total",
+            kind: "counter",
+            help: "Loads of each source that failed",
+            samples: per_load(|loads| Value::Count(loads.total_failures)),
+        },
+        Family {
+            name: "superconfig_source_load_duration_seconds",
+            kind: "summary",
+            help: "Time spent loading each source",
+            samples: stats
+                .source_loads
+                .iter()
+                .flat_map(|loads| {
+                    let labels = vec![("source", loads.name.as_str())];
+                    [
+                        Sample {
+                            suffix: "_sum",
+                            labels: labels.clone(),
+                            value: Value::Seconds(Duration::from_micros(loads.total_load_micros)),
+                        },
+                        Sample {
+                            suffix: "_count",
+                            labels,
+                            value: Value::Count(loads.total_loads),
+                        },
+                    ]
+                })
+                .collect(),
+        },
+        Family {
+            name: "superconfig_source_last_load_duration_seconds",
+            kind: "gauge",
+            help: "Time spent in the most recent load of each source",
+            samples: per_load(|loads| {
+                Value::Seconds(Duration::from_micros(loads.last_load_micros))
+            }),
+        },
+        Family {
+            name: "superconfig_source_circuit_state",
+            kind: "gauge",
+            help: "Circuit breaker state of each resilient source, 1 for the current state",
+            samples: stats
+                .sources
+                .iter()
+                .flat_map(|health| {
+                    CIRCUIT_STATES.iter().map(move |(state, label)| {
+                        Sample::new(
+                            vec![("source", health.name.as_str()), ("state", *label)],
+                            Value::Count(u64::from(health.state == *state)),
+                        )
+                    })
+                })
+                .collect(),
+        },
+        Family {
+            name: "superconfig_source_timeouts_total",
+            kind: "counter",
+            help: "Loads of each resilient source that timed out",
+            samples: per_health(|health| health.total_timeouts),
+        },
+        Family {
+            name: "superconfig_source_stale_serves_total",
+            kind: "counter",
+            help: "Loads of each resilient source answered with cached configuration",
+            samples: per_health(|health| health.stale_serves),
+        },
+    ]
+}
+
+/// Escape a label value for the text exposition format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+/// Render `stats` in the Prometheus text exposition format
+///
+/// Use this to export statistics gathered elsewhere, such as from several registries
+/// or from another process; [`ConfigRegistry::metrics_text`] renders a registry's own.
+#[must_use]
+pub fn render(stats: &RegistryStats) -> String {
+    let mut text = String::new();
+    for family in families(stats) {
+        if family.samples.is_empty() {
+            continue;
+        }
+        let name = family.name;
+        let _ = writeln!(text, "# HELP {name} {}", family.help);
+        let _ = writeln!(text, "# TYPE {name} {}", family.kind);
+        for sample in &family.samples {
+            text.push_str(name);
+            text.push_str(sample.suffix);
+            if !sample.labels.is_empty() {
+                let labels: Vec<String> = sample
+                    .labels
+                    .iter()
+                    .map(|(key, value)| format!("{key}=\"{}\"", escape(value)))
+                    .collect();
+                let _ = write!(text, "{{{}}}", labels.join(","));
+            }
+            let _ = match sample.value {
+                Value::Count(value) => writeln!(text, " {value}"),
+                Value::Seconds(duration) => writeln!(text, " {}", duration.as_secs_f64()),
+            };
+        }
+    }
+    text
+}
+
+impl ConfigRegistry {
+    /// Render the registry's metrics in the Prometheus text exposition format
+    ///
+    /// See the [module documentation](crate::metrics) for the exported metrics.
+    #[must_use]
+    pub fn metrics_text(&self) -> String {
+        render(&self.stats())
+    }
+
+    /// Set the registry's metrics on the recorder installed for the `metrics` crate
+    ///
+    /// Counters are set to their absolute values, so call this periodically, such as
+    /// before each scrape, from one place per registry.
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+    pub fn record_metrics(&self) {
+        let stats = self.stats();
+        for family in families(&stats) {
+            for sample in family.samples {
+                let labels: Vec<metrics::Label> = sample
+                    .labels
+                    .iter()
+                    .map(|(key, value)| metrics::Label::new(*key, (*value).to_string()))
+                    .collect();
+                let name = format!("{}{}", family.name, sample.suffix);
+                match (family.kind, sample.value) {
+                    ("counter", Value::Count(value)) => {
+                        metrics::counter!(name, labels).absolute(value);
+                    }
+                    // Summaries are exported as their `_sum` and `_count` gauges
+                    (_, value) => {
+                        #[allow(clippy::cast_precision_loss)] // Gauges are floats
+                        let value = match value {
+                            Value::Count(count) => count as f64,
+                            Value::Seconds(duration) => duration.as_secs_f64(),
+                        };
+                        metrics::gauge!(name, labels).set(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_flags::runtime;
+
+    #[test]
+    fn test_render_registry_metrics() {
+        let registry = ConfigRegistry::new().enable(runtime::PARALLEL);
+        let handle = registry.create(1_u32).unwrap();
+        registry.read(&handle).unwrap();
+        registry.update(&handle, 2).unwrap();
+
+        let text = registry.metrics_text();
+        assert!(text.contains(
+            "# HELP superconfig_registry_handles Number of entries in the registry\n\
+             # TYPE superconfig_registry_handles gauge\n\
+             superconfig_registry_handles 1\n"
+        ));
+        assert!(text.contains("superconfig_registry_operations_total{operation=\"read\"} 1\n"));
+        assert!(text.contains("superconfig_registry_operations_total{operation=\"update\"} 1\n"));
+        assert!(text.contains("superconfig_registry_memory_bytes 4\n"));
+        assert!(text.contains("superconfig_registry_runtime_flag{flag=\"parallel\"} 1\n"));
+        assert!(text.contains("superconfig_registry_runtime_flag{flag=\"strict_mode\"} 0\n"));
+        // Families without samples are left out
+        assert!(!text.contains("superconfig_source"));
+    }
+
+    #[test]
+    fn test_render_source_metrics() {
+        let stats = RegistryStats {
+            source_loads: vec![SourceLoadStats {
+                name: "https://config.example.com/\"app\"".to_string(),
+                total_loads: 4,
+                total_failures: 1,
+                total_load_micros: 1_500_000,
+                last_load_micros: 250_000,
+            }],
+            sources: vec![SourceHealth {
+                name: "consul".to_string(),
+                state: CircuitState::HalfOpen,
+                total_timeouts: 2,
+                ..SourceHealth::default()
+            }],
+            ..RegistryStats::default()
+        };
+
+        let text = render(&stats);
+        let source = r#"source="https://config.example.com/\"app\"""#;
+        assert!(text.contains(&format!("superconfig_source_loads_total{{{source}}} 4\n")));
+        assert!(text.contains(&format!(
+            "superconfig_source_load_failures_total{{{source}}} 1\n"
+        )));
+        assert!(text.contains("# TYPE superconfig_source_load_duration_seconds summary\n"));
+        assert!(text.contains(&format!(
+            "superconfig_source_load_duration_seconds_sum{{{source}}} 1.5\n\
+             superconfig_source_load_duration_seconds_count{{{source}}} 4\n"
+        )));
+        assert!(text.contains(&format!(
+            "superconfig_source_last_load_duration_seconds{{{source}}} 0.25\n"
+        )));
+        assert!(text.contains(
+            "superconfig_source_circuit_state{source=\"consul\",state=\"closed\"} 0\n\
+             superconfig_source_circuit_state{source=\"consul\",state=\"open\"} 0\n\
+             superconfig_source_circuit_state{source=\"consul\",state=\"half_open\"} 1\n"
+        ));
+        assert!(text.contains("superconfig_source_timeouts_total{source=\"consul\"} 2\n"));
+    }
+
+    #[test]
+    fn test_escape_label_values() {
+        assert_eq!(escape("a\\b\"c\nd"), r#"a\\b\"c\nd"#);
+    }
+}
@@ -0,0 +1,662 @@
+//! Secrets read from AWS Secrets Manager and SSM Parameter Store
+//!
+//! [`AwsSecretsManager`] reads secrets with `GetSecretValue`, and [`AwsParameterStore`]
+//! reads parameters, decrypting `SecureString`s, with `GetParameter`. Both are
+//! [`SecretStore`]s for a [`SecretSource`](super::secrets::SecretSource), and sign
+//! their requests with Signature Version 4.
+//!
+//! [`from_env`](AwsSecretsManager::from_env) takes the region from `AWS_REGION` or
+//! `AWS_DEFAULT_REGION`, and the credentials from `AWS_ACCESS_KEY_ID`,
+//! `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`. Other credential providers, such as
+//! instance profiles, are not read: pass their credentials to
+//! [`AwsCredentials::new`].
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use superconfig::{AwsParameterStore, AwsSecretsManager, ConfigRegistry, SecretSource};
+//!
+//! #[derive(Deserialize)]
+//! struct App {
+//!     database: Database,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     password: String,
+//! }
+//!
+//! # tokio_test::block_on(async {
+//! let registry = ConfigRegistry::new();
+//!
+//! // A whole document held by one secret
+//! let source = SecretSource::<App>::new(AwsSecretsManager::from_env().unwrap())
+//!     .document("prod/app/config");
+//! let app = registry.create_from_source(&source).await.unwrap();
+//!
+//! // One key from a SecureString parameter
+//! let source = SecretSource::<App>::new(AwsParameterStore::from_env().unwrap())
+//!     .document("/prod/app/config")
+//!     .key("database.password", "/prod/app/db-password");
+//! let app = registry.create_from_source(&source).await.unwrap();
+//! # });
+//! ```
+
+use std::{
+    fmt,
+    fmt::Write as _,
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use super::secrets::{SecretStore, SecretValue};
+use crate::types::SECRET_MASK;
+
+pub use super::DEFAULT_TIMEOUT;
+
+/// Credentials signing requests to AWS
+#[derive(Clone)]
+pub struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    /// Long-term credentials of an IAM user
+    #[must_use]
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    /// Add the session token of temporary credentials
+    #[must_use]
+    pub fn with_session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.session_token = Some(session_token.into());
+        self
+    }
+
+    /// Credentials from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and, if set,
+    /// `AWS_SESSION_TOKEN`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the key id or secret key is not set.
+    pub fn from_env() -> Result<Self, String> {
+        let credentials = Self::new(
+            required_var("AWS_ACCESS_KEY_ID")?,
+            required_var("AWS_SECRET_ACCESS_KEY")?,
+        );
+        Ok(match std::env::var("AWS_SESSION_TOKEN") {
+            Ok(token) if !token.is_empty() => credentials.with_session_token(token),
+            _ => credentials,
+        })
+    }
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &SECRET_MASK)
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| SECRET_MASK),
+            )
+            .finish()
+    }
+}
+
+/// Secrets of AWS Secrets Manager
+#[derive(Debug)]
+pub struct AwsSecretsManager {
+    client: AwsClient,
+}
+
+impl AwsSecretsManager {
+    /// Secrets of `region`, read with `credentials`
+    #[must_use]
+    pub fn new(region: impl Into<String>, credentials: AwsCredentials) -> Self {
+        Self {
+            client: AwsClient::new(
+                "secretsmanager",
+                "secretsmanager",
+                region.into(),
+                credentials,
+            ),
+        }
+    }
+
+    /// Secrets of the region and credentials set in the environment
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the region or credentials are not set.
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Self::new(region_from_env()?, AwsCredentials::from_env()?))
+    }
+
+    /// Send requests to `endpoint` instead of the region's, such as a VPC endpoint
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.client.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Time allowed for a whole request
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client.timeout = timeout;
+        self
+    }
+}
+
+impl SecretStore for AwsSecretsManager {
+    fn name(&self) -> &'static str {
+        "aws-secretsmanager"
+    }
+
+    /// Fetch the current version of the secret with the name or ARN `id`
+    fn fetch(&self, id: &str) -> Result<SecretValue, String> {
+        let response = self
+            .client
+            .call("GetSecretValue", &json!({ "SecretId": id }))?;
+        let value = response
+            .get("SecretString")
+            .and_then(Value::as_str)
+            .ok_or("the secret has no SecretString")?;
+        let version = response
+            .get("VersionId")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        Ok(SecretValue::new(value, version))
+    }
+}
+
+/// Parameters of AWS Systems Manager Parameter Store
+#[derive(Debug)]
+pub struct AwsParameterStore {
+    client: AwsClient,
+}
+
+impl AwsParameterStore {
+    /// Parameters of `region`, read with `credentials`
+    #[must_use]
+    pub fn new(region: impl Into<String>, credentials: AwsCredentials) -> Self {
+        Self {
+            client: AwsClient::new("ssm", "AmazonSSM", region.into(), credentials),
+        }
+    }
+
+    /// Parameters of the region and credentials set in the environment
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the region or credentials are not set.
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Self::new(region_from_env()?, AwsCredentials::from_env()?))
+    }
+
+    /// Send requests to `endpoint` instead of the region's, such as a VPC endpoint
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.client.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Time allowed for a whole request
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client.timeout = timeout;
+        self
+    }
+}
+
+impl SecretStore for AwsParameterStore {
+    fn name(&self) -> &'static str {
+        "aws-ssm"
+    }
+
+    /// Fetch the parameter with the name or ARN `id`, decrypted if it's a
+    /// `SecureString`
+    fn fetch(&self, id: &str) -> Result<SecretValue, String> {
+        let response = self.client.call(
+            "GetParameter",
+            &json!({ "Name": id, "WithDecryption": true }),
+        )?;
+        let parameter = response.get("Parameter").ok_or("no parameter returned")?;
+        let value = parameter
+            .get("Value")
+            .and_then(Value::as_str)
+            .ok_or("the parameter has no value")?;
+        let version = parameter
+            .get("Version")
+            .map_or_else(String::new, |version| {
+                version
+                    .as_str()
+                    .map_or_else(|| version.to_string(), str::to_string)
+            });
+        Ok(SecretValue::new(value, version))
+    }
+}
+
+/// Client of an AWS JSON 1.1 API, such as Secrets Manager's
+struct AwsClient {
+    service: &'static str,
+    target_prefix: &'static str,
+    region: String,
+    endpoint: String,
+    credentials: AwsCredentials,
+    timeout: Duration,
+    agent: OnceLock<ureq::Agent>,
+}
+
+impl AwsClient {
+    fn new(
+        service: &'static str,
+        target_prefix: &'static str,
+        region: String,
+        credentials: AwsCredentials,
+    ) -> Self {
+        Self {
+            service,
+            target_prefix,
+            endpoint: format!("https://{service}.{region}.amazonaws.com"),
+            region,
+            credentials,
+            timeout: DEFAULT_TIMEOUT,
+            agent: OnceLock::new(),
+        }
+    }
+
+    /// Call `action` with `body`, returning the response document
+    fn call(&self, action: &str, body: &Value) -> Result<Value, String> {
+        let body = body.to_string();
+        let target = format!("{}.{action}", self.target_prefix);
+        let host = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, rest)| rest);
+        let amz_date = amz_date(SystemTime::now());
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("host", host.to_string()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        headers.push(("x-amz-target", target));
+        let authorization = authorization(
+            &self.credentials,
+            &Scope {
+                date: &amz_date[..8],
+                region: &self.region,
+                service: self.service,
+            },
+            &Request {
+                method: "POST",
+                path: "/",
+                query: "",
+                headers: &headers,
+                payload: body.as_bytes(),
+            },
+            &amz_date,
+        );
+
+        let mut request = self
+            .agent()
+            .post(format!("{}/", self.endpoint))
+            .header("Authorization", authorization);
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(*name, value);
+        }
+        let mut response = request.send(body).map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| format!("cannot read response: {e}"))?;
+        let document: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+        if status != 200 {
+            // Errors name their exception in `__type`, such as `ResourceNotFoundException`
+            let kind = document
+                .get("__type")
+                .and_then(Value::as_str)
+                .map_or("", |kind| kind.rsplit('#').next().unwrap_or(kind));
+            let message = document
+                .get("message")
+                .or_else(|| document.get("Message"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            return Err(format!("HTTP {status} {kind}: {message}")
+                .trim_end_matches([' ', ':'])
+                .to_string());
+        }
+        if document.is_null() {
+            return Err("invalid response".to_string());
+        }
+        Ok(document)
+    }
+
+    fn agent(&self) -> &ureq::Agent {
+        self.agent.get_or_init(|| {
+            ureq::Agent::new_with_config(
+                ureq::Agent::config_builder()
+                    .timeout_global(Some(self.timeout))
+                    .http_status_as_error(false)
+                    .build(),
+            )
+        })
+    }
+}
+
+impl fmt::Debug for AwsClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsClient")
+            .field("region", &self.region)
+            .field("endpoint", &self.endpoint)
+            .field("credentials", &self.credentials)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Credential scope of a signature
+struct Scope<'a> {
+    date: &'a str,
+    region: &'a str,
+    service: &'a str,
+}
+
+/// The parts of a request covered by its signature, with headers sorted by their
+/// lowercase names
+struct Request<'a> {
+    method: &'a str,
+    path: &'a str,
+    query: &'a str,
+    headers: &'a [(&'a str, String)],
+    payload: &'a [u8],
+}
+
+/// `Authorization` header signing `request` at `amz_date` with Signature Version 4
+fn authorization(
+    credentials: &AwsCredentials,
+    scope: &Scope<'_>,
+    request: &Request<'_>,
+    amz_date: &str,
+) -> String {
+    let signed_headers = request
+        .headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_headers =
+        request
+            .headers
+            .iter()
+            .fold(String::new(), |mut headers, (name, value)| {
+                let _ = writeln!(headers, "{name}:{}", value.trim());
+                headers
+            });
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+        request.method,
+        request.path,
+        request.query,
+        hex(&Sha256::digest(request.payload)),
+    );
+    let credential_scope = format!(
+        "{}/{}/{}/aws4_request",
+        scope.date, scope.region, scope.service
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{credential_scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = [scope.date, scope.region, scope.service, "aws4_request"]
+        .into_iter()
+        .fold(
+            format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+            |key, part| hmac(&key, part.as_bytes()),
+        );
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{credential_scope}, SignedHeaders={signed_headers}, Signature={}",
+        credentials.access_key_id,
+        hex(&hmac(&key, string_to_sign.as_bytes()))
+    )
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut text, byte| {
+        let _ = write!(text, "{byte:02x}");
+        text
+    })
+}
+
+/// `time` in the basic ISO 8601 format of `X-Amz-Date`, such as `20150830T123600Z`
+fn amz_date(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, rest) = (seconds / 86_400, seconds % 86_400);
+    // Civil date of a day count, after Howard Hinnant's `civil_from_days`
+    let shifted = days + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+fn region_from_env() -> Result<String, String> {
+    required_var("AWS_REGION").or_else(|_| required_var("AWS_DEFAULT_REGION"))
+}
+
+fn required_var(name: &str) -> Result<String, String> {
+    match std::env::var(name) {
+        Ok(value) if !value.is_empty() => Ok(value),
+        _ => Err(format!("superconfig.sources: {name} is not set")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ConfigRegistry,
+        sources::{
+            secrets::SecretSource,
+            test_server::{response, serve},
+        },
+    };
+    use serde::Deserialize;
+    use std::sync::Arc;
+
+    fn credentials() -> AwsCredentials {
+        AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY")
+    }
+
+    #[test]
+    fn test_amz_date() {
+        assert_eq!(
+            amz_date(UNIX_EPOCH + Duration::from_secs(1_440_938_160)),
+            "20150830T123600Z"
+        );
+        assert_eq!(amz_date(UNIX_EPOCH), "19700101T000000Z");
+        assert_eq!(
+            amz_date(UNIX_EPOCH + Duration::from_secs(951_825_600)),
+            "20000229T120000Z"
+        );
+    }
+
+    #[test]
+    fn test_signature_matches_aws_example() {
+        // The `ListUsers` example of the Signature Version 4 documentation
+        let headers = [
+            (
+                "content-type",
+                "application/x-www-form-urlencoded; charset=utf-8".to_string(),
+            ),
+            ("host", "iam.amazonaws.com".to_string()),
+            ("x-amz-date", "20150830T123600Z".to_string()),
+        ];
+        let authorization = authorization(
+            &credentials(),
+            &Scope {
+                date: "20150830",
+                region: "us-east-1",
+                service: "iam",
+            },
+            &Request {
+                method: "GET",
+                path: "/",
+                query: "Action=ListUsers&Version=2010-05-08",
+                headers: &headers,
+                payload: b"",
+            },
+            "20150830T123600Z",
+        );
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct App {
+        database: Database,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Database {
+        host: String,
+        password: String,
+    }
+
+    #[test]
+    fn test_secrets_manager_document() {
+        let secret = json!({
+            "SecretString": r#"{"database": {"host": "db", "password": "hunter2"}}"#,
+            "VersionId": "v1",
+        });
+        let (url, requests) = serve(vec![response("200 OK", "", &secret.to_string())]);
+        let store = AwsSecretsManager::new("eu-west-1", credentials().with_session_token("t"))
+            .with_endpoint(url);
+        let app = SecretSource::<App>::new(store)
+            .document("prod/app")
+            .load_blocking()
+            .unwrap();
+        assert_eq!(app.database.host, "db");
+        assert_eq!(app.database.password, "hunter2");
+
+        let requests = requests.lock();
+        let request = requests[0].to_lowercase();
+        assert!(request.starts_with("post / "));
+        assert!(request.contains("x-amz-target: secretsmanager.getsecretvalue"));
+        assert!(request.contains("x-amz-security-token: t"));
+        assert!(request.contains(
+            "signedheaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target"
+        ));
+        assert!(request.contains("/eu-west-1/secretsmanager/aws4_request"));
+        assert!(requests[0].ends_with(r#"{"SecretId":"prod/app"}"#));
+    }
+
+    #[test]
+    fn test_parameter_store_key_and_rotation() {
+        let parameter = |value: &str, version: u64| {
+            json!({"Parameter": {"Name": "/app/password", "Value": value, "Version": version}})
+                .to_string()
+        };
+        let (url, requests) = serve(vec![
+            response("200 OK", "", &parameter("hunter2", 1)),
+            response("200 OK", "", &parameter("correct horse", 2)),
+        ]);
+        let store = AwsParameterStore::new("us-east-1", credentials()).with_endpoint(url);
+        let source = SecretSource::<Value>::new(store).key("database.password", "/app/password");
+        let registry = Arc::new(ConfigRegistry::new());
+        let handle = registry.create(source.load_blocking().unwrap()).unwrap();
+        assert_eq!(
+            registry.read(&handle).unwrap()["database"]["password"],
+            "hunter2"
+        );
+
+        let refresh = source
+            .start_refresh(&registry, &handle, Duration::from_secs(3600))
+            .unwrap();
+        assert_eq!(refresh.refresh().unwrap(), Some(2));
+        assert_eq!(
+            registry.read(&handle).unwrap()["database"]["password"],
+            "correct horse"
+        );
+
+        let requests = requests.lock();
+        assert!(
+            requests[0]
+                .to_lowercase()
+                .contains("x-amz-target: amazonssm.getparameter")
+        );
+        assert!(requests[0].ends_with(r#"{"Name":"/app/password","WithDecryption":true}"#));
+    }
+
+    #[test]
+    fn test_error_names_the_exception() {
+        let error = json!({
+            "__type": "ResourceNotFoundException",
+            "message": "Secrets Manager can't find the specified secret.",
+        });
+        let (url, _) = serve(vec![response("400 Bad Request", "", &error.to_string())]);
+        let store = AwsSecretsManager::new("us-east-1", credentials()).with_endpoint(url);
+        let error = SecretSource::<Value>::new(store)
+            .document("missing")
+            .load_blocking()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "superconfig.sources: aws-secretsmanager: Cannot fetch secret missing: HTTP 400 \
+             ResourceNotFoundException: Secrets Manager can't find the specified secret."
+        );
+    }
+
+    #[test]
+    fn test_debug_hides_credentials() {
+        let debug = format!(
+            "{:?}",
+            AwsSecretsManager::new("us-east-1", credentials().with_session_token("token"))
+        );
+        assert!(debug.contains("AKIDEXAMPLE"));
+        assert!(!debug.contains("EXAMPLEKEY"));
+        assert!(!debug.contains("\"token\""));
+    }
+}
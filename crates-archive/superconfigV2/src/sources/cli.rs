@@ -0,0 +1,348 @@
+//! Command-line flags nested into configuration tables
+//!
+//! Flags name a dotted path into the configuration, with dashes read as underscores:
+//!
+//! | Arguments | Becomes |
+//! |-----------|---------|
+//! | `--db.host=localhost` or `--db.host localhost` | `{"db": {"host": "localhost"}}` |
+//! | `--log-level=debug` | `{"log_level": "debug"}` |
+//! | `--verbose`, followed by another flag or nothing | `{"verbose": true}` |
+//! | `--tag a --tag b` | `{"tag": ["a", "b"]}` |
+//!
+//! Values are coerced like [environment variables](super::env): JSON arrays, objects
+//! and strings are parsed, booleans and numbers get their JSON type, and anything else
+//! stays a string. Arguments that aren't `--` flags are ignored, so the source can read
+//! a command line that also has positional arguments, and a bare `--` ends the flags.
+//!
+//! A value is taken from the next argument when it isn't a flag, so write boolean
+//! flags followed by a positional argument as `--verbose=true`.
+
+use super::{env::coerce, insert_path};
+use crate::core::{ConfigHandle, ConfigRegistry, time::Instant};
+use logffi::error;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Command-line flags nested into a configuration tree
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use superconfig::{CliSource, ConfigRegistry};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     db: Db,
+///     verbose: bool,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Db {
+///     host: String,
+///     port: u16,
+///     replicas: Vec<String>,
+/// }
+///
+/// let registry = ConfigRegistry::new();
+/// let handle = registry
+///     .load_cli::<Config>([
+///         "--db.host=localhost",
+///         "--db.port",
+///         "5432",
+///         "--db.replicas=a",
+///         "--db.replicas=b",
+///         "--verbose",
+///     ])
+///     .unwrap();
+/// let config = registry.read(&handle).unwrap();
+/// assert_eq!((config.db.host.as_str(), config.db.port), ("localhost", 5432));
+/// assert_eq!(config.db.replicas, ["a", "b"]);
+/// assert!(config.verbose);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CliSource {
+    args: Option<Vec<String>>,
+    aliases: HashMap<String, String>,
+}
+
+impl CliSource {
+    /// Flags of the process's command line, without the program name
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flags of `args`, which shouldn't start with the program name
+    #[must_use]
+    pub fn from_args<I>(args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            args: Some(args.into_iter().map(Into::into).collect()),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Map the flag `--flag` to the dotted configuration path `path`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::CliSource;
+    ///
+    /// let source = CliSource::from_args(["--port=8080"]).with_alias("port", "server.port");
+    /// assert_eq!(source.tree()["server"]["port"], 8080);
+    /// ```
+    #[must_use]
+    pub fn with_alias(mut self, flag: impl Into<String>, path: impl Into<String>) -> Self {
+        self.aliases.insert(flag.into(), path.into());
+        self
+    }
+
+    /// The flags nested into a configuration tree
+    ///
+    /// Later flags replace earlier ones on the same path, except repeated flags, which
+    /// collect their values into an array. Flags with an empty path segment, and
+    /// arguments that aren't Unicode, are skipped.
+    #[must_use]
+    pub fn tree(&self) -> Value {
+        let args: Vec<String> = self.args.clone().unwrap_or_else(|| {
+            std::env::args_os()
+                .skip(1)
+                .filter_map(|arg| arg.into_string().ok())
+                .collect()
+        });
+
+        // Values per path, in the order paths first appear
+        let mut flags: Vec<(Vec<String>, Vec<Value>)> = Vec::new();
+        let mut args = args.iter().peekable();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                break;
+            }
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, coerce(value)),
+                None => (
+                    flag,
+                    args.next_if(|next| !next.starts_with("--"))
+                        .map_or(Value::Bool(true), |value| coerce(value)),
+                ),
+            };
+            let Some(path) = self.path(name) else {
+                continue;
+            };
+            match flags.iter_mut().find(|(seen, _)| *seen == path) {
+                Some((_, values)) => values.push(value),
+                None => flags.push((path, vec![value])),
+            }
+        }
+
+        let mut tree = Map::new();
+        for (path, mut values) in flags {
+            let value = if values.len() == 1 {
+                values.remove(0)
+            } else {
+                Value::Array(values)
+            };
+            insert_path(&mut tree, &path, value);
+        }
+        Value::Object(tree)
+    }
+
+    /// Deserialize the flags into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the flags don't deserialize into `T`.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.tree()).map_err(|e| {
+            error!(target: "superconfig.sources", "Invalid command-line flags: {e}");
+            format!("superconfig.sources: Invalid command-line flags: {e}")
+        })
+    }
+
+    /// Configuration path of the flag `name`, after aliases and with `-` read as `_`
+    fn path(&self, name: &str) -> Option<Vec<String>> {
+        let name = self.aliases.get(name).map_or(name, String::as_str);
+        let path: Vec<String> = name.split('.').map(|key| key.replace('-', "_")).collect();
+        (!path.iter().any(String::is_empty)).then_some(path)
+    }
+}
+
+impl ConfigRegistry {
+    /// Load the command-line flags `args` into `T` and store it
+    ///
+    /// See [`CliSource`] for the flag syntax. Pass `std::env::args().skip(1)` to read
+    /// the process's command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the flags don't deserialize into `T`.
+    pub fn load_cli<T>(
+        &self,
+        args: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.load_cli_with(&CliSource::from_args(args))
+    }
+
+    /// Load the flags of `source` into `T` and store it
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the flags don't deserialize into `T`.
+    pub fn load_cli_with<T>(&self, source: &CliSource) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let started = Instant::now();
+        let result = source.load();
+        self.record_source_load("cli", started.elapsed(), result.is_err());
+        self.create(result?)
+    }
+
+    /// Merge the flags of `source` over the stored configuration of `handle`
+    ///
+    /// The flags are applied as a JSON Merge Patch with
+    /// [`apply_patch`](Self::apply_patch), so flags override values loaded from files
+    /// or the environment and leave the others untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the handle doesn't exist or the merged configuration
+    /// isn't a valid `T`. The stored configuration is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use superconfig::{CliSource, ConfigRegistry};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Server {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let registry = ConfigRegistry::new();
+    /// let handle = registry.create(Server { host: "localhost".into(), port: 80 }).unwrap();
+    /// registry
+    ///     .merge_cli(&handle, &CliSource::from_args(["--port", "8080"]))
+    ///     .unwrap();
+    ///
+    /// let server = registry.read(&handle).unwrap();
+    /// assert_eq!((server.host.as_str(), server.port), ("localhost", 8080));
+    /// ```
+    pub fn merge_cli<T>(&self, handle: &ConfigHandle<T>, source: &CliSource) -> Result<(), String>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        let started = Instant::now();
+        let result = self.apply_patch(handle, &source.tree());
+        self.record_source_load("cli", started.elapsed(), result.is_err());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[test]
+    fn test_tree_parses_flag_forms() {
+        let source = CliSource::from_args([
+            "run",
+            "--db.host=db",
+            "--db.port",
+            "5432",
+            "--offset",
+            "-3",
+            "--log-level=debug",
+            "--dry-run",
+            "--json={\"a\": [1]}",
+            "--empty=",
+        ]);
+        assert_eq!(
+            source.tree(),
+            json!({
+                "db": {"host": "db", "port": 5432},
+                "offset": -3,
+                "log_level": "debug",
+                "dry_run": true,
+                "json": {"a": [1]},
+                "empty": "",
+            })
+        );
+    }
+
+    #[test]
+    fn test_repeated_flags_collect_into_arrays() {
+        let source = CliSource::from_args(["--tag", "a", "--port=1", "--tag=b", "--tag", "3"]);
+        assert_eq!(source.tree(), json!({"tag": ["a", "b", 3], "port": 1}));
+    }
+
+    #[test]
+    fn test_double_dash_ends_flags_and_bad_paths_are_skipped() {
+        let source = CliSource::from_args(["--a=1", "--=2", "--b..c=3", "--", "--d=4"]);
+        assert_eq!(source.tree(), json!({"a": 1}));
+    }
+
+    #[test]
+    fn test_aliases_map_to_nested_paths() {
+        let source = CliSource::from_args(["--host", "db", "--verbose"])
+            .with_alias("host", "server.host")
+            .with_alias("verbose", "log.verbose");
+        assert_eq!(
+            source.tree(),
+            json!({"server": {"host": "db"}, "log": {"verbose": true}})
+        );
+    }
+
+    #[test]
+    fn test_merge_cli_overrides_and_counts_loads() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        struct Config {
+            host: String,
+            port: u16,
+        }
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .load_cli::<Config>(["--host=a", "--port=1"])
+            .unwrap();
+        registry
+            .merge_cli(&handle, &CliSource::from_args(["--port=2"]))
+            .unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            Config {
+                host: "a".to_string(),
+                port: 2,
+            }
+        );
+
+        let error = registry
+            .merge_cli(&handle, &CliSource::from_args(["--port=high"]))
+            .unwrap_err();
+        assert!(error.contains("Patched configuration is invalid"));
+        assert_eq!(registry.read(&handle).unwrap().port, 2);
+
+        let error = registry.load_cli::<Config>(["--host=a"]).unwrap_err();
+        assert!(error.starts_with("superconfig.sources: Invalid command-line flags"));
+
+        let stats = registry.stats();
+        let cli = stats.source_loads.iter().find(|s| s.name == "cli").unwrap();
+        assert_eq!((cli.total_loads, cli.total_failures), (4, 2));
+    }
+}
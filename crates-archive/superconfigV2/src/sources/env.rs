@@ -0,0 +1,351 @@
+//! Environment variables nested into configuration tables
+//!
+//! Variables starting with the source's prefix are stripped of it, lowercased and
+//! split on the separator into a path: with the prefix `APP_`, `APP_DB_HOST=db`
+//! becomes `{"db": {"host": "db"}}`. Like the legacy `Nested` provider, keys are split
+//! into at most two segments by default, so `APP_DB_POOL_SIZE` sets `db.pool_size`;
+//! [`EnvSource::with_depth`] allows deeper nesting.
+//!
+//! Values are coerced into JSON types:
+//!
+//! | Value | Becomes |
+//! |-------|---------|
+//! | `[...]`, `{...}` or `"..."` that parses as JSON | The parsed JSON |
+//! | `true`, `yes`, `on` / `false`, `no`, `off`, ignoring case | A boolean |
+//! | An integer or finite float | A number |
+//! | Anything else | The trimmed string |
+//!
+//! Unlike the legacy provider, `1` and `0` stay numbers so they can set numeric fields.
+//! Quote a value as a JSON string, such as `APP_VERSION='"2"'`, to keep it a string.
+
+use super::insert_path;
+use crate::core::{ConfigHandle, ConfigRegistry, time::Instant};
+use logffi::error;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Environment variables with a prefix, nested into a configuration tree
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use superconfig::{ConfigRegistry, EnvSource};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     db: Db,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Db {
+///     host: String,
+///     pool_size: u32,
+///     replicas: Vec<String>,
+/// }
+///
+/// let source = EnvSource::prefixed("APP_").with_vars([
+///     ("APP_DB_HOST", "localhost"),
+///     ("APP_DB_POOL_SIZE", "8"),
+///     ("APP_DB_REPLICAS", r#"["a", "b"]"#),
+///     ("HOME", "/root"),
+/// ]);
+///
+/// let registry = ConfigRegistry::new();
+/// let handle = registry.load_env_with::<Config>(&source).unwrap();
+/// let config = registry.read(&handle).unwrap();
+/// assert_eq!(config.db.host, "localhost");
+/// assert_eq!(config.db.pool_size, 8);
+/// assert_eq!(config.db.replicas, ["a", "b"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnvSource {
+    prefix: String,
+    separator: String,
+    depth: usize,
+    vars: Option<Vec<(String, String)>>,
+}
+
+impl EnvSource {
+    /// Variables starting with `prefix`, split on `_`
+    ///
+    /// An empty prefix takes every variable of the environment.
+    #[must_use]
+    pub fn prefixed(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            separator: "_".to_string(),
+            depth: 2,
+            vars: None,
+        }
+    }
+
+    /// Split keys on `separator` instead of `_`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::EnvSource;
+    ///
+    /// let source = EnvSource::prefixed("APP__")
+    ///     .with_separator("__")
+    ///     .with_vars([("APP__LOG_LEVEL__DEFAULT", "warn")]);
+    /// assert_eq!(source.tree()["log_level"]["default"], "warn");
+    /// ```
+    #[must_use]
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Split keys into at most `depth` segments instead of two
+    ///
+    /// Separators past the last split stay in the final key, so field names can contain
+    /// the separator. A depth of 0 is treated as 1, keeping keys flat.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use superconfig::EnvSource;
+    ///
+    /// let source = EnvSource::prefixed("APP_")
+    ///     .with_depth(3)
+    ///     .with_vars([("APP_DB_POOL_MAX_SIZE", "8")]);
+    /// assert_eq!(source.tree()["db"]["pool"]["max_size"], 8);
+    /// ```
+    #[must_use]
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth.max(1);
+        self
+    }
+
+    /// Read `vars` instead of the process environment
+    ///
+    /// Useful in tests, and to load variables captured earlier.
+    #[must_use]
+    pub fn with_vars<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.vars = Some(
+            vars.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Prefix of the variables read
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Separator between the segments of a key
+    #[must_use]
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    /// The variables nested into a configuration tree
+    ///
+    /// Variables are applied in key order, so when `APP_DB` and `APP_DB_HOST` are both
+    /// set, the table created by `APP_DB_HOST` replaces the value of `APP_DB`. Variables
+    /// with an empty key segment, or whose name or value isn't Unicode, are skipped.
+    #[must_use]
+    pub fn tree(&self) -> Value {
+        let mut vars: Vec<(String, String)> = self.vars.clone().unwrap_or_else(|| {
+            std::env::vars_os()
+                .filter_map(|(key, value)| {
+                    Some((key.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect()
+        });
+        vars.sort_unstable();
+
+        let mut tree = Map::new();
+        for (key, value) in &vars {
+            let Some(key) = key.strip_prefix(&self.prefix) else {
+                continue;
+            };
+            let path: Vec<String> = key
+                .splitn(self.depth, self.separator.as_str())
+                .map(str::to_lowercase)
+                .collect();
+            if path.iter().any(String::is_empty) {
+                continue;
+            }
+            insert_path(&mut tree, &path, coerce(value));
+        }
+        Value::Object(tree)
+    }
+
+    /// Deserialize the variables into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the variables don't deserialize into `T`.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.tree()).map_err(|e| {
+            error!(target: "superconfig.sources", "Invalid environment {}*: {e}", self.prefix);
+            format!(
+                "superconfig.sources: Invalid environment {}*: {e}",
+                self.prefix
+            )
+        })
+    }
+}
+
+/// Coerce an environment value into its JSON type
+pub(crate) fn coerce(value: &str) -> Value {
+    let trimmed = value.trim();
+    let json = (trimmed.starts_with('[') && trimmed.ends_with(']'))
+        || (trimmed.starts_with('{') && trimmed.ends_with('}'))
+        || (trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"'));
+    if json && let Ok(parsed) = serde_json::from_str(trimmed) {
+        return parsed;
+    }
+
+    match trimmed.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" => return Value::Bool(true),
+        "false" | "no" | "off" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if let Ok(integer) = trimmed.parse::<i64>() {
+        return Value::from(integer);
+    }
+    if let Ok(integer) = trimmed.parse::<u64>() {
+        return Value::from(integer);
+    }
+    if let Ok(float) = trimmed.parse::<f64>()
+        && float.is_finite()
+    {
+        return Value::from(float);
+    }
+    Value::String(trimmed.to_string())
+}
+
+impl ConfigRegistry {
+    /// Load the environment variables starting with `prefix` into `T` and store it
+    ///
+    /// Keys are split on `_`; see [`EnvSource`] for the nesting and coercion rules, and
+    /// [`load_env_with`](Self::load_env_with) to change them.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the variables don't deserialize into `T`.
+    pub fn load_env<T>(&self, prefix: &str) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.load_env_with(&EnvSource::prefixed(prefix))
+    }
+
+    /// Load the variables of `source` into `T` and store it
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the variables don't deserialize into `T`.
+    pub fn load_env_with<T>(&self, source: &EnvSource) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let started = Instant::now();
+        let result = source.load();
+        self.record_source_load("env", started.elapsed(), result.is_err());
+        self.create(result?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[test]
+    fn test_coerce_values() {
+        assert_eq!(coerce(" true "), json!(true));
+        assert_eq!(coerce("Off"), json!(false));
+        assert_eq!(coerce("1"), json!(1));
+        assert_eq!(coerce("-3"), json!(-3));
+        assert_eq!(coerce("18446744073709551615"), json!(u64::MAX));
+        assert_eq!(coerce("0.5"), json!(0.5));
+        assert_eq!(coerce("NaN"), json!("NaN"));
+        assert_eq!(coerce(r#"{"a": [1]}"#), json!({"a": [1]}));
+        assert_eq!(coerce(r#""8080""#), json!("8080"));
+        assert_eq!(coerce("[not json"), json!("[not json"));
+        assert_eq!(coerce("[oops]"), json!("[oops]"));
+        assert_eq!(coerce(""), json!(""));
+    }
+
+    #[test]
+    fn test_tree_nests_prefixed_vars() {
+        let source = EnvSource::prefixed("APP_").with_vars([
+            ("APP_DB_HOST", "db"),
+            ("APP_DB_POOL_SIZE", "4"),
+            ("APP_DEBUG", "yes"),
+            ("APP_", "ignored"),
+            ("APP_DB_", "ignored"),
+            ("OTHER_DB_HOST", "ignored"),
+        ]);
+        assert_eq!(
+            source.tree(),
+            json!({"db": {"host": "db", "pool_size": 4}, "debug": true})
+        );
+    }
+
+    #[test]
+    fn test_tree_tables_replace_scalars() {
+        let source =
+            EnvSource::prefixed("APP_").with_vars([("APP_DB_HOST", "db"), ("APP_DB", "x")]);
+        assert_eq!(source.tree(), json!({"db": {"host": "db"}}));
+    }
+
+    #[test]
+    fn test_flat_keys_with_depth_one() {
+        let source = EnvSource::prefixed("APP_")
+            .with_depth(0)
+            .with_vars([("APP_DB_HOST", "db")]);
+        assert_eq!(source.tree(), json!({"db_host": "db"}));
+    }
+
+    #[test]
+    fn test_load_env_counts_loads() {
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            port: u16,
+        }
+
+        let registry = ConfigRegistry::new();
+        let source = EnvSource::prefixed("APP_").with_vars([("APP_PORT", "8080")]);
+        let handle = registry.load_env_with::<Config>(&source).unwrap();
+        assert_eq!(registry.read(&handle).unwrap().port, 8080);
+
+        let invalid = EnvSource::prefixed("APP_").with_vars([("APP_PORT", "high")]);
+        let error = registry.load_env_with::<Config>(&invalid).unwrap_err();
+        assert!(error.starts_with("superconfig.sources: Invalid environment APP_*"));
+        assert_eq!(registry.len(), 1);
+
+        let stats = registry.stats();
+        let env = stats.source_loads.iter().find(|s| s.name == "env").unwrap();
+        assert_eq!((env.total_loads, env.total_failures), (2, 1));
+    }
+
+    #[test]
+    fn test_load_env_reads_process_environment() {
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            #[serde(default)]
+            unset: Option<String>,
+        }
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .load_env::<Config>("SUPERCONFIG_TEST_UNSET_PREFIX_")
+            .unwrap();
+        assert!(registry.read(&handle).unwrap().unset.is_none());
+    }
+}
@@ -0,0 +1,357 @@
+//! Secrets read from GCP Secret Manager
+//!
+//! [`GcpSecretManager`] reads secret versions of a project through the Secret Manager
+//! REST API, as a [`SecretStore`] for a
+//! [`SecretSource`](super::secrets::SecretSource). A secret id such as `db-password`
+//! reads its latest version, and `db-password/versions/3` a pinned one.
+//!
+//! Requests carry an OAuth access token: the one given to
+//! [`with_token`](GcpSecretManager::with_token), else `GOOGLE_OAUTH_ACCESS_TOKEN`, else
+//! a token of the default service account from the metadata server, as on Compute
+//! Engine, GKE and Cloud Run. Metadata server tokens are cached until shortly before
+//! they expire.
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use superconfig::{ConfigRegistry, GcpSecretManager, SecretSource};
+//!
+//! #[derive(Deserialize)]
+//! struct App {
+//!     database: Database,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     password: String,
+//! }
+//!
+//! # tokio_test::block_on(async {
+//! let source = SecretSource::<App>::new(GcpSecretManager::new("my-project"))
+//!     .document("app-config")
+//!     .key("database.password", "db-password");
+//! let registry = ConfigRegistry::new();
+//! let handle = registry.create_from_source(&source).await.unwrap();
+//! # });
+//! ```
+
+use std::{
+    fmt,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use super::secrets::{SecretStore, SecretValue};
+
+pub use super::DEFAULT_TIMEOUT;
+
+/// Secret Manager API endpoint
+const ENDPOINT: &str = "https://secretmanager.googleapis.com";
+
+/// Metadata server of Google Cloud instances
+const METADATA_ENDPOINT: &str = "http://metadata.google.internal";
+
+/// Margin before a token's expiry after which a new one is requested
+const TOKEN_MARGIN: Duration = Duration::from_mins(1);
+
+/// Secrets of a GCP project's Secret Manager
+pub struct GcpSecretManager {
+    project: String,
+    endpoint: String,
+    metadata_endpoint: String,
+    token: Option<String>,
+    timeout: Duration,
+    /// Metadata server token and when it should be renewed
+    cached_token: Mutex<Option<(String, Instant)>>,
+    agent: OnceLock<ureq::Agent>,
+}
+
+impl GcpSecretManager {
+    /// Secrets of `project`, by project id or number
+    #[must_use]
+    pub fn new(project: impl Into<String>) -> Self {
+        Self {
+            project: project.into(),
+            endpoint: ENDPOINT.to_string(),
+            metadata_endpoint: METADATA_ENDPOINT.to_string(),
+            token: None,
+            timeout: DEFAULT_TIMEOUT,
+            cached_token: Mutex::new(None),
+            agent: OnceLock::new(),
+        }
+    }
+
+    /// Authenticate with the OAuth access `token`
+    #[must_use]
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Send requests to `endpoint` instead of `https://secretmanager.googleapis.com`,
+    /// such as a regional or Private Service Connect endpoint
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Ask the metadata server at `endpoint` for tokens, instead of
+    /// `http://metadata.google.internal`
+    #[must_use]
+    pub fn with_metadata_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.metadata_endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Time allowed for a whole request
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Resource name of the secret version `id`
+    fn version_name(&self, id: &str) -> String {
+        let secret = if id.starts_with("projects/") {
+            id.to_string()
+        } else {
+            format!("projects/{}/secrets/{id}", self.project)
+        };
+        if secret.contains("/versions/") {
+            secret
+        } else {
+            format!("{secret}/versions/latest")
+        }
+    }
+
+    /// The access token of requests
+    fn access_token(&self) -> Result<String, String> {
+        if let Some(token) = &self.token {
+            return Ok(token.clone());
+        }
+        if let Ok(token) = std::env::var("GOOGLE_OAUTH_ACCESS_TOKEN")
+            && !token.is_empty()
+        {
+            return Ok(token);
+        }
+        if let Some((token, renew_at)) = self.cached_token.lock().as_ref()
+            && Instant::now() < *renew_at
+        {
+            return Ok(token.clone());
+        }
+        let url = format!(
+            "{}/computeMetadata/v1/instance/service-accounts/default/token",
+            self.metadata_endpoint
+        );
+        let response = get_json(self.agent().get(&url).header("Metadata-Flavor", "Google"))
+            .map_err(|e| format!("cannot get a token from the metadata server: {e}"))?;
+        let token = response
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or("the metadata server returned no token")?
+            .to_string();
+        let lifetime = Duration::from_secs(
+            response
+                .get("expires_in")
+                .and_then(Value::as_u64)
+                .unwrap_or_default(),
+        );
+        *self.cached_token.lock() = Some((
+            token.clone(),
+            Instant::now() + lifetime.saturating_sub(TOKEN_MARGIN),
+        ));
+        Ok(token)
+    }
+
+    fn agent(&self) -> &ureq::Agent {
+        self.agent.get_or_init(|| {
+            ureq::Agent::new_with_config(
+                ureq::Agent::config_builder()
+                    .timeout_global(Some(self.timeout))
+                    .http_status_as_error(false)
+                    .build(),
+            )
+        })
+    }
+}
+
+impl SecretStore for GcpSecretManager {
+    fn name(&self) -> &'static str {
+        "gcp-secretmanager"
+    }
+
+    /// Access the secret version `id`, the latest one unless `id` names a version
+    fn fetch(&self, id: &str) -> Result<SecretValue, String> {
+        let url = format!("{}/v1/{}:access", self.endpoint, self.version_name(id));
+        let token = self.access_token()?;
+        let response = get_json(
+            self.agent()
+                .get(&url)
+                .header("Authorization", format!("Bearer {token}")),
+        )?;
+        let data = response
+            .pointer("/payload/data")
+            .and_then(Value::as_str)
+            .ok_or("the secret version has no payload")?;
+        let value = STANDARD
+            .decode(data)
+            .map_err(|e| format!("invalid payload: {e}"))
+            .and_then(|bytes| {
+                String::from_utf8(bytes).map_err(|_| "the payload is not UTF-8".to_string())
+            })?;
+        // The accessed version's name ends with its number, even for `latest`
+        let version = response
+            .get("name")
+            .and_then(Value::as_str)
+            .and_then(|name| name.rsplit('/').next())
+            .unwrap_or_default();
+        Ok(SecretValue::new(value, version))
+    }
+}
+
+impl fmt::Debug for GcpSecretManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GcpSecretManager")
+            .field("project", &self.project)
+            .field("endpoint", &self.endpoint)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Send `request`, returning the JSON document of a successful response
+fn get_json(request: ureq::RequestBuilder<ureq::typestate::WithoutBody>) -> Result<Value, String> {
+    let mut response = request.call().map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let text = response
+        .body_mut()
+        .read_to_string()
+        .map_err(|e| format!("cannot read response: {e}"))?;
+    let document: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+    if status != 200 {
+        let message = document
+            .pointer("/error/message")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        return Err(format!("HTTP {status}: {message}")
+            .trim_end_matches([' ', ':'])
+            .to_string());
+    }
+    if document.is_null() {
+        return Err("invalid response".to_string());
+    }
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::{
+        secrets::SecretSource,
+        test_server::{response, serve},
+    };
+    use serde_json::json;
+
+    fn version(project: &str, secret: &str, number: u32, value: &str) -> String {
+        json!({
+            "name": format!("projects/{project}/secrets/{secret}/versions/{number}"),
+            "payload": {"data": STANDARD.encode(value)},
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_version_names() {
+        let store = GcpSecretManager::new("p");
+        assert_eq!(
+            store.version_name("db"),
+            "projects/p/secrets/db/versions/latest"
+        );
+        assert_eq!(
+            store.version_name("db/versions/3"),
+            "projects/p/secrets/db/versions/3"
+        );
+        assert_eq!(
+            store.version_name("projects/q/secrets/db"),
+            "projects/q/secrets/db/versions/latest"
+        );
+    }
+
+    #[test]
+    fn test_fetch_with_token() {
+        let (url, requests) = serve(vec![response(
+            "200 OK",
+            "",
+            &version("p", "db", 4, "hunter2"),
+        )]);
+        let store = GcpSecretManager::new("p")
+            .with_endpoint(url)
+            .with_token("ya29.token");
+        assert_eq!(store.fetch("db").unwrap(), SecretValue::new("hunter2", "4"));
+
+        let requests = requests.lock();
+        assert!(requests[0].starts_with("GET /v1/projects/p/secrets/db/versions/latest:access "));
+        assert!(
+            requests[0]
+                .to_lowercase()
+                .contains("authorization: bearer ya29.token")
+        );
+    }
+
+    #[test]
+    fn test_metadata_server_token_is_cached() {
+        let token = json!({"access_token": "meta", "expires_in": 3599, "token_type": "Bearer"});
+        let (metadata, metadata_requests) = serve(vec![response("200 OK", "", &token.to_string())]);
+        let (url, requests) = serve(vec![
+            response(
+                "200 OK",
+                "",
+                &version("p", "config", 1, r#"{"port": 8080}"#),
+            ),
+            response(
+                "200 OK",
+                "",
+                &version("p", "config", 2, r#"{"port": 9090}"#),
+            ),
+        ]);
+        let store = GcpSecretManager::new("p")
+            .with_endpoint(url)
+            .with_metadata_endpoint(metadata);
+        let source = SecretSource::<Value>::new(store)
+            .document("config")
+            .with_ttl(Duration::ZERO);
+        assert_eq!(source.load_blocking().unwrap(), json!({"port": 8080}));
+        assert_eq!(source.load_blocking().unwrap(), json!({"port": 9090}));
+
+        assert_eq!(metadata_requests.lock().len(), 1);
+        assert!(
+            metadata_requests.lock()[0]
+                .to_lowercase()
+                .contains("metadata-flavor: google")
+        );
+        assert!(
+            requests
+                .lock()
+                .iter()
+                .all(|request| request.to_lowercase().contains("bearer meta"))
+        );
+    }
+
+    #[test]
+    fn test_error_message_is_reported() {
+        let error = json!({"error": {"code": 404, "message": "Secret [db] not found."}});
+        let (url, _) = serve(vec![response("404 Not Found", "", &error.to_string())]);
+        let store = GcpSecretManager::new("p")
+            .with_endpoint(url)
+            .with_token("t");
+        assert_eq!(
+            store.fetch("db").unwrap_err(),
+            "HTTP 404: Secret [db] not found."
+        );
+    }
+}
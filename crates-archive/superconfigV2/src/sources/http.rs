@@ -0,0 +1,555 @@
+//! Configuration fetched from HTTP(S) endpoints, such as an internal config service
+//!
+//! [`ConfigRegistry::load_url`] fetches and parses a configuration document in one
+//! call. An [`HttpSource`] adds request headers, a timeout and retries, and remembers
+//! the `ETag` of the last response: later fetches send `If-None-Match`, and a
+//! `304 Not Modified` reuses the cached document instead of downloading it again.
+//!
+//! Transport errors, `429` and `5xx` responses are retried with exponential backoff;
+//! other error statuses fail at once. The format comes from the `Content-Type` header,
+//! then the extension of the URL's path, then the content itself.
+//!
+//! [`HttpSource::start_refresh`] fetches the document periodically and updates the
+//! entry when it changed, until the returned [`HttpRefresh`] is dropped:
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use std::{sync::Arc, time::Duration};
+//! use superconfig::{ConfigRegistry, sources::http::HttpSource};
+//!
+//! #[derive(Deserialize)]
+//! struct App {
+//!     workers: u32,
+//! }
+//!
+//! let registry = Arc::new(ConfigRegistry::new());
+//! let source = HttpSource::new("https://config.mycorp/app.json")
+//!     .with_header("Authorization", "Bearer token")
+//!     .with_timeout(Duration::from_secs(5));
+//! let handle = registry.load_url_with::<App>(&source).unwrap();
+//!
+//! // Poll for changes every 30 seconds, sending the ETag of the first response
+//! let refresh = source
+//!     .start_refresh(&registry, &handle, Duration::from_secs(30))
+//!     .unwrap();
+//! ```
+
+use std::{
+    fmt,
+    path::Path,
+    sync::{Arc, OnceLock, Weak, mpsc},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::core::{ConfigHandle, ConfigRegistry, registry::HandleId};
+use crate::formats::{Format, parse_content};
+use logffi::{debug, error, warn};
+
+pub use super::DEFAULT_TIMEOUT;
+
+/// Retries after a failed request
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Delay before the first retry, doubled before each further retry
+pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(250);
+
+/// A configuration document served over HTTP(S)
+pub struct HttpSource {
+    url: String,
+    headers: Vec<(String, String)>,
+    format: Option<Format>,
+    timeout: Duration,
+    retries: u32,
+    backoff: Duration,
+    agent: OnceLock<ureq::Agent>,
+    cached: Mutex<Option<Cached>>,
+}
+
+/// The last document received, with the `ETag` it was served with
+struct Cached {
+    etag: String,
+    tree: Value,
+}
+
+/// Why a request failed, and whether retrying it may help
+enum Failure {
+    Transient(String),
+    Permanent(String),
+}
+
+impl HttpSource {
+    /// A source fetching `url`, with the default timeout and retries
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            format: None,
+            timeout: DEFAULT_TIMEOUT,
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            agent: OnceLock::new(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Send the header `name: value` with every request, such as a bearer token
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Parse responses as `format`, whatever their `Content-Type`
+    #[must_use]
+    pub const fn with_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Time allowed for each request
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry failed requests up to `retries` times, waiting `backoff` before the first
+    #[must_use]
+    pub const fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// URL of the document
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Fetch the document and deserialize it into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if every attempt failed, or the document fails to parse or
+    /// doesn't deserialize into `T`.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, String> {
+        let (tree, _) = self.fetch()?;
+        self.deserialize(tree)
+    }
+
+    /// Fetch the document, returning its tree and whether it changed since the last fetch
+    fn fetch(&self) -> Result<(Value, bool), String> {
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.request() {
+                Ok(fetched) => return Ok(fetched),
+                Err(Failure::Transient(e)) if attempt < self.retries => {
+                    attempt += 1;
+                    warn!(target: "superconfig.sources", "Fetching {} failed, retry {attempt} in {delay:?}: {e}", self.url);
+                    std::thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                Err(Failure::Transient(e) | Failure::Permanent(e)) => {
+                    return Err(http_error(&format!("Cannot fetch {}: {e}", self.url)));
+                }
+            }
+        }
+    }
+
+    /// Send one request, answering `304 Not Modified` from the cache
+    fn request(&self) -> Result<(Value, bool), Failure> {
+        let agent = self.agent.get_or_init(|| {
+            ureq::Agent::new_with_config(
+                ureq::Agent::config_builder()
+                    .timeout_global(Some(self.timeout))
+                    .http_status_as_error(false)
+                    .build(),
+            )
+        });
+        let mut request = agent.get(&self.url);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(cached) = &*self.cached.lock() {
+            request = request.header("If-None-Match", &cached.etag);
+        }
+
+        let mut response = request
+            .call()
+            .map_err(|e| Failure::Transient(e.to_string()))?;
+        let status = response.status().as_u16();
+        match status {
+            304 => self.cached.lock().as_ref().map_or_else(
+                || {
+                    Err(Failure::Permanent(
+                        "304 Not Modified without a cached document".to_string(),
+                    ))
+                },
+                |cached| Ok((cached.tree.clone(), false)),
+            ),
+            200..=299 => {
+                let header = |name: &str| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string)
+                };
+                let etag = header("etag");
+                let content_type = header("content-type");
+                let body = response
+                    .body_mut()
+                    .read_to_string()
+                    .map_err(|e| Failure::Transient(format!("cannot read body: {e}")))?;
+                let tree = self
+                    .parse(content_type.as_deref(), &body)
+                    .map_err(Failure::Permanent)?;
+                *self.cached.lock() = etag.map(|etag| Cached {
+                    etag,
+                    tree: tree.clone(),
+                });
+                Ok((tree, true))
+            }
+            429 | 500..=599 => Err(Failure::Transient(format!("HTTP {status}"))),
+            _ => Err(Failure::Permanent(format!("HTTP {status}"))),
+        }
+    }
+
+    /// Parse a response body in the format of its content type, URL or content
+    fn parse(&self, content_type: Option<&str>, body: &str) -> Result<Value, String> {
+        let format = self.format.or_else(|| {
+            let media_type = content_type?.split(';').next()?.trim();
+            Format::ALL.into_iter().find(|format| {
+                media_type.ends_with(&format!("/{}", format.name()))
+                    || media_type.ends_with(&format!("+{}", format.name()))
+            })
+        });
+        format.map_or_else(
+            || {
+                let path = self.url.split(['?', '#']).next().unwrap_or_default();
+                parse_content(Path::new(path), body)
+            },
+            |format| format.parse(body),
+        )
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, tree: Value) -> Result<T, String> {
+        serde_json::from_value(tree)
+            .map_err(|e| http_error(&format!("Invalid configuration from {}: {e}", self.url)))
+    }
+
+    /// Fetch the document every `interval` and update `handle` when it changed
+    ///
+    /// Requests send the `ETag` of the last response, so an unchanged document costs a
+    /// `304 Not Modified`. Failed fetches are logged and the entry keeps its
+    /// configuration. The refresh holds the registry weakly, and stops when it's dropped
+    /// or when the returned [`HttpRefresh`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the refresh thread can't be started.
+    pub fn start_refresh<T>(
+        self,
+        registry: &Arc<ConfigRegistry>,
+        handle: &ConfigHandle<T>,
+        interval: Duration,
+    ) -> Result<HttpRefresh, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let refresher = Arc::new(Refresher {
+            registry: Arc::downgrade(registry),
+            handle_id: handle.id(),
+            source: self,
+            store: |registry: &ConfigRegistry, handle_id: HandleId, source: &Self, tree| {
+                let data = source.deserialize::<T>(tree)?;
+                let handle = ConfigHandle::<T>::new(handle_id);
+                registry.update(&handle, data)?;
+                registry.generation(&handle)
+            },
+        });
+        let (stop, stopped) = mpsc::channel::<()>();
+        let worker = Arc::clone(&refresher);
+        let thread = std::thread::Builder::new()
+            .name("superconfig-http".to_string())
+            .spawn(move || {
+                // The channel disconnects when the `HttpRefresh` is dropped
+                while stopped.recv_timeout(interval) == Err(mpsc::RecvTimeoutError::Timeout) {
+                    match worker.refresh() {
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            warn!(target: "superconfig.sources", "Kept handle {} after failed refresh: {e}", worker.handle_id);
+                        }
+                        None => return,
+                    }
+                }
+            })
+            .map_err(|e| http_error(&format!("Cannot start refresh thread: {e}")))?;
+
+        debug!(target: "superconfig.sources", "Refreshing handle {} from {} every {interval:?}", handle.id(), refresher.source.url);
+        Ok(HttpRefresh {
+            stop: Some(stop),
+            thread: Some(thread),
+            refresher,
+        })
+    }
+}
+
+impl fmt::Debug for HttpSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Header values often carry credentials
+        let headers: Vec<&str> = self.headers.iter().map(|(name, _)| name.as_str()).collect();
+        f.debug_struct("HttpSource")
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("format", &self.format)
+            .field("timeout", &self.timeout)
+            .field("retries", &self.retries)
+            .field("backoff", &self.backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Fetches a source and stores changed documents into its entry
+trait Refresh: Send + Sync {
+    fn refresh(&self) -> Option<Result<Option<u64>, String>>;
+}
+
+struct Refresher<F> {
+    registry: Weak<ConfigRegistry>,
+    handle_id: HandleId,
+    source: HttpSource,
+    store: F,
+}
+
+impl<F> Refresh for Refresher<F>
+where
+    F: Fn(&ConfigRegistry, HandleId, &HttpSource, Value) -> Result<u64, String> + Send + Sync,
+{
+    /// Refresh the entry, returning its new generation if the document changed, or
+    /// `None` if the registry was dropped
+    fn refresh(&self) -> Option<Result<Option<u64>, String>> {
+        let registry = self.registry.upgrade()?;
+        let started = Instant::now();
+        let fetched = self.source.fetch();
+        registry.record_source_load("http", started.elapsed(), fetched.is_err());
+        Some(fetched.and_then(|(tree, changed)| {
+            if !changed {
+                return Ok(None);
+            }
+            let generation = (self.store)(&registry, self.handle_id, &self.source, tree)?;
+            debug!(target: "superconfig.sources", "Refreshed handle {} from {}", self.handle_id, self.source.url);
+            Ok(Some(generation))
+        }))
+    }
+}
+
+/// Running periodic refresh of an entry from an [`HttpSource`], stopped when dropped
+pub struct HttpRefresh {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    refresher: Arc<dyn Refresh>,
+}
+
+impl HttpRefresh {
+    /// Fetch the document now, without waiting for the next interval
+    ///
+    /// Returns the entry's new generation, or `None` if the document didn't change.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the registry was dropped, or the document failed to
+    /// fetch or load; the entry keeps its configuration.
+    pub fn refresh(&self) -> Result<Option<u64>, String> {
+        self.refresher
+            .refresh()
+            .unwrap_or_else(|| Err(http_error("Registry was dropped")))
+    }
+}
+
+impl Drop for HttpRefresh {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            error!(target: "superconfig.sources", "Refresh thread panicked");
+        }
+    }
+}
+
+impl fmt::Debug for HttpRefresh {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpRefresh")
+            .field("running", &self.thread.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+fn http_error(message: &str) -> String {
+    error!(target: "superconfig.sources", "{message}");
+    format!("superconfig.sources: {message}")
+}
+
+impl ConfigRegistry {
+    /// Fetch the configuration document at `url` into `T` and store it
+    ///
+    /// Uses the default timeout and retries; see [`load_url_with`](Self::load_url_with)
+    /// to change them or to refresh the entry periodically.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the document can't be fetched, fails to parse, or
+    /// doesn't deserialize into `T`.
+    pub fn load_url<T>(&self, url: &str) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.load_url_with(&HttpSource::new(url))
+    }
+
+    /// Fetch the document of `source` into `T` and store it
+    ///
+    /// The response's `ETag` is kept in `source`, so a later
+    /// [`start_refresh`](HttpSource::start_refresh) only downloads a changed document.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the document can't be fetched, fails to parse, or
+    /// doesn't deserialize into `T`.
+    pub fn load_url_with<T>(&self, source: &HttpSource) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let started = Instant::now();
+        let result = source.load();
+        self.record_source_load("http", started.elapsed(), result.is_err());
+        self.create(result?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::test_server::{response, serve};
+    use serde::Deserialize;
+    use std::net::TcpListener;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct App {
+        workers: u32,
+    }
+
+    fn quick(url: &str) -> HttpSource {
+        HttpSource::new(url).with_retries(2, Duration::from_millis(1))
+    }
+
+    #[test]
+    fn test_load_url_with_etag_cache() {
+        let (url, requests) = serve(vec![
+            response("200 OK", "ETag: \"v1\"\r\n", r#"{"workers": 4}"#),
+            response("304 Not Modified", "", ""),
+        ]);
+        let source = quick(&format!("{url}/app.json")).with_header("X-Token", "secret");
+        let registry = ConfigRegistry::new();
+        let handle = registry.load_url_with::<App>(&source).unwrap();
+        assert_eq!(registry.read(&handle).unwrap().workers, 4);
+
+        assert_eq!(
+            source.fetch().unwrap(),
+            (serde_json::json!({"workers": 4}), false)
+        );
+        let requests: Vec<String> = requests.lock().iter().map(|r| r.to_lowercase()).collect();
+        assert!(requests[0].starts_with("get /app.json "));
+        assert!(requests[0].contains("x-token: secret"));
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+        assert!(!format!("{source:?}").contains("secret"));
+        let stats = registry.stats();
+        let http = stats
+            .source_loads
+            .iter()
+            .find(|s| s.name == "http")
+            .unwrap();
+        assert_eq!(http.total_loads, 1);
+    }
+
+    #[test]
+    fn test_retries_transient_failures_only() {
+        let (url, requests) = serve(vec![
+            response("503 Service Unavailable", "", ""),
+            response(
+                "200 OK",
+                "Content-Type: application/json\r\n",
+                r#"{"workers": 2}"#,
+            ),
+            response("404 Not Found", "", ""),
+        ]);
+        let source = quick(&format!("{url}/config"));
+        assert_eq!(source.load::<App>().unwrap(), App { workers: 2 });
+
+        let error = source.load::<App>().unwrap_err();
+        assert!(error.starts_with("superconfig.sources: Cannot fetch"));
+        assert!(error.ends_with("HTTP 404"));
+        assert_eq!(requests.lock().len(), 3);
+    }
+
+    #[test]
+    fn test_refresh_updates_changed_documents() {
+        let (url, _) = serve(vec![
+            response("200 OK", "ETag: \"v1\"\r\n", r#"{"workers": 1}"#),
+            response("304 Not Modified", "", ""),
+            response("200 OK", "ETag: \"v2\"\r\n", r#"{"workers": 8}"#),
+            response("200 OK", "", r#"{"workers": "many"}"#),
+        ]);
+        let registry = Arc::new(ConfigRegistry::new());
+        let source = quick(&format!("{url}/app.json"));
+        let handle = registry.load_url_with::<App>(&source).unwrap();
+
+        // An hour-long interval leaves the refreshes to the test
+        let refresh = source
+            .start_refresh(&registry, &handle, Duration::from_secs(3600))
+            .unwrap();
+        assert_eq!(refresh.refresh().unwrap(), None);
+        assert_eq!(refresh.refresh().unwrap(), Some(2));
+        assert_eq!(registry.read(&handle).unwrap().workers, 8);
+
+        assert!(
+            refresh
+                .refresh()
+                .unwrap_err()
+                .contains("Invalid configuration")
+        );
+        assert_eq!(registry.read(&handle).unwrap().workers, 8);
+        drop(refresh);
+    }
+
+    #[test]
+    fn test_unreachable_url_fails_after_retries() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let registry = ConfigRegistry::new();
+        let error = registry
+            .load_url_with::<App>(&quick(&format!("http://127.0.0.1:{port}/app.json")))
+            .unwrap_err();
+        assert!(error.starts_with("superconfig.sources: Cannot fetch http://127.0.0.1"));
+        let stats = registry.stats();
+        let http = stats
+            .source_loads
+            .iter()
+            .find(|s| s.name == "http")
+            .unwrap();
+        assert_eq!(http.total_failures, 1);
+    }
+}
@@ -0,0 +1,681 @@
+//! Configuration stored in Consul KV or etcd v3
+//!
+//! A [`KvSource`] reads every key under a prefix and nests the keys into tables on
+//! `/`: with the prefix `app/`, the key `app/db/host` sets `db.host`. Values are
+//! coerced like [environment variables](super::env), so `8080` is a number and
+//! `["a", "b"]` a list.
+//!
+//! [`KvSource::start_watch`] keeps the entry in sync with the store, updating it after
+//! each change under the prefix:
+//!
+//! - **Consul** is watched with blocking queries on the KV HTTP API, which return as
+//!   soon as the prefix's index moves or after the wait time.
+//! - **etcd** is watched through the v3 JSON gateway's watch stream, starting after
+//!   the revision last read, and the prefix is read again after each event.
+//!
+//! Both backends are reached over HTTP(S), and a token is sent as `X-Consul-Token` to
+//! Consul and as `Authorization` to etcd.
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use std::sync::Arc;
+//! use superconfig::{ConfigRegistry, sources::kv::KvSource};
+//!
+//! #[derive(Deserialize)]
+//! struct App {
+//!     db: Db,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Db {
+//!     host: String,
+//!     pool_size: u32,
+//! }
+//!
+//! let registry = Arc::new(ConfigRegistry::new());
+//! let source = KvSource::consul("http://127.0.0.1:8500", "services/app/");
+//! let handle = registry.load_kv::<App>(&source).unwrap();
+//! let watch = source.start_watch(&registry, &handle).unwrap();
+//! ```
+
+use std::{
+    fmt,
+    io::{BufRead, BufReader},
+    sync::{
+        Arc, OnceLock, Weak,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+
+use super::{env::coerce, insert_path};
+use crate::core::{ConfigHandle, ConfigRegistry};
+use logffi::{debug, error, warn};
+
+/// Time allowed for reading the keys
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest time a watch waits for a change before asking again
+pub const DEFAULT_WAIT: Duration = Duration::from_secs(30);
+
+/// Delay before watching again after a failed request
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Key-value store holding the configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KvBackend {
+    /// Consul's KV store, through its HTTP API
+    Consul,
+    /// etcd v3, through its JSON gateway
+    Etcd,
+}
+
+impl KvBackend {
+    /// Name of the backend, used in errors and source statistics
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Consul => "consul",
+            Self::Etcd => "etcd",
+        }
+    }
+}
+
+/// The keys under a prefix of a Consul or etcd store
+pub struct KvSource {
+    backend: KvBackend,
+    endpoint: String,
+    prefix: String,
+    token: Option<String>,
+    timeout: Duration,
+    wait: Duration,
+    /// Consul index or etcd revision of the last read
+    index: AtomicU64,
+    agent: OnceLock<ureq::Agent>,
+}
+
+impl KvSource {
+    /// The keys under `prefix` of the Consul agent at `endpoint`, such as
+    /// `http://127.0.0.1:8500`
+    #[must_use]
+    pub fn consul(endpoint: impl Into<String>, prefix: impl Into<String>) -> Self {
+        Self::new(KvBackend::Consul, &endpoint.into(), prefix.into())
+    }
+
+    /// The keys under `prefix` of the etcd server at `endpoint`, such as
+    /// `http://127.0.0.1:2379`
+    #[must_use]
+    pub fn etcd(endpoint: impl Into<String>, prefix: impl Into<String>) -> Self {
+        Self::new(KvBackend::Etcd, &endpoint.into(), prefix.into())
+    }
+
+    fn new(backend: KvBackend, endpoint: &str, prefix: String) -> Self {
+        Self {
+            backend,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            prefix,
+            token: None,
+            timeout: DEFAULT_TIMEOUT,
+            wait: DEFAULT_WAIT,
+            index: AtomicU64::new(0),
+            agent: OnceLock::new(),
+        }
+    }
+
+    /// Authenticate with `token`
+    #[must_use]
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Time allowed for reading the keys
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Longest time a watch waits for a change before asking again
+    #[must_use]
+    pub const fn with_wait(mut self, wait: Duration) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    /// Store holding the keys
+    #[must_use]
+    pub const fn backend(&self) -> KvBackend {
+        self.backend
+    }
+
+    /// Prefix of the keys
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Read the keys and deserialize them into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the store can't be read, or the keys don't deserialize
+    /// into `T`.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, String> {
+        self.deserialize(self.read()?)
+    }
+
+    /// Read the keys into a tree, remembering the index of the read
+    fn read(&self) -> Result<Value, String> {
+        let (pairs, index) = match self.backend {
+            KvBackend::Consul => self.consul_get(None)?,
+            KvBackend::Etcd => self.etcd_range()?,
+        };
+        self.index.store(index, Ordering::Relaxed);
+        Ok(self.tree(pairs))
+    }
+
+    /// Wait for a change after the last read, returning the new tree if there was one
+    fn wait_for_change(&self) -> Result<Option<Value>, String> {
+        let index = self.index.load(Ordering::Relaxed);
+        match self.backend {
+            KvBackend::Consul => {
+                let (pairs, next) = self.consul_get(Some(index))?;
+                // An unchanged index means the wait ran out
+                if next == index {
+                    return Ok(None);
+                }
+                self.index.store(next, Ordering::Relaxed);
+                Ok(Some(self.tree(pairs)))
+            }
+            KvBackend::Etcd => {
+                if self.etcd_watch(index)? {
+                    self.read().map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    /// Nest `key = value` pairs under the prefix into a tree
+    fn tree(&self, pairs: Vec<(String, String)>) -> Value {
+        let mut tree = Map::new();
+        for (key, value) in pairs {
+            let Some(rest) = key.strip_prefix(&self.prefix) else {
+                continue;
+            };
+            let path: Vec<String> = rest
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(str::to_string)
+                .collect();
+            if !path.is_empty() {
+                insert_path(&mut tree, &path, coerce(&value));
+            }
+        }
+        Value::Object(tree)
+    }
+
+    /// Read the keys from Consul, with a blocking query if `index` is given
+    fn consul_get(&self, index: Option<u64>) -> Result<(Vec<(String, String)>, u64), String> {
+        let query = index.map_or_else(String::new, |index| {
+            format!("&index={index}&wait={}s", self.wait.as_secs().max(1))
+        });
+        let url = format!(
+            "{}/v1/kv/{}?recurse=true{query}",
+            self.endpoint, self.prefix
+        );
+        let mut request = self.agent().get(&url);
+        if let Some(token) = &self.token {
+            request = request.header("X-Consul-Token", token);
+        }
+        let mut response = request.call().map_err(|e| self.error(&e.to_string()))?;
+        let status = response.status().as_u16();
+        let next = response
+            .headers()
+            .get("x-consul-index")
+            .and_then(|value| value.to_str().ok()?.parse().ok())
+            .unwrap_or(0);
+        match status {
+            // Consul answers 404 when no key has the prefix
+            404 => Ok((Vec::new(), next)),
+            200 => {
+                let entries: Vec<Value> = response
+                    .body_mut()
+                    .read_to_string()
+                    .map_err(|e| e.to_string())
+                    .and_then(|body| serde_json::from_str(&body).map_err(|e| e.to_string()))
+                    .map_err(|e| self.error(&format!("invalid response: {e}")))?;
+                let pairs = entries
+                    .iter()
+                    .filter_map(|entry| {
+                        let key = entry.get("Key")?.as_str()?;
+                        let value = entry.get("Value")?.as_str()?;
+                        Some((key.to_string(), decode(value)))
+                    })
+                    .collect();
+                Ok((pairs, next))
+            }
+            _ => Err(self.error(&format!("HTTP {status}"))),
+        }
+    }
+
+    /// Read the keys from etcd with a range request over the prefix
+    fn etcd_range(&self) -> Result<(Vec<(String, String)>, u64), String> {
+        let body = self
+            .etcd_post(
+                "kv/range",
+                &json!({
+                    "key": STANDARD.encode(&self.prefix),
+                    "range_end": STANDARD.encode(prefix_end(self.prefix.as_bytes())),
+                }),
+                self.timeout,
+            )?
+            .ok_or_else(|| self.error("timed out"))?;
+        let response: Value = serde_json::from_reader(body)
+            .map_err(|e| self.error(&format!("invalid response: {e}")))?;
+        let pairs = response
+            .get("kvs")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|pair| {
+                let key = decode(pair.get("key")?.as_str()?);
+                let value = pair.get("value").and_then(Value::as_str).unwrap_or("");
+                Some((key, decode(value)))
+            })
+            .collect();
+        // The gateway writes 64-bit integers as strings
+        let revision = response
+            .pointer("/header/revision")
+            .and_then(|revision| match revision {
+                Value::String(text) => text.parse().ok(),
+                other => other.as_u64(),
+            })
+            .unwrap_or(0);
+        Ok((pairs, revision))
+    }
+
+    /// Watch the prefix after `revision`, returning whether a change happened before the
+    /// wait time ran out
+    fn etcd_watch(&self, revision: u64) -> Result<bool, String> {
+        let body = self.etcd_post(
+            "watch",
+            &json!({
+                "create_request": {
+                    "key": STANDARD.encode(&self.prefix),
+                    "range_end": STANDARD.encode(prefix_end(self.prefix.as_bytes())),
+                    "start_revision": (revision + 1).to_string(),
+                }
+            }),
+            self.wait,
+        )?;
+        let Some(body) = body else {
+            return Ok(false);
+        };
+        for line in BufReader::new(body).lines() {
+            // The stream is cut when the wait time runs out
+            let Ok(line) = line else {
+                return Ok(false);
+            };
+            let message: Value = serde_json::from_str(&line)
+                .map_err(|e| self.error(&format!("invalid watch message: {e}")))?;
+            if message.pointer("/result/canceled") == Some(&Value::Bool(true)) {
+                return Err(self.error("watch was canceled"));
+            }
+            let changed = message
+                .pointer("/result/events")
+                .and_then(Value::as_array)
+                .is_some_and(|events| !events.is_empty());
+            if changed {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Post `body` to an etcd gateway endpoint, returning the response body, or `None`
+    /// if `timeout` ran out
+    fn etcd_post(
+        &self,
+        path: &str,
+        body: &Value,
+        timeout: Duration,
+    ) -> Result<Option<ureq::BodyReader<'static>>, String> {
+        let mut request = self
+            .agent()
+            .post(format!("{}/v3/{path}", self.endpoint))
+            .config()
+            .timeout_global(Some(timeout))
+            .build()
+            .header("Content-Type", "application/json");
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", token);
+        }
+        let response = match request.send(body.to_string()) {
+            Ok(response) => response,
+            Err(ureq::Error::Timeout(_)) => return Ok(None),
+            Err(e) => return Err(self.error(&e.to_string())),
+        };
+        let status = response.status().as_u16();
+        if status != 200 {
+            return Err(self.error(&format!("HTTP {status}")));
+        }
+        Ok(Some(response.into_body().into_reader()))
+    }
+
+    fn agent(&self) -> &ureq::Agent {
+        self.agent.get_or_init(|| {
+            // Blocking queries may take the whole wait time, plus Consul's jitter
+            let consul_wait = self.wait + self.wait / 16 + self.timeout;
+            ureq::Agent::new_with_config(
+                ureq::Agent::config_builder()
+                    .timeout_global(Some(match self.backend {
+                        KvBackend::Consul => consul_wait,
+                        KvBackend::Etcd => self.timeout,
+                    }))
+                    .http_status_as_error(false)
+                    .build(),
+            )
+        })
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, tree: Value) -> Result<T, String> {
+        serde_json::from_value(tree).map_err(|e| self.error(&format!("invalid configuration: {e}")))
+    }
+
+    fn error(&self, message: &str) -> String {
+        let name = self.backend.name();
+        error!(target: "superconfig.sources", "Cannot read {name} keys {}: {message}", self.prefix);
+        format!(
+            "superconfig.sources: Cannot read {name} keys {}: {message}",
+            self.prefix
+        )
+    }
+
+    /// Update `handle` after each change of the keys, until the returned [`KvWatch`] is
+    /// dropped
+    ///
+    /// The watch starts after the last read of this source, such as by
+    /// [`load_kv`](ConfigRegistry::load_kv), so changes made since are not missed. When
+    /// the keys fail to read or no longer deserialize, the entry keeps its
+    /// configuration. The watch holds the registry weakly.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the watch thread can't be started.
+    pub fn start_watch<T>(
+        self,
+        registry: &Arc<ConfigRegistry>,
+        handle: &ConfigHandle<T>,
+    ) -> Result<KvWatch, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&stopped);
+        let registry = Arc::downgrade(registry);
+        let handle_id = handle.id();
+        let name = format!("superconfig-{}", self.backend.name());
+        std::thread::Builder::new()
+            .name(name)
+            .spawn(move || {
+                watch_loop(&self, &registry, &stopped, |registry, tree| {
+                    let handle = ConfigHandle::<T>::new(handle_id);
+                    registry.update(&handle, self.deserialize(tree)?)?;
+                    debug!(target: "superconfig.sources", "Updated handle {handle_id} from {} keys {}", self.backend.name(), self.prefix);
+                    Ok(())
+                });
+            })
+            .map_err(|e| {
+                error!(target: "superconfig.sources", "Cannot start watch thread: {e}");
+                format!("superconfig.sources: Cannot start watch thread: {e}")
+            })?;
+        Ok(KvWatch { stop })
+    }
+}
+
+/// Wait for changes of `source` and store them, until stopped or the registry is dropped
+fn watch_loop(
+    source: &KvSource,
+    registry: &Weak<ConfigRegistry>,
+    stopped: &AtomicBool,
+    store: impl Fn(&ConfigRegistry, Value) -> Result<(), String>,
+) {
+    let name = source.backend.name();
+    while !stopped.load(Ordering::Relaxed) {
+        let started = Instant::now();
+        let change = source.wait_for_change();
+        if stopped.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(registry) = registry.upgrade() else {
+            return;
+        };
+        match change {
+            Ok(None) => {}
+            Ok(Some(tree)) => {
+                registry.record_source_load(name, started.elapsed(), false);
+                if let Err(e) = store(&registry, tree) {
+                    warn!(target: "superconfig.sources", "Kept configuration after failed {name} update: {e}");
+                }
+            }
+            Err(_) => {
+                registry.record_source_load(name, started.elapsed(), true);
+                drop(registry);
+                std::thread::sleep(RETRY_DELAY);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for KvSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KvSource")
+            .field("backend", &self.backend)
+            .field("endpoint", &self.endpoint)
+            .field("prefix", &self.prefix)
+            .field("timeout", &self.timeout)
+            .field("wait", &self.wait)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Running watch of a [`KvSource`], stopped when dropped
+///
+/// Dropping doesn't wait for the watch thread, which exits once its pending request
+/// returns, at the latest after the source's wait time.
+#[derive(Debug)]
+pub struct KvWatch {
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for KvWatch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Decode a base64 value, as both stores encode them
+fn decode(value: &str) -> String {
+    STANDARD.decode(value).map_or_else(
+        |_| value.to_string(),
+        |bytes| String::from_utf8_lossy(&bytes).into_owned(),
+    )
+}
+
+/// End of the etcd key range holding every key starting with `prefix`
+fn prefix_end(prefix: &[u8]) -> Vec<u8> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return end;
+        }
+    }
+    // Every key: etcd reads `\0` as "no end"
+    vec![0]
+}
+
+impl ConfigRegistry {
+    /// Read the keys of `source` into `T` and store it
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the store can't be read, or the keys don't deserialize
+    /// into `T`.
+    pub fn load_kv<T>(&self, source: &KvSource) -> Result<ConfigHandle<T>, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let started = Instant::now();
+        let result = source.load();
+        self.record_source_load(source.backend.name(), started.elapsed(), result.is_err());
+        self.create(result?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::test_server::{response, serve};
+    use serde::Deserialize;
+    use std::net::TcpListener;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct App {
+        db: Db,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Db {
+        host: String,
+        port: u16,
+    }
+
+    fn consul_keys(index: u64, pairs: &[(&str, &str)]) -> String {
+        let entries: Vec<Value> = pairs
+            .iter()
+            .map(|(key, value)| json!({"Key": key, "Value": STANDARD.encode(value)}))
+            .chain([json!({"Key": "app/", "Value": null})])
+            .collect();
+        response(
+            "200 OK",
+            &format!("X-Consul-Index: {index}\r\n"),
+            &Value::Array(entries).to_string(),
+        )
+    }
+
+    fn etcd_keys(revision: u64, pairs: &[(&str, &str)]) -> String {
+        let kvs: Vec<Value> = pairs
+            .iter()
+            .map(|(key, value)| json!({"key": STANDARD.encode(key), "value": STANDARD.encode(value)}))
+            .collect();
+        response(
+            "200 OK",
+            "",
+            &json!({"header": {"revision": revision.to_string()}, "kvs": kvs}).to_string(),
+        )
+    }
+
+    fn wait_for(registry: &ConfigRegistry, handle: &ConfigHandle<App>, port: u16) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while registry.read(handle).unwrap().db.port != port {
+            assert!(Instant::now() < deadline, "no update to port {port}");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_prefix_end() {
+        assert_eq!(prefix_end(b"app/"), b"app0");
+        assert_eq!(prefix_end(b"a\xff"), b"b");
+        assert_eq!(prefix_end(b""), [0]);
+    }
+
+    #[test]
+    fn test_consul_load_and_watch() {
+        let (url, requests) = serve(vec![
+            consul_keys(
+                7,
+                &[
+                    ("app/db/host", "db"),
+                    ("app/db/port", "5432"),
+                    ("other", "x"),
+                ],
+            ),
+            consul_keys(8, &[("app/db/host", "db"), ("app/db/port", "6543")]),
+        ]);
+        let registry = Arc::new(ConfigRegistry::new());
+        let source = KvSource::consul(url, "app/").with_token("secret");
+        let handle = registry.load_kv::<App>(&source).unwrap();
+        assert_eq!(
+            *registry.read(&handle).unwrap(),
+            App {
+                db: Db {
+                    host: "db".to_string(),
+                    port: 5432
+                }
+            }
+        );
+
+        let watch = source.start_watch(&registry, &handle).unwrap();
+        wait_for(&registry, &handle, 6543);
+        drop(watch);
+
+        let requests: Vec<String> = requests.lock().iter().map(|r| r.to_lowercase()).collect();
+        assert!(requests[0].starts_with("get /v1/kv/app/?recurse=true "));
+        assert!(requests[0].contains("x-consul-token: secret"));
+        assert!(requests[1].starts_with("get /v1/kv/app/?recurse=true&index=7&wait=30s "));
+    }
+
+    #[test]
+    fn test_etcd_load_and_watch() {
+        let watch_stream = "{\"result\":{\"created\":true}}\n{\"result\":{\"events\":[{}]}}\n";
+        let (url, requests) = serve(vec![
+            etcd_keys(5, &[("app/db/host", "db"), ("app/db/port", "5432")]),
+            format!("HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n{watch_stream}"),
+            etcd_keys(6, &[("app/db/host", "db"), ("app/db/port", "6543")]),
+        ]);
+        let registry = Arc::new(ConfigRegistry::new());
+        let source = KvSource::etcd(url, "app/");
+        let handle = registry.load_kv::<App>(&source).unwrap();
+        assert_eq!(registry.read(&handle).unwrap().db.port, 5432);
+
+        let watch = source.start_watch(&registry, &handle).unwrap();
+        wait_for(&registry, &handle, 6543);
+        drop(watch);
+
+        let requests: Vec<String> = requests.lock().iter().map(|r| r.to_lowercase()).collect();
+        assert!(requests[0].starts_with("post /v3/kv/range "));
+        assert!(requests[0].contains(&STANDARD.encode("app0").to_lowercase()));
+        assert!(requests[1].starts_with("post /v3/watch "));
+        assert!(requests[1].contains("\"start_revision\":\"6\""));
+    }
+
+    #[test]
+    fn test_unreachable_store_is_an_error() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let registry = ConfigRegistry::new();
+        let source = KvSource::consul(format!("http://127.0.0.1:{port}"), "app/");
+        let error = registry.load_kv::<App>(&source).unwrap_err();
+        assert!(error.starts_with("superconfig.sources: Cannot read consul keys app/"));
+        let stats = registry.stats();
+        let consul = stats
+            .source_loads
+            .iter()
+            .find(|s| s.name == "consul")
+            .unwrap();
+        assert_eq!(consul.total_failures, 1);
+    }
+}
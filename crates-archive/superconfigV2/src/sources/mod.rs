@@ -0,0 +1,79 @@
+//! Configuration sources read from the process environment, command line and network
+//!
+//! Sources build a [`serde_json::Value`] tree from where the configuration lives and
+//! deserialize it into the typed configuration stored in the registry. They complement
+//! the [`formats`](crate::formats) module, which reads configuration files:
+//!
+//! - [`env`] - Environment variables nested into tables, such as `APP_DB_HOST`
+//! - [`cli`] - Command-line flags with dotted keys, such as `--db.host=localhost`
+//! - `http` - Documents fetched from HTTP(S) endpoints, with the `http` feature
+//! - `kv` - Keys of Consul KV or etcd v3 nested on `/`, with the `kv` feature
+//! - `secrets` - Documents and keys held by AWS Secrets Manager, SSM Parameter Store
+//!   (`aws`) or GCP Secret Manager (`gcp`), with the `aws` or `gcp` feature
+//!
+//! Every load is counted in [`RegistryStats::source_loads`](crate::RegistryStats) under
+//! the source's name.
+
+#[cfg(feature = "aws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "aws")))]
+pub mod aws;
+pub mod cli;
+pub mod env;
+#[cfg(feature = "gcp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gcp")))]
+pub mod gcp;
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+pub mod http;
+#[cfg(feature = "kv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv")))]
+pub mod kv;
+#[cfg(feature = "secrets")]
+#[cfg_attr(docsrs, doc(cfg(feature = "secrets")))]
+pub mod secrets;
+#[cfg(all(
+    test,
+    any(feature = "aws", feature = "gcp", feature = "http", feature = "kv")
+))]
+mod test_server;
+
+#[cfg(feature = "aws")]
+pub use aws::{AwsCredentials, AwsParameterStore, AwsSecretsManager};
+pub use cli::CliSource;
+pub use env::EnvSource;
+#[cfg(feature = "gcp")]
+pub use gcp::GcpSecretManager;
+#[cfg(feature = "http")]
+pub use http::HttpSource;
+#[cfg(feature = "kv")]
+pub use kv::KvSource;
+#[cfg(feature = "secrets")]
+pub use secrets::{SecretRefresh, SecretSource, SecretStore, SecretValue};
+
+use serde_json::{Map, Value};
+use std::time::Duration;
+
+/// Time allowed for a whole request of a network source, from connecting to reading
+/// the body
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Insert `value` at `path`, replacing non-table values on the way with tables
+pub(crate) fn insert_path(tree: &mut Map<String, Value>, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut table = tree;
+    for key in parents {
+        let node = table
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !node.is_object() {
+            *node = Value::Object(Map::new());
+        }
+        let Value::Object(child) = node else {
+            unreachable!("node was just made a table");
+        };
+        table = child;
+    }
+    table.insert(last.clone(), value);
+}
@@ -0,0 +1,592 @@
+//! Configuration read from cloud secret managers
+//!
+//! A [`SecretSource`] builds the configuration from secrets of a [`SecretStore`]:
+//!
+//! - a **document** secret holds a whole configuration document in any
+//!   [format](crate::Format), merged into the configuration like a JSON Merge Patch
+//! - a **key** secret holds one value, such as a password, stored as a string at a
+//!   dotted key such as `database.password`
+//!
+//! The stores are AWS Secrets Manager and SSM Parameter Store with the `aws` feature,
+//! and GCP Secret Manager with the `gcp` feature. Other stores implement
+//! [`SecretStore`].
+//!
+//! A [`SecretSource`] is an [`AsyncConfigSource`], fetching on tokio's blocking thread
+//! pool, so it loads with [`create_from_source`](ConfigRegistry::create_from_source).
+//! Fetched secrets are cached for the source's TTL, so loading again soon after, such
+//! as with [`update_from_source`](ConfigRegistry::update_from_source), doesn't ask the
+//! store. [`SecretSource::start_refresh`] asks the store periodically and updates the
+//! entry once a secret is rotated, that is when its version changes.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use std::{sync::Arc, time::Duration};
+//! use superconfig::{ConfigRegistry, SecretSource, SecretStore, SecretValue};
+//!
+//! struct Vault;
+//!
+//! impl SecretStore for Vault {
+//!     fn name(&self) -> &str {
+//!         "vault"
+//!     }
+//!
+//!     fn fetch(&self, id: &str) -> Result<SecretValue, String> {
+//!         let value = match id {
+//!             "app/config" => r#"{"database": {"host": "db.internal", "password": ""}}"#,
+//!             "app/db-password" => "hunter2",
+//!             _ => return Err(format!("no secret {id}")),
+//!         };
+//!         Ok(SecretValue::new(value, "1"))
+//!     }
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct App {
+//!     database: Database,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     password: String,
+//! }
+//!
+//! # tokio_test::block_on(async {
+//! let source = SecretSource::<App>::new(Vault)
+//!     .document("app/config")
+//!     .key("database.password", "app/db-password")
+//!     .with_ttl(Duration::from_secs(60));
+//! let registry = Arc::new(ConfigRegistry::new());
+//! let handle = registry.create_from_source(&source).await.unwrap();
+//! assert_eq!(registry.read(&handle).unwrap().database.password, "hunter2");
+//!
+//! // Update the entry when a secret is rotated, checking every 5 minutes
+//! let refresh = source
+//!     .start_refresh(&registry, &handle, Duration::from_secs(300))
+//!     .unwrap();
+//! # });
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    sync::{Arc, mpsc},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use super::insert_path;
+use crate::core::{AsyncConfigSource, ConfigHandle, ConfigRegistry, patch::apply_merge_patch};
+use crate::formats::Format;
+use logffi::{debug, error, warn};
+
+/// How long fetched secrets are reused before asking the store again
+pub const DEFAULT_TTL: Duration = Duration::from_mins(5);
+
+/// A secret fetched from a store, with the version identifying its rotation
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretValue {
+    /// The secret itself
+    pub value: String,
+    /// Version of the secret, which changes when it's rotated
+    pub version: String,
+}
+
+impl SecretValue {
+    /// The secret `value` at `version`
+    #[must_use]
+    pub fn new(value: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            version: version.into(),
+        }
+    }
+}
+
+impl fmt::Debug for SecretValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretValue")
+            .field("value", &crate::types::SECRET_MASK)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+/// A secret manager holding secrets by id
+pub trait SecretStore: Send + Sync {
+    /// Name of the store, used in errors and source statistics
+    fn name(&self) -> &str;
+
+    /// Fetch the current version of the secret `id`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the secret can't be fetched.
+    fn fetch(&self, id: &str) -> Result<SecretValue, String>;
+}
+
+/// Where the value of a secret goes in the configuration
+#[derive(Debug, Clone)]
+enum Target {
+    Document,
+    Key(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    id: String,
+    target: Target,
+}
+
+struct Cached {
+    secret: SecretValue,
+    fetched: Instant,
+}
+
+/// The untyped part of a source, shared with blocking tasks and refresh threads
+#[derive(Clone)]
+struct Secrets {
+    store: Arc<dyn SecretStore>,
+    bindings: Vec<Binding>,
+    ttl: Duration,
+    cache: Arc<Mutex<HashMap<String, Cached>>>,
+}
+
+impl Secrets {
+    /// Build the configuration tree, fetching secrets missing from the cache or all of
+    /// them if `fresh`, and telling whether a secret's version changed
+    fn tree(&self, fresh: bool) -> Result<(Value, bool), String> {
+        let mut tree = Value::Object(Map::new());
+        let mut rotated = false;
+        for binding in &self.bindings {
+            let (secret, changed) = self.secret(&binding.id, fresh)?;
+            rotated |= changed;
+            match &binding.target {
+                Target::Document => {
+                    let document = Format::detect(&secret.value)
+                        .ok_or_else(|| {
+                            self.error(&format!("Unknown format of secret {}", binding.id))
+                        })?
+                        .parse(&secret.value)
+                        .map_err(|e| self.error(&format!("{e} (in secret {})", binding.id)))?;
+                    apply_merge_patch(&mut tree, &document);
+                }
+                Target::Key(path) => {
+                    if let Value::Object(table) = &mut tree {
+                        insert_path(table, path, Value::String(secret.value));
+                    }
+                }
+            }
+        }
+        Ok((tree, rotated))
+    }
+
+    /// The secret `id` from the cache, or else fetched, telling whether its version
+    /// differs from the cached one
+    fn secret(&self, id: &str, fresh: bool) -> Result<(SecretValue, bool), String> {
+        if !fresh
+            && let Some(cached) = self.cache.lock().get(id)
+            && cached.fetched.elapsed() < self.ttl
+        {
+            return Ok((cached.secret.clone(), false));
+        }
+        let secret = self
+            .store
+            .fetch(id)
+            .map_err(|e| self.error(&format!("Cannot fetch secret {id}: {e}")))?;
+        let previous = self.cache.lock().insert(
+            id.to_string(),
+            Cached {
+                secret: secret.clone(),
+                fetched: Instant::now(),
+            },
+        );
+        let changed = previous.is_some_and(|cached| cached.secret.version != secret.version);
+        if changed {
+            debug!(target: "superconfig.sources", "Secret {id} of {} rotated to version {}", self.store.name(), secret.version);
+        }
+        Ok((secret, changed))
+    }
+
+    fn error(&self, message: &str) -> String {
+        let name = self.store.name();
+        error!(target: "superconfig.sources", "{name}: {message}");
+        format!("superconfig.sources: {name}: {message}")
+    }
+}
+
+/// Configuration built from the secrets of a [`SecretStore`], deserialized into `T`
+pub struct SecretSource<T> {
+    secrets: Secrets,
+    config: PhantomData<fn() -> T>,
+}
+
+impl<T> SecretSource<T> {
+    /// A source of the secrets in `store`, without any secret yet
+    #[must_use]
+    pub fn new(store: impl SecretStore + 'static) -> Self {
+        Self {
+            secrets: Secrets {
+                store: Arc::new(store),
+                bindings: Vec::new(),
+                ttl: DEFAULT_TTL,
+                cache: Arc::default(),
+            },
+            config: PhantomData,
+        }
+    }
+
+    /// Merge the configuration document held by the secret `id`
+    ///
+    /// Documents merge in the order they are added, before the keys.
+    #[must_use]
+    pub fn document(mut self, id: impl Into<String>) -> Self {
+        self.secrets.bindings.push(Binding {
+            id: id.into(),
+            target: Target::Document,
+        });
+        self
+    }
+
+    /// Set the dotted `key`, such as `database.password`, to the secret `id`
+    #[must_use]
+    pub fn key(mut self, key: &str, id: impl Into<String>) -> Self {
+        let path = key
+            .split('.')
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect();
+        self.secrets.bindings.push(Binding {
+            id: id.into(),
+            target: Target::Key(path),
+        });
+        self
+    }
+
+    /// How long fetched secrets are reused before asking the store again
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.secrets.ttl = ttl;
+        self
+    }
+
+    /// Forget the cached secrets, so the next load asks the store
+    pub fn clear_cache(&self) {
+        self.secrets.cache.lock().clear();
+    }
+}
+
+impl<T: DeserializeOwned> SecretSource<T> {
+    /// Fetch the secrets, blocking the current thread, and deserialize them into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if a secret can't be fetched, a document fails to parse,
+    /// or the configuration doesn't deserialize into `T`.
+    pub fn load_blocking(&self) -> Result<T, String> {
+        let (tree, _) = self.secrets.tree(false)?;
+        self.deserialize(tree)
+    }
+
+    fn deserialize(&self, tree: Value) -> Result<T, String> {
+        serde_json::from_value(tree)
+            .map_err(|e| self.secrets.error(&format!("Invalid configuration: {e}")))
+    }
+}
+
+impl<T> SecretSource<T>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    /// Fetch the secrets every `interval` and update `handle` when one was rotated
+    ///
+    /// Each check fetches every secret, ignoring the TTL, and compares its version with
+    /// the cached one. Failed checks are logged and the entry keeps its configuration.
+    /// The refresh holds the registry weakly, and stops when it's dropped or when the
+    /// returned [`SecretRefresh`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the refresh thread can't be started.
+    pub fn start_refresh(
+        self,
+        registry: &Arc<ConfigRegistry>,
+        handle: &ConfigHandle<T>,
+        interval: Duration,
+    ) -> Result<SecretRefresh, String> {
+        let registry = Arc::downgrade(registry);
+        let handle_id = handle.id();
+        let refresher: Arc<Refresher> = Arc::new(move || {
+            let registry = registry.upgrade()?;
+            let started = Instant::now();
+            let fetched = self.secrets.tree(true);
+            registry.record_source_load(
+                self.secrets.store.name(),
+                started.elapsed(),
+                fetched.is_err(),
+            );
+            Some(fetched.and_then(|(tree, rotated)| {
+                if !rotated {
+                    return Ok(None);
+                }
+                let handle = ConfigHandle::<T>::new(handle_id);
+                registry.update(&handle, self.deserialize(tree)?)?;
+                debug!(target: "superconfig.sources", "Refreshed handle {handle_id} from rotated secrets of {}", self.secrets.store.name());
+                registry.generation(&handle).map(Some)
+            }))
+        });
+        let (stop, stopped) = mpsc::channel::<()>();
+        let worker = Arc::clone(&refresher);
+        let thread = std::thread::Builder::new()
+            .name("superconfig-secrets".to_string())
+            .spawn(move || {
+                // The channel disconnects when the `SecretRefresh` is dropped
+                while stopped.recv_timeout(interval) == Err(mpsc::RecvTimeoutError::Timeout) {
+                    match worker() {
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            warn!(target: "superconfig.sources", "Kept handle {handle_id} after failed secret refresh: {e}");
+                        }
+                        None => return,
+                    }
+                }
+            })
+            .map_err(|e| {
+                error!(target: "superconfig.sources", "Cannot start secret refresh thread: {e}");
+                format!("superconfig.sources: Cannot start secret refresh thread: {e}")
+            })?;
+        Ok(SecretRefresh {
+            stop: Some(stop),
+            thread: Some(thread),
+            refresher,
+        })
+    }
+}
+
+impl<T> AsyncConfigSource for SecretSource<T>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    type Output = T;
+
+    fn name(&self) -> &str {
+        self.secrets.store.name()
+    }
+
+    async fn load(&self) -> Result<T, String> {
+        let secrets = self.secrets.clone();
+        let (tree, _) = tokio::task::spawn_blocking(move || secrets.tree(false))
+            .await
+            .map_err(|err| format!("secret fetch failed: {err}"))??;
+        self.deserialize(tree)
+    }
+}
+
+impl<T> fmt::Debug for SecretSource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretSource")
+            .field("store", &self.secrets.store.name())
+            .field("bindings", &self.secrets.bindings)
+            .field("ttl", &self.secrets.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Checks the secrets once, returning the entry's new generation if one was rotated,
+/// or `None` if the registry was dropped
+type Refresher = dyn Fn() -> Option<Result<Option<u64>, String>> + Send + Sync;
+
+/// Running rotation check of an entry from a [`SecretSource`], stopped when dropped
+pub struct SecretRefresh {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    refresher: Arc<Refresher>,
+}
+
+impl SecretRefresh {
+    /// Check the secrets now, without waiting for the next interval
+    ///
+    /// Returns the entry's new generation, or `None` if no secret was rotated.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the registry was dropped, or the secrets failed to
+    /// fetch or load; the entry keeps its configuration.
+    pub fn refresh(&self) -> Result<Option<u64>, String> {
+        (self.refresher)().unwrap_or_else(|| {
+            error!(target: "superconfig.sources", "Registry was dropped");
+            Err("superconfig.sources: Registry was dropped".to_string())
+        })
+    }
+}
+
+impl Drop for SecretRefresh {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            error!(target: "superconfig.sources", "Secret refresh thread panicked");
+        }
+    }
+}
+
+impl fmt::Debug for SecretRefresh {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretRefresh")
+            .field("running", &self.thread.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct App {
+        database: Database,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        password: String,
+    }
+
+    /// Store serving shared secrets and counting its fetches
+    #[derive(Clone, Default)]
+    struct MemoryStore {
+        secrets: Arc<Mutex<HashMap<String, SecretValue>>>,
+        fetches: Arc<Mutex<u32>>,
+    }
+
+    impl MemoryStore {
+        fn set(&self, id: &str, value: &str, version: &str) {
+            self.secrets
+                .lock()
+                .insert(id.to_string(), SecretValue::new(value, version));
+        }
+    }
+
+    impl SecretStore for MemoryStore {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+
+        fn fetch(&self, id: &str) -> Result<SecretValue, String> {
+            *self.fetches.lock() += 1;
+            self.secrets
+                .lock()
+                .get(id)
+                .cloned()
+                .ok_or_else(|| format!("no secret {id}"))
+        }
+    }
+
+    fn store() -> MemoryStore {
+        let store = MemoryStore::default();
+        store.set(
+            "config",
+            r#"{"database": {"host": "db", "password": "unset"}}"#,
+            "1",
+        );
+        store.set("password", "hunter2", "1");
+        store
+    }
+
+    #[test]
+    fn test_documents_and_keys_are_merged() {
+        tokio_test::block_on(async {
+            let source = SecretSource::<App>::new(store())
+                .document("config")
+                .key("database.password", "password");
+            let registry = ConfigRegistry::new();
+            let handle = registry.create_from_source(&source).await.unwrap();
+            assert_eq!(
+                *registry.read(&handle).unwrap(),
+                App {
+                    database: Database {
+                        host: "db".to_string(),
+                        password: "hunter2".to_string(),
+                    }
+                }
+            );
+            assert_eq!(registry.stats().source_loads[0].name, "memory");
+        });
+    }
+
+    #[test]
+    fn test_secrets_are_cached_for_the_ttl() {
+        let store = store();
+        let source = SecretSource::<App>::new(store.clone())
+            .document("config")
+            .key("database.password", "password");
+        source.load_blocking().unwrap();
+        source.load_blocking().unwrap();
+        assert_eq!(*store.fetches.lock(), 2);
+
+        source.clear_cache();
+        source.load_blocking().unwrap();
+        assert_eq!(*store.fetches.lock(), 4);
+
+        let source = source.with_ttl(Duration::ZERO);
+        source.load_blocking().unwrap();
+        assert_eq!(*store.fetches.lock(), 6);
+    }
+
+    #[test]
+    fn test_refresh_updates_entry_after_rotation() {
+        let store = store();
+        let source = SecretSource::<App>::new(store.clone())
+            .document("config")
+            .key("database.password", "password");
+        let registry = Arc::new(ConfigRegistry::new());
+        let handle = registry.create(source.load_blocking().unwrap()).unwrap();
+        let refresh = source
+            .start_refresh(&registry, &handle, Duration::from_secs(3600))
+            .unwrap();
+
+        assert_eq!(refresh.refresh().unwrap(), None);
+        store.set("password", "correct horse", "2");
+        assert_eq!(refresh.refresh().unwrap(), Some(2));
+        assert_eq!(
+            registry.read(&handle).unwrap().database.password,
+            "correct horse"
+        );
+
+        store.secrets.lock().remove("config");
+        assert!(refresh.refresh().unwrap_err().contains("no secret config"));
+        assert_eq!(registry.generation(&handle).unwrap(), 2);
+
+        drop(registry);
+        assert!(refresh.refresh().is_err());
+    }
+
+    #[test]
+    fn test_unknown_document_format_is_an_error() {
+        let store = store();
+        store.set("text", "just text", "1");
+        let error = SecretSource::<App>::new(store)
+            .document("text")
+            .load_blocking()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "superconfig.sources: memory: Unknown format of secret text"
+        );
+    }
+
+    #[test]
+    fn test_debug_masks_secret_values() {
+        let debug = format!("{:?}", SecretValue::new("hunter2", "3"));
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("\"3\""));
+    }
+}
@@ -0,0 +1,9 @@
+//! Value types with special meaning to the registry
+//!
+//! ## Modules
+//!
+//! - [`secret`] - Values masked in debug output, logs and JSON exports
+
+pub mod secret;
+
+pub use secret::{SECRET_MASK, Secret};
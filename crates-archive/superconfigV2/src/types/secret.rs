@@ -0,0 +1,179 @@
+//! Secrets kept out of debug output, logs and JSON exports
+//!
+//! Wrapping a field in [`Secret`] masks it as `"***"` wherever the configuration is
+//! printed or serialized: its `Debug` and `Display` output, log messages formatting the
+//! configuration, and the JSON of `read_as_json`, `get_path` and snapshots. The value
+//! is only reachable through [`Secret::expose_secret`], so every use of it is explicit.
+//!
+//! Secrets deserialize transparently, so they load from files, environment variables
+//! and flags like any other value. Deserializing the mask itself fails instead of
+//! storing `"***"` as the secret, which means `set_path` and `restore` reject
+//! configuration that went through a masked export. Reload secrets from their
+//! sources rather than from snapshots.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use superconfig::{ConfigRegistry, Secret};
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Database {
+//!     user: String,
+//!     password: Secret<String>,
+//! }
+//!
+//! let database: Database =
+//!     serde_json::from_str(r#"{"user": "app", "password": "hunter2"}"#).unwrap();
+//! assert_eq!(database.password.expose_secret(), "hunter2");
+//! assert_eq!(
+//!     format!("{database:?}"),
+//!     r#"Database { user: "app", password: "***" }"#
+//! );
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry.create(database).unwrap();
+//! assert_eq!(
+//!     registry.read_as_json(&handle),
+//!     r#"{"data":{"password":"***","user":"app"},"success":true}"#
+//! );
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+use serde_json::Value;
+
+/// Text shown in place of a secret
+pub const SECRET_MASK: &str = "***";
+
+/// A configuration value masked as [`SECRET_MASK`] in debug output, logs and exports
+///
+/// See the [module documentation](self) for how secrets are loaded and exported.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wrap `value` as a secret
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// The secret value, for the code that actually needs it
+    pub const fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    /// Unwrap the secret value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(SECRET_MASK, f)
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(SECRET_MASK)
+    }
+}
+
+impl<T> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(SECRET_MASK)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Go through a tree to catch the mask of a previously exported secret
+        let value = Value::deserialize(deserializer)?;
+        if value.as_str() == Some(SECRET_MASK) {
+            return Err(D::Error::custom(
+                "secret is masked, load it from its source instead",
+            ));
+        }
+        T::deserialize(value).map(Self).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigRegistry;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Database {
+        host: String,
+        port: u16,
+        password: Secret<String>,
+    }
+
+    fn database() -> Database {
+        Database {
+            host: "localhost".to_string(),
+            port: 5432,
+            password: Secret::new("hunter2".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_secret_is_masked_when_formatted() {
+        let secret = Secret::new(42);
+        assert_eq!(format!("{secret:?}"), "\"***\"");
+        assert_eq!(secret.to_string(), "***");
+        assert_eq!(*secret.expose_secret(), 42);
+        assert_eq!(secret.into_inner(), 42);
+    }
+
+    #[test]
+    fn test_secret_deserializes_transparently() {
+        let secret: Secret<u16> = serde_json::from_str("8080").unwrap();
+        assert_eq!(*secret.expose_secret(), 8080);
+        assert!(serde_json::from_str::<Secret<u16>>("\"high\"").is_err());
+
+        let error = serde_json::from_str::<Secret<String>>("\"***\"").unwrap_err();
+        assert!(error.to_string().contains("secret is masked"));
+    }
+
+    #[test]
+    fn test_registry_exports_mask_secrets() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(database()).unwrap();
+        assert_eq!(
+            registry.get_path::<String>(&handle, "password").unwrap(),
+            SECRET_MASK
+        );
+        assert!(!registry.read_as_json(&handle).contains("hunter2"));
+        assert_eq!(
+            registry.read(&handle).unwrap().password.expose_secret(),
+            "hunter2"
+        );
+    }
+
+    #[test]
+    fn test_set_path_keeps_secret_out_of_masked_tree() {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(database()).unwrap();
+        let error = registry.set_path(&handle, "port", 6543).unwrap_err();
+        assert!(error.contains("secret is masked"));
+        assert_eq!(
+            registry.read(&handle).unwrap().password.expose_secret(),
+            "hunter2"
+        );
+
+        // Setting the secret itself replaces the mask
+        registry.set_path(&handle, "password", "s3cret").unwrap();
+        let database = registry.read(&handle).unwrap();
+        assert_eq!(database.port, 5432);
+        assert_eq!(database.password.expose_secret(), "s3cret");
+    }
+}
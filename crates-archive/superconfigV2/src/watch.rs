@@ -0,0 +1,511 @@
+//! Hot reloading of configuration files
+//!
+//! [`HotReload`] watches the files an entry was loaded from and reloads them into the
+//! entry with [`ConfigRegistry::update`] when they change. Editors often save a file
+//! with several writes or by replacing it, so changes are debounced: the files are
+//! reloaded once they have been quiet for the debounce delay. When a file fails to
+//! parse or no longer deserializes, the entry keeps its current configuration and the
+//! failure is reported, so a half-written file never takes effect.
+//!
+//! Each reload is reported to the callbacks registered with [`HotReload::on_reload`]
+//! and to the channels of [`FileWatcher::reloads`]. Watching stops when the
+//! [`FileWatcher`] is dropped.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use std::time::Duration;
+//! use superconfig::{ConfigRegistry, watch::{HotReload, ReloadEvent}};
+//!
+//! #[derive(Deserialize)]
+//! struct Server {
+//!     port: u16,
+//! }
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let path = dir.path().join("server.json");
+//! std::fs::write(&path, r#"{"port": 80}"#).unwrap();
+//!
+//! let registry = ConfigRegistry::new();
+//! let handle = registry.create_from_file::<Server>(&path).unwrap();
+//! let watcher = HotReload::new([&path])
+//!     .debounce(Duration::from_millis(20))
+//!     .start(&registry, &handle)
+//!     .unwrap();
+//! let reloads = watcher.reloads();
+//!
+//! std::fs::write(&path, r#"{"port": 8080}"#).unwrap();
+//! while let Ok(event) = reloads.recv_timeout(Duration::from_secs(10)) {
+//!     if let ReloadEvent::Reloaded { .. } = event {
+//!         break;
+//!     }
+//! }
+//! assert_eq!(registry.read(&handle).unwrap().port, 8080);
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Weak, mpsc},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::core::{ConfigHandle, ConfigRegistry, registry::HandleId};
+use crate::formats::load_files;
+use logffi::{debug, error, warn};
+
+/// Result of reloading the files of a [`FileWatcher`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ReloadEvent {
+    /// The files were reloaded into the entry
+    Reloaded {
+        /// Id of the reloaded handle
+        handle_id: HandleId,
+        /// Generation of the entry holding the reloaded configuration
+        generation: u64,
+    },
+    /// The files failed to load, and the entry kept its configuration
+    Failed {
+        /// Id of the handle that wasn't reloaded
+        handle_id: HandleId,
+        /// Why the files failed to load
+        error: String,
+    },
+}
+
+type ReloadCallback = Arc<dyn Fn(&ReloadEvent) + Send + Sync>;
+
+/// Callbacks and channels receiving the reload events of a watcher
+#[derive(Default)]
+struct Listeners {
+    callbacks: Vec<ReloadCallback>,
+    channels: parking_lot::Mutex<Vec<mpsc::Sender<ReloadEvent>>>,
+}
+
+impl Listeners {
+    /// Report `event`, running the callbacks on the registry's worker pool if it has one
+    fn emit(&self, registry: &ConfigRegistry, event: &ReloadEvent) {
+        for callback in &self.callbacks {
+            match registry.pool() {
+                Some(pool) => {
+                    let (callback, event) = (Arc::clone(callback), event.clone());
+                    pool.execute(move || callback(&event));
+                }
+                None => callback(event),
+            }
+        }
+        // Channels whose receiver was dropped are removed
+        self.channels
+            .lock()
+            .retain(|sender| sender.send(event.clone()).is_ok());
+    }
+}
+
+/// Builder of a [`FileWatcher`] reloading an entry from its files
+#[must_use]
+pub struct HotReload {
+    paths: Vec<PathBuf>,
+    debounce: Duration,
+    callbacks: Vec<ReloadCallback>,
+}
+
+impl HotReload {
+    /// Debounce delay used unless [`debounce`](Self::debounce) sets another
+    pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+    /// Reload from `paths`, merged in order like
+    /// [`create_from_files`](ConfigRegistry::create_from_files)
+    pub fn new(paths: impl IntoIterator<Item = impl AsRef<Path>>) -> Self {
+        Self {
+            paths: paths
+                .into_iter()
+                .map(|path| path.as_ref().to_path_buf())
+                .collect(),
+            debounce: Self::DEFAULT_DEBOUNCE,
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Reload once the files have been quiet for `delay`
+    pub const fn debounce(mut self, delay: Duration) -> Self {
+        self.debounce = delay;
+        self
+    }
+
+    /// Call `callback` with the result of each reload, on the watcher's thread
+    ///
+    /// With the registry's `THREAD_POOL` startup flag, callbacks run on its worker
+    /// pool instead, so slow callbacks don't delay the next reload; they may then run
+    /// concurrently.
+    pub fn on_reload(mut self, callback: impl Fn(&ReloadEvent) + Send + Sync + 'static) -> Self {
+        self.callbacks.push(Arc::new(callback));
+        self
+    }
+
+    /// Start watching the files and reloading them into `handle`
+    ///
+    /// The watcher holds the registry weakly, and stops reloading once it's dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if no files were given, a file's directory doesn't exist,
+    /// or the file system can't be watched.
+    pub fn start<T>(
+        self,
+        registry: &Arc<ConfigRegistry>,
+        handle: &ConfigHandle<T>,
+    ) -> Result<FileWatcher, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        if self.paths.is_empty() {
+            return Err(watch_error("No files to watch"));
+        }
+        // Watch the directories, since editors may replace files instead of writing them
+        let mut files = Vec::with_capacity(self.paths.len());
+        let mut directories = Vec::new();
+        for path in &self.paths {
+            let (Some(directory), Some(name)) = (path.parent(), path.file_name()) else {
+                return Err(watch_error(&format!("Invalid file {}", path.display())));
+            };
+            let directory = if directory.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                directory
+            };
+            let directory = directory
+                .canonicalize()
+                .map_err(|e| watch_error(&format!("Cannot watch {}: {e}", directory.display())))?;
+            files.push(directory.join(name));
+            if !directories.contains(&directory) {
+                directories.push(directory);
+            }
+        }
+
+        let count = files.len();
+        let (events, changes) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(events)
+            .map_err(|e| watch_error(&format!("Cannot watch files: {e}")))?;
+        for directory in &directories {
+            watcher
+                .watch(directory, RecursiveMode::NonRecursive)
+                .map_err(|e| watch_error(&format!("Cannot watch {}: {e}", directory.display())))?;
+        }
+
+        let listeners = Arc::new(Listeners {
+            callbacks: self.callbacks,
+            channels: parking_lot::Mutex::new(Vec::new()),
+        });
+        let reload = Reloader {
+            registry: Arc::downgrade(registry),
+            handle_id: handle.id(),
+            paths: self.paths,
+            listeners: Arc::clone(&listeners),
+            load: |registry: &ConfigRegistry, handle_id: HandleId, paths: &[PathBuf]| {
+                let data = load_files::<T>(registry, paths)?;
+                let handle = ConfigHandle::<T>::new(handle_id);
+                registry.update(&handle, data)?;
+                registry.generation(&handle)
+            },
+        };
+        let reload = Arc::new(reload);
+        let worker = Arc::clone(&reload);
+        let debounce = self.debounce;
+        let thread = std::thread::Builder::new()
+            .name("superconfig-watch".to_string())
+            .spawn(move || watch_loop(&changes, &files, debounce, &*worker))
+            .map_err(|e| watch_error(&format!("Cannot start watcher thread: {e}")))?;
+
+        debug!(target: "superconfig.watch", "Watching {count} file(s) for handle {}", handle.id());
+        Ok(FileWatcher {
+            watcher: Some(watcher),
+            thread: Some(thread),
+            reload,
+            listeners,
+        })
+    }
+}
+
+/// Reloads the files of a watcher into its entry
+trait Reload: Send + Sync {
+    fn reload(&self) -> Option<ReloadEvent>;
+}
+
+struct Reloader<F> {
+    registry: Weak<ConfigRegistry>,
+    handle_id: HandleId,
+    paths: Vec<PathBuf>,
+    listeners: Arc<Listeners>,
+    load: F,
+}
+
+impl<F> Reload for Reloader<F>
+where
+    F: Fn(&ConfigRegistry, HandleId, &[PathBuf]) -> Result<u64, String> + Send + Sync,
+{
+    /// Reload and report the result, or `None` if the registry was dropped
+    fn reload(&self) -> Option<ReloadEvent> {
+        let registry = self.registry.upgrade()?;
+        let event = match (self.load)(&registry, self.handle_id, &self.paths) {
+            Ok(generation) => {
+                debug!(target: "superconfig.watch", "Reloaded handle {}", self.handle_id);
+                ReloadEvent::Reloaded {
+                    handle_id: self.handle_id,
+                    generation,
+                }
+            }
+            Err(e) => {
+                warn!(target: "superconfig.watch", "Kept handle {} after failed reload: {e}", self.handle_id);
+                ReloadEvent::Failed {
+                    handle_id: self.handle_id,
+                    error: e,
+                }
+            }
+        };
+        self.listeners.emit(&registry, &event);
+        Some(event)
+    }
+}
+
+/// Reload after each burst of changes to `files`, until the watcher is dropped
+fn watch_loop(
+    changes: &mpsc::Receiver<notify::Result<notify::Event>>,
+    files: &[PathBuf],
+    debounce: Duration,
+    reload: &dyn Reload,
+) {
+    let relevant = |event: &notify::Result<notify::Event>| match event {
+        Ok(event) => {
+            !matches!(event.kind, EventKind::Access(_))
+                && event.paths.iter().any(|path| files.contains(path))
+        }
+        Err(e) => {
+            error!(target: "superconfig.watch", "File watch error: {e}");
+            false
+        }
+    };
+
+    // The channel disconnects when the `FileWatcher` drops the notify watcher
+    while let Ok(event) = changes.recv() {
+        if !relevant(&event) {
+            continue;
+        }
+        loop {
+            match changes.recv_timeout(debounce) {
+                Ok(_) => {}
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if reload.reload().is_none() {
+            return;
+        }
+    }
+}
+
+/// Running hot reload of an entry, stopped when dropped
+pub struct FileWatcher {
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+    reload: Arc<dyn Reload>,
+    listeners: Arc<Listeners>,
+}
+
+impl FileWatcher {
+    /// Receive the result of each reload from now on
+    ///
+    /// Like [`watch`](ConfigRegistry::watch) channels, sends never block, so events
+    /// queue up until received.
+    #[must_use]
+    pub fn reloads(&self) -> mpsc::Receiver<ReloadEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.listeners.channels.lock().push(sender);
+        receiver
+    }
+
+    /// Reload the files now, without waiting for a change
+    ///
+    /// Useful to reload on a signal such as `SIGHUP`. The result is also reported to
+    /// the watcher's callbacks and channels.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if the registry was dropped or the files failed to load;
+    /// the entry keeps its configuration.
+    pub fn reload(&self) -> Result<u64, String> {
+        match self.reload.reload() {
+            Some(ReloadEvent::Reloaded { generation, .. }) => Ok(generation),
+            Some(ReloadEvent::Failed { error, .. }) => Err(watch_error(&error)),
+            None => Err(watch_error("Registry was dropped")),
+        }
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        // Dropping the notify watcher disconnects the loop's channel
+        drop(self.watcher.take());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            error!(target: "superconfig.watch", "Watcher thread panicked");
+        }
+    }
+}
+
+impl std::fmt::Debug for FileWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileWatcher")
+            .field("running", &self.thread.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+fn watch_error(message: &str) -> String {
+    error!(target: "superconfig.watch", "{message}");
+    format!("superconfig.watch: {message}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Deserialize)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    /// Receive events until one matches, since a write may be seen as several changes
+    fn wait_for(
+        reloads: &mpsc::Receiver<ReloadEvent>,
+        matches: impl Fn(&ReloadEvent) -> bool,
+    ) -> ReloadEvent {
+        loop {
+            let event = reloads.recv_timeout(Duration::from_secs(10)).unwrap();
+            if matches(&event) {
+                return event;
+            }
+        }
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_config_and_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.json");
+        std::fs::write(&path, r#"{"host": "a", "port": 1}"#).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry.create_from_file::<Server>(&path).unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let callback_seen = Arc::clone(&seen);
+        let watcher = HotReload::new([&path])
+            .debounce(Duration::from_millis(20))
+            .on_reload(move |event| callback_seen.lock().unwrap().push(event.clone()))
+            .start(&registry, &handle)
+            .unwrap();
+        let reloads = watcher.reloads();
+
+        std::fs::write(&path, r#"{"host": "b""#).unwrap();
+        let event = wait_for(&reloads, |event| {
+            matches!(event, ReloadEvent::Failed { .. })
+        });
+        assert!(
+            matches!(&event, ReloadEvent::Failed { error, .. } if error.contains("Invalid json"))
+        );
+        assert_eq!(registry.read(&handle).unwrap().host, "a");
+
+        std::fs::write(&path, r#"{"host": "b", "port": 2}"#).unwrap();
+        let event = wait_for(&reloads, |event| {
+            matches!(event, ReloadEvent::Reloaded { .. })
+        });
+        assert_eq!(
+            event,
+            ReloadEvent::Reloaded {
+                handle_id: handle.id(),
+                generation: registry.generation(&handle).unwrap(),
+            }
+        );
+        let server = registry.read(&handle).unwrap();
+        assert_eq!((server.host.as_str(), server.port), ("b", 2));
+        assert!(seen.lock().unwrap().len() >= 2);
+    }
+
+    #[test]
+    fn test_replaced_file_reloads_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.json");
+        let local = dir.path().join("local.json");
+        std::fs::write(&base, r#"{"host": "a", "port": 1}"#).unwrap();
+        std::fs::write(&local, r#"{"port": 2}"#).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry
+            .create_from_files::<Server>(&[&base, &local])
+            .unwrap();
+        let watcher = HotReload::new([&base, &local])
+            .debounce(Duration::from_millis(20))
+            .start(&registry, &handle)
+            .unwrap();
+        let reloads = watcher.reloads();
+
+        // Replace the file like editors saving atomically
+        let temporary = dir.path().join("local.json.tmp");
+        std::fs::write(&temporary, r#"{"port": 3}"#).unwrap();
+        std::fs::rename(&temporary, &local).unwrap();
+        wait_for(&reloads, |event| {
+            matches!(event, ReloadEvent::Reloaded { .. })
+        });
+        let server = registry.read(&handle).unwrap();
+        assert_eq!((server.host.as_str(), server.port), ("a", 3));
+    }
+
+    #[test]
+    fn test_manual_reload_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.json");
+        std::fs::write(&path, r#"{"host": "a", "port": 1}"#).unwrap();
+
+        let registry = ConfigRegistry::new();
+        let handle = registry.create_from_file::<Server>(&path).unwrap();
+        let no_files: [&Path; 0] = [];
+        assert!(HotReload::new(no_files).start(&registry, &handle).is_err());
+        let missing = dir.path().join("missing").join("server.json");
+        assert!(HotReload::new([missing]).start(&registry, &handle).is_err());
+
+        let watcher = HotReload::new([&path]).start(&registry, &handle).unwrap();
+        assert_eq!(watcher.reload().unwrap(), 2);
+        drop(registry);
+        let error = watcher.reload().unwrap_err();
+        assert!(error.contains("Registry was dropped"));
+    }
+
+    #[test]
+    fn test_callbacks_run_on_worker_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.json");
+        std::fs::write(&path, r#"{"host": "a", "port": 1}"#).unwrap();
+
+        let registry =
+            ConfigRegistry::custom_with_threads(crate::config_flags::startup::NO_FLAGS, 1);
+        let handle = registry.create_from_file::<Server>(&path).unwrap();
+        let (sender, threads) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let watcher = HotReload::new([&path])
+            .on_reload(move |_| {
+                let name = std::thread::current().name().map(str::to_string);
+                sender.lock().unwrap().send(name).unwrap();
+            })
+            .start(&registry, &handle)
+            .unwrap();
+
+        watcher.reload().unwrap();
+        let thread = threads.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(thread.as_deref(), Some("superconfig-worker-0"));
+    }
+}
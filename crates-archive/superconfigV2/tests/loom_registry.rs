@@ -0,0 +1,114 @@
+//! Loom model tests for the registry's concurrent bookkeeping
+//!
+//! Loom runs each test body under every interleaving of the registry's atomic
+//! operations (handle ids, statistics, overlay counts). Map operations go through
+//! `DashMap` shard locks, which loom doesn't model, so these tests check the counters
+//! and their ordering relative to the map, not the map itself.
+//!
+//! Run with:
+//!
+//! ```sh
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom_registry
+//! ```
+
+#![cfg(loom)]
+
+use loom::thread;
+use serde_json::json;
+use superconfig::ConfigRegistry;
+
+#[test]
+fn concurrent_creates_get_unique_ids_and_exact_stats() {
+    loom::model(|| {
+        let registry = ConfigRegistry::new();
+
+        let first = {
+            let registry = registry.clone();
+            thread::spawn(move || registry.create(1_u32).unwrap())
+        };
+        let second = registry.create(2_u32).unwrap();
+        let first = first.join().unwrap();
+
+        assert_ne!(first.id(), second.id());
+        // Data is visible to the thread that receives the handle
+        assert_eq!(*registry.read(&first).unwrap(), 1);
+        assert_eq!(*registry.read(&second).unwrap(), 2);
+
+        let stats = registry.stats();
+        assert_eq!(stats.total_creates, 2);
+        assert_eq!(stats.total_handles, 2);
+        assert_eq!(stats.memory_usage_bytes, 2 * size_of::<u32>() as u64);
+    });
+}
+
+#[test]
+fn delete_racing_create_keeps_stats_exact() {
+    loom::model(|| {
+        let registry = ConfigRegistry::new();
+
+        let creator = {
+            let registry = registry.clone();
+            thread::spawn(move || {
+                registry.create(7_u64).unwrap();
+            })
+        };
+        // Delete the entry as soon as it is in the map, possibly before `create`
+        // returns. Loom doesn't see the map, so yield until the entry shows up
+        let handle = loop {
+            if let Some(handle) = registry.entries_by_type::<u64>().pop() {
+                break handle;
+            }
+            thread::yield_now();
+        };
+        registry.delete(&handle).unwrap();
+        creator.join().unwrap();
+
+        let stats = registry.stats();
+        assert_eq!(stats.total_creates, 1);
+        assert_eq!(stats.total_handles, 0);
+        assert_eq!(stats.memory_usage_bytes, 0);
+    });
+}
+
+#[test]
+fn delete_racing_update_keeps_memory_exact() {
+    loom::model(|| {
+        let registry = ConfigRegistry::new();
+        let handle = registry.create(1_u64).unwrap();
+
+        let updater = {
+            let registry = registry.clone();
+            thread::spawn(move || registry.update(&handle, 2_u64).unwrap())
+        };
+        // Delete the new data as soon as it replaced the old, possibly before
+        // `update` returns
+        while *registry.read(&handle).unwrap() != 2 {
+            thread::yield_now();
+        }
+        registry.delete(&handle).unwrap();
+        updater.join().unwrap();
+
+        let stats = registry.stats();
+        assert_eq!(stats.total_handles, 0);
+        assert_eq!(stats.total_updates, 1);
+        assert_eq!(stats.memory_usage_bytes, 0);
+    });
+}
+
+#[test]
+fn overlays_are_released_across_threads() {
+    loom::model(|| {
+        let registry = ConfigRegistry::new();
+        let base = registry.create(json!({ "pool": 10 })).unwrap();
+        let overlay = registry.overlay(&base, json!({ "pool": 2 })).unwrap();
+
+        let reader = thread::spawn(move || {
+            assert_eq!(*overlay.get("/pool").unwrap(), 2);
+        });
+        let other = registry.overlay(&base, json!({})).unwrap();
+        drop(other);
+        reader.join().unwrap();
+
+        assert_eq!(registry.stats().active_overlays, 0);
+    });
+}
@@ -0,0 +1,127 @@
+//! Runtime choice of the backends each log event is dispatched to
+//!
+//! The logging macros fan each event out to every enabled backend whose level filter
+//! lets it through. Both backends are enabled for all levels by default; the tracing
+//! backend still applies the subscriber's own filter (`RUST_LOG`) after this one.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use tracing::level_filters::LevelFilter;
+
+/// Destination of log events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// The `tracing` subscriber
+    Tracing,
+    /// The FFI callback (`callback` feature), see `set_callback`
+    Callback,
+}
+
+impl Backend {
+    /// All backends, in dispatch order
+    pub const ALL: [Self; 2] = [Self::Tracing, Self::Callback];
+
+    fn state(self) -> &'static AtomicU8 {
+        match self {
+            Self::Tracing => &TRACING,
+            Self::Callback => &CALLBACK,
+        }
+    }
+}
+
+// Each backend's state: the `ENABLED` bit, and the most verbose level it accepts
+// (`0` for off to `5` for trace)
+const ENABLED: u8 = 0x80;
+static TRACING: AtomicU8 = AtomicU8::new(ENABLED | 5);
+static CALLBACK: AtomicU8 = AtomicU8::new(ENABLED | 5);
+
+const fn filter_rank(filter: LevelFilter) -> u8 {
+    match filter.into_level() {
+        None => 0,
+        Some(tracing::Level::ERROR) => 1,
+        Some(tracing::Level::WARN) => 2,
+        Some(tracing::Level::INFO) => 3,
+        Some(tracing::Level::DEBUG) => 4,
+        Some(_) => 5,
+    }
+}
+
+fn rank_filter(rank: u8) -> LevelFilter {
+    match rank {
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::ERROR,
+        2 => LevelFilter::WARN,
+        3 => LevelFilter::INFO,
+        4 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Dispatch log events to `backends` only, disabling the others
+///
+/// Level filters set with [`set_backend_level`] are kept.
+///
+/// ```rust
+/// use logfusion::{Backend, set_backends};
+///
+/// // Send events to the host language only
+/// set_backends(&[Backend::Callback]);
+/// # set_backends(&Backend::ALL);
+/// ```
+pub fn set_backends(backends: &[Backend]) {
+    for backend in Backend::ALL {
+        let state = backend.state();
+        if backends.contains(&backend) {
+            state.fetch_or(ENABLED, Ordering::Relaxed);
+        } else {
+            state.fetch_and(!ENABLED, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Backends log events are dispatched to
+pub fn backends() -> Vec<Backend> {
+    Backend::ALL
+        .into_iter()
+        .filter(|backend| backend.state().load(Ordering::Relaxed) & ENABLED != 0)
+        .collect()
+}
+
+/// Dispatch only events at `filter` or more severe to `backend`
+///
+/// ```rust
+/// use logfusion::{Backend, LevelFilter, set_backend_level};
+///
+/// // Everything to tracing, only warnings and errors across the FFI bridge
+/// set_backend_level(Backend::Callback, LevelFilter::WARN);
+/// # set_backend_level(Backend::Callback, LevelFilter::TRACE);
+/// ```
+pub fn set_backend_level(backend: Backend, filter: LevelFilter) {
+    let state = backend.state();
+    let rank = filter_rank(filter);
+    let _ = state.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+        Some(current & ENABLED | rank)
+    });
+}
+
+/// Level filter of `backend`
+pub fn backend_level(backend: Backend) -> LevelFilter {
+    rank_filter(backend.state().load(Ordering::Relaxed) & !ENABLED)
+}
+
+/// Whether an event from the `macro_name` macro goes to `backend`
+///
+/// `event!` carries its level as an argument, so it only needs the backend enabled
+/// and not filtered `OFF`.
+#[doc(hidden)]
+pub fn enabled(backend: Backend, macro_name: &str) -> bool {
+    let state = backend.state().load(Ordering::Relaxed);
+    let rank = match macro_name {
+        "error" => 1,
+        "warn" => 2,
+        "info" => 3,
+        "debug" => 4,
+        "trace" => 5,
+        _ => 1,
+    };
+    state & ENABLED != 0 && rank <= state & !ENABLED
+}
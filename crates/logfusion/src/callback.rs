@@ -2,28 +2,379 @@ use cfg_if::cfg_if;
 
 cfg_if! {
     if #[cfg(feature = "callback")] {
-        use std::sync::Mutex;
+        use std::cell::Cell;
+        use std::collections::VecDeque;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+        use std::time::{Duration, Instant};
 
         /// Callback function type for FFI bridges
         pub type Callback = Box<dyn Fn(&str, &str, &str) + Send + Sync>;
 
+        /// Callback receiving log records in batches, see [`set_batched`]
+        pub type BatchCallback = Box<dyn Fn(&[Record]) + Send + Sync>;
+
         /// Global callback storage
         static CALLBACK: Mutex<Option<Callback>> = Mutex::new(None);
 
+        /// Global batched callback, used instead of `CALLBACK` when set
+        static BATCHER: Mutex<Option<Arc<Batcher>>> = Mutex::new(None);
+
+        thread_local! {
+            /// Whether this thread is delivering a batch, so logs from the callback
+            /// itself never block on the full queue
+            static DELIVERING: Cell<bool> = const { Cell::new(false) };
+        }
+
         /// Set callback for bridging logs to other systems (Python, Node.js, etc.)
+        ///
+        /// Replaces any batched callback, delivering its queued records first.
         pub fn set(callback: Callback) {
             let mut guard = CALLBACK.lock().unwrap();
             *guard = Some(callback);
+            drop(guard);
+            replace_batcher(None);
         }
 
         /// Internal function to call the callback if set
         #[doc(hidden)]
         pub fn call(level: &str, target: &str, message: &str) {
+            let batcher = BATCHER.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            if let Some(batcher) = batcher {
+                batcher.push(Record {
+                    level: level.to_string(),
+                    target: target.to_string(),
+                    message: message.to_string(),
+                });
+                return;
+            }
             if let Ok(guard) = CALLBACK.lock() {
                 if let Some(callback) = guard.as_ref() {
                     callback(level, target, message);
                 }
             }
         }
+
+        /// Log record delivered to a batched callback
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct Record {
+            /// Level of the log macro, such as `"info"`
+            pub level: String,
+            /// Target, the module path unless given
+            pub target: String,
+            /// Formatted message
+            pub message: String,
+        }
+
+        /// What to do with a record when the queue of a batched callback is full
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+        pub enum Overflow {
+            /// Drop the oldest queued record to make room
+            #[default]
+            DropOldest,
+            /// Drop the new record
+            DropNew,
+            /// Wait until the callback has taken records from the queue
+            Block,
+        }
+
+        /// Batching and queue settings of a batched callback
+        ///
+        /// Defaults to batches of up to 100 records, delivered at least every 100ms,
+        /// from a queue of 10 000 records dropping the oldest when full.
+        #[derive(Debug, Clone)]
+        pub struct Batching {
+            max_records: usize,
+            interval: Duration,
+            capacity: usize,
+            overflow: Overflow,
+        }
+
+        impl Default for Batching {
+            fn default() -> Self {
+                Self {
+                    max_records: 100,
+                    interval: Duration::from_millis(100),
+                    capacity: 10_000,
+                    overflow: Overflow::DropOldest,
+                }
+            }
+        }
+
+        impl Batching {
+            /// Deliver a batch as soon as `count` records are queued
+            #[must_use]
+            pub fn max_records(mut self, count: usize) -> Self {
+                self.max_records = count.max(1);
+                self
+            }
+
+            /// Deliver queued records at most `interval` after the oldest was logged
+            #[must_use]
+            pub fn interval(mut self, interval: Duration) -> Self {
+                self.interval = interval;
+                self
+            }
+
+            /// Queue at most `count` records waiting for delivery
+            #[must_use]
+            pub fn capacity(mut self, count: usize) -> Self {
+                self.capacity = count.max(1);
+                self
+            }
+
+            /// What to do with records logged while the queue is full
+            #[must_use]
+            pub fn overflow(mut self, overflow: Overflow) -> Self {
+                self.overflow = overflow;
+                self
+            }
+        }
+
+        /// Delivery counters of the batched callback, see [`stats`]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+        pub struct Stats {
+            /// Records waiting in the queue
+            pub queued: u64,
+            /// Records passed to the callback
+            pub delivered: u64,
+            /// Records dropped because the queue was full
+            pub dropped: u64,
+            /// Calls of the callback
+            pub batches: u64,
+        }
+
+        /// Queue of the batched callback, guarded by `Batcher::queue`
+        #[derive(Default)]
+        struct Queue {
+            records: VecDeque<Record>,
+            /// When the oldest queued record was logged
+            oldest: Option<Instant>,
+            /// Records taken by the worker but not yet delivered
+            in_flight: usize,
+            flush: bool,
+            shutdown: bool,
+        }
+
+        /// Batched callback with its queue, drained by a worker thread
+        struct Batcher {
+            callback: BatchCallback,
+            batching: Batching,
+            queue: Mutex<Queue>,
+            /// Signals the worker about new records, flushes and shutdown
+            wake_worker: Condvar,
+            /// Signals producers and `flush` that the queue has been drained
+            drained: Condvar,
+            delivered: AtomicU64,
+            dropped: AtomicU64,
+            batches: AtomicU64,
+        }
+
+        impl Batcher {
+            fn lock(&self) -> MutexGuard<'_, Queue> {
+                self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+            }
+
+            fn push(&self, record: Record) {
+                let mut queue = self.lock();
+                let overflow = if DELIVERING.with(Cell::get) {
+                    // Waiting on its own worker would never end
+                    match self.batching.overflow {
+                        Overflow::Block => Overflow::DropNew,
+                        overflow => overflow,
+                    }
+                } else {
+                    self.batching.overflow
+                };
+                while queue.records.len() >= self.batching.capacity || queue.shutdown {
+                    if queue.shutdown {
+                        // Replaced while waiting, and the worker is gone
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    match overflow {
+                        Overflow::DropOldest => {
+                            queue.records.pop_front();
+                            self.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Overflow::DropNew => {
+                            self.dropped.fetch_add(1, Ordering::Relaxed);
+                            return;
+                        }
+                        Overflow::Block => {
+                            queue = self
+                                .drained
+                                .wait(queue)
+                                .unwrap_or_else(PoisonError::into_inner);
+                        }
+                    }
+                }
+                // The worker waits without a deadline while the queue is empty
+                let first = queue.oldest.is_none();
+                queue.oldest.get_or_insert_with(Instant::now);
+                queue.records.push_back(record);
+                if first || queue.records.len() >= self.batching.max_records {
+                    self.wake_worker.notify_one();
+                }
+            }
+
+            /// Deliver batches until shut down and drained
+            fn run(&self) {
+                DELIVERING.with(|delivering| delivering.set(true));
+                loop {
+                    let mut queue = self.lock();
+                    loop {
+                        let ready = queue.records.len() >= self.batching.max_records
+                            || (!queue.records.is_empty() && (queue.flush || queue.shutdown));
+                        if ready || (queue.shutdown && queue.records.is_empty()) {
+                            break;
+                        }
+                        let deadline = queue.oldest.map(|oldest| oldest + self.batching.interval);
+                        queue = match deadline {
+                            None => self.wake_worker.wait(queue).unwrap_or_else(PoisonError::into_inner),
+                            Some(deadline) => {
+                                let now = Instant::now();
+                                if now >= deadline {
+                                    break;
+                                }
+                                self.wake_worker
+                                    .wait_timeout(queue, deadline - now)
+                                    .unwrap_or_else(PoisonError::into_inner)
+                                    .0
+                            }
+                        };
+                    }
+                    if queue.records.is_empty() {
+                        // Shut down with nothing left to deliver
+                        self.drained.notify_all();
+                        return;
+                    }
+
+                    let count = queue.records.len().min(self.batching.max_records);
+                    let batch: Vec<Record> = queue.records.drain(..count).collect();
+                    queue.oldest = (!queue.records.is_empty()).then(Instant::now);
+                    queue.in_flight = batch.len();
+                    drop(queue);
+                    self.drained.notify_all();
+
+                    (self.callback)(&batch);
+                    self.delivered.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    self.batches.fetch_add(1, Ordering::Relaxed);
+
+                    let mut queue = self.lock();
+                    queue.in_flight = 0;
+                    if queue.records.is_empty() {
+                        queue.flush = false;
+                    }
+                    drop(queue);
+                    self.drained.notify_all();
+                }
+            }
+
+            /// Wait until every record queued so far has been delivered
+            fn flush(&self) {
+                let mut queue = self.lock();
+                queue.flush = true;
+                self.wake_worker.notify_one();
+                while !queue.records.is_empty() || queue.in_flight > 0 {
+                    queue = self.drained.wait(queue).unwrap_or_else(PoisonError::into_inner);
+                }
+                queue.flush = false;
+            }
+
+            fn stats(&self) -> Stats {
+                let queued = self.lock().records.len() as u64;
+                Stats {
+                    queued,
+                    delivered: self.delivered.load(Ordering::Relaxed),
+                    dropped: self.dropped.load(Ordering::Relaxed),
+                    batches: self.batches.load(Ordering::Relaxed),
+                }
+            }
+        }
+
+        /// Install `batcher`, delivering the queue of the previous one first
+        fn replace_batcher(batcher: Option<Arc<Batcher>>) {
+            let previous = std::mem::replace(
+                &mut *BATCHER.lock().unwrap_or_else(PoisonError::into_inner),
+                batcher,
+            );
+            if let Some(previous) = previous {
+                previous.flush();
+                previous.lock().shutdown = true;
+                previous.wake_worker.notify_one();
+                previous.drained.notify_all();
+            }
+        }
+
+        /// Set a callback receiving log records in batches, for hosts where a call per
+        /// event is too costly (Python, Node.js, etc.)
+        ///
+        /// Records are queued and delivered from a background thread when a batch is
+        /// full or its oldest record has waited for the batching interval. Replaces
+        /// the callback set with [`set`]. Call [`flush`] before exiting, or records
+        /// still queued are lost.
+        ///
+        /// ```rust
+        /// use logfusion::{Batching, Overflow, flush_callback, set_batch_callback};
+        /// use std::time::Duration;
+        ///
+        /// set_batch_callback(
+        ///     Box::new(|records| {
+        ///         for record in records {
+        ///             println!("[{}] {}: {}", record.level, record.target, record.message);
+        ///         }
+        ///     }),
+        ///     Batching::default()
+        ///         .max_records(500)
+        ///         .interval(Duration::from_millis(250))
+        ///         .overflow(Overflow::DropNew),
+        /// );
+        ///
+        /// logfusion::info!("Delivered in the next batch");
+        /// flush_callback();
+        /// ```
+        pub fn set_batched(callback: BatchCallback, batching: Batching) {
+            let batcher = Arc::new(Batcher {
+                callback,
+                batching,
+                queue: Mutex::new(Queue::default()),
+                wake_worker: Condvar::new(),
+                drained: Condvar::new(),
+                delivered: AtomicU64::new(0),
+                dropped: AtomicU64::new(0),
+                batches: AtomicU64::new(0),
+            });
+            let worker = Arc::clone(&batcher);
+            std::thread::Builder::new()
+                .name("logfusion-callback".to_string())
+                .spawn(move || worker.run())
+                .expect("failed to spawn the logfusion callback thread");
+            *CALLBACK.lock().unwrap_or_else(PoisonError::into_inner) = None;
+            replace_batcher(Some(batcher));
+        }
+
+        /// Deliver all queued records to the batched callback, waiting until done
+        ///
+        /// Must not be called from the batched callback itself.
+        pub fn flush() {
+            let batcher = BATCHER.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            if let Some(batcher) = batcher {
+                batcher.flush();
+            }
+        }
+
+        /// Delivery counters of the current batched callback, all zero without one
+        ///
+        /// The counters are plain numbers, so FFI bridges can pass them to the host
+        /// language as they are.
+        pub fn stats() -> Stats {
+            BATCHER
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .as_ref()
+                .map(|batcher| batcher.stats())
+                .unwrap_or_default()
+        }
     }
 }
@@ -0,0 +1,63 @@
+//! Machine-readable catalog of the errors defined with `define_errors!`
+
+use serde_json::{Value, json};
+
+/// Error variant as declared in `define_errors!`, returned by the generated
+/// `catalog()` function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorEntry {
+    /// Variant name, as returned by `code()`
+    pub name: &'static str,
+    /// Numeric code given with `code = ...`
+    pub code: Option<u32>,
+    /// Category given with `category = "..."`
+    pub category: Option<&'static str>,
+    /// Message template, with `{field}` placeholders
+    pub message: &'static str,
+    /// Level the error is logged at
+    pub level: &'static str,
+    /// Target the error is logged to
+    pub target: &'static str,
+}
+
+impl ErrorEntry {
+    /// The entry as a JSON object, leaving out a missing code or category
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let mut entry = json!({
+            "name": self.name,
+            "message": self.message,
+            "level": self.level,
+            "target": self.target,
+        });
+        if let Some(code) = self.code {
+            entry["code"] = code.into();
+        }
+        if let Some(category) = self.category {
+            entry["category"] = category.into();
+        }
+        entry
+    }
+}
+
+/// Export error catalogs as a pretty-printed JSON array, for documentation and FFI
+/// clients
+///
+/// ```rust
+/// use logfusion::define_errors;
+///
+/// define_errors! {
+///     StorageError {
+///         DiskFull {} : "Disk is full" [level = warn, code = 2001, category = "io"],
+///         Corrupted { path: String } : "Corrupted file {path}" [code = 2002]
+///     }
+/// }
+///
+/// let catalog = logfusion::catalog_json(&StorageError::catalog());
+/// assert!(catalog.contains(r#""code": 2001"#));
+/// ```
+#[must_use]
+pub fn catalog_json(entries: &[ErrorEntry]) -> String {
+    let entries: Vec<Value> = entries.iter().map(ErrorEntry::to_json).collect();
+    serde_json::to_string_pretty(&entries).unwrap_or_default()
+}
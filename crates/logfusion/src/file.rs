@@ -0,0 +1,358 @@
+//! Log files with size and time based rotation, gzip and retention
+//!
+//! [`file`] starts a [`FileAppender`] builder. The active log is always written at the
+//! given path; when it rotates, it's renamed with the period or time it covers
+//! (`app.log.2025-01-02`), optionally gzipped (`app.log.2025-01-02.gz`), and the
+//! oldest rotated files beyond the retention count are removed.
+//!
+//! ```rust,no_run
+//! logfusion::file("logs/app.log")
+//!     .rotate_daily()
+//!     .max_size(100 * 1024 * 1024)
+//!     .gzip()
+//!     .keep(7)
+//!     .init()
+//!     .expect("logging already initialized");
+//!
+//! logfusion::info!("Written to logs/app.log");
+//! ```
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Start configuring a log file at `path`
+///
+/// Without rotation options, the file grows forever like a plain append.
+pub fn file(path: impl Into<PathBuf>) -> FileAppender {
+    FileAppender {
+        path: path.into(),
+        period: None,
+        max_size: None,
+        keep: None,
+        gzip: false,
+    }
+}
+
+/// Time period covered by a log file, in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Period {
+    Hourly,
+    Daily,
+}
+
+impl Period {
+    const fn seconds(self) -> u64 {
+        match self {
+            Self::Hourly => 3600,
+            Self::Daily => 86400,
+        }
+    }
+
+    /// Index of the period containing `time`, counted from the Unix epoch
+    fn index(self, time: SystemTime) -> u64 {
+        seconds_since_epoch(time) / self.seconds()
+    }
+
+    /// Name suffix of the period `index`, such as `2025-01-02` or `2025-01-02-13`
+    fn label(self, index: u64) -> String {
+        let start = index * self.seconds();
+        let (year, month, day) = civil_date(start / 86400);
+        match self {
+            Self::Daily => format!("{year:04}-{month:02}-{day:02}"),
+            Self::Hourly => {
+                let hour = start % 86400 / 3600;
+                format!("{year:04}-{month:02}-{day:02}-{hour:02}")
+            }
+        }
+    }
+}
+
+/// Builder of a [`RollingFile`], started with [`file`]
+///
+/// Time and size rotation combine: the file rotates when its period ends or when it
+/// would grow beyond the maximum size, whichever comes first.
+#[derive(Debug, Clone)]
+pub struct FileAppender {
+    path: PathBuf,
+    period: Option<Period>,
+    max_size: Option<u64>,
+    keep: Option<usize>,
+    gzip: bool,
+}
+
+impl FileAppender {
+    /// Rotate at the start of each UTC day, naming rotated files `app.log.2025-01-02`
+    #[must_use]
+    pub const fn rotate_daily(mut self) -> Self {
+        self.period = Some(Period::Daily);
+        self
+    }
+
+    /// Rotate at the start of each hour, naming rotated files `app.log.2025-01-02-13`
+    #[must_use]
+    pub const fn rotate_hourly(mut self) -> Self {
+        self.period = Some(Period::Hourly);
+        self
+    }
+
+    /// Rotate before the file grows beyond `bytes`
+    ///
+    /// Without time rotation, rotated files are named by the time they were rotated,
+    /// such as `app.log.2025-01-02-134501`. A single event larger than `bytes` still
+    /// goes to one file.
+    #[must_use]
+    pub const fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Keep only the `count` most recent rotated files, removing older ones
+    #[must_use]
+    pub const fn keep(mut self, count: usize) -> Self {
+        self.keep = Some(count);
+        self
+    }
+
+    /// Compress rotated files with gzip, adding a `.gz` extension
+    #[must_use]
+    pub const fn gzip(mut self) -> Self {
+        self.gzip = true;
+        self
+    }
+
+    /// Open the log file, creating it and its directory if needed
+    ///
+    /// The returned [`RollingFile`] is a [`MakeWriter`] for `tracing_subscriber`
+    /// layers, such as `fmt::layer().with_writer(file)` or
+    /// [`JsonLayer::with_writer`](crate::JsonLayer::with_writer).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or the file can't be created or opened.
+    pub fn build(self) -> io::Result<RollingFile> {
+        if let Some(dir) = parent_dir(&self.path) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = open_append(&self.path)?;
+        let metadata = file.metadata()?;
+        // A file left by a previous run belongs to the period it was last written in
+        let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+        let state = State {
+            period_index: self.period.map(|period| period.index(modified)),
+            size: metadata.len(),
+            file: BufWriter::new(file),
+        };
+        Ok(RollingFile {
+            config: Arc::new(self),
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Install a global subscriber writing plain text logs to the file
+    ///
+    /// Events are filtered by `RUST_LOG`, showing `info` and above when it isn't set.
+    /// For JSON lines, pass [`build`](Self::build)'s file to
+    /// [`JsonLayer::with_writer`](crate::JsonLayer::with_writer) instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be opened or a global subscriber is
+    /// already installed.
+    pub fn init(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let env_filter = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+        tracing_subscriber::fmt()
+            .with_env_filter(env_filter)
+            .with_ansi(false)
+            .with_writer(self.build()?)
+            .try_init()
+    }
+}
+
+/// Open log file
+struct State {
+    file: BufWriter<File>,
+    size: u64,
+    period_index: Option<u64>,
+}
+
+/// Log file rotating by time and size, shared by all its clones
+///
+/// Each write is flushed, so a line is never split across files and logs survive a
+/// crash. Rotated files are compressed and pruned on the thread whose write
+/// triggered the rotation.
+#[derive(Clone)]
+pub struct RollingFile {
+    config: Arc<FileAppender>,
+    state: Arc<Mutex<State>>,
+}
+
+impl RollingFile {
+    /// Path of the active log file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.config.path
+    }
+
+    fn write_at(&self, buf: &[u8], now: SystemTime) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let config = &self.config;
+
+        let period_ended = config
+            .period
+            .zip(state.period_index)
+            .is_some_and(|(period, index)| period.index(now) != index);
+        let too_large = config
+            .max_size
+            .is_some_and(|max_size| state.size > 0 && state.size + buf.len() as u64 > max_size);
+        if period_ended || too_large {
+            self.rotate(&mut state, now)?;
+        }
+
+        state.file.write_all(buf)?;
+        state.file.flush()?;
+        state.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    /// Move the active file aside, compress and prune, and start a new file
+    fn rotate(&self, state: &mut State, now: SystemTime) -> io::Result<()> {
+        let config = &self.config;
+        state.file.flush()?;
+
+        let suffix = match config.period.zip(state.period_index) {
+            Some((period, index)) => period.label(index),
+            None => {
+                let seconds = seconds_since_epoch(now);
+                let (year, month, day) = civil_date(seconds / 86400);
+                let time = seconds % 86400;
+                let (hour, minute, second) = (time / 3600, time % 3600 / 60, time % 60);
+                format!("{year:04}-{month:02}-{day:02}-{hour:02}{minute:02}{second:02}")
+            }
+        };
+        let rotated = self.free_name(&suffix);
+        fs::rename(&config.path, &rotated)?;
+
+        state.file = BufWriter::new(open_append(&config.path)?);
+        state.size = 0;
+        state.period_index = config.period.map(|period| period.index(now));
+
+        if config.gzip {
+            compress(&rotated)?;
+        }
+        if let Some(keep) = config.keep {
+            self.prune(keep)?;
+        }
+        Ok(())
+    }
+
+    /// `app.log.<suffix>`, numbered `.1`, `.2`, ... if it's taken, gzipped or not
+    fn free_name(&self, suffix: &str) -> PathBuf {
+        let base = format!("{}.{suffix}", self.config.path.display());
+        let taken =
+            |name: &str| Path::new(name).exists() || Path::new(&format!("{name}.gz")).exists();
+        let mut name = base.clone();
+        let mut number = 0;
+        while taken(&name) {
+            number += 1;
+            name = format!("{base}.{number}");
+        }
+        PathBuf::from(name)
+    }
+
+    /// Remove the oldest rotated files beyond the `keep` most recent ones
+    fn prune(&self, keep: usize) -> io::Result<()> {
+        let path = &self.config.path;
+        let dir = parent_dir(path).unwrap_or_else(|| Path::new("."));
+        let prefix = format!(
+            "{}.",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+
+        let mut rotated = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let modified = entry.metadata()?.modified().unwrap_or(UNIX_EPOCH);
+                rotated.push((modified, entry.file_name(), entry.path()));
+            }
+        }
+        // Newest first, breaking ties by the later name
+        rotated.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+        for (_, _, old) in rotated.into_iter().skip(keep) {
+            fs::remove_file(old)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for &RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_at(buf, SystemTime::now())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.file.flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingFile {
+    type Writer = &'a Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self
+    }
+}
+
+/// Directory of `path`, or `None` for a bare file name
+fn parent_dir(path: &Path) -> Option<&Path> {
+    path.parent().filter(|dir| !dir.as_os_str().is_empty())
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Replace `path` with a gzipped `path.gz`
+fn compress(path: &Path) -> io::Result<()> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let mut input = BufReader::new(File::open(path)?);
+    let mut encoder = GzEncoder::new(File::create(&gz_name)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+fn seconds_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+/// Year, month and day of the UTC date `days` after 1970-01-01
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+const fn civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,220 @@
+//! JSON log output for log shippers such as Filebeat, Fluent Bit, or Vector
+//!
+//! [`JsonLayer`] writes one JSON object per line for each event:
+//!
+//! ```json
+//! {"fields":{"order_id":42,"user":"alice"},"level":"INFO","message":"Order placed","span_id":"2","target":"shop::orders","timestamp":"2025-01-02T10:00:00.000000Z","trace_id":"1"}
+//! ```
+//!
+//! - `fields` holds the event's fields, merged over the fields of its spans (outer
+//!   spans first), so context recorded on a span appears on every event inside it.
+//! - `span_id` is the ID of the innermost span and `trace_id` the ID of the outermost
+//!   one; both are left out for events outside spans. A `trace_id` or `span_id` field,
+//!   such as one propagated from an incoming request, takes their place.
+
+use serde_json::{Map, Number, Value};
+use std::fmt::Debug;
+use std::io::{self, Write};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::{FormatTime, SystemTime};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::{SubscriberInitExt, TryInitError};
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Fields recorded on a span, stored in its extensions
+struct SpanFields(Map<String, Value>);
+
+/// Collects the message and fields of an event or span as JSON values
+#[derive(Default)]
+struct JsonVisitor {
+    message: Option<String>,
+    fields: Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = Some(match value {
+                Value::String(message) => message,
+                other => other.to_string(),
+            });
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        let value = Number::from_f64(value).map_or_else(|| value.to_string().into(), Value::Number);
+        self.insert(field, value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.insert(field, value.to_string().into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.insert(field, format!("{value:?}").into());
+    }
+}
+
+/// Layer writing each event as a line of JSON
+///
+/// Install it with [`init_json`], or compose it with other layers:
+///
+/// ```rust
+/// use logfusion::{EnvFilter, JsonLayer, SubscriberExt};
+///
+/// let subscriber = logfusion::registry()
+///     .with(EnvFilter::new("info"))
+///     .with(JsonLayer::new().with_writer(std::io::stderr));
+/// tracing::subscriber::with_default(subscriber, || {
+///     logfusion::info!(user = "alice", "User logged in");
+/// });
+/// ```
+pub struct JsonLayer<W = fn() -> io::Stdout> {
+    make_writer: W,
+}
+
+impl JsonLayer {
+    /// Layer writing to standard output
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            make_writer: io::stdout,
+        }
+    }
+}
+
+impl Default for JsonLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W> JsonLayer<W> {
+    /// Write to `make_writer` instead, such as `std::io::stderr` or a non-blocking
+    /// file appender
+    pub fn with_writer<W2>(self, make_writer: W2) -> JsonLayer<W2>
+    where
+        W2: for<'writer> MakeWriter<'writer> + 'static,
+    {
+        JsonLayer { make_writer }
+    }
+}
+
+impl<S, W> Layer<S> for JsonLayer<W>
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+    W: for<'writer> MakeWriter<'writer> + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = JsonVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanFields(visitor.fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = JsonVisitor::default();
+        values.record(&mut visitor);
+        if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+            fields.extend(visitor.fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut entry = Map::new();
+
+        let mut timestamp = String::new();
+        if SystemTime
+            .format_time(&mut Writer::new(&mut timestamp))
+            .is_ok()
+        {
+            entry.insert("timestamp".into(), timestamp.into());
+        }
+        entry.insert("level".into(), metadata.level().as_str().into());
+        entry.insert("target".into(), metadata.target().into());
+
+        let mut fields = Map::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            let mut trace_id = None;
+            let mut span_id = None;
+            for span in scope.from_root() {
+                trace_id.get_or_insert_with(|| format!("{:x}", span.id().into_u64()));
+                span_id = Some(format!("{:x}", span.id().into_u64()));
+                if let Some(SpanFields(span_fields)) = span.extensions().get::<SpanFields>() {
+                    fields.extend(span_fields.clone());
+                }
+            }
+            entry.extend(trace_id.map(|id| ("trace_id".to_string(), id.into())));
+            entry.extend(span_id.map(|id| ("span_id".to_string(), id.into())));
+        }
+
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        fields.extend(visitor.fields);
+        for id in ["trace_id", "span_id"] {
+            if let Some(value) = fields.remove(id) {
+                entry.insert(id.into(), value);
+            }
+        }
+        entry.insert("message".into(), visitor.message.unwrap_or_default().into());
+        entry.insert("fields".into(), fields.into());
+
+        let mut line = Value::Object(entry).to_string();
+        line.push('\n');
+        // Logging must not fail the application, so write errors are dropped
+        let _ = self
+            .make_writer
+            .make_writer_for(metadata)
+            .write_all(line.as_bytes());
+    }
+}
+
+/// Install a global subscriber writing JSON lines to standard output
+///
+/// Events are filtered by `RUST_LOG` like the default subscriber, showing `info` and
+/// above when it isn't set. Call it at the start of `main`, before the first log
+/// macro installs the default subscriber.
+///
+/// ```rust,no_run
+/// logfusion::init_json().expect("logging already initialized");
+/// logfusion::info!(port = 8080, "Server started");
+/// // {"fields":{"port":8080},"level":"INFO","message":"Server started",...}
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if a global subscriber is already installed.
+pub fn init_json() -> Result<(), TryInitError> {
+    let env_filter = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(env_filter))
+        .with(JsonLayer::new())
+        .try_init()
+}
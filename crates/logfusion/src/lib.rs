@@ -6,11 +6,36 @@
 // Include macros.rs first, before any logging macros are defined
 include!("macros.rs");
 
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub mod backend;
 #[doc(hidden)]
 pub mod callback;
+#[cfg(feature = "tracing")]
+mod catalog;
+#[cfg(feature = "file")]
+mod file;
+#[cfg(feature = "tracing")]
+mod json;
+#[cfg(all(feature = "callback", feature = "tracing"))]
+mod spans;
 mod tracing;
 
+#[cfg(feature = "tracing")]
+pub use crate::backend::{Backend, backend_level, backends, set_backend_level, set_backends};
+#[cfg(feature = "tracing")]
+pub use crate::catalog::{ErrorEntry, catalog_json};
+#[cfg(feature = "file")]
+pub use crate::file::{FileAppender, RollingFile, file};
+#[cfg(feature = "tracing")]
+pub use crate::json::{JsonLayer, init_json};
+#[cfg(all(feature = "callback", feature = "tracing"))]
+pub use crate::spans::{CallbackLayer, SpanCallback, SpanEvent, set_span_callback};
 pub use crate::tracing::*;
 
 #[cfg(feature = "callback")]
-pub use crate::callback::{Callback, set as set_callback};
+pub use crate::callback::{
+    BatchCallback, Batching, Callback, Overflow, Record, Stats as CallbackStats,
+    flush as flush_callback, set as set_callback, set_batched as set_batch_callback,
+    stats as callback_stats,
+};
@@ -24,7 +24,7 @@ macro_rules! define_errors {
         $(#[$enum_meta:meta])*
         $vis:vis enum $name:ident {
             $(
-                #[error($msg:literal $(, level = $level:ident)? $(, target = $target:literal)? $(, source)?)]
+                #[error($msg:literal $(, level = $level:ident)? $(, target = $target:literal)? $(, code = $error_code:literal)? $(, category = $category:literal)? $(, source)?)]
                 $variant:ident $({
                     $(
                         $(#[$field_meta:meta])*
@@ -97,6 +97,44 @@ macro_rules! define_errors {
                     )*
                 }
             }
+            /// Numeric error code given with `code = ...`, stable across renames
+            pub fn error_code(&self) -> Option<u32> {
+                match self {
+                    $(
+                        Self::$variant { .. } => define_errors!(@option $($error_code)?),
+                    )*
+                }
+            }
+
+            /// Category given with `category = "..."`, such as `"io"`
+            pub fn category(&self) -> Option<&'static str> {
+                match self {
+                    $(
+                        Self::$variant { .. } => define_errors!(@option $($category)?),
+                    )*
+                }
+            }
+
+            /// Catalog of all variants
+            ///
+            /// Export it as JSON with `logfusion::catalog_json`.
+            pub fn catalog() -> Vec<$crate::ErrorEntry> {
+                vec![
+                    $(
+                        {
+                            let (name, level, target) = define_errors!(@extract_thiserror_info $($level)? $($target)? ; stringify!($variant));
+                            $crate::ErrorEntry {
+                                name,
+                                code: define_errors!(@option $($error_code)?),
+                                category: define_errors!(@option $($category)?),
+                                message: $msg,
+                                level,
+                                target,
+                            }
+                        },
+                    )*
+                ]
+            }
         }
     };
 
@@ -195,7 +233,7 @@ macro_rules! define_errors {
                         Self::$variant => {
                             let code = self.code();
                             let message = self.to_string();
-                            define_errors!(@log_simple $([$($attr)*])? ; code, message);
+                            define_errors!(@without_ids log_simple $([$($attr)*])? ; code, message);
                         },
                     )*
                 }
@@ -225,11 +263,49 @@ macro_rules! define_errors {
                     $(
                         Self::$variant => {
                             let code = stringify!($variant);
-                            define_errors!(@extract_info $([$($attr)*])? ; code)
+                            define_errors!(@without_ids extract_info $([$($attr)*])? ; code)
                         },
                     )*
                 }
             }
+            /// Numeric error code given with `code = ...`, stable across renames
+            pub fn error_code(&self) -> Option<u32> {
+                match self {
+                    $(
+                        Self::$variant => define_errors!(@attr_code $([$($attr)*])?),
+                    )*
+                }
+            }
+
+            /// Category given with `category = "..."`, such as `"io"`
+            pub fn category(&self) -> Option<&'static str> {
+                match self {
+                    $(
+                        Self::$variant => define_errors!(@attr_category $([$($attr)*])?),
+                    )*
+                }
+            }
+
+            /// Catalog of all variants
+            ///
+            /// Export it as JSON with `logfusion::catalog_json`.
+            pub fn catalog() -> Vec<$crate::ErrorEntry> {
+                vec![
+                    $(
+                        {
+                            let (name, level, target) = define_errors!(@without_ids extract_info $([$($attr)*])? ; stringify!($variant));
+                            $crate::ErrorEntry {
+                                name,
+                                code: define_errors!(@attr_code $([$($attr)*])?),
+                                category: define_errors!(@attr_category $([$($attr)*])?),
+                                message: $msg,
+                                level,
+                                target,
+                            }
+                        },
+                    )*
+                ]
+            }
         }
     };
 
@@ -252,7 +328,7 @@ macro_rules! define_errors {
                         Self::$variant { .. } => {
                             let code = self.code();
                             let message = self.to_string();
-                            define_errors!(@log_simple $([$($attr)*])? ; code, message);
+                            define_errors!(@without_ids log_simple $([$($attr)*])? ; code, message);
                         },
                     )*
                 }
@@ -282,11 +358,49 @@ macro_rules! define_errors {
                     $(
                         Self::$variant { .. } => {
                             let code = stringify!($variant);
-                            define_errors!(@extract_info $([$($attr)*])? ; code)
+                            define_errors!(@without_ids extract_info $([$($attr)*])? ; code)
                         },
                     )*
                 }
             }
+            /// Numeric error code given with `code = ...`, stable across renames
+            pub fn error_code(&self) -> Option<u32> {
+                match self {
+                    $(
+                        Self::$variant { .. } => define_errors!(@attr_code $([$($attr)*])?),
+                    )*
+                }
+            }
+
+            /// Category given with `category = "..."`, such as `"io"`
+            pub fn category(&self) -> Option<&'static str> {
+                match self {
+                    $(
+                        Self::$variant { .. } => define_errors!(@attr_category $([$($attr)*])?),
+                    )*
+                }
+            }
+
+            /// Catalog of all variants
+            ///
+            /// Export it as JSON with `logfusion::catalog_json`.
+            pub fn catalog() -> Vec<$crate::ErrorEntry> {
+                vec![
+                    $(
+                        {
+                            let (name, level, target) = define_errors!(@without_ids extract_info $([$($attr)*])? ; stringify!($variant));
+                            $crate::ErrorEntry {
+                                name,
+                                code: define_errors!(@attr_code $([$($attr)*])?),
+                                category: define_errors!(@attr_category $([$($attr)*])?),
+                                message: $msg,
+                                level,
+                                target,
+                            }
+                        },
+                    )*
+                ]
+            }
         }
     };
 
@@ -358,14 +472,14 @@ macro_rules! define_errors {
                         Self::$unit_variant => {
                             let code = self.code();
                             let message = self.to_string();
-                            define_errors!(@log_simple $([$($unit_attr)*])? ; code, message);
+                            define_errors!(@without_ids log_simple $([$($unit_attr)*])? ; code, message);
                         },
                     )*
                     $(
                         Self::$struct_variant { .. } => {
                             let code = self.code();
                             let message = self.to_string();
-                            define_errors!(@log_simple $([$($struct_attr)*])? ; code, message);
+                            define_errors!(@without_ids log_simple $([$($struct_attr)*])? ; code, message);
                         },
                     )*
                 }
@@ -398,17 +512,74 @@ macro_rules! define_errors {
                     $(
                         Self::$unit_variant => {
                             let code = stringify!($unit_variant);
-                            define_errors!(@extract_info $([$($unit_attr)*])? ; code)
+                            define_errors!(@without_ids extract_info $([$($unit_attr)*])? ; code)
                         },
                     )*
                     $(
                         Self::$struct_variant { .. } => {
                             let code = stringify!($struct_variant);
-                            define_errors!(@extract_info $([$($struct_attr)*])? ; code)
+                            define_errors!(@without_ids extract_info $([$($struct_attr)*])? ; code)
                         },
                     )*
                 }
             }
+            /// Numeric error code given with `code = ...`, stable across renames
+            pub fn error_code(&self) -> Option<u32> {
+                match self {
+                    $(
+                        Self::$unit_variant => define_errors!(@attr_code $([$($unit_attr)*])?),
+                    )*
+                    $(
+                        Self::$struct_variant { .. } => define_errors!(@attr_code $([$($struct_attr)*])?),
+                    )*
+                }
+            }
+
+            /// Category given with `category = "..."`, such as `"io"`
+            pub fn category(&self) -> Option<&'static str> {
+                match self {
+                    $(
+                        Self::$unit_variant => define_errors!(@attr_category $([$($unit_attr)*])?),
+                    )*
+                    $(
+                        Self::$struct_variant { .. } => define_errors!(@attr_category $([$($struct_attr)*])?),
+                    )*
+                }
+            }
+
+            /// Catalog of all variants
+            ///
+            /// Export it as JSON with `logfusion::catalog_json`.
+            pub fn catalog() -> Vec<$crate::ErrorEntry> {
+                vec![
+                    $(
+                        {
+                            let (name, level, target) = define_errors!(@without_ids extract_info $([$($unit_attr)*])? ; stringify!($unit_variant));
+                            $crate::ErrorEntry {
+                                name,
+                                code: define_errors!(@attr_code $([$($unit_attr)*])?),
+                                category: define_errors!(@attr_category $([$($unit_attr)*])?),
+                                message: $unit_msg,
+                                level,
+                                target,
+                            }
+                        },
+                    )*
+                    $(
+                        {
+                            let (name, level, target) = define_errors!(@without_ids extract_info $([$($struct_attr)*])? ; stringify!($struct_variant));
+                            $crate::ErrorEntry {
+                                name,
+                                code: define_errors!(@attr_code $([$($struct_attr)*])?),
+                                category: define_errors!(@attr_category $([$($struct_attr)*])?),
+                                message: $struct_msg,
+                                level,
+                                target,
+                            }
+                        },
+                    )*
+                ]
+            }
         }
     };
 
@@ -492,6 +663,56 @@ macro_rules! define_errors {
         define_errors!(@log_with_attrs ; $code, $message);
     };
 
+    // -----------------------------------------------------------------------------------
+    // ERROR CODE AND CATEGORY ATTRIBUTES (code = N, category = "..." syntax)
+    // -----------------------------------------------------------------------------------
+    // Drop code and category from the attributes, then continue with `@$next`, so the
+    // level and target patterns only ever see level and target
+    (@without_ids $next:ident ; $($args:tt)*) => {
+        define_errors!(@$next ; $($args)*)
+    };
+    (@without_ids $next:ident [$($attr:tt)*] ; $($args:tt)*) => {
+        define_errors!(@without_ids $next [] [$($attr)*] ; $($args)*)
+    };
+    (@without_ids $next:ident [$($kept:tt)*] [code = $value:literal $(, $($rest:tt)*)?] ; $($args:tt)*) => {
+        define_errors!(@without_ids $next [$($kept)*] [$($($rest)*)?] ; $($args)*)
+    };
+    (@without_ids $next:ident [$($kept:tt)*] [category = $value:literal $(, $($rest:tt)*)?] ; $($args:tt)*) => {
+        define_errors!(@without_ids $next [$($kept)*] [$($($rest)*)?] ; $($args)*)
+    };
+    (@without_ids $next:ident [] [$key:ident = $value:tt $(, $($rest:tt)*)?] ; $($args:tt)*) => {
+        define_errors!(@without_ids $next [$key = $value] [$($($rest)*)?] ; $($args)*)
+    };
+    (@without_ids $next:ident [$($kept:tt)+] [$key:ident = $value:tt $(, $($rest:tt)*)?] ; $($args:tt)*) => {
+        define_errors!(@without_ids $next [$($kept)+, $key = $value] [$($($rest)*)?] ; $($args)*)
+    };
+    (@without_ids $next:ident [] [] ; $($args:tt)*) => {
+        define_errors!(@$next ; $($args)*)
+    };
+    (@without_ids $next:ident [$($kept:tt)+] [] ; $($args:tt)*) => {
+        define_errors!(@$next [$($kept)+] ; $($args)*)
+    };
+
+    // Find `code = N` in the attributes
+    (@attr_code) => { None };
+    (@attr_code []) => { None };
+    (@attr_code [code = $value:literal $(, $($rest:tt)*)?]) => { Some($value) };
+    (@attr_code [$key:ident = $value:tt $(, $($rest:tt)*)?]) => {
+        define_errors!(@attr_code [$($($rest)*)?])
+    };
+
+    // Find `category = "..."` in the attributes
+    (@attr_category) => { None };
+    (@attr_category []) => { None };
+    (@attr_category [category = $value:literal $(, $($rest:tt)*)?]) => { Some($value) };
+    (@attr_category [$key:ident = $value:tt $(, $($rest:tt)*)?]) => {
+        define_errors!(@attr_category [$($($rest)*)?])
+    };
+
+    // Optional thiserror attribute value
+    (@option $value:literal) => { Some($value) };
+    (@option) => { None };
+
     // -----------------------------------------------------------------------------------
     // STRUCTURED ERROR INFO EXTRACTION
     // -----------------------------------------------------------------------------------
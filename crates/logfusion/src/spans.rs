@@ -0,0 +1,166 @@
+//! Span lifecycle callbacks, so FFI bridges can rebuild span timing and hierarchy
+//!
+//! [`CallbackLayer`] reports every span's start and end to the callback set with
+//! [`set_span_callback`]. The default subscriber installs it when the `callback`
+//! feature is enabled; custom subscribers add it like any other layer.
+
+use std::fmt::Debug;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::Subscriber;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Start or end of a span, passed to the span callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanEvent<'a> {
+    /// A span was created
+    Start {
+        /// ID of the span, unique among open spans
+        id: u64,
+        /// ID of the parent span, `None` for a root span
+        parent_id: Option<u64>,
+        /// Name given to the span macro
+        name: &'a str,
+        /// Target, the module path unless given
+        target: &'a str,
+        /// Level such as `"INFO"`
+        level: &'a str,
+        /// Fields given at creation, as `(name, value)` pairs
+        fields: &'a [(String, String)],
+    },
+    /// A span was closed, after its last handle was dropped
+    End {
+        /// ID of the span, as given at its start
+        id: u64,
+        /// Name given to the span macro
+        name: &'a str,
+        /// Time from start to end
+        elapsed: Duration,
+        /// Fields at the end, including ones recorded after creation
+        fields: &'a [(String, String)],
+    },
+}
+
+/// Callback receiving span lifecycle events
+pub type SpanCallback = Box<dyn Fn(SpanEvent<'_>) + Send + Sync>;
+
+/// Global span callback storage
+static SPAN_CALLBACK: Mutex<Option<SpanCallback>> = Mutex::new(None);
+
+/// Set the callback receiving span starts and ends, replacing the previous one
+///
+/// The callback runs on the thread creating or closing the span, so span events are
+/// never batched.
+///
+/// ```rust
+/// use logfusion::{SpanEvent, set_span_callback};
+///
+/// set_span_callback(Box::new(|event| match event {
+///     SpanEvent::Start { id, parent_id, name, .. } => {
+///         println!("start {name} ({id}, parent {parent_id:?})");
+///     }
+///     SpanEvent::End { id, elapsed, .. } => println!("end {id} after {elapsed:?}"),
+/// }));
+/// ```
+pub fn set_span_callback(callback: SpanCallback) {
+    *SPAN_CALLBACK.lock().unwrap_or_else(PoisonError::into_inner) = Some(callback);
+}
+
+fn call(event: SpanEvent<'_>) {
+    if let Some(callback) = SPAN_CALLBACK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+    {
+        callback(event);
+    }
+}
+
+/// Fields and start time of a span, stored in its extensions
+struct SpanState {
+    start: Instant,
+    fields: Vec<(String, String)>,
+}
+
+/// Collects fields as `(name, value)` strings
+struct FieldVisitor<'a>(&'a mut Vec<(String, String)>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.set(field, format!("{value:?}"));
+    }
+}
+
+impl FieldVisitor<'_> {
+    fn set(&mut self, field: &Field, value: String) {
+        match self.0.iter_mut().find(|(name, _)| name == field.name()) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((field.name().to_string(), value)),
+        }
+    }
+}
+
+/// Layer reporting span starts and ends to the span callback
+///
+/// Only needed with a custom subscriber:
+///
+/// ```rust
+/// use logfusion::{CallbackLayer, SubscriberExt};
+///
+/// let subscriber = logfusion::registry().with(CallbackLayer);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallbackLayer;
+
+impl<S> Layer<S> for CallbackLayer
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = Vec::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+
+        let metadata = span.metadata();
+        call(SpanEvent::Start {
+            id: id.into_u64(),
+            parent_id: span.parent().map(|parent| parent.id().into_u64()),
+            name: metadata.name(),
+            target: metadata.target(),
+            level: metadata.level().as_str(),
+            fields: &fields,
+        });
+        span.extensions_mut().insert(SpanState {
+            start: Instant::now(),
+            fields,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if let Some(state) = span.extensions_mut().get_mut::<SpanState>() {
+            values.record(&mut FieldVisitor(&mut state.fields));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(state) = span.extensions_mut().remove::<SpanState>() else {
+            return;
+        };
+        call(SpanEvent::End {
+            id: id.into_u64(),
+            name: span.name(),
+            elapsed: state.start.elapsed(),
+            fields: &state.fields,
+        });
+    }
+}
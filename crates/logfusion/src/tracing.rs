@@ -24,9 +24,18 @@ cfg_if! {
                     let env_filter = std::env::var("RUST_LOG")
                         .unwrap_or_else(|_| "info".to_string());
 
-                    let _ = tracing_subscriber::fmt()
+                    let subscriber = tracing_subscriber::fmt()
                         .with_env_filter(env_filter)
-                        .try_init();
+                        .finish();
+
+                    // Report spans to the span callback as well
+                    #[cfg(feature = "callback")]
+                    let subscriber = {
+                        use tracing_subscriber::layer::SubscriberExt;
+                        subscriber.with(crate::CallbackLayer)
+                    };
+
+                    let _ = tracing_subscriber::util::SubscriberInitExt::try_init(subscriber);
                 }
             });
         }
@@ -43,13 +52,17 @@ cfg_if! {
                         $crate::ensure_logging_initialized();
 
                         // Call tracing macro with structured fields
-                        ::tracing::%{macro_name}!(target: $target, $($field = $value,)* $fmt $(, $($arg),*)?);
+                        if $crate::backend::enabled($crate::Backend::Tracing, stringify!(%{macro_name})) {
+                            ::tracing::%{macro_name}!(target: $target, $($field = $value,)* $fmt $(, $($arg),*)?);
+                        }
 
                         // Call FFI callback only if feature enabled (zero-overhead by default)
                         #[cfg(feature = "callback")]
                         {
-                            let message = format!($fmt $(, $($arg),*)?);
-                            $crate::callback::call(stringify!(%{macro_name}), $target, &message);
+                            if $crate::backend::enabled($crate::Backend::Callback, stringify!(%{macro_name})) {
+                                let message = format!($fmt $(, $($arg),*)?);
+                                $crate::callback::call(stringify!(%{macro_name}), $target, &message);
+                            }
                         }
                     }
                 };
@@ -59,13 +72,17 @@ cfg_if! {
                         $crate::ensure_logging_initialized();
 
                         // Call tracing macro
-                        ::tracing::%{macro_name}!(target: $target, $fmt $(, $($arg),*)?);
+                        if $crate::backend::enabled($crate::Backend::Tracing, stringify!(%{macro_name})) {
+                            ::tracing::%{macro_name}!(target: $target, $fmt $(, $($arg),*)?);
+                        }
 
                         // Call FFI callback only if feature enabled (zero-overhead by default)
                         #[cfg(feature = "callback")]
                         {
-                            let message = format!($fmt $(, $($arg),*)?);
-                            $crate::callback::call(stringify!(%{macro_name}), $target, &message);
+                            if $crate::backend::enabled($crate::Backend::Callback, stringify!(%{macro_name})) {
+                                let message = format!($fmt $(, $($arg),*)?);
+                                $crate::callback::call(stringify!(%{macro_name}), $target, &message);
+                            }
                         }
                     }
                 };
@@ -75,14 +92,18 @@ cfg_if! {
                         $crate::ensure_logging_initialized();
 
                         // Call tracing macro
-                        ::tracing::%{macro_name}!(target: $target, $($arg)*);
+                        if $crate::backend::enabled($crate::Backend::Tracing, stringify!(%{macro_name})) {
+                            ::tracing::%{macro_name}!(target: $target, $($arg)*);
+                        }
 
                         // Call FFI callback only if feature enabled (zero-overhead by default)
                         #[cfg(feature = "callback")]
                         {
-                            // For complex syntax, just use a generic message
-                            let message = concat!("Complex log: ", stringify!($($arg)*));
-                            $crate::callback::call(stringify!(%{macro_name}), $target, message);
+                            if $crate::backend::enabled($crate::Backend::Callback, stringify!(%{macro_name})) {
+                                // For complex syntax, just use a generic message
+                                let message = concat!("Complex log: ", stringify!($($arg)*));
+                                $crate::callback::call(stringify!(%{macro_name}), $target, message);
+                            }
                         }
                     }
                 };
@@ -136,7 +157,7 @@ cfg_if! {
         // Direct re-exports (no wrapping needed)
         pub use tracing::{
             // Types
-            Level, Event, Span, Id, Metadata, Dispatch,
+            Level, Event, Span, Id, Metadata, Dispatch, level_filters::LevelFilter,
             // Traits
             Subscriber,
             // Functions
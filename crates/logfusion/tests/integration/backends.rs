@@ -0,0 +1,51 @@
+//! Tests for backend selection and per-backend level filters
+//!
+//! Only the callback backend is switched off here: tests logging through tracing run
+//! in parallel and must keep receiving their events.
+
+use logfusion::{
+    Backend, LevelFilter, backend_level, backends, error, info, set_backend_level, set_backends,
+    set_callback, warn,
+};
+use serial_test::serial;
+use std::sync::{Arc, Mutex};
+
+const TARGET: &str = "backends_test";
+
+#[test]
+#[serial(callback)]
+fn callback_backend_filters_and_switches() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    set_callback(Box::new(move |level, target, message| {
+        if target == TARGET {
+            sink.lock().unwrap().push(format!("{level}: {message}"));
+        }
+    }));
+
+    set_backend_level(Backend::Callback, LevelFilter::WARN);
+    assert_eq!(backend_level(Backend::Callback), LevelFilter::WARN);
+    info!(target: TARGET, "Filtered out");
+    warn!(target: TARGET, "Warning");
+
+    set_backends(&[Backend::Tracing]);
+    assert_eq!(backends(), [Backend::Tracing]);
+    error!(target: TARGET, "Callback disabled");
+
+    // Enabling again keeps the level filter
+    set_backends(&[Backend::Tracing, Backend::Callback]);
+    assert_eq!(backend_level(Backend::Callback), LevelFilter::WARN);
+    error!(target: TARGET, "Error");
+
+    set_backend_level(Backend::Callback, LevelFilter::OFF);
+    error!(target: TARGET, "Callback off");
+    logfusion::event!(target: TARGET, logfusion::Level::ERROR, "Event off");
+
+    set_backend_level(Backend::Callback, LevelFilter::TRACE);
+    info!(target: TARGET, "Info");
+    assert_eq!(backends(), Backend::ALL);
+    assert_eq!(
+        *received.lock().unwrap(),
+        ["warn: Warning", "error: Error", "info: Info"]
+    );
+}
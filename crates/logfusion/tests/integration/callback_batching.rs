@@ -0,0 +1,142 @@
+//! Tests for batched callback delivery
+//!
+//! Verifies that batched callbacks:
+//! - receive records in batches by size and by interval
+//! - apply the overflow policy when the queue is full
+//! - report delivery stats
+
+use logfusion::{
+    Batching, Overflow, Record, callback_stats, flush_callback, info, set_batch_callback,
+    set_callback,
+};
+use serial_test::serial;
+use std::sync::mpsc::{Receiver, channel};
+use std::sync::{Arc, Barrier};
+use std::time::Duration;
+
+const TARGET: &str = "callback_batching";
+
+/// Batched callback sending each batch's messages from this test's target
+fn batches(batching: Batching) -> Receiver<Vec<String>> {
+    let (sender, receiver) = channel();
+    set_batch_callback(
+        Box::new(move |records: &[Record]| {
+            let messages: Vec<_> = records
+                .iter()
+                .filter(|record| record.target == TARGET)
+                .map(|record| record.message.clone())
+                .collect();
+            if !messages.is_empty() {
+                let _ = sender.send(messages);
+            }
+        }),
+        batching,
+    );
+    receiver
+}
+
+#[test]
+#[serial(callback)]
+fn test_delivers_full_batches() {
+    let received = batches(
+        Batching::default()
+            .max_records(3)
+            .interval(Duration::from_secs(60)),
+    );
+
+    for n in 0..6 {
+        info!(target: TARGET, "Record {}", n);
+    }
+
+    let timeout = Duration::from_secs(5);
+    assert_eq!(
+        received.recv_timeout(timeout).unwrap(),
+        ["Record 0", "Record 1", "Record 2"]
+    );
+    assert_eq!(
+        received.recv_timeout(timeout).unwrap(),
+        ["Record 3", "Record 4", "Record 5"]
+    );
+    let stats = callback_stats();
+    assert_eq!(stats.batches, 2);
+    assert_eq!(stats.delivered, 6);
+    assert_eq!(stats.dropped, 0);
+}
+
+#[test]
+#[serial(callback)]
+fn test_delivers_after_interval() {
+    let received = batches(Batching::default().interval(Duration::from_millis(20)));
+
+    info!(target: TARGET, "Waiting for the interval");
+
+    let batch = received.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(batch, ["Waiting for the interval"]);
+}
+
+#[test]
+#[serial(callback)]
+fn test_overflow_policies() {
+    for (overflow, kept) in [
+        (Overflow::DropOldest, ["Record 2", "Record 3"]),
+        (Overflow::DropNew, ["Record 0", "Record 1"]),
+    ] {
+        // Hold the worker in the first batch so later records queue up
+        let barrier = Arc::new(Barrier::new(2));
+        let worker_barrier = Arc::clone(&barrier);
+        let (sender, received) = channel();
+        set_batch_callback(
+            Box::new(move |records: &[Record]| {
+                if records.iter().any(|record| record.message == "Blocker") {
+                    worker_barrier.wait();
+                    return;
+                }
+                let messages = records.iter().map(|record| record.message.clone());
+                let _ = sender.send(messages.collect::<Vec<_>>());
+            }),
+            Batching::default()
+                .max_records(1)
+                .capacity(2)
+                .overflow(overflow),
+        );
+
+        info!(target: TARGET, "Blocker");
+        while callback_stats().queued > 0 {
+            std::thread::yield_now();
+        }
+        for n in 0..4 {
+            info!(target: TARGET, "Record {}", n);
+        }
+        assert_eq!(callback_stats().dropped, 2);
+        barrier.wait();
+        flush_callback();
+
+        let delivered: Vec<_> = received.try_iter().flatten().collect();
+        assert_eq!(delivered, kept, "{overflow:?}");
+    }
+}
+
+#[test]
+#[serial(callback)]
+fn test_block_waits_for_room() {
+    let received = batches(
+        Batching::default()
+            .max_records(1)
+            .capacity(1)
+            .overflow(Overflow::Block),
+    );
+
+    for n in 0..20 {
+        info!(target: TARGET, "Record {}", n);
+    }
+    flush_callback();
+
+    let delivered: Vec<_> = received.try_iter().flatten().collect();
+    assert_eq!(delivered.len(), 20);
+    assert_eq!(callback_stats().dropped, 0);
+    assert_eq!(callback_stats().queued, 0);
+
+    // A direct callback replaces batching
+    set_callback(Box::new(|_, _, _| {}));
+    assert_eq!(callback_stats(), logfusion::CallbackStats::default());
+}
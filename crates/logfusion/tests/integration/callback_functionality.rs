@@ -5,9 +5,11 @@
 #![cfg(feature = "callback")]
 
 use logfusion::{error, info, set_callback};
+use serial_test::serial;
 use std::sync::{Arc, Mutex};
 
 #[test]
+#[serial(callback)]
 fn callback_receives_log_messages() {
     use std::sync::atomic::{AtomicBool, Ordering};
 
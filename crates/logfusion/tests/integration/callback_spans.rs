@@ -0,0 +1,121 @@
+//! Tests for span callbacks
+//!
+//! Verifies that span starts and ends cross the callback bridge with their IDs,
+//! parents, names, fields and timing.
+
+use logfusion::{CallbackLayer, SpanEvent, SubscriberExt, info_span, set_span_callback};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Owned copy of a span event
+#[derive(Debug, Clone, PartialEq)]
+enum Captured {
+    Start {
+        id: u64,
+        parent_id: Option<u64>,
+        name: String,
+        fields: Vec<(String, String)>,
+    },
+    End {
+        id: u64,
+        name: String,
+        elapsed: Duration,
+        fields: Vec<(String, String)>,
+    },
+}
+
+fn field(name: &str, value: &str) -> (String, String) {
+    (name.to_string(), value.to_string())
+}
+
+#[test]
+fn span_lifecycle_reaches_callback() {
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&captured);
+    set_span_callback(Box::new(move |event| {
+        let event = match event {
+            SpanEvent::Start {
+                id,
+                parent_id,
+                name,
+                fields,
+                ..
+            } => Captured::Start {
+                id,
+                parent_id,
+                name: name.to_string(),
+                fields: fields.to_vec(),
+            },
+            SpanEvent::End {
+                id,
+                name,
+                elapsed,
+                fields,
+            } => Captured::End {
+                id,
+                name: name.to_string(),
+                elapsed,
+                fields: fields.to_vec(),
+            },
+        };
+        // Only spans of this test, other tests may log through the global subscriber
+        let name = match &event {
+            Captured::Start { name, .. } | Captured::End { name, .. } => name,
+        };
+        if name.starts_with("bridge_") {
+            sink.lock().unwrap().push(event);
+        }
+    }));
+
+    let subscriber = logfusion::registry().with(CallbackLayer);
+    tracing::subscriber::with_default(subscriber, || {
+        let request = info_span!("bridge_request", user = "alice", attempt = 1);
+        let _request = request.enter();
+        let query = info_span!("bridge_query", table = tracing::field::Empty);
+        query.record("table", "orders");
+        std::thread::sleep(Duration::from_millis(10));
+        drop(query);
+    });
+
+    let events = captured.lock().unwrap().clone();
+    assert_eq!(events.len(), 4, "{events:?}");
+    let Captured::Start {
+        id: request_id,
+        parent_id: None,
+        ref name,
+        ref fields,
+    } = events[0]
+    else {
+        panic!("expected the request start, got {:?}", events[0]);
+    };
+    assert_eq!(name, "bridge_request");
+    assert_eq!(fields, &[field("user", "alice"), field("attempt", "1")]);
+
+    let Captured::Start {
+        id: query_id,
+        parent_id,
+        ref fields,
+        ..
+    } = events[1]
+    else {
+        panic!("expected the query start, got {:?}", events[1]);
+    };
+    assert_eq!(parent_id, Some(request_id));
+    assert!(fields.is_empty(), "{fields:?}");
+
+    let Captured::End {
+        id,
+        ref name,
+        elapsed,
+        ref fields,
+    } = events[2]
+    else {
+        panic!("expected the query end, got {:?}", events[2]);
+    };
+    assert_eq!(id, query_id);
+    assert_eq!(name, "bridge_query");
+    assert!(elapsed >= Duration::from_millis(10));
+    assert_eq!(fields, &[field("table", "orders")]);
+
+    assert!(matches!(events[3], Captured::End { id, .. } if id == request_id));
+}
@@ -0,0 +1,144 @@
+//! Tests for error codes, categories and the error catalog
+//!
+//! Verifies that `define_errors!` accepts `code` and `category` attributes in both
+//! syntaxes, alongside level and target, and exports them in the catalog.
+
+use logfusion::{ErrorEntry, catalog_json, define_errors};
+
+#[test]
+fn logfusion_syntax_codes_and_categories() {
+    define_errors! {
+        StorageError {
+            DiskFull {} : "Disk is full" [level = warn, code = 2001, category = "io"],
+            Corrupted { path: String } : "Corrupted file {path}" [code = 2002, target = "app::storage"],
+            Locked {} : "Storage is locked" [category = "concurrency", level = info, target = "app::lock"],
+            Unknown {} : "Unknown storage error"
+        }
+    }
+
+    let full = StorageError::DiskFull;
+    assert_eq!(full.error_code(), Some(2001));
+    assert_eq!(full.category(), Some("io"));
+    assert_eq!(full.error_info().1, "warn");
+    full.log();
+
+    let corrupted = StorageError::Corrupted {
+        path: "data.db".to_string(),
+    };
+    assert_eq!(corrupted.error_code(), Some(2002));
+    assert_eq!(corrupted.category(), None);
+    assert_eq!(
+        corrupted.error_info(),
+        ("Corrupted", "error", "app::storage")
+    );
+    corrupted.log();
+
+    let locked = StorageError::Locked;
+    assert_eq!(locked.error_code(), None);
+    assert_eq!(locked.error_info(), ("Locked", "info", "app::lock"));
+    locked.log();
+
+    assert_eq!(StorageError::Unknown.error_code(), None);
+    assert_eq!(StorageError::Unknown.category(), None);
+
+    let catalog = StorageError::catalog();
+    assert_eq!(catalog.len(), 4);
+    let corrupted = catalog.iter().find(|entry| entry.name == "Corrupted");
+    assert_eq!(
+        corrupted,
+        Some(&ErrorEntry {
+            name: "Corrupted",
+            code: Some(2002),
+            category: None,
+            message: "Corrupted file {path}",
+            level: "error",
+            target: "app::storage",
+        })
+    );
+}
+
+#[test]
+fn unit_and_struct_only_codes() {
+    define_errors! {
+        UnitError {
+            Timeout {} : "Timed out" [code = 10, category = "network"]
+        }
+    }
+    define_errors! {
+        StructError {
+            Refused { host: String } : "Connection to {host} refused" [level = warn, code = 11]
+        }
+    }
+
+    assert_eq!(UnitError::Timeout.error_code(), Some(10));
+    assert_eq!(UnitError::catalog()[0].category, Some("network"));
+    let refused = StructError::Refused {
+        host: "db".to_string(),
+    };
+    assert_eq!(refused.error_code(), Some(11));
+    assert_eq!(StructError::catalog()[0].level, "warn");
+}
+
+#[test]
+fn thiserror_syntax_codes_and_categories() {
+    define_errors! {
+        pub enum ApiError {
+            #[error("Rate limited", level = warn, code = 429, category = "quota")]
+            RateLimited,
+
+            #[error("Request failed: {reason}", target = "app::api", code = 500)]
+            Failed { reason: String },
+
+            #[error("Not found")]
+            NotFound,
+        }
+    }
+
+    assert_eq!(ApiError::RateLimited.error_code(), Some(429));
+    assert_eq!(ApiError::RateLimited.category(), Some("quota"));
+    let failed = ApiError::Failed {
+        reason: "timeout".to_string(),
+    };
+    assert_eq!(failed.error_code(), Some(500));
+    assert_eq!(failed.category(), None);
+    assert_eq!(ApiError::NotFound.error_code(), None);
+
+    let catalog = ApiError::catalog();
+    assert_eq!(catalog[0].level, "warn");
+    assert_eq!(catalog[1].target, "app::api");
+    assert_eq!(catalog[1].message, "Request failed: {reason}");
+}
+
+#[test]
+fn catalog_exports_json() {
+    define_errors! {
+        ConfigError {
+            Missing { key: String } : "Missing key {key}" [level = warn, code = 3001, category = "config"],
+            Invalid {} : "Invalid configuration"
+        }
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_str(&catalog_json(&ConfigError::catalog())).unwrap();
+    let entry = |name: &str| {
+        let entries = json.as_array().unwrap().iter();
+        entries
+            .into_iter()
+            .find(|entry| entry["name"] == name)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(
+        entry("Missing"),
+        serde_json::json!({
+            "name": "Missing",
+            "code": 3001,
+            "category": "config",
+            "message": "Missing key {key}",
+            "level": "warn",
+            "target": module_path!(),
+        })
+    );
+    assert!(entry("Invalid").get("code").is_none());
+    assert!(entry("Invalid").get("category").is_none());
+}
@@ -0,0 +1,117 @@
+//! Tests for the rotating file appender
+//!
+//! Verifies that files configured with `logfusion::file`:
+//! - rotate by size and by period, naming rotated files by their period
+//! - gzip rotated files
+//! - keep only the most recent rotated files
+
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Names of the files in `dir`, sorted
+fn names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_rotates_by_size() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("logs/app.log");
+    let file = logfusion::file(&path).max_size(10).build().unwrap();
+
+    (&file).write_all(b"first\n").unwrap();
+    (&file).write_all(b"second\n").unwrap();
+    (&file).write_all(b"larger than the maximum\n").unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "larger than the maximum\n"
+    );
+    let names = names(&dir.path().join("logs"));
+    assert_eq!(names.len(), 3);
+    assert_eq!(names[0], "app.log");
+    let rotated: Vec<_> = names[1..]
+        .iter()
+        .map(|name| fs::read_to_string(dir.path().join("logs").join(name)).unwrap())
+        .collect();
+    assert!(rotated.contains(&"first\n".to_string()));
+    assert!(rotated.contains(&"second\n".to_string()));
+}
+
+#[test]
+fn test_rotates_previous_day_with_gzip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    fs::write(&path, "yesterday\n").unwrap();
+    let yesterday = SystemTime::UNIX_EPOCH + Duration::from_secs(20_000 * 86400 + 3600);
+    fs::File::options()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(yesterday)
+        .unwrap();
+
+    let file = logfusion::file(&path)
+        .rotate_daily()
+        .gzip()
+        .build()
+        .unwrap();
+    (&file).write_all(b"today\n").unwrap();
+
+    // Day 20000 after the epoch is 2024-10-04
+    assert_eq!(names(dir.path()), ["app.log", "app.log.2024-10-04.gz"]);
+    let mut rotated = String::new();
+    GzDecoder::new(fs::File::open(dir.path().join("app.log.2024-10-04.gz")).unwrap())
+        .read_to_string(&mut rotated)
+        .unwrap();
+    assert_eq!(rotated, "yesterday\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "today\n");
+}
+
+#[test]
+fn test_keeps_recent_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    let file = logfusion::file(&path).max_size(1).keep(2).build().unwrap();
+
+    for line in ["1\n", "2\n", "3\n", "4\n", "5\n"] {
+        (&file).write_all(line.as_bytes()).unwrap();
+        // Separate modification times so the newest files are known
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    let names = names(dir.path());
+    assert_eq!(names.len(), 3, "{names:?}");
+    let contents: Vec<_> = names
+        .iter()
+        .map(|name| fs::read_to_string(dir.path().join(name)).unwrap())
+        .collect();
+    assert!(contents.contains(&"3\n".to_string()));
+    assert!(contents.contains(&"4\n".to_string()));
+    assert!(contents.contains(&"5\n".to_string()));
+}
+
+#[test]
+fn test_writes_tracing_events() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = logfusion::file(dir.path().join("app.log")).build().unwrap();
+    let subscriber = logfusion::fmt()
+        .with_ansi(false)
+        .with_writer(file.clone())
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        logfusion::info!("Written to the file");
+    });
+
+    let contents = fs::read_to_string(file.path()).unwrap();
+    assert!(contents.contains("Written to the file"), "{contents}");
+}
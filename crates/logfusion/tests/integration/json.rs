@@ -0,0 +1,104 @@
+//! Tests for the JSON log layer
+//!
+//! Verifies that `JsonLayer` writes one JSON object per event with:
+//! - level, target, message and timestamp
+//! - event fields merged over span fields
+//! - span and trace IDs inside spans
+
+use logfusion::{JsonLayer, SubscriberExt, info, info_span, warn};
+use serde_json::Value;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Writer collecting the output in a shared buffer
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    fn lines(&self) -> Vec<Value> {
+        let output = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Buffer {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn capture(f: impl FnOnce()) -> Vec<Value> {
+    let buffer = Buffer::default();
+    let subscriber = logfusion::registry().with(JsonLayer::new().with_writer(buffer.clone()));
+    tracing::subscriber::with_default(subscriber, f);
+    buffer.lines()
+}
+
+#[test]
+fn test_json_event_fields() {
+    let lines = capture(|| {
+        info!(target: "shop::orders", order_id = 42, user = "alice", "Order {} placed", 42);
+    });
+
+    assert_eq!(lines.len(), 1);
+    let line = &lines[0];
+    assert_eq!(line["level"], "INFO");
+    assert_eq!(line["target"], "shop::orders");
+    assert_eq!(line["message"], "Order 42 placed");
+    assert_eq!(line["fields"]["order_id"], 42);
+    assert_eq!(line["fields"]["user"], "alice");
+    assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+    assert!(line.get("span_id").is_none());
+    assert!(line.get("trace_id").is_none());
+}
+
+#[test]
+fn test_json_span_context() {
+    let lines = capture(|| {
+        let request = info_span!("request", request_id = "r-1", user = "alice");
+        let _request = request.enter();
+        let query = info_span!("query", table = "orders");
+        let _query = query.enter();
+        warn!(user = "bob", "Slow query");
+    });
+
+    let line = &lines[0];
+    assert_eq!(line["level"], "WARN");
+    assert_eq!(line["message"], "Slow query");
+    assert_eq!(line["fields"]["request_id"], "r-1");
+    assert_eq!(line["fields"]["table"], "orders");
+    // Event fields override span fields
+    assert_eq!(line["fields"]["user"], "bob");
+
+    let trace_id = line["trace_id"].as_str().unwrap();
+    let span_id = line["span_id"].as_str().unwrap();
+    assert_ne!(trace_id, span_id);
+}
+
+#[test]
+fn test_json_propagated_trace_id() {
+    let lines = capture(|| {
+        let request = info_span!("request", trace_id = "4bf92f3577b34da6");
+        let _request = request.enter();
+        info!("Handled");
+    });
+
+    assert_eq!(lines[0]["trace_id"], "4bf92f3577b34da6");
+    assert!(lines[0]["fields"].get("trace_id").is_none());
+}
@@ -2,11 +2,21 @@ mod auto_initialization;
 mod define_errors_logfusion; // Temporarily disabled - focus on minimal_test first
 mod define_errors_thiserror;
 mod error_analytics_integration;
+mod error_catalog;
 mod error_info_method;
+mod json;
 mod logfusion_structured_logging;
 mod logging_macros;
 mod thiserror;
 mod tracing;
 
+#[cfg(feature = "callback")]
+mod backends;
+#[cfg(feature = "callback")]
+mod callback_batching;
 #[cfg(feature = "callback")]
 mod callback_functionality;
+#[cfg(feature = "callback")]
+mod callback_spans;
+#[cfg(feature = "file")]
+mod file_appender;
@@ -0,0 +1,4 @@
+fn main() {
+    #[cfg(feature = "napi-build")]
+    napi_build::setup();
+}
@@ -0,0 +1,120 @@
+//! Fixture crate for the MultiFFI conformance tests
+//!
+//! Each case of `../spec.json` calls an item of this crate, and the same spec runs
+//! against the Python extension, the Node.js addon and the WebAssembly module built
+//! from it. Add an item here for every binding feature that needs cross-target coverage.
+
+#[multiffi::module(name = "conformance_fixture")]
+mod bindings {
+    use multiffi::multiffi;
+    use std::fmt;
+
+    /// Error returned by the fixture's fallible functions
+    #[multiffi(error)]
+    #[derive(Debug)]
+    pub enum FixtureError {
+        InvalidPort(String),
+        Overflow(u32),
+    }
+
+    impl fmt::Display for FixtureError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidPort(value) => write!(f, "invalid port: {value}"),
+                Self::Overflow(count) => write!(f, "counter overflow at {count}"),
+            }
+        }
+    }
+
+    impl std::error::Error for FixtureError {}
+
+    #[multiffi]
+    pub enum Level {
+        Low,
+        High,
+    }
+
+    #[multiffi]
+    pub fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    #[multiffi]
+    pub fn greet(name: String, greeting: Option<String>) -> String {
+        format!("{}, {name}", greeting.as_deref().unwrap_or("Hello"))
+    }
+
+    #[multiffi]
+    pub fn split_keys(keys: String) -> Vec<String> {
+        keys.split(',').map(str::to_string).collect()
+    }
+
+    #[multiffi]
+    pub fn parse_port(value: String) -> Result<u16, FixtureError> {
+        value.parse().map_err(|_| FixtureError::InvalidPort(value))
+    }
+
+    #[multiffi]
+    pub fn level_of(value: u32) -> Level {
+        if value > 5 { Level::High } else { Level::Low }
+    }
+
+    #[multiffi(rename = "describe")]
+    pub fn describe_fixture() -> String {
+        "conformance fixture".to_string()
+    }
+
+    #[multiffi(skip(wasm))]
+    pub fn native_only() -> bool {
+        true
+    }
+
+    #[multiffi]
+    pub struct Counter {
+        pub count: u32,
+        pub max_count: u32,
+        #[multiffi(rename = "label")]
+        pub name: String,
+    }
+
+    #[multiffi]
+    impl Counter {
+        pub fn new(start: u32) -> Self {
+            Self {
+                count: start,
+                max_count: u32::MAX,
+                name: "counter".to_string(),
+            }
+        }
+
+        pub fn zero() -> Self {
+            Self::new(0)
+        }
+
+        pub fn limit() -> u32 {
+            u32::MAX
+        }
+
+        pub fn bump(&mut self) -> u32 {
+            self.count += 1;
+            self.count
+        }
+
+        pub fn try_add(&mut self, amount: u32) -> Result<u32, FixtureError> {
+            let count = self
+                .count
+                .checked_add(amount)
+                .filter(|count| *count <= self.max_count)
+                .ok_or(FixtureError::Overflow(self.count))?;
+            self.count = count;
+            Ok(count)
+        }
+    }
+
+    #[multiffi]
+    pub fn counter_from_keys(keys: String) -> Counter {
+        let mut counter = Counter::new(split_keys(keys).len() as u32);
+        counter.name = "keys".to_string();
+        counter
+    }
+}
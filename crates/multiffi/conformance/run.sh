@@ -0,0 +1,72 @@
+#!/usr/bin/env bash
+# Builds the conformance fixture for each target and runs spec.json against it.
+#
+# Usage: conformance/run.sh [python] [nodejs] [wasm]   (default: all three)
+#
+# Needs python3 for the Python target, node for Node.js and WebAssembly, and the
+# wasm32-unknown-unknown target plus a wasm-bindgen CLI matching the fixture's
+# wasm-bindgen version for WebAssembly.
+set -euo pipefail
+
+HERE="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+FIXTURE="$HERE/fixture"
+SPEC="$HERE/spec.json"
+TARGET_DIR="$FIXTURE/target"
+OUT="$TARGET_DIR/conformance"
+LIB="conformance_fixture"
+
+case "$(uname -s)" in
+  Darwin) DYLIB="lib$LIB.dylib" ;;
+  *) DYLIB="lib$LIB.so" ;;
+esac
+
+# Steps are chained with && because `set -e` doesn't apply inside `if ! run_<target>`
+build() {
+  cargo build --manifest-path "$FIXTURE/Cargo.toml" --target-dir "$TARGET_DIR" "$@"
+}
+
+run_python() {
+  build --features python &&
+    mkdir -p "$OUT/python" &&
+    cp "$TARGET_DIR/debug/$DYLIB" "$OUT/python/$LIB.so" &&
+    python3 "$HERE/runners/python.py" "$OUT/python" "$SPEC"
+}
+
+run_nodejs() {
+  build --features nodejs &&
+    mkdir -p "$OUT/nodejs" &&
+    cp "$TARGET_DIR/debug/$DYLIB" "$OUT/nodejs/$LIB.node" &&
+    node "$HERE/runners/node.mjs" nodejs "$OUT/nodejs/$LIB.node" "$SPEC"
+}
+
+run_wasm() {
+  build --features wasm --target wasm32-unknown-unknown &&
+    wasm-bindgen --target nodejs --out-dir "$OUT/wasm" \
+      "$TARGET_DIR/wasm32-unknown-unknown/debug/$LIB.wasm" &&
+    node "$HERE/runners/node.mjs" wasm "$OUT/wasm/$LIB.js" "$SPEC"
+}
+
+targets=("$@")
+if [ ${#targets[@]} -eq 0 ]; then
+  targets=(python nodejs wasm)
+fi
+
+failed=()
+for target in "${targets[@]}"; do
+  case "$target" in
+    python | nodejs | wasm) ;;
+    *)
+      echo "unknown target: $target (expected python, nodejs or wasm)" >&2
+      exit 2
+      ;;
+  esac
+  echo "== $target"
+  if ! "run_$target"; then
+    failed+=("$target")
+  fi
+done
+
+if [ ${#failed[@]} -gt 0 ]; then
+  echo "conformance failures: ${failed[*]}" >&2
+  exit 1
+fi
@@ -0,0 +1,104 @@
+// Runs the MultiFFI conformance spec against the Node.js addon or the WebAssembly module.
+//
+// Usage: node node.mjs <nodejs|wasm> <addon .node file or wasm-bindgen --target nodejs .js> <spec.json>
+
+import { readFileSync } from "node:fs";
+import { createRequire } from "node:module";
+import { resolve } from "node:path";
+import { isDeepStrictEqual } from "node:util";
+
+const [target, modulePath, specPath] = process.argv.slice(2);
+
+// JavaScript names are the camelCase Rust names, unless the spec gives a per-target name
+function nameFor(name) {
+  if (typeof name === "object") {
+    return name[target];
+  }
+  return name.replace(/_+([a-z0-9])/g, (_, letter) => letter.toUpperCase());
+}
+
+// Converts a returned value to JSON-comparable data: `undefined` is `null`
+function normalize(value) {
+  if (value === undefined) {
+    return null;
+  }
+  if (Array.isArray(value)) {
+    return value.map(normalize);
+  }
+  return value;
+}
+
+function check(step, produce) {
+  let result;
+  try {
+    result = produce();
+  } catch (error) {
+    if ("raises" in step && String(error.message).includes(step.raises)) {
+      return undefined;
+    }
+    throw new Error(`unexpected error: ${error}`);
+  }
+  if ("raises" in step) {
+    throw new Error(`expected an error containing ${JSON.stringify(step.raises)}, got ${result}`);
+  }
+  if ("returns" in step && !isDeepStrictEqual(normalize(result), step.returns)) {
+    throw new Error(
+      `expected ${JSON.stringify(step.returns)}, got ${JSON.stringify(normalize(result))}`,
+    );
+  }
+  return result;
+}
+
+function runSteps(instance, steps) {
+  for (const step of steps) {
+    if ("call" in step) {
+      const method = nameFor(step.call);
+      check(step, () => instance[method](...(step.args ?? [])));
+    } else if ("get" in step) {
+      check(step, () => instance[nameFor(step.get)]);
+    } else if ("set" in step) {
+      instance[nameFor(step.set)] = step.value;
+    }
+  }
+}
+
+function runCase(module, testCase) {
+  let produce;
+  if ("class" in testCase) {
+    const cls = module[nameFor(testCase.class)];
+    if ("static" in testCase) {
+      produce = () => cls[nameFor(testCase.static)](...(testCase.args ?? []));
+    } else {
+      produce = () => new cls(...(testCase.new ?? []));
+    }
+  } else {
+    const fn = module[nameFor(testCase.call)];
+    produce = () => fn(...(testCase.args ?? []));
+  }
+
+  const result = check(testCase, produce);
+  runSteps(result, testCase.steps ?? []);
+}
+
+const spec = JSON.parse(readFileSync(specPath, "utf8"));
+const module = createRequire(import.meta.url)(resolve(modulePath));
+
+let passed = 0;
+let failures = 0;
+for (const testCase of spec.cases) {
+  if ((testCase.skip ?? []).includes(target)) {
+    console.log(`skip ${testCase.name}`);
+    continue;
+  }
+  try {
+    runCase(module, testCase);
+    passed += 1;
+    console.log(`ok   ${testCase.name}`);
+  } catch (error) {
+    failures += 1;
+    console.log(`FAIL ${testCase.name}: ${error.message}`);
+  }
+}
+
+console.log(`${target}: ${passed} passed, ${failures} failed`);
+process.exit(failures ? 1 : 0);
@@ -0,0 +1,98 @@
+"""Runs the MultiFFI conformance spec against the Python extension.
+
+Usage: python3 python.py <directory containing the extension> <spec.json>
+"""
+
+import importlib
+import json
+import sys
+
+TARGET = "python"
+
+
+def name_for(name):
+    """Python names are the Rust names, unless the spec gives a per-target name."""
+    return name[TARGET] if isinstance(name, dict) else name
+
+
+def normalize(value):
+    """Converts a returned value to JSON-comparable data. Unit enums become their discriminant."""
+    if value is None or isinstance(value, (bool, int, float, str)):
+        return value
+    if isinstance(value, (list, tuple)):
+        return [normalize(item) for item in value]
+    if isinstance(value, dict):
+        return {key: normalize(item) for key, item in value.items()}
+    if hasattr(value, "__int__"):
+        return int(value)
+    return value
+
+
+def check(step, produce):
+    """Runs `produce` and compares its outcome with the step's `returns` or `raises`."""
+    try:
+        result = produce()
+    except Exception as error:  # noqa: BLE001 - every binding error must be reported
+        if "raises" in step and step["raises"] in str(error):
+            return None
+        raise AssertionError(f"unexpected error: {type(error).__name__}: {error}") from error
+    if "raises" in step:
+        raise AssertionError(f"expected an error containing {step['raises']!r}, got {result!r}")
+    if "returns" in step and normalize(result) != step["returns"]:
+        raise AssertionError(f"expected {step['returns']!r}, got {normalize(result)!r}")
+    return result
+
+
+def run_steps(instance, steps):
+    for step in steps:
+        if "call" in step:
+            method = getattr(instance, name_for(step["call"]))
+            check(step, lambda: method(*step.get("args", [])))
+        elif "get" in step:
+            check(step, lambda: getattr(instance, name_for(step["get"])))
+        elif "set" in step:
+            setattr(instance, name_for(step["set"]), step["value"])
+
+
+def run_case(module, case):
+    if "class" in case:
+        cls = getattr(module, name_for(case["class"]))
+        if "static" in case:
+            produce = lambda: getattr(cls, name_for(case["static"]))(*case.get("args", []))
+        else:
+            produce = lambda: cls(*case.get("new", []))
+    else:
+        function = getattr(module, name_for(case["call"]))
+        produce = lambda: function(*case.get("args", []))
+
+    result = check(case, produce)
+    run_steps(result, case.get("steps", []))
+
+
+def main():
+    directory, spec_path = sys.argv[1:3]
+    with open(spec_path, encoding="utf-8") as spec_file:
+        spec = json.load(spec_file)
+
+    sys.path.insert(0, directory)
+    module = importlib.import_module(spec["module"])
+
+    passed = failures = 0
+    for case in spec["cases"]:
+        if TARGET in case.get("skip", []):
+            print(f"skip {case['name']}")
+            continue
+        try:
+            run_case(module, case)
+            passed += 1
+            print(f"ok   {case['name']}")
+        except Exception as error:  # noqa: BLE001 - report and keep going
+            failures += 1
+            print(f"FAIL {case['name']}: {error}")
+
+    print(f"{TARGET}: {passed} passed, {failures} failed")
+    sys.exit(1 if failures else 0)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,237 @@
+//! Attribute arguments for `#[multiffi(...)]`.
+//!
+//! Arguments opt individual items out of specific targets or export them under a
+//! different name, without duplicating types per language:
+//!
+//! ```ignore
+//! #[multiffi(skip(wasm))]                       // not exported to WebAssembly
+//! #[multiffi(rename = "loadConfig")]            // same name in every target
+//! #[multiffi(rename(python = "load_config", nodejs = "loadConfig"))]
+//! #[multiffi(error)]                            // error type, see `error_support`
+//! #[multiffi(constructor)]                      // method creating instances of its class
+//! ```
+//!
+//! Inside a `#[multiffi]` impl block, methods accept the same arguments through their
+//! own `#[multiffi(...)]` attribute, which the outer macro consumes. Skips declared on
+//! the impl block apply to every method in it. Public fields of a `#[multiffi]` struct
+//! accept `skip(...)` and `rename` the same way.
+
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{Attribute, LitStr, Meta, Token, meta::ParseNestedMeta, parse::Parser};
+
+/// A binding target language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Target {
+    Python,
+    Nodejs,
+    Wasm,
+}
+
+impl Target {
+    const ALL: [Self; 3] = [Self::Python, Self::Nodejs, Self::Wasm];
+
+    /// Returns `true` when bindings for this target are generated in the current build.
+    pub(crate) const fn enabled(self) -> bool {
+        match self {
+            Self::Python => cfg!(feature = "python"),
+            Self::Nodejs => cfg!(feature = "nodejs"),
+            Self::Wasm => cfg!(feature = "wasm"),
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|target| meta.path.is_ident(target.name()))
+            .ok_or_else(|| {
+                meta.error("multiffi: unknown target, expected `python`, `nodejs`, or `wasm`")
+            })
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Python => "python",
+            Self::Nodejs => "nodejs",
+            Self::Wasm => "wasm",
+        }
+    }
+}
+
+/// Parsed `#[multiffi(...)]` arguments for one item.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ItemArgs {
+    skip: [bool; 3],
+    rename: [Option<String>; 3],
+    rename_all: Option<String>,
+    error: bool,
+    constructor: bool,
+    /// Target an `__expand_for_tests!` expansion is limited to
+    only: Option<Target>,
+}
+
+impl ItemArgs {
+    /// Parses the arguments passed to the `#[multiffi]` attribute macro.
+    pub(crate) fn parse(args: TokenStream2) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        syn::meta::parser(|meta| {
+            if meta.path.is_ident("constructor") {
+                return Err(meta.error("multiffi: `constructor` applies to methods"));
+            }
+            parsed.parse_meta(&meta)
+        })
+        .parse2(args)?;
+        Ok(parsed)
+    }
+
+    fn parse_meta(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("error") {
+            self.error = true;
+            Ok(())
+        } else if meta.path.is_ident("constructor") {
+            self.constructor = true;
+            Ok(())
+        } else if meta.path.is_ident("skip") {
+            meta.parse_nested_meta(|nested| {
+                self.skip[Target::from_meta(&nested)?.index()] = true;
+                Ok(())
+            })
+        } else if meta.path.is_ident("rename") {
+            if meta.input.peek(Token![=]) {
+                self.rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                meta.parse_nested_meta(|nested| {
+                    let target = Target::from_meta(&nested)?;
+                    self.rename[target.index()] = Some(nested.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                })
+            }
+        } else {
+            Err(meta.error(
+                "multiffi: unsupported argument, expected `skip(...)`, `rename`, `error`, or `constructor`",
+            ))
+        }
+    }
+
+    /// Removes `#[multiffi(...)]` attributes from a method or field and returns its arguments.
+    ///
+    /// Target skips declared on the enclosing impl block are inherited; renames are not,
+    /// since an impl-level rename names the exported class.
+    pub(crate) fn take_from_method(&self, attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut args = Self {
+            skip: self.skip,
+            ..Self::default()
+        };
+
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("multiffi") {
+                return true;
+            }
+            if let Meta::List(list) = &attr.meta {
+                let parsed =
+                    syn::meta::parser(|meta| args.parse_meta(&meta)).parse2(list.tokens.clone());
+                if let Err(error) = parsed {
+                    result = Err(error);
+                } else if args.error {
+                    result = Err(syn::Error::new_spanned(
+                        attr,
+                        "multiffi: `error` applies to error types, not methods or fields",
+                    ));
+                }
+            }
+            false
+        });
+
+        result.map(|()| args)
+    }
+
+    /// Removes the `#[multiffi(...)]` attribute from an item and returns its arguments.
+    ///
+    /// Used by `__expand_for_tests!`, whose input is the item with its attribute still
+    /// on it; an item without the attribute gets the default arguments.
+    pub(crate) fn take_from_item(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut args = Ok(Self::default());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("multiffi") {
+                return true;
+            }
+            if let Meta::List(list) = &attr.meta {
+                args = Self::parse(list.tokens.clone());
+            }
+            false
+        });
+        args
+    }
+
+    /// Returns these arguments with every target except `target` skipped.
+    ///
+    /// Fails when `target` isn't a target name or its feature isn't enabled.
+    pub(crate) fn only(mut self, target: &syn::Ident) -> syn::Result<Self> {
+        let target = Target::ALL
+            .into_iter()
+            .find(|candidate| target == candidate.name())
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    target,
+                    "multiffi: unknown target, expected `python`, `nodejs`, or `wasm`",
+                )
+            })?;
+        if !target.enabled() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "multiffi: expanding for `{0}` needs the `{0}` feature",
+                    target.name()
+                ),
+            ));
+        }
+        for other in Target::ALL {
+            self.skip[other.index()] |= other != target;
+        }
+        self.only = Some(target);
+        Ok(self)
+    }
+
+    /// Returns `true` when the item is exported to `target` in the current build.
+    pub(crate) fn exports(&self, target: Target) -> bool {
+        target.enabled() && !self.skip[target.index()]
+    }
+
+    /// Returns `true` when `target` generates any code for the item, even for a skipped
+    /// item, in the current build.
+    pub(crate) fn generates(&self, target: Target) -> bool {
+        target.enabled() && self.only.is_none_or(|only| only == target)
+    }
+
+    /// Returns `true` when both arguments skip the same targets, whatever the build.
+    pub(crate) fn skips_same_targets(&self, other: &Self) -> bool {
+        self.skip == other.skip
+    }
+
+    /// Returns `true` when the item is an error type (`#[multiffi(error)]`).
+    pub(crate) const fn is_error(&self) -> bool {
+        self.error
+    }
+
+    /// Returns `true` when the method is its class's constructor.
+    pub(crate) const fn is_constructor(&self) -> bool {
+        self.constructor
+    }
+
+    /// Marks the method as its class's constructor.
+    pub(crate) const fn mark_constructor(&mut self) {
+        self.constructor = true;
+    }
+
+    /// Returns the exported name for `target`, if the item was renamed.
+    pub(crate) fn name(&self, target: Target) -> Option<&str> {
+        self.rename[target.index()]
+            .as_deref()
+            .or(self.rename_all.as_deref())
+    }
+}
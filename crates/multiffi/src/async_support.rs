@@ -0,0 +1,185 @@
+//! Async function support for MultiFFI bindings.
+//!
+//! Neither PyO3 (without its experimental `async` feature) nor wasm-bindgen's
+//! attribute-append model can export an `async fn` as-is, so MultiFFI generates a
+//! synchronous wrapper per target that hands the future to the target's runtime:
+//!
+//! | Target | Generated code | Returns |
+//! |--------|----------------|---------|
+//! | Python | `pyo3_async_runtimes::tokio::future_into_py` | awaitable |
+//! | Node.js | original `async fn` with `#[napi]` (NAPI supports async natively) | `Promise` |
+//! | WebAssembly | `wasm_bindgen_futures::future_to_promise` | `Promise` |
+//!
+//! Wrappers are named `__multiffi_py_<name>` / `__multiffi_wasm_<name>` in Rust and
+//! exported under the original name (camelCase for WebAssembly methods), or the name
+//! given with `#[multiffi(rename = ...)]`.
+//!
+//! ## Requirements
+//!
+//! - The futures must be `'static`: arguments are taken by value, and methods take
+//!   `&self` (the receiver is cloned - `#[multiffi]` structs always derive `Clone`)
+//! - Crates using the `python` feature need `pyo3-async-runtimes` with its `tokio-runtime` feature
+//! - Crates using the `wasm` feature need `wasm-bindgen-futures` and `js-sys`
+//!
+//! Futures resolving to `Result<T, E>` reject with the converted error (see the
+//! `error_support` module).
+
+// Only the wrapper generators for enabled targets are used
+#![cfg_attr(
+    not(any(feature = "python", feature = "wasm")),
+    allow(dead_code, unused_imports)
+)]
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Attribute, FnArg, Pat, Signature};
+
+/// How the async function is invoked from its wrapper.
+#[derive(Clone, Copy)]
+pub(crate) enum AsyncCallKind {
+    /// Standalone function: `name(args)`
+    Free,
+    /// Associated function without receiver: `Self::name(args)`
+    Static,
+    /// Method taking `&self` or `self`: `this.name(args)` on a clone of the receiver
+    Method,
+}
+
+/// Classifies a signature for wrapper generation.
+///
+/// Returns an error for `&mut self` receivers, which cannot be moved into a `'static` future.
+pub(crate) fn call_kind(sig: &Signature, in_impl: bool) -> syn::Result<AsyncCallKind> {
+    match sig.receiver() {
+        Some(receiver) if receiver.mutability.is_some() && receiver.reference.is_some() => {
+            Err(syn::Error::new_spanned(
+                receiver,
+                "multiffi: async methods taking `&mut self` are not supported, use `&self` with interior mutability",
+            ))
+        }
+        Some(_) => Ok(AsyncCallKind::Method),
+        None if in_impl => Ok(AsyncCallKind::Static),
+        None => Ok(AsyncCallKind::Free),
+    }
+}
+
+/// Builds the receiver, extra parameters, prelude, and call expression for a wrapper.
+fn wrapper_parts(
+    sig: &Signature,
+    kind: AsyncCallKind,
+) -> syn::Result<(TokenStream2, Vec<TokenStream2>, TokenStream2, TokenStream2)> {
+    let ident = &sig.ident;
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+
+    for input in &sig.inputs {
+        if let FnArg::Typed(pat_type) = input {
+            let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+                return Err(syn::Error::new_spanned(
+                    &pat_type.pat,
+                    "multiffi: async function parameters must be simple identifiers",
+                ));
+            };
+            let name = &pat_ident.ident;
+            let ty = &pat_type.ty;
+            params.push(quote! { #name: #ty });
+            args.push(quote! { #name });
+        }
+    }
+
+    let (receiver, prelude, call) = match kind {
+        AsyncCallKind::Free => (quote! {}, quote! {}, quote! { #ident(#(#args),*) }),
+        AsyncCallKind::Static => (quote! {}, quote! {}, quote! { Self::#ident(#(#args),*) }),
+        AsyncCallKind::Method => (
+            quote! { &self, },
+            quote! { let this = ::std::clone::Clone::clone(self); },
+            quote! { this.#ident(#(#args),*) },
+        ),
+    };
+
+    Ok((receiver, params, prelude, call))
+}
+
+/// Generates a Python wrapper that returns an awaitable via `pyo3-async-runtimes`.
+///
+/// Free functions get `#[pyo3::pyfunction]`; methods rely on the surrounding `#[pymethods]` block.
+/// The doc comments among `attrs` become the wrapper's docstring.
+#[cfg(feature = "python")]
+pub(crate) fn python_wrapper(
+    sig: &Signature,
+    attrs: &[Attribute],
+    kind: AsyncCallKind,
+    py_name: &str,
+) -> syn::Result<TokenStream2> {
+    let (receiver, params, prelude, call) = wrapper_parts(sig, kind)?;
+    let docs = crate::doc_support::doc_attrs(attrs);
+    let wrapper = format_ident!("__multiffi_py_{}", sig.ident);
+    let pyfunction = matches!(kind, AsyncCallKind::Free).then(|| quote! { #[pyo3::pyfunction] });
+    let static_method = matches!(kind, AsyncCallKind::Static).then(|| quote! { #[staticmethod] });
+    let signature = crate::types::python_signature(sig, &[]);
+    let body = if crate::error_support::result_types(&sig.output).is_some() {
+        quote! { #call.await.map_err(pyo3::PyErr::from) }
+    } else {
+        quote! { Ok::<_, pyo3::PyErr>(#call.await) }
+    };
+
+    Ok(quote! {
+        #(#docs)*
+        #pyfunction
+        #static_method
+        #[pyo3(name = #py_name)]
+        #signature
+        fn #wrapper<'py>(
+            #receiver
+            py: pyo3::Python<'py>,
+            #(#params),*
+        ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::PyAny>> {
+            #prelude
+            pyo3_async_runtimes::tokio::future_into_py(py, async move { #body })
+        }
+    })
+}
+
+/// Generates a WebAssembly wrapper that returns a `Promise` via `wasm-bindgen-futures`.
+///
+/// The doc comments among `attrs` are copied to the wrapper.
+#[cfg(feature = "wasm")]
+pub(crate) fn wasm_wrapper(
+    sig: &Signature,
+    attrs: &[Attribute],
+    kind: AsyncCallKind,
+    js_name: &str,
+) -> syn::Result<TokenStream2> {
+    let (receiver, params, prelude, call) = wrapper_parts(sig, kind)?;
+    let docs = crate::doc_support::doc_attrs(attrs);
+    let wrapper = format_ident!("__multiffi_wasm_{}", sig.ident);
+    let body = match &sig.output {
+        syn::ReturnType::Default => quote! {
+            #call.await;
+            Ok(wasm_bindgen::JsValue::UNDEFINED)
+        },
+        syn::ReturnType::Type(..) if crate::error_support::result_types(&sig.output).is_some() => {
+            quote! {
+                #call.await
+                    .map(wasm_bindgen::JsValue::from)
+                    .map_err(wasm_bindgen::JsValue::from)
+            }
+        }
+        syn::ReturnType::Type(..) => quote! {
+            Ok(wasm_bindgen::JsValue::from(#call.await))
+        },
+    };
+
+    Ok(quote! {
+        #(#docs)*
+        #[wasm_bindgen::prelude::wasm_bindgen(js_name = #js_name)]
+        pub fn #wrapper(#receiver #(#params),*) -> js_sys::Promise {
+            #prelude
+            wasm_bindgen_futures::future_to_promise(async move { #body })
+        }
+    })
+}
+
+/// Returns `true` when the signature is `async`.
+pub(crate) fn is_async(sig: &Signature) -> bool {
+    sig.asyncness.is_some()
+}
@@ -0,0 +1,305 @@
+//! Callback parameters for MultiFFI bindings.
+//!
+//! Functions taking closures (`impl Fn(String)`, `impl FnMut(..)`, `impl FnOnce(..)` or
+//! `Box<dyn Fn(..)>`) accept a host-language function in every target:
+//!
+//! | Target | Parameter type | Calling the closure |
+//! |--------|----------------|---------------------|
+//! | Python | any callable (`pyo3::Py<PyAny>`) | acquires the GIL and calls it; exceptions are reported with `sys.unraisablehook` |
+//! | Node.js | a JavaScript function (`ThreadsafeFunction`) | queues a non-blocking call on the JavaScript thread |
+//! | WebAssembly | a JavaScript function (`js_sys::Function`) | calls it; exceptions are rethrown to the JavaScript caller |
+//!
+//! None of the frameworks accept closure parameters, so these functions are exported
+//! through `__multiffi_<target>_<name>` wrappers that turn the host function into a
+//! Rust closure and call the original function with it.
+//!
+//! ## Requirements
+//!
+//! - Callbacks return `()`: Node.js calls them asynchronously, so no target can hand a
+//!   return value back to Rust consistently
+//! - Callback arguments convert to the target's values (`IntoPyObject`, NAPI's
+//!   `ToNapiValue`, `JsValue::from`); Node.js needs owned arguments
+//! - Only free functions are supported, not methods, and they can't be `async`
+//! - The closures built for Python and Node.js are `Send + Sync + 'static`; the
+//!   WebAssembly closure holds a `js_sys::Function`, which is not `Send`
+
+// Only the wrappers of enabled targets are generated
+#![cfg_attr(
+    not(any(feature = "python", feature = "nodejs", feature = "wasm")),
+    allow(dead_code, unused_imports)
+)]
+
+use crate::args::Target;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, FnArg, GenericArgument, PathArguments, ReturnType, Signature, Type, TypeParamBound,
+    punctuated::Punctuated, token::Plus,
+};
+
+/// A closure parameter: the argument types of its `Fn(..)` bound.
+pub(crate) struct Callback {
+    pub(crate) inputs: Vec<Type>,
+    pub(crate) boxed: bool,
+}
+
+/// Returns the callback described by `ty`, if it is `impl Fn*(..)` or `Box<dyn Fn*(..)>`.
+///
+/// Returns an error for callbacks with a return value.
+pub(crate) fn callback_type(ty: &Type) -> syn::Result<Option<Callback>> {
+    let (bounds, boxed) = match ty {
+        Type::ImplTrait(impl_trait) => (&impl_trait.bounds, false),
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return Ok(None);
+            };
+            let PathArguments::AngleBracketed(generics) = &segment.arguments else {
+                return Ok(None);
+            };
+            match generics.args.first() {
+                Some(GenericArgument::Type(Type::TraitObject(object)))
+                    if segment.ident == "Box" =>
+                {
+                    (&object.bounds, true)
+                }
+                _ => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    let Some(arguments) = closure_bound(bounds) else {
+        return Ok(None);
+    };
+    if let ReturnType::Type(_, output) = &arguments.output
+        && !matches!(output.as_ref(), Type::Tuple(tuple) if tuple.elems.is_empty())
+    {
+        return Err(syn::Error::new_spanned(
+            output,
+            "multiffi: callbacks can't return values, Node.js calls them asynchronously",
+        ));
+    }
+
+    Ok(Some(Callback {
+        inputs: arguments.inputs.iter().cloned().collect(),
+        boxed,
+    }))
+}
+
+/// Returns the parenthesized arguments of the `Fn`, `FnMut` or `FnOnce` bound among `bounds`.
+fn closure_bound(
+    bounds: &Punctuated<TypeParamBound, Plus>,
+) -> Option<&syn::ParenthesizedGenericArguments> {
+    bounds.iter().find_map(|bound| {
+        let TypeParamBound::Trait(trait_bound) = bound else {
+            return None;
+        };
+        let segment = trait_bound.path.segments.last()?;
+        match &segment.arguments {
+            PathArguments::Parenthesized(arguments)
+                if ["Fn", "FnMut", "FnOnce"]
+                    .iter()
+                    .any(|name| segment.ident == name) =>
+            {
+                Some(arguments)
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Returns `true` when one of the parameters of `sig` is a callback.
+pub(crate) fn has_callbacks(sig: &Signature) -> syn::Result<bool> {
+    for input in &sig.inputs {
+        if let FnArg::Typed(pat_type) = input
+            && callback_type(&pat_type.ty)?.is_some()
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Generates the wrapper exporting a function with callback parameters to `target` as `name`.
+///
+/// The doc comments among `attrs` are copied to the wrapper.
+pub(crate) fn wrapper(
+    sig: &Signature,
+    attrs: &[Attribute],
+    target: Target,
+    name: &str,
+) -> syn::Result<TokenStream2> {
+    if sig.receiver().is_some() || sig.asyncness.is_some() {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "multiffi: callback parameters are only supported on synchronous free functions",
+        ));
+    }
+
+    let ident = &sig.ident;
+    let docs = crate::doc_support::doc_attrs(attrs);
+    let args = crate::forwarded_args(sig)?;
+    let mut params = Vec::new();
+    let mut conversions = Vec::new();
+    for (input, arg) in sig.inputs.iter().zip(&args) {
+        let FnArg::Typed(pat_type) = input else {
+            continue;
+        };
+        match callback_type(&pat_type.ty)? {
+            Some(callback) => {
+                let host_type = host_type(&callback, target);
+                params.push(quote! { #arg: #host_type });
+                let closure = closure(arg, &callback, target);
+                let closure = if callback.boxed {
+                    quote! { ::std::boxed::Box::new(#closure) }
+                } else {
+                    closure
+                };
+                conversions.push(quote! { let #arg = #closure; });
+            }
+            None => {
+                let ty = &pat_type.ty;
+                params.push(quote! { #arg: #ty });
+            }
+        }
+    }
+
+    let call = quote! { #ident(#(#args),*) };
+    let (wrapper, attributes, output, body) = match target {
+        Target::Python => {
+            let signature = crate::types::python_signature(sig, attrs);
+            (
+                format_ident!("__multiffi_py_{}", ident),
+                quote! {
+                    #[pyo3::pyfunction]
+                    #[pyo3(name = #name)]
+                    #signature
+                },
+                sig.output.clone(),
+                call,
+            )
+        }
+        Target::Nodejs => {
+            let (output, body) = match crate::error_support::result_types(&sig.output) {
+                Some((ok, _)) if crate::error_support::needs_napi_wrapper(sig) => (
+                    syn::parse_quote!(-> napi::Result<#ok>),
+                    quote! { #call.map_err(napi::Error::from) },
+                ),
+                _ => (sig.output.clone(), call),
+            };
+            (
+                format_ident!("__multiffi_napi_{}", ident),
+                quote! { #[napi::napi(js_name = #name)] },
+                output,
+                body,
+            )
+        }
+        Target::Wasm => (
+            format_ident!("__multiffi_wasm_{}", ident),
+            quote! { #[wasm_bindgen::prelude::wasm_bindgen(js_name = #name)] },
+            sig.output.clone(),
+            call,
+        ),
+    };
+
+    Ok(quote! {
+        #(#docs)*
+        #attributes
+        pub fn #wrapper(#(#params),*) #output {
+            #(#conversions)*
+            #body
+        }
+    })
+}
+
+/// Returns the type a wrapper takes in place of `callback`.
+fn host_type(callback: &Callback, target: Target) -> TokenStream2 {
+    match target {
+        Target::Python => quote! { pyo3::Py<pyo3::PyAny> },
+        Target::Nodejs => {
+            let value = napi_value_type(callback);
+            quote! {
+                napi::threadsafe_function::ThreadsafeFunction<
+                    #value,
+                    napi::bindgen_prelude::Unknown<'static>,
+                    #value,
+                    napi::Status,
+                    false,
+                >
+            }
+        }
+        Target::Wasm => quote! { js_sys::Function },
+    }
+}
+
+/// Returns the value queued on a NAPI threadsafe function: the single argument, `()`,
+/// or `FnArgs` spreading a tuple into several JavaScript arguments.
+fn napi_value_type(callback: &Callback) -> TokenStream2 {
+    match callback.inputs.as_slice() {
+        [] => quote! { () },
+        [single] => quote! { #single },
+        inputs => quote! { napi::bindgen_prelude::FnArgs<(#(#inputs),*)> },
+    }
+}
+
+/// Builds the Rust closure calling the host function `callback` for `target`.
+fn closure(callback_ident: &syn::Ident, callback: &Callback, target: Target) -> TokenStream2 {
+    let names: Vec<_> = (0..callback.inputs.len())
+        .map(|index| format_ident!("__multiffi_arg{}", index))
+        .collect();
+    let inputs = &callback.inputs;
+    let params = quote! { #(#names: #inputs),* };
+
+    match target {
+        Target::Python => {
+            let call = if names.is_empty() {
+                quote! { #callback_ident.call0(py) }
+            } else {
+                quote! { #callback_ident.call1(py, (#(#names,)*)) }
+            };
+            quote! {
+                move |#params| {
+                    pyo3::Python::with_gil(|py| {
+                        if let Err(error) = #call {
+                            error.write_unraisable(py, Some(#callback_ident.bind(py)));
+                        }
+                    });
+                }
+            }
+        }
+        Target::Nodejs => {
+            let value = match names.as_slice() {
+                [] => quote! { () },
+                [single] => quote! { #single },
+                names => quote! { (#(#names),*).into() },
+            };
+            quote! {
+                move |#params| {
+                    let _ = #callback_ident.call(
+                        #value,
+                        napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
+                    );
+                }
+            }
+        }
+        Target::Wasm => {
+            let arguments = if names.is_empty() {
+                quote! { js_sys::Array::new() }
+            } else {
+                quote! {
+                    [#(wasm_bindgen::JsValue::from(#names)),*]
+                        .into_iter()
+                        .collect::<js_sys::Array>()
+                }
+            };
+            quote! {
+                move |#params| {
+                    let arguments = #arguments;
+                    if let Err(error) = #callback_ident.apply(&wasm_bindgen::JsValue::NULL, &arguments) {
+                        wasm_bindgen::throw_val(error);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Documentation forwarding for MultiFFI bindings.
+//!
+//! Each framework reads Rust doc comments (`#[doc = "..."]` attributes) from the items
+//! it exports:
+//!
+//! | Target | Doc comments become | Signatures |
+//! |--------|---------------------|------------|
+//! | Python | `__doc__` of classes, methods, functions, and properties | `__text_signature__`, generated by PyO3 from the parameters and the `signature` MultiFFI adds |
+//! | Node.js | JSDoc comments in the generated `.d.ts` | TypeScript types |
+//! | WebAssembly | JSDoc comments in the generated `.d.ts` | TypeScript types |
+//!
+//! Items annotated in place keep their doc comments, but the wrappers MultiFFI
+//! generates (async wrappers, NAPI error wrappers, WebAssembly delegates and collection
+//! accessors) are new items: they copy the doc comments of the function or field they
+//! wrap, so IDEs show the same documentation whichever path an export takes. Python
+//! exception classes get the error type's doc comment as their docstring.
+
+// Only the wrappers of enabled targets use these helpers
+#![cfg_attr(
+    not(any(feature = "python", feature = "nodejs", feature = "wasm")),
+    allow(dead_code)
+)]
+
+use syn::{Attribute, Expr, ExprLit, Lit, Meta};
+
+/// Returns the doc comment attributes among `attrs`, to copy onto a generated wrapper.
+pub(crate) fn doc_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .collect()
+}
+
+/// Joins the literal doc comment lines of `attrs` into one string.
+///
+/// Returns `None` when there is no doc comment. Lines are trimmed by the single space
+/// that `///` comments start with, like rustdoc does.
+pub(crate) fn doc_string(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(line),
+                    ..
+                }) => Some(line.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .map_or_else(|| line.clone(), str::to_string)
+        })
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join("\n").trim().to_string())
+}
@@ -0,0 +1,230 @@
+//! `Result<T, E>` support for MultiFFI bindings.
+//!
+//! An `Err` returned across the boundary surfaces through each target's native error
+//! mechanism:
+//!
+//! | Target | `Err(error)` becomes | Generated code |
+//! |--------|----------------------|----------------|
+//! | Python | an instance of a generated exception class | `create_exception!` + `From<E> for PyErr` |
+//! | Node.js | a thrown (or rejected) `Error` | `From<E> for napi::Error` + wrapper returning `napi::Result` |
+//! | WebAssembly | a thrown `JsError` | `From<E> for JsValue` |
+//!
+//! The conversions are generated for types annotated with `#[multiffi(error)]`. The
+//! message is the error's `Display` output, prefixed with the variant name for enums
+//! (`"NotFound: config.toml not found"`), so `thiserror` errors keep both.
+//!
+//! PyO3 and wasm-bindgen accept `Result<T, E>` returns as long as `E` converts into
+//! their error type, so functions are exported unchanged. NAPI only accepts its own
+//! error type, so functions returning `Result<T, E>` with any other `E` are exported to
+//! Node.js through a `__multiffi_napi_<name>` wrapper that converts the error.
+//!
+//! The Python exception class lives in a generated `<snake_case_name>_exception`
+//! module and is registered by `#[multiffi::module]` like any other item.
+
+// Only the conversions for enabled targets are used
+#![cfg_attr(
+    not(any(feature = "python", feature = "nodejs", feature = "wasm")),
+    allow(dead_code, unused_imports)
+)]
+
+use crate::args::{ItemArgs, Target};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, GenericArgument, Ident, PathArguments, ReturnType, Signature, Type, Variant,
+    Visibility, punctuated::Punctuated, token::Comma,
+};
+
+/// Returns the `T` and `E` of a `Result<T, E>` return type.
+///
+/// Single-parameter aliases such as `PyResult<T>` or `napi::Result<T>` already use the
+/// target's error type and are not matched.
+pub(crate) fn result_types(output: &ReturnType) -> Option<(&Type, &Type)> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let Type::Path(type_path) = ty.as_ref() else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(generics) = &segment.arguments else {
+        return None;
+    };
+
+    let mut types = generics.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    match (types.next(), types.next(), types.next()) {
+        (Some(ok), Some(error), None) => Some((ok, error)),
+        _ => None,
+    }
+}
+
+/// Returns `true` when a function must be exported to Node.js through an error-converting
+/// wrapper, i.e. it returns `Result<T, E>` with an error type outside the `napi` crate.
+pub(crate) fn needs_napi_wrapper(sig: &Signature) -> bool {
+    result_types(&sig.output).is_some_and(|(_, error)| !is_napi_type(error))
+}
+
+fn is_napi_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path)
+        if type_path.path.segments.first().is_some_and(|segment| segment.ident == "napi"))
+}
+
+/// Generates a NAPI wrapper that exports `sig` as `js_name`, converting its error with
+/// `napi::Error::from`.
+///
+/// `in_impl` selects `Self::name(...)` over `name(...)` for functions without receiver,
+/// and the doc comments among `attrs` become the wrapper's JSDoc.
+pub(crate) fn napi_wrapper(
+    sig: &Signature,
+    attrs: &[Attribute],
+    in_impl: bool,
+    js_name: &str,
+) -> syn::Result<TokenStream2> {
+    let Some((ok, _)) = result_types(&sig.output) else {
+        return Err(syn::Error::new_spanned(
+            &sig.output,
+            "multiffi: expected a `Result<T, E>` return type",
+        ));
+    };
+
+    let ident = &sig.ident;
+    let wrapper = format_ident!("__multiffi_napi_{}", ident);
+    let docs = crate::doc_support::doc_attrs(attrs);
+    let inputs = &sig.inputs;
+    let asyncness = &sig.asyncness;
+    let args = crate::forwarded_args(sig)?;
+    let call = if sig.receiver().is_some() {
+        quote! { self.#ident(#(#args),*) }
+    } else if in_impl {
+        quote! { Self::#ident(#(#args),*) }
+    } else {
+        quote! { #ident(#(#args),*) }
+    };
+    let call = match asyncness {
+        Some(_) => quote! { #call.await },
+        None => call,
+    };
+
+    Ok(quote! {
+        #(#docs)*
+        #[napi::napi(js_name = #js_name)]
+        pub #asyncness fn #wrapper(#inputs) -> napi::Result<#ok> {
+            #call.map_err(napi::Error::from)
+        }
+    })
+}
+
+/// Generates the target error conversions for a `#[multiffi(error)]` type.
+///
+/// `variants` is `None` for structs, whose message is their `Display` output alone. The
+/// doc comment among `attrs` becomes the Python exception's docstring.
+#[allow(unused_variables)]
+pub(crate) fn error_conversions(
+    ident: &Ident,
+    attrs: &[Attribute],
+    vis: &Visibility,
+    variants: Option<&Punctuated<Variant, Comma>>,
+    args: &ItemArgs,
+) -> TokenStream2 {
+    let message = error_message(ident, variants);
+    #[allow(unused_mut)]
+    let mut conversions = TokenStream2::new();
+
+    #[cfg(feature = "python")]
+    if args.exports(Target::Python) {
+        let (module, exception) = python_exception(ident, args);
+        let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| "multiffi".into());
+        let crate_name = format_ident!("{}", crate_name);
+        let doc = crate::doc_support::doc_string(attrs).map(|doc| quote! { , #doc });
+
+        conversions.extend(quote! {
+            #vis mod #module {
+                pyo3::create_exception!(#crate_name, #exception, pyo3::exceptions::PyException #doc);
+            }
+
+            impl ::std::convert::From<#ident> for pyo3::PyErr {
+                fn from(error: #ident) -> Self {
+                    #module::#exception::new_err(#message)
+                }
+            }
+        });
+    }
+
+    #[cfg(feature = "nodejs")]
+    if args.exports(Target::Nodejs) {
+        conversions.extend(quote! {
+            impl ::std::convert::From<#ident> for napi::Error {
+                fn from(error: #ident) -> Self {
+                    napi::Error::from_reason(#message)
+                }
+            }
+        });
+    }
+
+    #[cfg(feature = "wasm")]
+    if args.exports(Target::Wasm) {
+        conversions.extend(quote! {
+            impl ::std::convert::From<#ident> for wasm_bindgen::JsValue {
+                fn from(error: #ident) -> Self {
+                    wasm_bindgen::JsError::new(&#message).into()
+                }
+            }
+        });
+    }
+
+    conversions
+}
+
+/// Returns the generated module and class names of the Python exception for `ident`.
+pub(crate) fn python_exception(ident: &Ident, args: &ItemArgs) -> (Ident, Ident) {
+    let module = format_ident!("{}_exception", convert_to_snake_case(&ident.to_string()));
+    let exception = args
+        .name(Target::Python)
+        .map_or_else(|| ident.clone(), |name| format_ident!("{}", name));
+    (module, exception)
+}
+
+/// Builds the expression formatting `error` into the message seen by the target language.
+pub(crate) fn error_message(
+    ident: &Ident,
+    variants: Option<&Punctuated<Variant, Comma>>,
+) -> TokenStream2 {
+    let Some(variants) = variants else {
+        return quote! { ::std::string::ToString::to_string(&error) };
+    };
+
+    let arms = variants.iter().map(|variant| {
+        let name = &variant.ident;
+        let label = name.to_string();
+        quote! { #ident::#name { .. } => #label }
+    });
+
+    quote! {{
+        let variant = match &error {
+            #(#arms),*
+        };
+        format!("{variant}: {error}")
+    }}
+}
+
+/// Converts a `CamelCase` type name to `snake_case`.
+fn convert_to_snake_case(camel_name: &str) -> String {
+    let mut result = String::new();
+    for (index, ch) in camel_name.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if index > 0 {
+                result.push('_');
+            }
+            result.push(ch.to_ascii_lowercase());
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
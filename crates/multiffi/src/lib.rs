@@ -51,6 +51,7 @@
 //!
 //! MultiFFI can be applied to:
 //! - **Structs** - Generates language-specific class/object bindings
+//! - **Enums** - Generates enum bindings (unit-only and data-carrying variants)
 //! - **Impl blocks** - Generates method bindings for the target languages
 //! - **Functions** - Generates standalone function bindings
 //!
@@ -74,20 +75,174 @@
 //! - `wasm` - Generates wasm-bindgen bindings for WebAssembly
 //! - `all` - Enables all target languages
 //!
+//! ## Async Functions
+//!
+//! `async fn` items and methods become awaitable in every target:
+//! - **Python**: wrapped with `pyo3_async_runtimes::tokio::future_into_py` (returns an awaitable)
+//! - **Node.js**: exported as-is, NAPI turns `async fn` into a `Promise`
+//! - **WebAssembly**: wrapped with `wasm_bindgen_futures::future_to_promise` (returns a `Promise`)
+//!
+//! Async functions must take owned arguments, and async methods must take `&self`
+//! (the receiver is cloned into the future). Your crate needs `pyo3-async-runtimes`
+//! (Python) or `wasm-bindgen-futures` and `js-sys` (WebAssembly) as dependencies.
+//!
+//! ## Attribute Arguments
+//!
+//! Items can opt out of individual targets or be exported under a different name:
+//!
+//! ```ignore
+//! #[multiffi(skip(wasm))]
+//! pub fn read_file(path: String) -> String { /* ... */ }
+//!
+//! #[multiffi(rename = "loadConfig")]
+//! pub fn load(path: String) -> Config { /* ... */ }
+//!
+//! #[multiffi]
+//! impl Config {
+//!     #[multiffi(skip(python), rename(nodejs = "toBuffer", wasm = "toBytes"))]
+//!     pub fn to_bytes(&self) -> Vec<u8> { /* ... */ }
+//! }
+//! ```
+//!
+//! - `skip(python, nodejs, wasm)` - Don't export the item to the listed targets
+//! - `rename = "name"` - Export under `name` in every target (replaces the camelCase conversion)
+//! - `rename(python = "...", nodejs = "...", wasm = "...")` - Per-target names
+//! - `constructor` - On methods: export the method as the class constructor (a static
+//!   `new` returning `Self` is one by default)
+//!
+//! On impl blocks, skips apply to every method and `rename` gives the class name the
+//! struct was renamed to (required by wasm-bindgen's `js_class`).
+//!
+//! Public struct fields accept `skip(...)` and `rename` too, to hide a field from a
+//! target or name its property there.
+//!
+//! ## Optional Values
+//!
+//! `Option<T>` parameters, return values, and struct fields map to `None` in Python and
+//! `null`/`undefined` in JavaScript. Trailing `Option<T>` parameters can be omitted in
+//! every target (MultiFFI generates the PyO3 signature defaulting them to `None`), and
+//! public struct fields are exposed as properties.
+//!
+//! ## Errors
+//!
+//! Functions returning `Result<T, E>` raise native errors in every target. Annotate the
+//! error type with `#[multiffi(error)]` to generate the conversions:
+//!
+//! ```ignore
+//! #[multiffi(error)]
+//! #[derive(Debug, thiserror::Error)]
+//! pub enum ConfigError {
+//!     #[error("{0} not found")]
+//!     NotFound(String),
+//! }
+//!
+//! #[multiffi]
+//! pub fn load_config(path: String) -> Result<Config, ConfigError> { /* ... */ }
+//! ```
+//!
+//! - **Python**: raises a generated `ConfigError` exception class (registered by
+//!   [`macro@module`], or add `config_error_exception::ConfigError` to your own module)
+//! - **Node.js**: throws (or rejects with) an `Error`
+//! - **WebAssembly**: throws a `JsError`
+//!
+//! The message keeps the variant name and the `Display` output: `"NotFound: config.toml not found"`.
+//!
+//! ## Modules
+//!
+//! `#[multiffi::module]` on an inline `mod` generates the Python `#[pymodule]` init
+//! function for the `#[multiffi]` items inside it, plus Node.js and WebAssembly entry
+//! points running an optional `init` function:
+//!
+//! ```ignore
+//! #[multiffi::module(name = "your_library")]
+//! mod bindings {
+//!     #[multiffi::multiffi]
+//!     pub struct Config { pub name: String }
+//!
+//!     #[multiffi::multiffi]
+//!     pub fn load(path: String) -> Config { /* ... */ }
+//! }
+//! ```
+//!
+//! [`export_module!`] does the same for items listed explicitly, wherever they live.
+//!
+//! ## Documentation
+//!
+//! Doc comments on items, methods, and fields become Python docstrings and JSDoc in the
+//! `.d.ts` files of NAPI and wasm-bindgen. Generated wrappers copy the doc comments of the
+//! function they wrap, and PyO3 derives `__text_signature__` from the parameters, including
+//! the `None` defaults of trailing `Option<T>` parameters.
+//!
+//! ## Callbacks
+//!
+//! Closure parameters (`impl Fn(..)`, `impl FnMut(..)`, `impl FnOnce(..)`, `Box<dyn Fn(..)>`)
+//! of free functions accept host-language functions:
+//!
+//! ```ignore
+//! #[multiffi]
+//! pub fn on_change(key: String, callback: impl Fn(String) + Send + 'static) { /* ... */ }
+//! ```
+//!
+//! - **Python**: any callable, called with the GIL held; exceptions go to `sys.unraisablehook`
+//! - **Node.js**: a function, called through a NAPI `ThreadsafeFunction`
+//! - **WebAssembly**: a `js_sys::Function`; exceptions are rethrown to the caller
+//!
+//! Callbacks return `()`, since Node.js calls them asynchronously.
+//!
+//! ## Traits
+//!
+//! `#[multiffi]` on a trait declares an interface that Python and JavaScript objects
+//! implement, so Rust code can call back into them:
+//!
+//! ```ignore
+//! #[multiffi]
+//! pub trait ConfigSource {
+//!     /// Loads the source as a JSON string.
+//!     fn load(&self, profile: String) -> Result<String, String>;
+//! }
+//!
+//! #[multiffi]
+//! pub fn register_source(source: PyConfigSource) { /* ... */ }
+//! ```
+//!
+//! - **Python**: a `ConfigSource` abstract base class (registered by [`macro@module`]),
+//!   and `PyConfigSource`, which implements the trait for any object with the methods
+//! - **WebAssembly**: a TypeScript `ConfigSource` interface and `JsConfigSource`, an
+//!   extern type implementing the trait
+//! - **Node.js**: not supported yet
+//!
+//! Only required methods belong to the interface. Exceptions raised by the host object
+//! become `Err(E::from(message))` for methods returning `Result<T, E>`.
+//!
 //! ## Safety and Limitations
 //!
 //! - All generated bindings follow the safety requirements of their respective FFI frameworks
 //! - Complex generic types may not be supported across all target languages
-//! - Async functions are not currently supported
+//! - Async functions are exported through per-target wrappers (see [Async Functions](#async-functions))
 //! - Some Rust-specific features (like advanced lifetime annotations) may not translate directly
 
+mod args;
+mod async_support;
+mod callback_support;
+mod doc_support;
+mod error_support;
+mod module_support;
+mod trait_support;
+mod types;
+
+use args::{ItemArgs, Target};
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{ImplItem, Item, ItemFn, ItemImpl, ItemStruct, parse_macro_input};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use std::collections::HashMap;
+use syn::{
+    Fields, FnArg, ImplItem, ImplItemFn, Item, ItemEnum, ItemFn, ItemImpl, ItemStruct, Pat,
+    Signature, parse_macro_input,
+};
 
 /// A procedural macro that generates FFI bindings for multiple target languages.
 ///
-/// This macro can be applied to structs, impl blocks, and functions to automatically generate
+/// This macro can be applied to structs, enums, impl blocks, and functions to automatically generate
 /// bindings for Python (PyO3), Node.js (NAPI), and WebAssembly (wasm-bindgen) based on enabled features.
 ///
 /// **Naming Conventions:** MultiFFI automatically converts `snake_case` function names to `camelCase`
@@ -105,14 +260,40 @@ use syn::{ImplItem, Item, ItemFn, ItemImpl, ItemStruct, parse_macro_input};
 /// }
 /// ```
 ///
+/// ### On Enums
+/// Unit-only enums become native enums in every target language. Enums with
+/// data-carrying variants are exposed as tagged unions (`{ "type": "Variant", ... }`):
+/// ```ignore
+/// #[multiffi]
+/// pub enum LogLevel {
+///     Debug,
+///     Info,
+///     Error,
+/// }
+///
+/// #[multiffi]
+/// pub enum Source {
+///     File { path: String },
+///     Env { prefix: String },
+///     Defaults,
+/// }
+/// ```
+///
 /// ### On Impl Blocks
-/// Generates method bindings for the struct:
+/// Generates method bindings for the struct. A static `new` returning `Self` becomes the
+/// class constructor (`#[new]`, `#[napi(constructor)]`, `#[wasm_bindgen(constructor)]`).
+/// Other methods without `self` become static methods (`#[staticmethod]` in Python, and
+/// `#[napi(factory)]` when they return `Self`):
 /// ```ignore
 /// #[multiffi]
 /// impl Person {
 ///     pub fn new(name: String, age: u32) -> Self {
 ///         Self { name, age }
 ///     }
+///
+///     pub fn anonymous() -> Self {
+///         Self::new("Anonymous".to_string(), 0)
+///     }
 ///     
 ///     pub fn greet(&self) -> String {
 ///         format!("Hello, I'm {} and I'm {} years old", self.name, self.age)
@@ -120,6 +301,16 @@ use syn::{ImplItem, Item, ItemFn, ItemImpl, ItemStruct, parse_macro_input};
 /// }
 /// ```
 ///
+/// ### On Traits
+/// Generates an interface implemented by host-language objects, and the `Py<Trait>` and
+/// `Js<Trait>` types dispatching the trait's required methods to them:
+/// ```ignore
+/// #[multiffi]
+/// pub trait Greeter {
+///     fn greet(&self, name: String) -> String;
+/// }
+/// ```
+///
 /// ### On Functions
 /// Generates standalone function bindings:
 /// ```ignore
@@ -145,16 +336,30 @@ use syn::{ImplItem, Item, ItemFn, ItemImpl, ItemStruct, parse_macro_input};
 ///
 /// ## Arguments
 ///
-/// Currently, this macro doesn't accept any arguments. Configuration is done through Cargo features.
+/// Targets are selected through Cargo features. Individual items (and methods inside a
+/// `#[multiffi]` impl block) accept optional arguments:
+/// - `skip(python, nodejs, wasm)` - Exclude the item from the listed targets
+/// - `rename = "name"` or `rename(python = "...", nodejs = "...", wasm = "...")` - Export
+///   under a different name
+/// - `error` - On error types: generate conversions into each target's native error
+///   (Python exception, JavaScript `Error`, `JsError`) instead of class bindings
+/// - `constructor` - On methods: make the method the class constructor instead of `new`
+///
+/// ```ignore
+/// #[multiffi(skip(python), rename = "loadConfig")]
+/// pub fn load_config(path: String) -> String {
+///     path
+/// }
+/// ```
 ///
 /// ## Errors
 ///
 /// This macro will produce a compilation error if applied to unsupported items:
-/// - Enums (not yet supported)
-/// - Traits (not supported)
 /// - Modules (not supported)
 /// - Other item types
 ///
+/// Unknown arguments or target names are also reported as compilation errors.
+///
 /// ## Examples
 ///
 /// ### Basic Configuration Struct
@@ -193,22 +398,209 @@ use syn::{ImplItem, Item, ItemFn, ItemImpl, ItemStruct, parse_macro_input};
 /// }
 /// ```
 #[proc_macro_attribute]
-pub fn multiffi(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn multiffi(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match ItemArgs::parse(args.into()) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
+    };
     let input_item = parse_macro_input!(input as Item);
+    expand_item(input_item, &args)
+}
 
+/// Generates the bindings of a `#[multiffi]` item for every enabled target it exports to.
+fn expand_item(input_item: Item, args: &ItemArgs) -> TokenStream {
     match input_item {
-        Item::Struct(item_struct) => generate_struct_bindings(item_struct),
-        Item::Impl(item_impl) => generate_impl_bindings(item_impl),
-        Item::Fn(item_fn) => generate_fn_bindings(item_fn),
+        Item::Struct(item_struct) if args.is_error() => {
+            let conversions = error_support::error_conversions(
+                &item_struct.ident,
+                &item_struct.attrs,
+                &item_struct.vis,
+                None,
+                args,
+            );
+            let registration = error_registration(&item_struct.ident, &item_struct.vis, args);
+            quote! { #item_struct #conversions #registration }.into()
+        }
+        Item::Enum(item_enum) if args.is_error() => {
+            let conversions = error_support::error_conversions(
+                &item_enum.ident,
+                &item_enum.attrs,
+                &item_enum.vis,
+                Some(&item_enum.variants),
+                args,
+            );
+            let registration = error_registration(&item_enum.ident, &item_enum.vis, args);
+            quote! { #item_enum #conversions #registration }.into()
+        }
+        Item::Struct(item_struct) => generate_struct_bindings(item_struct, args),
+        Item::Enum(item_enum) => generate_enum_bindings(item_enum, args),
+        Item::Impl(item_impl) => generate_impl_bindings(item_impl, args),
+        Item::Fn(item_fn) => generate_fn_bindings(item_fn, args),
+        _ if args.is_error() => syn::Error::new_spanned(
+            &input_item,
+            "multiffi: `error` can only be applied to structs and enums",
+        )
+        .to_compile_error()
+        .into(),
+        Item::Trait(item_trait) => trait_support::trait_bindings(item_trait, args)
+            .unwrap_or_else(syn::Error::into_compile_error)
+            .into(),
         _ => syn::Error::new_spanned(
             &input_item,
-            "multiffi can only be applied to structs, impls, or functions",
+            "multiffi can only be applied to structs, enums, impls, traits, or functions",
         )
         .to_compile_error()
         .into(),
     }
 }
 
+/// Generates the Python module registration of a `#[multiffi(error)]` type's exception.
+fn error_registration(ident: &syn::Ident, vis: &syn::Visibility, args: &ItemArgs) -> TokenStream2 {
+    let (module, exception) = error_support::python_exception(ident, args);
+    let name = exception.to_string();
+    module_support::python_registration(
+        ident,
+        vis,
+        args,
+        quote! { module.add(#name, module.py().get_type::<#module::#exception>()) },
+    )
+}
+
+/// Generates the Python module init, Node.js, and WebAssembly entry points for the
+/// `#[multiffi]` items of an inline module.
+///
+/// Items annotated with `#[multiffi]` inside the module (structs, enums, functions, and
+/// error types) are registered with the generated Python module. Node.js and
+/// WebAssembly register exports themselves, so they only get entry points running the
+/// optional `init` function.
+///
+/// ## Usage
+///
+/// ```ignore
+/// #[multiffi::module(name = "your_library", init = setup)]
+/// mod bindings {
+///     use multiffi::multiffi;
+///
+///     #[multiffi]
+///     pub struct Calculator { pub value: f64 }
+///
+///     #[multiffi]
+///     pub fn fibonacci(n: u32) -> u64 { /* ... */ }
+///
+///     fn setup() { /* runs once when the module loads */ }
+/// }
+/// ```
+///
+/// ## Arguments
+///
+/// - `name = "..."` - Python module name (defaults to the `mod`'s name), which must match
+///   the library name
+/// - `init = path` - `fn()` run when the module loads, in every target
+///
+/// ## Generated Entry Points
+///
+/// - **Python**: `#[pyo3::pymodule]` init function adding every item
+/// - **Node.js**: `#[napi_derive::module_init]` running `init`, when given
+/// - **WebAssembly**: `#[wasm_bindgen(start)]` function running `init`
+///
+/// Use [`export_module!`] when the items live in other files.
+#[proc_macro_attribute]
+pub fn module(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match module_support::ModuleArgs::parse(args.into(), false) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let item_mod = parse_macro_input!(input as syn::ItemMod);
+
+    match module_support::module_bindings(item_mod, args) {
+        Ok(item_mod) => quote! { #item_mod }.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Generates the same entry points as [`macro@module`] for an explicit list of
+/// `#[multiffi]` items, which may live anywhere in the crate.
+///
+/// ## Usage
+///
+/// ```ignore
+/// multiffi::export_module!(
+///     name = "your_library",
+///     init = setup,
+///     items(Calculator, math::fibonacci, ConfigError),
+/// );
+/// ```
+///
+/// `name` is required. Items are paths to `#[multiffi]` structs, enums, functions, or
+/// error types; impl blocks are exported with their struct.
+#[proc_macro]
+pub fn export_module(input: TokenStream) -> TokenStream {
+    module_support::ModuleArgs::parse(input.into(), true)
+        .and_then(module_support::export_module)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+/// Expands a `#[multiffi]` item for one target and returns the generated code as a
+/// string literal, for snapshot tests of the macro output.
+///
+/// The first argument is the target (`python`, `nodejs`, or `wasm`), whose feature must
+/// be enabled; the item may carry its own `#[multiffi(...)]` arguments:
+///
+/// ```ignore
+/// const PYTHON: &str = multiffi::__expand_for_tests!(python,
+///     #[multiffi(rename = "load")]
+///     pub fn load_config(path: String) -> String { path }
+/// );
+/// assert!(PYTHON.contains("pyo3 :: pyfunction"));
+/// ```
+///
+/// Other targets are skipped as if by `skip(...)` and generate nothing, so the string
+/// holds exactly what the target contributes, including its module registration, whichever
+/// other features are enabled. Whitespace is collapsed to single spaces. Not a stable API.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __expand_for_tests(input: TokenStream) -> TokenStream {
+    let (target, mut item) = match syn::parse::Parser::parse(
+        |input: syn::parse::ParseStream| {
+            let target: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![,]>()?;
+            Ok((target, input.parse::<Item>()?))
+        },
+        input,
+    ) {
+        Ok(parsed) => parsed,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let attrs = match &mut item {
+        Item::Struct(item) => &mut item.attrs,
+        Item::Enum(item) => &mut item.attrs,
+        Item::Fn(item) => &mut item.attrs,
+        Item::Impl(item) => &mut item.attrs,
+        Item::Trait(item) => &mut item.attrs,
+        other => {
+            return syn::Error::new_spanned(other, "multiffi: expected a `#[multiffi]` item")
+                .to_compile_error()
+                .into();
+        }
+    };
+    let expanded = ItemArgs::take_from_item(attrs)
+        .and_then(|args| args.only(&target).map(|args| expand_item(item, &args)));
+    match expanded {
+        Ok(expanded) => {
+            // The compiler wraps long token streams; keep snapshots on one line
+            let code = expanded
+                .to_string()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            quote! { #code }.into()
+        }
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
 // ============================================================================
 // Naming conversion utilities for cross-language consistency
 // ============================================================================
@@ -242,7 +634,6 @@ pub fn multiffi(_args: TokenStream, input: TokenStream) -> TokenStream {
 /// ## Returns
 ///
 /// A String containing the camelCase equivalent
-#[cfg(feature = "wasm")]
 fn convert_to_camel_case(snake_name: &str) -> String {
     let parts: Vec<&str> = snake_name.split('_').filter(|s| !s.is_empty()).collect();
 
@@ -285,38 +676,145 @@ fn convert_to_camel_case(snake_name: &str) -> String {
 /// - **Node.js**: `#[napi::napi(object)]` for NAPI compatibility  
 /// - **WebAssembly**: `#[wasm_bindgen::prelude::wasm_bindgen]` for wasm-bindgen compatibility
 ///
+/// Targets listed in `skip(...)` get no annotation, and `rename` sets the exported
+/// class name (`name` for PyO3, `js_name` for NAPI and wasm-bindgen).
+///
+/// Public fields are exposed as properties in every target: `#[pyo3(get, set)]` for
+/// Python, and `getter_with_clone` for WebAssembly so non-`Copy` fields like `String`
+/// or `Option<String>` work. `Option<T>` fields map to `None`/`null`/`undefined`.
+/// JavaScript properties use camelCase names in both Node.js and WebAssembly, and
+/// fields accept their own `#[multiffi(skip(...), rename = ...)]` attribute.
+///
+/// Collection fields such as `HashMap<String, ServerConfig>` or `Vec<ServerConfig>` map
+/// to `dict`/`list` in Python and plain objects/arrays in JavaScript. wasm-bindgen can't
+/// pass them, so they get generated accessors converting through `serde-wasm-bindgen`
+/// (see the `types` module), and WebAssembly structs derive serde's
+/// `Serialize`/`Deserialize` so they can be nested this way.
+///
 /// ## Parameters
 ///
 /// * `item_struct` - The parsed struct from the original Rust code
+/// * `args` - The `#[multiffi(...)]` arguments
 ///
 /// ## Returns
 ///
 /// A `TokenStream` containing the struct with all appropriate FFI annotations
 #[allow(unused_variables)]
-fn generate_struct_bindings(item_struct: ItemStruct) -> TokenStream {
-    // Create mutable binding only when features that require mutation are enabled
-    #[cfg(any(feature = "python", feature = "nodejs", feature = "wasm"))]
-    let mut item_struct = item_struct;
+fn generate_struct_bindings(mut item_struct: ItemStruct, args: &ItemArgs) -> TokenStream {
+    // Field attributes are removed in every build, they aren't attributes rustc knows
+    let field_args = match take_field_args(&mut item_struct, args) {
+        Ok(field_args) => field_args,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    #[allow(unused_mut)]
+    let mut collection_accessors = TokenStream2::new();
 
     // Add FFI annotations to the original struct based on enabled features
 
     #[cfg(feature = "python")]
-    {
-        item_struct.attrs.push(syn::parse_quote!(#[pyo3::pyclass]));
+    if args.exports(Target::Python) {
+        match args.name(Target::Python) {
+            Some(name) => item_struct
+                .attrs
+                .push(syn::parse_quote!(#[pyo3::pyclass(name = #name)])),
+            None => item_struct.attrs.push(syn::parse_quote!(#[pyo3::pyclass])),
+        }
+        for (field, field_args) in public_fields(&mut item_struct).zip(&field_args) {
+            if !field_args.exports(Target::Python) || types::has_pyo3_option(&field.attrs, "get") {
+                continue;
+            }
+            match field_args.name(Target::Python) {
+                Some(name) => field
+                    .attrs
+                    .push(syn::parse_quote!(#[pyo3(get, set, name = #name)])),
+                None => field.attrs.push(syn::parse_quote!(#[pyo3(get, set)])),
+            }
+        }
     }
 
     #[cfg(feature = "nodejs")]
-    {
-        item_struct
-            .attrs
-            .push(syn::parse_quote!(#[napi::napi(object)]));
+    if args.exports(Target::Nodejs) {
+        match args.name(Target::Nodejs) {
+            Some(name) => item_struct
+                .attrs
+                .push(syn::parse_quote!(#[napi::napi(object, js_name = #name)])),
+            None => item_struct
+                .attrs
+                .push(syn::parse_quote!(#[napi::napi(object)])),
+        }
+        // NAPI converts the other field names to camelCase itself
+        for (field, field_args) in public_fields(&mut item_struct).zip(&field_args) {
+            if !field_args.exports(Target::Nodejs) {
+                field.attrs.push(syn::parse_quote!(#[napi(skip)]));
+            } else if let Some(name) = field_args.name(Target::Nodejs) {
+                field
+                    .attrs
+                    .push(syn::parse_quote!(#[napi(js_name = #name)]));
+            }
+        }
     }
 
     #[cfg(feature = "wasm")]
-    {
-        item_struct
-            .attrs
-            .push(syn::parse_quote!(#[wasm_bindgen::prelude::wasm_bindgen]));
+    if args.exports(Target::Wasm) {
+        let mut options = Vec::new();
+        if let Some(name) = args.name(Target::Wasm) {
+            options.push(quote! { js_name = #name });
+        }
+        // wasm-bindgen only generates accessors for `Copy` fields without this
+        if public_fields(&mut item_struct).next().is_some() {
+            options.push(quote! { getter_with_clone });
+        }
+        item_struct.attrs.push(binding_attr(
+            quote! { wasm_bindgen::prelude::wasm_bindgen },
+            &options,
+        ));
+
+        // Collections wasm-bindgen can't pass get serde-converting accessors instead.
+        // Field attributes must use the bare path, wasm-bindgen leaves qualified ones behind
+        let mut accessors = Vec::new();
+        for (field, field_args) in public_fields(&mut item_struct).zip(&field_args) {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+            let original_name = ident.to_string();
+            let js_name = javascript_name(&original_name, field_args.name(Target::Wasm));
+            if !field_args.exports(Target::Wasm) {
+                field.attrs.push(syn::parse_quote!(#[wasm_bindgen(skip)]));
+            } else if types::needs_wasm_conversion(&field.ty) {
+                accessors.push(types::wasm_collection_accessors(field, &js_name));
+                field.attrs.push(syn::parse_quote!(#[wasm_bindgen(skip)]));
+            } else if js_name != original_name {
+                field
+                    .attrs
+                    .push(syn::parse_quote!(#[wasm_bindgen(js_name = #js_name)]));
+            }
+        }
+        if !accessors.is_empty() {
+            let ident = &item_struct.ident;
+            let (impl_generics, ty_generics, where_clause) = item_struct.generics.split_for_impl();
+            collection_accessors = quote! {
+                #[wasm_bindgen::prelude::wasm_bindgen]
+                impl #impl_generics #ident #ty_generics #where_clause {
+                    #(#accessors)*
+                }
+            };
+        }
+
+        // Lets other structs hold this one in collections converted with serde
+        let missing: Vec<TokenStream2> = ["Serialize", "Deserialize"]
+            .into_iter()
+            .filter(|name| !has_derive(&item_struct.attrs, name))
+            .map(|name| {
+                let name = format_ident!("{}", name);
+                quote! { serde::#name }
+            })
+            .collect();
+        if !missing.is_empty() {
+            item_struct
+                .attrs
+                .push(syn::parse_quote!(#[derive(#(#missing),*)]));
+        }
     }
 
     // Always add Clone derive for FFI compatibility
@@ -325,7 +823,246 @@ fn generate_struct_bindings(item_struct: ItemStruct) -> TokenStream {
         item_struct.attrs.push(syn::parse_quote!(#[derive(Clone)]));
     }
 
-    quote! { #item_struct }.into()
+    let ident = &item_struct.ident;
+    let registration = module_support::python_registration(
+        ident,
+        &item_struct.vis,
+        args,
+        quote! { module.add_class::<#ident>() },
+    );
+
+    quote! { #item_struct #collection_accessors #registration }.into()
+}
+
+/// Generates FFI bindings for enum definitions.
+///
+/// Unit-only enums (`enum LogLevel { Debug, Info }`) map to native enums in every
+/// target language. Enums with data-carrying variants are exposed as tagged unions,
+/// using a `type` field that holds the variant name.
+///
+/// ## Generated Bindings
+///
+/// For unit-only enums:
+/// - **Python**: `#[pyo3::pyclass(eq, eq_int)]` (comparable, integer-convertible enum)
+/// - **Node.js**: `#[napi::napi]` (numeric TypeScript enum)
+/// - **WebAssembly**: `#[wasm_bindgen::prelude::wasm_bindgen]` (numeric JS enum)
+///
+/// For data-carrying enums:
+/// - **Python**: `#[pyo3::pyclass]` (complex enum, one subclass per variant)
+/// - **Node.js**: `#[napi::napi(discriminant = "type")]` (discriminated union object)
+/// - **WebAssembly**: serde derives with `#[serde(tag = "type")]` for use with
+///   `serde-wasm-bindgen`, since wasm-bindgen only supports C-style enums. Enums with
+///   tuple variants use adjacent tagging (`{ "type": ..., "value": ... }`) instead.
+///
+/// `Clone` (plus `Copy` and `PartialEq` for unit-only enums) is derived when not
+/// already present.
+///
+/// Targets listed in `skip(...)` get no annotation, and `rename` sets the exported
+/// enum name. Data-carrying enums cross the WebAssembly boundary as plain objects, so
+/// their name is not exported there.
+///
+/// ## Parameters
+///
+/// * `item_enum` - The parsed enum from the original Rust code
+/// * `args` - The `#[multiffi(...)]` arguments
+///
+/// ## Returns
+///
+/// A `TokenStream` containing the enum with all appropriate FFI annotations
+#[allow(unused_variables)]
+fn generate_enum_bindings(item_enum: ItemEnum, args: &ItemArgs) -> TokenStream {
+    // Create mutable binding only when features that require mutation are enabled
+    #[cfg(any(feature = "python", feature = "nodejs", feature = "wasm"))]
+    let mut item_enum = item_enum;
+
+    let unit_only = is_unit_only_enum(&item_enum);
+
+    #[cfg(feature = "python")]
+    if args.exports(Target::Python) {
+        let mut options = Vec::new();
+        if unit_only {
+            options.extend([quote! { eq }, quote! { eq_int }]);
+        }
+        if let Some(name) = args.name(Target::Python) {
+            options.push(quote! { name = #name });
+        }
+        item_enum
+            .attrs
+            .push(binding_attr(quote! { pyo3::pyclass }, &options));
+    }
+
+    #[cfg(feature = "nodejs")]
+    if args.exports(Target::Nodejs) {
+        let mut options = Vec::new();
+        if !unit_only {
+            options.push(quote! { discriminant = "type" });
+        }
+        if let Some(name) = args.name(Target::Nodejs) {
+            options.push(quote! { js_name = #name });
+        }
+        item_enum
+            .attrs
+            .push(binding_attr(quote! { napi::napi }, &options));
+    }
+
+    #[cfg(feature = "wasm")]
+    if args.exports(Target::Wasm) {
+        if unit_only {
+            match args.name(Target::Wasm) {
+                Some(name) => item_enum.attrs.push(syn::parse_quote!(
+                    #[wasm_bindgen::prelude::wasm_bindgen(js_name = #name)]
+                )),
+                None => item_enum
+                    .attrs
+                    .push(syn::parse_quote!(#[wasm_bindgen::prelude::wasm_bindgen])),
+            }
+        } else {
+            item_enum
+                .attrs
+                .push(syn::parse_quote!(#[derive(serde::Serialize, serde::Deserialize)]));
+            if has_tuple_variants(&item_enum) {
+                item_enum
+                    .attrs
+                    .push(syn::parse_quote!(#[serde(tag = "type", content = "value")]));
+            } else {
+                item_enum
+                    .attrs
+                    .push(syn::parse_quote!(#[serde(tag = "type")]));
+            }
+        }
+    }
+
+    // Add the derives FFI enums rely on, skipping any the user already declared
+    #[cfg(any(feature = "python", feature = "nodejs", feature = "wasm"))]
+    {
+        let required: &[&str] = if unit_only {
+            &["Clone", "Copy", "PartialEq"]
+        } else {
+            &["Clone"]
+        };
+        let missing: Vec<syn::Ident> = required
+            .iter()
+            .filter(|name| !has_derive(&item_enum.attrs, name))
+            .map(|name| syn::Ident::new(name, proc_macro2::Span::call_site()))
+            .collect();
+        if !missing.is_empty() {
+            item_enum
+                .attrs
+                .push(syn::parse_quote!(#[derive(#(#missing),*)]));
+        }
+    }
+
+    let ident = &item_enum.ident;
+    let registration = module_support::python_registration(
+        ident,
+        &item_enum.vis,
+        args,
+        quote! { module.add_class::<#ident>() },
+    );
+
+    quote! { #item_enum #registration }.into()
+}
+
+/// Removes the `#[multiffi(...)]` attributes of the struct's fields and returns the
+/// arguments of each public field, in order.
+///
+/// Fields accept `skip(...)` and `rename`, like methods.
+fn take_field_args(item_struct: &mut ItemStruct, args: &ItemArgs) -> syn::Result<Vec<ItemArgs>> {
+    let mut field_args = Vec::new();
+    for field in item_struct.fields.iter_mut() {
+        let is_public = matches!(field.vis, syn::Visibility::Public(_));
+        let has_args = field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("multiffi"));
+        let parsed = args.take_from_method(&mut field.attrs)?;
+        if parsed.is_constructor() {
+            return Err(syn::Error::new_spanned(
+                &field.ident,
+                "multiffi: `constructor` applies to methods, not fields",
+            ));
+        }
+        if !is_public && has_args {
+            return Err(syn::Error::new_spanned(
+                &field.ident,
+                "multiffi: only public fields are exported",
+            ));
+        }
+        if is_public && field.ident.is_some() {
+            field_args.push(parsed);
+        }
+    }
+    Ok(field_args)
+}
+
+/// Iterates over the public named fields of a struct.
+#[cfg_attr(
+    not(any(feature = "python", feature = "nodejs", feature = "wasm")),
+    allow(dead_code)
+)]
+fn public_fields(item_struct: &mut ItemStruct) -> impl Iterator<Item = &mut syn::Field> {
+    let fields = match &mut item_struct.fields {
+        Fields::Named(fields) => Some(fields.named.iter_mut()),
+        _ => None,
+    };
+    fields
+        .into_iter()
+        .flatten()
+        .filter(|field| matches!(field.vis, syn::Visibility::Public(_)))
+}
+
+/// Builds `#[path]`, or `#[path(options, ...)]` when there are options.
+fn binding_attr(path: TokenStream2, options: &[TokenStream2]) -> syn::Attribute {
+    if options.is_empty() {
+        syn::parse_quote!(#[#path])
+    } else {
+        syn::parse_quote!(#[#path(#(#options),*)])
+    }
+}
+
+/// Returns `true` when every variant of the enum is a unit variant.
+fn is_unit_only_enum(item_enum: &ItemEnum) -> bool {
+    item_enum
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit))
+}
+
+/// Returns `true` when any variant of the enum has unnamed (tuple) fields.
+#[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+fn has_tuple_variants(item_enum: &ItemEnum) -> bool {
+    item_enum
+        .variants
+        .iter()
+        .any(|variant| matches!(variant.fields, Fields::Unnamed(_)))
+}
+
+/// Returns `true` when `attrs` contain a `#[derive(...)]` that includes `name`.
+///
+/// Matches both bare (`Clone`) and path-qualified (`std::clone::Clone`) derives.
+#[cfg_attr(
+    not(any(feature = "python", feature = "nodejs", feature = "wasm")),
+    allow(dead_code)
+)]
+fn has_derive(attrs: &[syn::Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == name)
+                {
+                    found = true;
+                }
+                Ok(())
+            });
+            found
+        })
 }
 
 /// Generates FFI bindings for impl block methods.
@@ -340,71 +1077,511 @@ fn generate_struct_bindings(item_struct: ItemStruct) -> TokenStream {
 /// - **Node.js**: `#[napi::napi]` on each method  
 /// - **WebAssembly**: `#[wasm_bindgen::prelude::wasm_bindgen]` on each method
 ///
+/// Methods may carry their own `#[multiffi(skip(...), rename = ...)]` attribute.
+/// `#[pymethods]` and wasm-bindgen export every method of their block, so methods
+/// skipped for Python move to a separate impl block, and when only some methods of a
+/// block are exported to WebAssembly they are exported through delegating wrappers.
+///
 /// ## Parameters
 ///
 /// * `item_impl` - The parsed impl block from the original Rust code
+/// * `args` - The `#[multiffi(...)]` arguments; `rename` sets wasm-bindgen's `js_class`
 ///
 /// ## Returns
 ///
 /// A `TokenStream` containing the impl block with FFI-annotated methods
-#[allow(unused_variables)]
-fn generate_impl_bindings(mut item_impl: ItemImpl) -> TokenStream {
-    let struct_type = &item_impl.self_ty;
+fn generate_impl_bindings(item_impl: ItemImpl, args: &ItemArgs) -> TokenStream {
+    match impl_bindings(item_impl, args) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
 
-    // Add impl-level annotations for certain targets
-    #[cfg(feature = "python")]
-    {
-        item_impl.attrs.push(syn::parse_quote!(#[pyo3::pymethods]));
+fn impl_bindings(mut item_impl: ItemImpl, args: &ItemArgs) -> syn::Result<TokenStream2> {
+    // Consume method-level `#[multiffi(...)]` attributes, keyed by method name
+    let mut method_args = HashMap::new();
+    for item in &mut item_impl.items {
+        if let ImplItem::Fn(method) = item {
+            if callback_support::has_callbacks(&method.sig)? {
+                return Err(syn::Error::new_spanned(
+                    &method.sig,
+                    "multiffi: callback parameters are only supported on free functions",
+                ));
+            }
+            let parsed = args.take_from_method(&mut method.attrs)?;
+            method_args.insert(method.sig.ident.to_string(), parsed);
+        }
     }
+    mark_constructor(&item_impl, &mut method_args)?;
 
-    #[cfg(feature = "nodejs")]
-    {
-        item_impl.attrs.push(syn::parse_quote!(#[napi::napi]));
+    // Async methods can't live in `#[pymethods]` or be exported directly by wasm-bindgen:
+    // move them to a companion impl block and export wrappers from this one instead
+    #[cfg(any(feature = "python", feature = "wasm"))]
+    let (async_impl, python_wrappers, wasm_wrappers) =
+        split_async_methods(&mut item_impl, args, &method_args)?;
+    #[cfg(not(any(feature = "python", feature = "wasm")))]
+    let (async_impl, python_wrappers, wasm_wrappers): (Option<ItemImpl>, _, _) =
+        (None, Vec::new(), Vec::new());
+
+    // Methods skipped for Python must not be inside the `#[pymethods]` block
+    let pymethods = args.exports(Target::Python);
+    let python_skipped = if pymethods {
+        let (skipped, exported): (Vec<ImplItem>, Vec<ImplItem>) =
+            std::mem::take(&mut item_impl.items)
+                .into_iter()
+                .partition(|item| {
+                    matches!(item, ImplItem::Fn(method)
+                        if !method_args[&method.sig.ident.to_string()].exports(Target::Python))
+                });
+        item_impl.items = exported;
+        (!skipped.is_empty()).then(|| ItemImpl {
+            items: skipped,
+            ..item_impl.clone()
+        })
+    } else {
+        None
+    };
+
+    let exported = annotate_impl(
+        item_impl,
+        args,
+        &method_args,
+        pymethods,
+        python_wrappers,
+        wasm_wrappers,
+    )?;
+    let python_skipped = python_skipped
+        .map(|block| annotate_impl(block, args, &method_args, false, Vec::new(), Vec::new()))
+        .transpose()?;
+
+    Ok(quote! { #exported #python_skipped #async_impl })
+}
+
+/// Finds the constructor of an impl block and marks it in `method_args`.
+///
+/// A method marked `#[multiffi(constructor)]` (or PyO3's `#[new]`) is the constructor.
+/// Otherwise, a static method named `new` returning the type, `Self`, or a `Result` of
+/// either is one. Constructors can't be async, and a class has at most one.
+fn mark_constructor(
+    item_impl: &ItemImpl,
+    method_args: &mut HashMap<String, ItemArgs>,
+) -> syn::Result<()> {
+    let methods: Vec<&ImplItemFn> = item_impl
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(method) => Some(method),
+            _ => None,
+        })
+        .collect();
+
+    let explicit: Vec<&ImplItemFn> = methods
+        .iter()
+        .copied()
+        .filter(|method| {
+            method_args[&method.sig.ident.to_string()].is_constructor()
+                || has_attr(&method.attrs, "new")
+        })
+        .collect();
+    let constructor = match explicit.as_slice() {
+        [] => methods.into_iter().find(|method| {
+            method.sig.ident == "new"
+                && method.sig.receiver().is_none()
+                && method.sig.asyncness.is_none()
+                && returns_self(&method.sig.output, &item_impl.self_ty)
+        }),
+        [method] => Some(*method),
+        [_, second, ..] => {
+            return Err(syn::Error::new_spanned(
+                &second.sig,
+                "multiffi: a class can only have one constructor",
+            ));
+        }
+    };
+
+    let Some(method) = constructor else {
+        return Ok(());
+    };
+    if method.sig.receiver().is_some() || method.sig.asyncness.is_some() {
+        return Err(syn::Error::new_spanned(
+            &method.sig,
+            "multiffi: constructors must be synchronous static methods",
+        ));
+    }
+    if let Some(args) = method_args.get_mut(&method.sig.ident.to_string()) {
+        args.mark_constructor();
     }
+    Ok(())
+}
 
-    #[cfg(feature = "wasm")]
-    {
-        item_impl
-            .attrs
-            .push(syn::parse_quote!(#[wasm_bindgen::prelude::wasm_bindgen]));
+/// Returns `true` when `output` is `Self`, `self_ty`, or a `Result` of either.
+fn returns_self(output: &syn::ReturnType, self_ty: &syn::Type) -> bool {
+    let is_self = |ty: &syn::Type| match (ty, self_ty) {
+        (syn::Type::Path(ty), syn::Type::Path(self_ty)) => {
+            ty.path.is_ident("Self")
+                || ty.path.segments.last().map(|segment| &segment.ident)
+                    == self_ty.path.segments.last().map(|segment| &segment.ident)
+        }
+        _ => false,
+    };
+    match output {
+        syn::ReturnType::Type(_, ty) => {
+            is_self(ty) || error_support::result_types(output).is_some_and(|(ok, _)| is_self(ok))
+        }
+        syn::ReturnType::Default => false,
     }
+}
 
-    // Add method-level annotations to each function
-    for item in &mut item_impl.items {
-        if let ImplItem::Fn(method) = item {
-            // Add Python method annotation
-            #[cfg(feature = "python")]
-            {
-                // pymethods impl blocks handle individual method binding automatically
-                // No per-method annotation needed for Python
+/// Returns `true` when `attrs` contain `#[name]`.
+fn has_attr(attrs: &[syn::Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+/// Adds target annotations to an impl block and its methods.
+///
+/// Wrapper methods for async functions are appended after annotation so they keep
+/// their own target attributes. Returns the block, followed by a separate
+/// wasm-bindgen block when only some of its methods are exported to WebAssembly.
+fn annotate_impl(
+    mut block: ItemImpl,
+    args: &ItemArgs,
+    method_args: &HashMap<String, ItemArgs>,
+    pymethods: bool,
+    python_wrappers: Vec<ImplItem>,
+    wasm_wrappers: Vec<ImplItem>,
+) -> syn::Result<TokenStream2> {
+    let args_of = |method: &ImplItemFn| method_args[&method.sig.ident.to_string()].clone();
+    let plain_block = block.clone();
+
+    if pymethods {
+        block.attrs.push(syn::parse_quote!(#[pyo3::pymethods]));
+        for method in impl_methods(&mut block) {
+            // PyO3 names constructors after the class, they can't be renamed
+            if args_of(method).is_constructor() {
+                if !has_attr(&method.attrs, "new") {
+                    method.attrs.push(syn::parse_quote!(#[new]));
+                }
+            } else {
+                // PyO3 needs associated functions without receiver marked explicitly
+                if method.sig.receiver().is_none()
+                    && !has_attr(&method.attrs, "staticmethod")
+                    && !has_attr(&method.attrs, "classmethod")
+                {
+                    method.attrs.push(syn::parse_quote!(#[staticmethod]));
+                }
+                if let Some(name) = args_of(method).name(Target::Python) {
+                    method.attrs.push(syn::parse_quote!(#[pyo3(name = #name)]));
+                }
             }
+            if let Some(signature) = types::python_signature(&method.sig, &method.attrs) {
+                method.attrs.push(signature);
+            }
+        }
+    }
 
-            // Add Node.js method annotation
-            #[cfg(feature = "nodejs")]
-            {
-                method.attrs.push(syn::parse_quote!(#[napi::napi]));
+    // Python wrappers are private, so neither NAPI nor wasm-bindgen exports them
+    let napi_block = if args.exports(Target::Nodejs) {
+        annotate_napi_methods(&mut block, method_args)?
+    } else {
+        None
+    };
+
+    if !args.exports(Target::Wasm) {
+        block.items.extend(python_wrappers);
+        return Ok(quote! { #block #napi_block });
+    }
+
+    let wasm_attr = binding_attr(
+        quote! { wasm_bindgen::prelude::wasm_bindgen },
+        &args
+            .name(Target::Wasm)
+            .map(|name| quote! { js_class = #name })
+            .into_iter()
+            .collect::<Vec<_>>(),
+    );
+
+    let all_exported = impl_methods(&mut block).all(|method| args_of(method).exports(Target::Wasm));
+    if all_exported {
+        block.attrs.push(wasm_attr);
+        for method in impl_methods(&mut block) {
+            method
+                .attrs
+                .push(syn::parse_quote!(#[wasm_bindgen::prelude::wasm_bindgen]));
+
+            if args_of(method).is_constructor() {
+                method
+                    .attrs
+                    .push(syn::parse_quote!(#[wasm_bindgen(constructor)]));
+                continue;
             }
 
-            // Add WASM method annotation
-            #[cfg(feature = "wasm")]
-            {
+            // Add js_name attribute for camelCase (or the renamed name) in JavaScript
+            let original_name = method.sig.ident.to_string();
+            let js_name = javascript_name(&original_name, args_of(method).name(Target::Wasm));
+            if original_name != js_name {
                 method
                     .attrs
-                    .push(syn::parse_quote!(#[wasm_bindgen::prelude::wasm_bindgen]));
-
-                // Add js_name attribute for camelCase in JavaScript
-                let original_name = &method.sig.ident;
-                let camel_name = convert_to_camel_case(&original_name.to_string());
-                if *original_name != camel_name {
-                    method
-                        .attrs
-                        .push(syn::parse_quote!(#[wasm_bindgen(js_name = #camel_name)]));
-                }
+                    .push(syn::parse_quote!(#[wasm_bindgen(js_name = #js_name)]));
+            }
+        }
+        block.items.extend(wasm_wrappers);
+        block.items.extend(python_wrappers);
+        return Ok(quote! { #block #napi_block });
+    }
+
+    // wasm-bindgen would export every method of this block, so delegate from a separate one
+    let mut delegates = Vec::new();
+    for item in &plain_block.items {
+        if let ImplItem::Fn(method) = item {
+            let method_args = args_of(method);
+            if method_args.exports(Target::Wasm) {
+                let js_name = javascript_name(
+                    &method.sig.ident.to_string(),
+                    method_args.name(Target::Wasm),
+                );
+                delegates.push(wasm_delegate(
+                    method,
+                    &js_name,
+                    method_args.is_constructor(),
+                )?);
+            }
+        }
+    }
+    delegates.extend(wasm_wrappers);
+
+    let wasm_block = ItemImpl {
+        attrs: vec![wasm_attr],
+        items: delegates,
+        ..plain_block
+    };
+
+    block.items.extend(python_wrappers);
+    Ok(quote! { #block #napi_block #wasm_block })
+}
+
+/// Adds `#[napi]` to an impl block and its methods exported to Node.js.
+///
+/// NAPI only accepts its own error type, so methods returning `Result<T, E>` with any
+/// other error are exported through error-converting wrappers instead (see the
+/// `error_support` module), returned in a separate `#[napi]` block.
+fn annotate_napi_methods(
+    block: &mut ItemImpl,
+    method_args: &HashMap<String, ItemArgs>,
+) -> syn::Result<Option<ItemImpl>> {
+    block.attrs.push(syn::parse_quote!(#[napi::napi]));
+
+    let self_ty = block.self_ty.clone();
+    let mut wrappers = Vec::new();
+    for method in impl_methods(block) {
+        let method_args = &method_args[&method.sig.ident.to_string()];
+        if !method_args.exports(Target::Nodejs) {
+            continue;
+        }
+        let factory = is_factory(method, &self_ty, method_args);
+
+        if error_support::needs_napi_wrapper(&method.sig) {
+            let js_name = javascript_name(
+                &method.sig.ident.to_string(),
+                method_args.name(Target::Nodejs),
+            );
+            let mut wrapper: ImplItemFn = syn::parse2(error_support::napi_wrapper(
+                &method.sig,
+                &method.attrs,
+                true,
+                &js_name,
+            )?)?;
+            // NAPI names constructors after the class, and factories need their own
+            // option: replace the wrapper's `js_name` binding
+            let binding: Option<syn::Attribute> = if method_args.is_constructor() {
+                Some(syn::parse_quote!(#[napi::napi(constructor)]))
+            } else if factory {
+                Some(syn::parse_quote!(#[napi::napi(factory, js_name = #js_name)]))
+            } else {
+                None
+            };
+            if let Some(binding) = binding {
+                wrapper.attrs.retain(|attr| {
+                    attr.path()
+                        .segments
+                        .first()
+                        .is_none_or(|segment| segment.ident != "napi")
+                });
+                wrapper.attrs.push(binding);
             }
+            wrappers.push(ImplItem::Fn(wrapper));
+        } else {
+            let options: Vec<TokenStream2> = if method_args.is_constructor() {
+                vec![quote! { constructor }]
+            } else {
+                factory
+                    .then(|| quote! { factory })
+                    .into_iter()
+                    .chain(
+                        method_args
+                            .name(Target::Nodejs)
+                            .map(|name| quote! { js_name = #name }),
+                    )
+                    .collect()
+            };
+            method
+                .attrs
+                .push(binding_attr(quote! { napi::napi }, &options));
+        }
+    }
+
+    Ok((!wrappers.is_empty()).then(|| ItemImpl {
+        attrs: vec![syn::parse_quote!(#[napi::napi])],
+        items: wrappers,
+        ..block.clone()
+    }))
+}
+
+/// Returns `true` when `method` is a NAPI factory: a synchronous static method other
+/// than the constructor that returns the type, `Self`, or a `Result` of either.
+fn is_factory(method: &ImplItemFn, self_ty: &syn::Type, args: &ItemArgs) -> bool {
+    !args.is_constructor()
+        && method.sig.receiver().is_none()
+        && method.sig.asyncness.is_none()
+        && returns_self(&method.sig.output, self_ty)
+}
+
+/// Iterates over the methods of an impl block.
+fn impl_methods(block: &mut ItemImpl) -> impl Iterator<Item = &mut ImplItemFn> {
+    block.items.iter_mut().filter_map(|item| match item {
+        ImplItem::Fn(method) => Some(method),
+        _ => None,
+    })
+}
+
+/// Returns the JavaScript name of a method exported through a wrapper: the rename, or camelCase.
+fn javascript_name(rust_name: &str, rename: Option<&str>) -> String {
+    rename.map_or_else(|| convert_to_camel_case(rust_name), str::to_string)
+}
+
+/// Generates a wasm-bindgen method that forwards to `method`, with its doc comments.
+///
+/// Used when a block mixes methods exported to WebAssembly with skipped ones. Delegates
+/// for a constructor are exported as the class's constructor instead of as `js_name`.
+fn wasm_delegate(method: &ImplItemFn, js_name: &str, constructor: bool) -> syn::Result<ImplItem> {
+    let sig = &method.sig;
+    let docs = doc_support::doc_attrs(&method.attrs);
+    let ident = &sig.ident;
+    let wrapper = format_ident!("__multiffi_wasm_{}", ident);
+    let inputs = &sig.inputs;
+    let output = &sig.output;
+    let args = forwarded_args(sig)?;
+
+    let call = if sig.receiver().is_some() {
+        quote! { self.#ident(#(#args),*) }
+    } else {
+        quote! { Self::#ident(#(#args),*) }
+    };
+
+    let binding = if constructor {
+        quote! { constructor }
+    } else {
+        quote! { js_name = #js_name }
+    };
+
+    syn::parse2(quote! {
+        #(#docs)*
+        #[wasm_bindgen::prelude::wasm_bindgen(#binding)]
+        pub fn #wrapper(#inputs) #output {
+            #call
+        }
+    })
+}
+
+/// Returns the parameter names of `sig`, for wrappers forwarding their arguments.
+fn forwarded_args(sig: &Signature) -> syn::Result<Vec<&syn::Ident>> {
+    let mut args = Vec::new();
+    for input in &sig.inputs {
+        if let FnArg::Typed(pat_type) = input {
+            let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+                return Err(syn::Error::new_spanned(
+                    &pat_type.pat,
+                    "multiffi: parameters of functions exported through a wrapper must be simple identifiers",
+                ));
+            };
+            args.push(&pat_ident.ident);
+        }
+    }
+    Ok(args)
+}
+
+/// Moves async methods out of an impl block and builds their target wrappers.
+///
+/// Returns the companion impl block holding the original async methods (annotated for
+/// Node.js, which supports async natively) and the Python and WebAssembly wrapper
+/// methods to add to the exported impl blocks.
+#[cfg(any(feature = "python", feature = "wasm"))]
+fn split_async_methods(
+    item_impl: &mut ItemImpl,
+    args: &ItemArgs,
+    method_args: &HashMap<String, ItemArgs>,
+) -> syn::Result<(Option<ItemImpl>, Vec<ImplItem>, Vec<ImplItem>)> {
+    let (async_items, items): (Vec<ImplItem>, Vec<ImplItem>) =
+        std::mem::take(&mut item_impl.items).into_iter().partition(
+            |item| matches!(item, ImplItem::Fn(method) if async_support::is_async(&method.sig)),
+        );
+    item_impl.items = items;
+
+    if async_items.is_empty() {
+        return Ok((None, Vec::new(), Vec::new()));
+    }
+
+    #[allow(unused_mut)]
+    let mut python_wrappers = Vec::new();
+    #[allow(unused_mut)]
+    let mut wasm_wrappers = Vec::new();
+    for item in &async_items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let kind = async_support::call_kind(&method.sig, true)?;
+        let method_args = &method_args[&method.sig.ident.to_string()];
+
+        #[cfg(feature = "python")]
+        if method_args.exports(Target::Python) {
+            let py_name = method_args
+                .name(Target::Python)
+                .map_or_else(|| method.sig.ident.to_string(), str::to_string);
+            python_wrappers.push(syn::parse2(async_support::python_wrapper(
+                &method.sig,
+                &method.attrs,
+                kind,
+                &py_name,
+            )?)?);
+        }
+
+        #[cfg(feature = "wasm")]
+        if method_args.exports(Target::Wasm) {
+            let js_name = javascript_name(
+                &method.sig.ident.to_string(),
+                method_args.name(Target::Wasm),
+            );
+            wasm_wrappers.push(syn::parse2(async_support::wasm_wrapper(
+                &method.sig,
+                &method.attrs,
+                kind,
+                &js_name,
+            )?)?);
+        }
+    }
+
+    let mut async_impl = ItemImpl {
+        items: async_items,
+        ..item_impl.clone()
+    };
+
+    if args.exports(Target::Nodejs) {
+        // The companion block is only exported to Node.js, so wrappers can live in it
+        if let Some(wrappers) = annotate_napi_methods(&mut async_impl, method_args)? {
+            async_impl.items.extend(wrappers.items);
         }
     }
 
-    quote! { #item_impl }.into()
+    Ok((Some(async_impl), python_wrappers, wasm_wrappers))
 }
 
 /// Generates FFI bindings for standalone functions.
@@ -419,38 +1596,145 @@ fn generate_impl_bindings(mut item_impl: ItemImpl) -> TokenStream {
 /// - **Node.js**: `#[napi::napi]` annotation  
 /// - **WebAssembly**: `#[wasm_bindgen::prelude::wasm_bindgen]` annotation
 ///
+/// For `async fn`, Python and WebAssembly get a generated wrapper function instead
+/// (see the `async_support` module), while Node.js exports the async function directly.
+/// Functions returning `Result<T, E>` are exported to Node.js through an
+/// error-converting wrapper (see the `error_support` module). Functions taking
+/// closures are exported through wrappers accepting host-language functions (see the
+/// `callback_support` module).
+///
+/// Targets listed in `skip(...)` get no annotation, and `rename` sets the exported
+/// function name.
+///
 /// ## Parameters
 ///
 /// * `item_fn` - The parsed function from the original Rust code
+/// * `args` - The `#[multiffi(...)]` arguments
 ///
 /// ## Returns
 ///
 /// A `TokenStream` containing the function with all appropriate FFI annotations
-fn generate_fn_bindings(item_fn: ItemFn) -> TokenStream {
+#[allow(unused_variables)]
+fn generate_fn_bindings(item_fn: ItemFn, args: &ItemArgs) -> TokenStream {
     // Create mutable binding only when features that require mutation are enabled
     #[cfg(any(feature = "python", feature = "nodejs", feature = "wasm"))]
     let mut item_fn = item_fn;
 
+    #[cfg(any(feature = "python", feature = "wasm"))]
+    let is_async = async_support::is_async(&item_fn.sig);
+    #[allow(unused_mut)]
+    let mut wrappers = proc_macro2::TokenStream::new();
+
+    // Closure parameters are exported through wrappers taking host functions
+    let has_callbacks = match callback_support::has_callbacks(&item_fn.sig) {
+        Ok(has_callbacks) => has_callbacks,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    #[cfg(any(feature = "python", feature = "wasm"))]
+    let kind = match async_support::call_kind(&item_fn.sig, false) {
+        Ok(kind) => kind,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
     // Add FFI annotations to the original function based on enabled features
 
     #[cfg(feature = "python")]
-    {
-        item_fn.attrs.push(syn::parse_quote!(#[pyo3::pyfunction]));
+    if args.exports(Target::Python) {
+        let py_name = args
+            .name(Target::Python)
+            .map_or_else(|| item_fn.sig.ident.to_string(), str::to_string);
+        if has_callbacks {
+            match callback_support::wrapper(&item_fn.sig, &item_fn.attrs, Target::Python, &py_name)
+            {
+                Ok(wrapper) => wrappers.extend(wrapper),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        } else if is_async {
+            match async_support::python_wrapper(&item_fn.sig, &item_fn.attrs, kind, &py_name) {
+                Ok(wrapper) => wrappers.extend(wrapper),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        } else {
+            item_fn.attrs.push(syn::parse_quote!(#[pyo3::pyfunction]));
+            if let Some(name) = args.name(Target::Python) {
+                item_fn.attrs.push(syn::parse_quote!(#[pyo3(name = #name)]));
+            }
+            if let Some(signature) = types::python_signature(&item_fn.sig, &item_fn.attrs) {
+                item_fn.attrs.push(signature);
+            }
+        }
     }
 
     #[cfg(feature = "nodejs")]
-    {
-        item_fn.attrs.push(syn::parse_quote!(#[napi::napi]));
+    if args.exports(Target::Nodejs) {
+        if has_callbacks {
+            let js_name =
+                javascript_name(&item_fn.sig.ident.to_string(), args.name(Target::Nodejs));
+            match callback_support::wrapper(&item_fn.sig, &item_fn.attrs, Target::Nodejs, &js_name)
+            {
+                Ok(wrapper) => wrappers.extend(wrapper),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        } else if error_support::needs_napi_wrapper(&item_fn.sig) {
+            let js_name =
+                javascript_name(&item_fn.sig.ident.to_string(), args.name(Target::Nodejs));
+            match error_support::napi_wrapper(&item_fn.sig, &item_fn.attrs, false, &js_name) {
+                Ok(wrapper) => wrappers.extend(wrapper),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        } else {
+            match args.name(Target::Nodejs) {
+                Some(name) => item_fn
+                    .attrs
+                    .push(syn::parse_quote!(#[napi::napi(js_name = #name)])),
+                None => item_fn.attrs.push(syn::parse_quote!(#[napi::napi])),
+            }
+        }
     }
 
     #[cfg(feature = "wasm")]
-    {
-        item_fn
-            .attrs
-            .push(syn::parse_quote!(#[wasm_bindgen::prelude::wasm_bindgen]));
+    if args.exports(Target::Wasm) {
+        let js_name = args
+            .name(Target::Wasm)
+            .map_or_else(|| item_fn.sig.ident.to_string(), str::to_string);
+        if has_callbacks {
+            match callback_support::wrapper(&item_fn.sig, &item_fn.attrs, Target::Wasm, &js_name) {
+                Ok(wrapper) => wrappers.extend(wrapper),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        } else if is_async {
+            match async_support::wasm_wrapper(&item_fn.sig, &item_fn.attrs, kind, &js_name) {
+                Ok(wrapper) => wrappers.extend(wrapper),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        } else {
+            match args.name(Target::Wasm) {
+                Some(name) => item_fn.attrs.push(syn::parse_quote!(
+                    #[wasm_bindgen::prelude::wasm_bindgen(js_name = #name)]
+                )),
+                None => item_fn
+                    .attrs
+                    .push(syn::parse_quote!(#[wasm_bindgen::prelude::wasm_bindgen])),
+            }
+        }
     }
 
-    quote! { #item_fn }.into()
+    // Async functions and functions taking callbacks are exported to Python through their wrapper
+    let ident = &item_fn.sig.ident;
+    let function = if has_callbacks || async_support::is_async(&item_fn.sig) {
+        format_ident!("__multiffi_py_{}", ident)
+    } else {
+        ident.clone()
+    };
+    let registration = module_support::python_registration(
+        ident,
+        &item_fn.vis,
+        args,
+        quote! { module.add_function(pyo3::wrap_pyfunction!(#function, module)?) },
+    );
+
+    quote! { #item_fn #wrappers #registration }.into()
 }
 
 // Tests are in a separate module to keep lib.rs clean
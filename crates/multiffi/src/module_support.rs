@@ -0,0 +1,215 @@
+//! Module-level registration for MultiFFI bindings.
+//!
+//! Each target loads the exported items differently:
+//!
+//! | Target | Items are registered by | Generated entry point |
+//! |--------|-------------------------|-----------------------|
+//! | Python | a `#[pymodule]` init function listing every class, function, and exception | the init function |
+//! | Node.js | NAPI itself, when the addon loads | `#[napi_derive::module_init]` running `init` |
+//! | WebAssembly | wasm-bindgen's exports | `#[wasm_bindgen(start)]` running `init` |
+//!
+//! `#[multiffi]` only sees one item at a time, so every annotated struct, enum, function,
+//! and error type also gets a hidden `__multiffi_py_register_<name>` function adding it to
+//! a Python module (doing nothing when the item skips Python). `#[multiffi::module]` on an
+//! inline `mod` collects the `#[multiffi]` items it contains; `export_module!` takes them
+//! as an explicit list for items spread across files. Both generate the init function
+//! calling each item's registration, plus the Node.js and WebAssembly entry points.
+
+// The entry points are only generated for enabled targets
+#![cfg_attr(
+    not(any(feature = "python", feature = "nodejs", feature = "wasm")),
+    allow(dead_code, unused_imports)
+)]
+
+use crate::args::{ItemArgs, Target};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Ident, Item, ItemMod, LitStr, Path, Visibility, parse::Parser};
+
+/// Parsed `#[multiffi::module(...)]` or `export_module!(...)` arguments.
+#[derive(Debug, Default)]
+pub(crate) struct ModuleArgs {
+    name: Option<String>,
+    init: Option<Path>,
+    items: Vec<Path>,
+}
+
+impl ModuleArgs {
+    /// Parses the arguments; `items(...)` is only accepted by `export_module!`.
+    pub(crate) fn parse(args: TokenStream2, allow_items: bool) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        syn::meta::parser(|meta| {
+            if meta.path.is_ident("name") {
+                parsed.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("init") {
+                parsed.init = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("items") && allow_items {
+                meta.parse_nested_meta(|item| {
+                    parsed.items.push(item.path.clone());
+                    Ok(())
+                })
+            } else if allow_items {
+                Err(meta.error(
+                    "multiffi: unsupported argument, expected `name`, `init`, or `items(...)`",
+                ))
+            } else {
+                Err(meta.error("multiffi: unsupported argument, expected `name` or `init`"))
+            }
+        })
+        .parse2(args)?;
+        Ok(parsed)
+    }
+}
+
+/// Expands `#[multiffi::module]` on an inline `mod`, appending the module entry points.
+///
+/// The module name defaults to the `mod`'s name.
+pub(crate) fn module_bindings(mut item_mod: ItemMod, mut args: ModuleArgs) -> syn::Result<ItemMod> {
+    let Some((_, items)) = &mut item_mod.content else {
+        return Err(syn::Error::new_spanned(
+            &item_mod,
+            "multiffi: `module` needs an inline module, use `export_module!` for items in other files",
+        ));
+    };
+
+    args.items = items
+        .iter()
+        .filter_map(exported_item)
+        .map(Path::from)
+        .collect();
+    let name = args
+        .name
+        .clone()
+        .unwrap_or_else(|| item_mod.ident.to_string());
+    let entry_points = entry_points(&name, &args);
+    items.push(Item::Verbatim(entry_points));
+    Ok(item_mod)
+}
+
+/// Expands `export_module!`, which requires an explicit `name`.
+pub(crate) fn export_module(args: ModuleArgs) -> syn::Result<TokenStream2> {
+    let Some(name) = args.name.clone() else {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "multiffi: `export_module!` needs a `name = \"...\"` argument",
+        ));
+    };
+    Ok(entry_points(&name, &args))
+}
+
+/// Returns the name of a struct, enum, trait, or function annotated with `#[multiffi]`.
+///
+/// Impl blocks are not collected, their methods are registered with their class.
+pub(crate) fn exported_item(item: &Item) -> Option<Ident> {
+    let (attrs, ident) = match item {
+        Item::Struct(item) => (&item.attrs, &item.ident),
+        Item::Enum(item) => (&item.attrs, &item.ident),
+        Item::Fn(item) => (&item.attrs, &item.sig.ident),
+        Item::Trait(item) => (&item.attrs, &item.ident),
+        _ => return None,
+    };
+    attrs
+        .iter()
+        .any(|attr| {
+            let segments = &attr.path().segments;
+            segments
+                .last()
+                .is_some_and(|segment| segment.ident == "multiffi")
+                && segments.len() <= 2
+        })
+        .then(|| ident.clone())
+}
+
+/// Generates the entry points of a module exporting `args.items`.
+#[allow(unused_variables, unused_mut)]
+fn entry_points(name: &str, args: &ModuleArgs) -> TokenStream2 {
+    let init = args.init.as_ref().map(|init| quote! { #init(); });
+    let mut entry_points = TokenStream2::new();
+
+    #[cfg(feature = "python")]
+    {
+        let registrations = args.items.iter().map(registration_path);
+        entry_points.extend(quote! {
+            #[pyo3::pymodule]
+            #[pyo3(name = #name)]
+            fn __multiffi_pymodule(
+                module: &pyo3::Bound<'_, pyo3::types::PyModule>,
+            ) -> pyo3::PyResult<()> {
+                #init
+                #(#registrations(module)?;)*
+                Ok(())
+            }
+        });
+    }
+
+    #[cfg(feature = "nodejs")]
+    if init.is_some() {
+        entry_points.extend(quote! {
+            #[napi_derive::module_init]
+            fn __multiffi_napi_init() {
+                #init
+            }
+        });
+    }
+
+    #[cfg(feature = "wasm")]
+    entry_points.extend(quote! {
+        #[wasm_bindgen::prelude::wasm_bindgen(start)]
+        pub fn __multiffi_start() {
+            #init
+        }
+    });
+
+    entry_points
+}
+
+/// Maps the path of an exported item to the path of its Python registration function.
+#[cfg_attr(not(feature = "python"), allow(dead_code))]
+pub(crate) fn registration_path(item: &Path) -> Path {
+    let mut path = item.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.ident = format_ident!("__multiffi_py_register_{}", last.ident);
+    }
+    path
+}
+
+/// Generates the hidden function adding `ident` to a Python module with `register`.
+///
+/// Items skipping Python get a function that does nothing, so modules listing them
+/// still compile. Nothing is generated when Python bindings are disabled, or when
+/// `__expand_for_tests!` expands another target.
+pub(crate) fn python_registration(
+    ident: &Ident,
+    vis: &Visibility,
+    args: &ItemArgs,
+    register: TokenStream2,
+) -> TokenStream2 {
+    if !args.generates(Target::Python) {
+        return TokenStream2::new();
+    }
+
+    let function = format_ident!("__multiffi_py_register_{}", ident);
+    let body = if args.exports(Target::Python) {
+        quote! {
+            use pyo3::types::PyModuleMethods;
+            #register
+        }
+    } else {
+        quote! {
+            let _ = module;
+            Ok(())
+        }
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        #vis fn #function(
+            module: &pyo3::Bound<'_, pyo3::types::PyModule>,
+        ) -> pyo3::PyResult<()> {
+            #body
+        }
+    }
+}
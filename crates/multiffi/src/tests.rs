@@ -14,8 +14,1260 @@ mod macro_tests {
     }
 }
 
+#[cfg(test)]
+mod enum_tests {
+    use crate::{has_derive, has_tuple_variants, is_unit_only_enum};
+    use syn::ItemEnum;
+
+    #[test]
+    fn test_unit_only_enum_detection() {
+        let unit: ItemEnum = syn::parse_quote! {
+            pub enum LogLevel { Debug, Info, Error }
+        };
+        let data: ItemEnum = syn::parse_quote! {
+            pub enum Source { File { path: String }, Defaults }
+        };
+
+        assert!(is_unit_only_enum(&unit));
+        assert!(!is_unit_only_enum(&data));
+    }
+
+    #[test]
+    fn test_tuple_variant_detection() {
+        let named: ItemEnum = syn::parse_quote! {
+            pub enum Source { File { path: String }, Defaults }
+        };
+        let tuple: ItemEnum = syn::parse_quote! {
+            pub enum Value { Int(i64), Text(String) }
+        };
+
+        assert!(!has_tuple_variants(&named));
+        assert!(has_tuple_variants(&tuple));
+    }
+
+    #[test]
+    fn test_existing_derives_are_detected() {
+        let item: ItemEnum = syn::parse_quote! {
+            #[derive(Debug, Clone, std::cmp::PartialEq)]
+            pub enum Environment { Development, Production }
+        };
+
+        assert!(has_derive(&item.attrs, "Clone"));
+        assert!(has_derive(&item.attrs, "PartialEq"));
+        assert!(!has_derive(&item.attrs, "Copy"));
+    }
+}
+
+#[cfg(test)]
+mod async_tests {
+    use crate::async_support::{AsyncCallKind, call_kind, is_async};
+    use syn::ImplItemFn;
+
+    #[test]
+    fn test_async_detection_and_call_kind() {
+        let method: ImplItemFn = syn::parse_quote! {
+            pub async fn load(&self, path: String) -> String { path }
+        };
+        let constructor: ImplItemFn = syn::parse_quote! {
+            pub async fn open(path: String) -> Self { todo!() }
+        };
+
+        assert!(is_async(&method.sig));
+        assert!(matches!(
+            call_kind(&method.sig, true),
+            Ok(AsyncCallKind::Method)
+        ));
+        assert!(matches!(
+            call_kind(&constructor.sig, true),
+            Ok(AsyncCallKind::Static)
+        ));
+        assert!(matches!(
+            call_kind(&constructor.sig, false),
+            Ok(AsyncCallKind::Free)
+        ));
+    }
+
+    #[test]
+    fn test_async_mut_self_is_rejected() {
+        let method: ImplItemFn = syn::parse_quote! {
+            pub async fn reload(&mut self) {}
+        };
+
+        assert!(call_kind(&method.sig, true).is_err());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_wrapper_uses_future_into_py() {
+        let method: ImplItemFn = syn::parse_quote! {
+            pub async fn load(&self, path: String) -> String { path }
+        };
+        let wrapper = crate::async_support::python_wrapper(
+            &method.sig,
+            &method.attrs,
+            AsyncCallKind::Method,
+            "load",
+        )
+        .expect("wrapper should generate");
+        let wrapper: ImplItemFn = syn::parse2(wrapper).expect("wrapper should parse");
+
+        assert_eq!(wrapper.sig.ident, "__multiffi_py_load");
+        assert!(wrapper.sig.asyncness.is_none());
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_wrapper_returns_promise() {
+        let function: syn::ItemFn = syn::parse_quote! {
+            pub async fn fetch_config(url: String) {}
+        };
+        let wrapper = crate::async_support::wasm_wrapper(
+            &function.sig,
+            &function.attrs,
+            AsyncCallKind::Free,
+            "fetchConfig",
+        )
+        .expect("wrapper should generate");
+        let wrapper: syn::ItemFn = syn::parse2(wrapper).expect("wrapper should parse");
+
+        assert_eq!(wrapper.sig.ident, "__multiffi_wasm_fetch_config");
+        assert!(
+            quote::quote!(#wrapper)
+                .to_string()
+                .contains("future_to_promise")
+        );
+    }
+}
+
+#[cfg(test)]
+mod args_tests {
+    use crate::args::{ItemArgs, Target};
+    use quote::quote;
+    use syn::ImplItemFn;
+
+    #[test]
+    fn test_parse_skip_and_rename() {
+        let args = ItemArgs::parse(quote! { skip(python, wasm), rename = "loadConfig" })
+            .expect("arguments should parse");
+
+        assert!(!args.exports(Target::Python));
+        assert!(!args.exports(Target::Wasm));
+        assert_eq!(args.exports(Target::Nodejs), cfg!(feature = "nodejs"));
+        assert_eq!(args.name(Target::Nodejs), Some("loadConfig"));
+    }
+
+    #[test]
+    fn test_per_target_rename_overrides_rename_all() {
+        let args = ItemArgs::parse(quote! { rename = "load", rename(python = "load_config") })
+            .expect("arguments should parse");
+
+        assert_eq!(args.name(Target::Python), Some("load_config"));
+        assert_eq!(args.name(Target::Wasm), Some("load"));
+        assert_eq!(ItemArgs::parse(quote! {}).unwrap().name(Target::Wasm), None);
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_rejected() {
+        assert!(ItemArgs::parse(quote! { skip(ruby) }).is_err());
+        assert!(ItemArgs::parse(quote! { rename = 42 }).is_err());
+        assert!(ItemArgs::parse(quote! { export_all }).is_err());
+    }
+
+    #[test]
+    fn test_method_attributes_are_consumed_and_inherit_skips() {
+        let impl_args = ItemArgs::parse(quote! { skip(nodejs), rename = "Config" }).unwrap();
+        let mut method: ImplItemFn = syn::parse_quote! {
+            #[doc = "Loads the file"]
+            #[multiffi(skip(python), rename(wasm = "loadFile"))]
+            pub fn load(&self) {}
+        };
+
+        let args = impl_args.take_from_method(&mut method.attrs).unwrap();
+
+        assert_eq!(method.attrs.len(), 1);
+        assert!(!args.exports(Target::Python));
+        assert!(!args.exports(Target::Nodejs));
+        assert_eq!(args.name(Target::Wasm), Some("loadFile"));
+        assert_eq!(args.name(Target::Python), None);
+    }
+}
+
+#[cfg(test)]
+mod impl_tests {
+    use crate::{args::ItemArgs, impl_bindings};
+    use syn::{File, Item, ItemImpl};
+
+    fn expand(item_impl: ItemImpl) -> Vec<ItemImpl> {
+        let tokens = impl_bindings(item_impl, &ItemArgs::default()).expect("impl should expand");
+        let file: File = syn::parse2(tokens).expect("expansion should parse");
+        file.items
+            .into_iter()
+            .map(|item| match item {
+                Item::Impl(item_impl) => item_impl,
+                other => panic!("unexpected item: {other:?}"),
+            })
+            .collect()
+    }
+
+    fn method_names(item_impl: &ItemImpl) -> Vec<String> {
+        item_impl
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::ImplItem::Fn(method) => Some(method.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_impl_without_arguments_stays_single_block() {
+        let blocks = expand(syn::parse_quote! {
+            impl Config {
+                #[multiffi(rename = "create")]
+                pub fn new() -> Self { Self }
+                pub fn name(&self) -> String { String::new() }
+            }
+        });
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(method_names(&blocks[0]), ["new", "name"]);
+        let expanded = quote::quote!(#(#blocks)*).to_string();
+        assert!(!expanded.contains("multiffi"));
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_skipped_methods_leave_pymethods_block() {
+        let blocks = expand(syn::parse_quote! {
+            impl Config {
+                pub fn name(&self) -> String { String::new() }
+                #[multiffi(skip(python))]
+                pub fn raw(&self) -> Vec<u8> { Vec::new() }
+            }
+        });
+
+        assert_eq!(method_names(&blocks[0]), ["name"]);
+        assert!(
+            blocks[0]
+                .attrs
+                .iter()
+                .any(|attr| quote::quote!(#attr).to_string().contains("pymethods"))
+        );
+        let skipped = blocks.last().unwrap();
+        assert_eq!(method_names(skipped), ["raw"]);
+        assert!(
+            !skipped
+                .attrs
+                .iter()
+                .any(|attr| quote::quote!(#attr).to_string().contains("pymethods"))
+        );
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_skipped_methods_are_not_delegated() {
+        let blocks = expand(syn::parse_quote! {
+            impl Config {
+                pub fn file_name(&self, index: u32) -> String { String::new() }
+                #[multiffi(skip(wasm))]
+                pub fn raw(&self) -> Vec<u8> { Vec::new() }
+            }
+        });
+
+        let wasm_block = blocks.last().unwrap();
+        assert_eq!(method_names(wasm_block), ["__multiffi_wasm_file_name"]);
+        assert!(
+            quote::quote!(#wasm_block)
+                .to_string()
+                .contains("js_name = \"fileName\"")
+        );
+    }
+}
+
+#[cfg(test)]
+mod constructor_tests {
+    use crate::{args::ItemArgs, mark_constructor};
+    use std::collections::HashMap;
+    use syn::{ImplItem, ItemImpl};
+
+    fn constructors(mut item_impl: ItemImpl) -> syn::Result<Vec<String>> {
+        let args = ItemArgs::default();
+        let mut method_args = HashMap::new();
+        for item in &mut item_impl.items {
+            if let ImplItem::Fn(method) = item {
+                let parsed = args.take_from_method(&mut method.attrs)?;
+                method_args.insert(method.sig.ident.to_string(), parsed);
+            }
+        }
+        mark_constructor(&item_impl, &mut method_args)?;
+        let mut names: Vec<String> = method_args
+            .into_iter()
+            .filter(|(_, args)| args.is_constructor())
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    #[test]
+    fn test_static_new_returning_the_type_is_detected() {
+        let found = constructors(syn::parse_quote! {
+            impl Config {
+                pub fn new(path: String) -> Result<Self, ConfigError> { todo!() }
+                pub fn name(&self) -> String { String::new() }
+            }
+        });
+        assert_eq!(found.unwrap(), ["new"]);
+
+        let found = constructors(syn::parse_quote! {
+            impl Config {
+                pub fn new() -> Config { Config }
+            }
+        });
+        assert_eq!(found.unwrap(), ["new"]);
+    }
+
+    #[test]
+    fn test_other_new_methods_are_not_constructors() {
+        let found = constructors(syn::parse_quote! {
+            impl Config {
+                pub fn new(&self) -> Self { Self }
+                pub async fn create() -> Self { Self }
+            }
+        });
+        assert!(found.unwrap().is_empty());
+
+        let found = constructors(syn::parse_quote! {
+            impl Config {
+                pub fn new() -> String { String::new() }
+            }
+        });
+        assert!(found.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_explicit_constructor_replaces_detection() {
+        let found = constructors(syn::parse_quote! {
+            impl Config {
+                pub fn new() -> Self { Self }
+                #[multiffi(constructor)]
+                pub fn from_file(path: String) -> Self { Self }
+            }
+        });
+        assert_eq!(found.unwrap(), ["from_file"]);
+
+        let found = constructors(syn::parse_quote! {
+            impl Config {
+                #[new]
+                pub fn create() -> Self { Self }
+            }
+        });
+        assert_eq!(found.unwrap(), ["create"]);
+    }
+
+    #[test]
+    fn test_invalid_constructors_are_rejected() {
+        let multiple = constructors(syn::parse_quote! {
+            impl Config {
+                #[multiffi(constructor)]
+                pub fn create() -> Self { Self }
+                #[multiffi(constructor)]
+                pub fn from_file(path: String) -> Self { Self }
+            }
+        });
+        assert!(
+            multiple
+                .unwrap_err()
+                .to_string()
+                .contains("one constructor")
+        );
+
+        let with_receiver = constructors(syn::parse_quote! {
+            impl Config {
+                #[multiffi(constructor)]
+                pub fn reset(&self) -> Self { Self }
+            }
+        });
+        assert!(with_receiver.is_err());
+        assert!(ItemArgs::parse(quote::quote! { constructor }).is_err());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_constructor_gets_new() {
+        let tokens = crate::impl_bindings(
+            syn::parse_quote! {
+                impl Config {
+                    pub fn new() -> Self { Self }
+                }
+            },
+            &ItemArgs::default(),
+        )
+        .unwrap()
+        .to_string();
+
+        assert_eq!(tokens.matches("# [new]").count(), 1);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_constructor_is_annotated() {
+        let tokens = crate::impl_bindings(
+            syn::parse_quote! {
+                impl Config {
+                    pub fn new() -> Self { Self }
+                    #[multiffi(skip(wasm))]
+                    pub fn raw(&self) -> Vec<u8> { Vec::new() }
+                }
+            },
+            &ItemArgs::default(),
+        )
+        .unwrap()
+        .to_string();
+
+        assert!(tokens.contains("wasm_bindgen (constructor)"));
+        assert!(!tokens.contains("js_name = \"new\""));
+    }
+}
+
+#[cfg(test)]
+mod static_method_tests {
+    use crate::{args::ItemArgs, is_factory};
+    use syn::ImplItemFn;
+
+    #[test]
+    fn test_static_methods_returning_self_are_factories() {
+        let self_ty: syn::Type = syn::parse_quote!(Config);
+        let args = ItemArgs::default();
+        let factory = |method: ImplItemFn| is_factory(&method, &self_ty, &args);
+
+        assert!(factory(
+            syn::parse_quote! { pub fn from_env() -> Self { Self } }
+        ));
+        assert!(factory(syn::parse_quote! {
+            pub fn from_path(path: String) -> Result<Config, ConfigError> { todo!() }
+        }));
+        assert!(!factory(
+            syn::parse_quote! { pub fn default_path() -> String { String::new() } }
+        ));
+        assert!(!factory(
+            syn::parse_quote! { pub fn reset(&self) -> Self { Self } }
+        ));
+        assert!(!factory(
+            syn::parse_quote! { pub async fn fetch() -> Self { Self } }
+        ));
+
+        let mut constructor = ItemArgs::default();
+        constructor.mark_constructor();
+        let method: ImplItemFn = syn::parse_quote! { pub fn new() -> Self { Self } };
+        assert!(!is_factory(&method, &self_ty, &constructor));
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_static_methods_get_staticmethod() {
+        let tokens = crate::impl_bindings(
+            syn::parse_quote! {
+                impl Config {
+                    pub fn new() -> Self { Self }
+                    pub fn from_path(path: String) -> Self { Self }
+                    #[multiffi(rename = "defaultPath")]
+                    pub fn default_path() -> String { String::new() }
+                    #[classmethod]
+                    pub fn kind(cls: &pyo3::Bound<'_, pyo3::types::PyType>) -> String { String::new() }
+                    pub fn name(&self) -> String { String::new() }
+                }
+            },
+            &ItemArgs::default(),
+        )
+        .unwrap()
+        .to_string();
+
+        assert_eq!(tokens.matches("# [staticmethod]").count(), 2);
+        assert!(tokens.contains("# [staticmethod] # [pyo3 (name = \"defaultPath\")]"));
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use crate::args::ItemArgs;
+    use crate::error_support::{error_message, needs_napi_wrapper, result_types};
+    use quote::quote;
+    use syn::{ImplItemFn, ItemEnum};
+
+    #[test]
+    fn test_result_types_require_explicit_error() {
+        let explicit: ImplItemFn = syn::parse_quote! {
+            pub fn load(&self) -> std::result::Result<String, ConfigError> { todo!() }
+        };
+        let alias: ImplItemFn = syn::parse_quote! {
+            pub fn load(&self) -> PyResult<String> { todo!() }
+        };
+        let plain: ImplItemFn = syn::parse_quote! {
+            pub fn load(&self) -> String { todo!() }
+        };
+
+        let (ok, error) = result_types(&explicit.sig.output).expect("should match Result<T, E>");
+        assert_eq!(quote!(#ok).to_string(), "String");
+        assert_eq!(quote!(#error).to_string(), "ConfigError");
+        assert!(result_types(&alias.sig.output).is_none());
+        assert!(result_types(&plain.sig.output).is_none());
+    }
+
+    #[test]
+    fn test_napi_errors_are_not_wrapped() {
+        let custom: ImplItemFn = syn::parse_quote! {
+            pub fn load(&self) -> Result<String, ConfigError> { todo!() }
+        };
+        let native: ImplItemFn = syn::parse_quote! {
+            pub fn load(&self) -> Result<String, napi::Error> { todo!() }
+        };
+
+        assert!(needs_napi_wrapper(&custom.sig));
+        assert!(!needs_napi_wrapper(&native.sig));
+    }
+
+    #[test]
+    fn test_error_message_keeps_variant_name() {
+        let item: ItemEnum = syn::parse_quote! {
+            pub enum ConfigError { NotFound(String), Invalid { key: String }, Locked }
+        };
+
+        let message = error_message(&item.ident, Some(&item.variants)).to_string();
+
+        assert!(message.contains("ConfigError :: NotFound { .. } => \"NotFound\""));
+        assert!(message.contains("ConfigError :: Locked { .. } => \"Locked\""));
+        assert!(message.contains("format !"));
+        assert!(
+            error_message(&item.ident, None)
+                .to_string()
+                .contains("to_string")
+        );
+    }
+
+    #[test]
+    fn test_error_argument_is_rejected_on_methods() {
+        let args = ItemArgs::parse(quote! { error }).expect("arguments should parse");
+        let mut method: ImplItemFn = syn::parse_quote! {
+            #[multiffi(error)]
+            pub fn load(&self) {}
+        };
+
+        assert!(args.is_error());
+        assert!(
+            ItemArgs::default()
+                .take_from_method(&mut method.attrs)
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "nodejs")]
+    #[test]
+    fn test_result_methods_are_wrapped_for_nodejs() {
+        let tokens = crate::impl_bindings(
+            syn::parse_quote! {
+                impl Config {
+                    pub fn name(&self) -> String { String::new() }
+                    pub fn load_file(&self, path: String) -> Result<String, ConfigError> { todo!() }
+                }
+            },
+            &ItemArgs::default(),
+        )
+        .expect("impl should expand")
+        .to_string();
+
+        assert!(tokens.contains("fn __multiffi_napi_load_file"));
+        assert!(tokens.contains("js_name = \"loadFile\""));
+        assert!(tokens.contains("napi :: Result < String >"));
+        assert!(tokens.contains("map_err (napi :: Error :: from)"));
+    }
+}
+
+#[cfg(test)]
+mod option_tests {
+    use crate::types::{has_pyo3_option, is_option, python_signature};
+    use quote::quote;
+    use syn::{ImplItemFn, Type};
+
+    #[test]
+    fn test_option_detection() {
+        let option: Type = syn::parse_quote!(Option<String>);
+        let qualified: Type = syn::parse_quote!(std::option::Option<u32>);
+        let plain: Type = syn::parse_quote!(String);
+
+        assert!(is_option(&option));
+        assert!(is_option(&qualified));
+        assert!(!is_option(&plain));
+    }
+
+    #[test]
+    fn test_trailing_options_default_to_none() {
+        let method: ImplItemFn = syn::parse_quote! {
+            pub fn load(&self, path: String, profile: Option<String>, strict: Option<bool>) {}
+        };
+
+        let signature = python_signature(&method.sig, &method.attrs).expect("should generate");
+
+        assert_eq!(
+            quote!(#signature).to_string(),
+            quote!(#[pyo3(signature = (path, profile = None, strict = None))]).to_string()
+        );
+    }
+
+    #[test]
+    fn test_signature_is_skipped_when_not_needed() {
+        let leading: ImplItemFn = syn::parse_quote! {
+            pub fn load(profile: Option<String>, path: String) {}
+        };
+        let declared: ImplItemFn = syn::parse_quote! {
+            #[pyo3(signature = (profile = Some("dev".to_string())))]
+            pub fn load(profile: Option<String>) {}
+        };
+
+        assert!(python_signature(&leading.sig, &leading.attrs).is_none());
+        assert!(python_signature(&declared.sig, &declared.attrs).is_none());
+        assert!(has_pyo3_option(&declared.attrs, "signature"));
+    }
+
+    #[test]
+    fn test_python_token_is_not_a_parameter() {
+        let function: ImplItemFn = syn::parse_quote! {
+            pub fn load(py: Python<'_>, path: Option<String>) {}
+        };
+
+        let signature = python_signature(&function.sig, &function.attrs).unwrap();
+
+        assert_eq!(
+            quote!(#signature).to_string(),
+            quote!(#[pyo3(signature = (path = None))]).to_string()
+        );
+    }
+}
+
+#[cfg(test)]
+mod field_tests {
+    use crate::{args::ItemArgs, args::Target, take_field_args};
+    use quote::quote;
+    use syn::ItemStruct;
+
+    #[test]
+    fn test_field_attributes_are_consumed() {
+        let mut item: ItemStruct = syn::parse_quote! {
+            pub struct AppConfig {
+                /// Name of the application.
+                #[multiffi(rename(python = "app_name", wasm = "appName"))]
+                pub name: String,
+                pub max_connections: u32,
+                #[multiffi(skip(wasm))]
+                pub raw_data: Vec<u8>,
+                cache_dir: String,
+            }
+        };
+
+        let field_args = take_field_args(&mut item, &ItemArgs::default()).unwrap();
+
+        assert_eq!(field_args.len(), 3);
+        assert_eq!(field_args[0].name(Target::Python), Some("app_name"));
+        assert_eq!(field_args[0].name(Target::Wasm), Some("appName"));
+        assert_eq!(field_args[1], ItemArgs::default());
+        assert!(!field_args[2].exports(Target::Wasm));
+        assert!(item.fields.iter().all(|field| {
+            !field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("multiffi"))
+        }));
+        assert_eq!(item.fields.iter().next().unwrap().attrs.len(), 1);
+    }
+
+    #[test]
+    fn test_private_field_attributes_are_rejected() {
+        let mut item: ItemStruct = syn::parse_quote! {
+            pub struct AppConfig {
+                #[multiffi(rename = "cacheDir")]
+                cache_dir: String,
+            }
+        };
+
+        let error = take_field_args(&mut item, &ItemArgs::default()).unwrap_err();
+
+        assert!(error.to_string().contains("only public fields"));
+    }
+
+    #[test]
+    fn test_struct_skips_are_inherited() {
+        let args = ItemArgs::parse(quote! { skip(nodejs) }).unwrap();
+        let mut item: ItemStruct = syn::parse_quote! {
+            pub struct AppConfig { pub name: String }
+        };
+
+        let field_args = take_field_args(&mut item, &args).unwrap();
+
+        assert!(!field_args[0].exports(Target::Nodejs));
+    }
+
+    #[test]
+    fn test_javascript_field_names_are_camel_case() {
+        assert_eq!(
+            crate::javascript_name("max_connections", None),
+            "maxConnections"
+        );
+        assert_eq!(crate::javascript_name("name", None), "name");
+        assert_eq!(crate::javascript_name("raw_data", Some("bytes")), "bytes");
+    }
+}
+
+#[cfg(test)]
+mod collection_tests {
+    use crate::types::{needs_wasm_conversion, wasm_collection_accessors};
+    use syn::{Field, ImplItemFn, ItemStruct, Type};
+
+    fn needs_conversion(ty: Type) -> bool {
+        needs_wasm_conversion(&ty)
+    }
+
+    #[test]
+    fn test_maps_and_nested_sequences_need_conversion() {
+        assert!(needs_conversion(
+            syn::parse_quote!(HashMap<String, ServerConfig>)
+        ));
+        assert!(needs_conversion(syn::parse_quote!(
+            std::collections::BTreeMap<String, u32>
+        )));
+        assert!(needs_conversion(syn::parse_quote!(Vec<ServerConfig>)));
+        assert!(needs_conversion(syn::parse_quote!(Vec<Vec<u8>>)));
+        assert!(needs_conversion(syn::parse_quote!(Option<HashSet<String>>)));
+    }
+
+    #[test]
+    fn test_natively_supported_fields_are_left_alone() {
+        assert!(!needs_conversion(syn::parse_quote!(Vec<String>)));
+        assert!(!needs_conversion(syn::parse_quote!(Vec<u8>)));
+        assert!(!needs_conversion(syn::parse_quote!(Option<String>)));
+        assert!(!needs_conversion(syn::parse_quote!(ServerConfig)));
+    }
+
+    #[test]
+    fn test_accessors_use_javascript_name() {
+        let item: ItemStruct = syn::parse_quote! {
+            pub struct AppConfig { pub servers: HashMap<String, ServerConfig> }
+        };
+        let field: &Field = item.fields.iter().next().unwrap();
+
+        let tokens = wasm_collection_accessors(field, "servers");
+        let file: syn::File = syn::parse2(quote::quote! {
+            impl AppConfig { #tokens }
+        })
+        .expect("accessors should parse");
+        let syn::Item::Impl(block) = &file.items[0] else {
+            panic!("expected an impl block");
+        };
+        let methods: Vec<&ImplItemFn> = block
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::ImplItem::Fn(method) => Some(method),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(methods[0].sig.ident, "__multiffi_get_servers");
+        assert_eq!(methods[1].sig.ident, "__multiffi_set_servers");
+        let expanded = tokens.to_string();
+        assert!(expanded.contains("getter = \"servers\""));
+        assert!(expanded.contains("json_compatible"));
+    }
+}
+
 // Integration tests using trybuild would go in tests/ directory
 // rather than in src/tests.rs for proc-macro crates
 
 // Additional module-level tests that don't depend on naming functions can go here
 // (currently none, but this structure allows for future expansion)
+
+#[cfg(test)]
+mod doc_tests {
+    use crate::doc_support::{doc_attrs, doc_string};
+    use syn::ItemFn;
+
+    #[test]
+    fn test_doc_comments_are_collected() {
+        let function: ItemFn = syn::parse_quote! {
+            /// Loads the configuration.
+            ///
+            /// Reads `path` once.
+            #[inline]
+            pub fn load(path: String) {}
+        };
+
+        assert_eq!(doc_attrs(&function.attrs).len(), 3);
+        assert_eq!(
+            doc_string(&function.attrs).as_deref(),
+            Some("Loads the configuration.\n\nReads `path` once.")
+        );
+
+        let undocumented: ItemFn = syn::parse_quote! { pub fn load() {} };
+        assert!(doc_attrs(&undocumented.attrs).is_empty());
+        assert!(doc_string(&undocumented.attrs).is_none());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_async_wrapper_keeps_docstring() {
+        let function: ItemFn = syn::parse_quote! {
+            /// Fetches the remote configuration.
+            pub async fn fetch(url: String) -> String { url }
+        };
+        let wrapper = crate::async_support::python_wrapper(
+            &function.sig,
+            &function.attrs,
+            crate::async_support::AsyncCallKind::Free,
+            "fetch",
+        )
+        .expect("wrapper should generate");
+        let wrapper: ItemFn = syn::parse2(wrapper).expect("wrapper should parse");
+
+        assert_eq!(
+            doc_string(&wrapper.attrs).as_deref(),
+            Some("Fetches the remote configuration.")
+        );
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_exception_gets_docstring() {
+        let error: syn::ItemEnum = syn::parse_quote! {
+            /// Raised when the configuration can't be loaded.
+            pub enum ConfigError { NotFound(String) }
+        };
+        let conversions = crate::error_support::error_conversions(
+            &error.ident,
+            &error.attrs,
+            &error.vis,
+            Some(&error.variants),
+            &crate::args::ItemArgs::default(),
+        )
+        .to_string();
+
+        assert!(
+            conversions
+                .contains("PyException , \"Raised when the configuration can't be loaded.\"")
+        );
+    }
+
+    #[cfg(feature = "nodejs")]
+    #[test]
+    fn test_napi_wrapper_keeps_jsdoc() {
+        let function: ItemFn = syn::parse_quote! {
+            /// Loads the configuration file.
+            pub fn load_file(path: String) -> Result<String, ConfigError> { todo!() }
+        };
+        let wrapper =
+            crate::error_support::napi_wrapper(&function.sig, &function.attrs, false, "loadFile")
+                .expect("wrapper should generate");
+        let wrapper: ItemFn = syn::parse2(wrapper).expect("wrapper should parse");
+
+        assert_eq!(
+            doc_string(&wrapper.attrs).as_deref(),
+            Some("Loads the configuration file.")
+        );
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_delegates_and_accessors_keep_docs() {
+        let tokens = crate::impl_bindings(
+            syn::parse_quote! {
+                impl Config {
+                    /// Name of the file at `index`.
+                    pub fn file_name(&self, index: u32) -> String { String::new() }
+                    #[multiffi(skip(wasm))]
+                    pub fn raw(&self) -> Vec<u8> { Vec::new() }
+                }
+            },
+            &crate::args::ItemArgs::default(),
+        )
+        .expect("impl should expand");
+        let file: syn::File = syn::parse2(tokens).expect("expansion should parse");
+        let Some(syn::Item::Impl(wasm_block)) = file.items.last() else {
+            panic!("expected the wasm-bindgen block last");
+        };
+        let syn::ImplItem::Fn(delegate) = &wasm_block.items[0] else {
+            panic!("expected a delegate method");
+        };
+        assert_eq!(
+            doc_string(&delegate.attrs).as_deref(),
+            Some("Name of the file at `index`.")
+        );
+
+        let item: syn::ItemStruct = syn::parse_quote! {
+            pub struct AppConfig {
+                /// Servers by name.
+                pub servers: HashMap<String, ServerConfig>,
+            }
+        };
+        let syn::Fields::Named(fields) = &item.fields else {
+            unreachable!()
+        };
+        let accessors =
+            crate::types::wasm_collection_accessors(&fields.named[0], "servers").to_string();
+        assert_eq!(accessors.matches("\" Servers by name.\"").count(), 2);
+    }
+}
+
+#[cfg(test)]
+mod callback_tests {
+    use crate::args::Target;
+    use crate::callback_support::{callback_type, has_callbacks, wrapper};
+    use syn::{ItemFn, Type};
+
+    fn on_change() -> ItemFn {
+        syn::parse_quote! {
+            /// Notifies `callback` when `key` changes.
+            pub fn on_change(key: String, callback: impl Fn(String, u32) + Send + 'static) -> Result<u64, ConfigError> {
+                todo!()
+            }
+        }
+    }
+
+    #[test]
+    fn test_callback_types_are_detected() {
+        let callback: Type = syn::parse_quote!(impl Fn(String, u32) + Send);
+        let callback = callback_type(&callback)
+            .expect("callback should parse")
+            .expect("callback should be detected");
+        assert_eq!(callback.inputs.len(), 2);
+        assert!(!callback.boxed);
+
+        let boxed: Type = syn::parse_quote!(Box<dyn FnMut(&str) + Send + Sync>);
+        let boxed = callback_type(&boxed)
+            .expect("callback should parse")
+            .expect("callback should be detected");
+        assert!(boxed.boxed);
+
+        for ty in [
+            syn::parse_quote!(String),
+            syn::parse_quote!(Box<dyn std::error::Error>),
+            syn::parse_quote!(impl Iterator<Item = u32>),
+        ] {
+            let ty: Type = ty;
+            assert!(callback_type(&ty).expect("type should parse").is_none());
+        }
+
+        let returning: Type = syn::parse_quote!(impl Fn(String) -> bool);
+        assert!(callback_type(&returning).is_err());
+        let unit: Type = syn::parse_quote!(impl FnOnce() -> ());
+        assert!(
+            callback_type(&unit)
+                .expect("callback should parse")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_only_free_functions_take_callbacks() {
+        let function = on_change();
+        assert!(has_callbacks(&function.sig).expect("signature should parse"));
+
+        let method: ItemFn = syn::parse_quote! {
+            pub async fn watch(callback: impl Fn(String)) {}
+        };
+        assert!(wrapper(&method.sig, &method.attrs, Target::Python, "watch").is_err());
+
+        let item_impl = syn::parse_quote! {
+            impl Config {
+                pub fn watch(&self, callback: impl Fn(String)) {}
+            }
+        };
+        assert!(crate::impl_bindings(item_impl, &crate::args::ItemArgs::default()).is_err());
+    }
+
+    #[test]
+    fn test_python_wrapper_calls_python_callable() {
+        let function = on_change();
+        let tokens = wrapper(&function.sig, &function.attrs, Target::Python, "on_change")
+            .expect("wrapper should generate")
+            .to_string();
+
+        assert!(tokens.contains("# [pyo3 (name = \"on_change\")]"));
+        assert!(tokens.contains(
+            "fn __multiffi_py_on_change (key : String , callback : pyo3 :: Py < pyo3 :: PyAny >)"
+        ));
+        assert!(tokens.contains("callback . call1 (py , (__multiffi_arg0 , __multiffi_arg1 ,))"));
+        assert!(tokens.contains("write_unraisable"));
+        assert!(tokens.contains("on_change (key , callback)"));
+    }
+
+    #[test]
+    fn test_nodejs_wrapper_uses_threadsafe_function() {
+        let function = on_change();
+        let tokens = wrapper(&function.sig, &function.attrs, Target::Nodejs, "onChange")
+            .expect("wrapper should generate")
+            .to_string();
+
+        assert!(tokens.contains("# [napi :: napi (js_name = \"onChange\")]"));
+        assert!(
+            tokens.contains(
+                "ThreadsafeFunction < napi :: bindgen_prelude :: FnArgs < (String , u32) >"
+            )
+        );
+        assert!(tokens.contains("ThreadsafeFunctionCallMode :: NonBlocking"));
+        assert!(tokens.contains("-> napi :: Result < u64 >"));
+        assert!(tokens.contains("map_err (napi :: Error :: from)"));
+    }
+
+    #[test]
+    fn test_wasm_wrapper_uses_js_function() {
+        let function: ItemFn = syn::parse_quote! {
+            pub fn on_reload(callback: Box<dyn Fn()>) {}
+        };
+        let tokens = wrapper(&function.sig, &function.attrs, Target::Wasm, "on_reload")
+            .expect("wrapper should generate")
+            .to_string();
+
+        assert!(tokens.contains("callback : js_sys :: Function"));
+        assert!(tokens.contains(":: std :: boxed :: Box :: new (move |"));
+        assert!(tokens.contains("js_sys :: Array :: new ()"));
+        assert!(tokens.contains("wasm_bindgen :: throw_val (error)"));
+    }
+}
+
+#[cfg(test)]
+mod trait_tests {
+    use crate::args::ItemArgs;
+    use crate::trait_support::trait_bindings;
+    use quote::quote;
+    use syn::ItemTrait;
+
+    fn config_source() -> ItemTrait {
+        syn::parse_quote! {
+            /// A configuration source implemented by the host.
+            pub trait ConfigSource {
+                /// Loads the source for `profile`.
+                fn load(&self, profile: String) -> Result<String, String>;
+                #[multiffi(rename(python = "source_name", wasm = "sourceName"))]
+                fn name(&self) -> String;
+                fn describe(&self) -> String { self.name() }
+            }
+        }
+    }
+
+    #[test]
+    fn test_trait_keeps_methods_and_strips_attributes() {
+        let tokens =
+            trait_bindings(config_source(), &ItemArgs::default()).expect("trait should expand");
+        let file: syn::File = syn::parse2(tokens).expect("expansion should parse");
+        let Some(syn::Item::Trait(item_trait)) = file.items.first() else {
+            panic!("expected the trait first");
+        };
+
+        assert_eq!(item_trait.items.len(), 3);
+        assert!(!quote!(#item_trait).to_string().contains("multiffi"));
+    }
+
+    #[test]
+    fn test_unsupported_interface_methods_are_rejected() {
+        let args = ItemArgs::default();
+        let rejected: [ItemTrait; 5] = [
+            syn::parse_quote! { pub trait Source<T> { fn load(&self) -> T; } },
+            syn::parse_quote! { pub trait Source { fn load(&mut self) -> String; } },
+            syn::parse_quote! { pub trait Source { fn load() -> String; } },
+            syn::parse_quote! { pub trait Source { async fn load(&self) -> String; } },
+            syn::parse_quote! {
+                pub trait Source { #[multiffi(skip(python))] fn load(&self) -> String; }
+            },
+        ];
+        for item_trait in rejected {
+            assert!(trait_bindings(item_trait, &args).is_err());
+        }
+
+        // Provided methods stay in Rust, whatever their signature
+        let provided: ItemTrait = syn::parse_quote! {
+            pub trait Source {
+                fn load(&self) -> String;
+                fn reload(&mut self) -> String { self.load() }
+            }
+        };
+        assert!(trait_bindings(provided, &args).is_ok());
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_typescript_type_mapping() {
+        use crate::trait_support::typescript_type;
+        use syn::Type;
+
+        let cases: [(Type, &str); 7] = [
+            (syn::parse_quote!(String), "string"),
+            (syn::parse_quote!(&str), "string"),
+            (syn::parse_quote!(u64), "bigint"),
+            (syn::parse_quote!(Option<f64>), "number | undefined"),
+            (syn::parse_quote!(Vec<u8>), "Uint8Array"),
+            (syn::parse_quote!(Vec<bool>), "boolean[]"),
+            (syn::parse_quote!(ServerConfig), "ServerConfig"),
+        ];
+        for (ty, expected) in cases {
+            assert_eq!(typescript_type(&ty), expected);
+        }
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_interface() {
+        let tokens = trait_bindings(config_source(), &ItemArgs::default())
+            .expect("trait should expand")
+            .to_string();
+
+        assert!(tokens.contains("pub struct PyConfigSource (pyo3 :: Py < pyo3 :: PyAny >)"));
+        assert!(tokens.contains("impl ConfigSource for PyConfigSource"));
+        assert!(tokens.contains("call_method1 (\"load\" , (profile ,))"));
+        assert!(tokens.contains("call_method0 (\"source_name\")"));
+        assert!(!tokens.contains("\"describe\""));
+        assert!(tokens.contains("fn __multiffi_py_register_ConfigSource"));
+        assert!(tokens.contains("class ConfigSource(abc.ABC)"));
+        assert!(tokens.contains("def load(self, profile)"));
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_interface() {
+        let tokens = trait_bindings(config_source(), &ItemArgs::default())
+            .expect("trait should expand")
+            .to_string();
+
+        assert!(tokens.contains("typescript_custom_section"));
+        assert!(tokens.contains("export interface ConfigSource {"));
+        assert!(tokens.contains("load(profile: string): string;"));
+        assert!(tokens.contains("sourceName(): string;"));
+        assert!(tokens.contains("pub type JsConfigSource"));
+        assert!(tokens.contains("method , catch , js_name = \"load\""));
+        assert!(tokens.contains("impl ConfigSource for JsConfigSource"));
+    }
+}
+
+#[cfg(test)]
+mod module_tests {
+    use crate::module_support::{
+        ModuleArgs, export_module, exported_item, module_bindings, registration_path,
+    };
+    use quote::quote;
+    use syn::{Item, ItemMod, Path};
+
+    #[test]
+    fn test_module_collects_annotated_items() {
+        let items: Vec<Item> = vec![
+            syn::parse_quote! { #[multiffi] pub struct Calculator { pub value: f64 } },
+            syn::parse_quote! { #[multiffi::multiffi(error)] pub enum ConfigError { Missing } },
+            syn::parse_quote! { #[multiffi(skip(python))] pub fn fibonacci(n: u32) -> u64 { 0 } },
+            syn::parse_quote! { #[multiffi] impl Calculator {} },
+            syn::parse_quote! { #[multiffi] pub trait ConfigSource { fn load(&self) -> String; } },
+            syn::parse_quote! { pub fn helper() {} },
+        ];
+
+        let exported: Vec<String> = items
+            .iter()
+            .filter_map(exported_item)
+            .map(|ident| ident.to_string())
+            .collect();
+
+        assert_eq!(
+            exported,
+            ["Calculator", "ConfigError", "fibonacci", "ConfigSource"]
+        );
+    }
+
+    #[test]
+    fn test_registration_path_keeps_module_prefix() {
+        let path: Path = syn::parse_quote!(math::fibonacci);
+        let registration = registration_path(&path);
+
+        assert_eq!(
+            quote!(#registration).to_string(),
+            "math :: __multiffi_py_register_fibonacci"
+        );
+    }
+
+    #[test]
+    fn test_module_arguments() {
+        let args = ModuleArgs::parse(quote! { name = "your_library", init = setup }, false);
+        assert!(args.is_ok());
+        assert!(ModuleArgs::parse(quote! { items(Calculator) }, false).is_err());
+        assert!(ModuleArgs::parse(quote! { items(Calculator, math::fibonacci) }, true).is_ok());
+
+        let unnamed =
+            ModuleArgs::parse(quote! { items(Calculator) }, true).expect("arguments should parse");
+        assert!(export_module(unnamed).is_err());
+    }
+
+    #[test]
+    fn test_module_requires_inline_content() {
+        let external: ItemMod = syn::parse_quote! { mod bindings; };
+        let inline: ItemMod = syn::parse_quote! {
+            mod bindings {
+                #[multiffi]
+                pub fn fibonacci(n: u32) -> u64 { 0 }
+            }
+        };
+
+        assert!(module_bindings(external, ModuleArgs::default()).is_err());
+        assert!(module_bindings(inline, ModuleArgs::default()).is_ok());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_module_registers_items() {
+        let item_mod: ItemMod = syn::parse_quote! {
+            mod bindings {
+                #[multiffi]
+                pub struct Calculator { pub value: f64 }
+            }
+        };
+        let args =
+            ModuleArgs::parse(quote! { init = setup }, false).expect("arguments should parse");
+
+        let item_mod = module_bindings(item_mod, args).expect("module should expand");
+        let tokens = quote!(#item_mod).to_string();
+
+        assert!(tokens.contains("# [pyo3 :: pymodule]"));
+        assert!(tokens.contains("# [pyo3 (name = \"bindings\")]"));
+        assert!(tokens.contains("setup () ;"));
+        assert!(tokens.contains("__multiffi_py_register_Calculator (module) ?"));
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_items_skipping_python_register_nothing() {
+        use crate::args::ItemArgs;
+        use crate::module_support::python_registration;
+
+        let ident = syn::parse_quote!(fibonacci);
+        let vis = syn::parse_quote!(pub);
+        let register = quote! { module.add_function(pyo3::wrap_pyfunction!(fibonacci, module)?) };
+
+        let exported = python_registration(&ident, &vis, &ItemArgs::default(), register.clone());
+        let skipped = ItemArgs::parse(quote! { skip(python) }).expect("arguments should parse");
+        let skipped = python_registration(&ident, &vis, &skipped, register);
+
+        assert!(exported.to_string().contains("wrap_pyfunction"));
+        assert!(
+            skipped
+                .to_string()
+                .contains("fn __multiffi_py_register_fibonacci")
+        );
+        assert!(!skipped.to_string().contains("wrap_pyfunction"));
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_start_runs_init() {
+        let args = ModuleArgs::parse(quote! { name = "your_library", init = setup }, true)
+            .expect("arguments should parse");
+        let tokens = export_module(args)
+            .expect("module should expand")
+            .to_string();
+
+        assert!(tokens.contains("wasm_bindgen (start)"));
+        assert!(tokens.contains("setup () ;"));
+    }
+}
@@ -0,0 +1,508 @@
+//! Interface bindings for traits implemented in the host language.
+//!
+//! `#[multiffi]` on a trait describes an interface that Python or JavaScript objects
+//! implement, so Rust code can call back into them (plugin-style configuration sources,
+//! hooks, loggers). Only required methods (without a default body) are part of the
+//! interface; provided methods keep running their Rust default.
+//!
+//! | Target | Host-side declaration | Rust-side dispatch type |
+//! |--------|-----------------------|-------------------------|
+//! | Python | an `abc.ABC` subclass with one abstract method per trait method, whose `__subclasshook__` also accepts any object providing the methods | `Py<Trait>`, extracted from any Python object that has the methods |
+//! | WebAssembly | a TypeScript `interface` in the generated `.d.ts` | `Js<Trait>`, a wasm-bindgen extern type calling the object's methods |
+//! | Node.js | not generated yet: NAPI has no equivalent of wasm-bindgen's extern types | - |
+//!
+//! The dispatch types implement the trait, so exported functions take them as
+//! parameters (`fn register(source: PyConfigSource)`) and hand them to Rust code
+//! expecting `impl ConfigSource` or `Box<dyn ConfigSource>`.
+//!
+//! ## Errors
+//!
+//! Methods returning `Result<T, E>` turn a Python exception or a thrown JavaScript
+//! value into `Err(E::from(message))`, so `E` must implement `From<String>`. For other
+//! methods, a Python exception panics with the exception's message; JavaScript
+//! exceptions can't be caught by wasm-bindgen without `Result`.
+//!
+//! ## Requirements
+//!
+//! Interface methods take `&self`, are neither generic nor `async`, and their
+//! parameters are simple identifiers. Parameter and return types must convert to and
+//! from the target's values (`IntoPyObject`/`FromPyObject`, wasm-bindgen ABI types).
+
+// Only the interfaces of enabled targets are generated
+#![cfg_attr(
+    not(any(feature = "python", feature = "wasm")),
+    allow(dead_code, unused_imports)
+)]
+
+use crate::args::{ItemArgs, Target};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Attribute, FnArg, Ident, ItemTrait, ReturnType, Signature, TraitItem, Type};
+
+/// A required trait method, as exposed to the host language.
+pub(crate) struct InterfaceMethod {
+    sig: Signature,
+    docs: Vec<Attribute>,
+    params: Vec<(Ident, Type)>,
+    py_name: String,
+    #[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+    js_name: String,
+}
+
+/// Expands `#[multiffi]` on a trait: the trait, its dispatch types, and the host-side
+/// declarations of every enabled target.
+pub(crate) fn trait_bindings(
+    mut item_trait: ItemTrait,
+    args: &ItemArgs,
+) -> syn::Result<TokenStream2> {
+    if !item_trait.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_trait.generics,
+            "multiffi: generic traits can't be implemented by host objects",
+        ));
+    }
+
+    let mut methods = Vec::new();
+    for item in &mut item_trait.items {
+        let TraitItem::Fn(method) = item else {
+            continue;
+        };
+        let method_args = args.take_from_method(&mut method.attrs)?;
+        if method_args.is_constructor() {
+            return Err(syn::Error::new_spanned(
+                &method.sig,
+                "multiffi: trait methods are called on host objects and can't be constructors",
+            ));
+        }
+        if method.default.is_some() {
+            continue;
+        }
+        if !method_args.skips_same_targets(args) {
+            return Err(syn::Error::new_spanned(
+                &method.sig,
+                "multiffi: trait methods are implemented by the host object and can't skip targets, skip the trait instead",
+            ));
+        }
+        methods.push(interface_method(&method.sig, &method.attrs, &method_args)?);
+    }
+
+    #[allow(unused_mut)]
+    let mut bindings = TokenStream2::new();
+
+    #[cfg(feature = "python")]
+    if args.exports(Target::Python) {
+        bindings.extend(python_dispatch(&item_trait, &methods));
+    }
+
+    #[cfg(feature = "wasm")]
+    if args.exports(Target::Wasm) {
+        bindings.extend(wasm_dispatch(&item_trait, &methods, args));
+    }
+
+    let registration = python_registration(&item_trait, &methods, args);
+    Ok(quote! { #item_trait #bindings #registration })
+}
+
+/// Validates a required method and collects what the dispatch types need.
+fn interface_method(
+    sig: &Signature,
+    attrs: &[Attribute],
+    args: &ItemArgs,
+) -> syn::Result<InterfaceMethod> {
+    let takes_ref_self = sig
+        .receiver()
+        .is_some_and(|receiver| receiver.reference.is_some() && receiver.mutability.is_none());
+    if !takes_ref_self {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "multiffi: interface methods must take `&self`",
+        ));
+    }
+    if sig.asyncness.is_some() || !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "multiffi: interface methods can't be `async` or generic",
+        ));
+    }
+
+    let names = crate::forwarded_args(sig)?;
+    let types = sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(pat_type) => Some((*pat_type.ty).clone()),
+        FnArg::Receiver(_) => None,
+    });
+    let params = names.into_iter().cloned().zip(types).collect();
+
+    let rust_name = sig.ident.to_string();
+    Ok(InterfaceMethod {
+        sig: sig.clone(),
+        docs: crate::doc_support::doc_attrs(attrs)
+            .into_iter()
+            .cloned()
+            .collect(),
+        params,
+        py_name: args
+            .name(Target::Python)
+            .map_or_else(|| rust_name.clone(), str::to_string),
+        js_name: crate::javascript_name(&rust_name, args.name(Target::Wasm)),
+    })
+}
+
+/// Returns the `Ok` type of a `Result<T, E>` return, or `None` for other returns.
+fn result_ok_type(output: &ReturnType) -> Option<&Type> {
+    crate::error_support::result_types(output).map(|(ok, _)| ok)
+}
+
+/// Returns `true` when `ty` is `()`.
+#[cfg(feature = "python")]
+fn is_unit(ty: &Type) -> bool {
+    matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
+// ============================================================================
+// Python
+// ============================================================================
+
+/// Generates `Py<Trait>`: extraction from any Python object providing the methods, and
+/// the trait implementation calling them.
+#[cfg(feature = "python")]
+fn python_dispatch(item_trait: &ItemTrait, methods: &[InterfaceMethod]) -> TokenStream2 {
+    let trait_ident = &item_trait.ident;
+    let vis = &item_trait.vis;
+    let dispatch = format_ident!("Py{}", trait_ident);
+    let struct_doc = format!("Python object implementing [`{trait_ident}`]");
+    let method_names: Vec<&str> = methods.iter().map(|m| m.py_name.as_str()).collect();
+
+    let implementations = methods.iter().map(|method| {
+        let sig = &method.sig;
+        let py_name = &method.py_name;
+        let args = method.params.iter().map(|(name, _)| name);
+        let call = if method.params.is_empty() {
+            quote! { object.call_method0(#py_name) }
+        } else {
+            quote! { object.call_method1(#py_name, (#(#args,)*)) }
+        };
+        let failure = format!(
+            "multiffi: Python implementation of `{trait_ident}.{py_name}` failed: {{error}}"
+        );
+
+        let convert = match (&sig.output, result_ok_type(&sig.output)) {
+            (_, Some(ok)) if is_unit(ok) => quote! {
+                result.map(|_| ()).map_err(|error| ::std::convert::From::from(error.to_string()))
+            },
+            (_, Some(ok)) => quote! {
+                result
+                    .and_then(|value| value.extract::<#ok>())
+                    .map_err(|error| ::std::convert::From::from(error.to_string()))
+            },
+            (ReturnType::Type(_, ty), None) if !is_unit(ty) => quote! {
+                result
+                    .and_then(|value| value.extract::<#ty>())
+                    .unwrap_or_else(|error| panic!(#failure))
+            },
+            _ => quote! {
+                if let Err(error) = result {
+                    panic!(#failure);
+                }
+            },
+        };
+
+        quote! {
+            #sig {
+                pyo3::Python::with_gil(|py| {
+                    use pyo3::types::PyAnyMethods;
+                    let object = self.0.bind(py);
+                    let result = #call;
+                    #convert
+                })
+            }
+        }
+    });
+
+    quote! {
+        #[doc = #struct_doc]
+        #vis struct #dispatch(pyo3::Py<pyo3::PyAny>);
+
+        impl #dispatch {
+            /// Wraps a Python object without checking its methods.
+            #vis fn new(object: pyo3::Py<pyo3::PyAny>) -> Self {
+                Self(object)
+            }
+
+            /// The wrapped Python object.
+            #vis fn object(&self) -> &pyo3::Py<pyo3::PyAny> {
+                &self.0
+            }
+        }
+
+        impl<'py> pyo3::FromPyObject<'py> for #dispatch {
+            fn extract_bound(object: &pyo3::Bound<'py, pyo3::PyAny>) -> pyo3::PyResult<Self> {
+                use pyo3::types::{PyAnyMethods, PyTypeMethods};
+                for method in [#(#method_names),*] {
+                    if !object.hasattr(method)? {
+                        return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                            "{} does not implement {}: missing method `{}`",
+                            object.get_type().name()?,
+                            stringify!(#trait_ident),
+                            method,
+                        )));
+                    }
+                }
+                Ok(Self(object.clone().unbind()))
+            }
+        }
+
+        impl #trait_ident for #dispatch {
+            #(#implementations)*
+        }
+    }
+}
+
+/// Generates the hidden function adding the trait's abstract base class to a Python module.
+fn python_registration(
+    item_trait: &ItemTrait,
+    methods: &[InterfaceMethod],
+    args: &ItemArgs,
+) -> TokenStream2 {
+    let ident = &item_trait.ident;
+    let class_name = args
+        .name(Target::Python)
+        .map_or_else(|| ident.to_string(), str::to_string);
+    let source = python_abc_source(&class_name, &item_trait.attrs, methods);
+    let module_name = format!("multiffi_interface_{ident}");
+
+    crate::module_support::python_registration(
+        ident,
+        &item_trait.vis,
+        args,
+        quote! {
+            use pyo3::types::PyAnyMethods;
+            let to_cstring = |text: &str| {
+                ::std::ffi::CString::new(text).map_err(|error| {
+                    pyo3::exceptions::PyValueError::new_err(error.to_string())
+                })
+            };
+            let interface = pyo3::types::PyModule::from_code(
+                module.py(),
+                &to_cstring(#source)?,
+                &to_cstring(concat!(#module_name, ".py"))?,
+                &to_cstring(#module_name)?,
+            )?;
+            let class = interface.getattr(#class_name)?;
+            class.setattr("__module__", module.name()?)?;
+            module.add(#class_name, class)
+        },
+    )
+}
+
+/// Builds the Python source of the abstract base class declaring the interface.
+fn python_abc_source(class_name: &str, attrs: &[Attribute], methods: &[InterfaceMethod]) -> String {
+    let mut source = format!("import abc\n\n\nclass {class_name}(abc.ABC):\n");
+    if let Some(doc) = crate::doc_support::doc_string(attrs) {
+        source.push_str(&format!("    {}\n\n", python_docstring(&doc)));
+    }
+
+    let names: Vec<String> = methods
+        .iter()
+        .map(|method| format!("{:?}", method.py_name))
+        .collect();
+    source.push_str(&format!(
+        "    @classmethod\n    def __subclasshook__(cls, other):\n        if cls is {class_name} and all(\n            any(name in vars(base) for base in other.__mro__) for name in ({},)\n        ):\n            return True\n        return NotImplemented\n",
+        names.join(", ")
+    ));
+
+    for method in methods {
+        let params: String = method
+            .params
+            .iter()
+            .map(|(name, _)| format!(", {name}"))
+            .collect();
+        let body = crate::doc_support::doc_string(&method.docs)
+            .map_or_else(|| "...".to_string(), |doc| python_docstring(&doc));
+        source.push_str(&format!(
+            "\n    @abc.abstractmethod\n    def {}(self{params}):\n        {body}\n",
+            method.py_name
+        ));
+    }
+    source
+}
+
+/// Quotes `doc` as a Python docstring.
+fn python_docstring(doc: &str) -> String {
+    let escaped = doc.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"\"\"{escaped}\"\"\"")
+}
+
+// ============================================================================
+// WebAssembly
+// ============================================================================
+
+/// Generates the TypeScript interface and `Js<Trait>`, a wasm-bindgen extern type
+/// implementing the trait by calling the JavaScript object's methods.
+#[cfg(feature = "wasm")]
+fn wasm_dispatch(
+    item_trait: &ItemTrait,
+    methods: &[InterfaceMethod],
+    args: &ItemArgs,
+) -> TokenStream2 {
+    let trait_ident = &item_trait.ident;
+    let vis = &item_trait.vis;
+    let dispatch = format_ident!("Js{}", trait_ident);
+    let interface_name = args
+        .name(Target::Wasm)
+        .map_or_else(|| trait_ident.to_string(), str::to_string);
+    let typescript = typescript_interface(&interface_name, &item_trait.attrs, methods);
+    let section = format_ident!("__MULTIFFI_TS_{}", trait_ident.to_string().to_uppercase());
+    let struct_doc = format!("JavaScript object implementing [`{trait_ident}`]");
+
+    let mut imports = Vec::new();
+    let mut implementations = Vec::new();
+    for method in methods {
+        let sig = &method.sig;
+        let js_name = &method.js_name;
+        let import = format_ident!("__multiffi_{}", sig.ident);
+        let params = method.params.iter().map(|(name, ty)| quote! { #name: #ty });
+        let args = method.params.iter().map(|(name, _)| name);
+
+        if let Some(ok) = result_ok_type(&sig.output) {
+            imports.push(quote! {
+                #[wasm_bindgen(method, catch, js_name = #js_name)]
+                fn #import(this: &#dispatch, #(#params),*) -> ::std::result::Result<#ok, wasm_bindgen::JsValue>;
+            });
+            implementations.push(quote! {
+                #sig {
+                    self.#import(#(#args),*).map_err(|error| {
+                        ::std::convert::From::from(
+                            error.as_string().unwrap_or_else(|| format!("{error:?}")),
+                        )
+                    })
+                }
+            });
+        } else {
+            let output = &sig.output;
+            imports.push(quote! {
+                #[wasm_bindgen(method, js_name = #js_name)]
+                fn #import(this: &#dispatch, #(#params),*) #output;
+            });
+            implementations.push(quote! {
+                #sig {
+                    self.#import(#(#args),*)
+                }
+            });
+        }
+    }
+
+    quote! {
+        #[wasm_bindgen::prelude::wasm_bindgen(typescript_custom_section)]
+        const #section: &'static str = #typescript;
+
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        extern "C" {
+            #[doc = #struct_doc]
+            #[wasm_bindgen(typescript_type = #interface_name)]
+            #vis type #dispatch;
+
+            #(#imports)*
+        }
+
+        impl #trait_ident for #dispatch {
+            #(#implementations)*
+        }
+    }
+}
+
+/// Builds the TypeScript `interface` declaring the trait's methods.
+#[cfg(feature = "wasm")]
+fn typescript_interface(name: &str, attrs: &[Attribute], methods: &[InterfaceMethod]) -> String {
+    let mut source = String::new();
+    if let Some(doc) = crate::doc_support::doc_string(attrs) {
+        source.push_str(&jsdoc(&doc, ""));
+    }
+    source.push_str(&format!("export interface {name} {{\n"));
+    for method in methods {
+        if let Some(doc) = crate::doc_support::doc_string(&method.docs) {
+            source.push_str(&jsdoc(&doc, "  "));
+        }
+        let params: Vec<String> = method
+            .params
+            .iter()
+            .map(|(param, ty)| {
+                format!(
+                    "{}: {}",
+                    crate::convert_to_camel_case(&param.to_string()),
+                    typescript_type(ty)
+                )
+            })
+            .collect();
+        let output = match &method.sig.output {
+            ReturnType::Default => "void".to_string(),
+            ReturnType::Type(_, ty) => result_ok_type(&method.sig.output)
+                .map_or_else(|| typescript_type(ty), typescript_type),
+        };
+        source.push_str(&format!(
+            "  {}({}): {output};\n",
+            method.js_name,
+            params.join(", ")
+        ));
+    }
+    source.push_str("}\n");
+    source
+}
+
+/// Formats `doc` as a JSDoc comment indented by `indent`.
+#[cfg(feature = "wasm")]
+fn jsdoc(doc: &str, indent: &str) -> String {
+    let mut comment = format!("{indent}/**\n");
+    for line in doc.lines() {
+        let line = line.replace("*/", "*\\/");
+        comment.push_str(format!("{indent} * {line}").trim_end());
+        comment.push('\n');
+    }
+    comment.push_str(&format!("{indent} */\n"));
+    comment
+}
+
+/// Maps a Rust parameter or return type to the TypeScript type wasm-bindgen uses for it.
+///
+/// Types that aren't primitives, strings, options, or vectors keep their Rust name,
+/// which matches the class of an exported `#[multiffi]` struct.
+#[cfg(feature = "wasm")]
+pub(crate) fn typescript_type(ty: &Type) -> String {
+    use syn::{GenericArgument, PathArguments};
+
+    match ty {
+        Type::Reference(reference) => typescript_type(&reference.elem),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => "void".to_string(),
+        Type::Slice(slice) => format!("{}[]", typescript_type(&slice.elem)),
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return "any".to_string();
+            };
+            let inner = match &segment.arguments {
+                PathArguments::AngleBracketed(generics) => {
+                    generics.args.iter().find_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                }
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), inner) {
+                ("String" | "str" | "char", _) => "string".to_string(),
+                ("bool", _) => "boolean".to_string(),
+                (
+                    "i8" | "i16" | "i32" | "u8" | "u16" | "u32" | "f32" | "f64" | "isize" | "usize",
+                    _,
+                ) => "number".to_string(),
+                ("i64" | "u64" | "i128" | "u128", _) => "bigint".to_string(),
+                ("Option", Some(inner)) => format!("{} | undefined", typescript_type(inner)),
+                ("Vec", Some(Type::Path(path))) if path.path.is_ident("u8") => {
+                    "Uint8Array".to_string()
+                }
+                ("Vec", Some(inner)) => format!("{}[]", typescript_type(inner)),
+                ("JsValue", _) => "any".to_string(),
+                (name, _) => name.to_string(),
+            }
+        }
+        _ => "any".to_string(),
+    }
+}
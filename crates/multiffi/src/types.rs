@@ -0,0 +1,198 @@
+//! Type-level helpers for the generated bindings.
+//!
+//! ## `Option<T>`
+//!
+//! All three frameworks map `Option<T>` natively (`None` in Python, `null`/`undefined`
+//! in JavaScript), with two gaps MultiFFI fills:
+//!
+//! - PyO3 requires every parameter, including `Option<T>` ones, unless a signature says
+//!   otherwise. Trailing `Option<T>` parameters get `#[pyo3(signature = (..., name = None))]`
+//!   so they can be omitted, like they can in JavaScript.
+//! - Struct fields must be exposed explicitly: public fields get `#[pyo3(get, set)]`, and
+//!   wasm-bindgen structs use `getter_with_clone` so non-`Copy` fields such as
+//!   `Option<String>` get accessors, named in camelCase like NAPI's.
+//!
+//! ## Collections
+//!
+//! PyO3 (`dict`/`list`) and NAPI (plain objects and arrays) convert `HashMap<String, T>`
+//! and `Vec<T>` fields natively, including when `T` is another `#[multiffi]` struct.
+//! wasm-bindgen only handles vectors of primitives and strings, so fields holding maps,
+//! sets, or vectors of other types are skipped by wasm-bindgen and get generated
+//! accessors that convert through `serde-wasm-bindgen` instead: maps become plain
+//! objects, sequences become arrays. Element types must implement serde's
+//! `Serialize`/`Deserialize`, which `#[multiffi]` derives for WebAssembly structs.
+
+// Signatures and field options are Python helpers, collection accessors WebAssembly ones
+#![cfg_attr(not(all(feature = "python", feature = "wasm")), allow(dead_code))]
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Attribute, Field, FnArg, GenericArgument, Pat, PathArguments, Signature, Type};
+
+/// Map and set types, which wasm-bindgen cannot pass at all.
+const UNORDERED_COLLECTIONS: &[&str] = &["HashMap", "BTreeMap", "IndexMap", "HashSet", "BTreeSet"];
+
+/// Sequence types, which wasm-bindgen only passes when their elements are primitives.
+const SEQUENCES: &[&str] = &["Vec", "VecDeque"];
+
+/// Types wasm-bindgen passes inside a `Vec<T>`.
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128",
+    "usize", "f32", "f64", "String",
+];
+
+/// Returns `true` when `ty` is `Option<T>`.
+pub(crate) fn is_option(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path.path.segments.last().is_some_and(|segment| {
+        segment.ident == "Option"
+            && matches!(&segment.arguments, PathArguments::AngleBracketed(generics)
+                if matches!(generics.args.first(), Some(GenericArgument::Type(_))))
+    })
+}
+
+/// Returns `true` when a field of type `ty` needs generated conversion glue for
+/// WebAssembly, i.e. it is (or wraps in an `Option`) a map, a set, or a sequence of
+/// anything but primitives and strings.
+pub(crate) fn needs_wasm_conversion(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    let name = segment.ident.to_string();
+    let mut generics = type_arguments(segment);
+
+    if UNORDERED_COLLECTIONS.contains(&name.as_str()) {
+        true
+    } else if SEQUENCES.contains(&name.as_str()) {
+        !generics.next().is_some_and(is_primitive)
+    } else if name == "Option" {
+        generics.next().is_some_and(needs_wasm_conversion)
+    } else {
+        false
+    }
+}
+
+fn type_arguments(segment: &syn::PathSegment) -> impl Iterator<Item = &Type> {
+    let generics = match &segment.arguments {
+        PathArguments::AngleBracketed(generics) => Some(generics.args.iter()),
+        _ => None,
+    };
+    generics.into_iter().flatten().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+fn is_primitive(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path)
+        if type_path.qself.is_none()
+            && type_path.path.segments.len() == 1
+            && PRIMITIVES.iter().any(|name| type_path.path.is_ident(name)))
+}
+
+/// Generates wasm-bindgen accessors for a collection field, converting it to and from
+/// plain JavaScript objects and arrays with `serde-wasm-bindgen`.
+///
+/// The accessors define the `js_name` property and keep the field's doc comments, like
+/// the ones generated for the other fields. The field itself must be marked
+/// `#[wasm_bindgen(skip)]`.
+pub(crate) fn wasm_collection_accessors(field: &Field, js_name: &str) -> TokenStream2 {
+    let Some(name) = &field.ident else {
+        return TokenStream2::new();
+    };
+    let ty = &field.ty;
+    let docs = crate::doc_support::doc_attrs(&field.attrs);
+    let getter = format_ident!("__multiffi_get_{}", name);
+    let setter = format_ident!("__multiffi_set_{}", name);
+
+    quote! {
+        #(#docs)*
+        #[wasm_bindgen::prelude::wasm_bindgen(getter = #js_name)]
+        pub fn #getter(&self) -> ::std::result::Result<wasm_bindgen::JsValue, wasm_bindgen::JsValue> {
+            let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+            serde::Serialize::serialize(&self.#name, &serializer).map_err(wasm_bindgen::JsValue::from)
+        }
+
+        #(#docs)*
+        #[wasm_bindgen::prelude::wasm_bindgen(setter = #js_name)]
+        pub fn #setter(&mut self, value: wasm_bindgen::JsValue) -> ::std::result::Result<(), wasm_bindgen::JsValue> {
+            self.#name = serde_wasm_bindgen::from_value::<#ty>(value)?;
+            Ok(())
+        }
+    }
+}
+
+/// Returns `true` when `ty` is PyO3's `Python<'py>` token, which is not a Python-level parameter.
+fn is_python_token(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path)
+        if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Python"))
+}
+
+/// Builds a `#[pyo3(signature = ...)]` attribute defaulting trailing `Option<T>` parameters
+/// to `None`.
+///
+/// Returns `None` when there is no trailing `Option<T>` parameter, when a parameter is not
+/// a simple identifier, or when `attrs` already declare a signature.
+pub(crate) fn python_signature(sig: &Signature, attrs: &[Attribute]) -> Option<Attribute> {
+    if has_pyo3_option(attrs, "signature") {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    for input in &sig.inputs {
+        let FnArg::Typed(pat_type) = input else {
+            continue;
+        };
+        if is_python_token(&pat_type.ty) {
+            continue;
+        }
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            return None;
+        };
+        params.push((&pat_ident.ident, is_option(&pat_type.ty)));
+    }
+
+    let required = params
+        .iter()
+        .rposition(|(_, optional)| !optional)
+        .map_or(0, |index| index + 1);
+    if required == params.len() {
+        return None;
+    }
+
+    let params = params.iter().enumerate().map(|(index, (name, _))| {
+        if index < required {
+            quote! { #name }
+        } else {
+            quote! { #name = None }
+        }
+    });
+    let signature: TokenStream2 = quote! { signature = (#(#params),*) };
+    Some(syn::parse_quote!(#[pyo3(#signature)]))
+}
+
+/// Returns `true` when `attrs` contain a `#[pyo3(...)]` attribute that sets `option`.
+pub(crate) fn has_pyo3_option(attrs: &[Attribute], option: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pyo3"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(option) {
+                    found = true;
+                }
+                // Skip the option's value, if any
+                if meta.input.peek(syn::Token![=]) {
+                    let _: syn::Expr = meta.value()?.parse()?;
+                }
+                Ok(())
+            });
+            found
+        })
+}
@@ -0,0 +1,136 @@
+//! Snapshot tests of the code `#[multiffi]` generates for each target.
+//!
+//! `multiffi::__expand_for_tests!(target, item)` expands an item for a single target and
+//! returns the generated code as a string, so each test runs under any combination of
+//! features that includes its target.
+
+#[cfg(feature = "python")]
+mod python {
+    #[test]
+    fn function_with_rename_and_optional_parameter() {
+        let expanded = multiffi::__expand_for_tests!(
+            python,
+            #[multiffi(rename = "load")]
+            pub fn load_config(path: String, profile: Option<String>) -> String {
+                path
+            }
+        );
+
+        assert_eq!(
+            expanded,
+            "#[pyo3 :: pyfunction] #[pyo3(name = \"load\")] \
+             #[pyo3(signature = (path, profile = None))] \
+             pub fn load_config(path : String, profile : Option < String >) -> String { path } \
+             #[doc(hidden)] #[allow(non_snake_case)] \
+             pub fn __multiffi_py_register_load_config(module : & pyo3 :: Bound < '_, pyo3 :: \
+             types :: PyModule > ,) -> pyo3 :: PyResult < () > \
+             { use pyo3 :: types :: PyModuleMethods; \
+             module.add_function(pyo3 :: wrap_pyfunction! (load_config, module) ?) }"
+        );
+    }
+
+    #[test]
+    fn struct_fields_become_properties() {
+        let expanded = multiffi::__expand_for_tests!(
+            python,
+            pub struct Server {
+                pub host: String,
+                port: u16,
+            }
+        );
+
+        assert!(expanded.starts_with("#[pyo3 :: pyclass] #[derive(Clone)] pub struct Server"));
+        assert!(expanded.contains("#[pyo3(get, set)] pub host : String"));
+        assert!(expanded.contains(" port : u16"));
+        assert!(!expanded.contains("#[pyo3(get, set)] port"));
+        assert!(expanded.contains("module.add_class :: < Server > ()"));
+    }
+
+    #[test]
+    fn skipped_item_keeps_an_empty_registration() {
+        let expanded = multiffi::__expand_for_tests!(
+            python,
+            #[multiffi(skip(python))]
+            pub fn native_only() -> bool {
+                true
+            }
+        );
+
+        assert!(!expanded.contains("pyfunction"));
+        assert!(expanded.contains("__multiffi_py_register_native_only"));
+        assert!(expanded.contains("let _ = module; Ok(())"));
+    }
+}
+
+#[cfg(feature = "nodejs")]
+mod nodejs {
+    #[test]
+    fn function_with_rename() {
+        let expanded = multiffi::__expand_for_tests!(
+            nodejs,
+            #[multiffi(rename = "load")]
+            pub fn load_config(path: String) -> String {
+                path
+            }
+        );
+
+        assert_eq!(
+            expanded,
+            "#[napi :: napi(js_name = \"load\")] \
+             pub fn load_config(path : String) -> String { path }"
+        );
+    }
+
+    #[test]
+    fn impl_block_methods_and_constructor() {
+        let expanded = multiffi::__expand_for_tests!(nodejs,
+            impl Counter {
+                pub fn new(start: u32) -> Self { Self { count: start } }
+                pub fn bump_count(&mut self) -> u32 { self.count += 1; self.count }
+            }
+        );
+
+        assert!(expanded.starts_with("#[napi :: napi] impl Counter"));
+        assert!(expanded.contains("#[napi :: napi(constructor)] pub fn new"));
+        assert!(expanded.contains("#[napi :: napi] pub fn bump_count"));
+        assert!(!expanded.contains("pyo3"));
+        assert!(!expanded.contains("wasm_bindgen"));
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm {
+    #[test]
+    fn function_with_rename() {
+        let expanded = multiffi::__expand_for_tests!(
+            wasm,
+            #[multiffi(rename = "load")]
+            pub fn load_config(path: String) -> String {
+                path
+            }
+        );
+
+        assert_eq!(
+            expanded,
+            "#[wasm_bindgen :: prelude :: wasm_bindgen(js_name = \"load\")] \
+             pub fn load_config(path : String) -> String { path }"
+        );
+    }
+
+    #[test]
+    fn unit_enum() {
+        let expanded = multiffi::__expand_for_tests!(
+            wasm,
+            pub enum Level {
+                Low,
+                High,
+            }
+        );
+
+        assert_eq!(
+            expanded,
+            "#[wasm_bindgen :: prelude :: wasm_bindgen] #[derive(Clone, Copy, PartialEq)] \
+             pub enum Level { Low, High, }"
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Implementation of the `#[generate_batch_method]` procedural macro
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, Pat, Receiver, ReturnType, Type,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+};
+
+/// Arguments for the generate_batch_method macro: an optional method name prefix
+struct BatchArgs {
+    prefix: Option<Ident>,
+}
+
+impl Parse for BatchArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let prefix = if input.is_empty() {
+            None
+        } else {
+            Some(input.parse()?)
+        };
+        Ok(BatchArgs { prefix })
+    }
+}
+
+/// A method marked with `#[batch]`
+struct Operation<'a> {
+    method: &'a ImplItemFn,
+    receiver: &'a Receiver,
+}
+
+impl Operation<'_> {
+    fn by_value(&self) -> bool {
+        self.receiver.reference.is_none()
+    }
+
+    fn returns_result(&self) -> bool {
+        match &self.method.sig.output {
+            ReturnType::Type(_, ty) => match ty.as_ref() {
+                Type::Path(type_path) => type_path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Result"),
+                _ => false,
+            },
+            ReturnType::Default => false,
+        }
+    }
+
+    /// `match` arm applying the operation to `target`, evaluating to `Result<(), String>`
+    fn arm(&self) -> syn::Result<TokenStream2> {
+        let fn_name = &self.method.sig.ident;
+        let op_name = fn_name.to_string();
+
+        let mut arguments = Vec::new();
+        let mut call_args = Vec::new();
+        for param in self.method.sig.inputs.iter().skip(1) {
+            let FnArg::Typed(pat_type) = param else {
+                continue;
+            };
+            let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+                return Err(syn::Error::new_spanned(
+                    &pat_type.pat,
+                    "batch operations need plain parameter names, used as argument names",
+                ));
+            };
+            let name = &pat_ident.ident;
+            let arg_name = name.to_string();
+            // References are deserialized into owned values and passed borrowed
+            let (owned_type, call_arg) = match pat_type.ty.as_ref() {
+                Type::Reference(reference) => {
+                    let owned = match reference.elem.as_ref() {
+                        Type::Path(path) if path.path.is_ident("str") => quote! { String },
+                        elem => quote! { #elem },
+                    };
+                    let call_arg = if reference.mutability.is_some() {
+                        quote! { &mut #name }
+                    } else {
+                        quote! { &#name }
+                    };
+                    (owned, call_arg)
+                }
+                ty => (quote! { #ty }, quote! { #name }),
+            };
+            let binding = if reference_is_mut(&pat_type.ty) {
+                quote! { mut #name }
+            } else {
+                quote! { #name }
+            };
+            arguments.push(quote! {
+                let #binding: #owned_type = match __batch_arg(&args, #arg_name) {
+                    Ok(value) => value,
+                    Err(error) => break 'op Err(error),
+                };
+            });
+            call_args.push(call_arg);
+        }
+
+        let apply = match (self.by_value(), self.returns_result()) {
+            (true, true) => quote! {
+                match ::core::clone::Clone::clone(&target).#fn_name(#(#call_args),*) {
+                    Ok(next) => {
+                        target = next;
+                        Ok(())
+                    }
+                    Err(error) => Err(error.to_string()),
+                }
+            },
+            (true, false) => quote! {
+                target = target.#fn_name(#(#call_args),*);
+                Ok(())
+            },
+            (false, true) => quote! {
+                target
+                    .#fn_name(#(#call_args),*)
+                    .map(|_| ())
+                    .map_err(|error| error.to_string())
+            },
+            (false, false) if matches!(self.method.sig.output, ReturnType::Default) => quote! {
+                target.#fn_name(#(#call_args),*);
+                Ok(())
+            },
+            (false, false) => quote! {
+                let _ = target.#fn_name(#(#call_args),*);
+                Ok(())
+            },
+        };
+
+        // Argument errors break out of the labeled block, which only exists when there are arguments
+        if arguments.is_empty() {
+            Ok(quote! { #op_name => { #apply } })
+        } else {
+            Ok(quote! {
+                #op_name => 'op: {
+                    #(#arguments)*
+                    #apply
+                }
+            })
+        }
+    }
+}
+
+fn reference_is_mut(ty: &Type) -> bool {
+    matches!(ty, Type::Reference(reference) if reference.mutability.is_some())
+}
+
+/// Whether `attr` is the inert `#[batch]` marker
+fn is_batch_marker(attr: &syn::Attribute) -> bool {
+    attr.path().is_ident("batch")
+}
+
+/// Implementation of the `generate_batch_method` procedural macro
+pub fn generate_batch_method_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as BatchArgs);
+    let mut item_impl = parse_macro_input!(input as ItemImpl);
+
+    match batch_method(&args, &item_impl) {
+        Ok(batch_method) => {
+            for item in &mut item_impl.items {
+                if let ImplItem::Fn(method) = item {
+                    method.attrs.retain(|attr| !is_batch_marker(attr));
+                }
+            }
+            item_impl.items.push(ImplItem::Verbatim(batch_method));
+            TokenStream::from(quote! { #item_impl })
+        }
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Generate the batch method of the operations marked with `#[batch]` in `item_impl`
+fn batch_method(args: &BatchArgs, item_impl: &ItemImpl) -> syn::Result<TokenStream2> {
+    let mut operations = Vec::new();
+    for item in &item_impl.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let Some(marker) = method.attrs.iter().find(|attr| is_batch_marker(attr)) else {
+            continue;
+        };
+        let Some(receiver) = method.sig.receiver() else {
+            return Err(syn::Error::new_spanned(
+                marker,
+                "batch operations must take `self`, `&self`, or `&mut self`",
+            ));
+        };
+        if !method.sig.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &method.sig.generics,
+                "batch operations cannot be generic, their arguments are deserialized from JSON",
+            ));
+        }
+        operations.push(Operation { method, receiver });
+    }
+    if operations.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "#[generate_batch_method] needs at least one method marked with #[batch]",
+        ));
+    }
+
+    // By-value operations thread the receiver through the chain, so they must share its type
+    let by_value: Vec<&Operation> = operations.iter().filter(|op| op.by_value()).collect();
+    if let Some(first) = by_value.first() {
+        let first_type = &first.receiver.ty;
+        if let Some(other) = by_value.iter().find(|op| op.receiver.ty != *first_type) {
+            return Err(syn::Error::new_spanned(
+                other.receiver,
+                "batch operations taking `self` by value must all use the same receiver type",
+            ));
+        }
+        if let Some(op) = operations
+            .iter()
+            .find(|op| op.receiver.mutability.is_some() && op.receiver.reference.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                op.receiver,
+                "batch operations cannot mix `&mut self` with operations taking `self` by value",
+            ));
+        }
+    }
+
+    let batch_name = match &args.prefix {
+        Some(prefix) => format_ident!("{}_batch", prefix),
+        None => format_ident!("batch"),
+    };
+    let arms = operations
+        .iter()
+        .map(Operation::arm)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let (signature, target, invalid_json, finish) = match by_value.first() {
+        Some(first) => {
+            let receiver_type = &first.receiver.ty;
+            (
+                quote! { (self: #receiver_type, ops: &str) -> (#receiver_type, String) },
+                quote! { let mut target = self; },
+                quote! { (target, response) },
+                quote! { (target, report) },
+            )
+        }
+        None => {
+            let receiver = if operations.iter().any(|op| op.receiver.mutability.is_some()) {
+                quote! { &mut self }
+            } else {
+                quote! { &self }
+            };
+            (
+                quote! { (#receiver, ops: &str) -> String },
+                quote! { let target = self; },
+                quote! { response },
+                quote! { report },
+            )
+        }
+    };
+    let doc = format!(
+        "Apply a JSON array of operations (`[{{\"op\": \"name\", \"args\": {{...}}}}]`) in order\n\n\
+         Operations: {}. Failed operations are reported and skipped, the others still apply.\n\
+         The report is `{{\"success\": bool, \"applied\": n, \"errors\": [{{\"index\": i, \"op\": \"name\", \"error\": \"...\"}}]}}`.",
+        operations
+            .iter()
+            .map(|op| format!("`{}`", op.method.sig.ident))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        pub fn #batch_name #signature {
+            fn __batch_arg<T: serde::de::DeserializeOwned>(
+                args: &serde_json::Value,
+                name: &str,
+            ) -> Result<T, String> {
+                let value = args.get(name).cloned().unwrap_or(serde_json::Value::Null);
+                serde_json::from_value(value)
+                    .map_err(|error| format!("Invalid argument '{}': {}", name, error))
+            }
+
+            #target
+            let ops: Vec<serde_json::Value> = match serde_json::from_str(ops) {
+                Ok(ops) => ops,
+                Err(error) => {
+                    let response = serde_json::json!({
+                        "success": false,
+                        "applied": 0,
+                        "errors": [{ "index": null, "op": null, "error": format!("Invalid JSON: {}", error) }]
+                    })
+                    .to_string();
+                    return #invalid_json;
+                }
+            };
+
+            let mut applied = 0usize;
+            let mut errors = Vec::new();
+            for (index, op) in ops.iter().enumerate() {
+                let name = op.get("op").and_then(serde_json::Value::as_str).unwrap_or_default();
+                let args = op.get("args").cloned().unwrap_or(serde_json::Value::Null);
+                let outcome: Result<(), String> = match name {
+                    #(#arms)*
+                    _ => Err(format!("Unknown operation '{}'", name)),
+                };
+                match outcome {
+                    Ok(()) => applied += 1,
+                    Err(error) => errors.push(serde_json::json!({
+                        "index": index,
+                        "op": name,
+                        "error": error
+                    })),
+                }
+            }
+
+            let report = serde_json::json!({
+                "success": errors.is_empty(),
+                "applied": applied,
+                "errors": errors
+            })
+            .to_string();
+            #finish
+        }
+    })
+}
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::{
-    FnArg, Ident, ItemFn, Pat, ReturnType, Token, Type,
+    FnArg, Ident, ItemFn, LitStr, Pat, ReturnType, Token, Type, parenthesized,
     parse::{Parse, ParseStream},
     parse_macro_input,
 };
@@ -22,6 +22,8 @@ enum JsonDirection {
 struct JsonHelperArgs {
     directions: Vec<JsonDirection>,
     handle_mode: bool,
+    associated: bool,
+    envelope: Envelope,
 }
 
 impl Parse for JsonHelperArgs {
@@ -30,11 +32,15 @@ impl Parse for JsonHelperArgs {
             return Ok(JsonHelperArgs {
                 directions: vec![JsonDirection::Auto],
                 handle_mode: false,
+                associated: false,
+                envelope: Envelope::default(),
             });
         }
 
         let mut directions = Vec::new();
         let mut handle_mode = false;
+        let mut associated = false;
+        let mut envelope = Envelope::default();
 
         loop {
             let ident: Ident = input.parse()?;
@@ -44,10 +50,12 @@ impl Parse for JsonHelperArgs {
                 "auto" => directions.push(JsonDirection::Auto),
                 "bidirectional" => directions.push(JsonDirection::Both), // Legacy support
                 "handle_mode" => handle_mode = true,
+                "associated" => associated = true,
+                "envelope" => envelope = Envelope::parse(input)?,
                 _ => {
                     return Err(syn::Error::new_spanned(
                         ident,
-                        "Expected 'in', 'out', 'incoming', 'outgoing', 'auto', 'bidirectional', or 'handle_mode'",
+                        "Expected 'in', 'out', 'incoming', 'outgoing', 'auto', 'bidirectional', 'handle_mode', 'associated', or 'envelope'",
                     ));
                 }
             };
@@ -63,6 +71,11 @@ impl Parse for JsonHelperArgs {
             }
         }
 
+        // No directions besides flags means auto-detection
+        if directions.is_empty() {
+            directions.push(JsonDirection::Auto);
+        }
+
         // Convert "in,out" to Both
         if directions.len() == 2
             && directions.contains(&JsonDirection::Incoming)
@@ -74,10 +87,152 @@ impl Parse for JsonHelperArgs {
         Ok(JsonHelperArgs {
             directions,
             handle_mode,
+            associated,
+            envelope,
         })
     }
 }
 
+/// Shape of the JSON strings returned by the generated helpers
+#[derive(Debug, Clone, PartialEq)]
+enum Envelope {
+    /// `{"success": true, "data": ...}` or `{"success": false, "error": "..."}`, with
+    /// configurable field names
+    Standard {
+        success: String,
+        data: String,
+        error: String,
+    },
+    /// The bare data on success, `{"error": "..."}` on failure
+    Flat,
+    /// JSON-RPC 2.0 response objects, with a `null` id
+    JsonRpc,
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Envelope::Standard {
+            success: "success".to_string(),
+            data: "data".to_string(),
+            error: "error".to_string(),
+        }
+    }
+}
+
+impl Envelope {
+    /// Parse `= "standard" | "flat" | "jsonrpc"` or `(success = "..", data = "..", error = "..")`
+    /// following the `envelope` argument
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            let name: LitStr = input.parse()?;
+            return match name.value().as_str() {
+                "standard" => Ok(Envelope::default()),
+                "flat" => Ok(Envelope::Flat),
+                "jsonrpc" => Ok(Envelope::JsonRpc),
+                _ => Err(syn::Error::new_spanned(
+                    name,
+                    "Expected envelope \"standard\", \"flat\", or \"jsonrpc\"",
+                )),
+            };
+        }
+
+        let content;
+        parenthesized!(content in input);
+        let mut envelope = Envelope::default();
+        let Envelope::Standard {
+            success,
+            data,
+            error,
+        } = &mut envelope
+        else {
+            unreachable!("the default envelope is the standard one")
+        };
+        while !content.is_empty() {
+            let field: Ident = content.parse()?;
+            content.parse::<Token![=]>()?;
+            let name = content.parse::<LitStr>()?.value();
+            match field.to_string().as_str() {
+                "success" => *success = name,
+                "data" => *data = name,
+                "error" => *error = name,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "Expected envelope field 'success', 'data', or 'error'",
+                    ));
+                }
+            }
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        Ok(envelope)
+    }
+
+    /// Local `__json_ok(Option<Value>)` and `__json_error(code, message)` functions building
+    /// the responses of a generated helper
+    ///
+    /// The code is only used by JSON-RPC: -32700 for invalid JSON, -32602 for invalid
+    /// parameters, and -32000 for errors returned by the wrapped method.
+    fn functions(&self) -> TokenStream2 {
+        let (ok, error) = match self {
+            Envelope::Standard {
+                success,
+                data: data_field,
+                error,
+            } => (
+                quote! {
+                    match data {
+                        Some(data) => serde_json::json!({ #success: true, #data_field: data }),
+                        None => serde_json::json!({ #success: true }),
+                    }
+                },
+                quote! { serde_json::json!({ #success: false, #error: message }) },
+            ),
+            Envelope::Flat => (
+                quote! { data.unwrap_or(serde_json::Value::Null) },
+                quote! { serde_json::json!({ "error": message }) },
+            ),
+            Envelope::JsonRpc => (
+                quote! {
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "result": data.unwrap_or(serde_json::Value::Null),
+                        "id": null
+                    })
+                },
+                quote! {
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "error": { "code": code, "message": message },
+                        "id": null
+                    })
+                },
+            ),
+        };
+        let code = if matches!(self, Envelope::JsonRpc) {
+            quote! { code }
+        } else {
+            quote! { _code }
+        };
+
+        quote! {
+            #[allow(dead_code)]
+            fn __json_ok(data: Option<serde_json::Value>) -> String {
+                let response: serde_json::Value = #ok;
+                response.to_string()
+            }
+
+            #[allow(dead_code)]
+            fn __json_error(#code: i64, message: String) -> String {
+                let response: serde_json::Value = #error;
+                response.to_string()
+            }
+        }
+    }
+}
+
 /// Check if a type is an Arc<T>
 fn is_arc_type(ty: &Type) -> bool {
     match ty {
@@ -186,6 +341,8 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
     // Check if using auto-detection
     let is_auto_mode = args.directions.contains(&JsonDirection::Auto);
     let handle_mode = args.handle_mode;
+    let associated = args.associated;
+    let envelope_fns = args.envelope.functions();
 
     // Determine which directions to generate
     let directions = if is_auto_mode {
@@ -220,6 +377,16 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
     let generics = &input_fn.sig.generics;
     let vis = &input_fn.vis;
 
+    // Methods are called on `self`; functions without a receiver are called directly, or
+    // through `Self` when `associated` says they are declared in an impl block
+    let callee = if input_fn.sig.receiver().is_some() {
+        quote! { self.#fn_name }
+    } else if associated {
+        quote! { Self::#fn_name }
+    } else {
+        quote! { #fn_name }
+    };
+
     // Create modified generics with Serialize constraint for JSON methods
     let mut json_generics = generics.clone();
     for param in &mut json_generics.params {
@@ -299,17 +466,11 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                 let #param_name: #param_type = match params_json.get(#field_name) {
                     Some(value) => match serde_json::from_value(value.clone()) {
                         Ok(deserialized) => deserialized,
-                        Err(e) => return serde_json::to_string(&serde_json::json!({
-                            "success": false,
-                            "error": format!("Failed to deserialize parameter {} '{}' (type: {}): {}",
-                                #param_index, #field_name, stringify!(#param_type), e)
-                        })).unwrap(),
+                        Err(e) => return __json_error(-32602, format!("Failed to deserialize parameter {} '{}' (type: {}): {}",
+                                #param_index, #field_name, stringify!(#param_type), e)),
                     },
-                    None => return serde_json::to_string(&serde_json::json!({
-                        "success": false,
-                        "error": format!("Missing required parameter {} '{}' (type: {})",
-                            #param_index, #field_name, stringify!(#param_type))
-                    })).unwrap(),
+                    None => return __json_error(-32602, format!("Missing required parameter {} '{}' (type: {})",
+                            #param_index, #field_name, stringify!(#param_type))),
                 };
             }
         }).collect();
@@ -331,36 +492,27 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
 
         generated_methods.push(quote! {
             #vis fn #json_unified_name(#self_param_tokens #simple_param_sigs json_params: &str) -> String {
+                #envelope_fns
+
                 // Parse the incoming JSON
                 let params_json: serde_json::Value = match serde_json::from_str(json_params) {
                     Ok(json) => json,
-                    Err(e) => return serde_json::to_string(&serde_json::json!({
-                        "success": false,
-                        "error": format!("Invalid JSON: {}", e)
-                    })).unwrap(),
+                    Err(e) => return __json_error(-32700, format!("Invalid JSON: {}", e)),
                 };
 
                 // Deserialize complex parameters
                 #(#complex_param_deserializations)*
 
                 // Call the original method with all parameters
-                match self.#fn_name(#(#all_param_names),*) {
+                match #callee(#(#all_param_names),*) {
                     Ok(result) => {
                         // Serialize the result
                         match serde_json::to_value(&result) {
-                            Ok(serialized) => serde_json::to_string(&serde_json::json!({
-                                "success": true,
-                                "data": serialized
-                            })).unwrap(),
-                            Err(_) => serde_json::to_string(&serde_json::json!({
-                                "success": true
-                            })).unwrap(),
+                            Ok(serialized) => __json_ok(Some(serialized)),
+                            Err(_) => __json_ok(None),
                         }
                     },
-                    Err(e) => serde_json::to_string(&serde_json::json!({
-                        "success": false,
-                        "error": e.to_string()
-                    })).unwrap(),
+                    Err(e) => __json_error(-32000, e.to_string()),
                 }
             }
         });
@@ -376,8 +528,8 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                 match &input_fn.sig.output {
                     ReturnType::Default => {
                         quote! {
-                            let _result = self.#fn_name(#(#param_names),*);
-                            serde_json::to_string(&serde_json::json!({"success": true})).unwrap()
+                            let _result = #callee(#(#param_names),*);
+                            __json_ok(None)
                         }
                     }
                     ReturnType::Type(_, ty) => {
@@ -387,34 +539,31 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                                     if segment.ident == "Result" {
                                         // Result type - handle Ok/Err
                                         quote! {
-                                            match self.#fn_name(#(#param_names),*) {
-                                                Ok(_) => serde_json::to_string(&serde_json::json!({"success": true})).unwrap(),
-                                                Err(e) => serde_json::to_string(&serde_json::json!({
-                                                    "success": false,
-                                                    "error": e.to_string()
-                                                })).unwrap(),
+                                            match #callee(#(#param_names),*) {
+                                                Ok(_) => __json_ok(None),
+                                                Err(e) => __json_error(-32000, e.to_string()),
                                             }
                                         }
                                     } else {
                                         // Non-Result type
                                         quote! {
-                                            let _result = self.#fn_name(#(#param_names),*);
-                                            serde_json::to_string(&serde_json::json!({"success": true})).unwrap()
+                                            let _result = #callee(#(#param_names),*);
+                                            __json_ok(None)
                                         }
                                     }
                                 } else {
                                     // Fallback for non-Result
                                     quote! {
-                                        let _result = self.#fn_name(#(#param_names),*);
-                                        serde_json::to_string(&serde_json::json!({"success": true})).unwrap()
+                                        let _result = #callee(#(#param_names),*);
+                                        __json_ok(None)
                                     }
                                 }
                             }
                             _ => {
                                 // Fallback for non-Result
                                 quote! {
-                                    let _result = self.#fn_name(#(#param_names),*);
-                                    serde_json::to_string(&serde_json::json!({"success": true})).unwrap()
+                                    let _result = #callee(#(#param_names),*);
+                                    __json_ok(None)
                                 }
                             }
                         }
@@ -425,15 +574,10 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                 match &input_fn.sig.output {
                     ReturnType::Default => {
                         quote! {
-                            let result = self.#fn_name(#(#param_names),*);
+                            let result = #callee(#(#param_names),*);
                             match serde_json::to_value(&result) {
-                                Ok(serialized) => serde_json::to_string(&serde_json::json!({
-                                    "success": true,
-                                    "data": serialized
-                                })).unwrap(),
-                                Err(_) => serde_json::to_string(&serde_json::json!({
-                                    "success": true
-                                })).unwrap(),
+                                Ok(serialized) => __json_ok(Some(serialized)),
+                                Err(_) => __json_ok(None),
                             }
                         }
                     }
@@ -478,22 +622,14 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                                         };
 
                                         quote! {
-                                            match self.#fn_name(#(#param_names),*) {
+                                            match #callee(#(#param_names),*) {
                                                 Ok(result) => {
                                                     match serde_json::to_value(#serialize_expr) {
-                                                        Ok(serialized) => serde_json::to_string(&serde_json::json!({
-                                                            "success": true,
-                                                            "data": serialized
-                                                        })).unwrap(),
-                                                        Err(_) => serde_json::to_string(&serde_json::json!({
-                                                            "success": true
-                                                        })).unwrap(),
+                                                        Ok(serialized) => __json_ok(Some(serialized)),
+                                                        Err(_) => __json_ok(None),
                                                     }
                                                 },
-                                                Err(e) => serde_json::to_string(&serde_json::json!({
-                                                    "success": false,
-                                                    "error": e.to_string()
-                                                })).unwrap(),
+                                                Err(e) => __json_error(-32000, e.to_string()),
                                             }
                                         }
                                     } else {
@@ -505,15 +641,10 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                                         };
 
                                         quote! {
-                                            let result = self.#fn_name(#(#param_names),*);
+                                            let result = #callee(#(#param_names),*);
                                             match serde_json::to_value(#serialize_expr) {
-                                                Ok(serialized) => serde_json::to_string(&serde_json::json!({
-                                                    "success": true,
-                                                    "data": serialized
-                                                })).unwrap(),
-                                                Err(_) => serde_json::to_string(&serde_json::json!({
-                                                    "success": true
-                                                })).unwrap(),
+                                                Ok(serialized) => __json_ok(Some(serialized)),
+                                                Err(_) => __json_ok(None),
                                             }
                                         }
                                     }
@@ -526,15 +657,10 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                                     };
 
                                     quote! {
-                                        let result = self.#fn_name(#(#param_names),*);
+                                        let result = #callee(#(#param_names),*);
                                         match serde_json::to_value(#serialize_expr) {
-                                            Ok(serialized) => serde_json::to_string(&serde_json::json!({
-                                                "success": true,
-                                                "data": serialized
-                                            })).unwrap(),
-                                            Err(_) => serde_json::to_string(&serde_json::json!({
-                                                "success": true
-                                            })).unwrap(),
+                                            Ok(serialized) => __json_ok(Some(serialized)),
+                                            Err(_) => __json_ok(None),
                                         }
                                     }
                                 }
@@ -542,15 +668,10 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                             _ => {
                                 // Fallback for non-Result - assume non-Arc for unknown types
                                 quote! {
-                                    let result = self.#fn_name(#(#param_names),*);
+                                    let result = #callee(#(#param_names),*);
                                     match serde_json::to_value(&result) {
-                                        Ok(serialized) => serde_json::to_string(&serde_json::json!({
-                                            "success": true,
-                                            "data": serialized
-                                        })).unwrap(),
-                                        Err(_) => serde_json::to_string(&serde_json::json!({
-                                            "success": true
-                                        })).unwrap(),
+                                        Ok(serialized) => __json_ok(Some(serialized)),
+                                        Err(_) => __json_ok(None),
                                     }
                                 }
                             }
@@ -573,6 +694,8 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
             generated_methods.push(quote! {
                 #[doc = #doc_content]
                 #vis fn #json_out_name #json_generics (#params) -> String {
+                    #envelope_fns
+
                     #method_call
                 }
             });
@@ -629,17 +752,11 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
                     let #param_name: #param_type = match params_json.get(#field_name) {
                         Some(value) => match serde_json::from_value(value.clone()) {
                             Ok(deserialized) => deserialized,
-                            Err(e) => return serde_json::to_string(&serde_json::json!({
-                                "success": false,
-                                "error": format!("Failed to deserialize parameter {} '{}' (type: {}): {}",
-                                    #param_index, #field_name, stringify!(#param_type), e)
-                            })).unwrap(),
+                            Err(e) => return __json_error(-32602, format!("Failed to deserialize parameter {} '{}' (type: {}): {}",
+                                    #param_index, #field_name, stringify!(#param_type), e)),
                         },
-                        None => return serde_json::to_string(&serde_json::json!({
-                            "success": false,
-                            "error": format!("Missing required parameter {} '{}' (type: {})",
-                                #param_index, #field_name, stringify!(#param_type))
-                        })).unwrap(),
+                        None => return __json_error(-32602, format!("Missing required parameter {} '{}' (type: {})",
+                                #param_index, #field_name, stringify!(#param_type))),
                     };
                 }
             }).collect();
@@ -661,36 +778,27 @@ pub fn generate_json_helper_impl(args: TokenStream, input: TokenStream) -> Token
 
             generated_methods.push(quote! {
                 #vis fn #json_in_name(#self_param_tokens #simple_param_sigs json_params: &str) -> String {
+                    #envelope_fns
+
                     // Parse the incoming JSON
                     let params_json: serde_json::Value = match serde_json::from_str(json_params) {
                         Ok(json) => json,
-                        Err(e) => return serde_json::to_string(&serde_json::json!({
-                            "success": false,
-                            "error": format!("Invalid JSON: {}", e)
-                        })).unwrap(),
+                        Err(e) => return __json_error(-32700, format!("Invalid JSON: {}", e)),
                     };
 
                     // Deserialize complex parameters
                     #(#complex_param_deserializations)*
 
                     // Call the original method with all parameters
-                    match self.#fn_name(#(#all_param_names),*) {
+                    match #callee(#(#all_param_names),*) {
                         Ok(result) => {
                             // Try to serialize the result
                             match serde_json::to_value(&result) {
-                                Ok(serialized) => serde_json::to_string(&serde_json::json!({
-                                    "success": true,
-                                    "data": serialized
-                                })).unwrap(),
-                                Err(_) => serde_json::to_string(&serde_json::json!({
-                                    "success": true
-                                })).unwrap(),
+                                Ok(serialized) => __json_ok(Some(serialized)),
+                                Err(_) => __json_ok(None),
                             }
                         },
-                        Err(e) => serde_json::to_string(&serde_json::json!({
-                            "success": false,
-                            "error": e.to_string()
-                        })).unwrap(),
+                        Err(e) => __json_error(-32000, e.to_string()),
                     }
                 }
             });
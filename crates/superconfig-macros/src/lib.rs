@@ -6,6 +6,7 @@
 //!
 //! - **Automatic try method generation** - Transform fallible methods into error-collecting variants
 //! - **Bidirectional JSON helpers** - Generate FFI-compatible JSON serialization methods  
+//! - **Batched fluent chains** - Apply a JSON array of operations in a single FFI call
 //! - **Intelligent type detection** - Auto-detect complex types for optimal JSON helper generation
 //! - **Fluent API support** - Seamless integration with method chaining patterns
 //! - **Zero runtime overhead** - Pure compile-time code generation
@@ -21,10 +22,12 @@
 //!
 //! ## Core Macros
 //!
-//! This crate provides two key procedural macros:
+//! This crate provides four key procedural macros:
 //!
 //! - [`macro@generate_try_method`] - Automatically generates `try_*` method variants that collect errors instead of returning them
 //! - [`macro@generate_json_helper`] - Automatically generates `*_as_json` method variants for FFI compatibility
+//! - [`macro@generate_batch_method`] - Generates a `*_batch` method applying a JSON array of operations
+//! - [`macro@PartialConfig`] - Derives a copy of a configuration struct with every field optional, for override files
 //!
 //! ## Error Handling Philosophy
 //!
@@ -106,6 +109,25 @@ use proc_macro::TokenStream;
 /// // Permissive: let config = config.try_enable(42).try_set_timeout(5000);
 /// ```
 ///
+/// # Receivers
+///
+/// | Receiver | `try_*` returns | On error |
+/// |----------|-----------------|----------|
+/// | `self`, `mut self` | `T` of `Result<T, E>` | `self`, unchanged (requires `Self: Clone`) |
+/// | `self: Arc<Self>`, `Rc<Self>`, `Box<Self>` | `T` of `Result<T, E>` | the receiver, only the pointer is cloned for `Arc`/`Rc` |
+/// | `&self`, `&mut self` | `&Self`, `&mut Self` | the receiver, the `Ok` value is dropped on success too |
+///
+/// ```rust,ignore
+/// #[generate_try_method]
+/// pub fn enable(self: Arc<Self>, flags: u64) -> Result<Arc<Self>, RegistryError> { ... }
+///
+/// #[generate_try_method]
+/// pub fn set_timeout(&mut self, ms: u64) -> Result<(), ConfigError> { ... }
+///
+/// let registry = registry.try_enable(flags);
+/// builder.try_set_timeout(0).try_set_timeout(5000);
+/// ```
+///
 /// # Requirements
 ///
 /// The target type must implement a `collect_error` method with the signature:
@@ -132,6 +154,35 @@ pub fn generate_try_method(_args: TokenStream, input: TokenStream) -> TokenStrea
 /// | `in` | `*_from_json` only | Accept JSON from FFI |
 /// | `in,out` | Both directions | Full bidirectional FFI |
 ///
+/// # Response Envelopes
+///
+/// `envelope` sets the shape of the returned JSON, to match an existing client protocol:
+///
+/// | Argument | Success | Error |
+/// |----------|---------|-------|
+/// | `envelope = "standard"` (default) | `{"success": true, "data": ...}` | `{"success": false, "error": "..."}` |
+/// | `envelope(success = "ok", data = "result", error = "message")` | `{"ok": true, "result": ...}` | `{"ok": false, "message": "..."}` |
+/// | `envelope = "flat"` | the bare data | `{"error": "..."}` |
+/// | `envelope = "jsonrpc"` | `{"jsonrpc": "2.0", "result": ..., "id": null}` | `{"jsonrpc": "2.0", "error": {"code": ..., "message": "..."}, "id": null}` |
+///
+/// JSON-RPC error codes are -32700 for invalid JSON, -32602 for missing or invalid
+/// parameters, and -32000 for errors returned by the method.
+///
+/// # Functions Without `self`
+///
+/// Free functions get free `*_as_json`/`*_json` functions calling `foo(...)` directly.
+/// Associated functions need the `associated` flag, so the helpers call `Self::foo(...)`:
+///
+/// ```rust,ignore
+/// #[generate_json_helper(outgoing)]
+/// pub fn global_settings() -> Settings { global_registry().settings() }
+///
+/// impl ConfigRegistry {
+///     #[generate_json_helper(outgoing, associated)]
+///     pub fn with_defaults() -> Arc<Self> { ... }
+/// }
+/// ```
+///
 /// # Complex Type Detection
 ///
 /// **Simple Types** (no JSON conversion needed):
@@ -205,5 +256,109 @@ pub fn generate_json_helper(_args: TokenStream, input: TokenStream) -> TokenStre
     crate::json_helper::generate_json_helper_impl(_args, input)
 }
 
+/// Generates a batch method applying a JSON array of operations in one call.
+///
+/// Put on an `impl` block, it adds `<prefix>_batch` (or `batch` without a prefix) calling
+/// the methods marked with `#[batch]` in order, so FFI clients can run a whole fluent
+/// chain in a single boundary crossing instead of one call per method:
+///
+/// ```json
+/// [
+///     {"op": "enable", "args": {"flags": 3}},
+///     {"op": "with_file", "args": {"path": "app.toml"}}
+/// ]
+/// ```
+///
+/// Arguments are deserialized by parameter name, so parameter types must implement
+/// `Deserialize`; reference parameters are deserialized into owned values (`&str` into
+/// `String`) and passed borrowed. Operations without arguments may omit `args`.
+///
+/// Every operation runs even after one fails. The returned report lists the failures:
+///
+/// ```json
+/// {"success": false, "applied": 1, "errors": [{"index": 1, "op": "with_file", "error": "..."}]}
+/// ```
+///
+/// An operation fails when it is unknown, when an argument is missing or invalid, or when
+/// it returns `Err`.
+///
+/// # Receivers
+///
+/// | Operation receivers | Generated signature |
+/// |---------------------|---------------------|
+/// | `&self` only | `fn batch(&self, ops: &str) -> String` |
+/// | `&mut self` (and `&self`) | `fn batch(&mut self, ops: &str) -> String` |
+/// | `self` or `self: Arc<Self>` (and `&self`) | `fn batch(self: R, ops: &str) -> (R, String)` |
+///
+/// By-value operations must return their receiver type, or a `Result` of it. The value
+/// is threaded through the chain; fallible ones are called on a clone, like
+/// [`macro@generate_try_method`], so a failure keeps the previous value.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use superconfig_macros::generate_batch_method;
+///
+/// #[generate_batch_method(registry)]
+/// impl ConfigRegistry {
+///     #[batch]
+///     pub fn enable(self: Arc<Self>, flags: u64) -> Arc<Self> { ... }
+///
+///     #[batch]
+///     pub fn with_file(self: Arc<Self>, path: &str) -> Result<Arc<Self>, String> { ... }
+/// }
+///
+/// // Generates:
+/// // pub fn registry_batch(self: Arc<Self>, ops: &str) -> (Arc<Self>, String) { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn generate_batch_method(args: TokenStream, input: TokenStream) -> TokenStream {
+    crate::batch_method::generate_batch_method_impl(args, input)
+}
+
+/// Derives `PartialConfig`: a `Partial<Name>` struct with every field optional.
+///
+/// Override files rarely set every key, so extracting them into the configuration struct
+/// fails on the missing fields. The generated partial struct deserializes from any subset
+/// of them, and `PartialConfig::apply` sets the fields it has on a complete value:
+///
+/// ```rust,ignore
+/// use superconfig::{Partial, PartialConfig, SuperConfig};
+///
+/// #[derive(Deserialize, Serialize, PartialConfig)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+///     #[partial(nested)]
+///     database: Database,
+/// }
+///
+/// #[derive(Deserialize, Serialize, PartialConfig)]
+/// struct Database {
+///     url: String,
+///     timeout: u32,
+/// }
+///
+/// // override.toml: port = 9090, [database] timeout = 30
+/// let overrides: Partial<Config> = SuperConfig::new().with_file("override.toml").extract()?;
+/// config.apply(overrides); // `host` and `database.url` unchanged
+/// ```
+///
+/// # Attributes
+///
+/// | Attribute | Effect |
+/// |-----------|--------|
+/// | `#[partial(nested)]` on a field | The field is itself partial (its type must derive `PartialConfig`), so tables are overridden key by key instead of replaced whole |
+/// | `#[partial(crate = "path")]` on the struct | Path of the crate defining the `PartialConfig` trait, `::superconfig` by default |
+///
+/// `#[serde(rename, alias, rename_all, deny_unknown_fields)]` attributes are copied to the
+/// partial struct; other serde attributes depend on the field type and are left out.
+#[proc_macro_derive(PartialConfig, attributes(partial))]
+pub fn derive_partial_config(input: TokenStream) -> TokenStream {
+    crate::partial_config::derive_partial_config_impl(input)
+}
+
+mod batch_method;
 mod json_helper;
+mod partial_config;
 mod try_method;
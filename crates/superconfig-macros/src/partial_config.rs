@@ -0,0 +1,165 @@
+//! Implementation of the `#[derive(PartialConfig)]` procedural macro
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, LitStr, Path, meta::ParseNestedMeta,
+    parse_macro_input,
+};
+
+/// Serde attributes forwarded to the partial struct, which don't change a field's type
+const FORWARDED_SERDE: [&str; 4] = ["rename", "alias", "rename_all", "deny_unknown_fields"];
+
+/// Options of a `#[partial(...)]` container attribute
+struct ContainerOptions {
+    /// Path of the crate defining the `PartialConfig` trait
+    krate: Path,
+}
+
+pub fn derive_partial_config_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match partial_config(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn partial_config(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "PartialConfig can only be derived for structs with named fields",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "PartialConfig can only be derived for structs with named fields",
+        ));
+    };
+
+    let options = container_options(&input.attrs)?;
+    let krate = &options.krate;
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let partial = format_ident!("Partial{}", ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+    let serde_attrs = forwarded_serde_attrs(&input.attrs)?;
+    let doc = format!("[`{ident}`] with every field optional, for partial overrides");
+
+    let mut partial_fields = Vec::new();
+    let mut applies = Vec::new();
+    for field in &fields.named {
+        let name = field.ident.as_ref().expect("named fields have names");
+        let field_vis = &field.vis;
+        let ty = &field.ty;
+        let serde_attrs = forwarded_serde_attrs(&field.attrs)?;
+        if is_nested(field)? {
+            partial_fields.push(quote! {
+                #(#serde_attrs)*
+                #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+                #field_vis #name: ::std::option::Option<<#ty as #krate::PartialConfig>::Partial>,
+            });
+            applies.push(quote! {
+                if let ::std::option::Option::Some(value) = partial.#name {
+                    #krate::PartialConfig::apply(&mut self.#name, value);
+                }
+            });
+        } else {
+            partial_fields.push(quote! {
+                #(#serde_attrs)*
+                #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+                #field_vis #name: ::std::option::Option<#ty>,
+            });
+            applies.push(quote! {
+                if let ::std::option::Option::Some(value) = partial.#name {
+                    self.#name = value;
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(::std::default::Default, ::serde::Serialize, ::serde::Deserialize)]
+        #(#serde_attrs)*
+        #vis struct #partial #generics #where_clause {
+            #(#partial_fields)*
+        }
+
+        impl #impl_generics #krate::PartialConfig for #ident #ty_generics #where_clause {
+            type Partial = #partial #ty_generics;
+
+            fn apply(&mut self, partial: Self::Partial) {
+                #(#applies)*
+            }
+        }
+    })
+}
+
+/// Parse the `#[partial(crate = "...")]` container attribute
+fn container_options(attrs: &[Attribute]) -> syn::Result<ContainerOptions> {
+    let mut options = ContainerOptions {
+        krate: syn::parse_quote!(::superconfig),
+    };
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("partial")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                options.krate = meta.value()?.parse::<LitStr>()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate = \"path\"`"))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+/// Whether the field is marked `#[partial(nested)]`
+fn is_nested(field: &Field) -> syn::Result<bool> {
+    let mut nested = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("partial"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("nested") {
+                nested = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `nested`"))
+            }
+        })?;
+    }
+    Ok(nested)
+}
+
+/// The `#[serde(...)]` attributes of `attrs` that only rename, which keep their meaning on
+/// the partial struct; others, such as `with` or `default`, depend on the field's type
+fn forwarded_serde_attrs(attrs: &[Attribute]) -> syn::Result<Vec<&Attribute>> {
+    let mut forwarded = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let mut renames_only = true;
+        attr.parse_nested_meta(|meta| {
+            renames_only &= FORWARDED_SERDE.iter().any(|name| meta.path.is_ident(name));
+            skip_value(&meta)
+        })?;
+        if renames_only {
+            forwarded.push(attr);
+        }
+    }
+    Ok(forwarded)
+}
+
+/// Consume the value of a `name = value` or `name(...)` entry
+fn skip_value(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|inner| skip_value(&inner))?;
+    }
+    Ok(())
+}
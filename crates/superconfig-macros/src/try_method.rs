@@ -24,18 +24,14 @@ fn returns_result(input_fn: &ItemFn) -> bool {
 
 /// Extract the Ok type from Result<T, E>
 fn extract_ok_type(input_fn: &ItemFn) -> Option<Type> {
-    if let ReturnType::Type(_, ty) = &input_fn.sig.output {
-        if let Type::Path(type_path) = ty.as_ref() {
-            if let Some(segment) = type_path.path.segments.last() {
-                if segment.ident == "Result" {
-                    if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                        if let Some(syn::GenericArgument::Type(ok_type)) = args.args.first() {
-                            return Some(ok_type.clone());
-                        }
-                    }
-                }
-            }
-        }
+    if let ReturnType::Type(_, ty) = &input_fn.sig.output
+        && let Type::Path(type_path) = ty.as_ref()
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == "Result"
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(ok_type)) = args.args.first()
+    {
+        return Some(ok_type.clone());
     }
     None
 }
@@ -72,22 +68,40 @@ pub fn generate_try_method_impl(_args: TokenStream, input: TokenStream) -> Token
         })
         .collect();
 
-    // Determine return type for try method by extracting T from Result<T, E>
-    let try_return_type = match extract_ok_type(&input_fn) {
-        Some(ok_type) => quote! { #ok_type },
-        None => quote! { Self }, // Fallback
-    };
-
-    // Use a universal approach that works for any struct/error type
-    let final_error_handling = quote! {
-        let self_clone = self.clone();
-        match self_clone.#fn_name(#(#param_names.clone()),*) {
-            Ok(result) => result,
-            Err(_e) => {
-                // For now, just return self on error
-                // Individual structs can override this by providing their own try_* methods
+    let call = quote! { #fn_name(#(#param_names.clone()),*) };
+    let (try_return_type, final_error_handling) = match input_fn.sig.receiver() {
+        // `&self` and `&mut self`: return the receiver for chaining, whatever the Ok type
+        Some(receiver) if receiver.reference.is_some() => {
+            let receiver_type = &receiver.ty;
+            // Returning `self` from an `Err` arm would overlap the borrow held by the call
+            // result, so the result is dropped before `self` is returned
+            let handling = quote! {
+                let _ = self.#call;
                 self
-            }
+            };
+            (quote! { #receiver_type }, handling)
+        }
+        // `self`, `mut self`, and `self: Arc<Self>` (or `Rc<Self>`, `Box<Self>`): call on a
+        // clone, cloning the pointer rather than `Self` for smart pointer receivers
+        _ => {
+            // Determine return type for try method by extracting T from Result<T, E>
+            let try_return_type = match extract_ok_type(&input_fn) {
+                Some(ok_type) => quote! { #ok_type },
+                None => quote! { Self }, // Fallback
+            };
+            // Use a universal approach that works for any struct/error type
+            let handling = quote! {
+                let self_clone = ::core::clone::Clone::clone(&self);
+                match self_clone.#call {
+                    Ok(result) => result,
+                    Err(_e) => {
+                        // For now, just return self on error
+                        // Individual structs can override this by providing their own try_* methods
+                        self
+                    }
+                }
+            };
+            (try_return_type, handling)
         }
     };
 
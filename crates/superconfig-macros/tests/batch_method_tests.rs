@@ -0,0 +1,193 @@
+//! Tests for `#[generate_batch_method]`
+
+use serde::Deserialize;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+use superconfig_macros::generate_batch_method;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Source {
+    pub path: String,
+    pub required: bool,
+}
+
+/// Fluent registry in the style of the FFI-facing `Arc<Self>` APIs
+#[derive(Debug, Default)]
+pub struct Registry {
+    flags: AtomicU64,
+    sources: Mutex<Vec<Source>>,
+}
+
+#[generate_batch_method(registry)]
+impl Registry {
+    #[batch]
+    pub fn enable(self: Arc<Self>, flags: u64) -> Arc<Self> {
+        self.flags.fetch_or(flags, Ordering::Relaxed);
+        self
+    }
+
+    #[batch]
+    pub fn with_file(self: Arc<Self>, path: &str) -> Result<Arc<Self>, String> {
+        if !path.ends_with(".toml") {
+            return Err(format!("unsupported file: {path}"));
+        }
+        self.add_source(Source {
+            path: path.to_string(),
+            required: true,
+        })?;
+        Ok(self)
+    }
+
+    #[batch]
+    pub fn add_source(&self, source: Source) -> Result<(), String> {
+        let mut sources = self.sources.lock().unwrap();
+        if sources.contains(&source) {
+            return Err(format!("duplicate source: {}", source.path));
+        }
+        sources.push(source);
+        Ok(())
+    }
+
+    #[batch]
+    pub fn reset(self: Arc<Self>) -> Arc<Self> {
+        self.flags.store(0, Ordering::Relaxed);
+        self
+    }
+
+    pub fn flags(&self) -> u64 {
+        self.flags.load(Ordering::Relaxed)
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        let sources = self.sources.lock().unwrap();
+        sources.iter().map(|source| source.path.clone()).collect()
+    }
+}
+
+/// `&mut self` builder
+#[derive(Debug, Default)]
+pub struct Builder {
+    name: String,
+    retries: Option<u32>,
+}
+
+#[generate_batch_method]
+impl Builder {
+    #[batch]
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    #[batch]
+    pub fn set_retries(&mut self, retries: Option<u32>) -> &mut Self {
+        self.retries = retries;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn parse(report: &str) -> Value {
+        serde_json::from_str(report).unwrap()
+    }
+
+    #[test]
+    fn test_batch_applies_operations_in_order() {
+        let registry = Arc::new(Registry::default());
+        let (registry, report) = registry.registry_batch(
+            r#"[
+                {"op": "enable", "args": {"flags": 1}},
+                {"op": "reset"},
+                {"op": "enable", "args": {"flags": 6}},
+                {"op": "with_file", "args": {"path": "app.toml"}},
+                {"op": "add_source", "args": {"source": {"path": "extra.toml", "required": false}}}
+            ]"#,
+        );
+
+        assert_eq!(
+            parse(&report),
+            json!({"success": true, "applied": 5, "errors": []})
+        );
+        assert_eq!(registry.flags(), 6);
+        assert_eq!(registry.paths(), vec!["app.toml", "extra.toml"]);
+    }
+
+    #[test]
+    fn test_batch_collects_per_operation_errors() {
+        let registry = Arc::new(Registry::default());
+        let (registry, report) = registry.registry_batch(
+            r#"[
+                {"op": "with_file", "args": {"path": "app.yaml"}},
+                {"op": "enable", "args": {"flags": "all"}},
+                {"op": "enable"},
+                {"op": "explode"},
+                {"op": "with_file", "args": {"path": "app.toml"}},
+                {"op": "with_file", "args": {"path": "app.toml"}},
+                {"op": "enable", "args": {"flags": 2}}
+            ]"#,
+        );
+
+        let report = parse(&report);
+        assert_eq!(report["success"], false);
+        assert_eq!(report["applied"], 2);
+        let errors = report["errors"].as_array().unwrap();
+        let indexes: Vec<u64> = errors
+            .iter()
+            .map(|e| e["index"].as_u64().unwrap())
+            .collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3, 5]);
+        assert_eq!(errors[0]["op"], "with_file");
+        assert_eq!(errors[0]["error"], "unsupported file: app.yaml");
+        assert!(
+            errors[1]["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid argument 'flags'")
+        );
+        assert_eq!(errors[3]["error"], "Unknown operation 'explode'");
+        assert_eq!(errors[4]["error"], "duplicate source: app.toml");
+
+        // Failed operations keep the previous value, later ones still apply
+        assert_eq!(registry.flags(), 2);
+        assert_eq!(registry.paths(), vec!["app.toml"]);
+    }
+
+    #[test]
+    fn test_batch_rejects_invalid_json() {
+        let registry = Arc::new(Registry::default());
+        let (registry, report) = registry.registry_batch(r#"{"op": "enable"}"#);
+
+        let report = parse(&report);
+        assert_eq!(report["success"], false);
+        assert_eq!(report["applied"], 0);
+        assert!(
+            report["errors"][0]["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid JSON")
+        );
+        assert_eq!(registry.flags(), 0);
+    }
+
+    #[test]
+    fn test_batch_on_mut_self_builder() {
+        let mut builder = Builder::default();
+        let report = builder.batch(
+            r#"[
+                {"op": "set_name", "args": {"name": "app"}},
+                {"op": "set_retries", "args": {"retries": 3}},
+                {"op": "set_retries", "args": {}}
+            ]"#,
+        );
+
+        assert_eq!(parse(&report)["applied"], 3);
+        assert_eq!(builder.name, "app");
+        // Missing optional arguments deserialize to `None`
+        assert_eq!(builder.retries, None);
+    }
+}
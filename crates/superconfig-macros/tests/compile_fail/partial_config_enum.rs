@@ -0,0 +1,9 @@
+use superconfig_macros::PartialConfig;
+
+#[derive(PartialConfig)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+fn main() {}
@@ -0,0 +1,117 @@
+//! Tests for the `envelope` argument of `#[generate_json_helper]`
+
+use serde::{Deserialize, Serialize};
+use superconfig_macros::generate_json_helper;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Limits {
+    pub max: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Service {
+    limits: Limits,
+}
+
+impl Service {
+    #[generate_json_helper(outgoing, envelope = "flat")]
+    pub fn limits(&self) -> Result<Limits, String> {
+        if self.limits.max == 0 {
+            return Err("no limits".to_string());
+        }
+        Ok(self.limits.clone())
+    }
+
+    #[generate_json_helper(envelope = "jsonrpc")]
+    pub fn apply(&self, limits: Limits) -> Result<Limits, String> {
+        if limits.max > 100 {
+            return Err(format!("max {} is above 100", limits.max));
+        }
+        Ok(limits)
+    }
+
+    #[generate_json_helper(
+        incoming,
+        outgoing,
+        envelope(success = "ok", data = "result", error = "message")
+    )]
+    pub fn merge(&self, limits: Limits) -> Result<Limits, String> {
+        if limits.max == 0 {
+            return Err("max must be positive".to_string());
+        }
+        Ok(Limits {
+            max: limits.max.max(self.limits.max),
+        })
+    }
+
+    #[generate_json_helper(outgoing, envelope = "standard")]
+    pub fn snapshot(&self) -> Limits {
+        self.limits.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn parse(response: String) -> Value {
+        serde_json::from_str(&response).unwrap()
+    }
+
+    fn service(max: u32) -> Service {
+        Service {
+            limits: Limits { max },
+        }
+    }
+
+    #[test]
+    fn test_flat_envelope() {
+        assert_eq!(parse(service(5).limits_as_json()), json!({"max": 5}));
+        assert_eq!(
+            parse(service(0).limits_as_json()),
+            json!({"error": "no limits"})
+        );
+    }
+
+    #[test]
+    fn test_jsonrpc_envelope() {
+        assert_eq!(
+            parse(service(5).apply_json(r#"{"limits": {"max": 7}}"#)),
+            json!({"jsonrpc": "2.0", "result": {"max": 7}, "id": null})
+        );
+        assert_eq!(
+            parse(service(5).apply_json(r#"{"limits": {"max": 700}}"#)),
+            json!({
+                "jsonrpc": "2.0",
+                "error": {"code": -32000, "message": "max 700 is above 100"},
+                "id": null
+            })
+        );
+
+        let invalid_params = parse(service(5).apply_json(r#"{"limits": "none"}"#));
+        assert_eq!(invalid_params["error"]["code"], -32602);
+        let invalid_json = parse(service(5).apply_json("{"));
+        assert_eq!(invalid_json["error"]["code"], -32700);
+    }
+
+    #[test]
+    fn test_custom_field_names() {
+        assert_eq!(
+            parse(service(5).merge_json(r#"{"limits": {"max": 3}}"#)),
+            json!({"ok": true, "result": {"max": 5}})
+        );
+        assert_eq!(
+            parse(service(5).merge_json(r#"{"limits": {"max": 0}}"#)),
+            json!({"ok": false, "message": "max must be positive"})
+        );
+    }
+
+    #[test]
+    fn test_standard_envelope_is_the_default() {
+        assert_eq!(
+            parse(service(2).snapshot_as_json()),
+            json!({"success": true, "data": {"max": 2}})
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Tests for `#[generate_json_helper]` on free functions and associated functions
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use superconfig_macros::generate_json_helper;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub name: String,
+    pub retries: u32,
+}
+
+pub struct Registry {
+    settings: Settings,
+}
+
+impl Registry {
+    #[generate_json_helper(outgoing, associated)]
+    pub fn with_defaults() -> Arc<Self> {
+        Arc::new(Self {
+            settings: Settings {
+                name: "default".to_string(),
+                retries: 3,
+            },
+        })
+    }
+
+    #[generate_json_helper(associated)]
+    pub fn validate(settings: Settings) -> Result<Settings, String> {
+        if settings.retries > 10 {
+            Err(format!("too many retries: {}", settings.retries))
+        } else {
+            Ok(settings)
+        }
+    }
+}
+
+impl Serialize for Registry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.settings.serialize(serializer)
+    }
+}
+
+fn global_registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry {
+        settings: Settings {
+            name: "global".to_string(),
+            retries: 1,
+        },
+    })
+}
+
+#[generate_json_helper(outgoing)]
+pub fn global_settings() -> Settings {
+    global_registry().settings.clone()
+}
+
+#[generate_json_helper]
+pub fn rename(settings: Settings, name: String) -> Result<Settings, String> {
+    if name.is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    Ok(Settings { name, ..settings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn parse(response: String) -> Value {
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn test_free_function_as_json() {
+        assert_eq!(
+            parse(global_settings_as_json()),
+            json!({"success": true, "data": {"name": "global", "retries": 1}})
+        );
+    }
+
+    #[test]
+    fn test_free_function_json_with_simple_param() {
+        let settings = r#"{"settings": {"name": "a", "retries": 2}}"#;
+        assert_eq!(
+            parse(rename_json("b".to_string(), settings)),
+            json!({"success": true, "data": {"name": "b", "retries": 2}})
+        );
+        assert_eq!(
+            parse(rename_json(String::new(), settings))["error"],
+            "name must not be empty"
+        );
+    }
+
+    #[test]
+    fn test_associated_functions() {
+        assert_eq!(
+            parse(Registry::with_defaults_as_json())["data"],
+            json!({"name": "default", "retries": 3})
+        );
+
+        let response = parse(Registry::validate_json(
+            r#"{"settings": {"name": "x", "retries": 11}}"#,
+        ));
+        assert_eq!(response["success"], false);
+        assert_eq!(response["error"], "too many retries: 11");
+    }
+}
@@ -0,0 +1,79 @@
+//! Tests for `#[derive(PartialConfig)]`
+
+use serde::{Deserialize, Serialize};
+use superconfig_macros::PartialConfig;
+
+/// Stand-in for the trait defined by the superconfig crate
+pub mod config {
+    pub trait PartialConfig {
+        type Partial: serde::Serialize + serde::de::DeserializeOwned + Default;
+
+        fn apply(&mut self, partial: Self::Partial);
+    }
+}
+
+use config::PartialConfig as _;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PartialConfig)]
+#[partial(crate = "crate::config")]
+#[serde(rename_all = "camelCase")]
+pub struct Server {
+    pub host_name: String,
+    #[serde(alias = "listen_port")]
+    pub port: u16,
+    #[partial(nested)]
+    pub database: Database,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PartialConfig)]
+#[partial(crate = "crate::config")]
+pub struct Database {
+    pub url: String,
+    pub timeout: u32,
+}
+
+fn server() -> Server {
+    Server {
+        host_name: "localhost".to_string(),
+        port: 8080,
+        database: Database {
+            url: "postgres://localhost".to_string(),
+            timeout: 5,
+        },
+        tags: vec!["a".to_string()],
+    }
+}
+
+#[test]
+fn test_partial_deserializes_any_subset() {
+    let partial: PartialServer =
+        serde_json::from_str(r#"{"listen_port": 9090, "database": {"timeout": 30}}"#).unwrap();
+    assert_eq!(partial.port, Some(9090));
+    assert_eq!(partial.host_name, None);
+
+    let mut server = server();
+    server.apply(partial);
+    assert_eq!(server.port, 9090);
+    assert_eq!(server.host_name, "localhost");
+    assert_eq!(server.database.timeout, 30);
+    assert_eq!(server.database.url, "postgres://localhost");
+    assert_eq!(server.tags, ["a"]);
+}
+
+#[test]
+fn test_partial_serializes_only_set_fields() {
+    let partial = PartialServer {
+        host_name: Some("example.com".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&partial).unwrap(),
+        serde_json::json!({"hostName": "example.com"})
+    );
+    assert_eq!(
+        serde_json::to_value(PartialServer::default()).unwrap(),
+        serde_json::json!({})
+    );
+}
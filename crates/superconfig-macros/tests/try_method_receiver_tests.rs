@@ -0,0 +1,101 @@
+//! Tests for `#[generate_try_method]` on `self: Arc<Self>`, `&self` and `&mut self` receivers
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use superconfig_macros::generate_try_method;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestError(String);
+
+// Shaped like `ConfigRegistry`: shared through `Arc` and not `Clone`
+#[derive(Debug, Default)]
+pub struct Registry {
+    flags: AtomicU64,
+}
+
+impl Registry {
+    #[generate_try_method]
+    pub fn enable(self: Arc<Self>, flags: u64) -> Result<Arc<Self>, TestError> {
+        if flags == 0 {
+            return Err(TestError("no flags".to_string()));
+        }
+        self.flags.fetch_or(flags, Ordering::Relaxed);
+        Ok(self)
+    }
+
+    #[generate_try_method]
+    pub fn check(&self, flags: u64) -> Result<bool, TestError> {
+        if flags == 0 {
+            return Err(TestError("no flags".to_string()));
+        }
+        Ok(self.flags.load(Ordering::Relaxed) & flags == flags)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Builder {
+    timeout: u64,
+    retries: u32,
+}
+
+impl Builder {
+    #[generate_try_method]
+    pub fn set_timeout(&mut self, timeout: u64) -> Result<(), TestError> {
+        if timeout == 0 {
+            return Err(TestError("timeout must be positive".to_string()));
+        }
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    #[generate_try_method]
+    pub fn set_retries(&mut self, retries: u32) -> Result<&mut Self, TestError> {
+        if retries > 10 {
+            return Err(TestError("too many retries".to_string()));
+        }
+        self.retries = retries;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arc_receiver_keeps_the_same_registry() {
+        let registry = Arc::new(Registry::default());
+
+        let enabled = Arc::clone(&registry)
+            .try_enable(0b01)
+            .try_enable(0)
+            .try_enable(0b10);
+
+        assert!(Arc::ptr_eq(&registry, &enabled));
+        assert_eq!(enabled.flags.load(Ordering::Relaxed), 0b11);
+        assert_eq!(Arc::strong_count(&registry), 2);
+        assert!(registry.enable(0).is_err());
+    }
+
+    #[test]
+    fn test_ref_receiver_returns_self() {
+        let registry = Registry::default();
+        assert!(std::ptr::eq(registry.try_check(0), &registry));
+        assert_eq!(registry.check(1), Ok(false));
+    }
+
+    #[test]
+    fn test_mut_receivers_chain() {
+        let mut builder = Builder::default();
+
+        builder
+            .try_set_timeout(0)
+            .try_set_timeout(500)
+            .try_set_retries(3)
+            .try_set_retries(11);
+
+        assert_eq!(builder.timeout, 500);
+        assert_eq!(builder.retries, 3);
+        assert!(builder.set_timeout(0).is_err());
+    }
+}